@@ -0,0 +1,29 @@
+//! Build script: record the git commit the binary is built from
+//!
+//! The commit is exposed as `IOPULSE_GIT_SHA` and written into JSON results.
+//! Builds outside a git checkout (e.g. from a source tarball) can set
+//! `IOPULSE_GIT_SHA` in the environment instead.
+
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=IOPULSE_GIT_SHA");
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs");
+
+    let sha = std::env::var("IOPULSE_GIT_SHA").ok()
+        .filter(|sha| !sha.is_empty())
+        .or_else(|| {
+            Command::new("git")
+                .args(["rev-parse", "--short=12", "HEAD"])
+                .output()
+                .ok()
+                .filter(|output| output.status.success())
+                .and_then(|output| String::from_utf8(output.stdout).ok())
+                .map(|sha| sha.trim().to_string())
+        });
+
+    if let Some(sha) = sha {
+        println!("cargo:rustc-env=IOPULSE_GIT_SHA={}", sha);
+    }
+}
@@ -1,6 +1,6 @@
 //! CLI argument parsing using clap
 
-use clap::{Parser, ValueEnum};
+use clap::{ArgAction, Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
 
 /// Execution mode
@@ -12,6 +12,67 @@ pub enum ExecutionMode {
     Coordinator,
     /// Service mode - run service on node (accepts coordinator commands)
     Service,
+    /// MPI mode - one process per rank under mpirun/srun, rank 0 coordinates
+    Mpi,
+}
+
+/// Utility subcommands (run instead of a test)
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Query node services for status (uptime, current test, version, capabilities)
+    ///
+    /// Nodes default to --host-list / --clients-file when none are given.
+    Ping {
+        /// Node addresses (host or host:port, default port from --worker-port)
+        #[arg(value_name = "NODE")]
+        nodes: Vec<String>,
+        
+        /// Seconds to wait for each node to answer
+        #[arg(long, default_value = "5")]
+        timeout: u64,
+    },
+    
+    /// Merge aggregate JSON results of independent runs into one report
+    ///
+    /// Counters and rates are summed; latency percentiles are recomputed when
+    /// every run has a histogram file (--json-histogram).
+    Merge {
+        /// Aggregate JSON result files
+        #[arg(value_name = "RESULT", required = true, num_args = 2..)]
+        inputs: Vec<PathBuf>,
+        
+        /// Merged JSON output file
+        #[arg(short, long)]
+        output: PathBuf,
+        
+        /// Merge even if the runs used different workloads
+        #[arg(long)]
+        force: bool,
+    },
+    
+    /// Check a target against the write journal of an earlier run (read-only)
+    ///
+    /// Every block the run wrote must still hold the data of its last write,
+    /// e.g. after a power cut or a firmware upgrade.
+    VerifyJournal {
+        /// Block device or file the journal was written for
+        #[arg(value_name = "TARGET")]
+        target: PathBuf,
+        
+        /// Journal files of the run's workers (journal.worker-<id>.bin)
+        #[arg(value_name = "JOURNAL", required = true, num_args = 1..)]
+        journals: Vec<PathBuf>,
+    },
+    
+    /// Check the write ordering of a target written with --commit-every (read-only)
+    ///
+    /// No block may claim a generation past the one in progress at the last
+    /// durable commit point, e.g. after a power cut.
+    VerifyOrder {
+        /// Block device or file the run wrote
+        #[arg(value_name = "TARGET")]
+        target: PathBuf,
+    },
 }
 
 /// IOPulse - High-performance IO profiling tool
@@ -19,6 +80,10 @@ pub enum ExecutionMode {
 #[command(name = "iopulse")]
 #[command(version, about, long_about = None)]
 pub struct Cli {
+    /// Utility subcommand (e.g., ping, merge, verify-journal, verify-order)
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    
     /// Execution mode: standalone, coordinator, or service
     #[arg(long, value_enum, default_value = "standalone")]
     pub mode: ExecutionMode,
@@ -27,18 +92,48 @@ pub struct Cli {
     #[arg(long, default_value = "9999")]
     pub listen_port: u16,
     
+    /// Close connections idle this many seconds during test setup (service mode only, 0 = never)
+    #[arg(long, default_value = "600")]
+    pub idle_timeout: u64,
+    
     /// Comma-separated list of node addresses for coordinator mode (e.g., "10.0.1.10:9999,10.0.1.11:9999")
     #[arg(long)]
     pub host_list: Option<String>,
     
     /// File containing list of node addresses (one per line, for coordinator mode)
+    /// Append "=ROLE" to an address to give the node a role (e.g., "10.0.1.10=writer")
     #[arg(long)]
     pub clients_file: Option<PathBuf>,
     
+    /// Workload fragment for a node role: ROLE=FILE, a TOML table of workload fields (repeatable)
+    #[arg(long, value_name = "ROLE=FILE")]
+    pub role_workload: Vec<String>,
+    
     /// Port to connect to on worker nodes (coordinator mode only)
     #[arg(long, default_value = "9999")]
     pub worker_port: u16,
     
+    /// Job ID reported by nodes and in results, and substituted for {run_id}
+    /// in target and output paths (default: generated from time and PID)
+    #[arg(long, visible_alias = "run-id")]
+    pub job_id: Option<String>,
+    
+    /// Jobs allowed to wait while another runs; 0 rejects them as BUSY (service mode only)
+    #[arg(long, default_value = "0")]
+    pub max_queued_jobs: usize,
+    
+    /// Serve an HTTP API for submitting jobs and polling results (coordinator mode only, e.g., 0.0.0.0:8080)
+    #[arg(long, value_name = "ADDR")]
+    pub api_listen: Option<String>,
+    
+    /// Unix socket accepting commands that pause, resume, rate limit or re-mix the running test
+    #[arg(long, value_name = "PATH")]
+    pub control_socket: Option<PathBuf>,
+    
+    /// Shared directory where ranks exchange node addresses (mpi mode only, default: current directory)
+    #[arg(long, value_name = "DIR")]
+    pub rendezvous: Option<PathBuf>,
+    
     /// Target path (file, directory, or block device)
     /// 
     /// Not required in service mode (coordinator sends configuration)
@@ -46,14 +141,26 @@ pub struct Cli {
     pub target: Option<PathBuf>,
 
     // === Basic Options ===
-    /// Number of worker threads
+    /// Number of worker threads, or "auto" to find it with a scaling sweep
     #[arg(short = 't', long, default_value = "1")]
-    pub threads: usize,
+    pub threads: ThreadCount,
 
-    /// Block size for IO operations (e.g., 4k, 1M, 64k)
+    /// Block size for IO operations (e.g., 4k, 1M, 64k; buffered IO also takes sizes like 100B or 3KB)
     #[arg(short = 'b', long, default_value = "4k")]
     pub block_size: String,
 
+    /// Mix of block sizes with percentages for reads and writes (e.g., "4k:60,64k:30,1m:10")
+    #[arg(long)]
+    pub bs_split: Option<String>,
+
+    /// Mix of block sizes for reads only (overrides --bs-split for reads)
+    #[arg(long)]
+    pub read_bs_split: Option<String>,
+
+    /// Mix of block sizes for writes only (overrides --bs-split for writes)
+    #[arg(long)]
+    pub write_bs_split: Option<String>,
+
     /// File size for created files (e.g., 1G, 100M)
     #[arg(short = 's', long)]
     pub file_size: Option<String>,
@@ -70,6 +177,10 @@ pub struct Cli {
     #[arg(long)]
     pub run_until_complete: bool,
 
+    /// Stop all workers as soon as the first one completes (--total-bytes / --run-until-complete)
+    #[arg(long)]
+    pub stonewall: bool,
+
     // === Workload Options ===
     /// Use random offsets instead of sequential
     #[arg(long)]
@@ -83,6 +194,22 @@ pub struct Cli {
     #[arg(long)]
     pub write_percent: Option<u8>,
 
+    /// Where sequential streams begin: "random" (a block-aligned offset picked per run) or an offset (e.g. 4G)
+    #[arg(long)]
+    pub offset_start: Option<String>,
+
+    /// What the read/write percentages divide: ops, or bytes (for mixed block sizes)
+    #[arg(long, value_enum, default_value = "ops")]
+    pub rw_split_by: RwSplitBy,
+
+    /// Scatter chunks of the target with a permutation seeded by SEED (simulates an aged, fragmented volume)
+    #[arg(long, value_name = "SEED")]
+    pub remap_offsets: Option<u64>,
+
+    /// Bytes --remap-offsets moves together (default: the block size)
+    #[arg(long, value_name = "SIZE", requires = "remap_offsets")]
+    pub remap_chunk: Option<String>,
+
     /// IO queue depth (1-1024)
     #[arg(short = 'q', long, default_value = "1")]
     pub queue_depth: usize,
@@ -91,6 +218,79 @@ pub struct Cli {
     #[arg(long, value_enum, default_value = "random")]
     pub write_pattern: VerifyPattern,
 
+    /// Payload for the custom pattern: a file whose contents repeat across the target
+    #[arg(long, value_name = "PATH", conflicts_with = "pattern_runs")]
+    pub pattern_file: Option<PathBuf>,
+
+    /// Payload for the custom pattern as SIZE:BYTE runs (e.g., "4k:0xaa,4k:0x55")
+    #[arg(long, value_name = "RUNS")]
+    pub pattern_runs: Option<String>,
+
+    /// Append-grow mode: writes extend the file at EOF until it reaches this size (e.g., 10G),
+    /// then overwrite existing blocks
+    #[arg(long)]
+    pub grow_to: Option<String>,
+
+    // === Queue Depth Ramp Options ===
+    /// Ramp queue depth from 1 up to --queue-depth (doubling each step) to find the optimal QD
+    #[arg(long)]
+    pub qd_ramp: bool,
+
+    /// Duration of each queue depth ramp step (e.g., 5s, 1m)
+    #[arg(long, default_value = "5s")]
+    pub qd_ramp_step: String,
+
+    /// Stop ramping when step p99 latency exceeds this (e.g., 500us, 2ms)
+    #[arg(long)]
+    pub qd_ramp_max_latency: Option<String>,
+
+    /// Stop ramping when throughput improves by less than this percentage
+    #[arg(long, default_value = "5.0")]
+    pub qd_ramp_plateau: f64,
+
+    // === Thread Scaling Options (--threads auto) ===
+    /// Duration of each thread count step (e.g., 5s, 1m)
+    #[arg(long, default_value = "5s")]
+    pub threads_auto_step: String,
+
+    /// Stop adding workers when throughput improves by less than this percentage
+    #[arg(long, default_value = "10.0")]
+    pub threads_auto_plateau: f64,
+
+    /// Most workers per node to try (default: number of CPUs)
+    #[arg(long)]
+    pub threads_auto_max: Option<usize>,
+
+    // === Latency Target Options ===
+    /// Adjust the IOPS limit until p99 latency sits at this target and report the sustainable IOPS (e.g., 500us, 2ms)
+    #[arg(long)]
+    pub target_p99: Option<String>,
+
+    /// Duration of each latency target step (e.g., 5s, 1m)
+    #[arg(long, default_value = "5s")]
+    pub target_p99_step: String,
+
+    // === Visibility Test Options ===
+    /// Measure cross-node metadata visibility: the first node creates (or renames) N files in the target directory, the other nodes look for each one
+    #[arg(long, value_name = "N")]
+    pub visibility_test: Option<u64>,
+
+    /// Operation made on the first node in the visibility test
+    #[arg(long, value_enum, default_value = "create")]
+    pub visibility_op: VisibilityOp,
+
+    /// How the other nodes look for each name in the visibility test
+    #[arg(long, value_enum, default_value = "stat")]
+    pub visibility_check: VisibilityCheck,
+
+    /// Operations per coordinator-sequenced batch in the visibility test
+    #[arg(long, default_value = "1")]
+    pub visibility_batch: usize,
+
+    /// Give up on a name not visible after this long (e.g., 10s, 500ms)
+    #[arg(long, default_value = "10s")]
+    pub visibility_timeout: String,
+
     // === Distribution Options ===
     /// Random distribution type
     #[arg(long, value_enum, default_value = "uniform")]
@@ -112,6 +312,11 @@ pub struct Cli {
     #[arg(long, default_value = "0.5")]
     pub gaussian_center: f64,
 
+    /// Move the Gaussian center over time: linear:RATE sweeps, walk:RATE random-walks
+    /// (RATE in fractions of the range per second, e.g. linear:0.01)
+    #[arg(long, value_name = "SPEC")]
+    pub gaussian_drift: Option<String>,
+
     // === Think Time Options ===
     /// Think time between IOs (e.g., 100us, 1ms, 10ms)
     #[arg(long)]
@@ -129,19 +334,52 @@ pub struct Cli {
     #[arg(long)]
     pub think_adaptive_percent: Option<u8>,
 
+    /// Spread think time over every operation (deadline-based) instead of
+    /// pausing once every --think-every blocks
+    #[arg(long)]
+    pub think_smooth: bool,
+
     // === IO Engine Options ===
     /// IO engine to use
     #[arg(long, value_enum, default_value = "sync")]
     pub engine: EngineType,
 
+    /// Run --engine as given: no sync engine at queue depth 1, no thread pool when io_uring or libaio is unavailable
+    #[arg(long)]
+    pub no_engine_fallback: bool,
+
     /// Use direct IO (O_DIRECT) - bypasses page cache for real storage testing
     /// Note: Requires aligned buffers and may require pre-existing files
     #[arg(long)]
     pub direct: bool,
 
+    /// Percentage of operations (0-100) issued through a second, buffered fd of each target (requires --direct)
+    #[arg(long)]
+    pub buffered_percent: Option<u8>,
+
     /// Use synchronous IO (O_SYNC)
     #[arg(long)]
     pub sync: bool,
+    
+    /// Extra open flags (comma-separated: noatime,dsync,rsync)
+    #[arg(long, value_name = "FLAGS")]
+    pub open_flags: Option<String>,
+
+    /// Don't pre-fault mmap mappings (MAP_POPULATE); page faults then land in IO latency
+    #[arg(long)]
+    pub no_mmap_populate: bool,
+
+    /// Pin mmap mappings in memory with mlock (subject to RLIMIT_MEMLOCK)
+    #[arg(long)]
+    pub mmap_lock: bool,
+
+    /// Use non-temporal (streaming) stores for mmap writes to avoid cache pollution
+    #[arg(long)]
+    pub mmap_nontemporal: bool,
+
+    /// Huge page backing for IO buffers (madvise = transparent huge pages, hugetlb = reserved pool)
+    #[arg(long, value_enum, default_value = "none")]
+    pub buffer_hugepages: HugePagesMode,
 
     // === fadvise/madvise Options ===
     /// fadvise hints (comma-separated: seq,rand,willneed,dontneed,noreuse)
@@ -162,6 +400,10 @@ pub struct Cli {
     #[arg(long, value_enum, default_value = "shared")]
     pub file_distribution: FileDistributionType,
 
+    /// What to do when a shared file list pick is in use by another worker
+    #[arg(long, value_enum, default_value = "proceed")]
+    pub busy_file: BusyFileMode,
+
     /// Number of files per directory
     #[arg(short = 'n', long)]
     pub num_files: Option<usize>,
@@ -197,6 +439,10 @@ pub struct Cli {
     #[arg(long = "preallocate")]
     pub preallocate: bool,
 
+    /// Pre-allocate file space with this method (implies --preallocate)
+    #[arg(long, value_enum)]
+    pub prealloc_mode: Option<PreallocMode>,
+
     /// Truncate files to size on creation
     #[arg(long)]
     pub truncate_to_size: bool,
@@ -214,6 +460,33 @@ pub struct Cli {
     /// Use this flag to disable auto-fill and get an error instead.
     #[arg(long)]
     pub no_refill: bool,
+    
+    /// Cap the throughput of pre-allocation, refills and layout fills (e.g. 200M, per second,
+    /// split across nodes and workers), to spare other users of shared storage
+    #[arg(long, value_name = "SIZE")]
+    pub prep_rate_limit: Option<String>,
+    
+    /// Check that each target can be created, written and (with --direct) opened with O_DIRECT
+    /// on the coordinator host and every node before preparation begins
+    #[arg(long)]
+    pub preflight: bool,
+    
+    /// Fill whole files, including regions the workload can't reach
+    /// By default, preparation only fills the ranges a composite distribution can reach.
+    #[arg(long)]
+    pub full_fill: bool,
+
+    /// Restrict IO to the byte range START:END of an existing file or block device (e.g. 10G:20G)
+    #[arg(long, value_name = "START:END", conflicts_with_all = ["target_offset", "target_length"])]
+    pub device_region: Option<String>,
+
+    /// Start of the byte range of an existing file or block device that IO is restricted to (e.g. 10G)
+    #[arg(long, value_name = "SIZE")]
+    pub target_offset: Option<String>,
+
+    /// Length of the byte range that IO is restricted to (default: up to the end of the target)
+    #[arg(long, value_name = "SIZE")]
+    pub target_length: Option<String>,
 
     // === Output Options ===
     /// JSON output file path or directory
@@ -240,10 +513,50 @@ pub struct Cli {
     #[arg(long)]
     pub json_interval: Option<String>,
 
+    /// Latency of each time-series interval: its own operations, or all since the start
+    #[arg(long, value_enum, default_value = "interval")]
+    pub interval_latency: IntervalLatency,
+
     /// CSV output file path
     #[arg(long)]
     pub csv_output: Option<PathBuf>,
 
+    /// Start a new CSV/JSON time-series file after this long (e.g., 1h, 30m)
+    #[arg(long)]
+    pub rotate_interval: Option<String>,
+
+    /// Start a new CSV/JSON time-series file once the current one reaches this size (e.g., 100M)
+    #[arg(long)]
+    pub rotate_size: Option<String>,
+
+    /// Gzip rotated time-series files
+    #[arg(long)]
+    pub rotate_compress: bool,
+
+    /// Print a full latency summary of the last interval every N (e.g., 30m, 1h)
+    #[arg(long)]
+    pub summary_interval: Option<String>,
+
+    /// Alert when a node's interval p99 latency exceeds this (e.g., 5ms)
+    #[arg(long)]
+    pub alert_p99: Option<String>,
+
+    /// Alert when a node's interval error rate exceeds this percentage
+    #[arg(long)]
+    pub alert_error_rate: Option<f64>,
+
+    /// Shell command run on alert (JSON payload on stdin, reasons in $IOPULSE_ALERT)
+    #[arg(long)]
+    pub alert_exec: Option<String>,
+
+    /// http:// URL the JSON alert payload is POSTed to
+    #[arg(long)]
+    pub alert_webhook: Option<String>,
+
+    /// Minimum time between alerts
+    #[arg(long, default_value = "60s")]
+    pub alert_cooldown: String,
+
     /// Enable Prometheus metrics endpoint
     #[arg(long)]
     pub prometheus: bool,
@@ -261,6 +574,30 @@ pub struct Cli {
     /// Number of buckets for heatmap (default: 100)
     #[arg(long, default_value = "100")]
     pub heatmap_buckets: usize,
+    
+    /// Write the heatmap buckets to FILE (.csv or .json) for external analysis
+    #[arg(long, value_name = "FILE")]
+    pub heatmap_output: Option<PathBuf>,
+    
+    /// Also record the heatmap buckets of every live interval in --heatmap-output
+    #[arg(long)]
+    pub heatmap_series: bool,
+    
+    /// Render SVG charts (IOPS, throughput, latency CDF, heatmap) into DIR after the run
+    #[arg(long, value_name = "DIR")]
+    pub plot: Option<PathBuf>,
+    
+    /// Compare the achieved block accesses with the distribution's curve after the run
+    #[arg(long)]
+    pub distribution_report: bool,
+
+    /// Rated IOPS of the device (e.g., 800K), reported as an achieved percentage
+    #[arg(long)]
+    pub rated_iops: Option<String>,
+
+    /// Rated throughput of the device per second (e.g., 3500M), or "auto" to use its interface link speed
+    #[arg(long)]
+    pub rated_throughput: Option<String>,
 
     /// Show latency statistics
     #[arg(long)]
@@ -282,6 +619,49 @@ pub struct Cli {
     #[arg(long)]
     pub no_live: bool,
 
+    /// Print a live line per interval with open/close/fsync rates and open fd counts
+    /// (also written to <csv-output>_live.csv)
+    #[arg(long, conflicts_with = "no_live")]
+    pub live_metadata: bool,
+
+    /// Share of worker time live stats snapshots may take, in percent [default: 0.1]
+    #[arg(long, value_name = "PERCENT")]
+    pub live_overhead: Option<f64>,
+
+    /// Increase output detail (-v: per-worker table, engine details, setup timing; -vv: read/write split)
+    #[arg(short = 'v', long, action = ArgAction::Count)]
+    pub verbose: u8,
+
+    /// Print only a one-line summary (for scripts)
+    #[arg(long, conflicts_with = "verbose")]
+    pub quiet: bool,
+
+    /// Print the final summary in IOR or mdtest format (for existing HPC harnesses)
+    #[arg(long, value_enum, default_value = "text", conflicts_with_all = ["verbose", "quiet"])]
+    pub summary_format: SummaryFormat,
+
+    /// Report target file fragmentation (FIEMAP extent counts) after the test
+    #[arg(long)]
+    pub fragmentation: bool,
+
+    /// Report filesystem free space before and after the test, and the space
+    /// consumed per byte written
+    #[arg(long)]
+    pub space_usage: bool,
+
+    /// Latency percentiles to report (comma-separated, e.g., 50,95,99.5,99.99)
+    #[arg(long)]
+    pub percentiles: Option<String>,
+
+    /// Label the run in JSON and CSV results (repeatable, e.g., --label campaign=q3 --label fw=2.1)
+    #[arg(long = "label", visible_alias = "tag", value_name = "KEY=VALUE")]
+    pub labels: Vec<String>,
+
+    /// Run UUID to record in results and logs instead of a generated one
+    /// (e.g., the ID of an outer test harness run)
+    #[arg(long, value_name = "UUID")]
+    pub run_uuid: Option<String>,
+
     // === CPU/NUMA Options ===
     /// CPU cores to bind workers to (comma-separated)
     #[arg(long)]
@@ -291,6 +671,14 @@ pub struct Cli {
     #[arg(long)]
     pub numa_zones: Option<String>,
 
+    /// CPU cores for service, coordinator and network threads (e.g. 0-1), kept off IO workers
+    #[arg(long, value_name = "CORES")]
+    pub housekeeping_cores: Option<String>,
+
+    /// Pin each worker to a core of its own and send live stats over per-worker queues
+    #[arg(long)]
+    pub thread_per_core: bool,
+
     // === Error Handling Options ===
     /// Continue on IO errors instead of aborting
     #[arg(long)]
@@ -300,6 +688,18 @@ pub struct Cli {
     #[arg(long)]
     pub max_errors: Option<usize>,
 
+    /// Keep running when a worker fails (error or panic); report it and exclude its stats
+    #[arg(long)]
+    pub continue_on_worker_failure: bool,
+
+    /// Treat a node connection as lost after this long without heartbeats (e.g., 15s)
+    #[arg(long, default_value = "15s")]
+    pub keepalive_timeout: String,
+
+    /// Wait this long for a lost node connection to come back before failing the run (0 = fail at once)
+    #[arg(long, default_value = "60s")]
+    pub reconnect_timeout: String,
+
     // === Data Integrity Options ===
     /// Enable data verification
     #[arg(long)]
@@ -309,8 +709,16 @@ pub struct Cli {
     #[arg(long, value_enum)]
     pub verify_pattern: Option<VerifyPattern>,
 
+    /// Nodes (indices into the host list, e.g. "0,1") that write the data for two-phase verification
+    #[arg(long, value_name = "NODES")]
+    pub verify_writers: Option<String>,
+
+    /// Nodes that read back and verify the data written by --verify-writers (default: all others)
+    #[arg(long, value_name = "NODES")]
+    pub verify_readers: Option<String>,
+
     // === Configuration File ===
-    /// TOML configuration file
+    /// TOML configuration file (one job, or several concurrent [[jobs]])
     #[arg(short = 'c', long)]
     pub config: Option<PathBuf>,
 
@@ -322,11 +730,81 @@ pub struct Cli {
     #[arg(long)]
     pub debug: bool,
     
+    /// Write each worker's debug log to <DIR>/worker-<id>.log
+    #[arg(long, value_name = "DIR")]
+    pub log_dir: Option<PathBuf>,
+    
+    /// Seed the random op sequence so a run can be reproduced
+    #[arg(long)]
+    pub seed: Option<u64>,
+    
+    /// Stop each worker after submitting N operations (replay up to a failing op)
+    #[arg(long, value_name = "N")]
+    pub stop_at_op: Option<u64>,
+    
+    /// Write each worker's submitted ops to FILE (offsets.txt -> offsets.worker-<id>.txt)
+    #[arg(long, value_name = "FILE")]
+    pub dump_offsets: Option<PathBuf>,
+    
+    /// Journal each worker's writes (offset and checksum) to FILE for `iopulse verify-journal`
+    #[arg(long, value_name = "FILE")]
+    pub write_journal: Option<PathBuf>,
+    
+    /// Stamp writes with generation numbers and sync a commit point every N writes,
+    /// for `iopulse verify-order` after a power cut
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    pub commit_every: Option<u64>,
+    
+    /// Memory budget for coverage/heatmap tracking and retained time-series (e.g. 512M);
+    /// past it, resolution is reduced instead of growing
+    #[arg(long, value_name = "SIZE")]
+    pub stats_mem_limit: Option<String>,
+    
+    /// Record the latency of 1 in N operations to cut overhead at extreme IOPS (counters stay exact)
+    #[arg(long, value_name = "N")]
+    pub latency_sample: Option<u64>,
+    
+    /// Report the N files with the highest mean latency (multi-file runs)
+    #[arg(long, value_name = "N")]
+    pub file_latency_top: Option<usize>,
+    
+    /// Group --file-latency-top by parent directory instead of file
+    #[arg(long, requires = "file_latency_top")]
+    pub file_latency_by_dir: bool,
+    
+    /// Report throughput and latency per Lustre OST from each file's striping layout
+    #[arg(long)]
+    pub ost_stats: bool,
+    
+    /// Split worker time into CPU time and IO wait time (per worker and per engine call)
+    #[arg(long)]
+    pub cpu_accounting: bool,
+    
+    /// Clock used to time operations (tsc: CPU timestamp counter, x86_64 only)
+    #[arg(long, value_enum, default_value = "clock")]
+    pub timer: TimerSource,
+    
+    /// Time io_uring NOP round trips through each worker's ring before the test and report them as a latency floor
+    #[arg(long)]
+    pub latency_floor: bool,
+    
+    /// Operations completing after --duration expired: include, exclude or separate (report apart)
+    #[arg(long, value_enum, default_value = "include")]
+    pub drain_latency: DrainPolicy,
+    
     /// Allow write conflicts in shared mode (benchmark mode - may cause data corruption)
     /// Use this flag to bypass write conflict detection when benchmarking raw performance.
     /// WARNING: This may result in data corruption when multiple workers write to shared files.
     #[arg(long)]
     pub allow_write_conflicts: bool,
+    
+    /// Write to a block device even if it is mounted or holds a filesystem, partition table, RAID, LVM or swap signature
+    #[arg(long)]
+    pub force: bool,
+    
+    /// Open targets read-only (O_RDONLY): refuse writes and never create, fill, truncate or lock-file anything
+    #[arg(long)]
+    pub readonly: bool,
 }
 
 /// Random distribution type
@@ -363,6 +841,99 @@ pub enum EngineType {
     Libaio,
     /// Memory-mapped IO
     Mmap,
+    /// Sync IO on helper threads, for queue depth > 1 where io_uring and libaio are unavailable
+    Threadpool,
+}
+
+/// Final summary layout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SummaryFormat {
+    /// IOPulse report
+    Text,
+    /// IOR "Max Write/Read" lines and summary table
+    Ior,
+    /// mdtest "SUMMARY rate" table
+    Mdtest,
+}
+
+/// What time-series interval latency covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum IntervalLatency {
+    /// Operations completed during the interval
+    Interval,
+    /// All operations since the test started
+    Cumulative,
+}
+
+/// Operation measured by the visibility test
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum VisibilityOp {
+    /// Create a new file
+    Create,
+    /// Rename an existing file to a new name
+    Rename,
+}
+
+/// Check that finds a name visible in the visibility test
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum VisibilityCheck {
+    /// stat() succeeds
+    Stat,
+    /// open() succeeds
+    Open,
+}
+
+/// Clock used to time operations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TimerSource {
+    /// clock_gettime(CLOCK_MONOTONIC)
+    Clock,
+    /// CPU timestamp counter (invariant TSC)
+    Tsc,
+}
+
+/// What the read/write percentages divide
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum RwSplitBy {
+    /// Operation counts
+    Ops,
+    /// Bytes transferred
+    Bytes,
+}
+
+/// Handling of operations drained after the duration expired
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DrainPolicy {
+    /// Count them like any other operation
+    Include,
+    /// Leave them out of the results
+    Exclude,
+    /// Report them separately from the results
+    Separate,
+}
+
+/// Huge page backing for IO buffers
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum HugePagesMode {
+    /// Regular pages
+    None,
+    /// Transparent huge pages (MADV_HUGEPAGE)
+    Madvise,
+    /// Reserved huge pages (MAP_HUGETLB)
+    Hugetlb,
+}
+
+/// File preallocation method
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum PreallocMode {
+    /// fallocate(2): unwritten extents, error if unsupported
+    Fallocate,
+    /// Write zeros over the whole file
+    Zerofill,
+    /// posix_fallocate (writes zeros where fallocate is unsupported)
+    Posix,
+    /// Only set the file size (sparse)
+    None,
 }
 
 /// File locking mode
@@ -376,6 +947,17 @@ pub enum LockMode {
     Full,
 }
 
+/// Busy file policy (shared file lists)
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum BusyFileMode {
+    /// Use the file anyway and count the collision
+    Proceed,
+    /// Pick another file
+    Retry,
+    /// Wait for the other worker to move on
+    Wait,
+}
+
 /// File distribution strategy
 #[derive(Debug, Clone, Copy, ValueEnum)]
 pub enum FileDistributionType {
@@ -398,6 +980,30 @@ pub enum VerifyPattern {
     Random,
     /// Sequential pattern
     Sequential,
+    /// Repeating payload from --pattern-file or --pattern-runs
+    Custom,
+}
+
+/// Worker thread count
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThreadCount {
+    /// This many workers per node
+    Fixed(usize),
+    /// Find the count with a scaling sweep
+    Auto,
+}
+
+impl std::str::FromStr for ThreadCount {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("auto") {
+            return Ok(ThreadCount::Auto);
+        }
+        s.parse()
+            .map(ThreadCount::Fixed)
+            .map_err(|_| format!("expected a thread count or 'auto', got '{}'", s))
+    }
 }
 
 impl Cli {
@@ -408,21 +1014,102 @@ impl Cli {
 
     /// Validate CLI arguments
     pub fn validate(&self) -> anyhow::Result<()> {
-        // Service mode doesn't need validation (coordinator sends config)
-        if self.mode == ExecutionMode::Service {
+        // Service mode doesn't need validation (coordinator sends config),
+        // utility subcommands don't run a test, and API jobs are validated on submit
+        if self.mode == ExecutionMode::Service || self.command.is_some() {
+            return Ok(());
+        }
+        if self.api_listen.is_some() {
+            if self.mode != ExecutionMode::Coordinator {
+                anyhow::bail!("--api-listen requires --mode coordinator");
+            }
+            if self.control_socket.is_some() {
+                anyhow::bail!("--control-socket can't be used with --api-listen (use POST /jobs/{{id}}/control)");
+            }
+            return Ok(());
+        }
+        // Configuration files define their own targets and workloads
+        if self.config.is_some() {
+            if self.mode != ExecutionMode::Standalone {
+                anyhow::bail!("--config requires standalone mode");
+            }
+            if self.target.is_some() {
+                anyhow::bail!("--config can't be combined with a target path (targets come from the config file)");
+            }
             return Ok(());
         }
         
         // Validate threads
-        if self.threads == 0 {
+        if self.threads == ThreadCount::Fixed(0) {
             anyhow::bail!("threads must be at least 1");
         }
+        if self.threads_auto_max == Some(0) {
+            anyhow::bail!("--threads-auto-max must be at least 1");
+        }
+        if self.threads_auto_plateau < 0.0 || self.threads_auto_plateau > 100.0 {
+            anyhow::bail!("threads_auto_plateau must be between 0 and 100");
+        }
 
         // Validate queue depth
         if self.queue_depth == 0 || self.queue_depth > 1024 {
             anyhow::bail!("queue_depth must be between 1 and 1024");
         }
 
+        // Replaying a sequence only makes sense when it is reproducible
+        if self.stop_at_op == Some(0) {
+            anyhow::bail!("--stop-at-op must be at least 1");
+        }
+        if self.stop_at_op.is_some() && self.seed.is_none() {
+            anyhow::bail!("--stop-at-op requires --seed (without it the op sequence is random)");
+        }
+
+        // Two-phase verification needs several nodes and the verification pass
+        if self.verify_readers.is_some() && self.verify_writers.is_none() {
+            anyhow::bail!("--verify-readers requires --verify-writers");
+        }
+        if self.verify_writers.is_some() {
+            if !self.verify {
+                anyhow::bail!("--verify-writers requires --verify");
+            }
+            if self.mode != ExecutionMode::Coordinator {
+                anyhow::bail!("--verify-writers requires --mode coordinator");
+            }
+        }
+
+        // Rotation splits the time-series streamed during duration runs
+        let rotating = self.rotate_interval.is_some() || self.rotate_size.is_some();
+        if self.rotate_compress && !rotating {
+            anyhow::bail!("--rotate-compress requires --rotate-interval or --rotate-size");
+        }
+        if rotating {
+            if self.csv_output.is_none() && self.json_output.is_none() {
+                anyhow::bail!("--rotate-interval/--rotate-size require --csv-output or --json-output");
+            }
+            if self.duration.is_none() {
+                anyhow::bail!("--rotate-interval/--rotate-size require --duration (time-series is only streamed in duration mode)");
+            }
+        }
+
+        if self.summary_interval.is_some() && self.duration.is_none() {
+            anyhow::bail!("--summary-interval requires --duration");
+        }
+        if self.live_metadata && self.duration.is_none() {
+            anyhow::bail!("--live-metadata requires --duration (live lines come from duration-mode heartbeats)");
+        }
+        if self.drain_latency != DrainPolicy::Include && self.duration.is_none() {
+            anyhow::bail!("--drain-latency requires --duration");
+        }
+
+        // Alerts need both a threshold and a hook, and are checked on heartbeats
+        let alert_threshold = self.alert_p99.is_some() || self.alert_error_rate.is_some();
+        let alert_hook = self.alert_exec.is_some() || self.alert_webhook.is_some();
+        if alert_threshold != alert_hook {
+            anyhow::bail!("Alerts need a threshold (--alert-p99, --alert-error-rate) and a hook (--alert-exec, --alert-webhook)");
+        }
+        if alert_threshold && self.duration.is_none() {
+            anyhow::bail!("--alert-p99/--alert-error-rate require --duration");
+        }
+
         // Validate read/write percentages
         if let (Some(r), Some(w)) = (self.read_percent, self.write_percent) {
             if r + w != 100 {
@@ -452,6 +1139,9 @@ impl Cli {
             }
             _ => {}
         }
+        if self.gaussian_drift.is_some() && !matches!(self.distribution, DistributionType::Gaussian) {
+            anyhow::bail!("--gaussian-drift requires --distribution gaussian");
+        }
 
         // Validate think time adaptive percent
         if let Some(pct) = self.think_adaptive_percent {
@@ -467,7 +1157,8 @@ impl Cli {
             self.run_until_complete,
         ];
         let count = completion_modes.iter().filter(|&&x| x).count();
-        if count == 0 {
+        // The visibility test runs no IO, so it needs no completion mode
+        if count == 0 && self.visibility_test.is_none() {
             anyhow::bail!("must specify one of: --duration, --total-bytes, or --run-until-complete");
         }
         if count > 1 {
@@ -480,9 +1171,69 @@ impl Cli {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    
     #[test]
     fn test_validate_threads() {
         // This would require mocking CLI parsing, skip for now
         // Real validation will be tested via integration tests
     }
+    
+    #[test]
+    fn test_threads_auto() {
+        let cli = Cli::try_parse_from(["iopulse", "/tmp/iopulse.dat", "-t", "auto", "--threads-auto-max", "8"]).unwrap();
+        assert_eq!(cli.threads, ThreadCount::Auto);
+        assert_eq!(cli.threads_auto_max, Some(8));
+        assert_eq!(cli.threads_auto_step, "5s");
+
+        let cli = Cli::try_parse_from(["iopulse", "/tmp/iopulse.dat", "-t", "4"]).unwrap();
+        assert_eq!(cli.threads, ThreadCount::Fixed(4));
+        assert!(Cli::try_parse_from(["iopulse", "/tmp/iopulse.dat", "-t", "many"]).is_err());
+    }
+
+    #[test]
+    fn test_ping_subcommand() {
+        let cli = Cli::try_parse_from(["iopulse", "ping", "node1", "node2:9000", "--timeout", "2"]).unwrap();
+        match cli.command {
+            Some(Command::Ping { nodes, timeout }) => {
+                assert_eq!(nodes, vec!["node1", "node2:9000"]);
+                assert_eq!(timeout, 2);
+            }
+            _ => panic!("Expected ping subcommand"),
+        }
+        
+        // A plain target path is still a test run
+        let cli = Cli::try_parse_from(["iopulse", "/tmp/iopulse.dat"]).unwrap();
+        assert!(cli.command.is_none());
+        assert_eq!(cli.target, Some(PathBuf::from("/tmp/iopulse.dat")));
+    }
+
+    #[test]
+    fn test_merge_subcommand() {
+        let cli = Cli::try_parse_from(["iopulse", "merge", "a/aggregate.json", "b/aggregate.json", "-o", "merged.json"]).unwrap();
+        match cli.command {
+            Some(Command::Merge { inputs, output, force }) => {
+                assert_eq!(inputs.len(), 2);
+                assert_eq!(output, PathBuf::from("merged.json"));
+                assert!(!force);
+            }
+            _ => panic!("Expected merge subcommand"),
+        }
+
+        // At least two results are needed
+        assert!(Cli::try_parse_from(["iopulse", "merge", "a.json", "-o", "merged.json"]).is_err());
+    }
+
+    #[test]
+    fn test_verify_journal_subcommand() {
+        let cli = Cli::try_parse_from(["iopulse", "verify-journal", "/dev/sdb", "j.worker-0.bin", "j.worker-1.bin"]).unwrap();
+        match cli.command {
+            Some(Command::VerifyJournal { target, journals }) => {
+                assert_eq!(target, PathBuf::from("/dev/sdb"));
+                assert_eq!(journals.len(), 2);
+            }
+            _ => panic!("Expected verify-journal subcommand"),
+        }
+        assert!(Cli::try_parse_from(["iopulse", "verify-journal", "/dev/sdb"]).is_err());
+    }
 }
@@ -3,6 +3,7 @@
 use crate::config::cli;
 use crate::config::workload;
 use anyhow::{Context, Result};
+use std::collections::BTreeMap;
 
 /// Parse a size string (e.g., "1G", "100M", "4k") to bytes
 pub fn parse_size(s: &str) -> Result<u64> {
@@ -16,6 +17,8 @@ pub fn parse_size(s: &str) -> Result<u64> {
         (s.trim_end_matches("gb").trim_end_matches("g"), 1024 * 1024 * 1024)
     } else if s.ends_with("t") || s.ends_with("tb") {
         (s.trim_end_matches("tb").trim_end_matches("t"), 1024 * 1024 * 1024 * 1024)
+    } else if s.ends_with("b") {
+        (s.trim_end_matches("b"), 1)
     } else {
         (s.as_str(), 1)
     };
@@ -46,6 +49,31 @@ pub fn parse_duration(s: &str) -> Result<u64> {
     Ok(num * multiplier)
 }
 
+/// Parse a duration string (e.g., "500ms", "2s", "5m", "1h") to milliseconds
+///
+/// A number without a unit is seconds.
+pub fn parse_duration_ms(s: &str) -> Result<u64> {
+    let s = s.trim().to_lowercase();
+    
+    let (num_str, multiplier) = if let Some(num) = s.strip_suffix("ms") {
+        (num, 1u64)
+    } else if let Some(num) = s.strip_suffix("sec").or_else(|| s.strip_suffix('s')) {
+        (num, 1000)
+    } else if let Some(num) = s.strip_suffix("min").or_else(|| s.strip_suffix('m')) {
+        (num, 60_000)
+    } else if let Some(num) = s.strip_suffix("hr").or_else(|| s.strip_suffix('h')) {
+        (num, 3_600_000)
+    } else {
+        (s.as_str(), 1000)
+    };
+    
+    let num: u64 = num_str.parse()
+        .with_context(|| format!("Invalid duration format: {}", s))?;
+    
+    num.checked_mul(multiplier)
+        .with_context(|| format!("Duration too long: {}", s))
+}
+
 /// Parse a time string (e.g., "100us", "1ms", "10ms") to microseconds
 pub fn parse_time_us(s: &str) -> Result<u64> {
     let s = s.trim().to_lowercase();
@@ -66,6 +94,164 @@ pub fn parse_time_us(s: &str) -> Result<u64> {
     Ok(num * multiplier)
 }
 
+/// Parse a block size split (e.g., "4k:60,64k:30,1m:10") into IO patterns
+///
+/// Each entry is SIZE:PERCENT; the percentages must add up to 100. The
+/// patterns take the workload's access pattern.
+pub fn parse_bs_split(s: &str, random: bool) -> Result<Vec<workload::IOPattern>> {
+    let access = if random { workload::AccessPattern::Random } else { workload::AccessPattern::Sequential };
+    let mut patterns: Vec<workload::IOPattern> = Vec::new();
+    for entry in s.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let (size, weight) = entry.split_once(':')
+            .ok_or_else(|| anyhow::anyhow!("Invalid block size split entry '{}' (expected SIZE:PERCENT, e.g. 4k:60)", entry))?;
+        let block_size = parse_size(size)?;
+        let weight: u8 = weight.trim().trim_end_matches('%').parse().ok()
+            .filter(|&w| w > 0 && w <= 100)
+            .ok_or_else(|| anyhow::anyhow!("Invalid percentage in block size split entry '{}' (expected 1-100)", entry))?;
+        if patterns.iter().any(|p| p.block_size == block_size) {
+            anyhow::bail!("Block size {} appears twice in the split", size.trim());
+        }
+        patterns.push(workload::IOPattern { weight, access, block_size });
+    }
+    let total: u32 = patterns.iter().map(|p| p.weight as u32).sum();
+    if total != 100 {
+        anyhow::bail!("Block size split percentages must add up to 100, got {}", total);
+    }
+    Ok(patterns)
+}
+
+/// Parse a comma-separated percentile list (e.g., "50,95,99.5,99.99")
+pub fn parse_percentiles(s: &str) -> Result<Vec<f64>> {
+    let mut percentiles = Vec::new();
+    for part in s.split(',') {
+        let p: f64 = part.trim().parse()
+            .with_context(|| format!("Invalid percentile: {}", part.trim()))?;
+        if !(p > 0.0 && p <= 100.0) {
+            anyhow::bail!("Percentile {} out of range (must be > 0 and <= 100)", p);
+        }
+        percentiles.push(p);
+    }
+    Ok(percentiles)
+}
+
+/// Parse `--label key=value` options
+///
+/// Keys may contain letters, digits, `_`, `-` and `.`; values are free text.
+pub fn parse_labels(specs: &[String]) -> Result<BTreeMap<String, String>> {
+    let mut labels = BTreeMap::new();
+    for spec in specs {
+        let (key, value) = spec.split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("Invalid label '{}' (expected KEY=VALUE)", spec))?;
+        let key = key.trim();
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.')) {
+            anyhow::bail!("Invalid label key '{}' (use letters, digits, '_', '-' and '.')", key);
+        }
+        if labels.insert(key.to_string(), value.trim().to_string()).is_some() {
+            anyhow::bail!("Duplicate label '{}'", key);
+        }
+    }
+    Ok(labels)
+}
+
+/// Parse a comma-separated open flag list (e.g., "noatime,dsync")
+pub fn parse_open_flags(s: &str) -> Result<workload::OpenFlagOptions> {
+    let mut flags = workload::OpenFlagOptions::default();
+    for flag in s.split(',') {
+        match flag.trim().to_lowercase().trim_start_matches("o_") {
+            "noatime" => flags.noatime = true,
+            "dsync" => flags.dsync = true,
+            "rsync" => flags.rsync = true,
+            "" => {}
+            other => anyhow::bail!("Unknown open flag: {} (expected noatime, dsync, rsync)", other),
+        }
+    }
+    Ok(flags)
+}
+
+/// Parse a run-length pattern (e.g., "4k:0xaa,4k:0x55") into a custom pattern
+///
+/// Each run is SIZE:BYTE, with the byte in hex (0x prefix) or decimal.
+pub fn parse_pattern_runs(s: &str) -> Result<workload::CustomPattern> {
+    let mut payload = Vec::new();
+    for run in s.split(',').map(str::trim).filter(|r| !r.is_empty()) {
+        let (size, byte) = run.split_once(':')
+            .ok_or_else(|| anyhow::anyhow!("Invalid pattern run '{}' (expected SIZE:BYTE, e.g. 4k:0xaa)", run))?;
+        let size = parse_size(size)? as usize;
+        let byte = match byte.trim().strip_prefix("0x").or_else(|| byte.trim().strip_prefix("0X")) {
+            Some(hex) => u8::from_str_radix(hex, 16),
+            None => byte.trim().parse(),
+        }.map_err(|_| anyhow::anyhow!("Invalid byte '{}' in pattern run '{}' (expected 0x00-0xff)", byte, run))?;
+        if size == 0 {
+            anyhow::bail!("Pattern run '{}' has zero length", run);
+        }
+        if payload.len() + size > workload::CustomPattern::MAX_BYTES {
+            anyhow::bail!("Pattern runs exceed {} bytes", workload::CustomPattern::MAX_BYTES);
+        }
+        payload.resize(payload.len() + size, byte);
+    }
+    if payload.is_empty() {
+        anyhow::bail!("At least one pattern run is required");
+    }
+    Ok(workload::CustomPattern { source: format!("runs {}", s.trim()), payload })
+}
+
+/// Load a custom pattern payload from a file
+pub fn load_pattern_file(path: &std::path::Path) -> Result<workload::CustomPattern> {
+    let size = std::fs::metadata(path)
+        .with_context(|| format!("Failed to read pattern file: {}", path.display()))?
+        .len();
+    if size == 0 || size > workload::CustomPattern::MAX_BYTES as u64 {
+        anyhow::bail!("Pattern file {} must be 1 to {} bytes, got {}",
+                      path.display(), workload::CustomPattern::MAX_BYTES, size);
+    }
+    let payload = std::fs::read(path)
+        .with_context(|| format!("Failed to read pattern file: {}", path.display()))?;
+    Ok(workload::CustomPattern { source: format!("file {}", path.display()), payload })
+}
+
+/// Custom pattern payload from --pattern-file or --pattern-runs, if either is given
+pub fn custom_pattern(file: Option<&std::path::Path>, runs: Option<&str>) -> Result<Option<workload::CustomPattern>> {
+    match (file, runs) {
+        (Some(path), _) => load_pattern_file(path).map(Some),
+        (None, Some(runs)) => parse_pattern_runs(runs).context("Invalid --pattern-runs").map(Some),
+        (None, None) => Ok(None),
+    }
+}
+
+/// Parse a comma-separated list of node indices (e.g., "0,2,3")
+pub fn parse_node_indices(s: &str) -> Result<Vec<usize>> {
+    let mut nodes = Vec::new();
+    for part in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let index: usize = part.parse()
+            .map_err(|_| anyhow::anyhow!("Invalid node index: {}", part))?;
+        if !nodes.contains(&index) {
+            nodes.push(index);
+        }
+    }
+    if nodes.is_empty() {
+        anyhow::bail!("At least one node index is required");
+    }
+    Ok(nodes)
+}
+
+/// Parse a comma-separated madvise hint list (e.g., "seq,hugepage")
+pub fn parse_madvise_flags(s: &str) -> Result<workload::MadviseFlags> {
+    let mut flags = workload::MadviseFlags::default();
+    for flag in s.split(',') {
+        match flag.trim().to_lowercase().as_str() {
+            "seq" | "sequential" => flags.sequential = true,
+            "rand" | "random" => flags.random = true,
+            "willneed" => flags.willneed = true,
+            "dontneed" => flags.dontneed = true,
+            "hugepage" => flags.hugepage = true,
+            "nohugepage" => flags.nohugepage = true,
+            "" => {}
+            other => anyhow::bail!("Invalid madvise flag: {}", other),
+        }
+    }
+    Ok(flags)
+}
+
 /// Convert CLI EngineType to workload EngineType
 pub fn convert_engine_type(cli_type: cli::EngineType) -> workload::EngineType {
     match cli_type {
@@ -73,6 +259,122 @@ pub fn convert_engine_type(cli_type: cli::EngineType) -> workload::EngineType {
         cli::EngineType::IoUring => workload::EngineType::IoUring,
         cli::EngineType::Libaio => workload::EngineType::Libaio,
         cli::EngineType::Mmap => workload::EngineType::Mmap,
+        cli::EngineType::Threadpool => workload::EngineType::Threadpool,
+    }
+}
+
+/// Convert CLI SummaryFormat to output SummaryFormat
+pub fn convert_summary_format(cli_format: cli::SummaryFormat) -> crate::config::SummaryFormat {
+    match cli_format {
+        cli::SummaryFormat::Text => crate::config::SummaryFormat::Text,
+        cli::SummaryFormat::Ior => crate::config::SummaryFormat::Ior,
+        cli::SummaryFormat::Mdtest => crate::config::SummaryFormat::Mdtest,
+    }
+}
+
+/// Build the offset remapping from --remap-offsets and --remap-chunk
+pub fn parse_offset_remap(seed: Option<u64>, chunk: Option<&str>) -> Result<Option<workload::OffsetRemap>> {
+    let Some(seed) = seed else { return Ok(None) };
+    let chunk = chunk.map(parse_size).transpose().context("Invalid --remap-chunk")?;
+    Ok(Some(workload::OffsetRemap { seed, chunk }))
+}
+
+/// Convert CLI IntervalLatency to output IntervalLatency
+pub fn convert_interval_latency(cli_latency: cli::IntervalLatency) -> crate::config::IntervalLatency {
+    match cli_latency {
+        cli::IntervalLatency::Interval => crate::config::IntervalLatency::Interval,
+        cli::IntervalLatency::Cumulative => crate::config::IntervalLatency::Cumulative,
+    }
+}
+
+/// Convert CLI TimerSource to runtime TimerSource
+pub fn convert_timer_source(cli_timer: cli::TimerSource) -> crate::config::TimerSource {
+    match cli_timer {
+        cli::TimerSource::Clock => crate::config::TimerSource::Clock,
+        cli::TimerSource::Tsc => crate::config::TimerSource::Tsc,
+    }
+}
+
+/// Parse --offset-start: "random" or a byte offset (e.g. "4G")
+pub fn parse_offset_start(s: &str) -> Result<workload::OffsetStart> {
+    if s.trim().eq_ignore_ascii_case("random") {
+        return Ok(workload::OffsetStart::Random);
+    }
+    Ok(workload::OffsetStart::At(parse_size(s)?))
+}
+
+/// Parse --device-region: "START:END" byte offsets (e.g. "10G:20G")
+pub fn parse_device_region(s: &str) -> Result<(u64, u64)> {
+    let (start, end) = s.split_once(':')
+        .ok_or_else(|| anyhow::anyhow!("Invalid device region '{}' (expected START:END, e.g. 10G:20G)", s))?;
+    let start = parse_size(start).context("Invalid device region start")?;
+    let end = parse_size(end).context("Invalid device region end")?;
+    if start >= end {
+        anyhow::bail!("Invalid device region '{}': the end must be after the start", s);
+    }
+    Ok((start, end))
+}
+
+/// Resolve the target region from --device-region or --target-offset/--target-length
+///
+/// Without --target-length the region runs to the end of the target, which
+/// must then exist.
+pub fn parse_target_region(cli: &cli::Cli, target: &std::path::Path) -> Result<Option<(u64, u64)>> {
+    if let Some(ref region) = cli.device_region {
+        return parse_device_region(region).map(Some);
+    }
+    if cli.target_offset.is_none() && cli.target_length.is_none() {
+        return Ok(None);
+    }
+    let start = cli.target_offset.as_deref().map(parse_size).transpose()
+        .context("Invalid --target-offset")?
+        .unwrap_or(0);
+    let end = match cli.target_length {
+        Some(ref length) => start + parse_size(length).context("Invalid --target-length")?,
+        None => current_size(target)
+            .ok_or_else(|| anyhow::anyhow!("--target-length is required: {} does not exist", target.display()))?,
+    };
+    if start >= end {
+        anyhow::bail!("Target region {}-{} is empty (--target-offset at or past the end of {}?)", start, end, target.display());
+    }
+    Ok(Some((start, end)))
+}
+
+/// Size of an existing file or block device
+fn current_size(path: &std::path::Path) -> Option<u64> {
+    use std::os::unix::fs::FileTypeExt;
+    
+    let metadata = std::fs::metadata(path).ok()?;
+    if metadata.file_type().is_block_device() {
+        crate::target::block::device_size(path).ok()
+    } else {
+        Some(metadata.len())
+    }
+}
+
+/// Convert CLI RwSplitBy to workload RwSplitBy
+pub fn convert_rw_split_by(cli_split: cli::RwSplitBy) -> workload::RwSplitBy {
+    match cli_split {
+        cli::RwSplitBy::Ops => workload::RwSplitBy::Ops,
+        cli::RwSplitBy::Bytes => workload::RwSplitBy::Bytes,
+    }
+}
+
+/// Convert CLI DrainPolicy to runtime DrainPolicy
+pub fn convert_drain_policy(cli_policy: cli::DrainPolicy) -> crate::config::DrainPolicy {
+    match cli_policy {
+        cli::DrainPolicy::Include => crate::config::DrainPolicy::Include,
+        cli::DrainPolicy::Exclude => crate::config::DrainPolicy::Exclude,
+        cli::DrainPolicy::Separate => crate::config::DrainPolicy::Separate,
+    }
+}
+
+/// Convert CLI HugePagesMode to workload BufferHugePages
+pub fn convert_hugepages(cli_mode: cli::HugePagesMode) -> workload::BufferHugePages {
+    match cli_mode {
+        cli::HugePagesMode::None => workload::BufferHugePages::None,
+        cli::HugePagesMode::Madvise => workload::BufferHugePages::Madvise,
+        cli::HugePagesMode::Hugetlb => workload::BufferHugePages::Hugetlb,
     }
 }
 
@@ -83,6 +385,7 @@ pub fn convert_distribution_type(
     pareto_h: f64,
     gaussian_stddev: Option<f64>,
     gaussian_center: f64,
+    gaussian_drift: Option<&str>,
 ) -> Result<workload::DistributionType> {
     match cli_type {
         cli::DistributionType::Uniform => Ok(workload::DistributionType::Uniform),
@@ -94,11 +397,66 @@ pub fn convert_distribution_type(
             Ok(workload::DistributionType::Gaussian {
                 stddev,
                 center: gaussian_center,
+                drift: gaussian_drift.map(parse_gaussian_drift).transpose()?,
             })
         }
     }
 }
 
+/// Parse a Gaussian center drift (e.g., "linear:0.01", "walk:0.05")
+///
+/// The rate is in fractions of the target per second.
+pub fn parse_gaussian_drift(s: &str) -> Result<workload::CenterDrift> {
+    let (mode, rate) = s.trim().split_once(':')
+        .with_context(|| format!("Invalid Gaussian drift '{}': expected linear:RATE or walk:RATE", s))?;
+    let rate: f64 = rate.trim().parse()
+        .with_context(|| format!("Invalid Gaussian drift rate: {}", rate))?;
+    if !rate.is_finite() {
+        anyhow::bail!("Gaussian drift rate must be a finite number, got {}", rate);
+    }
+    match mode.trim().to_lowercase().as_str() {
+        "linear" => Ok(workload::CenterDrift::Linear { rate }),
+        "walk" | "random-walk" => Ok(workload::CenterDrift::RandomWalk { rate }),
+        _ => anyhow::bail!("Unknown Gaussian drift mode '{}': expected linear or walk", mode),
+    }
+}
+
+/// Parse a count with an optional decimal suffix (e.g., "800K", "1.2M")
+pub fn parse_count(s: &str) -> Result<u64> {
+    let s = s.trim().to_lowercase();
+    let (num_str, multiplier) = match s.chars().last() {
+        Some('k') => (&s[..s.len() - 1], 1e3),
+        Some('m') => (&s[..s.len() - 1], 1e6),
+        Some('g') => (&s[..s.len() - 1], 1e9),
+        _ => (s.as_str(), 1.0),
+    };
+    let num: f64 = num_str.parse()
+        .with_context(|| format!("Invalid count: {}", s))?;
+    if !num.is_finite() || num < 0.0 {
+        anyhow::bail!("Invalid count: {}", s);
+    }
+    Ok((num * multiplier).round() as u64)
+}
+
+/// Parse device ratings (--rated-iops, --rated-throughput)
+pub fn parse_device_rating(iops: Option<&str>, throughput: Option<&str>) -> Result<Option<crate::config::DeviceRating>> {
+    use crate::config::RatedThroughput;
+    
+    if iops.is_none() && throughput.is_none() {
+        return Ok(None);
+    }
+    let throughput = match throughput.map(str::trim) {
+        Some(s) if s.eq_ignore_ascii_case("auto") => Some(RatedThroughput::Auto),
+        Some(s) => Some(RatedThroughput::BytesPerSec(parse_size(s.trim_end_matches("/s"))
+            .context("Invalid --rated-throughput")?)),
+        None => None,
+    };
+    Ok(Some(crate::config::DeviceRating {
+        iops: iops.map(parse_count).transpose().context("Invalid --rated-iops")?,
+        throughput,
+    }))
+}
+
 /// Convert CLI VerifyPattern to workload VerifyPattern
 pub fn convert_verify_pattern(cli_pattern: cli::VerifyPattern) -> workload::VerifyPattern {
     match cli_pattern {
@@ -106,6 +464,7 @@ pub fn convert_verify_pattern(cli_pattern: cli::VerifyPattern) -> workload::Veri
         cli::VerifyPattern::Ones => workload::VerifyPattern::Ones,
         cli::VerifyPattern::Random => workload::VerifyPattern::Random,
         cli::VerifyPattern::Sequential => workload::VerifyPattern::Sequential,
+        cli::VerifyPattern::Custom => workload::VerifyPattern::Custom,
     }
 }
 
@@ -127,6 +486,25 @@ pub fn convert_file_distribution(cli_dist: cli::FileDistributionType) -> workloa
     }
 }
 
+/// Convert CLI BusyFileMode to workload BusyFilePolicy
+pub fn convert_busy_file(cli_mode: cli::BusyFileMode) -> workload::BusyFilePolicy {
+    match cli_mode {
+        cli::BusyFileMode::Proceed => workload::BusyFilePolicy::Proceed,
+        cli::BusyFileMode::Retry => workload::BusyFilePolicy::Retry,
+        cli::BusyFileMode::Wait => workload::BusyFilePolicy::Wait,
+    }
+}
+
+/// Convert CLI PreallocMode to workload PreallocMode
+pub fn convert_prealloc_mode(cli_mode: cli::PreallocMode) -> workload::PreallocMode {
+    match cli_mode {
+        cli::PreallocMode::Fallocate => workload::PreallocMode::Fallocate,
+        cli::PreallocMode::Zerofill => workload::PreallocMode::Zerofill,
+        cli::PreallocMode::Posix => workload::PreallocMode::Posix,
+        cli::PreallocMode::None => workload::PreallocMode::None,
+    }
+}
+
 /// Convert CLI ThinkMode to workload ThinkTimeMode
 pub fn convert_think_mode(cli_mode: cli::ThinkMode) -> workload::ThinkTimeMode {
     match cli_mode {
@@ -135,6 +513,92 @@ pub fn convert_think_mode(cli_mode: cli::ThinkMode) -> workload::ThinkTimeMode {
     }
 }
 
+/// Build queue depth ramp configuration from CLI arguments
+///
+/// Returns `None` unless `--qd-ramp` is set.
+pub fn convert_qd_ramp(
+    enabled: bool,
+    step: &str,
+    max_latency: Option<&str>,
+    plateau_percent: f64,
+) -> Result<Option<workload::QdRampConfig>> {
+    if !enabled {
+        return Ok(None);
+    }
+    
+    let step_seconds = parse_duration(step)
+        .context("Invalid qd-ramp step duration")?;
+    let max_latency_us = max_latency
+        .map(parse_time_us)
+        .transpose()
+        .context("Invalid qd-ramp max latency")?;
+    
+    Ok(Some(workload::QdRampConfig {
+        step_seconds,
+        max_latency_us,
+        plateau_percent,
+    }))
+}
+
+/// Build the worker thread count and scaling configuration from CLI arguments
+///
+/// With `--threads auto` the thread count is the sweep ceiling
+/// (`--threads-auto-max`, by default the number of CPUs).
+pub fn convert_threads(
+    threads: cli::ThreadCount,
+    step: &str,
+    plateau_percent: f64,
+    max_threads: Option<usize>,
+) -> Result<(usize, Option<crate::config::ThreadScalingConfig>)> {
+    match threads {
+        cli::ThreadCount::Fixed(n) => Ok((n, None)),
+        cli::ThreadCount::Auto => {
+            let step_seconds = parse_duration(step)
+                .context("Invalid threads-auto step duration")?;
+            let max_threads = max_threads.unwrap_or_else(num_cpus::get);
+            Ok((max_threads, Some(crate::config::ThreadScalingConfig {
+                step_seconds,
+                plateau_percent,
+                max_threads,
+            })))
+        }
+    }
+}
+
+/// Build latency goal-seeking configuration from CLI arguments
+///
+/// Returns `None` unless `--target-p99` is set.
+pub fn convert_latency_target(target: Option<&str>, step: &str) -> Result<Option<crate::config::LatencyTargetConfig>> {
+    let Some(target) = target else { return Ok(None) };
+    let p99_us = parse_time_us(target)
+        .context("Invalid target-p99 latency")?;
+    let step_seconds = parse_duration(step)
+        .context("Invalid target-p99 step duration")?;
+    Ok(Some(crate::config::LatencyTargetConfig { p99_us, step_seconds }))
+}
+
+/// Build the visibility test configuration from CLI arguments
+///
+/// Returns `None` unless `--visibility-test` is set.
+pub fn convert_visibility(cli: &cli::Cli) -> Result<Option<crate::config::VisibilityConfig>> {
+    let Some(ops) = cli.visibility_test else { return Ok(None) };
+    let timeout_ms = parse_duration_ms(&cli.visibility_timeout)
+        .context("Invalid visibility timeout")?;
+    Ok(Some(crate::config::VisibilityConfig {
+        ops,
+        batch: cli.visibility_batch,
+        op: match cli.visibility_op {
+            cli::VisibilityOp::Create => crate::config::VisibilityOp::Create,
+            cli::VisibilityOp::Rename => crate::config::VisibilityOp::Rename,
+        },
+        check: match cli.visibility_check {
+            cli::VisibilityCheck::Stat => crate::config::VisibilityCheck::Stat,
+            cli::VisibilityCheck::Open => crate::config::VisibilityCheck::Open,
+        },
+        timeout_ms,
+    }))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -143,6 +607,8 @@ mod tests {
     fn test_parse_size_bytes() {
         assert_eq!(parse_size("1024").unwrap(), 1024);
         assert_eq!(parse_size("512").unwrap(), 512);
+        assert_eq!(parse_size("1B").unwrap(), 1);
+        assert_eq!(parse_size("100b").unwrap(), 100);
     }
     
     #[test]
@@ -151,6 +617,7 @@ mod tests {
         assert_eq!(parse_size("4K").unwrap(), 4096);
         assert_eq!(parse_size("4kb").unwrap(), 4096);
         assert_eq!(parse_size("4KB").unwrap(), 4096);
+        assert_eq!(parse_size("3KB").unwrap(), 3072);
     }
     
     #[test]
@@ -166,6 +633,19 @@ mod tests {
         assert_eq!(parse_size("10G").unwrap(), 10 * 1024 * 1024 * 1024);
     }
     
+    #[test]
+    fn test_parse_bs_split() {
+        let patterns = parse_bs_split("4k:60, 64k:30, 1m:10", true).unwrap();
+        let sizes: Vec<_> = patterns.iter().map(|p| (p.block_size, p.weight)).collect();
+        assert_eq!(sizes, vec![(4096, 60), (65536, 30), (1 << 20, 10)]);
+        assert_eq!(patterns[0].access, workload::AccessPattern::Random);
+
+        assert!(parse_bs_split("4k:60,64k:30", false).unwrap_err().to_string().contains("add up to 100"));
+        assert!(parse_bs_split("4k:50,4k:50", false).is_err());
+        assert!(parse_bs_split("4k", false).is_err());
+        assert!(parse_bs_split("4k:0,8k:100", false).is_err());
+    }
+    
     #[test]
     fn test_parse_duration_seconds() {
         assert_eq!(parse_duration("60").unwrap(), 60);
@@ -185,10 +665,153 @@ mod tests {
         assert_eq!(parse_duration("2hr").unwrap(), 7200);
     }
     
+    #[test]
+    fn test_parse_duration_ms() {
+        assert_eq!(parse_duration_ms("500ms").unwrap(), 500);
+        assert_eq!(parse_duration_ms("250MS").unwrap(), 250);
+        assert_eq!(parse_duration_ms("2s").unwrap(), 2000);
+        assert_eq!(parse_duration_ms("60").unwrap(), 60_000);
+        assert_eq!(parse_duration_ms("5min").unwrap(), 300_000);
+        assert_eq!(parse_duration_ms("1h").unwrap(), 3_600_000);
+        assert!(parse_duration_ms("1.5s").is_err());
+        assert!(parse_duration_ms("18446744073709552s").is_err());
+    }
+    
     #[test]
     fn test_parse_time_us() {
         assert_eq!(parse_time_us("100us").unwrap(), 100);
         assert_eq!(parse_time_us("1ms").unwrap(), 1000);
         assert_eq!(parse_time_us("1s").unwrap(), 1_000_000);
     }
+
+    #[test]
+    fn test_parse_percentiles() {
+        assert_eq!(parse_percentiles("50,95,99.5,99.99").unwrap(), vec![50.0, 95.0, 99.5, 99.99]);
+        assert_eq!(parse_percentiles(" 99 , 100").unwrap(), vec![99.0, 100.0]);
+        assert!(parse_percentiles("0").is_err());
+        assert!(parse_percentiles("101").is_err());
+        assert!(parse_percentiles("50,p99").is_err());
+    }
+
+    #[test]
+    fn test_parse_labels() {
+        let labels = parse_labels(&["campaign=q3".to_string(), "fw = 2.1, beta".to_string()]).unwrap();
+        assert_eq!(labels["campaign"], "q3");
+        assert_eq!(labels["fw"], "2.1, beta");
+        assert!(parse_labels(&["campaign".to_string()]).is_err());
+        assert!(parse_labels(&["bad key=1".to_string()]).is_err());
+        assert!(parse_labels(&["a=1".to_string(), "a=2".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_parse_device_region() {
+        assert_eq!(parse_device_region("10G:20G").unwrap(), (10 << 30, 20 << 30));
+        assert_eq!(parse_device_region("0:4096").unwrap(), (0, 4096));
+        assert!(parse_device_region("20G:10G").is_err());
+        assert!(parse_device_region("10G").is_err());
+    }
+
+    #[test]
+    fn test_parse_target_region() {
+        use clap::Parser;
+        
+        let path = std::env::temp_dir().join(format!("iopulse-region-{}.dat", std::process::id()));
+        std::fs::write(&path, vec![0u8; 64 << 10]).unwrap();
+        let region = |args: &[&str]| {
+            let cli = cli::Cli::try_parse_from([&["iopulse", path.to_str().unwrap()], args].concat()).unwrap();
+            parse_target_region(&cli, &path)
+        };
+        assert_eq!(region(&[]).unwrap(), None);
+        assert_eq!(region(&["--target-offset", "16K", "--target-length", "8K"]).unwrap(), Some((16 << 10, 24 << 10)));
+        // Without a length, up to the end of the file
+        assert_eq!(region(&["--target-offset", "16K"]).unwrap(), Some((16 << 10, 64 << 10)));
+        assert_eq!(region(&["--target-length", "4K"]).unwrap(), Some((0, 4 << 10)));
+        assert_eq!(region(&["--device-region", "4K:8K"]).unwrap(), Some((4 << 10, 8 << 10)));
+        assert!(region(&["--target-offset", "64K"]).is_err());
+        std::fs::remove_file(&path).unwrap();
+        assert!(region(&["--target-offset", "16K"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_open_flags() {
+        let flags = parse_open_flags("noatime,DSYNC").unwrap();
+        assert!(flags.noatime && flags.dsync && !flags.rsync);
+        assert!(parse_open_flags("O_RSYNC").unwrap().rsync);
+        assert!(parse_open_flags("direct").is_err());
+    }
+    
+    #[test]
+    fn test_parse_pattern_runs() {
+        let pattern = parse_pattern_runs("4k:0xaa, 2:85").unwrap();
+        assert_eq!(pattern.payload.len(), 4098);
+        assert_eq!(pattern.payload[4095], 0xAA);
+        assert_eq!(pattern.payload[4096..], [0x55, 0x55]);
+        assert!(parse_pattern_runs("4k").is_err());
+        assert!(parse_pattern_runs("4k:0x1ff").is_err());
+        assert!(parse_pattern_runs("0:0xaa").is_err());
+        assert!(parse_pattern_runs("8m:0").is_err());
+    }
+
+    #[test]
+    fn test_parse_node_indices() {
+        assert_eq!(parse_node_indices("0, 2,2").unwrap(), vec![0, 2]);
+        assert!(parse_node_indices("").is_err());
+        assert!(parse_node_indices("a").is_err());
+    }
+
+    #[test]
+    fn test_convert_qd_ramp() {
+        assert!(convert_qd_ramp(false, "5s", None, 5.0).unwrap().is_none());
+        
+        let ramp = convert_qd_ramp(true, "10s", Some("2ms"), 3.0).unwrap().unwrap();
+        assert_eq!(ramp.step_seconds, 10);
+        assert_eq!(ramp.max_latency_us, Some(2000));
+        assert_eq!(ramp.plateau_percent, 3.0);
+    }
+
+    #[test]
+    fn test_convert_threads() {
+        assert_eq!(convert_threads(cli::ThreadCount::Fixed(4), "5s", 10.0, None).unwrap(), (4, None));
+
+        let (threads, scaling) = convert_threads(cli::ThreadCount::Auto, "2s", 15.0, Some(32)).unwrap();
+        assert_eq!(threads, 32);
+        let scaling = scaling.unwrap();
+        assert_eq!((scaling.step_seconds, scaling.plateau_percent, scaling.max_threads), (2, 15.0, 32));
+    }
+
+    #[test]
+    fn test_convert_latency_target() {
+        assert!(convert_latency_target(None, "5s").unwrap().is_none());
+        let target = convert_latency_target(Some("2ms"), "10s").unwrap().unwrap();
+        assert_eq!((target.p99_us, target.step_seconds), (2000, 10));
+        assert!(convert_latency_target(Some("fast"), "5s").is_err());
+    }
+    
+    #[test]
+    fn test_parse_gaussian_drift() {
+        assert_eq!(parse_gaussian_drift("linear:0.01").unwrap(), workload::CenterDrift::Linear { rate: 0.01 });
+        assert_eq!(parse_gaussian_drift("linear:-0.5").unwrap(), workload::CenterDrift::Linear { rate: -0.5 });
+        assert_eq!(parse_gaussian_drift("walk:0.1").unwrap(), workload::CenterDrift::RandomWalk { rate: 0.1 });
+        assert!(parse_gaussian_drift("linear").is_err());
+        assert!(parse_gaussian_drift("spiral:0.1").is_err());
+        assert!(parse_gaussian_drift("walk:inf").is_err());
+    }
+
+    #[test]
+    fn test_parse_device_rating() {
+        use crate::config::RatedThroughput;
+        
+        assert_eq!(parse_count("800K").unwrap(), 800_000);
+        assert_eq!(parse_count("1.5m").unwrap(), 1_500_000);
+        assert_eq!(parse_count("42").unwrap(), 42);
+        assert!(parse_count("-1").is_err());
+
+        assert_eq!(parse_device_rating(None, None).unwrap(), None);
+        let rated = parse_device_rating(Some("800k"), Some("3500M/s")).unwrap().unwrap();
+        assert_eq!(rated.iops, Some(800_000));
+        assert_eq!(rated.throughput, Some(RatedThroughput::BytesPerSec(3500 * 1024 * 1024)));
+        let rated = parse_device_rating(None, Some("auto")).unwrap().unwrap();
+        assert_eq!(rated.throughput, Some(RatedThroughput::Auto));
+        assert!(parse_device_rating(Some("fast"), None).is_err());
+    }
 }
@@ -9,8 +9,10 @@ pub mod validator;
 pub mod workload;
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fmt;
 use std::path::PathBuf;
+use std::time::Duration;
 use workload::*;
 
 /// Complete test configuration
@@ -73,12 +75,109 @@ pub struct WorkloadConfig {
     /// Pattern to use for write buffer data
     #[serde(default)]
     pub write_pattern: VerifyPattern,
+    /// Queue depth ramp (queue_depth becomes the ramp ceiling; protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub qd_ramp: Option<QdRampConfig>,
+    /// mmap engine options (protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub mmap: MmapOptions,
+    /// Huge page backing for IO buffers (protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub buffer_hugepages: BufferHugePages,
+    /// Append-grow mode: extend the file at EOF up to this size, then overwrite (protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub grow_to: Option<u64>,
+    /// Extra open flags: O_NOATIME, O_DSYNC, O_RSYNC (protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub open_flags: OpenFlagOptions,
+    /// Payload for the custom write/verify/refill pattern (protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub custom_pattern: Option<CustomPattern>,
+    /// Whether read_percent splits operations or bytes (protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub rw_split_by: RwSplitBy,
+    /// Where sequential streams begin (default: offset 0, protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub offset_start: Option<OffsetStart>,
+    /// Access pattern per group of workers, in worker order (protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub worker_groups: Vec<WorkerGroup>,
+    /// Percentage of operations issued through a buffered fd alongside O_DIRECT (protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub buffered_percent: u8,
+    /// Chunk permutation that scatters logical offsets over the target (protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub remap_offsets: Option<OffsetRemap>,
+    /// Run the configured engine as given: no sync at queue depth 1, no thread pool stand-in (protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub no_engine_fallback: bool,
+}
+
+impl WorkloadConfig {
+    /// Every IO size of the workload (block size and distribution patterns)
+    pub fn io_sizes(&self) -> impl Iterator<Item = u64> + '_ {
+        std::iter::once(self.block_size)
+            .chain(self.read_distribution.iter().chain(&self.write_distribution).map(|p| p.block_size))
+    }
+
+    /// Group of worker `worker_id`, if workers are grouped
+    ///
+    /// Groups take consecutive workers; with more workers than the groups
+    /// hold (several nodes), the assignment repeats.
+    pub fn worker_group(&self, worker_id: usize) -> Option<&WorkerGroup> {
+        let total: usize = self.worker_groups.iter().map(|g| g.workers).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut index = worker_id % total;
+        self.worker_groups.iter().find(|group| {
+            let found = index < group.workers;
+            index = index.saturating_sub(group.workers);
+            found
+        })
+    }
+
+    /// Apply the access pattern of worker `worker_id`'s group
+    pub fn apply_worker_group(&mut self, worker_id: usize) {
+        if let Some(group) = self.worker_group(worker_id).cloned() {
+            self.random = group.random;
+            self.distribution = group.distribution;
+        }
+    }
+
+    /// Engine the workers run
+    ///
+    /// Queue depth 1 uses the sync engine instead of an async one, which only
+    /// adds overhead without requests in flight, unless `no_engine_fallback`
+    /// asks for the configured engine.
+    pub fn effective_engine(&self) -> EngineType {
+        match self.engine {
+            EngineType::Libaio | EngineType::IoUring | EngineType::Threadpool
+                if self.queue_depth == 1 && !self.no_engine_fallback => EngineType::Sync,
+            engine => engine,
+        }
+    }
+
+    /// Whether any worker uses random access
+    pub fn any_random(&self) -> bool {
+        if self.worker_groups.is_empty() {
+            self.random
+        } else {
+            self.worker_groups.iter().any(|group| group.random)
+        }
+    }
 }
 
 fn default_block_size() -> u64 {
     4096
 }
 
+/// Largest IO size (block size or distribution pattern)
+pub const MAX_IO_SIZE: u64 = 64 * 1024 * 1024;
+
+/// O_DIRECT IO sizes must be a multiple of this (the smallest sector size)
+pub const MIN_DIRECT_IO_SIZE: u64 = 512;
+
 fn default_queue_depth() -> usize {
     1
 }
@@ -134,6 +233,19 @@ pub struct TargetConfig {
     /// Disable automatic file filling for read tests
     #[serde(default)]
     pub no_refill: bool,
+    /// Busy file policy for shared file lists (protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub busy_file: BusyFilePolicy,
+    /// Byte range [start, end) of an existing file or block device that IO,
+    /// refill and verification are restricted to (protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub region: Option<(u64, u64)>,
+    /// Fill the whole file, not only the regions the workload can reach (protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub full_fill: bool,
+    /// How space is preallocated (protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub prealloc_mode: PreallocMode,
 }
 
 /// Target type
@@ -202,12 +314,195 @@ pub struct WorkerConfig {
     /// Only used when file_distribution is Partitioned
     #[serde(skip)]
     pub offset_range: Option<(u64, u64)>,
+    /// ID of the node, then of each worker, in the run (see `util::trace_id`)
+    /// Set by the node service for its workers
+    #[serde(skip)]
+    pub unit_id: Option<String>,
+    /// Core this worker owns (`--thread-per-core`)
+    /// Set by the node service for its workers
+    #[serde(skip)]
+    pub core: Option<usize>,
+    /// Stop all workers when the first one reaches its completion condition (protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub stonewall: bool,
+    /// Give each worker a core of its own and a lock-free path for live statistics (protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub thread_per_core: bool,
+    /// Find the thread count with a scaling sweep (`--threads auto`; `threads` is the ceiling)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub thread_scaling: Option<ThreadScalingConfig>,
+    /// Adjust the IOPS limit until p99 latency sits at a target (`--target-p99`)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub latency_target: Option<LatencyTargetConfig>,
+    /// Measure cross-node metadata visibility instead of IO (`--visibility-test`)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub visibility: Option<VisibilityConfig>,
 }
 
 fn default_threads() -> usize {
     1
 }
 
+/// Thread count scaling configuration
+///
+/// The coordinator runs steps of `step_seconds` at 1, 2, 4, ... workers per
+/// node until throughput improves by less than `plateau_percent` or
+/// `max_threads` is reached, then runs the test with the last thread count
+/// that still paid off.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ThreadScalingConfig {
+    /// Duration of each thread count step in seconds
+    #[serde(default = "default_thread_scaling_step_seconds")]
+    pub step_seconds: u64,
+    /// Stop adding workers when throughput improves by less than this percentage
+    #[serde(default = "default_thread_scaling_plateau_percent")]
+    pub plateau_percent: f64,
+    /// Most workers per node to try
+    pub max_threads: usize,
+}
+
+fn default_thread_scaling_step_seconds() -> u64 {
+    5
+}
+
+fn default_thread_scaling_plateau_percent() -> f64 {
+    10.0
+}
+
+impl ThreadScalingConfig {
+    /// Validate the thread scaling configuration
+    pub fn validate(&self) -> Result<(), String> {
+        if self.step_seconds == 0 {
+            return Err("thread_scaling step_seconds must be greater than 0".to_string());
+        }
+        if self.max_threads == 0 {
+            return Err("thread_scaling max_threads must be greater than 0".to_string());
+        }
+        if !(0.0..=100.0).contains(&self.plateau_percent) {
+            return Err(format!(
+                "thread_scaling plateau_percent must be 0-100, got {}",
+                self.plateau_percent
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Latency goal-seeking configuration
+///
+/// Every `step_seconds` the coordinator compares the step's p99 latency with
+/// `p99_us` and moves the whole test's IOPS limit toward the highest rate
+/// that still meets it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LatencyTargetConfig {
+    /// Target p99 latency in microseconds
+    pub p99_us: u64,
+    /// Duration of each step in seconds
+    #[serde(default = "default_latency_target_step_seconds")]
+    pub step_seconds: u64,
+}
+
+fn default_latency_target_step_seconds() -> u64 {
+    5
+}
+
+impl LatencyTargetConfig {
+    /// Validate the latency target configuration
+    pub fn validate(&self) -> Result<(), String> {
+        if self.p99_us == 0 {
+            return Err("latency_target p99_us must be greater than 0".to_string());
+        }
+        if self.step_seconds == 0 {
+            return Err("latency_target step_seconds must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Cross-node visibility test configuration
+///
+/// The first node creates (or renames) `ops` files in batches of `batch`;
+/// after each batch the other nodes check every name until they see it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct VisibilityConfig {
+    /// Paired operations to measure
+    pub ops: u64,
+    /// Operations per coordinator-sequenced batch
+    #[serde(default = "default_visibility_batch")]
+    pub batch: usize,
+    /// Operation made on the first node
+    #[serde(default)]
+    pub op: VisibilityOp,
+    /// How the other nodes look for the result
+    #[serde(default)]
+    pub check: VisibilityCheck,
+    /// Give up on a name not visible after this many milliseconds
+    #[serde(default = "default_visibility_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+fn default_visibility_batch() -> usize {
+    1
+}
+
+fn default_visibility_timeout_ms() -> u64 {
+    10_000
+}
+
+impl VisibilityConfig {
+    /// Validate the visibility test configuration
+    pub fn validate(&self) -> Result<(), String> {
+        if self.ops == 0 {
+            return Err("visibility ops must be greater than 0".to_string());
+        }
+        if self.batch == 0 {
+            return Err("visibility batch must be greater than 0".to_string());
+        }
+        if self.timeout_ms == 0 {
+            return Err("visibility timeout_ms must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Operation whose visibility is measured
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VisibilityOp {
+    /// Create a new file
+    #[default]
+    Create,
+    /// Rename an existing file to a new name
+    Rename,
+}
+
+impl fmt::Display for VisibilityOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VisibilityOp::Create => write!(f, "create"),
+            VisibilityOp::Rename => write!(f, "rename"),
+        }
+    }
+}
+
+/// Check that finds a name visible
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VisibilityCheck {
+    /// stat() succeeds (attribute and lookup caches)
+    #[default]
+    Stat,
+    /// open() succeeds (close-to-open revalidation)
+    Open,
+}
+
+impl fmt::Display for VisibilityCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VisibilityCheck::Stat => write!(f, "stat"),
+            VisibilityCheck::Open => write!(f, "open"),
+        }
+    }
+}
+
 impl Default for WorkerConfig {
     fn default() -> Self {
         Self {
@@ -217,6 +512,13 @@ impl Default for WorkerConfig {
             rate_limit_iops: None,
             rate_limit_throughput: None,
             offset_range: None,
+            unit_id: None,
+            core: None,
+            stonewall: false,
+            thread_per_core: false,
+            thread_scaling: None,
+            latency_target: None,
+            visibility: None,
         }
     }
 }
@@ -257,14 +559,281 @@ pub struct OutputConfig {
     /// Show latency percentiles
     #[serde(default)]
     pub show_percentiles: bool,
-    /// Live statistics interval (seconds)
+    /// Live statistics interval (seconds; see `live_interval_ms`)
     pub live_interval: Option<u64>,
     /// Disable live statistics
     #[serde(default)]
     pub no_live: bool,
-    /// Output verbosity level
+    /// Output verbosity level (0 = normal, 1 = verbose, 2 = very verbose)
     #[serde(default)]
     pub verbosity: u8,
+    /// Quiet mode: print only a one-line summary (protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub quiet: bool,
+    /// Analyze target file fragmentation after the test (protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub fragmentation: bool,
+    /// Report filesystem space consumed by the test (protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub space_usage: bool,
+    /// Latency percentiles to report, replacing the default set (protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub percentiles: Option<Vec<f64>>,
+    /// Run labels (`--label key=value`) recorded in JSON and CSV results (protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub labels: BTreeMap<String, String>,
+    /// Run UUID recorded in results and logs (`--run-uuid`, otherwise
+    /// generated by the coordinator) (protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub run_uuid: Option<String>,
+    /// Start a new time-series segment after this many seconds (protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub rotate_interval: Option<u64>,
+    /// Start a new time-series segment once the current one reaches this size in bytes (protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub rotate_size: Option<u64>,
+    /// Gzip time-series segments once they are closed (protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub rotate_compress: bool,
+    /// Print a summary checkpoint every this many seconds (protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub summary_interval: Option<u64>,
+    /// Alert hooks fired on latency or error thresholds (protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub alert: Option<AlertConfig>,
+    /// Layout of the final summary (protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub summary_format: SummaryFormat,
+    /// Share of worker time live stats snapshots may take, in percent (protocol v3+)
+    #[serde(default = "default_live_overhead_percent", skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub live_overhead_percent: f64,
+    /// Live statistics interval in milliseconds, replacing `live_interval` (protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub live_interval_ms: Option<u64>,
+    /// Write the heatmap buckets to this file, CSV or JSON by extension (protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub heatmap_output: Option<PathBuf>,
+    /// Also record the heatmap buckets of every live interval (protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub heatmap_series: bool,
+    /// Compare block accesses with the configured distribution after the run (protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub distribution_report: bool,
+    /// Rated performance of the device, reported as achieved-vs-rated percentages (protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub rated: Option<DeviceRating>,
+    /// Latency of time-series intervals: that interval's operations or all since the start (protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub interval_latency: IntervalLatency,
+    /// Render SVG charts of the run into this directory (protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub plot: Option<PathBuf>,
+    /// Print live lines with open/close/fsync rates and open fd counts (protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub live_metadata: bool,
+}
+
+/// Layout of the final summary
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SummaryFormat {
+    /// IOPulse report
+    #[default]
+    Text,
+    /// IOR "Max Write/Read" lines and summary table
+    Ior,
+    /// mdtest "SUMMARY rate" table
+    Mdtest,
+}
+
+impl fmt::Display for SummaryFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SummaryFormat::Text => write!(f, "text"),
+            SummaryFormat::Ior => write!(f, "ior"),
+            SummaryFormat::Mdtest => write!(f, "mdtest"),
+        }
+    }
+}
+
+/// Clock used to time individual operations
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimerSource {
+    /// clock_gettime(CLOCK_MONOTONIC)
+    #[default]
+    Clock,
+    /// CPU timestamp counter (x86_64 with invariant TSC)
+    Tsc,
+}
+
+/// What happens to operations that complete after the test duration expired
+///
+/// When the duration expires, workers stop submitting and wait for the
+/// operations still in flight. Those stragglers often queued behind the
+/// deadline and can skew the tail percentiles.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DrainPolicy {
+    /// Count them like any other operation
+    #[default]
+    Include,
+    /// Leave them out of the results (only their count is reported)
+    Exclude,
+    /// Leave them out of the results and report their latency separately
+    Separate,
+}
+
+impl fmt::Display for DrainPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DrainPolicy::Include => write!(f, "include"),
+            DrainPolicy::Exclude => write!(f, "exclude"),
+            DrainPolicy::Separate => write!(f, "separate"),
+        }
+    }
+}
+
+/// What the latency of a time-series interval covers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IntervalLatency {
+    /// Only the operations completed during the interval
+    #[default]
+    Interval,
+    /// Every operation since the test started
+    Cumulative,
+}
+
+impl fmt::Display for IntervalLatency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntervalLatency::Interval => write!(f, "interval"),
+            IntervalLatency::Cumulative => write!(f, "cumulative"),
+        }
+    }
+}
+
+/// Alert thresholds and the hooks fired when an interval breaches them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlertConfig {
+    /// Interval p99 latency threshold (microseconds)
+    #[serde(default)]
+    pub p99_us: Option<u64>,
+    /// Interval error rate threshold (percent of operations)
+    #[serde(default)]
+    pub error_rate_percent: Option<f64>,
+    /// Shell command run with the alert payload on stdin
+    #[serde(default)]
+    pub exec: Option<String>,
+    /// http:// URL the alert payload is POSTed to
+    #[serde(default)]
+    pub webhook: Option<String>,
+    /// Minimum time between alerts (seconds)
+    #[serde(default = "default_alert_cooldown")]
+    pub cooldown_secs: u64,
+}
+
+fn default_alert_cooldown() -> u64 {
+    60
+}
+
+impl AlertConfig {
+    /// Validate the alert configuration
+    pub fn validate(&self) -> Result<(), String> {
+        if self.p99_us.is_none() && self.error_rate_percent.is_none() {
+            return Err("alert needs a p99 or error rate threshold".to_string());
+        }
+        if self.exec.is_none() && self.webhook.is_none() {
+            return Err("alert needs an exec command or webhook".to_string());
+        }
+        if let Some(rate) = self.error_rate_percent {
+            if !(rate > 0.0 && rate <= 100.0) {
+                return Err("alert error rate must be > 0 and <= 100".to_string());
+            }
+        }
+        if let Some(ref url) = self.webhook {
+            if !url.starts_with("http://") {
+                return Err(format!("alert webhook must be an http:// URL: {}", url));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Rated performance of the device under test
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeviceRating {
+    /// Rated IOPS (reads and writes together)
+    #[serde(default)]
+    pub iops: Option<u64>,
+    /// Rated throughput (reads and writes together)
+    #[serde(default)]
+    pub throughput: Option<RatedThroughput>,
+}
+
+impl DeviceRating {
+    /// Validate the ratings
+    pub fn validate(&self) -> Result<(), String> {
+        if self.iops.is_none() && self.throughput.is_none() {
+            return Err("rated needs an IOPS or throughput rating".to_string());
+        }
+        if self.iops == Some(0) || self.throughput.as_ref().and_then(RatedThroughput::bytes_per_sec) == Some(0) {
+            return Err("rated IOPS and throughput must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Rated throughput of the device under test
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum RatedThroughput {
+    /// The interface link of the first target's device, read before the run
+    /// (see `Config::resolve_device_rating`)
+    Auto,
+    /// Bytes per second, as given
+    BytesPerSec(u64),
+    /// Bytes per second the interface link carries
+    Link { bytes_per_sec: u64, description: String },
+}
+
+impl RatedThroughput {
+    /// Bytes per second (None until `Auto` is resolved)
+    pub fn bytes_per_sec(&self) -> Option<u64> {
+        match *self {
+            RatedThroughput::Auto => None,
+            RatedThroughput::BytesPerSec(rate) | RatedThroughput::Link { bytes_per_sec: rate, .. } => Some(rate),
+        }
+    }
+    
+    /// Where the rating came from
+    pub fn source(&self) -> &str {
+        match self {
+            RatedThroughput::Link { description, .. } => description,
+            _ => "user",
+        }
+    }
+}
+
+/// Placeholder for the run's job ID in target and output paths
+pub const RUN_ID_PLACEHOLDER: &str = "{run_id}";
+
+/// Latency percentiles reported when `--percentiles` is not given
+pub const DEFAULT_PERCENTILES: [f64; 6] = [50.0, 90.0, 95.0, 99.0, 99.9, 99.99];
+
+/// Shortest live statistics interval (node heartbeat and time-series cadence)
+pub const MIN_LIVE_INTERVAL_MS: u64 = 100;
+
+impl OutputConfig {
+    /// Latency percentiles to report (configured list or the default set)
+    pub fn report_percentiles(&self) -> &[f64] {
+        self.percentiles.as_deref().unwrap_or(&DEFAULT_PERCENTILES)
+    }
+    
+    /// Interval of live statistics, node heartbeats and time-series records (default 1s)
+    pub fn live_interval_duration(&self) -> Duration {
+        match (self.live_interval_ms, self.live_interval) {
+            (Some(millis), _) => Duration::from_millis(millis),
+            (None, Some(seconds)) => Duration::from_secs(seconds),
+            (None, None) => Duration::from_secs(1),
+        }
+    }
 }
 
 fn default_json_name() -> String {
@@ -275,6 +844,13 @@ fn default_prometheus_port() -> u16 {
     9090
 }
 
+/// Default live stats overhead budget (percent of worker time)
+pub const DEFAULT_LIVE_OVERHEAD_PERCENT: f64 = 0.1;
+
+fn default_live_overhead_percent() -> f64 {
+    DEFAULT_LIVE_OVERHEAD_PERCENT
+}
+
 impl Default for OutputConfig {
     fn default() -> Self {
         Self {
@@ -293,6 +869,27 @@ impl Default for OutputConfig {
             live_interval: None,
             no_live: false,
             verbosity: 0,
+            quiet: false,
+            fragmentation: false,
+            space_usage: false,
+            percentiles: None,
+            labels: BTreeMap::new(),
+            run_uuid: None,
+            rotate_interval: None,
+            rotate_size: None,
+            rotate_compress: false,
+            summary_interval: None,
+            alert: None,
+            summary_format: SummaryFormat::Text,
+            live_overhead_percent: DEFAULT_LIVE_OVERHEAD_PERCENT,
+            live_interval_ms: None,
+            heatmap_output: None,
+            heatmap_series: false,
+            distribution_report: false,
+            rated: None,
+            interval_latency: IntervalLatency::Interval,
+            plot: None,
+            live_metadata: false,
         }
     }
 }
@@ -322,6 +919,101 @@ pub struct RuntimeConfig {
     /// Allow write conflicts in shared mode (benchmark mode)
     #[serde(default)]
     pub allow_write_conflicts: bool,
+    /// Directory for per-worker log files (one `worker-<id>.log` per worker)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub log_dir: Option<PathBuf>,
+    /// Seed for op selection and offset distributions (random when None)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub seed: Option<u64>,
+    /// Stop each worker after submitting this many operations
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub stop_at_op: Option<u64>,
+    /// Write each worker's submitted operations to a file derived from this path
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub offset_dump: Option<PathBuf>,
+    /// Writer and reader node groups for two-phase distributed verification
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub verify_groups: Option<VerifyGroups>,
+    /// Seed mixed into the random verification pattern (shared by writer and reader nodes)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub verify_seed: Option<u64>,
+    /// Memory budget in bytes for block tracking and retained time-series
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub stats_mem_limit: Option<u64>,
+    /// Record the latency of 1 in N operations (counters stay exact)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub latency_sample: Option<u64>,
+    /// Track latency per file and report the N slowest files
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub file_latency_top: Option<usize>,
+    /// Group per-file latency by parent directory
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub file_latency_by_dir: bool,
+    /// Attribute IOs to Lustre OSTs and report per-OST statistics
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub ost_stats: bool,
+    /// Split worker time into CPU time and wait time
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub cpu_accounting: bool,
+    /// Clock used to time operations
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub timer: TimerSource,
+    /// Seconds without heartbeats (or their acknowledgments) before a node connection counts as lost
+    #[serde(default = "default_keepalive_timeout", skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub keepalive_timeout: u64,
+    /// Seconds to wait for a lost node connection to be re-established (0 = fail at once)
+    #[serde(default = "default_reconnect_timeout", skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub reconnect_timeout: u64,
+    /// Operations completing after the duration expired: include, exclude or report separately
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub drain_latency: DrainPolicy,
+    /// Write to block devices that are mounted or hold a filesystem signature
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub force_device_write: bool,
+    /// Open targets read-only and refuse anything that would write to them
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub read_only: bool,
+    /// Record each worker's writes (offset and checksum) to a journal derived from this path
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub write_journal: Option<PathBuf>,
+    /// Stamp writes with generations and commit one every this many writes (protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub commit_every: Option<u64>,
+    /// Cap on the run's total preparation throughput in bytes/sec: pre-allocation, refills and layout fills (protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub prep_rate_limit: Option<u64>,
+    /// Check that every node can create and write each target before preparation (protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub preflight: bool,
+    /// Time io_uring NOP round trips through each worker's ring before the test (protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub latency_floor: bool,
+}
+
+/// Default keep-alive timeout (seconds)
+pub const DEFAULT_KEEPALIVE_TIMEOUT: u64 = 15;
+
+/// Default reconnect timeout (seconds)
+pub const DEFAULT_RECONNECT_TIMEOUT: u64 = 60;
+
+fn default_keepalive_timeout() -> u64 {
+    DEFAULT_KEEPALIVE_TIMEOUT
+}
+
+fn default_reconnect_timeout() -> u64 {
+    DEFAULT_RECONNECT_TIMEOUT
+}
+
+/// Node groups for two-phase distributed verification
+///
+/// Indices refer to the coordinator's node list (`--host-list` order).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerifyGroups {
+    /// Nodes that write the verification pattern in the first phase
+    pub writers: Vec<usize>,
+    /// Nodes that read it back and verify in the second phase (empty = all other nodes)
+    #[serde(default)]
+    pub readers: Vec<usize>,
 }
 
 impl Default for RuntimeConfig {
@@ -335,6 +1027,29 @@ impl Default for RuntimeConfig {
             dry_run: false,
             debug: false,
             allow_write_conflicts: false,
+            log_dir: None,
+            seed: None,
+            stop_at_op: None,
+            offset_dump: None,
+            verify_groups: None,
+            verify_seed: None,
+            stats_mem_limit: None,
+            latency_sample: None,
+            file_latency_top: None,
+            file_latency_by_dir: false,
+            ost_stats: false,
+            cpu_accounting: false,
+            timer: TimerSource::Clock,
+            keepalive_timeout: DEFAULT_KEEPALIVE_TIMEOUT,
+            reconnect_timeout: DEFAULT_RECONNECT_TIMEOUT,
+            drain_latency: DrainPolicy::Include,
+            force_device_write: false,
+            read_only: false,
+            write_journal: None,
+            commit_every: None,
+            prep_rate_limit: None,
+            preflight: false,
+            latency_floor: false,
         }
     }
 }
@@ -392,6 +1107,15 @@ impl fmt::Display for WorkloadConfig {
             "{}% read / {}% write, queue_depth={}, engine={}, completion={}",
             self.read_percent, self.write_percent, self.queue_depth, self.engine, self.completion_mode
         )?;
+        if self.rw_split_by != RwSplitBy::Ops {
+            write!(f, ", rw_split_by={}", self.rw_split_by)?;
+        }
+        if let Some(start) = self.offset_start {
+            write!(f, ", offset_start={}", start)?;
+        }
+        if let Some(remap) = self.remap_offsets {
+            write!(f, ", remap_offsets=({})", remap)?;
+        }
         if !self.read_distribution.is_empty() {
             write!(f, ", read_dist=[{}]", 
                 self.read_distribution.iter()
@@ -411,6 +1135,9 @@ impl fmt::Display for WorkloadConfig {
         if let Some(ref think_time) = self.think_time {
             write!(f, ", think_time={}", think_time)?;
         }
+        if let Some(ref qd_ramp) = self.qd_ramp {
+            write!(f, ", qd_ramp=[{}]", qd_ramp)?;
+        }
         Ok(())
     }
 }
@@ -427,6 +1154,9 @@ impl fmt::Display for TargetConfig {
         if self.lock_mode != FileLockMode::None {
             write!(f, ", lock={}", self.lock_mode)?;
         }
+        if self.busy_file != BusyFilePolicy::Proceed {
+            write!(f, ", busy_file={}", self.busy_file)?;
+        }
         Ok(())
     }
 }
@@ -467,13 +1197,30 @@ impl fmt::Display for NamingPattern {
 
 impl fmt::Display for WorkerConfig {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{} thread(s)", self.threads)?;
+        match self.thread_scaling {
+            Some(ref scaling) => write!(f, "auto (1-{} threads, {}s steps, plateau <{}%)",
+                scaling.max_threads, scaling.step_seconds, scaling.plateau_percent)?,
+            None => write!(f, "{} thread(s)", self.threads)?,
+        }
         if let Some(ref cores) = self.cpu_cores {
             write!(f, ", cpu_cores={}", cores)?;
         }
         if let Some(ref zones) = self.numa_zones {
             write!(f, ", numa_zones={}", zones)?;
         }
+        if self.stonewall {
+            write!(f, ", stonewall")?;
+        }
+        if self.thread_per_core {
+            write!(f, ", thread per core")?;
+        }
+        if let Some(ref target) = self.latency_target {
+            write!(f, ", target p99={}us ({}s steps)", target.p99_us, target.step_seconds)?;
+        }
+        if let Some(ref visibility) = self.visibility {
+            write!(f, ", visibility test: {} {} ops ({} per batch), {} check",
+                visibility.ops, visibility.op, visibility.batch, visibility.check)?;
+        }
         Ok(())
     }
 }
@@ -487,9 +1234,28 @@ impl fmt::Display for OutputConfig {
         if let Some(ref path) = self.csv_output {
             parts.push(format!("csv={}", path.display()));
         }
+        if let Some(ref path) = self.plot {
+            parts.push(format!("plots={}", path.display()));
+        }
         if self.prometheus {
             parts.push(format!("prometheus=:{}", self.prometheus_port));
         }
+        if self.summary_format != SummaryFormat::Text {
+            parts.push(format!("{} summary", self.summary_format));
+        } else if self.quiet {
+            parts.push("quiet".to_string());
+        } else if self.verbosity > 0 {
+            parts.push(format!("verbosity={}", self.verbosity));
+        }
+        if self.is_rotating() {
+            parts.push("rotating time-series".to_string());
+        }
+        if self.interval_latency == IntervalLatency::Cumulative {
+            parts.push("cumulative interval latency".to_string());
+        }
+        for (key, value) in &self.labels {
+            parts.push(format!("label {}={}", key, value));
+        }
         if parts.is_empty() {
             write!(f, "text output")
         } else {
@@ -554,6 +1320,92 @@ impl Config {
         
         Ok(())
     }
+
+    /// Pick the offset of `--offset-start random` for this run
+    ///
+    /// Chosen once, before the config goes to the workers and nodes, so every
+    /// stream of the run starts at the same offset and the results record it
+    /// (`--offset-start <offset>` repeats the run). The offset is below the
+    /// target size when it is known, and derived from `--seed` when given.
+    pub fn resolve_offset_start(&mut self) {
+        use rand::{Rng, SeedableRng};
+        
+        if self.workload.offset_start != Some(OffsetStart::Random) {
+            return;
+        }
+        let block_size = self.workload.block_size.max(1);
+        let size = self.targets.first()
+            .and_then(|target| {
+                target.region.map(|(start, end)| end - start)
+                    .or(target.file_size)
+                    .or_else(|| std::fs::metadata(&target.path).ok().map(|m| m.len()))
+            })
+            .filter(|&size| size >= block_size)
+            .unwrap_or(u64::MAX);
+        let mut rng = match self.runtime.seed {
+            Some(seed) => rand_xoshiro::Xoshiro256PlusPlus::seed_from_u64(seed),
+            None => rand_xoshiro::Xoshiro256PlusPlus::from_entropy(),
+        };
+        let block = rng.gen_range(0..size / block_size);
+        self.workload.offset_start = Some(OffsetStart::At(block * block_size));
+    }
+    
+    /// Read `--rated-throughput auto` from the first target's interface link
+    ///
+    /// Done once, where the targets are local, so the nodes and the results
+    /// all get the same figure.
+    pub fn resolve_device_rating(&mut self) -> anyhow::Result<()> {
+        let Some(ref mut rated) = self.output.rated else {
+            return Ok(());
+        };
+        if rated.throughput != Some(RatedThroughput::Auto) {
+            return Ok(());
+        }
+        let Some(target) = self.targets.first() else {
+            return Ok(());
+        };
+        let link = crate::util::device_link::interface_link(&target.path)
+            .ok_or_else(|| anyhow::anyhow!(
+                "Can't read the interface link of the device holding {}; give --rated-throughput as a rate (e.g., 3500M)",
+                target.path.display()
+            ))?;
+        rated.throughput = Some(RatedThroughput::Link {
+            bytes_per_sec: link.bytes_per_sec,
+            description: link.description,
+        });
+        Ok(())
+    }
+    
+    /// Replace `{run_id}` in target and output paths with the run's job ID
+    ///
+    /// Lets concurrent runs share one configuration while each works on its
+    /// own dataset and writes its own results (e.g. `/mnt/nvme/test_{run_id}.dat`).
+    pub fn expand_run_id(&mut self, run_id: &str) {
+        fn expand(path: &mut PathBuf, run_id: &str) {
+            let text = path.to_string_lossy();
+            if text.contains(RUN_ID_PLACEHOLDER) {
+                *path = PathBuf::from(text.replace(RUN_ID_PLACEHOLDER, run_id));
+            }
+        }
+
+        for target in &mut self.targets {
+            expand(&mut target.path, run_id);
+            for path in [&mut target.layout_manifest, &mut target.export_layout_manifest].into_iter().flatten() {
+                expand(path, run_id);
+            }
+        }
+        for path in [
+            &mut self.output.json_output,
+            &mut self.output.csv_output,
+            &mut self.output.heatmap_output,
+            &mut self.output.plot,
+            &mut self.runtime.log_dir,
+            &mut self.runtime.offset_dump,
+            &mut self.runtime.write_journal,
+        ].into_iter().flatten() {
+            expand(path, run_id);
+        }
+    }
 }
 
 impl WorkloadConfig {
@@ -601,6 +1453,23 @@ impl WorkloadConfig {
             ));
         }
         
+        // Validate block size (sub-sector sizes are fine for buffered IO)
+        if self.block_size == 0 {
+            return Err("block_size must be greater than 0".to_string());
+        }
+        if self.block_size > MAX_IO_SIZE {
+            return Err(format!("block_size must be at most 64MB, got {}", self.block_size));
+        }
+        let pattern_sizes = self.read_distribution.iter().chain(&self.write_distribution).map(|p| p.block_size);
+        if let Some(size) = std::iter::once(self.block_size).chain(pattern_sizes)
+            .find(|size| self.direct && !size.is_multiple_of(MIN_DIRECT_IO_SIZE))
+        {
+            return Err(format!(
+                "direct IO requires block sizes that are a multiple of {} bytes, got {}",
+                MIN_DIRECT_IO_SIZE, size
+            ));
+        }
+        
         // Validate queue depth
         if self.queue_depth == 0 {
             return Err("queue_depth must be greater than 0".to_string());
@@ -639,6 +1508,9 @@ impl WorkloadConfig {
         
         // Validate distribution type
         self.distribution.validate()?;
+        for (i, group) in self.worker_groups.iter().enumerate() {
+            group.distribution.validate().map_err(|e| format!("worker_groups[{}]: {}", i, e))?;
+        }
         
         // Validate completion mode
         self.completion_mode.validate()?;
@@ -648,6 +1520,29 @@ impl WorkloadConfig {
             think_time.validate()?;
         }
         
+        // Validate queue depth ramp
+        if let Some(ref qd_ramp) = self.qd_ramp {
+            qd_ramp.validate(self.queue_depth)?;
+        }
+        
+        // mmap options only apply to the mmap engine
+        if self.mmap != MmapOptions::default() && self.engine != EngineType::Mmap {
+            return Err(format!("mmap options ({}) require the mmap engine, got {}", self.mmap, self.engine));
+        }
+        
+        // Append-grow extends the file with writes
+        if let Some(grow_to) = self.grow_to {
+            if self.write_percent != 100 {
+                return Err(format!("grow_to requires write_percent = 100, got {}", self.write_percent));
+            }
+            if self.engine == EngineType::Mmap {
+                return Err("grow_to is not supported with the mmap engine (mappings cannot extend the file)".to_string());
+            }
+            if grow_to < self.block_size {
+                return Err(format!("grow_to ({}) must be at least block_size ({})", grow_to, self.block_size));
+            }
+        }
+        
         Ok(())
     }
 }
@@ -723,6 +1618,15 @@ impl WorkerConfig {
         if self.threads == 0 {
             return Err("threads must be greater than 0".to_string());
         }
+        if let Some(ref scaling) = self.thread_scaling {
+            scaling.validate()?;
+        }
+        if let Some(ref target) = self.latency_target {
+            target.validate()?;
+        }
+        if let Some(ref visibility) = self.visibility {
+            visibility.validate()?;
+        }
         
         // Validate CPU cores format if specified
         if let Some(ref cores) = self.cpu_cores {
@@ -750,9 +1654,51 @@ impl OutputConfig {
                 return Err("live_interval must be greater than 0".to_string());
             }
         }
+        if self.live_interval_duration() < Duration::from_millis(MIN_LIVE_INTERVAL_MS) {
+            return Err(format!("live interval must be at least {}ms", MIN_LIVE_INTERVAL_MS));
+        }
+        
+        if self.rotate_interval == Some(0) || self.rotate_size == Some(0) {
+            return Err("rotate_interval and rotate_size must be greater than 0".to_string());
+        }
+        if let Some(ref alert) = self.alert {
+            alert.validate()?;
+        }
+        if let Some(ref rated) = self.rated {
+            rated.validate()?;
+        }
+        if self.summary_interval == Some(0) {
+            return Err("summary_interval must be greater than 0".to_string());
+        }
+        if self.rotate_compress && !self.is_rotating() {
+            return Err("rotate_compress requires rotate_interval or rotate_size".to_string());
+        }
         
         Ok(())
     }
+    
+    /// Whether time-series output is split into rotated segment files
+    pub fn is_rotating(&self) -> bool {
+        self.rotate_interval.is_some() || self.rotate_size.is_some()
+    }
+    
+    /// Whether progress and the full report are suppressed
+    ///
+    /// True in quiet mode and with an IOR/mdtest summary format, whose
+    /// output must be only the summary harnesses parse.
+    pub fn is_quiet(&self) -> bool {
+        self.quiet || self.summary_format != SummaryFormat::Text
+    }
+    
+    /// Whether verbose sections (per-worker tables, engine details, setup timing) are shown
+    pub fn is_verbose(&self) -> bool {
+        !self.quiet && self.verbosity >= 1
+    }
+    
+    /// Whether very verbose sections (read/write split, tail latency per worker) are shown
+    pub fn is_very_verbose(&self) -> bool {
+        !self.quiet && self.verbosity >= 2
+    }
 }
 
 impl RuntimeConfig {
@@ -764,6 +1710,11 @@ impl RuntimeConfig {
             }
         }
         
+        // Heartbeats are sent every second; allow for a few late ones
+        if self.keepalive_timeout < 3 {
+            return Err("keepalive_timeout must be at least 3 seconds".to_string());
+        }
+        
         Ok(())
     }
 }
@@ -907,6 +1858,18 @@ mod tests {
             heatmap: false,
             heatmap_buckets: 100,
             write_pattern: workload::VerifyPattern::Random,
+            qd_ramp: None,
+            mmap: MmapOptions::default(),
+            buffer_hugepages: BufferHugePages::None,
+            grow_to: None,
+            open_flags: OpenFlagOptions::default(),
+            custom_pattern: None,
+            rw_split_by: RwSplitBy::Ops,
+            offset_start: None,
+            worker_groups: Vec::new(),
+            buffered_percent: 0,
+            remap_offsets: None,
+            no_engine_fallback: false,
         };
 
         let engine_config = workload.to_engine_config();
@@ -936,6 +1899,18 @@ mod tests {
             heatmap: false,
             heatmap_buckets: 100,
             write_pattern: workload::VerifyPattern::Random,
+            qd_ramp: None,
+            mmap: MmapOptions::default(),
+            buffer_hugepages: BufferHugePages::None,
+            grow_to: None,
+            open_flags: OpenFlagOptions::default(),
+            custom_pattern: None,
+            rw_split_by: RwSplitBy::Ops,
+            offset_start: None,
+            worker_groups: Vec::new(),
+            buffered_percent: 0,
+            remap_offsets: None,
+            no_engine_fallback: false,
         };
 
         let engine_config = workload.to_engine_config();
@@ -964,6 +1939,18 @@ mod tests {
             heatmap: false,
             heatmap_buckets: 100,
             write_pattern: workload::VerifyPattern::Random,
+            qd_ramp: None,
+            mmap: MmapOptions::default(),
+            buffer_hugepages: BufferHugePages::None,
+            grow_to: None,
+            open_flags: OpenFlagOptions::default(),
+            custom_pattern: None,
+            rw_split_by: RwSplitBy::Ops,
+            offset_start: None,
+            worker_groups: Vec::new(),
+            buffered_percent: 0,
+            remap_offsets: None,
+            no_engine_fallback: false,
         };
 
         let engine_config = workload.to_engine_config();
@@ -991,6 +1978,18 @@ mod tests {
             heatmap: false,
             heatmap_buckets: 100,
             write_pattern: workload::VerifyPattern::Random,
+            qd_ramp: None,
+            mmap: MmapOptions::default(),
+            buffer_hugepages: BufferHugePages::None,
+            grow_to: None,
+            open_flags: OpenFlagOptions::default(),
+            custom_pattern: None,
+            rw_split_by: RwSplitBy::Ops,
+            offset_start: None,
+            worker_groups: Vec::new(),
+            buffered_percent: 0,
+            remap_offsets: None,
+            no_engine_fallback: false,
         };
 
         let engine_config = workload.to_engine_config();
@@ -1018,6 +2017,18 @@ mod tests {
             heatmap: false,
             heatmap_buckets: 100,
             write_pattern: workload::VerifyPattern::Random,
+            qd_ramp: None,
+            mmap: MmapOptions::default(),
+            buffer_hugepages: BufferHugePages::None,
+            grow_to: None,
+            open_flags: OpenFlagOptions::default(),
+            custom_pattern: None,
+            rw_split_by: RwSplitBy::Ops,
+            offset_start: None,
+            worker_groups: Vec::new(),
+            buffered_percent: 0,
+            remap_offsets: None,
+            no_engine_fallback: false,
         };
 
         let engine_config = workload.to_engine_config();
@@ -1025,4 +2036,151 @@ mod tests {
         assert!(!engine_config.use_registered_buffers); // libaio doesn't use io_uring features
         assert!(!engine_config.use_fixed_files);
     }
+
+    #[test]
+    fn test_mmap_options_require_mmap_engine() {
+        let mut workload = WorkloadConfig {
+            read_percent: 0,
+            write_percent: 100,
+            read_distribution: vec![],
+            write_distribution: vec![],
+            block_size: 4096,
+            queue_depth: 1,
+            completion_mode: CompletionMode::RunUntilComplete,
+            random: false,
+            distribution: DistributionType::Uniform,
+            think_time: None,
+            engine: workload::EngineType::Sync,
+            direct: false,
+            sync: false,
+            heatmap: false,
+            heatmap_buckets: 100,
+            write_pattern: workload::VerifyPattern::Random,
+            qd_ramp: None,
+            mmap: MmapOptions::default(),
+            buffer_hugepages: BufferHugePages::None,
+            grow_to: None,
+            open_flags: OpenFlagOptions::default(),
+            custom_pattern: None,
+            rw_split_by: RwSplitBy::Ops,
+            offset_start: None,
+            worker_groups: Vec::new(),
+            buffered_percent: 0,
+            remap_offsets: None,
+            no_engine_fallback: false,
+        };
+        assert!(workload.validate().is_ok());
+
+        workload.mmap.nontemporal = true;
+        assert!(workload.validate().is_err());
+
+        workload.engine = workload::EngineType::Mmap;
+        assert!(workload.validate().is_ok());
+    }
+
+    #[test]
+    fn test_expand_run_id() {
+        let mut config: Config = ::toml::from_str(r#"
+            [[targets]]
+            path = "/mnt/test_{run_id}.dat"
+
+            [workload]
+            read_percent = 100
+            write_percent = 0
+            block_size = 4096
+            queue_depth = 1
+            completion_mode = "RunUntilComplete"
+
+            [output]
+            json_output = "results/{run_id}"
+        "#).unwrap();
+        config.expand_run_id("job-7");
+        assert_eq!(config.targets[0].path, PathBuf::from("/mnt/test_job-7.dat"));
+        assert_eq!(config.output.json_output, Some(PathBuf::from("results/job-7")));
+        assert_eq!(config.output.csv_output, None);
+    }
+
+    #[test]
+    fn test_effective_engine() {
+        let mut workload: WorkloadConfig = ::toml::from_str(r#"
+            read_percent = 100
+            write_percent = 0
+            queue_depth = 1
+            engine = "io_uring"
+            completion_mode = "RunUntilComplete"
+        "#).unwrap();
+        assert_eq!(workload.effective_engine(), workload::EngineType::Sync);
+        workload.queue_depth = 32;
+        assert_eq!(workload.effective_engine(), workload::EngineType::IoUring);
+
+        // Forced: the requested engine even at queue depth 1
+        workload.queue_depth = 1;
+        workload.no_engine_fallback = true;
+        assert_eq!(workload.effective_engine(), workload::EngineType::IoUring);
+    }
+
+    #[test]
+    fn test_millisecond_duration_and_live_interval() {
+        let config: Config = ::toml::from_str(r#"
+            [[targets]]
+            path = "/mnt/test.dat"
+
+            [workload]
+            read_percent = 100
+            write_percent = 0
+            block_size = 4096
+            queue_depth = 1
+            completion_mode = { Duration = { seconds = 0, millis = 500 } }
+
+            [output]
+            live_interval_ms = 250
+        "#).unwrap();
+        assert_eq!(config.workload.completion_mode.test_duration(), Some(Duration::from_millis(500)));
+        assert_eq!(config.workload.completion_mode.to_string(), "duration(500ms)");
+        assert_eq!(config.output.live_interval_duration(), Duration::from_millis(250));
+        assert_eq!(OutputConfig::default().live_interval_duration(), Duration::from_secs(1));
+
+        let output = OutputConfig { live_interval_ms: Some(50), ..Default::default() };
+        assert!(output.validate().is_err());
+    }
+
+    #[test]
+    fn test_worker_groups() {
+        let mut config: Config = ::toml::from_str(r#"
+            [[targets]]
+            path = "/mnt/test.dat"
+
+            [workers]
+            threads = 8
+
+            [workload]
+            read_percent = 100
+            write_percent = 0
+            queue_depth = 1
+            completion_mode = "RunUntilComplete"
+
+            [[workload.worker_groups]]
+            workers = 2
+
+            [[workload.worker_groups]]
+            workers = 6
+            random = true
+            distribution = { Zipf = { theta = 1.2 } }
+        "#).unwrap();
+        assert!(crate::config::validator::validate_config(&config).is_ok());
+        assert!(config.workload.any_random());
+        assert!(!config.workload.worker_group(1).unwrap().random);
+        assert!(config.workload.worker_group(2).unwrap().random);
+        // A second node's workers repeat the mix
+        assert!(!config.workload.worker_group(9).unwrap().random);
+
+        let mut workload = config.workload.clone();
+        workload.apply_worker_group(7);
+        assert!(workload.random);
+        assert!(matches!(workload.distribution, DistributionType::Zipf { .. }));
+        assert_eq!(config.workload.worker_groups[0].to_string(), "2 x sequential");
+
+        config.workers.threads = 4;
+        assert!(crate::config::validator::validate_config(&config).is_err());
+    }
 }
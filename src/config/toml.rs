@@ -34,6 +34,21 @@ pub fn parse_multi_phase_toml(path: &Path) -> Result<MultiPhaseConfig> {
     Ok(config)
 }
 
+/// Merge a TOML table into another
+///
+/// Fields present in `overlay` replace those of `base`; nested tables are
+/// merged field by field.
+pub fn merge_table(base: &mut ::toml::Table, overlay: &::toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(key), value) {
+            (Some(::toml::Value::Table(base)), ::toml::Value::Table(overlay)) => merge_table(base, overlay),
+            _ => {
+                base.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
 /// Merge CLI arguments with TOML configuration (CLI takes precedence)
 pub fn merge_cli_with_config(cli: &Cli, mut config: Config) -> Result<Config> {
     // Override workload settings from CLI
@@ -43,6 +58,12 @@ pub fn merge_cli_with_config(cli: &Cli, mut config: Config) -> Result<Config> {
     if let Some(write_pct) = cli.write_percent {
         config.workload.write_percent = write_pct;
     }
+    if let Some(ref start) = cli.offset_start {
+        config.workload.offset_start = Some(crate::config::cli_convert::parse_offset_start(start)?);
+    }
+    if cli.rw_split_by != cli::RwSplitBy::Ops {
+        config.workload.rw_split_by = crate::config::cli_convert::convert_rw_split_by(cli.rw_split_by);
+    }
 
     // Override queue depth
     if cli.queue_depth != 1 {
@@ -59,18 +80,21 @@ pub fn merge_cli_with_config(cli: &Cli, mut config: Config) -> Result<Config> {
             DistributionType::Gaussian {
                 stddev,
                 center: cli.gaussian_center,
+                drift: cli.gaussian_drift.as_deref()
+                    .map(crate::config::cli_convert::parse_gaussian_drift)
+                    .transpose()?,
             }
         }
     };
 
     // Override completion mode
     if let Some(duration_str) = &cli.duration {
-        let seconds = parse_duration(duration_str)?;
-        if seconds == 0 {
+        let millis = crate::config::cli_convert::parse_duration_ms(duration_str)?;
+        if millis == 0 {
             // Duration 0 means "run until file is complete"
             config.workload.completion_mode = CompletionMode::RunUntilComplete;
         } else {
-            config.workload.completion_mode = CompletionMode::Duration { seconds };
+            config.workload.completion_mode = CompletionMode::duration(std::time::Duration::from_millis(millis));
         }
     } else if let Some(bytes_str) = &cli.total_bytes {
         let bytes = parse_size(bytes_str)?;
@@ -90,7 +114,22 @@ pub fn merge_cli_with_config(cli: &Cli, mut config: Config) -> Result<Config> {
             },
             apply_every_n_blocks: cli.think_every,
             adaptive_percent: cli.think_adaptive_percent,
+            smooth: cli.think_smooth,
         });
+    } else if cli.think_smooth {
+        if let Some(ref mut think_time) = config.workload.think_time {
+            think_time.smooth = true;
+        }
+    }
+
+    // Override queue depth ramp
+    if cli.qd_ramp {
+        config.workload.qd_ramp = crate::config::cli_convert::convert_qd_ramp(
+            true,
+            &cli.qd_ramp_step,
+            cli.qd_ramp_max_latency.as_deref(),
+            cli.qd_ramp_plateau,
+        )?;
     }
 
     // Override engine
@@ -99,6 +138,7 @@ pub fn merge_cli_with_config(cli: &Cli, mut config: Config) -> Result<Config> {
         CliEngineType::IoUring => EngineType::IoUring,
         CliEngineType::Libaio => EngineType::Libaio,
         CliEngineType::Mmap => EngineType::Mmap,
+        CliEngineType::Threadpool => EngineType::Threadpool,
     };
 
     // Override direct/sync flags
@@ -108,10 +148,44 @@ pub fn merge_cli_with_config(cli: &Cli, mut config: Config) -> Result<Config> {
     if cli.sync {
         config.workload.sync = true;
     }
+    if cli.no_engine_fallback {
+        config.workload.no_engine_fallback = true;
+    }
+    if let Some(percent) = cli.buffered_percent {
+        config.workload.buffered_percent = percent;
+    }
+    if let Some(remap) = crate::config::cli_convert::parse_offset_remap(cli.remap_offsets, cli.remap_chunk.as_deref())? {
+        config.workload.remap_offsets = Some(remap);
+    }
+    if let Some(ref flags) = cli.open_flags {
+        config.workload.open_flags = crate::config::cli_convert::parse_open_flags(flags)
+            .context("Invalid --open-flags")?;
+    }
+
+    // Override custom pattern payload
+    if let Some(pattern) = crate::config::cli_convert::custom_pattern(cli.pattern_file.as_deref(), cli.pattern_runs.as_deref())? {
+        config.workload.custom_pattern = Some(pattern);
+    }
 
     // Override worker settings
-    if cli.threads != 1 {
-        config.workers.threads = cli.threads;
+    if cli.threads != crate::config::cli::ThreadCount::Fixed(1) {
+        let (threads, thread_scaling) = crate::config::cli_convert::convert_threads(
+            cli.threads,
+            &cli.threads_auto_step,
+            cli.threads_auto_plateau,
+            cli.threads_auto_max,
+        )?;
+        config.workers.threads = threads;
+        config.workers.thread_scaling = thread_scaling;
+    }
+    if cli.target_p99.is_some() {
+        config.workers.latency_target = crate::config::cli_convert::convert_latency_target(
+            cli.target_p99.as_deref(),
+            &cli.target_p99_step,
+        )?;
+    }
+    if cli.visibility_test.is_some() {
+        config.workers.visibility = crate::config::cli_convert::convert_visibility(cli)?;
     }
     if let Some(ref cores) = cli.cpu_cores {
         config.workers.cpu_cores = Some(cores.clone());
@@ -119,6 +193,9 @@ pub fn merge_cli_with_config(cli: &Cli, mut config: Config) -> Result<Config> {
     if let Some(ref zones) = cli.numa_zones {
         config.workers.numa_zones = Some(zones.clone());
     }
+    if cli.thread_per_core {
+        config.workers.thread_per_core = true;
+    }
 
     // Override output settings
     if let Some(ref path) = cli.json_output {
@@ -141,12 +218,40 @@ pub fn merge_cli_with_config(cli: &Cli, mut config: Config) -> Result<Config> {
         config.output.show_percentiles = true;
     }
     if let Some(ref interval_str) = cli.live_interval {
-        let seconds = parse_duration(interval_str)?;
-        config.output.live_interval = Some(seconds);
+        config.output.live_interval_ms = Some(crate::config::cli_convert::parse_duration_ms(interval_str)?);
     }
     if cli.no_live {
         config.output.no_live = true;
     }
+    if cli.live_metadata {
+        config.output.live_metadata = true;
+    }
+    if let Some(percent) = cli.live_overhead {
+        config.output.live_overhead_percent = percent;
+    }
+    if cli.verbose > 0 {
+        config.output.verbosity = cli.verbose;
+        config.output.quiet = false;
+    }
+    if cli.summary_format != cli::SummaryFormat::Text {
+        config.output.summary_format = crate::config::cli_convert::convert_summary_format(cli.summary_format);
+    }
+    if let Some(ref uuid) = cli.run_uuid {
+        config.output.run_uuid = Some(crate::util::trace_id::parse_run_uuid(uuid)?);
+    }
+    if let Some(ref dir) = cli.plot {
+        config.output.plot = Some(dir.clone());
+    }
+    if cli.interval_latency != cli::IntervalLatency::Interval {
+        config.output.interval_latency = crate::config::cli_convert::convert_interval_latency(cli.interval_latency);
+    }
+    if cli.quiet {
+        config.output.quiet = true;
+        config.output.verbosity = 0;
+    }
+    if let Some(rated) = crate::config::cli_convert::parse_device_rating(cli.rated_iops.as_deref(), cli.rated_throughput.as_deref())? {
+        config.output.rated = Some(rated);
+    }
 
     // Override runtime settings
     if cli.continue_on_error {
@@ -164,11 +269,27 @@ pub fn merge_cli_with_config(cli: &Cli, mut config: Config) -> Result<Config> {
             cli::VerifyPattern::Ones => VerifyPattern::Ones,
             cli::VerifyPattern::Random => VerifyPattern::Random,
             cli::VerifyPattern::Sequential => VerifyPattern::Sequential,
+            cli::VerifyPattern::Custom => VerifyPattern::Custom,
         });
     }
     if cli.dry_run {
         config.runtime.dry_run = true;
     }
+    if cli.timer != cli::TimerSource::Clock {
+        config.runtime.timer = crate::config::cli_convert::convert_timer_source(cli.timer);
+    }
+    if cli.drain_latency != cli::DrainPolicy::Include {
+        config.runtime.drain_latency = crate::config::cli_convert::convert_drain_policy(cli.drain_latency);
+    }
+    if cli.preflight {
+        config.runtime.preflight = true;
+    }
+    if cli.latency_floor {
+        config.runtime.latency_floor = true;
+    }
+    if let Some(ref rate) = cli.prep_rate_limit {
+        config.runtime.prep_rate_limit = Some(crate::config::cli_convert::parse_size(rate).context("Invalid --prep-rate-limit")?);
+    }
 
     // Override target settings if CLI provides target
     if let Some(ref target_path) = cli.target {
@@ -194,6 +315,7 @@ pub fn merge_cli_with_config(cli: &Cli, mut config: Config) -> Result<Config> {
 fn create_target_from_cli(cli: &Cli) -> Result<TargetConfig> {
     let target_path = cli.target.clone()
         .ok_or_else(|| anyhow::anyhow!("Target path required"))?;
+    let region = crate::config::cli_convert::parse_target_region(cli, &target_path)?;
     
     let target = TargetConfig {
         path: target_path,
@@ -216,7 +338,9 @@ fn create_target_from_cli(cli: &Cli) -> Result<TargetConfig> {
             cli::LockMode::Range => FileLockMode::Range,
             cli::LockMode::Full => FileLockMode::Full,
         },
-        preallocate: cli.preallocate,  // Default: false
+        busy_file: crate::config::cli_convert::convert_busy_file(cli.busy_file),
+        preallocate: cli.preallocate || cli.prealloc_mode.is_some(),  // Default: false
+        prealloc_mode: cli.prealloc_mode.map(crate::config::cli_convert::convert_prealloc_mode).unwrap_or_default(),
         truncate_to_size: cli.truncate_to_size,
         refill: cli.refill,
         refill_pattern: match cli.refill_pattern {
@@ -224,8 +348,11 @@ fn create_target_from_cli(cli: &Cli) -> Result<TargetConfig> {
             cli::VerifyPattern::Ones => VerifyPattern::Ones,
             cli::VerifyPattern::Random => VerifyPattern::Random,
             cli::VerifyPattern::Sequential => VerifyPattern::Sequential,
+            cli::VerifyPattern::Custom => VerifyPattern::Custom,
         },
         no_refill: cli.no_refill,
+        region,
+        full_fill: cli.full_fill,
     };
 
     Ok(target)
@@ -245,6 +372,10 @@ fn apply_cli_target_overrides(target: &mut TargetConfig, cli: &Cli) -> Result<()
     if cli.preallocate {  // Only preallocate if --preallocate flag is passed
         target.preallocate = true;
     }
+    if let Some(mode) = cli.prealloc_mode {
+        target.preallocate = true;
+        target.prealloc_mode = crate::config::cli_convert::convert_prealloc_mode(mode);
+    }
     if cli.truncate_to_size {
         target.truncate_to_size = true;
     }
@@ -268,6 +399,11 @@ fn apply_cli_target_overrides(target: &mut TargetConfig, cli: &Cli) -> Result<()
         };
     }
 
+    // Override busy file policy if not default
+    if !matches!(cli.busy_file, cli::BusyFileMode::Proceed) {
+        target.busy_file = crate::config::cli_convert::convert_busy_file(cli.busy_file);
+    }
+
     // Override file distribution if not default
     if !matches!(cli.file_distribution, cli::FileDistributionType::Shared) {
         target.distribution = match cli.file_distribution {
@@ -280,40 +416,6 @@ fn apply_cli_target_overrides(target: &mut TargetConfig, cli: &Cli) -> Result<()
     Ok(())
 }
 
-/// Parse duration string (e.g., "60s", "5m", "1h") to seconds
-fn parse_duration(s: &str) -> Result<u64> {
-    let s = s.trim();
-    if s.is_empty() {
-        anyhow::bail!("Empty duration string");
-    }
-
-    let (num_str, unit) = if s.ends_with("ms") {
-        (&s[..s.len() - 2], "ms")
-    } else {
-        let unit_start = s.len() - 1;
-        (&s[..unit_start], &s[unit_start..])
-    };
-
-    let num: u64 = num_str.parse()
-        .with_context(|| format!("Invalid number in duration: {}", num_str))?;
-
-    let seconds = match unit {
-        "s" => num,
-        "m" => num * 60,
-        "h" => num * 3600,
-        "ms" => {
-            if num < 1000 {
-                1 // Round up to 1 second
-            } else {
-                num / 1000
-            }
-        }
-        _ => anyhow::bail!("Invalid duration unit: {}. Use s, m, h, or ms", unit),
-    };
-
-    Ok(seconds)
-}
-
 /// Parse duration string to microseconds (e.g., "100us", "1ms", "10ms")
 fn parse_duration_us(s: &str) -> Result<u64> {
     let s = s.trim();
@@ -392,39 +494,15 @@ fn parse_fadvise_flags(s: Option<&str>) -> Result<FadviseFlags> {
 
 /// Parse madvise flags from comma-separated string
 fn parse_madvise_flags(s: Option<&str>) -> Result<MadviseFlags> {
-    let mut flags = MadviseFlags::default();
-
-    if let Some(s) = s {
-        for flag in s.split(',') {
-            match flag.trim().to_lowercase().as_str() {
-                "seq" | "sequential" => flags.sequential = true,
-                "rand" | "random" => flags.random = true,
-                "willneed" => flags.willneed = true,
-                "dontneed" => flags.dontneed = true,
-                "hugepage" => flags.hugepage = true,
-                "nohugepage" => flags.nohugepage = true,
-                "" => {}
-                other => anyhow::bail!("Invalid madvise flag: {}", other),
-            }
-        }
-    }
-
-    Ok(flags)
+    Ok(s.map(crate::config::cli_convert::parse_madvise_flags)
+        .transpose()?
+        .unwrap_or_default())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_parse_duration() {
-        assert_eq!(parse_duration("60s").unwrap(), 60);
-        assert_eq!(parse_duration("5m").unwrap(), 300);
-        assert_eq!(parse_duration("1h").unwrap(), 3600);
-        assert_eq!(parse_duration("1000ms").unwrap(), 1);
-        assert_eq!(parse_duration("500ms").unwrap(), 1); // Rounds up
-    }
-
     #[test]
     fn test_parse_duration_us() {
         assert_eq!(parse_duration_us("100us").unwrap(), 100);
@@ -545,6 +623,45 @@ path = "/tmp/testfile"
         assert_eq!(config.workload.read_distribution[1].weight, 30);
     }
 
+    #[test]
+    fn test_parse_toml_composite_distribution() {
+        let toml = r#"
+[workload]
+read_percent = 100
+write_percent = 0
+queue_depth = 1
+random = true
+
+[workload.completion_mode.Duration]
+seconds = 10
+
+[[workload.distribution.Composite.components]]
+weight = 80
+end = 0.1
+distribution = { Zipf = { theta = 1.2 } }
+
+[[workload.distribution.Composite.components]]
+weight = 20
+distribution = "Uniform"
+
+[[targets]]
+path = "/tmp/testfile"
+"#;
+
+        let config = parse_toml_string(toml).unwrap();
+        let DistributionType::Composite { ref components } = config.workload.distribution else {
+            panic!("Expected composite distribution");
+        };
+        assert_eq!(components.len(), 2);
+        assert_eq!((components[0].start, components[0].end), (0.0, 0.1));
+        assert_eq!((components[1].start, components[1].end), (0.0, 1.0));
+        assert!(config.workload.distribution.validate().is_ok());
+        assert_eq!(
+            config.workload.distribution.to_string(),
+            "composite(80% zipf(theta=1.2) over 0-10%, 20% uniform)"
+        );
+    }
+
     #[test]
     fn test_parse_toml_multi_phase() {
         let toml = r#"
@@ -10,9 +10,33 @@ pub fn validate_config(config: &Config) -> Result<()> {
     validate_workers(&config.workers)?;
     validate_output(&config.output)?;
     validate_runtime(&config.runtime)?;
+    validate_grow(config)?;
+    validate_target_region(config)?;
+    validate_write_journal(config)?;
+    validate_commit_every(config)?;
+    validate_read_only(config)?;
+    validate_buffered_mix(config)?;
+    validate_heatmap_output(config)?;
+    validate_stonewall(&config.workers, &config.workload.completion_mode)?;
+    validate_thread_scaling(config)?;
+    validate_latency_target(config)?;
+    validate_visibility(config)?;
+    validate_worker_groups(config)?;
+    validate_custom_pattern(config)?;
+    validate_latency_floor(config)?;
+
+    // Heartbeats double as keep-alives, so several must fit in the timeout
+    if config.output.live_interval_duration() * 3 > Duration::from_secs(config.runtime.keepalive_timeout) {
+        anyhow::bail!(
+            "--live-interval ({:.3}s) must be at most a third of --keepalive-timeout ({}s)",
+            config.output.live_interval_duration().as_secs_f64(),
+            config.runtime.keepalive_timeout
+        );
+    }
     
-    // Validate write conflicts (unless explicitly allowed)
-    if !config.runtime.allow_write_conflicts {
+    // Validate write conflicts (unless explicitly allowed). Two-phase
+    // verification replaces the workload's writes with a partitioned write phase.
+    if !config.runtime.allow_write_conflicts && config.runtime.verify_groups.is_none() {
         validate_write_conflicts(config)?;
     }
 
@@ -30,11 +54,19 @@ pub fn validate_workload(workload: &WorkloadConfig) -> Result<()> {
         );
     }
 
+    // Validate block size
+    validate_io_size(workload.block_size, "block_size", workload.direct)?;
+
     // Validate queue depth
     if workload.queue_depth == 0 || workload.queue_depth > 1024 {
         anyhow::bail!("queue_depth must be between 1 and 1024, got {}", workload.queue_depth);
     }
 
+    // Random access has no start
+    if workload.offset_start.is_some() && workload.random {
+        anyhow::bail!("--offset-start only applies to sequential access (remove --random)");
+    }
+
     // Validate read distribution weights
     if !workload.read_distribution.is_empty() {
         let total_weight: u32 = workload.read_distribution.iter().map(|p| p.weight as u32).sum();
@@ -46,7 +78,7 @@ pub fn validate_workload(workload: &WorkloadConfig) -> Result<()> {
         }
 
         for (i, pattern) in workload.read_distribution.iter().enumerate() {
-            validate_io_pattern(pattern, i, "read")?;
+            validate_io_pattern(pattern, i, "read", workload.direct)?;
         }
     }
 
@@ -61,23 +93,77 @@ pub fn validate_workload(workload: &WorkloadConfig) -> Result<()> {
         }
 
         for (i, pattern) in workload.write_distribution.iter().enumerate() {
-            validate_io_pattern(pattern, i, "write")?;
+            validate_io_pattern(pattern, i, "write", workload.direct)?;
         }
     }
 
     // Validate distribution parameters
     validate_distribution(&workload.distribution)?;
+    for (i, group) in workload.worker_groups.iter().enumerate() {
+        validate_distribution(&group.distribution)
+            .with_context(|| format!("worker_groups[{}]", i))?;
+    }
 
     // Validate think time
     if let Some(ref think_time) = workload.think_time {
         validate_think_time(think_time)?;
     }
 
+    // Validate queue depth ramp
+    if let Some(ref qd_ramp) = workload.qd_ramp {
+        validate_qd_ramp(qd_ramp, workload)?;
+    }
+
+    // mmap options only apply to the mmap engine
+    if workload.mmap != MmapOptions::default() && workload.engine != EngineType::Mmap {
+        anyhow::bail!("mmap options ({}) require --engine mmap", workload.mmap);
+    }
+
+    // O_DIRECT/buffered mix: a share of the O_DIRECT operations goes through the page cache
+    if workload.buffered_percent > 100 {
+        anyhow::bail!("--buffered-percent must be between 0 and 100, got {}", workload.buffered_percent);
+    }
+    if workload.buffered_percent > 0 {
+        if !workload.direct {
+            anyhow::bail!("--buffered-percent requires --direct (without it every operation is buffered)");
+        }
+        if workload.engine == EngineType::Mmap {
+            anyhow::bail!("--buffered-percent is not supported with --engine mmap");
+        }
+    }
+
+    // Remapping moves whole chunks, so no IO may span two
+    if let Some(remap) = workload.remap_offsets {
+        let chunk = remap.chunk_size(workload.io_sizes());
+        if chunk == 0 {
+            anyhow::bail!("--remap-chunk must be at least 1 byte");
+        }
+        if let Some(size) = workload.io_sizes().find(|size| !chunk.is_multiple_of(*size)) {
+            anyhow::bail!("--remap-chunk ({}) must be a multiple of every IO size, got {}", chunk, size);
+        }
+        if workload.grow_to.is_some() {
+            anyhow::bail!("--remap-offsets cannot be combined with --grow-to (the file size changes while it grows)");
+        }
+    }
+
+    // Append-grow extends the file with writes
+    if let Some(grow_to) = workload.grow_to {
+        if workload.write_percent != 100 {
+            anyhow::bail!("--grow-to requires --write-percent 100, got {}", workload.write_percent);
+        }
+        if workload.engine == EngineType::Mmap {
+            anyhow::bail!("--grow-to is not supported with --engine mmap (mappings cannot extend the file)");
+        }
+        if grow_to < workload.block_size {
+            anyhow::bail!("--grow-to ({}) must be at least the block size ({})", grow_to, workload.block_size);
+        }
+    }
+
     Ok(())
 }
 
 /// Validate IO pattern
-fn validate_io_pattern(pattern: &IOPattern, index: usize, op_type: &str) -> Result<()> {
+fn validate_io_pattern(pattern: &IOPattern, index: usize, op_type: &str, direct: bool) -> Result<()> {
     if pattern.weight == 0 {
         anyhow::bail!(
             "{} distribution pattern {} has zero weight",
@@ -86,29 +172,29 @@ fn validate_io_pattern(pattern: &IOPattern, index: usize, op_type: &str) -> Resu
         );
     }
 
-    if pattern.block_size < 512 {
-        anyhow::bail!(
-            "{} distribution pattern {} has block_size {} < 512 bytes",
-            op_type,
-            index,
-            pattern.block_size
-        );
+    validate_io_size(pattern.block_size, &format!("{} distribution pattern {} block_size", op_type, index), direct)
+}
+
+/// Validate an IO size
+///
+/// Buffered IO takes any size from 1 byte to 64MB, including sizes below a
+/// sector or not a power of two (journal records, metadata). O_DIRECT needs
+/// whole sectors; the device's actual alignment is checked when the target
+/// is opened.
+fn validate_io_size(size: u64, name: &str, direct: bool) -> Result<()> {
+    if size == 0 {
+        anyhow::bail!("{} must be at least 1 byte", name);
     }
 
-    if pattern.block_size > 64 * 1024 * 1024 {
-        anyhow::bail!(
-            "{} distribution pattern {} has block_size {} > 64MB",
-            op_type,
-            index,
-            pattern.block_size
-        );
+    if size > MAX_IO_SIZE {
+        anyhow::bail!("{} {} is larger than 64MB", name, size);
     }
 
-    // Check alignment (should be power of 2)
-    if !pattern.block_size.is_power_of_two() {
-        eprintln!(
-            "Warning: {} distribution pattern {} block_size {} is not a power of 2",
-            op_type, index, pattern.block_size
+    if direct && !size.is_multiple_of(MIN_DIRECT_IO_SIZE) {
+        anyhow::bail!(
+            "{} {} can't be used with --direct: O_DIRECT needs a multiple of the sector size ({} bytes or more). \
+             Use a multiple of {} bytes, or run without --direct for sub-sector IO.",
+            name, size, MIN_DIRECT_IO_SIZE, MIN_DIRECT_IO_SIZE
         );
     }
 
@@ -128,13 +214,22 @@ fn validate_distribution(dist: &DistributionType) -> Result<()> {
                 anyhow::bail!("Pareto h must be between 0.0 and 10.0, got {}", h);
             }
         }
-        DistributionType::Gaussian { stddev, center } => {
+        DistributionType::Gaussian { stddev, center, drift } => {
             if *stddev <= 0.0 {
                 anyhow::bail!("Gaussian stddev must be positive, got {}", stddev);
             }
             if *center < 0.0 || *center > 1.0 {
                 anyhow::bail!("Gaussian center must be between 0.0 and 1.0, got {}", center);
             }
+            if let Some(drift) = drift.filter(|d| !d.rate().is_finite()) {
+                anyhow::bail!("Gaussian drift rate must be a finite number, got {}", drift.rate());
+            }
+        }
+        DistributionType::Composite { components } => {
+            dist.validate().map_err(anyhow::Error::msg)?;
+            for component in components {
+                validate_distribution(&component.distribution)?;
+            }
         }
         DistributionType::Uniform => {}
     }
@@ -167,6 +262,31 @@ fn validate_think_time(think_time: &ThinkTimeConfig) -> Result<()> {
     Ok(())
 }
 
+/// Validate queue depth ramp configuration
+fn validate_qd_ramp(qd_ramp: &QdRampConfig, workload: &WorkloadConfig) -> Result<()> {
+    qd_ramp.validate(workload.queue_depth).map_err(anyhow::Error::msg)?;
+
+    if workload.engine == EngineType::Sync || workload.engine == EngineType::Mmap {
+        eprintln!(
+            "Warning: qd_ramp with the {} engine has no effect (only async engines keep multiple IOs in flight)",
+            workload.engine
+        );
+    }
+
+    if let Some(duration) = workload.completion_mode.test_duration() {
+        // Ramp needs log2(ceiling) + 1 steps to reach the ceiling
+        let max_steps = (workload.queue_depth as f64).log2().ceil() as u64 + 1;
+        if duration < Duration::from_secs(qd_ramp.step_seconds * max_steps) {
+            eprintln!(
+                "Warning: duration ({:.3}s) is shorter than a full queue depth ramp ({} steps x {}s)",
+                duration.as_secs_f64(), max_steps, qd_ramp.step_seconds
+            );
+        }
+    }
+
+    Ok(())
+}
+
 /// Validate targets configuration
 pub fn validate_targets(targets: &[TargetConfig]) -> Result<()> {
     if targets.is_empty() {
@@ -224,6 +344,14 @@ fn validate_target(target: &TargetConfig, index: usize) -> Result<()> {
         );
     }
 
+    // Only SHARED file lists let two workers pick the same file
+    if target.busy_file != BusyFilePolicy::Proceed && target.distribution != FileDistribution::Shared {
+        anyhow::bail!(
+            "Target {} --busy-file {} requires --file-distribution shared (got {})",
+            index, target.busy_file, target.distribution
+        );
+    }
+
     Ok(())
 }
 
@@ -250,10 +378,34 @@ pub fn validate_output(output: &OutputConfig) -> Result<()> {
         anyhow::bail!("prometheus_port must be greater than 0");
     }
 
+    if let Some(ref uuid) = output.run_uuid {
+        crate::util::trace_id::parse_run_uuid(uuid)?;
+    }
+
     if output.live_interval == Some(0) {
         anyhow::bail!("live_interval must be greater than 0");
     }
 
+    if output.live_interval_duration() < Duration::from_millis(crate::config::MIN_LIVE_INTERVAL_MS) {
+        anyhow::bail!("--live-interval must be at least {}ms", crate::config::MIN_LIVE_INTERVAL_MS);
+    }
+
+    if !(output.live_overhead_percent > 0.0 && output.live_overhead_percent <= 100.0) {
+        anyhow::bail!("--live-overhead must be > 0 and <= 100 (got {})", output.live_overhead_percent);
+    }
+
+    if output.quiet && output.verbosity > 0 {
+        anyhow::bail!("--quiet cannot be combined with -v/--verbose");
+    }
+
+    if output.summary_format != crate::config::SummaryFormat::Text && output.verbosity > 0 {
+        anyhow::bail!("--summary-format {} cannot be combined with -v/--verbose", output.summary_format);
+    }
+
+    if output.plot.is_some() && !cfg!(feature = "plot") {
+        anyhow::bail!("--plot is not available (built without the plot feature)");
+    }
+
     Ok(())
 }
 
@@ -269,6 +421,372 @@ pub fn validate_runtime(runtime: &RuntimeConfig) -> Result<()> {
         eprintln!("Warning: verify enabled but no verify_pattern specified, using default");
     }
 
+    if let Some(limit) = runtime.stats_mem_limit {
+        if limit < 1024 * 1024 {
+            anyhow::bail!("stats_mem_limit must be at least 1M");
+        }
+    }
+
+    if runtime.prep_rate_limit == Some(0) {
+        anyhow::bail!("prep_rate_limit must be greater than 0");
+    }
+
+    if runtime.latency_sample == Some(0) {
+        anyhow::bail!("latency_sample must be at least 1");
+    }
+
+    if runtime.file_latency_top == Some(0) {
+        anyhow::bail!("file_latency_top must be at least 1");
+    }
+
+    Ok(())
+}
+
+/// Validate stonewall (needs a completion condition workers reach on their own)
+fn validate_stonewall(workers: &WorkerConfig, completion_mode: &CompletionMode) -> Result<()> {
+    if workers.stonewall && matches!(completion_mode, CompletionMode::Duration { .. }) {
+        anyhow::bail!(
+            "--stonewall needs --total-bytes or --run-until-complete; \
+             with --duration all workers already stop together"
+        );
+    }
+    Ok(())
+}
+
+/// Validate thread scaling against modes that already vary the run
+fn validate_thread_scaling(config: &Config) -> Result<()> {
+    if config.workers.thread_scaling.is_none() {
+        return Ok(());
+    }
+    if config.workload.qd_ramp.is_some() {
+        anyhow::bail!("--threads auto cannot be combined with --qd-ramp (ramp one dimension at a time)");
+    }
+    if config.runtime.verify_groups.is_some() {
+        anyhow::bail!("--threads auto cannot be combined with two-phase verification");
+    }
+    if !config.workload.worker_groups.is_empty() {
+        anyhow::bail!("--threads auto cannot be combined with worker groups (their sizes fix the thread count)");
+    }
+    Ok(())
+}
+
+/// Validate latency goal-seeking against the run it steers
+fn validate_latency_target(config: &Config) -> Result<()> {
+    if config.workers.latency_target.is_none() {
+        return Ok(());
+    }
+    if config.workload.completion_mode.test_duration().is_none() {
+        anyhow::bail!("--target-p99 requires a duration-based test (--duration)");
+    }
+    if config.workers.thread_scaling.is_some() {
+        anyhow::bail!("--target-p99 cannot be combined with --threads auto");
+    }
+    if config.workload.qd_ramp.is_some() {
+        anyhow::bail!("--target-p99 cannot be combined with --qd-ramp (both steer the load by latency)");
+    }
+    Ok(())
+}
+
+/// Validate the visibility test, which replaces the IO workload
+fn validate_visibility(config: &Config) -> Result<()> {
+    if config.workers.visibility.is_none() {
+        return Ok(());
+    }
+    if config.workers.thread_scaling.is_some() || config.workers.latency_target.is_some() {
+        anyhow::bail!("--visibility-test cannot be combined with --threads auto or --target-p99 (it runs no IO)");
+    }
+    if config.runtime.verify_groups.is_some() {
+        anyhow::bail!("--visibility-test cannot be combined with two-phase verification");
+    }
+    match config.targets.first() {
+        Some(target) if target.target_type == crate::config::TargetType::BlockDevice => {
+            anyhow::bail!("--visibility-test needs a directory on the shared filesystem, not a block device")
+        }
+        Some(_) => Ok(()),
+        None => anyhow::bail!("--visibility-test needs a target directory on the shared filesystem"),
+    }
+}
+
+/// Validate that worker groups add up to the worker count
+fn validate_worker_groups(config: &Config) -> Result<()> {
+    let groups = &config.workload.worker_groups;
+    if groups.is_empty() {
+        return Ok(());
+    }
+    if let Some(i) = groups.iter().position(|group| group.workers == 0) {
+        anyhow::bail!("worker_groups[{}] must have at least one worker", i);
+    }
+    let total: usize = groups.iter().map(|group| group.workers).sum();
+    if total != config.workers.threads {
+        anyhow::bail!(
+            "worker_groups hold {} workers, but threads is {} (they must match)",
+            total, config.workers.threads
+        );
+    }
+    Ok(())
+}
+
+/// Validate append-grow mode against targets and workers
+///
+/// Each worker appends at its own view of EOF, so there must be exactly one
+/// writer per file: a single worker and a single regular file.
+fn validate_grow(config: &Config) -> Result<()> {
+    use crate::config::workload::FileDistribution;
+    
+    if config.workload.grow_to.is_none() {
+        return Ok(());
+    }
+    
+    if config.workers.threads != 1 {
+        anyhow::bail!(
+            "--grow-to needs one writer per file, got {} threads. Use --threads 1.",
+            config.workers.threads
+        );
+    }
+    
+    for target in &config.targets {
+        if target.target_type != TargetType::File
+            || target.layout_config.is_some()
+            || target.layout_manifest.is_some()
+        {
+            anyhow::bail!("--grow-to only supports a single regular file target: {}", target.path.display());
+        }
+        if target.file_size.is_some() {
+            anyhow::bail!("--grow-to starts from the file's current size; remove --file-size");
+        }
+        if target.distribution == FileDistribution::Partitioned {
+            anyhow::bail!("--grow-to does not support --file-distribution partitioned");
+        }
+    }
+    
+    Ok(())
+}
+
+/// Validate the target region (--target-offset/--target-length, --device-region):
+/// a single existing file or a block device, block-aligned and at least one block
+///
+/// A region of a file leaves its size alone, so it can't be combined with a
+/// file size, which would resize (and possibly reallocate) the file.
+fn validate_target_region(config: &Config) -> Result<()> {
+    for target in &config.targets {
+        let Some((start, end)) = target.region else {
+            continue;
+        };
+        match target.target_type {
+            TargetType::BlockDevice => {}
+            TargetType::File if target.num_files.is_none() && target.layout_config.is_none() && target.layout_manifest.is_none() => {
+                if target.file_size.is_some() {
+                    anyhow::bail!("A target region applies to an existing file; remove --file-size: {}", target.path.display());
+                }
+            }
+            _ => anyhow::bail!("A target region only applies to a single file or a block device: {}", target.path.display()),
+        }
+        if start >= end {
+            anyhow::bail!("Target region end ({}) must be after its start ({})", end, start);
+        }
+        if start % config.workload.block_size != 0 {
+            anyhow::bail!("Target region start ({}) must be a multiple of the block size ({})",
+                start, config.workload.block_size);
+        }
+        if end - start < config.workload.block_size {
+            anyhow::bail!("Target region ({} bytes) is smaller than the block size ({})",
+                end - start, config.workload.block_size);
+        }
+    }
+    Ok(())
+}
+
+/// Validate --write-journal: writes of one block size to a single target
+///
+/// Journal entries are keyed by offset, so every write must cover exactly
+/// one block of a single file or device.
+fn validate_write_journal(config: &Config) -> Result<()> {
+    if config.runtime.write_journal.is_none() {
+        return Ok(());
+    }
+    if config.workload.write_percent == 0 {
+        anyhow::bail!("--write-journal needs writes (--write-percent)");
+    }
+    let workload = &config.workload;
+    if workload.read_distribution.iter().chain(&workload.write_distribution)
+        .any(|pattern| pattern.block_size != workload.block_size)
+    {
+        anyhow::bail!("--write-journal needs a single block size (remove the block size mix)");
+    }
+    if workload.grow_to.is_some() {
+        anyhow::bail!("--write-journal does not support --grow-to");
+    }
+    match config.targets.as_slice() {
+        [target] if target.target_type != TargetType::Directory
+            && target.layout_config.is_none()
+            && target.layout_manifest.is_none()
+            && target.num_files.unwrap_or(1) <= 1
+            && target.distribution != crate::config::workload::FileDistribution::PerWorker => Ok(()),
+        _ => anyhow::bail!("--write-journal needs a single block device or file target"),
+    }
+}
+
+/// Validate --commit-every: stamped writes of one block size to a single target
+///
+/// Each worker keeps its commit slots in the first blocks of its span, so
+/// several workers need partitioned distribution to get a span each.
+fn validate_commit_every(config: &Config) -> Result<()> {
+    if config.runtime.commit_every.is_none() {
+        return Ok(());
+    }
+    let workload = &config.workload;
+    if workload.write_percent == 0 {
+        anyhow::bail!("--commit-every needs writes (--write-percent)");
+    }
+    if workload.read_distribution.iter().chain(&workload.write_distribution)
+        .any(|pattern| pattern.block_size != workload.block_size)
+    {
+        anyhow::bail!("--commit-every needs a single block size (remove the block size mix)");
+    }
+    if workload.block_size < crate::worker::write_order::STAMP_LEN as u64 {
+        anyhow::bail!("--commit-every needs blocks of at least {} bytes", crate::worker::write_order::STAMP_LEN);
+    }
+    if workload.grow_to.is_some() || config.runtime.verify {
+        anyhow::bail!("--commit-every does not support --grow-to or --verify");
+    }
+    let target = match config.targets.as_slice() {
+        [target] if target.target_type != TargetType::Directory
+            && target.layout_config.is_none()
+            && target.layout_manifest.is_none()
+            && target.num_files.unwrap_or(1) <= 1
+            && target.distribution != crate::config::workload::FileDistribution::PerWorker => target,
+        _ => anyhow::bail!("--commit-every needs a single block device or file target"),
+    };
+    if config.workers.threads > 1 && target.distribution != crate::config::workload::FileDistribution::Partitioned {
+        anyhow::bail!("--commit-every with several workers needs --file-distribution partitioned");
+    }
+    Ok(())
+}
+
+/// Validate --readonly: nothing may write to, create or resize a target
+///
+/// Role workloads replace `config.workload` on their nodes, so the
+/// coordinator runs this once per role as well.
+pub fn validate_read_only(config: &Config) -> Result<()> {
+    if !config.runtime.read_only {
+        return Ok(());
+    }
+    if config.workload.write_percent > 0 {
+        anyhow::bail!("--readonly can't run writes (--write-percent {})", config.workload.write_percent);
+    }
+    if config.workload.grow_to.is_some() {
+        anyhow::bail!("--readonly does not support --grow-to");
+    }
+    if config.runtime.verify_groups.is_some() {
+        anyhow::bail!("--readonly does not support --verify-writers (its first phase writes)");
+    }
+    for target in &config.targets {
+        if target.preallocate || target.refill || target.truncate_to_size {
+            anyhow::bail!("--readonly does not support --preallocate, --refill or --truncate-to-size: {}",
+                target.path.display());
+        }
+        if target.layout_config.is_some() && target.layout_manifest.is_none() {
+            anyhow::bail!("--readonly can't generate a directory layout; use --layout-manifest: {}",
+                target.path.display());
+        }
+    }
+    Ok(())
+}
+
+/// Validate --buffered-percent: the second fd is opened once per target, not per file of a layout
+fn validate_buffered_mix(config: &Config) -> Result<()> {
+    if config.workload.buffered_percent == 0 {
+        return Ok(());
+    }
+    for target in &config.targets {
+        if target.target_type == TargetType::Directory
+            || target.layout_config.is_some()
+            || target.layout_manifest.is_some()
+        {
+            anyhow::bail!("--buffered-percent needs file or block device targets, not a directory layout: {}",
+                target.path.display());
+        }
+    }
+    Ok(())
+}
+
+/// Validate the heatmap export (.csv or .json) and the distribution report, which need the heatmap
+fn validate_heatmap_output(config: &Config) -> Result<()> {
+    if config.output.distribution_report {
+        if !config.workload.heatmap || !config.workload.random {
+            anyhow::bail!("--distribution-report needs --heatmap and --random");
+        }
+        if !config.workload.worker_groups.is_empty() {
+            anyhow::bail!("--distribution-report doesn't support worker groups");
+        }
+        let workload = &config.workload;
+        if workload.read_distribution.iter().chain(&workload.write_distribution)
+            .any(|pattern| pattern.block_size != workload.block_size)
+        {
+            anyhow::bail!("--distribution-report needs a single block size (remove the block size mix)");
+        }
+        match workload.distribution {
+            crate::config::workload::DistributionType::Gaussian { drift: Some(_), .. } => {
+                anyhow::bail!("--distribution-report can't compare against a drifting Gaussian center (remove --gaussian-drift)");
+            }
+            crate::config::workload::DistributionType::Composite { .. } => {
+                anyhow::bail!("--distribution-report doesn't support composite distributions");
+            }
+            _ => {}
+        }
+    }
+    let output = &config.output;
+    let Some(ref path) = output.heatmap_output else {
+        if output.heatmap_series && output.plot.is_none() {
+            anyhow::bail!("--heatmap-series needs --heatmap-output or --plot");
+        }
+        if output.heatmap_series && !config.workload.heatmap {
+            anyhow::bail!("--heatmap-series needs --heatmap");
+        }
+        return Ok(());
+    };
+    if !config.workload.heatmap {
+        anyhow::bail!("--heatmap-output needs --heatmap");
+    }
+    if crate::output::heatmap::HeatmapFormat::from_path(path).is_none() {
+        anyhow::bail!("--heatmap-output must end in .csv or .json: {}", path.display());
+    }
+    Ok(())
+}
+
+/// Validate that the custom pattern has a payload, and that a payload is used
+fn validate_custom_pattern(config: &Config) -> Result<()> {
+    let uses_custom = config.workload.write_pattern == VerifyPattern::Custom
+        || config.runtime.verify_pattern == Some(VerifyPattern::Custom)
+        || config.targets.iter().any(|t| t.refill_pattern == VerifyPattern::Custom);
+    
+    match config.workload.custom_pattern {
+        None if uses_custom => {
+            anyhow::bail!("The custom pattern needs a payload: add --pattern-file PATH or --pattern-runs RUNS");
+        }
+        Some(ref custom) if !uses_custom => {
+            eprintln!(
+                "Warning: Pattern payload {} is not used; select it with --write-pattern, --verify-pattern or --refill-pattern custom",
+                custom
+            );
+        }
+        _ => {}
+    }
+    
+    Ok(())
+}
+
+/// Validate --latency-floor: the NOPs go through the io_uring ring the workers run
+fn validate_latency_floor(config: &Config) -> Result<()> {
+    if !config.runtime.latency_floor {
+        return Ok(());
+    }
+    if config.workload.engine != EngineType::IoUring {
+        anyhow::bail!("--latency-floor times io_uring NOPs: it needs --engine io_uring, got {}", config.workload.engine);
+    }
+    if config.workload.effective_engine() != EngineType::IoUring {
+        anyhow::bail!("--latency-floor needs the io_uring ring, which the sync engine replaces at queue depth 1: add --no-engine-fallback or raise --queue-depth");
+    }
     Ok(())
 }
 
@@ -288,7 +806,7 @@ pub fn validate_write_conflicts(config: &Config) -> Result<()> {
     for target in &config.targets {
         let is_shared = target.distribution == FileDistribution::Shared;
         let has_writes = config.workload.write_percent > 0;
-        let is_random = config.workload.random;
+        let is_random = config.workload.any_random();
         let no_locking = target.lock_mode == crate::config::workload::FileLockMode::None;
         
         // Detect risky scenario: shared + writes + random + no locks
@@ -398,6 +916,18 @@ mod tests {
             heatmap: false,
             heatmap_buckets: 100,
             write_pattern: crate::config::workload::VerifyPattern::Random,
+            qd_ramp: None,
+            mmap: MmapOptions::default(),
+            buffer_hugepages: BufferHugePages::None,
+            grow_to: None,
+            open_flags: OpenFlagOptions::default(),
+            custom_pattern: None,
+            rw_split_by: RwSplitBy::Ops,
+            offset_start: None,
+            worker_groups: Vec::new(),
+            buffered_percent: 0,
+            remap_offsets: None,
+            no_engine_fallback: false,
         };
 
         assert!(validate_workload(&workload).is_ok());
@@ -425,6 +955,18 @@ mod tests {
             heatmap: false,
             heatmap_buckets: 100,
             write_pattern: crate::config::workload::VerifyPattern::Random,
+            qd_ramp: None,
+            mmap: MmapOptions::default(),
+            buffer_hugepages: BufferHugePages::None,
+            grow_to: None,
+            open_flags: OpenFlagOptions::default(),
+            custom_pattern: None,
+            rw_split_by: RwSplitBy::Ops,
+            offset_start: None,
+            worker_groups: Vec::new(),
+            buffered_percent: 0,
+            remap_offsets: None,
+            no_engine_fallback: false,
         };
 
         assert!(validate_workload(&workload).is_err());
@@ -436,6 +978,221 @@ mod tests {
         assert!(validate_workload(&workload).is_err());
     }
 
+    #[test]
+    fn test_validate_odd_io_sizes() {
+        let mut workload = WorkloadConfig {
+            read_percent: 100,
+            write_percent: 0,
+            read_distribution: vec![],
+            write_distribution: vec![],
+            block_size: 1,
+            queue_depth: 1,
+            completion_mode: CompletionMode::RunUntilComplete,
+            random: false,
+            distribution: DistributionType::Uniform,
+            think_time: None,
+            engine: EngineType::Sync,
+            direct: false,
+            sync: false,
+            heatmap: false,
+            heatmap_buckets: 100,
+            write_pattern: crate::config::workload::VerifyPattern::Random,
+            qd_ramp: None,
+            mmap: MmapOptions::default(),
+            buffer_hugepages: BufferHugePages::None,
+            grow_to: None,
+            open_flags: OpenFlagOptions::default(),
+            custom_pattern: None,
+            rw_split_by: RwSplitBy::Ops,
+            offset_start: None,
+            worker_groups: Vec::new(),
+            buffered_percent: 0,
+            remap_offsets: None,
+            no_engine_fallback: false,
+        };
+
+        // Buffered IO takes sub-sector and non-power-of-two sizes
+        for size in [1, 100, 3 * 1024] {
+            workload.block_size = size;
+            assert!(validate_workload(&workload).is_ok(), "{}", size);
+        }
+        workload.read_distribution = vec![IOPattern { weight: 100, access: AccessPattern::Random, block_size: 100 }];
+        assert!(validate_workload(&workload).is_ok());
+
+        // O_DIRECT needs whole sectors
+        workload.direct = true;
+        let err = validate_workload(&workload).unwrap_err().to_string();
+        assert!(err.contains("--direct"), "{}", err);
+        workload.read_distribution.clear();
+        assert!(validate_workload(&workload).is_ok());
+        workload.block_size = 100;
+        assert!(validate_workload(&workload).is_err());
+        workload.block_size = 1536;
+        assert!(validate_workload(&workload).is_ok());
+
+        workload.block_size = 0;
+        assert!(validate_workload(&workload).is_err());
+    }
+
+    #[test]
+    fn test_validate_buffered_percent() {
+        let mut workload = WorkloadConfig {
+            read_percent: 100,
+            write_percent: 0,
+            read_distribution: vec![],
+            write_distribution: vec![],
+            block_size: 1,
+            queue_depth: 1,
+            completion_mode: CompletionMode::RunUntilComplete,
+            random: false,
+            distribution: DistributionType::Uniform,
+            think_time: None,
+            engine: EngineType::Sync,
+            direct: false,
+            sync: false,
+            heatmap: false,
+            heatmap_buckets: 100,
+            write_pattern: crate::config::workload::VerifyPattern::Random,
+            qd_ramp: None,
+            mmap: MmapOptions::default(),
+            buffer_hugepages: BufferHugePages::None,
+            grow_to: None,
+            open_flags: OpenFlagOptions::default(),
+            custom_pattern: None,
+            rw_split_by: RwSplitBy::Ops,
+            offset_start: None,
+            worker_groups: Vec::new(),
+            buffered_percent: 0,
+            remap_offsets: None,
+            no_engine_fallback: false,
+        };
+
+        workload.block_size = 4096;
+        workload.direct = true;
+        workload.buffered_percent = 25;
+        assert!(validate_workload(&workload).is_ok());
+
+        // Only a mix with O_DIRECT
+        workload.direct = false;
+        assert!(validate_workload(&workload).is_err());
+        workload.direct = true;
+
+        workload.buffered_percent = 101;
+        assert!(validate_workload(&workload).is_err());
+        workload.buffered_percent = 25;
+
+        workload.engine = EngineType::Mmap;
+        assert!(validate_workload(&workload).is_err());
+    }
+
+    #[test]
+    fn test_validate_remap_offsets() {
+        let mut workload = WorkloadConfig {
+            read_percent: 100,
+            write_percent: 0,
+            read_distribution: vec![],
+            write_distribution: vec![],
+            block_size: 4096,
+            queue_depth: 1,
+            completion_mode: CompletionMode::RunUntilComplete,
+            random: false,
+            distribution: DistributionType::Uniform,
+            think_time: None,
+            engine: EngineType::Sync,
+            direct: false,
+            sync: false,
+            heatmap: false,
+            heatmap_buckets: 100,
+            write_pattern: crate::config::workload::VerifyPattern::Random,
+            qd_ramp: None,
+            mmap: MmapOptions::default(),
+            buffer_hugepages: BufferHugePages::None,
+            grow_to: None,
+            open_flags: OpenFlagOptions::default(),
+            custom_pattern: None,
+            rw_split_by: RwSplitBy::Ops,
+            offset_start: None,
+            worker_groups: Vec::new(),
+            buffered_percent: 0,
+            remap_offsets: None,
+            no_engine_fallback: false,
+        };
+
+        workload.remap_offsets = Some(OffsetRemap { seed: 7, chunk: None });
+        assert!(validate_workload(&workload).is_ok());
+
+        // Mixed IO sizes default to a chunk that fits all of them
+        workload.read_distribution = vec![
+            IOPattern { weight: 50, access: AccessPattern::Sequential, block_size: 8192 },
+            IOPattern { weight: 50, access: AccessPattern::Sequential, block_size: 12288 },
+        ];
+        assert_eq!(workload.remap_offsets.unwrap().chunk_size(workload.io_sizes()), 24576);
+        assert!(validate_workload(&workload).is_ok());
+
+        // An 8K IO could span two 12K chunks
+        workload.remap_offsets = Some(OffsetRemap { seed: 7, chunk: Some(12288) });
+        assert!(validate_workload(&workload).is_err());
+        workload.read_distribution = vec![];
+
+        workload.remap_offsets = Some(OffsetRemap { seed: 7, chunk: Some(0) });
+        assert!(validate_workload(&workload).is_err());
+    }
+
+    #[test]
+    fn test_validate_qd_ramp() {
+        let mut workload = WorkloadConfig {
+            read_percent: 100,
+            write_percent: 0,
+            read_distribution: vec![],
+            write_distribution: vec![],
+            block_size: 4096,
+            queue_depth: 64,
+            completion_mode: CompletionMode::RunUntilComplete,
+            random: true,
+            distribution: DistributionType::Uniform,
+            think_time: None,
+            engine: EngineType::IoUring,
+            direct: false,
+            sync: false,
+            heatmap: false,
+            heatmap_buckets: 100,
+            write_pattern: crate::config::workload::VerifyPattern::Random,
+            qd_ramp: Some(QdRampConfig::default()),
+            mmap: MmapOptions::default(),
+            buffer_hugepages: BufferHugePages::None,
+            grow_to: None,
+            open_flags: OpenFlagOptions::default(),
+            custom_pattern: None,
+            rw_split_by: RwSplitBy::Ops,
+            offset_start: None,
+            worker_groups: Vec::new(),
+            buffered_percent: 0,
+            remap_offsets: None,
+            no_engine_fallback: false,
+        };
+
+        assert!(validate_workload(&workload).is_ok());
+
+        // Ceiling of 1 leaves nothing to ramp
+        workload.queue_depth = 1;
+        assert!(validate_workload(&workload).is_err());
+
+        workload.queue_depth = 64;
+        workload.qd_ramp = Some(QdRampConfig {
+            step_seconds: 0,
+            ..QdRampConfig::default()
+        });
+        assert!(validate_workload(&workload).is_err());
+
+        // Same checks as WorkloadConfig::validate
+        workload.qd_ramp = Some(QdRampConfig {
+            plateau_percent: 150.0,
+            ..QdRampConfig::default()
+        });
+        assert!(validate_workload(&workload).is_err());
+        assert!(workload.validate().is_err());
+    }
+
     #[test]
     fn test_validate_distribution_weights() {
         let workload = WorkloadConfig {
@@ -466,6 +1223,18 @@ mod tests {
             heatmap: false,
             heatmap_buckets: 100,
             write_pattern: crate::config::workload::VerifyPattern::Random,
+            qd_ramp: None,
+            mmap: MmapOptions::default(),
+            buffer_hugepages: BufferHugePages::None,
+            grow_to: None,
+            open_flags: OpenFlagOptions::default(),
+            custom_pattern: None,
+            rw_split_by: RwSplitBy::Ops,
+            offset_start: None,
+            worker_groups: Vec::new(),
+            buffered_percent: 0,
+            remap_offsets: None,
+            no_engine_fallback: false,
         };
 
         // Weights sum to 90, should fail
@@ -489,14 +1258,46 @@ mod tests {
         let dist = DistributionType::Gaussian {
             stddev: 0.1,
             center: 0.5,
+            drift: Some(crate::config::workload::CenterDrift::Linear { rate: -0.01 }),
         };
         assert!(validate_distribution(&dist).is_ok());
 
+        let dist = DistributionType::Gaussian {
+            stddev: 0.1,
+            center: 0.5,
+            drift: Some(crate::config::workload::CenterDrift::RandomWalk { rate: f64::NAN }),
+        };
+        assert!(validate_distribution(&dist).is_err());
+
         let dist = DistributionType::Gaussian {
             stddev: 0.1,
             center: 1.5,
+            drift: None,
         };
         assert!(validate_distribution(&dist).is_err());
+
+        let component = |weight, start, end, distribution| crate::config::workload::DistributionComponent {
+            weight, start, end, distribution,
+        };
+        let dist = DistributionType::Composite { components: vec![
+            component(80, 0.0, 0.1, DistributionType::Zipf { theta: 1.2 }),
+            component(20, 0.0, 1.0, DistributionType::Uniform),
+        ] };
+        assert!(validate_distribution(&dist).is_ok());
+
+        // Weights must sum to 100, ranges must be ordered, and components are checked too
+        let dist = DistributionType::Composite { components: vec![
+            component(80, 0.0, 0.1, DistributionType::Uniform),
+        ] };
+        assert!(validate_distribution(&dist).is_err());
+        let dist = DistributionType::Composite { components: vec![
+            component(100, 0.5, 0.5, DistributionType::Uniform),
+        ] };
+        assert!(validate_distribution(&dist).is_err());
+        let dist = DistributionType::Composite { components: vec![
+            component(100, 0.0, 1.0, DistributionType::Zipf { theta: 5.0 }),
+        ] };
+        assert!(validate_distribution(&dist).is_err());
     }
 
     #[test]
@@ -517,15 +1318,34 @@ mod tests {
             fadvise_flags: FadviseFlags::default(),
             madvise_flags: MadviseFlags::default(),
             lock_mode: FileLockMode::None,
+            busy_file: BusyFilePolicy::Proceed,
             preallocate: false,
+            prealloc_mode: PreallocMode::default(),
             truncate_to_size: false,
             refill: false,
             refill_pattern: VerifyPattern::Random,
             no_refill: false,
+            region: None,
+            full_fill: false,
         }];
         assert!(validate_targets(&targets).is_ok());
     }
 
+    #[test]
+    fn test_validate_output_verbosity() {
+        let mut output = OutputConfig::default();
+        assert!(validate_output(&output).is_ok());
+
+        output.verbosity = 2;
+        assert!(validate_output(&output).is_ok());
+
+        output.quiet = true;
+        assert!(validate_output(&output).is_err());
+
+        output.verbosity = 0;
+        assert!(validate_output(&output).is_ok());
+    }
+
     #[test]
     fn test_write_conflict_detection_read_only() {
         // Read-only workload should pass without warning
@@ -537,7 +1357,7 @@ mod tests {
                 write_distribution: vec![],
                 block_size: 4096,
                 queue_depth: 32,
-                completion_mode: CompletionMode::Duration { seconds: 10 },
+                completion_mode: CompletionMode::Duration { seconds: 10, millis: 0 },
                 random: true,
                 distribution: DistributionType::Uniform,
                 think_time: None,
@@ -547,6 +1367,18 @@ mod tests {
                 heatmap: false,
                 heatmap_buckets: 100,
                 write_pattern: VerifyPattern::Random,
+                qd_ramp: None,
+                mmap: MmapOptions::default(),
+                buffer_hugepages: BufferHugePages::None,
+                grow_to: None,
+                open_flags: OpenFlagOptions::default(),
+                custom_pattern: None,
+                rw_split_by: RwSplitBy::Ops,
+                offset_start: None,
+                worker_groups: Vec::new(),
+                buffered_percent: 0,
+                remap_offsets: None,
+                no_engine_fallback: false,
             },
             targets: vec![TargetConfig {
                 path: PathBuf::from("/tmp/test"),
@@ -561,11 +1393,15 @@ mod tests {
                 fadvise_flags: FadviseFlags::default(),
                 madvise_flags: MadviseFlags::default(),
                 lock_mode: FileLockMode::None,
+                busy_file: BusyFilePolicy::Proceed,
                 preallocate: false,
+                prealloc_mode: PreallocMode::default(),
                 truncate_to_size: false,
                 refill: false,
                 refill_pattern: VerifyPattern::Random,
                 no_refill: false,
+                region: None,
+                full_fill: false,
             }],
             workers: WorkerConfig {
                 threads: 8,
@@ -574,6 +1410,13 @@ mod tests {
                 rate_limit_iops: None,
                 rate_limit_throughput: None,
                 offset_range: None,
+                unit_id: None,
+                core: None,
+                stonewall: false,
+                thread_per_core: false,
+                thread_scaling: None,
+                latency_target: None,
+                visibility: None,
             },
             output: OutputConfig::default(),
             runtime: RuntimeConfig::default(),
@@ -593,7 +1436,7 @@ mod tests {
                 write_distribution: vec![],
                 block_size: 4096,
                 queue_depth: 32,
-                completion_mode: CompletionMode::Duration { seconds: 10 },
+                completion_mode: CompletionMode::Duration { seconds: 10, millis: 0 },
                 random: false, // Sequential
                 distribution: DistributionType::Uniform,
                 think_time: None,
@@ -603,6 +1446,18 @@ mod tests {
                 heatmap: false,
                 heatmap_buckets: 100,
                 write_pattern: VerifyPattern::Random,
+                qd_ramp: None,
+                mmap: MmapOptions::default(),
+                buffer_hugepages: BufferHugePages::None,
+                grow_to: None,
+                open_flags: OpenFlagOptions::default(),
+                custom_pattern: None,
+                rw_split_by: RwSplitBy::Ops,
+                offset_start: None,
+                worker_groups: Vec::new(),
+                buffered_percent: 0,
+                remap_offsets: None,
+                no_engine_fallback: false,
             },
             targets: vec![TargetConfig {
                 path: PathBuf::from("/tmp/test"),
@@ -617,11 +1472,15 @@ mod tests {
                 fadvise_flags: FadviseFlags::default(),
                 madvise_flags: MadviseFlags::default(),
                 lock_mode: FileLockMode::None,
+                busy_file: BusyFilePolicy::Proceed,
                 preallocate: false,
+                prealloc_mode: PreallocMode::default(),
                 truncate_to_size: false,
                 refill: false,
                 refill_pattern: VerifyPattern::Random,
                 no_refill: false,
+                region: None,
+                full_fill: false,
             }],
             workers: WorkerConfig {
                 threads: 8,
@@ -630,6 +1489,13 @@ mod tests {
                 rate_limit_iops: None,
                 rate_limit_throughput: None,
                 offset_range: None,
+                unit_id: None,
+                core: None,
+                stonewall: false,
+                thread_per_core: false,
+                thread_scaling: None,
+                latency_target: None,
+                visibility: None,
             },
             output: OutputConfig::default(),
             runtime: RuntimeConfig::default(),
@@ -649,7 +1515,7 @@ mod tests {
                 write_distribution: vec![],
                 block_size: 4096,
                 queue_depth: 32,
-                completion_mode: CompletionMode::Duration { seconds: 10 },
+                completion_mode: CompletionMode::Duration { seconds: 10, millis: 0 },
                 random: true,
                 distribution: DistributionType::Uniform,
                 think_time: None,
@@ -659,6 +1525,18 @@ mod tests {
                 heatmap: false,
                 heatmap_buckets: 100,
                 write_pattern: VerifyPattern::Random,
+                qd_ramp: None,
+                mmap: MmapOptions::default(),
+                buffer_hugepages: BufferHugePages::None,
+                grow_to: None,
+                open_flags: OpenFlagOptions::default(),
+                custom_pattern: None,
+                rw_split_by: RwSplitBy::Ops,
+                offset_start: None,
+                worker_groups: Vec::new(),
+                buffered_percent: 0,
+                remap_offsets: None,
+                no_engine_fallback: false,
             },
             targets: vec![TargetConfig {
                 path: PathBuf::from("/tmp/test"),
@@ -673,11 +1551,15 @@ mod tests {
                 fadvise_flags: FadviseFlags::default(),
                 madvise_flags: MadviseFlags::default(),
                 lock_mode: FileLockMode::Range, // Locking enabled
+                busy_file: BusyFilePolicy::Proceed,
                 preallocate: false,
+                prealloc_mode: PreallocMode::default(),
                 truncate_to_size: false,
                 refill: false,
                 refill_pattern: VerifyPattern::Random,
                 no_refill: false,
+                region: None,
+                full_fill: false,
             }],
             workers: WorkerConfig {
                 threads: 8,
@@ -686,6 +1568,13 @@ mod tests {
                 rate_limit_iops: None,
                 rate_limit_throughput: None,
                 offset_range: None,
+                unit_id: None,
+                core: None,
+                stonewall: false,
+                thread_per_core: false,
+                thread_scaling: None,
+                latency_target: None,
+                visibility: None,
             },
             output: OutputConfig::default(),
             runtime: RuntimeConfig::default(),
@@ -705,7 +1594,7 @@ mod tests {
                 write_distribution: vec![],
                 block_size: 4096,
                 queue_depth: 32,
-                completion_mode: CompletionMode::Duration { seconds: 10 },
+                completion_mode: CompletionMode::Duration { seconds: 10, millis: 0 },
                 random: true,
                 distribution: DistributionType::Uniform,
                 think_time: None,
@@ -715,6 +1604,18 @@ mod tests {
                 heatmap: false,
                 heatmap_buckets: 100,
                 write_pattern: VerifyPattern::Random,
+                qd_ramp: None,
+                mmap: MmapOptions::default(),
+                buffer_hugepages: BufferHugePages::None,
+                grow_to: None,
+                open_flags: OpenFlagOptions::default(),
+                custom_pattern: None,
+                rw_split_by: RwSplitBy::Ops,
+                offset_start: None,
+                worker_groups: Vec::new(),
+                buffered_percent: 0,
+                remap_offsets: None,
+                no_engine_fallback: false,
             },
             targets: vec![TargetConfig {
                 path: PathBuf::from("/tmp/test"),
@@ -729,11 +1630,15 @@ mod tests {
                 fadvise_flags: FadviseFlags::default(),
                 madvise_flags: MadviseFlags::default(),
                 lock_mode: FileLockMode::None,
+                busy_file: BusyFilePolicy::Proceed,
                 preallocate: false,
+                prealloc_mode: PreallocMode::default(),
                 truncate_to_size: false,
                 refill: false,
                 refill_pattern: VerifyPattern::Random,
                 no_refill: false,
+                region: None,
+                full_fill: false,
             }],
             workers: WorkerConfig {
                 threads: 8,
@@ -742,6 +1647,13 @@ mod tests {
                 rate_limit_iops: None,
                 rate_limit_throughput: None,
                 offset_range: None,
+                unit_id: None,
+                core: None,
+                stonewall: false,
+                thread_per_core: false,
+                thread_scaling: None,
+                latency_target: None,
+                visibility: None,
             },
             output: OutputConfig::default(),
             runtime: RuntimeConfig::default(),
@@ -761,7 +1673,7 @@ mod tests {
                 write_distribution: vec![],
                 block_size: 4096,
                 queue_depth: 32,
-                completion_mode: CompletionMode::Duration { seconds: 10 },
+                completion_mode: CompletionMode::Duration { seconds: 10, millis: 0 },
                 random: true,
                 distribution: DistributionType::Uniform,
                 think_time: None,
@@ -771,6 +1683,18 @@ mod tests {
                 heatmap: false,
                 heatmap_buckets: 100,
                 write_pattern: VerifyPattern::Random,
+                qd_ramp: None,
+                mmap: MmapOptions::default(),
+                buffer_hugepages: BufferHugePages::None,
+                grow_to: None,
+                open_flags: OpenFlagOptions::default(),
+                custom_pattern: None,
+                rw_split_by: RwSplitBy::Ops,
+                offset_start: None,
+                worker_groups: Vec::new(),
+                buffered_percent: 0,
+                remap_offsets: None,
+                no_engine_fallback: false,
             },
             targets: vec![TargetConfig {
                 path: PathBuf::from("/tmp/test"),
@@ -785,11 +1709,15 @@ mod tests {
                 fadvise_flags: FadviseFlags::default(),
                 madvise_flags: MadviseFlags::default(),
                 lock_mode: FileLockMode::None,
+                busy_file: BusyFilePolicy::Proceed,
                 preallocate: false,
+                prealloc_mode: PreallocMode::default(),
                 truncate_to_size: false,
                 refill: false,
                 refill_pattern: VerifyPattern::Random,
                 no_refill: false,
+                region: None,
+                full_fill: false,
             }],
             workers: WorkerConfig {
                 threads: 1, // Single worker
@@ -798,6 +1726,13 @@ mod tests {
                 rate_limit_iops: None,
                 rate_limit_throughput: None,
                 offset_range: None,
+                unit_id: None,
+                core: None,
+                stonewall: false,
+                thread_per_core: false,
+                thread_scaling: None,
+                latency_target: None,
+                visibility: None,
             },
             output: OutputConfig::default(),
             runtime: RuntimeConfig::default(),
@@ -818,7 +1753,7 @@ mod tests {
                 write_distribution: vec![],
                 block_size: 4096,
                 queue_depth: 32,
-                completion_mode: CompletionMode::Duration { seconds: 10 },
+                completion_mode: CompletionMode::Duration { seconds: 10, millis: 0 },
                 random: true, // Random
                 distribution: DistributionType::Uniform,
                 think_time: None,
@@ -828,6 +1763,18 @@ mod tests {
                 heatmap: false,
                 heatmap_buckets: 100,
                 write_pattern: VerifyPattern::Random,
+                qd_ramp: None,
+                mmap: MmapOptions::default(),
+                buffer_hugepages: BufferHugePages::None,
+                grow_to: None,
+                open_flags: OpenFlagOptions::default(),
+                custom_pattern: None,
+                rw_split_by: RwSplitBy::Ops,
+                offset_start: None,
+                worker_groups: Vec::new(),
+                buffered_percent: 0,
+                remap_offsets: None,
+                no_engine_fallback: false,
             },
             targets: vec![TargetConfig {
                 path: PathBuf::from("/tmp/test"),
@@ -842,11 +1789,15 @@ mod tests {
                 fadvise_flags: FadviseFlags::default(),
                 madvise_flags: MadviseFlags::default(),
                 lock_mode: FileLockMode::None, // No locking
+                busy_file: BusyFilePolicy::Proceed,
                 preallocate: false,
+                prealloc_mode: PreallocMode::default(),
                 truncate_to_size: false,
                 refill: false,
                 refill_pattern: VerifyPattern::Random,
                 no_refill: false,
+                region: None,
+                full_fill: false,
             }],
             workers: WorkerConfig {
                 threads: 8, // Multiple workers
@@ -855,6 +1806,13 @@ mod tests {
                 rate_limit_iops: None,
                 rate_limit_throughput: None,
                 offset_range: None,
+                unit_id: None,
+                core: None,
+                stonewall: false,
+                thread_per_core: false,
+                thread_scaling: None,
+                latency_target: None,
+                visibility: None,
             },
             output: OutputConfig::default(),
             runtime: RuntimeConfig::default(),
@@ -863,4 +1821,179 @@ mod tests {
         // This should fail with write conflict error
         assert!(validate_write_conflicts(&config).is_err());
     }
+
+    #[test]
+    fn test_validate_grow() {
+        let mut config = Config {
+            workload: WorkloadConfig {
+                read_percent: 0,
+                write_percent: 100,
+                read_distribution: vec![],
+                write_distribution: vec![],
+                block_size: 4096,
+                queue_depth: 32,
+                completion_mode: CompletionMode::RunUntilComplete,
+                random: false,
+                distribution: DistributionType::Uniform,
+                think_time: None,
+                engine: EngineType::IoUring,
+                direct: false,
+                sync: false,
+                heatmap: false,
+                heatmap_buckets: 100,
+                write_pattern: VerifyPattern::Random,
+                qd_ramp: None,
+                mmap: MmapOptions::default(),
+                buffer_hugepages: BufferHugePages::None,
+                grow_to: Some(1024 * 1024 * 1024),
+                open_flags: OpenFlagOptions::default(),
+                custom_pattern: None,
+                rw_split_by: RwSplitBy::Ops,
+                offset_start: None,
+                worker_groups: Vec::new(),
+                buffered_percent: 0,
+                remap_offsets: None,
+                no_engine_fallback: false,
+            },
+            targets: vec![TargetConfig {
+                path: PathBuf::from("/tmp/test"),
+                target_type: TargetType::File,
+                file_size: None,
+                num_files: None,
+                num_dirs: None,
+                layout_config: None,
+                layout_manifest: None,
+                export_layout_manifest: None,
+                distribution: FileDistribution::Shared,
+                fadvise_flags: FadviseFlags::default(),
+                madvise_flags: MadviseFlags::default(),
+                lock_mode: FileLockMode::None,
+                busy_file: BusyFilePolicy::Proceed,
+                preallocate: false,
+                prealloc_mode: PreallocMode::default(),
+                truncate_to_size: false,
+                refill: false,
+                refill_pattern: VerifyPattern::Random,
+                no_refill: false,
+                region: None,
+                full_fill: false,
+            }],
+            workers: WorkerConfig::default(),
+            output: OutputConfig::default(),
+            runtime: RuntimeConfig::default(),
+        };
+
+        assert!(validate_workload(&config.workload).is_ok());
+        assert!(validate_grow(&config).is_ok());
+
+        // One writer per file
+        config.workers.threads = 4;
+        assert!(validate_grow(&config).is_err());
+        config.workers.threads = 1;
+
+        // The file starts at its current size
+        config.targets[0].file_size = Some(1024 * 1024);
+        assert!(validate_grow(&config).is_err());
+        config.targets[0].file_size = None;
+
+        // Writes only
+        config.workload.read_percent = 50;
+        config.workload.write_percent = 50;
+        assert!(validate_workload(&config.workload).is_err());
+    }
+
+    #[test]
+    fn test_validate_read_only() {
+        let mut config = Config {
+            workload: WorkloadConfig {
+                read_percent: 100,
+                write_percent: 0,
+                read_distribution: vec![],
+                write_distribution: vec![],
+                block_size: 4096,
+                queue_depth: 32,
+                completion_mode: CompletionMode::RunUntilComplete,
+                random: false,
+                distribution: DistributionType::Uniform,
+                think_time: None,
+                engine: EngineType::IoUring,
+                direct: false,
+                sync: false,
+                heatmap: false,
+                heatmap_buckets: 100,
+                write_pattern: VerifyPattern::Random,
+                qd_ramp: None,
+                mmap: MmapOptions::default(),
+                buffer_hugepages: BufferHugePages::None,
+                grow_to: None,
+                open_flags: OpenFlagOptions::default(),
+                custom_pattern: None,
+                rw_split_by: RwSplitBy::Ops,
+                offset_start: None,
+                worker_groups: Vec::new(),
+                buffered_percent: 0,
+                remap_offsets: None,
+                no_engine_fallback: false,
+            },
+            targets: vec![TargetConfig {
+                path: PathBuf::from("/tmp/test"),
+                target_type: TargetType::File,
+                file_size: None,
+                num_files: None,
+                num_dirs: None,
+                layout_config: None,
+                layout_manifest: None,
+                export_layout_manifest: None,
+                distribution: FileDistribution::Shared,
+                fadvise_flags: FadviseFlags::default(),
+                madvise_flags: MadviseFlags::default(),
+                lock_mode: FileLockMode::None,
+                busy_file: BusyFilePolicy::Proceed,
+                preallocate: false,
+                prealloc_mode: PreallocMode::default(),
+                truncate_to_size: false,
+                refill: false,
+                refill_pattern: VerifyPattern::Random,
+                no_refill: false,
+                region: None,
+                full_fill: false,
+            }],
+            workers: WorkerConfig::default(),
+            output: OutputConfig::default(),
+            runtime: RuntimeConfig::default(),
+        };
+
+        config.runtime.read_only = true;
+        assert!(validate_read_only(&config).is_ok());
+
+        // Writes fail fast
+        config.workload.read_percent = 70;
+        config.workload.write_percent = 30;
+        assert!(validate_read_only(&config).is_err());
+        config.workload.read_percent = 100;
+        config.workload.write_percent = 0;
+
+        // Nothing may fill or resize the target
+        config.targets[0].refill = true;
+        assert!(validate_read_only(&config).is_err());
+        config.targets[0].refill = false;
+        config.targets[0].truncate_to_size = true;
+        assert!(validate_read_only(&config).is_err());
+        config.targets[0].truncate_to_size = false;
+
+        config.workload.grow_to = Some(1024 * 1024);
+        assert!(validate_read_only(&config).is_err());
+    }
+
+    #[test]
+    fn test_validate_stonewall() {
+        let workers = WorkerConfig {
+            stonewall: true,
+            ..WorkerConfig::default()
+        };
+        assert!(validate_stonewall(&workers, &CompletionMode::RunUntilComplete).is_ok());
+        assert!(validate_stonewall(&workers, &CompletionMode::TotalBytes { bytes: 1 << 30 }).is_ok());
+        assert!(validate_stonewall(&workers, &CompletionMode::Duration { seconds: 10, millis: 0 }).is_err());
+        assert!(validate_stonewall(&WorkerConfig::default(), &CompletionMode::Duration { seconds: 10, millis: 0 }).is_ok());
+    }
 }
@@ -27,7 +27,63 @@ pub enum DistributionType {
     Uniform,
     Zipf { theta: f64 },
     Pareto { h: f64 },
-    Gaussian { stddev: f64, center: f64 },
+    Gaussian {
+        stddev: f64,
+        center: f64,
+        /// Movement of the center over time (protocol v3+)
+        #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+        drift: Option<CenterDrift>,
+    },
+    /// Weighted mix of distributions, each over its own range of the target
+    /// (protocol v3+)
+    Composite { components: Vec<DistributionComponent> },
+}
+
+/// One distribution of a composite
+///
+/// Each offset is drawn from one component, picked by weight, and placed in
+/// the component's range of the target.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DistributionComponent {
+    /// Percentage of offsets drawn from this component (weights sum to 100)
+    pub weight: u8,
+    /// Distribution within the range
+    pub distribution: DistributionType,
+    /// Start of the range, as a fraction of the target (0.0-1.0)
+    #[serde(default)]
+    pub start: f64,
+    /// End of the range, as a fraction of the target (0.0-1.0, exclusive)
+    #[serde(default = "default_component_end")]
+    pub end: f64,
+}
+
+fn default_component_end() -> f64 {
+    1.0
+}
+
+/// Workers sharing an access pattern
+///
+/// Lets one run model a mixed population, e.g. two sequential streamers
+/// next to six Zipf random readers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkerGroup {
+    /// Workers in the group
+    pub workers: usize,
+    /// Random access (otherwise sequential)
+    #[serde(default)]
+    pub random: bool,
+    /// Distribution of random offsets
+    #[serde(default)]
+    pub distribution: DistributionType,
+}
+
+/// Movement of the Gaussian center over time, in fractions of the range per second
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum CenterDrift {
+    /// Sweep at `rate` per second (negative sweeps backwards), wrapping around at the ends
+    Linear { rate: f64 },
+    /// Random walk with a standard deviation of `rate` per second, reflecting at the ends
+    RandomWalk { rate: f64 },
 }
 
 impl Default for DistributionType {
@@ -37,9 +93,14 @@ impl Default for DistributionType {
 }
 
 /// Completion criteria
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum CompletionMode {
-    Duration { seconds: u64 },
+    Duration {
+        seconds: u64,
+        /// Milliseconds on top of `seconds` (protocol v3+)
+        #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+        millis: u32,
+    },
     TotalBytes { bytes: u64 },
     RunUntilComplete,
 }
@@ -63,12 +124,235 @@ pub struct ThinkTimeConfig {
     pub apply_every_n_blocks: usize,
     /// Adaptive percentage of IO latency
     pub adaptive_percent: Option<u8>,
+    /// Spread the think time over every operation instead of applying it
+    /// once every N blocks (protocol v3+)
+    #[serde(default, skip_serializing_if = "crate::distributed::protocol::since_v3")]
+    pub smooth: bool,
 }
 
 fn default_think_every() -> usize {
     1
 }
 
+/// Queue depth ramp configuration
+///
+/// When enabled, each worker starts at QD=1 and doubles its in-flight limit
+/// every step until p99 latency exceeds `max_latency_us`, throughput stops
+/// improving by at least `plateau_percent`, or the configured queue_depth
+/// (used as the ramp ceiling) is reached. The worker then holds the optimal
+/// queue depth for the remainder of the test.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QdRampConfig {
+    /// Duration of each queue depth step in seconds
+    #[serde(default = "default_qd_ramp_step_seconds")]
+    pub step_seconds: u64,
+    /// Stop ramping when step p99 latency exceeds this (microseconds)
+    pub max_latency_us: Option<u64>,
+    /// Stop ramping when throughput improves by less than this percentage
+    #[serde(default = "default_qd_ramp_plateau_percent")]
+    pub plateau_percent: f64,
+}
+
+fn default_qd_ramp_step_seconds() -> u64 {
+    5
+}
+
+fn default_qd_ramp_plateau_percent() -> f64 {
+    5.0
+}
+
+impl Default for QdRampConfig {
+    fn default() -> Self {
+        Self {
+            step_seconds: default_qd_ramp_step_seconds(),
+            max_latency_us: None,
+            plateau_percent: default_qd_ramp_plateau_percent(),
+        }
+    }
+}
+
+/// mmap engine options
+///
+/// Page faults and copy bandwidth both land in mmap IO latency. These options
+/// separate them: pre-fault (and optionally pin) the mapping during setup,
+/// and bypass the CPU cache on writes so copies don't evict the working set.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MmapOptions {
+    /// Pre-fault the whole mapping at mmap time (MAP_POPULATE)
+    #[serde(default = "default_true")]
+    pub populate: bool,
+    /// Pin the mapping in memory (mlock) so pages are never faulted back in
+    #[serde(default)]
+    pub lock: bool,
+    /// Use non-temporal (streaming) stores for writes
+    #[serde(default)]
+    pub nontemporal: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Extra open(2) flags for the test targets
+///
+/// `--direct` and `--sync` cover O_DIRECT and O_SYNC. These cover the rest of
+/// the flags that change results: atime updates on reads, and data-only
+/// (O_DSYNC) vs. data+metadata (O_SYNC) write integrity.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct OpenFlagOptions {
+    /// Don't update the access time on reads (O_NOATIME)
+    #[serde(default)]
+    pub noatime: bool,
+    /// Writes complete once data (not all metadata) is durable (O_DSYNC)
+    #[serde(default)]
+    pub dsync: bool,
+    /// Reads wait for pending writes to the same data to complete (O_RSYNC)
+    #[serde(default)]
+    pub rsync: bool,
+}
+
+impl OpenFlagOptions {
+    /// Whether any flag is set
+    pub fn any(&self) -> bool {
+        self.noatime || self.dsync || self.rsync
+    }
+}
+
+impl std::fmt::Display for OpenFlagOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let names: Vec<&str> = [
+            (self.noatime, "O_NOATIME"),
+            (self.dsync, "O_DSYNC"),
+            (self.rsync, "O_RSYNC"),
+        ]
+        .iter()
+        .filter(|(set, _)| *set)
+        .map(|(_, name)| *name)
+        .collect();
+        write!(f, "{}", names.join(","))
+    }
+}
+
+impl Default for MmapOptions {
+    fn default() -> Self {
+        Self {
+            populate: true,
+            lock: false,
+            nontemporal: false,
+        }
+    }
+}
+
+impl std::fmt::Display for MmapOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut opts = Vec::new();
+        opts.push(if self.populate { "populate" } else { "lazy faults" });
+        if self.lock {
+            opts.push("mlock");
+        }
+        if self.nontemporal {
+            opts.push("non-temporal writes");
+        }
+        write!(f, "{}", opts.join(", "))
+    }
+}
+
+/// Huge page backing for IO buffer pools
+///
+/// Large buffers on 4K pages cost TLB misses on every copy; huge pages
+/// remove most of them for big-block workloads.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum BufferHugePages {
+    /// Regular pages
+    #[default]
+    None,
+    /// Transparent huge pages (madvise MADV_HUGEPAGE), best effort
+    Madvise,
+    /// Reserved huge pages (MAP_HUGETLB); fails if vm.nr_hugepages is too small
+    Hugetlb,
+}
+
+impl fmt::Display for BufferHugePages {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::None => write!(f, "none"),
+            Self::Madvise => write!(f, "madvise"),
+            Self::Hugetlb => write!(f, "hugetlb"),
+        }
+    }
+}
+
+/// Where sequential streams begin
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum OffsetStart {
+    /// A random block-aligned offset, chosen once per run (see `Config::resolve_offset_start`)
+    Random,
+    /// This byte offset, rounded down to the block size and wrapping at the end of the target
+    At(u64),
+}
+
+impl fmt::Display for OffsetStart {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Random => write!(f, "random"),
+            Self::At(offset) => write!(f, "{}", offset),
+        }
+    }
+}
+
+/// Chunk permutation between the distribution and the target (`--remap-offsets`)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct OffsetRemap {
+    /// Seed of the permutation; the same seed gives the same layout
+    pub seed: u64,
+    /// Bytes moved together (default: the IO size, or the least common multiple of the IO sizes)
+    pub chunk: Option<u64>,
+}
+
+impl OffsetRemap {
+    /// Chunk size for IO sizes `sizes`
+    pub fn chunk_size(&self, sizes: impl IntoIterator<Item = u64>) -> u64 {
+        self.chunk.unwrap_or_else(|| sizes.into_iter().fold(1, lcm))
+    }
+}
+
+impl fmt::Display for OffsetRemap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "seed {}", self.seed)?;
+        if let Some(chunk) = self.chunk {
+            write!(f, ", {} byte chunks", chunk)?;
+        }
+        Ok(())
+    }
+}
+
+fn lcm(a: u64, b: u64) -> u64 {
+    let (mut x, mut y) = (a, b);
+    while y != 0 {
+        (x, y) = (y, x % y);
+    }
+    a / x * b
+}
+
+/// What the read/write percentages divide
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum RwSplitBy {
+    /// Operations: each op is a read with probability `read_percent`
+    #[default]
+    Ops,
+    /// Bytes: each op goes to the class below its share of bytes submitted
+    Bytes,
+}
+
+impl fmt::Display for RwSplitBy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ops => write!(f, "ops"),
+            Self::Bytes => write!(f, "bytes"),
+        }
+    }
+}
+
 /// File distribution strategy
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 pub enum FileDistribution {
@@ -86,6 +370,39 @@ impl Default for FileDistribution {
     }
 }
 
+/// What a worker does when the file it picked is in use by another worker
+///
+/// Only applies to SHARED distribution with a file list, where several
+/// workers of a node can pick the same file.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum BusyFilePolicy {
+    /// Use the file anyway (collisions are only counted)
+    #[default]
+    Proceed,
+    /// Pick another file, up to a few attempts
+    Retry,
+    /// Wait until the other worker moves on
+    Wait,
+}
+
+/// How file space is preallocated
+///
+/// fallocate'd space is made of unwritten extents: the first write to each
+/// extent converts it, which costs extra metadata updates. Zero-filled files
+/// are fully written, so later writes only overwrite.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum PreallocMode {
+    /// posix_fallocate (falls back to writing zeros where fallocate is unsupported)
+    #[default]
+    Posix,
+    /// fallocate(2) only: unwritten extents, fails where unsupported
+    Fallocate,
+    /// Write zeros over the whole file
+    Zerofill,
+    /// Only set the file size (sparse file)
+    None,
+}
+
 /// File locking mode
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 pub enum FileLockMode {
@@ -129,6 +446,8 @@ pub enum EngineType {
     IoUring,
     Libaio,
     Mmap,
+    /// Sync IO on helper threads, one per queue slot (where io_uring and libaio are unavailable)
+    Threadpool,
 }
 
 impl Default for EngineType {
@@ -144,6 +463,25 @@ pub enum VerifyPattern {
     Ones,
     Random,
     Sequential,
+    /// User payload from `--pattern-file` or `--pattern-runs` (see `CustomPattern`)
+    Custom,
+}
+
+/// User-defined fill pattern, repeated across the file
+///
+/// The byte at file offset N is `payload[N % payload.len()]`, so data read
+/// back at any offset and size can be verified against the same payload.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomPattern {
+    /// `--pattern-file` path or `--pattern-runs` list, for display
+    pub source: String,
+    /// Bytes repeated across the file
+    pub payload: Vec<u8>,
+}
+
+impl CustomPattern {
+    /// Largest payload accepted (the payload is sent to every node and held by every worker)
+    pub const MAX_BYTES: usize = 4 * 1024 * 1024;
 }
 
 // Display trait implementations
@@ -175,9 +513,60 @@ impl fmt::Display for DistributionType {
             DistributionType::Uniform => write!(f, "uniform"),
             DistributionType::Zipf { theta } => write!(f, "zipf(theta={})", theta),
             DistributionType::Pareto { h } => write!(f, "pareto(h={})", h),
-            DistributionType::Gaussian { stddev, center } => {
+            DistributionType::Gaussian { stddev, center, drift: None } => {
                 write!(f, "gaussian(stddev={}, center={})", stddev, center)
             }
+            DistributionType::Gaussian { stddev, center, drift: Some(drift) } => {
+                write!(f, "gaussian(stddev={}, center={}, drift={})", stddev, center, drift)
+            }
+            DistributionType::Composite { components } => {
+                write!(f, "composite(")?;
+                for (i, component) in components.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", component)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+impl fmt::Display for DistributionComponent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}% {}", self.weight, self.distribution)?;
+        if self.start > 0.0 || self.end < 1.0 {
+            write!(f, " over {}-{}%", self.start * 100.0, self.end * 100.0)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for WorkerGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.random {
+            write!(f, "{} x random {}", self.workers, self.distribution)
+        } else {
+            write!(f, "{} x sequential", self.workers)
+        }
+    }
+}
+
+impl CenterDrift {
+    /// Rate in fractions of the range per second
+    pub fn rate(&self) -> f64 {
+        match self {
+            CenterDrift::Linear { rate } | CenterDrift::RandomWalk { rate } => *rate,
+        }
+    }
+}
+
+impl fmt::Display for CenterDrift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CenterDrift::Linear { rate } => write!(f, "linear:{}", rate),
+            CenterDrift::RandomWalk { rate } => write!(f, "walk:{}", rate),
         }
     }
 }
@@ -185,7 +574,8 @@ impl fmt::Display for DistributionType {
 impl fmt::Display for CompletionMode {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            CompletionMode::Duration { seconds } => write!(f, "duration({}s)", seconds),
+            CompletionMode::Duration { seconds, millis: 0 } => write!(f, "duration({}s)", seconds),
+            CompletionMode::Duration { seconds, millis } => write!(f, "duration({}ms)", seconds * 1000 + *millis as u64),
             CompletionMode::TotalBytes { bytes } => {
                 write!(f, "total_bytes({})", format_bytes(*bytes))
             }
@@ -218,6 +608,19 @@ impl fmt::Display for ThinkTimeConfig {
             // Fixed duration mode
             write!(f, "{}us {} every {} blocks", self.duration_us, self.mode, self.apply_every_n_blocks)?;
         }
+        if self.smooth {
+            write!(f, ", smoothed per op")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for QdRampConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}s steps, plateau <{}%", self.step_seconds, self.plateau_percent)?;
+        if let Some(max_us) = self.max_latency_us {
+            write!(f, ", p99 limit {}us", max_us)?;
+        }
         Ok(())
     }
 }
@@ -232,6 +635,33 @@ impl fmt::Display for FileDistribution {
     }
 }
 
+impl fmt::Display for PreallocMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PreallocMode::Posix => write!(f, "posix"),
+            PreallocMode::Fallocate => write!(f, "fallocate"),
+            PreallocMode::Zerofill => write!(f, "zerofill"),
+            PreallocMode::None => write!(f, "none"),
+        }
+    }
+}
+
+impl fmt::Display for BusyFilePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BusyFilePolicy::Proceed => write!(f, "proceed"),
+            BusyFilePolicy::Retry => write!(f, "retry"),
+            BusyFilePolicy::Wait => write!(f, "wait"),
+        }
+    }
+}
+
+impl fmt::Display for CustomPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({} bytes)", self.source, self.payload.len())
+    }
+}
+
 impl fmt::Display for FileLockMode {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -304,6 +734,7 @@ impl fmt::Display for EngineType {
             EngineType::IoUring => write!(f, "io_uring"),
             EngineType::Libaio => write!(f, "libaio"),
             EngineType::Mmap => write!(f, "mmap"),
+            EngineType::Threadpool => write!(f, "threadpool"),
         }
     }
 }
@@ -321,6 +752,7 @@ impl fmt::Display for VerifyPattern {
             VerifyPattern::Ones => write!(f, "ones"),
             VerifyPattern::Random => write!(f, "random"),
             VerifyPattern::Sequential => write!(f, "sequential"),
+            VerifyPattern::Custom => write!(f, "custom"),
         }
     }
 }
@@ -336,13 +768,7 @@ impl IOPattern {
         if self.block_size == 0 {
             return Err("IOPattern block_size must be greater than 0".to_string());
         }
-        if self.block_size < 512 {
-            return Err(format!(
-                "IOPattern block_size must be at least 512 bytes, got {}",
-                self.block_size
-            ));
-        }
-        if self.block_size > 64 * 1024 * 1024 {
+        if self.block_size > super::MAX_IO_SIZE {
             return Err(format!(
                 "IOPattern block_size must be at most 64MB, got {}",
                 self.block_size
@@ -374,7 +800,7 @@ impl DistributionType {
                     Ok(())
                 }
             }
-            DistributionType::Gaussian { stddev, center } => {
+            DistributionType::Gaussian { stddev, center, drift } => {
                 if *stddev <= 0.0 {
                     Err(format!(
                         "Gaussian stddev must be greater than 0, got {}",
@@ -385,20 +811,61 @@ impl DistributionType {
                         "Gaussian center must be in range 0.0-1.0, got {}",
                         center
                     ))
+                } else if let Some(drift) = drift.filter(|d| !d.rate().is_finite()) {
+                    Err(format!("Gaussian drift rate must be a finite number, got {}", drift.rate()))
                 } else {
                     Ok(())
                 }
             }
+            DistributionType::Composite { components } => {
+                if components.is_empty() {
+                    return Err("Composite distribution needs at least one component".to_string());
+                }
+                let total: u32 = components.iter().map(|c| c.weight as u32).sum();
+                if total != 100 {
+                    return Err(format!("Composite distribution weights must sum to 100, got {}", total));
+                }
+                for (i, component) in components.iter().enumerate() {
+                    if matches!(component.distribution, DistributionType::Composite { .. }) {
+                        return Err(format!("components[{}]: composite distributions can't be nested", i));
+                    }
+                    let (start, end) = (component.start, component.end);
+                    if !(0.0..1.0).contains(&start) || !(start..=1.0).contains(&end) || end == start {
+                        return Err(format!(
+                            "components[{}]: range must satisfy 0.0 <= start < end <= 1.0, got {}-{}",
+                            i, start, end
+                        ));
+                    }
+                    component.distribution.validate()
+                        .map_err(|e| format!("components[{}]: {}", i, e))?;
+                }
+                Ok(())
+            }
         }
     }
 }
 
 impl CompletionMode {
+    /// Run for `length` (millisecond resolution)
+    pub fn duration(length: std::time::Duration) -> Self {
+        CompletionMode::Duration { seconds: length.as_secs(), millis: length.subsec_millis() }
+    }
+    
+    /// Length of a duration-based run
+    pub fn test_duration(&self) -> Option<std::time::Duration> {
+        match *self {
+            CompletionMode::Duration { seconds, millis } => {
+                Some(std::time::Duration::from_secs(seconds) + std::time::Duration::from_millis(millis as u64))
+            }
+            _ => None,
+        }
+    }
+    
     /// Validate the completion mode
     pub fn validate(&self) -> Result<(), String> {
         match self {
-            CompletionMode::Duration { seconds } => {
-                if *seconds == 0 {
+            CompletionMode::Duration { seconds, millis } => {
+                if *seconds == 0 && *millis == 0 {
                     Err("Duration must be greater than 0".to_string())
                 } else {
                     Ok(())
@@ -440,6 +907,35 @@ impl ThinkTimeConfig {
     }
 }
 
+impl QdRampConfig {
+    /// Validate the queue depth ramp configuration
+    ///
+    /// `queue_depth` is the ramp ceiling.
+    pub fn validate(&self, queue_depth: usize) -> Result<(), String> {
+        if queue_depth < 2 {
+            return Err(format!(
+                "qd_ramp requires queue_depth >= 2 (used as the ramp ceiling), got {}",
+                queue_depth
+            ));
+        }
+        if self.step_seconds == 0 {
+            return Err("qd_ramp step_seconds must be greater than 0".to_string());
+        }
+        if let Some(max_us) = self.max_latency_us {
+            if max_us == 0 {
+                return Err("qd_ramp max_latency_us must be greater than 0".to_string());
+            }
+        }
+        if !(0.0..=100.0).contains(&self.plateau_percent) {
+            return Err(format!(
+                "qd_ramp plateau_percent must be 0-100, got {}",
+                self.plateau_percent
+            ));
+        }
+        Ok(())
+    }
+}
+
 impl FadviseFlags {
     /// Validate fadvise flags
     pub fn validate(&self) -> Result<(), String> {
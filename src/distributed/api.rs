@@ -0,0 +1,578 @@
+//! HTTP API for the coordinator
+//!
+//! With `--api-listen`, the coordinator runs as a server instead of running a
+//! single test from the command line. Lab automation and dashboards submit
+//! test configurations and poll for progress and results over plain HTTP/JSON.
+//!
+//! # Endpoints
+//!
+//! ```text
+//! POST /jobs               Submit a config (TOML, or JSON with Content-Type: application/json)
+//!                          → 202 {"job_id": "...", "state": "queued"}
+//! GET  /jobs               List all jobs and their state
+//! GET  /jobs/{id}          State of one job (queued, running, completed, failed)
+//! GET  /jobs/{id}/live     Live aggregate stats from node heartbeats
+//! GET  /jobs/{id}/results  Final results (aggregate JSON output schema)
+//! POST /jobs/{id}/control  Control the running job (body: a control command,
+//!                          e.g. "pause" or "rate 5000") → {"reply": "..."}
+//! ```
+//!
+//! Submitted configs use the same schema as `--config` TOML files. Jobs run
+//! one at a time, in submission order, against the nodes given on the
+//! command line. The newest `MAX_FINISHED_JOBS` finished jobs are kept.
+
+use crate::config::Config;
+use crate::distributed::control::{ControlHandle, ControlInbox};
+use crate::distributed::coordinator::{generate_job_id, ping_node, DistributedCoordinator, JobProgress};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Largest accepted request (headers + body)
+const MAX_REQUEST_BYTES: usize = 1024 * 1024;
+
+/// How long a client may take to send its request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long to wait for nodes to release the previous job before starting the next
+const NODE_IDLE_WAIT: Duration = Duration::from_secs(30);
+
+/// Finished jobs kept for their results; older ones are dropped
+const MAX_FINISHED_JOBS: usize = 1000;
+
+/// Job lifecycle state
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobState {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+}
+
+/// A submitted job
+struct ApiJob {
+    job_id: String,
+    state: JobState,
+    submitted_at: String,
+    error: Option<String>,
+    progress: Arc<JobProgress>,
+    config: Arc<Config>,
+    /// Control commands for the job while it runs
+    control: Option<ControlHandle>,
+}
+
+/// Job summary returned by the API
+#[derive(Debug, Serialize)]
+struct JobSummary {
+    job_id: String,
+    state: JobState,
+    submitted_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Jobs by submission number, the numeric suffix of their ID
+type JobTable = Arc<Mutex<BTreeMap<u64, ApiJob>>>;
+
+/// HTTP API server
+pub struct ApiServer {
+    /// Address to listen on (e.g., 0.0.0.0:8080)
+    listen: String,
+
+    /// Node addresses every job runs against
+    node_addresses: Vec<String>,
+
+    /// Submitted jobs
+    jobs: JobTable,
+
+    /// Submission number of the next job
+    next_seq: AtomicU64,
+}
+
+impl ApiServer {
+    /// Create an API server for the given nodes
+    pub fn new(listen: String, node_addresses: Vec<String>) -> Result<Self> {
+        if node_addresses.is_empty() {
+            anyhow::bail!("No nodes specified for the API server");
+        }
+
+        Ok(Self {
+            listen,
+            node_addresses,
+            jobs: Arc::new(Mutex::new(BTreeMap::new())),
+            next_seq: AtomicU64::new(1),
+        })
+    }
+
+    /// Serve requests until the process exits
+    pub async fn run(self) -> Result<()> {
+        let listener = TcpListener::bind(&self.listen).await
+            .with_context(|| format!("Failed to bind API server to {}", self.listen))?;
+
+        println!("Coordinator API listening on http://{}", self.listen);
+        println!("Nodes: {}", self.node_addresses.join(", "));
+
+        let (job_tx, job_rx) = mpsc::channel::<u64>();
+        spawn_job_runner(job_rx, self.node_addresses.clone(), self.jobs.clone());
+
+        // One task per connection, so a slow client doesn't hold up the others
+        let server = Arc::new(self);
+        loop {
+            let (mut stream, _addr) = match listener.accept().await {
+                Ok(conn) => conn,
+                Err(e) => {
+                    tracing::warn!("Failed to accept API connection: {}", e);
+                    continue;
+                }
+            };
+
+            let server = server.clone();
+            let job_tx = job_tx.clone();
+            tokio::spawn(async move {
+                let (status, body) = match tokio::time::timeout(REQUEST_TIMEOUT, read_request(&mut stream)).await {
+                    Ok(Ok(request)) => server.route(&request, &job_tx).await,
+                    Ok(Err(e)) => (400, error_body(&format!("{:#}", e))),
+                    Err(_) => (408, error_body(&format!("request not received within {}s", REQUEST_TIMEOUT.as_secs()))),
+                };
+
+                if let Err(e) = write_response(&mut stream, status, &body).await {
+                    tracing::warn!("Failed to send API response: {}", e);
+                }
+            });
+        }
+    }
+
+    /// Dispatch a request to its endpoint
+    async fn route(&self, request: &HttpRequest, job_tx: &mpsc::Sender<u64>) -> (u16, String) {
+        let path = request.path.trim_end_matches('/');
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+        match (request.method.as_str(), segments.as_slice()) {
+            ("POST", ["jobs"]) => {
+                let mut config = match parse_config(request) {
+                    Ok(config) => config,
+                    Err(e) => return (400, error_body(&format!("{:#}", e))),
+                };
+
+                let seq = self.next_seq.fetch_add(1, Ordering::Relaxed);
+                let job_id = format!("{}-{}", generate_job_id(), seq);
+                config.expand_run_id(&job_id);
+                config.resolve_offset_start();
+                if let Err(e) = config.resolve_device_rating() {
+                    return (400, error_body(&format!("{:#}", e)));
+                }
+
+                let mut jobs = self.jobs.lock().unwrap();
+                jobs.insert(seq, ApiJob {
+                    job_id: job_id.clone(),
+                    state: JobState::Queued,
+                    submitted_at: chrono::Local::now().to_rfc3339(),
+                    error: None,
+                    progress: Arc::new(JobProgress::new()),
+                    config: Arc::new(config),
+                    control: None,
+                });
+                expire_finished_jobs(&mut jobs);
+                drop(jobs);
+
+                if job_tx.send(seq).is_err() {
+                    return (503, error_body("job runner stopped"));
+                }
+
+                println!("API: queued job {}", job_id);
+                (202, serde_json::json!({ "job_id": job_id, "state": JobState::Queued }).to_string())
+            }
+            ("GET", ["jobs"]) => {
+                let jobs = self.jobs.lock().unwrap();
+                let summaries: Vec<JobSummary> = jobs.values().map(summarize).collect();
+                (200, serde_json::to_string(&summaries).unwrap_or_default())
+            }
+            ("GET", ["jobs", id]) => {
+                match find_job(&self.jobs.lock().unwrap(), id) {
+                    Some(job) => (200, serde_json::to_string(&summarize(job)).unwrap_or_default()),
+                    None => (404, error_body(&format!("unknown job {}", id))),
+                }
+            }
+            ("GET", ["jobs", id, "live"]) => {
+                match find_job(&self.jobs.lock().unwrap(), id) {
+                    Some(job) => {
+                        let body = serde_json::json!({
+                            "job_id": id,
+                            "state": job.state,
+                            "live": job.progress.live(),
+                        });
+                        (200, body.to_string())
+                    }
+                    None => (404, error_body(&format!("unknown job {}", id))),
+                }
+            }
+            ("GET", ["jobs", id, "results"]) => {
+                match find_job(&self.jobs.lock().unwrap(), id) {
+                    Some(job) => match job.progress.result() {
+                        Some(result) => (200, result.to_string()),
+                        None => (409, error_body(&format!("job {} has no results (state: {:?})", id, job.state))),
+                    },
+                    None => (404, error_body(&format!("unknown job {}", id))),
+                }
+            }
+            ("POST", ["jobs", id, "control"]) => {
+                let handle = match find_job(&self.jobs.lock().unwrap(), id) {
+                    Some(job) => match job.control {
+                        Some(ref handle) => handle.clone(),
+                        None => return (409, error_body(&format!("job {} is not running (state: {:?})", id, job.state))),
+                    },
+                    None => return (404, error_body(&format!("unknown job {}", id))),
+                };
+
+                // Waits until the job's coordinator picks the command up
+                let reply = handle.send(&String::from_utf8_lossy(&request.body)).await;
+                match reply.strip_prefix("error: ") {
+                    Some(reason) => (400, error_body(reason)),
+                    None => (200, serde_json::json!({ "job_id": id, "reply": reply.trim_start_matches("ok: ") }).to_string()),
+                }
+            }
+            (_, ["jobs", ..]) => (405, error_body("method not allowed")),
+            _ => (404, error_body("not found")),
+        }
+    }
+}
+
+/// Job with the given ID
+///
+/// The ID's numeric suffix is the table key; the rest must match too.
+fn find_job<'a>(jobs: &'a BTreeMap<u64, ApiJob>, id: &str) -> Option<&'a ApiJob> {
+    let seq = id.rsplit_once('-')?.1.parse().ok()?;
+    jobs.get(&seq).filter(|job| job.job_id == id)
+}
+
+/// Drop the oldest finished jobs beyond `MAX_FINISHED_JOBS`
+fn expire_finished_jobs(jobs: &mut BTreeMap<u64, ApiJob>) {
+    let finished: Vec<u64> = jobs.iter()
+        .filter(|(_, job)| matches!(job.state, JobState::Completed | JobState::Failed))
+        .map(|(&seq, _)| seq)
+        .collect();
+    for seq in &finished[..finished.len().saturating_sub(MAX_FINISHED_JOBS)] {
+        jobs.remove(seq);
+    }
+}
+
+/// Run queued jobs one at a time on a dedicated thread
+///
+/// Each job gets its own coordinator (and runtime), exactly like a CLI run.
+fn spawn_job_runner(job_rx: mpsc::Receiver<u64>, node_addresses: Vec<String>, jobs: JobTable) {
+    std::thread::spawn(move || {
+        for seq in job_rx {
+            let (handle, control) = ControlHandle::channel();
+            let (job_id, config, progress) = {
+                let mut jobs = jobs.lock().unwrap();
+                let Some(job) = jobs.get_mut(&seq) else { continue };
+                job.state = JobState::Running;
+                job.control = Some(handle);
+                (job.job_id.clone(), job.config.clone(), job.progress.clone())
+            };
+
+            println!("API: running job {}", job_id);
+            let result = run_job(&job_id, config, progress, control, node_addresses.clone());
+
+            let mut jobs = jobs.lock().unwrap();
+            if let Some(job) = jobs.get_mut(&seq) {
+                job.control = None;
+                match result {
+                    Ok(()) => job.state = JobState::Completed,
+                    Err(e) => {
+                        tracing::error!("API: job {} failed: {:#}", job_id, e);
+                        job.state = JobState::Failed;
+                        job.error = Some(format!("{:#}", e));
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// Run a single job to completion
+fn run_job(
+    job_id: &str,
+    config: Arc<Config>,
+    progress: Arc<JobProgress>,
+    control: ControlInbox,
+    node_addresses: Vec<String>,
+) -> Result<()> {
+    let runtime = tokio::runtime::Runtime::new()
+        .context("Failed to create tokio runtime")?;
+
+    runtime.block_on(async {
+        wait_for_idle_nodes(&node_addresses).await;
+
+        let mut coordinator = DistributedCoordinator::new(config, node_addresses)
+            .context("Failed to create coordinator")?;
+        coordinator.set_job_id(job_id.to_string());
+        coordinator.set_progress(progress);
+        coordinator.set_control(control);
+
+        coordinator.run().await
+    })
+}
+
+/// Wait until no node reports an active job
+///
+/// Nodes hold their job slot briefly after sending RESULTS, so a job started
+/// right after the previous one would otherwise be rejected as BUSY. Nodes
+/// that can't be reached are left for the coordinator to report.
+async fn wait_for_idle_nodes(node_addresses: &[String]) {
+    let deadline = std::time::Instant::now() + NODE_IDLE_WAIT;
+
+    for addr in node_addresses {
+        while std::time::Instant::now() < deadline {
+            let busy = match ping_node(addr, Duration::from_secs(5)).await {
+                Ok(ping) => ping.status.is_some_and(|s| s.current_test.is_some()),
+                Err(_) => false,
+            };
+            if !busy {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(250)).await;
+        }
+    }
+}
+
+/// Parse and validate a submitted config
+fn parse_config(request: &HttpRequest) -> Result<Config> {
+    let body = std::str::from_utf8(&request.body)
+        .context("Request body is not valid UTF-8")?;
+
+    let is_json = request.content_type.as_deref()
+        .is_some_and(|ct| ct.starts_with("application/json"));
+
+    let config: Config = if is_json {
+        serde_json::from_str(body).context("Failed to parse JSON configuration")?
+    } else {
+        crate::config::toml::parse_toml_string(body)?
+    };
+
+    crate::config::validator::validate_config(&config)
+        .context("Configuration validation failed")?;
+
+    Ok(config)
+}
+
+fn summarize(job: &ApiJob) -> JobSummary {
+    JobSummary {
+        job_id: job.job_id.clone(),
+        state: job.state,
+        submitted_at: job.submitted_at.clone(),
+        error: job.error.clone(),
+    }
+}
+
+fn error_body(message: &str) -> String {
+    serde_json::json!({ "error": message }).to_string()
+}
+
+/// Minimal HTTP/1.1 request
+#[derive(Debug)]
+struct HttpRequest {
+    method: String,
+    path: String,
+    content_type: Option<String>,
+    body: Vec<u8>,
+}
+
+/// Read one request (request line, headers, Content-Length body)
+async fn read_request(stream: &mut TcpStream) -> Result<HttpRequest> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+
+    // Read until the end of the headers
+    let header_end = loop {
+        if let Some(pos) = find_header_end(&buf) {
+            break pos;
+        }
+        if buf.len() > MAX_REQUEST_BYTES {
+            anyhow::bail!("Request headers too large");
+        }
+        let n = stream.read(&mut chunk).await.context("Failed to read request")?;
+        if n == 0 {
+            anyhow::bail!("Connection closed before end of headers");
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    };
+
+    let parsed = parse_head(&buf[..header_end])?;
+    let body_start = header_end + 4;
+
+    if parsed.content_length > MAX_REQUEST_BYTES {
+        anyhow::bail!("Request body too large ({} bytes, max {})", parsed.content_length, MAX_REQUEST_BYTES);
+    }
+    while buf.len() < body_start + parsed.content_length {
+        let n = stream.read(&mut chunk).await.context("Failed to read request body")?;
+        if n == 0 {
+            anyhow::bail!("Connection closed before end of body");
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+
+    Ok(HttpRequest {
+        method: parsed.method,
+        path: parsed.path,
+        content_type: parsed.content_type,
+        body: buf[body_start..body_start + parsed.content_length].to_vec(),
+    })
+}
+
+/// Request line and the headers the API cares about
+struct RequestHead {
+    method: String,
+    path: String,
+    content_type: Option<String>,
+    content_length: usize,
+}
+
+fn find_header_end(buf: &[u8]) -> Option<usize> {
+    buf.windows(4).position(|w| w == b"\r\n\r\n")
+}
+
+fn parse_head(head: &[u8]) -> Result<RequestHead> {
+    let head = std::str::from_utf8(head).context("Request headers are not valid UTF-8")?;
+    let mut lines = head.split("\r\n");
+
+    let request_line = lines.next().unwrap_or_default();
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        anyhow::bail!("Malformed request line: {:?}", request_line);
+    };
+
+    // Ignore any query string
+    let path = target.split('?').next().unwrap_or(target).to_string();
+
+    let mut content_type = None;
+    let mut content_length = 0;
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else { continue };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("content-length") {
+            content_length = value.parse().context("Invalid Content-Length")?;
+        } else if name.eq_ignore_ascii_case("content-type") {
+            content_type = Some(value.to_ascii_lowercase());
+        }
+    }
+
+    Ok(RequestHead {
+        method: method.to_ascii_uppercase(),
+        path,
+        content_type,
+        content_length,
+    })
+}
+
+/// Write a JSON response and close the connection
+async fn write_response(stream: &mut TcpStream, status: u16, body: &str) -> Result<()> {
+    let reason = match status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        409 => "Conflict",
+        _ => "Service Unavailable",
+    };
+
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status, reason, body.len(), body
+    );
+    stream.write_all(response.as_bytes()).await.context("Failed to write response")?;
+    stream.flush().await.context("Failed to flush response")?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_head() {
+        let head = b"POST /jobs?wait=1 HTTP/1.1\r\nHost: x\r\nContent-Type: Application/JSON\r\nContent-Length: 42";
+        let parsed = parse_head(head).unwrap();
+        assert_eq!(parsed.method, "POST");
+        assert_eq!(parsed.path, "/jobs");
+        assert_eq!(parsed.content_type.as_deref(), Some("application/json"));
+        assert_eq!(parsed.content_length, 42);
+
+        assert!(parse_head(b"garbage").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_routes() {
+        let server = ApiServer::new("127.0.0.1:0".to_string(), vec!["127.0.0.1:9999".to_string()]).unwrap();
+        let (tx, _rx) = mpsc::channel();
+        let get = |path: &str| HttpRequest {
+            method: "GET".to_string(),
+            path: path.to_string(),
+            content_type: None,
+            body: Vec::new(),
+        };
+
+        assert_eq!(server.route(&get("/jobs"), &tx).await.0, 200);
+        assert_eq!(server.route(&get("/jobs/nope"), &tx).await.0, 404);
+        assert_eq!(server.route(&get("/metrics"), &tx).await.0, 404);
+
+        // Invalid config is rejected without queueing a job
+        let bad = HttpRequest {
+            method: "POST".to_string(),
+            path: "/jobs".to_string(),
+            content_type: Some("application/json".to_string()),
+            body: b"{}".to_vec(),
+        };
+        assert_eq!(server.route(&bad, &tx).await.0, 400);
+        assert!(server.jobs.lock().unwrap().is_empty());
+
+        let control = HttpRequest {
+            method: "POST".to_string(),
+            path: "/jobs/nope/control".to_string(),
+            content_type: None,
+            body: b"pause".to_vec(),
+        };
+        assert_eq!(server.route(&control, &tx).await.0, 404);
+    }
+
+    #[test]
+    fn test_job_table() {
+        let config: Arc<Config> = Arc::new(toml::from_str(
+            "targets = [{ path = \"/data/test.dat\" }]\n\
+             [workload]\nread_percent = 100\nwrite_percent = 0\ncompletion_mode = { Duration = { seconds = 10 } }\n",
+        ).unwrap());
+        let job = |seq: u64, state| ApiJob {
+            job_id: format!("20250101-120000-4242-{}", seq),
+            state,
+            submitted_at: String::new(),
+            error: None,
+            progress: Arc::new(JobProgress::new()),
+            config: config.clone(),
+            control: None,
+        };
+        let mut jobs = BTreeMap::new();
+        for seq in 1..=MAX_FINISHED_JOBS as u64 + 2 {
+            jobs.insert(seq, job(seq, if seq == 1 { JobState::Queued } else { JobState::Completed }));
+        }
+        jobs.insert(MAX_FINISHED_JOBS as u64 + 3, job(0, JobState::Running));
+
+        // Job 10 lists after job 9, and only the oldest finished job is dropped
+        expire_finished_jobs(&mut jobs);
+        assert_eq!(jobs.keys().take(3).copied().collect::<Vec<_>>(), [1, 3, 4]);
+        assert_eq!(jobs.len(), MAX_FINISHED_JOBS + 2);
+
+        assert!(find_job(&jobs, "20250101-120000-4242-10").is_some());
+        assert!(find_job(&jobs, "20250101-120000-4242-2").is_none());
+        assert!(find_job(&jobs, "19990101-000000-1-10").is_none());
+        assert!(find_job(&jobs, "nope").is_none());
+    }
+}
@@ -0,0 +1,344 @@
+//! Control commands for a running test
+//!
+//! A running test can be adjusted without restarting it, from the control
+//! socket (`--control-socket`) or the coordinator API
+//! (`POST /jobs/{id}/control`). Both accept the same one-line text commands:
+//!
+//! ```text
+//! pause                   Stop submitting new IO on every node
+//! resume                  Continue after a pause
+//! rate 5000               Limit the whole test to 5000 IOPS
+//! rate 200M/s             Limit the whole test to 200 MB/s
+//! rate 5000 200M/s        Both limits
+//! rate off                Remove the rate limit
+//! mix 70                  Switch to 70% reads / 30% writes
+//! checkpoint              Close a summary checkpoint now
+//! status                  Show the current control settings
+//! ```
+//!
+//! Every command gets a single reply line starting with `ok:` or `error:`.
+//! Commands are queued for the coordinator, which applies them between
+//! heartbeat reads and forwards node commands as CONTROL messages.
+
+use crate::config::Config;
+use crate::distributed::protocol::ControlMessage;
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::UnixListener;
+
+/// How long a command waits for the coordinator to pick it up
+///
+/// Commands sent while no test is running are dropped after this.
+const REPLY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A parsed control command
+#[derive(Debug, Clone, PartialEq)]
+pub enum ControlCommand {
+    /// Forwarded to every node
+    Node(ControlMessage),
+    /// Close a summary checkpoint now
+    Checkpoint,
+    /// Report the current control settings
+    Status,
+}
+
+impl FromStr for ControlCommand {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut words = s.split_whitespace();
+        let command = words.next().unwrap_or("").to_ascii_lowercase();
+        let args: Vec<&str> = words.collect();
+
+        let parsed = match (command.as_str(), args.as_slice()) {
+            ("pause", []) => ControlCommand::Node(ControlMessage::Pause),
+            ("resume", []) => ControlCommand::Node(ControlMessage::Resume),
+            ("rate", ["off"]) => ControlCommand::Node(ControlMessage::Rate { iops: 0, bytes_per_sec: 0 }),
+            ("rate", limits) if !limits.is_empty() && limits.len() <= 2 => {
+                let (mut iops, mut bytes_per_sec) = (0, 0);
+                for limit in limits {
+                    if let Some(size) = limit.strip_suffix("/s") {
+                        bytes_per_sec = crate::config::cli_convert::parse_size(size)
+                            .with_context(|| format!("Invalid throughput limit '{}'", limit))?;
+                    } else {
+                        iops = limit.parse()
+                            .with_context(|| format!("Invalid IOPS limit '{}'", limit))?;
+                    }
+                }
+                if iops == 0 && bytes_per_sec == 0 {
+                    anyhow::bail!("Use 'rate off' to remove the rate limit");
+                }
+                ControlCommand::Node(ControlMessage::Rate { iops, bytes_per_sec })
+            }
+            ("mix", [percent]) => {
+                let percent: u8 = percent.trim_end_matches('%').parse()
+                    .with_context(|| format!("Invalid read percentage '{}'", percent))?;
+                if percent > 100 {
+                    anyhow::bail!("Read percentage must be 0-100, got {}", percent);
+                }
+                ControlCommand::Node(ControlMessage::ReadPercent(percent))
+            }
+            ("checkpoint", []) => ControlCommand::Checkpoint,
+            ("status", []) => ControlCommand::Status,
+            ("", _) => anyhow::bail!("Empty command"),
+            _ => anyhow::bail!(
+                "Unknown command '{}' (expected pause, resume, rate <iops> <bytes>/s | off, mix <read%>, checkpoint or status)",
+                s.trim()
+            ),
+        };
+        Ok(parsed)
+    }
+}
+
+/// A command waiting for the coordinator
+#[derive(Debug)]
+pub struct ControlRequest {
+    pub command: ControlCommand,
+    sent: Instant,
+    reply: oneshot::Sender<Result<String, String>>,
+}
+
+impl ControlRequest {
+    /// Whether the sender already gave up waiting
+    pub fn is_stale(&self) -> bool {
+        self.sent.elapsed() >= REPLY_TIMEOUT
+    }
+
+    /// Answer the sender
+    pub fn reply(self, reply: Result<String, String>) {
+        let _ = self.reply.send(reply);
+    }
+}
+
+/// Receiving end of the control channel, polled by the coordinator
+pub type ControlInbox = std::sync::Arc<std::sync::Mutex<mpsc::Receiver<ControlRequest>>>;
+
+/// Sending end of the control channel
+#[derive(Debug, Clone)]
+pub struct ControlHandle {
+    tx: mpsc::Sender<ControlRequest>,
+}
+
+impl ControlHandle {
+    /// Create a control channel
+    pub fn channel() -> (Self, ControlInbox) {
+        let (tx, rx) = mpsc::channel();
+        (Self { tx }, std::sync::Arc::new(std::sync::Mutex::new(rx)))
+    }
+
+    /// Parse and send a command, waiting until the coordinator replies
+    ///
+    /// Returns the reply line (`ok: ...` or `error: ...`).
+    pub async fn send(&self, line: &str) -> String {
+        let command = match line.parse::<ControlCommand>() {
+            Ok(command) => command,
+            Err(e) => return format!("error: {:#}", e),
+        };
+
+        let (reply_tx, reply_rx) = oneshot::channel();
+        let request = ControlRequest { command, sent: Instant::now(), reply: reply_tx };
+        if self.tx.send(request).is_err() {
+            return "error: test has finished".to_string();
+        }
+
+        match tokio::time::timeout(REPLY_TIMEOUT, reply_rx).await {
+            Ok(Ok(Ok(reply))) => format!("ok: {}", reply),
+            Ok(Ok(Err(reason))) => format!("error: {}", reason),
+            Ok(Err(_)) | Err(_) => "error: no test is running".to_string(),
+        }
+    }
+}
+
+/// Control settings in effect, as the coordinator last set them
+#[derive(Debug)]
+pub struct ControlState {
+    paused: bool,
+    /// Whole-test IOPS limit (0 = unlimited)
+    iops: u64,
+    /// Whole-test throughput limit in bytes/sec (0 = unlimited)
+    bytes_per_sec: u64,
+    read_percent: u8,
+    /// Why the read/write mix can't be changed, if it can't
+    mix_blocked: Option<&'static str>,
+}
+
+impl ControlState {
+    /// Settings at the start of a test on `nodes` nodes
+    pub fn new(config: &Config, nodes: usize, has_roles: bool) -> Self {
+        let workers = (config.workers.threads * nodes) as u64;
+        let read_percent = config.workload.read_percent;
+        let mix_blocked = if config.runtime.verify {
+            Some("the read/write mix can't change while verifying")
+        } else if has_roles {
+            Some("node roles have their own workloads")
+        } else if read_percent == 0 || read_percent == 100 {
+            Some("the workload isn't a read/write mix")
+        } else {
+            None
+        };
+
+        Self {
+            paused: false,
+            iops: config.workers.rate_limit_iops.map_or(0, |limit| limit * workers),
+            bytes_per_sec: config.workers.rate_limit_throughput.map_or(0, |limit| limit * workers),
+            read_percent,
+            mix_blocked,
+        }
+    }
+
+    /// Check that a node command can be applied to this test
+    pub fn check(&self, msg: &ControlMessage) -> Result<(), String> {
+        match (msg, self.mix_blocked) {
+            (ControlMessage::ReadPercent(_), Some(reason)) => Err(reason.to_string()),
+            (ControlMessage::ReadPercent(0 | 100), None) => {
+                Err("the read percentage must stay between 1 and 99".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Record a command that was sent to the nodes
+    pub fn record(&mut self, msg: &ControlMessage) {
+        match *msg {
+            ControlMessage::Pause => self.paused = true,
+            ControlMessage::Resume => self.paused = false,
+            ControlMessage::Rate { iops, bytes_per_sec } => {
+                self.iops = iops;
+                self.bytes_per_sec = bytes_per_sec;
+            }
+            ControlMessage::ReadPercent(percent) => self.read_percent = percent,
+        }
+    }
+
+    /// One-line description of the current settings
+    pub fn status(&self) -> String {
+        let mut limits = Vec::new();
+        if self.iops > 0 {
+            limits.push(format!("{} IOPS", self.iops));
+        }
+        if self.bytes_per_sec > 0 {
+            limits.push(crate::util::time::format_throughput(self.bytes_per_sec as f64));
+        }
+        let rate = if limits.is_empty() { "unlimited".to_string() } else { limits.join(", ") };
+
+        format!("{}, rate {}, {}% reads",
+                if self.paused { "paused" } else { "running" },
+                rate,
+                self.read_percent)
+    }
+}
+
+/// Split a whole-test command into the share of node `node` of `nodes`
+///
+/// The first nodes take the remainder, so the shares add up to the total.
+/// A node is given at least 1, since 0 would lift its limit.
+pub fn per_node(msg: &ControlMessage, nodes: usize, node: usize) -> ControlMessage {
+    match *msg {
+        ControlMessage::Rate { iops, bytes_per_sec } => {
+            let share = |total: u64| if total == 0 { 0 } else { crate::worker::control::share(total, nodes, node).max(1) };
+            ControlMessage::Rate { iops: share(iops), bytes_per_sec: share(bytes_per_sec) }
+        }
+        other => other,
+    }
+}
+
+/// Unix socket accepting control commands, one per line
+///
+/// The socket file is removed when this is dropped.
+pub struct ControlSocket {
+    path: PathBuf,
+}
+
+impl ControlSocket {
+    /// Listen on `path`, forwarding commands to `handle`
+    ///
+    /// Must be called from within a tokio runtime.
+    pub fn bind(path: &Path, handle: ControlHandle) -> Result<Self> {
+        if path.exists() {
+            // A socket left by a crashed run; refuse anything else
+            let metadata = std::fs::metadata(path)?;
+            if !std::os::unix::fs::FileTypeExt::is_socket(&metadata.file_type()) {
+                anyhow::bail!("Control socket path {} exists and is not a socket", path.display());
+            }
+            std::fs::remove_file(path)
+                .with_context(|| format!("Failed to remove stale control socket {}", path.display()))?;
+        }
+        let listener = UnixListener::bind(path)
+            .with_context(|| format!("Failed to bind control socket {}", path.display()))?;
+
+        tokio::spawn(async move {
+            loop {
+                let stream = match listener.accept().await {
+                    Ok((stream, _)) => stream,
+                    Err(e) => {
+                        tracing::warn!("Failed to accept control connection: {}", e);
+                        continue;
+                    }
+                };
+                tokio::spawn(serve_connection(stream, handle.clone()));
+            }
+        });
+
+        Ok(Self { path: path.to_path_buf() })
+    }
+}
+
+impl Drop for ControlSocket {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Answer each command line of one control connection
+async fn serve_connection(stream: tokio::net::UnixStream, handle: ControlHandle) {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+
+    while let Ok(Some(line)) = lines.next_line().await {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let reply = handle.send(&line).await;
+        if writer.write_all(format!("{}\n", reply).as_bytes()).await.is_err() {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commands() {
+        let parse = |s: &str| s.parse::<ControlCommand>().unwrap();
+        assert_eq!(parse("pause"), ControlCommand::Node(ControlMessage::Pause));
+        assert_eq!(parse(" RESUME "), ControlCommand::Node(ControlMessage::Resume));
+        assert_eq!(parse("rate 5000"), ControlCommand::Node(ControlMessage::Rate { iops: 5000, bytes_per_sec: 0 }));
+        assert_eq!(parse("rate 5000 1M/s"),
+                   ControlCommand::Node(ControlMessage::Rate { iops: 5000, bytes_per_sec: 1024 * 1024 }));
+        assert_eq!(parse("rate off"), ControlCommand::Node(ControlMessage::Rate { iops: 0, bytes_per_sec: 0 }));
+        assert_eq!(parse("mix 70%"), ControlCommand::Node(ControlMessage::ReadPercent(70)));
+        assert_eq!(parse("checkpoint"), ControlCommand::Checkpoint);
+
+        assert!("rate".parse::<ControlCommand>().is_err());
+        assert!("rate 0".parse::<ControlCommand>().is_err());
+        assert!("mix 101".parse::<ControlCommand>().is_err());
+        assert!("stop".parse::<ControlCommand>().is_err());
+    }
+
+    #[test]
+    fn test_rate_split_across_nodes() {
+        let msg = ControlMessage::Rate { iops: 1001, bytes_per_sec: 0 };
+        assert_eq!(per_node(&msg, 2, 0), ControlMessage::Rate { iops: 501, bytes_per_sec: 0 });
+        assert_eq!(per_node(&msg, 2, 1), ControlMessage::Rate { iops: 500, bytes_per_sec: 0 });
+        assert_eq!(per_node(&ControlMessage::Rate { iops: 1, bytes_per_sec: 0 }, 2, 1),
+                   ControlMessage::Rate { iops: 1, bytes_per_sec: 0 });
+        assert_eq!(per_node(&ControlMessage::Pause, 2, 1), ControlMessage::Pause);
+    }
+}
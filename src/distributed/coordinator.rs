@@ -6,17 +6,152 @@
 //! - Distributes configuration
 //! - Coordinates synchronized start
 //! - Collects heartbeats
+//! - Applies control commands (pause, rate limit, read/write mix) to running nodes
 //! - Aggregates results
+//! - Sequences the writer and reader phases of two-phase verification
+//! - Runs the thread scaling sweep of `--threads auto`
 
 use crate::distributed::protocol::*;
+use crate::distributed::control::{self, ControlCommand, ControlInbox, ControlState};
+use crate::distributed::verify::{self, NodeRole, VerifyAssignment};
+use crate::distributed::roles;
+use crate::distributed::jobs::JobSetMember;
+use crate::distributed::session::{NodeSession, SessionTimeouts};
+use crate::distributed::thread_scaling::{ThreadScaling, ThreadScalingResult};
+use crate::distributed::latency_target::LatencyTarget;
+use crate::distributed::visibility::{self, VisibilityAction, VisibilityRecorder, VisibilityReport, VisibilityStep};
 use crate::config::Config;
 use crate::stats::WorkerStats;
+use crate::output::alert::AlertMonitor;
+use crate::output::checkpoint::CheckpointTracker;
+use crate::output::live::LiveMonitor;
+use crate::output::stream::TimeSeriesStream;
+use crate::stats::budget::{self, SeriesRetention};
+use crate::stats::skew::StartStopSkew;
+use crate::target::prep_limit::PrepLimit;
+use crate::util::lock_file::LockFile;
+use crate::util::preflight::PreflightReport;
 use anyhow::{Context, Result};
-use std::sync::Arc;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tokio::net::TcpStream;
 use tokio::time::sleep;
 
+/// Print a progress line unless quiet output was requested
+macro_rules! progress {
+    ($quiet:expr) => {
+        if !$quiet {
+            println!();
+        }
+    };
+    ($quiet:expr, $($arg:tt)*) => {
+        if !$quiet {
+            println!($($arg)*);
+        }
+    };
+}
+
+/// Latest cumulative counters reported by one node
+#[derive(Debug, Clone, Default)]
+struct LiveCounters {
+    elapsed: Duration,
+    read_ops: u64,
+    write_ops: u64,
+    read_bytes: u64,
+    write_bytes: u64,
+    errors: u64,
+}
+
+/// Live aggregate statistics of a running job
+#[derive(Debug, Clone, Serialize)]
+pub struct LiveStatus {
+    pub elapsed_secs: f64,
+    pub nodes_reporting: usize,
+    pub nodes_total: usize,
+    pub read_ops: u64,
+    pub write_ops: u64,
+    pub read_bytes: u64,
+    pub write_bytes: u64,
+    pub errors: u64,
+    pub iops: f64,
+    pub throughput_bps: f64,
+}
+
+/// Shared view of a job's progress for observers (e.g., the HTTP API)
+///
+/// The coordinator updates it from node heartbeats and stores the aggregate
+/// JSON results when the job completes.
+#[derive(Debug, Default)]
+pub struct JobProgress {
+    nodes: Mutex<Vec<Option<LiveCounters>>>,
+    result: Mutex<Option<serde_json::Value>>,
+}
+
+impl JobProgress {
+    /// Create an empty progress view
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    fn reset(&self, num_nodes: usize) {
+        *self.nodes.lock().unwrap() = vec![None; num_nodes];
+    }
+    
+    fn update_node(&self, node_idx: usize, stats: &WorkerStatsSnapshot, elapsed: Duration) {
+        if let Some(slot) = self.nodes.lock().unwrap().get_mut(node_idx) {
+            *slot = Some(LiveCounters {
+                elapsed,
+                read_ops: stats.read_ops,
+                write_ops: stats.write_ops,
+                read_bytes: stats.read_bytes,
+                write_bytes: stats.write_bytes,
+                errors: stats.errors,
+            });
+        }
+    }
+    
+    fn set_result(&self, result: serde_json::Value) {
+        *self.result.lock().unwrap() = Some(result);
+    }
+    
+    /// Aggregate of the latest heartbeat from each node (None before the first heartbeat)
+    pub fn live(&self) -> Option<LiveStatus> {
+        let nodes = self.nodes.lock().unwrap();
+        let reporting: Vec<&LiveCounters> = nodes.iter().flatten().collect();
+        if reporting.is_empty() {
+            return None;
+        }
+        
+        let elapsed = reporting.iter().map(|c| c.elapsed).max().unwrap_or_default();
+        let read_ops: u64 = reporting.iter().map(|c| c.read_ops).sum();
+        let write_ops: u64 = reporting.iter().map(|c| c.write_ops).sum();
+        let read_bytes: u64 = reporting.iter().map(|c| c.read_bytes).sum();
+        let write_bytes: u64 = reporting.iter().map(|c| c.write_bytes).sum();
+        let secs = elapsed.as_secs_f64();
+        let rate = |v: u64| if secs > 0.0 { v as f64 / secs } else { 0.0 };
+        
+        Some(LiveStatus {
+            elapsed_secs: secs,
+            nodes_reporting: reporting.len(),
+            nodes_total: nodes.len(),
+            read_ops,
+            write_ops,
+            read_bytes,
+            write_bytes,
+            errors: reporting.iter().map(|c| c.errors).sum(),
+            iops: rate(read_ops + write_ops),
+            throughput_bps: rate(read_bytes + write_bytes),
+        })
+    }
+    
+    /// Final aggregate results (same schema as the aggregate JSON output)
+    pub fn result(&self) -> Option<serde_json::Value> {
+        self.result.lock().unwrap().clone()
+    }
+}
+
 /// Distributed coordinator
 ///
 /// Orchestrates distributed testing across multiple nodes.
@@ -26,6 +161,36 @@ pub struct DistributedCoordinator {
     
     /// List of node addresses (IP:port)
     node_addresses: Vec<String>,
+    
+    /// Job identifier sent to nodes and reported in results
+    job_id: String,
+    
+    /// Progress view updated during the run (HTTP API)
+    progress: Option<Arc<JobProgress>>,
+    
+    /// Role of every node in this job (one phase of two-phase verification)
+    verify_assignment: Option<VerifyAssignment>,
+    
+    /// Role of each node (same order as `node_addresses`; empty without roles)
+    node_roles: Vec<Option<String>>,
+    
+    /// Workload of each role that has a workload fragment
+    role_workloads: BTreeMap<String, crate::config::WorkloadConfig>,
+    
+    /// Thread scaling sweep that chose this run's thread count (reported in the results)
+    thread_scaling: Option<ThreadScalingResult>,
+    
+    /// This run is a step of the thread scaling sweep (no summary printed)
+    scaling_step: bool,
+    
+    /// The datasets are already locked by the run this one is part of
+    dataset_locked: bool,
+    
+    /// Commands from the control socket or API (pause, rate, mix, checkpoint)
+    control: Option<ControlInbox>,
+    
+    /// Place in a job set (starts with the other jobs; the set reports the results)
+    job_set: Option<JobSetMember>,
 }
 
 impl DistributedCoordinator {
@@ -35,39 +200,218 @@ impl DistributedCoordinator {
             anyhow::bail!("No nodes specified for distributed mode");
         }
         
+        // One run UUID for every phase, step and node of the run
+        let mut config = config;
+        if config.output.run_uuid.is_none() {
+            Arc::make_mut(&mut config).output.run_uuid = Some(crate::util::trace_id::new_run_uuid());
+        }
+        
         Ok(Self {
             config,
             node_addresses,
+            job_id: generate_job_id(),
+            progress: None,
+            verify_assignment: None,
+            node_roles: Vec::new(),
+            role_workloads: BTreeMap::new(),
+            thread_scaling: None,
+            scaling_step: false,
+            dataset_locked: false,
+            control: None,
+            job_set: None,
         })
     }
     
+    /// Publish live stats and final results to a shared progress view
+    pub fn set_progress(&mut self, progress: Arc<JobProgress>) {
+        self.progress = Some(progress);
+    }
+    
+    /// Accept control commands while the test runs
+    pub fn set_control(&mut self, control: ControlInbox) {
+        self.control = Some(control);
+    }
+    
+    /// Run as a job of a job set
+    pub fn set_job_set(&mut self, member: JobSetMember) {
+        self.job_set = Some(member);
+    }
+    
+    /// Use a caller-chosen job ID instead of the generated one
+    pub fn set_job_id(&mut self, job_id: String) {
+        self.job_id = job_id;
+    }
+    
+    /// Job identifier for this run
+    pub fn job_id(&self) -> &str {
+        &self.job_id
+    }
+    
+    /// Assign a role to each node (in node address order) and apply per-role workload fragments
+    pub fn set_roles(&mut self, roles: Vec<Option<String>>, fragments: &BTreeMap<String, toml::Table>) -> Result<()> {
+        if roles.len() != self.node_addresses.len() {
+            anyhow::bail!("{} node roles for {} nodes", roles.len(), self.node_addresses.len());
+        }
+        let workloads = roles::resolve_workloads(&self.config.workload, &roles, fragments)?;
+        
+        // A role that writes must pass the same conflict and --readonly checks as the base workload
+        for (role, workload) in &workloads {
+            let mut config = (*self.config).clone();
+            config.workload = workload.clone();
+            crate::config::validator::validate_read_only(&config)
+                .with_context(|| format!("Invalid workload for role '{}'", role))?;
+            if !self.config.runtime.allow_write_conflicts {
+                crate::config::validator::validate_write_conflicts(&config)
+                    .with_context(|| format!("Invalid workload for role '{}'", role))?;
+            }
+        }
+        
+        self.node_roles = roles;
+        self.role_workloads = workloads;
+        Ok(())
+    }
+    
+    /// Role of a node, if it has one
+    fn node_role(&self, node_id: usize) -> Option<&str> {
+        self.node_roles.get(node_id).and_then(|role| role.as_deref())
+    }
+    
+    /// Config sent to a node: the role's workload replaces the global one
+    fn node_config(&self, node_id: usize) -> Config {
+        let mut config = (*self.config).clone();
+        if let Some(workload) = self.node_role(node_id).and_then(|r| self.role_workloads.get(r)) {
+            config.workload = workload.clone();
+        }
+        config
+    }
+    
+    /// Refuse to run nodes on an older protocol version that can't carry the config
+    ///
+    /// Options added since the node's version are left out of its CONFIG, so
+    /// it would silently run a different workload than the one asked for.
+    fn check_node_versions(&self, node_versions: &[u32]) -> Result<()> {
+        for (node_id, &version) in node_versions.iter().enumerate() {
+            if version >= PROTOCOL_VERSION {
+                continue;
+            }
+            let addr = &self.node_addresses[node_id];
+            if self.config.runtime.read_only {
+                // The node would open (or create) the targets read-write
+                anyhow::bail!("--readonly needs protocol v3 on every node, but node {} ({}) speaks v{}",
+                    node_id, addr, version);
+            }
+            let mut lost = lost_config_options(&self.node_config(node_id), version)?;
+            if self.verify_assignment.is_some() {
+                lost.push("two-phase verification".to_string());
+            }
+            if !lost.is_empty() {
+                anyhow::bail!("Node {} ({}) speaks protocol v{}, which cannot carry: {}\n\
+                     Upgrade the node or leave these options out",
+                    node_id, addr, version, lost.join(", "));
+            }
+        }
+        Ok(())
+    }
+    
+    /// Whether any node reads (files must then be filled before the test)
+    fn any_reads(&self) -> bool {
+        self.config.workload.read_percent > 0
+            || self.role_workloads.values().any(|w| w.read_percent > 0)
+    }
+    
+    /// Whether any node writes (datasets are then locked exclusively)
+    fn any_writes(&self) -> bool {
+        self.config.workload.write_percent > 0
+            || self.role_workloads.values().any(|w| w.write_percent > 0)
+    }
+    
+    /// Lock the file and directory targets against concurrent runs
+    ///
+    /// Only done when every node runs on this host: there the targets are
+    /// local paths that other runs (with their own node services) may share.
+    /// Across a cluster, each node service already runs one job at a time.
+    fn lock_datasets(&self) -> Result<Vec<LockFile>> {
+        let local = self.node_addresses.iter().all(|addr| is_local_address(addr));
+        if !local {
+            return Ok(Vec::new());
+        }
+        
+        let hostname = hostname::get().ok()
+            .and_then(|h| h.into_string().ok())
+            .unwrap_or_else(|| "localhost".to_string());
+        let holder = format!("job {}, pid {} on {}", self.job_id, std::process::id(), hostname);
+        let mut locks = Vec::new();
+        for target in &self.config.targets {
+            let is_dir = match target.target_type {
+                crate::config::TargetType::File => false,
+                crate::config::TargetType::Directory => true,
+                crate::config::TargetType::BlockDevice => continue,
+            };
+            locks.extend(crate::target::dataset_marker::lock_dataset(&target.path, is_dir, self.any_writes(), &holder)?);
+        }
+        Ok(locks)
+    }
+    
     /// Run the distributed test
     pub async fn run(self) -> Result<()> {
-        println!("Distributed Coordinator");
-        println!();
+        // Before anything is created (phases and steps are checked by the outer run)
+        if self.config.runtime.preflight && !self.dataset_locked {
+            self.preflight().await?;
+        }
+        
+        // Held until the test (and every phase or step of it) is over
+        // (a read-only run must not create the lock files either)
+        let _dataset_locks = if self.dataset_locked || self.config.runtime.read_only {
+            Vec::new()
+        } else {
+            self.lock_datasets()?
+        };
+        
+        if self.config.runtime.verify_groups.is_some() {
+            return Box::pin(self.run_verify_phases()).await;
+        }
+        if self.config.workers.thread_scaling.is_some() {
+            return Box::pin(self.run_thread_scaling()).await;
+        }
+        if self.config.workers.visibility.is_some() {
+            return Box::pin(self.run_visibility()).await;
+        }
+        
+        let quiet = self.config.output.is_quiet();
+        
+        // Setup/teardown phase timings (reported in the results)
+        let mut setup_timings = crate::stats::setup::SetupTimings::new();
+        let mut phase_start = std::time::Instant::now();
+        
+        progress!(quiet, "Distributed Coordinator");
+        progress!(quiet, "Job ID: {}", self.job_id);
+        if let Some(ref run_uuid) = self.config.output.run_uuid {
+            progress!(quiet, "Run UUID: {}", run_uuid);
+        }
+        progress!(quiet);
         
         // Load layout_manifest if specified OR generate layout
         let file_list: Option<Vec<std::path::PathBuf>> = if !self.config.targets.is_empty() {
             let target = &self.config.targets[0];
             
             if let Some(ref manifest_path) = target.layout_manifest {
-                println!("Loading layout manifest: {}", manifest_path.display());
+                progress!(quiet, "Loading layout manifest: {}", manifest_path.display());
                 
                 // Warn if conflicting parameters provided
                 if target.layout_config.is_some() {
-                    println!("⚠️  Warning: layout_manifest provided, ignoring --dir-depth, --dir-width, --total-files");
+                    progress!(quiet, "⚠️  Warning: layout_manifest provided, ignoring --dir-depth, --dir-width, --total-files");
                 }
                 
                 let manifest = crate::target::LayoutManifest::from_file(manifest_path)
                     .context("Failed to load layout manifest")?;
                 
-                println!("Layout manifest loaded: {} files", manifest.file_count());
+                progress!(quiet, "Layout manifest loaded: {} files", manifest.file_count());
                 
                 // Export if requested
                 if let Some(ref export_path) = target.export_layout_manifest {
                     manifest.to_file(export_path)
                         .context("Failed to export layout manifest")?;
-                    println!("Layout manifest exported to: {}", export_path.display());
+                    progress!(quiet, "Layout manifest exported to: {}", export_path.display());
                 }
                 
                 // Convert to absolute paths
@@ -88,12 +432,12 @@ impl DistributedCoordinator {
                 };
                 
                 // Generate layout from config
-                println!("Generating directory layout...");
+                progress!(quiet, "Generating directory layout...");
                 if let Some(nw) = num_workers {
-                    println!("  Depth: {}, Width: {}, Files per dir: {} (per-worker mode: {} workers)", 
+                    progress!(quiet, "  Depth: {}, Width: {}, Files per dir: {} (per-worker mode: {} workers)", 
                         layout_config.depth, layout_config.width, layout_config.files_per_dir, nw);
                 } else {
-                    println!("  Depth: {}, Width: {}, Files per dir: {}", 
+                    progress!(quiet, "  Depth: {}, Width: {}, Files per dir: {}", 
                         layout_config.depth, layout_config.width, layout_config.files_per_dir);
                 }
                 
@@ -119,10 +463,10 @@ impl DistributedCoordinator {
                 let file_count = generator.file_count();
                 if let Some(nw) = num_workers {
                     let base_files = file_count / nw;
-                    println!("Generated {} files ({} base × {} workers) in {} directories", 
+                    progress!(quiet, "Generated {} files ({} base × {} workers) in {} directories", 
                         file_count, base_files, nw, generator.stats().mkdir_count);
                 } else {
-                    println!("Generated {} files in {} directories", 
+                    progress!(quiet, "Generated {} files in {} directories", 
                         file_count, generator.stats().mkdir_count);
                 }
                 
@@ -131,6 +475,7 @@ impl DistributedCoordinator {
                     // Create manifest from generated files
                     let file_size = target.file_size.unwrap_or(0);
                     let manifest = crate::target::layout_manifest::LayoutManifest::from_paths_and_size(
+                        &target.path,
                         generator.file_paths().to_vec(),
                         file_size,
                         crate::target::layout_manifest::ManifestHeader {
@@ -143,11 +488,11 @@ impl DistributedCoordinator {
                             file_size: target.file_size.unwrap_or(0),
                             num_workers,
                         },
-                    );
+                    )?;
                     
                     manifest.to_file(export_path)
                         .context("Failed to export layout manifest")?;
-                    println!("Layout manifest exported to: {} ({} files)", 
+                    progress!(quiet, "Layout manifest exported to: {} ({} files)", 
                         export_path.display(), file_count);
                 }
                 
@@ -159,14 +504,24 @@ impl DistributedCoordinator {
             None
         };
         
+        if file_list.is_some() {
+            let phase = if self.config.targets[0].layout_manifest.is_some() {
+                "Layout manifest load"
+            } else {
+                "Layout generation"
+            };
+            setup_timings.record(phase, phase_start.elapsed());
+        }
+        
         // Validate and fill layout files if needed
         if let Some(ref file_list) = file_list {
             let target = &self.config.targets[0];
-            let has_reads = self.config.workload.read_percent > 0;
+            let has_reads = self.any_reads();
             let needs_fill_for_mmap = self.config.workload.engine == crate::config::workload::EngineType::Mmap;
+            let no_refill = target.no_refill || self.config.runtime.read_only;
             
             // Check if auto-fill is disabled
-            if target.no_refill && (has_reads || needs_fill_for_mmap) {
+            if no_refill && (has_reads || needs_fill_for_mmap) {
                 // Check if any files are empty/sparse
                 let has_empty_files = file_list.iter().any(|path| {
                     if let Ok(metadata) = std::fs::metadata(path) {
@@ -176,6 +531,9 @@ impl DistributedCoordinator {
                     }
                 });
                 
+                if has_empty_files && self.config.runtime.read_only {
+                    anyhow::bail!("Layout contains missing or empty files, which --readonly can't fill");
+                }
                 if has_empty_files {
                     anyhow::bail!(
                         "Layout contains empty files but auto-fill is disabled (--no-refill flag).\n\
@@ -184,81 +542,123 @@ impl DistributedCoordinator {
                 }
             }
             
-            if !target.no_refill && (has_reads || needs_fill_for_mmap) {
-                println!("Validating {} files...", file_list.len());
+            if !no_refill && (has_reads || needs_fill_for_mmap) {
+                progress!(quiet, "Validating {} files...", file_list.len());
                 
                 let start = std::time::Instant::now();
+                let file_size = target.file_size.unwrap_or(0);
+                let fill_regions = crate::distribution::reach::Reach::of(&self.config.workload)
+                    .filter(|_| !target.full_fill)
+                    .map(|reach| reach.regions(0, file_size));
                 let filled_count = validate_and_fill_files(
                     file_list,
-                    target.file_size.unwrap_or(0),
+                    file_size,
                     self.config.workload.write_pattern,
+                    self.config.workload.custom_pattern.as_ref(),
+                    fill_regions.as_deref(),
+                    self.config.runtime.prep_rate_limit.map(PrepLimit::new),
+                    quiet,
                 )?;
                 let elapsed = start.elapsed();
+                setup_timings.record("File validation and refill", elapsed);
                 
                 if filled_count > 0 {
-                    println!("✅ Filled {} sparse files in {:.2}s", filled_count, elapsed.as_secs_f64());
+                    progress!(quiet, "✅ Filled {} sparse files in {:.2}s", filled_count, elapsed.as_secs_f64());
                 } else {
-                    println!("✅ All files validated ({:.2}s)", elapsed.as_secs_f64());
+                    progress!(quiet, "✅ All files validated ({:.2}s)", elapsed.as_secs_f64());
                 }
             }
         }
         
         // Create parent directories for targets (before connecting to nodes)
-        println!("Preparing target directories...");
-        for target in &self.config.targets {
+        progress!(quiet, "Preparing target directories...");
+        for target in self.config.targets.iter().filter(|_| !self.config.runtime.read_only) {
             if let Some(parent) = target.path.parent() {
                 if !parent.exists() {
-                    std::fs::create_dir_all(parent)
-                        .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
-                    println!("  Created directory: {}", parent.display());
+                    crate::util::preflight::create_dirs(parent)?;
+                    progress!(quiet, "  Created directory: {}", parent.display());
                 }
             }
         }
         
-        println!();
-        println!("Connecting to {} nodes...", self.node_addresses.len());
+        phase_start = std::time::Instant::now();
+        
+        progress!(quiet);
+        progress!(quiet, "Connecting to {} nodes...", self.node_addresses.len());
         
-        // Connect to all nodes
+        // Connect to all nodes and negotiate a protocol version with each
         let mut connections = Vec::new();
+        let mut node_versions = Vec::new();
         for (i, addr) in self.node_addresses.iter().enumerate() {
-            println!("  Connecting to node {} ({})...", i, addr);
-            let stream = TcpStream::connect(addr).await
-                .with_context(|| format!("Failed to connect to {}", addr))?;
-            println!("  ✅ Connected to node {} ({})", i, addr);
+            progress!(quiet, "  Connecting to node {} ({})...", i, addr);
+            let (mut stream, version) = connect_and_negotiate(addr).await?;
+            
+            // Fail early if the node is running another job and can't queue ours
+            if version >= 3 {
+                let status = query_status(&mut stream, addr).await?;
+                if let Some(test) = status.current_test {
+                    if status.queued_jobs >= status.max_queued_jobs {
+                        anyhow::bail!("Node {} ({}) is busy: running job {} for coordinator {} (started {:.0}s ago)",
+                            i, addr, test.job_id, test.coordinator, test.elapsed_ns as f64 / 1e9);
+                    }
+                    progress!(quiet, "  Node {} is running job {}; this job will wait in its queue ({} ahead)",
+                        i, test.job_id, status.queued_jobs + 1);
+                }
+            }
+            progress!(quiet, "  ✅ Connected to node {} ({}, protocol v{})", i, addr, version);
             connections.push((i, addr.clone(), stream));
+            node_versions.push(version);
         }
         
-        println!();
-        println!("All {} nodes connected!", connections.len());
+        self.check_node_versions(&node_versions)?;
+        
+        progress!(quiet);
+        progress!(quiet, "All {} nodes connected!", connections.len());
+        if let Some(ref job_progress) = self.progress {
+            job_progress.reset(connections.len());
+        }
+        setup_timings.record("Node connections", phase_start.elapsed());
+        phase_start = std::time::Instant::now();
         
         // Prepare files if needed (create/fill before test)
         // Skip if we already have a file_list (layout was generated/loaded)
         let num_nodes = connections.len();
         
         if file_list.is_none() {
-            println!();
+            progress!(quiet);
             
-            let has_reads = self.config.workload.read_percent > 0;
-            let needs_preallocation = self.config.workload.direct;
+            let has_reads = self.any_reads();
+            // Block devices and target regions are used as they are
+            let needs_preallocation = self.config.workload.direct
+                && !self.config.runtime.read_only
+                && self.config.targets.iter().all(|t| t.target_type != crate::config::TargetType::BlockDevice && t.region.is_none());
             let is_shared = self.config.targets.iter()
                 .all(|t| t.distribution == crate::config::workload::FileDistribution::Shared);
             
             // Smart auto-partitioning for distributed pre-allocation
             // Only for single files
             if needs_preallocation && num_nodes > 1 && is_shared {
-                println!("Smart Partitioning: Distributing pre-allocation across {} nodes", num_nodes);
-                println!("  Reason: O_DIRECT requires pre-allocation, parallel is faster");
-                println!();
+                progress!(quiet, "Smart Partitioning: Distributing pre-allocation across {} nodes", num_nodes);
+                progress!(quiet, "  Reason: O_DIRECT requires pre-allocation, parallel is faster");
+                progress!(quiet);
                 
                 // Use distributed pre-allocation
-                self.distributed_preallocate(&mut connections, has_reads).await?;
+                self.distributed_preallocate(&mut connections, &node_versions, has_reads).await?;
             } else {
                 // Coordinator handles file preparation
-                println!("Preparing files...");
+                progress!(quiet, "Preparing files...");
                 
-                for target in &self.config.targets {
-                    if !target.path.exists() || (has_reads && is_file_sparse(&target.path)?) {
-                        println!("  Creating/filling: {}", target.path.display());
+                for (target_index, target) in self.config.targets.iter().enumerate() {
+                    if target.target_type == crate::config::TargetType::BlockDevice {
+                        progress!(quiet, "  ✅ Block device: {}", target.path.display());
+                    } else if self.config.runtime.read_only {
+                        // Used as it is: never created or filled
+                        if !target.path.exists() {
+                            anyhow::bail!("Read-only target does not exist: {}", target.path.display());
+                        }
+                        progress!(quiet, "  ✅ File exists (read-only): {}", target.path.display());
+                    } else if !target.path.exists() || (has_reads && is_file_sparse(&target.path)?) {
+                        progress!(quiet, "  Creating/filling: {}", target.path.display());
                     
                     use crate::target::file::FileTarget;
                     use crate::target::Target;
@@ -268,10 +668,16 @@ impl DistributedCoordinator {
                         target.path.clone(),
                         target.file_size,
                     );
+                    file_target.set_quiet(quiet);
+                    if let Some(regions) = crate::distribution::reach::fill_regions(&self.config, target_index, num_nodes) {
+                        file_target.set_fill_regions(regions);
+                    }
+                    file_target.set_prep_limit(self.config.runtime.prep_rate_limit.map(PrepLimit::new));
                     
                     // For O_DIRECT, we need to preallocate
-                    if self.config.workload.direct {
+                    if self.config.workload.direct || target.preallocate {
                         file_target.set_preallocate(true);
+                        file_target.set_prealloc_mode(target.prealloc_mode);
                     }
                     
                     let flags = OpenFlags {
@@ -279,6 +685,10 @@ impl DistributedCoordinator {
                         sync: false,
                         create: true,
                         truncate: false,
+                        noatime: false,
+                        dsync: false,
+                        rsync: false,
+                        read_only: false,
                     };
                     
                     file_target.open(flags)?;
@@ -303,30 +713,50 @@ impl DistributedCoordinator {
                     let needs_fill = has_reads || self.config.workload.engine == crate::config::workload::EngineType::Mmap;
                     
                     if needs_fill {
+                        if let Some(ref custom) = self.config.workload.custom_pattern {
+                            file_target.set_custom_payload(custom.payload.as_slice().into());
+                        }
                         file_target.refill(self.config.workload.write_pattern)?;
-                        println!("  ✅ File filled");
+                        progress!(quiet, "  ✅ File filled");
                     } else {
-                        println!("  ✅ File created");
+                        progress!(quiet, "  ✅ File created");
                     }
                     
                     file_target.close()?;
                 } else {
-                    println!("  ✅ File exists: {}", target.path.display());
+                    progress!(quiet, "  ✅ File exists: {}", target.path.display());
                 }
             }
             }  // End of if file_list.is_none()
+            setup_timings.record("Pre-allocation and refill", phase_start.elapsed());
+            phase_start = std::time::Instant::now();
         }
         
         // Calculate total workers
         let threads_per_node = self.config.workers.threads;
         let total_workers = connections.len() * threads_per_node;
-        println!();
-        println!("Total workers: {} ({} nodes × {} threads)", 
+        progress!(quiet);
+        progress!(quiet, "Total workers: {} ({} nodes × {} threads)", 
             total_workers, connections.len(), threads_per_node);
         
         // Send CONFIG messages to all nodes
-        println!();
-        println!("Sending configuration to all nodes...");
+        progress!(quiet);
+        progress!(quiet, "Sending configuration to all nodes...");
+        
+        // PARTITIONED single file: one region per node, which the node splits across its workers
+        let node_offset_ranges = match self.config.targets.first() {
+            Some(target) if file_list.is_none()
+                && target.distribution == crate::config::workload::FileDistribution::Partitioned =>
+            {
+                target.region.or(target.file_size.map(|size| (0, size))).map(|range| {
+                    crate::distributed::partition::split_range(range, connections.len(), self.config.workload.block_size)
+                })
+            }
+            _ => None,
+        };
+        
+        // Keep-alive and reconnect of test connections (--reconnect-timeout 0 disables them)
+        let session_timeouts = SessionTimeouts::from_config(&self.config.runtime);
         
         for (node_id, addr, stream) in &mut connections {
             let worker_id_start = *node_id * threads_per_node;
@@ -356,38 +786,67 @@ impl DistributedCoordinator {
                 (None, None)
             };
             
+            let version = node_versions[*node_id];
+            let role = self.node_role(*node_id).map(str::to_string);
+            let config = self.node_config(*node_id);
             let config_msg = ConfigMessage {
-                protocol_version: PROTOCOL_VERSION,
+                protocol_version: version,
                 node_id: addr.clone(),
-                config: (*self.config).clone(),
+                config,
                 worker_id_start,
                 worker_id_end,
                 file_list: node_file_list,
                 file_range: node_file_range,
                 skip_preallocation: true, // Coordinator already pre-allocated
+                job_id: Some(self.job_id.clone()),
+                total_workers: Some(total_workers),
+                verify: self.verify_assignment,
+                role: role.clone(),
+                node_offset_range: node_offset_ranges.as_ref().and_then(|ranges| ranges.get(*node_id).copied()),
+                keepalive: session_timeouts.is_some(),
+                node_uid: self.config.output.run_uuid.as_deref()
+                    .map(|run_uuid| crate::util::trace_id::node_uid(run_uuid, *node_id)),
             };
             
-            write_message(stream, &Message::Config(config_msg)).await
+            write_message_for_version(stream, &Message::Config(config_msg), version).await
                 .with_context(|| format!("Failed to send CONFIG to node {}", node_id))?;
             
-            println!("  ✅ Sent CONFIG to node {} (workers {}-{})", node_id, worker_id_start, worker_id_end - 1);
+            match role {
+                Some(role) => progress!(quiet, "  ✅ Sent CONFIG to node {} (workers {}-{}, role {})",
+                    node_id, worker_id_start, worker_id_end - 1, role),
+                None => progress!(quiet, "  ✅ Sent CONFIG to node {} (workers {}-{})", node_id, worker_id_start, worker_id_end - 1),
+            }
         }
         
+        setup_timings.record("Configuration distribution", phase_start.elapsed());
+        phase_start = std::time::Instant::now();
+        
         // Wait for READY messages from all nodes
-        println!();
-        println!("Waiting for all nodes to be ready...");
+        progress!(quiet);
+        progress!(quiet, "Waiting for all nodes to be ready...");
         
-        for (node_id, _addr, stream) in &mut connections {
+        let mut sessions = Vec::with_capacity(connections.len());
+        let mut node_capabilities = vec![None; self.node_addresses.len()];
+        for (node_id, addr, stream) in &mut connections {
             let msg = read_message(stream).await
                 .with_context(|| format!("Failed to read READY from node {}", node_id))?;
             
             match msg {
                 Message::Ready(ready) => {
-                    if ready.protocol_version != PROTOCOL_VERSION {
+                    // Nodes without session resumption fail the run on a lost connection, as before
+                    let timeouts = session_timeouts.filter(|_| ready.resumable);
+                    sessions.push(NodeSession::new(*node_id, addr.clone(), self.job_id.clone(), timeouts));
+                    if ready.protocol_version != node_versions[*node_id] {
                         anyhow::bail!("Protocol version mismatch on node {}: expected {}, got {}", 
-                            node_id, PROTOCOL_VERSION, ready.protocol_version);
+                            node_id, node_versions[*node_id], ready.protocol_version);
+                    }
+                    match ready.job_id {
+                        Some(ref job_id) if *job_id != self.job_id => {
+                            progress!(quiet, "  ✅ Node {} ready ({} workers, node job {})", node_id, ready.num_workers, job_id);
+                        }
+                        _ => progress!(quiet, "  ✅ Node {} ready ({} workers)", node_id, ready.num_workers),
                     }
-                    println!("  ✅ Node {} ready ({} workers)", node_id, ready.num_workers);
+                    node_capabilities[*node_id] = ready.capabilities;
                 }
                 Message::Error(err) => {
                     anyhow::bail!("Node {} reported error: {}", node_id, err.error);
@@ -398,10 +857,26 @@ impl DistributedCoordinator {
             }
         }
         
+        setup_timings.record("Node readiness", phase_start.elapsed());
+        
+        if !quiet {
+            progress!(quiet);
+            let rows: Vec<(String, Option<&crate::util::capabilities::NodeCapabilities>)> = connections.iter()
+                .map(|(node_id, addr, _)| (addr.clone(), node_capabilities[*node_id].as_ref()))
+                .collect();
+            crate::output::text::print_node_capabilities(&rows);
+        }
+        
         // Calculate start timestamp (now + 100ms)
-        println!();
-        println!("All nodes ready!");
-        println!("Synchronized start in 100ms...");
+        progress!(quiet);
+        progress!(quiet, "All nodes ready!");
+        progress!(quiet, "Synchronized start in 100ms...");
+        
+        // Jobs of a job set start together
+        if let Some(ref member) = self.job_set {
+            progress!(quiet, "Waiting for the other jobs of the set...");
+            member.ready_to_start().await;
+        }
         
         let start_delay = Duration::from_millis(100);
         let start_timestamp_ns = (std::time::SystemTime::now()
@@ -419,16 +894,58 @@ impl DistributedCoordinator {
                 .with_context(|| format!("Failed to send START to node {}", node_id))?;
         }
         
-        println!("Sent START to all nodes");
+        progress!(quiet, "Sent START to all nodes");
         
         // DON'T sleep here - start collecting heartbeats immediately to avoid race condition
-        println!();
-        println!("Test running...");
+        progress!(quiet);
+        progress!(quiet, "Test running...");
         
         // Collect heartbeats for time-series data (needed for CSV/JSON time-series)
         let csv_enabled = self.config.output.csv_output.is_some();
         let json_enabled = self.config.output.json_output.is_some();
-        let collect_time_series = csv_enabled || json_enabled;
+        let collect_time_series = csv_enabled || json_enabled
+            || self.config.output.summary_interval.is_some()
+            || self.config.output.alert.is_some()
+            || self.control.is_some()
+            || self.config.workers.latency_target.is_some()
+            || self.config.output.plot.is_some()
+            || self.config.output.live_metadata;
+        
+        // Threshold alerts on each node's heartbeat intervals (--alert-*)
+        let mut alerts = self.config.output.alert.clone()
+            .map(|config| AlertMonitor::new(config, self.job_id.clone(), self.config.output.run_uuid.clone()));
+        
+        // Live lines with metadata rates and open fd counts (--live-metadata)
+        let mut live_monitor = if self.config.output.live_metadata {
+            let csv_path = self.config.output.csv_output.as_deref().map(LiveMonitor::csv_path);
+            let print = !quiet && !self.config.output.no_live;
+            match LiveMonitor::new(self.config.output.live_interval_duration(), connections.len(), print, csv_path.as_deref()) {
+                Ok(monitor) => Some(monitor),
+                Err(e) => {
+                    eprintln!("Warning: Live metadata output disabled: {:#}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        
+        // Summary checkpoints of the interval since the previous one (--summary-interval)
+        // (the control `checkpoint` command also closes them on demand)
+        let mut checkpoints = match self.config.output.summary_interval {
+            Some(secs) => Some(CheckpointTracker::new(Duration::from_secs(secs), connections.len())),
+            None if self.control.is_some() => Some(CheckpointTracker::new(Duration::MAX, connections.len())),
+            None => None,
+        };
+        
+        // Settings changed by control commands (--control-socket, API)
+        let mut control_state = ControlState::new(&self.config, connections.len(), !self.role_workloads.is_empty());
+        
+        // IOPS limit steered toward a p99 target, one step per interval (--target-p99)
+        let mut latency_target = self.config.workers.latency_target.clone().map(|config| {
+            let steps = CheckpointTracker::new(Duration::from_secs(config.step_seconds), connections.len());
+            (LatencyTarget::new(config), steps)
+        });
         
         let mut time_series_snapshots: Vec<Vec<crate::output::json::AggregatedSnapshot>> = 
             vec![Vec::new(); connections.len()];
@@ -443,35 +960,68 @@ impl DistributedCoordinator {
         
         // Per-worker time-series collection (when --per-worker-output is enabled)
         let collect_per_worker = self.config.output.per_worker_output;
+        let interval_latency = self.config.output.interval_latency;
         let mut per_worker_time_series: Vec<Vec<Vec<crate::output::json::AggregatedSnapshot>>> = 
             vec![Vec::new(); connections.len()];  // node → timestamp → workers
         let mut previous_per_worker_cumulative: Vec<Option<Vec<crate::output::json::AggregatedSnapshot>>> = 
             vec![None; connections.len()];  // node → workers
         
-        if let crate::config::workload::CompletionMode::Duration { seconds } = self.config.workload.completion_mode {
-            let test_duration = Duration::from_secs(seconds);
+        // Retained time-series stay under their share of --stats-mem-limit
+        let series_entry_bytes = {
+            let snapshot = std::mem::size_of::<crate::output::json::AggregatedSnapshot>();
+            let workers = if collect_per_worker { self.config.workers.threads } else { 0 };
+            snapshot + std::mem::size_of::<crate::util::resource::ResourceStats>()
+                + workers * (snapshot + std::mem::size_of::<crate::worker::StatsSnapshot>())
+        };
+        let mut retention = SeriesRetention::new(
+            self.config.runtime.stats_mem_limit.map(budget::time_series_limit),
+            series_entry_bytes as u64,
+            connections.len(),
+        );
+        
+        // Nodes that finished on their own (byte-count and run-until-complete
+        // modes, or every worker reached --stop-at-op)
+        let mut early_results: Vec<Option<ResultsMessage>> = vec![None; connections.len()];
+        
+        // Interval records are also streamed to disk so a crashed run keeps its time-series
+        let mut time_series_stream: Option<TimeSeriesStream> = None;
+        
+        if let Some(test_duration) = self.config.workload.completion_mode.test_duration() {
             let start_time = std::time::Instant::now();
+            let live_interval = self.config.output.live_interval_duration();
             
             // Actively collect heartbeats if time-series is needed
             if collect_time_series {
-                println!("Collecting time-series data from heartbeats...");
+                progress!(quiet, "Collecting time-series data from heartbeats...");
+                
+                match TimeSeriesStream::open(&self.config) {
+                    Ok(stream) => time_series_stream = Some(stream),
+                    Err(e) => eprintln!("Warning: Time-series will only be written at the end: {:#}", e),
+                }
                 
                 loop {
                     let elapsed = start_time.elapsed();
-                    if elapsed >= test_duration {
+                    if elapsed >= test_duration || early_results.iter().all(Option::is_some)
+                        || self.job_set.as_ref().is_some_and(JobSetMember::foreground_stopped) {
                         break;
                     }
                     
                     // Try to read from all nodes
-                    // Heartbeats arrive every 1 second, so use 1-second timeout
-                    for (node_idx, (_node_id, _addr, stream)) in connections.iter_mut().enumerate() {
-                        // Use 1-second timeout (heartbeats are sent every 1 second)
-                        match tokio::time::timeout(Duration::from_secs(1), read_message(stream)).await {
-                            Ok(Ok(Message::Heartbeat(hb))) => {
+                    // Heartbeats arrive every live interval, so wait up to one interval
+                    for (node_idx, (node_id, addr, stream)) in connections.iter_mut().enumerate() {
+                        if early_results[node_idx].is_some() {
+                            continue;
+                        }
+                        match sessions[node_idx].read(stream, live_interval).await {
+                            Ok(Some(Message::Heartbeat(hb))) => {
                                 // Skip first heartbeat (startup artifact, not steady-state)
                                 let elapsed = Duration::from_nanos(hb.elapsed_ns);
-                                if elapsed.as_millis() < 500 {
-                                    continue;  // Skip heartbeats in first 500ms
+                                if elapsed < live_interval / 2 {
+                                    continue;  // Sent at the start, before any interval elapsed
+                                }
+                                
+                                if let Some(ref job_progress) = self.progress {
+                                    job_progress.update_node(node_idx, &hb.stats, elapsed);
                                 }
                                 
                                 // Convert WorkerStatsSnapshot to AggregatedSnapshot (cumulative values)
@@ -479,50 +1029,27 @@ impl DistributedCoordinator {
                                 
                                 // Calculate delta from previous cumulative snapshot
                                 let delta_snapshot = if let Some(ref prev) = previous_cumulative[node_idx] {
-                                    // Calculate deltas
-                                    crate::output::json::AggregatedSnapshot {
-                                        timestamp: cumulative.timestamp,
-                                        elapsed: cumulative.elapsed,
-                                        read_ops: cumulative.read_ops.saturating_sub(prev.read_ops),
-                                        write_ops: cumulative.write_ops.saturating_sub(prev.write_ops),
-                                        read_bytes: cumulative.read_bytes.saturating_sub(prev.read_bytes),
-                                        write_bytes: cumulative.write_bytes.saturating_sub(prev.write_bytes),
-                                        errors: cumulative.errors,
-                                        avg_latency_us: cumulative.avg_latency_us,
-                                        read_latency: cumulative.read_latency.clone(),
-                                        write_latency: cumulative.write_latency.clone(),
-                                        metadata_open_ops: cumulative.metadata_open_ops.saturating_sub(prev.metadata_open_ops),
-                                        metadata_close_ops: cumulative.metadata_close_ops.saturating_sub(prev.metadata_close_ops),
-                                        metadata_stat_ops: cumulative.metadata_stat_ops.saturating_sub(prev.metadata_stat_ops),
-                                        metadata_setattr_ops: cumulative.metadata_setattr_ops.saturating_sub(prev.metadata_setattr_ops),
-                                        metadata_mkdir_ops: cumulative.metadata_mkdir_ops.saturating_sub(prev.metadata_mkdir_ops),
-                                        metadata_rmdir_ops: cumulative.metadata_rmdir_ops.saturating_sub(prev.metadata_rmdir_ops),
-                                        metadata_unlink_ops: cumulative.metadata_unlink_ops.saturating_sub(prev.metadata_unlink_ops),
-                                        metadata_rename_ops: cumulative.metadata_rename_ops.saturating_sub(prev.metadata_rename_ops),
-                                        metadata_readdir_ops: cumulative.metadata_readdir_ops.saturating_sub(prev.metadata_readdir_ops),
-                                        metadata_fsync_ops: cumulative.metadata_fsync_ops.saturating_sub(prev.metadata_fsync_ops),
-                                        metadata_open_latency: cumulative.metadata_open_latency.clone(),
-                                        metadata_close_latency: cumulative.metadata_close_latency.clone(),
-                                        metadata_stat_latency: cumulative.metadata_stat_latency.clone(),
-                                        metadata_setattr_latency: cumulative.metadata_setattr_latency.clone(),
-                                        metadata_mkdir_latency: cumulative.metadata_mkdir_latency.clone(),
-                                        metadata_rmdir_latency: cumulative.metadata_rmdir_latency.clone(),
-                                        metadata_unlink_latency: cumulative.metadata_unlink_latency.clone(),
-                                        metadata_rename_latency: cumulative.metadata_rename_latency.clone(),
-                                        metadata_readdir_latency: cumulative.metadata_readdir_latency.clone(),
-                                        metadata_fsync_latency: cumulative.metadata_fsync_latency.clone(),
-                                        per_worker: None,
-                                    }
+                                    cumulative.since(prev, interval_latency)
                                 } else {
                                     // First snapshot - use cumulative as-is
                                     cumulative.clone()
                                 };
                                 
+                                if let Some(monitor) = live_monitor.as_mut() {
+                                    monitor.record(node_idx, &cumulative, hb.stats.open_fds);
+                                }
+                                
+                                if let (Some(monitor), Some(prev)) = (alerts.as_mut(), previous_cumulative[node_idx].as_ref()) {
+                                    let ip_addr = addr.split(':').next().unwrap_or(addr);
+                                    monitor.check(ip_addr, &cumulative, prev);
+                                }
+                                
                                 // Store cumulative for next delta calculation
                                 previous_cumulative[node_idx] = Some(cumulative);
                                 
                                 // Process per-worker snapshots if enabled
                                 let mut delta_snapshot = delta_snapshot;  // Make mutable
+                                let joins_last = retention.joins_last(node_idx, time_series_snapshots[node_idx].len());
                                 if collect_per_worker {
                                     if let Some(ref per_worker_snapshots) = hb.per_worker_stats {
                                         // Convert each worker snapshot to AggregatedSnapshot (cumulative)
@@ -534,41 +1061,7 @@ impl DistributedCoordinator {
                                         let delta_workers = if let Some(ref prev_workers) = previous_per_worker_cumulative[node_idx] {
                                             // Calculate delta for each worker
                                             cumulative_workers.iter().zip(prev_workers.iter())
-                                                .map(|(curr, prev)| {
-                                                    crate::output::json::AggregatedSnapshot {
-                                                        timestamp: curr.timestamp,
-                                                        elapsed: curr.elapsed,
-                                                        read_ops: curr.read_ops.saturating_sub(prev.read_ops),
-                                                        write_ops: curr.write_ops.saturating_sub(prev.write_ops),
-                                                        read_bytes: curr.read_bytes.saturating_sub(prev.read_bytes),
-                                                        write_bytes: curr.write_bytes.saturating_sub(prev.write_bytes),
-                                                        errors: curr.errors,
-                                                        avg_latency_us: curr.avg_latency_us,
-                                                        read_latency: curr.read_latency.clone(),
-                                                        write_latency: curr.write_latency.clone(),
-                                                        metadata_open_ops: curr.metadata_open_ops.saturating_sub(prev.metadata_open_ops),
-                                                        metadata_close_ops: curr.metadata_close_ops.saturating_sub(prev.metadata_close_ops),
-                                                        metadata_stat_ops: curr.metadata_stat_ops.saturating_sub(prev.metadata_stat_ops),
-                                                        metadata_setattr_ops: curr.metadata_setattr_ops.saturating_sub(prev.metadata_setattr_ops),
-                                                        metadata_mkdir_ops: curr.metadata_mkdir_ops.saturating_sub(prev.metadata_mkdir_ops),
-                                                        metadata_rmdir_ops: curr.metadata_rmdir_ops.saturating_sub(prev.metadata_rmdir_ops),
-                                                        metadata_unlink_ops: curr.metadata_unlink_ops.saturating_sub(prev.metadata_unlink_ops),
-                                                        metadata_rename_ops: curr.metadata_rename_ops.saturating_sub(prev.metadata_rename_ops),
-                                                        metadata_readdir_ops: curr.metadata_readdir_ops.saturating_sub(prev.metadata_readdir_ops),
-                                                        metadata_fsync_ops: curr.metadata_fsync_ops.saturating_sub(prev.metadata_fsync_ops),
-                                                        metadata_open_latency: curr.metadata_open_latency.clone(),
-                                                        metadata_close_latency: curr.metadata_close_latency.clone(),
-                                                        metadata_stat_latency: curr.metadata_stat_latency.clone(),
-                                                        metadata_setattr_latency: curr.metadata_setattr_latency.clone(),
-                                                        metadata_mkdir_latency: curr.metadata_mkdir_latency.clone(),
-                                                        metadata_rmdir_latency: curr.metadata_rmdir_latency.clone(),
-                                                        metadata_unlink_latency: curr.metadata_unlink_latency.clone(),
-                                                        metadata_rename_latency: curr.metadata_rename_latency.clone(),
-                                                        metadata_readdir_latency: curr.metadata_readdir_latency.clone(),
-                                                        metadata_fsync_latency: curr.metadata_fsync_latency.clone(),
-                                                        per_worker: None,
-                                                    }
-                                                })
+                                                .map(|(curr, prev)| curr.since(prev, interval_latency))
                                                 .collect()
                                         } else {
                                             // First heartbeat - use cumulative as-is
@@ -576,7 +1069,14 @@ impl DistributedCoordinator {
                                         };
                                         
                                         // Store deltas for this timestamp (for JSON per-worker time-series)
-                                        per_worker_time_series[node_idx].push(delta_workers.clone());
+                                        match per_worker_time_series[node_idx].last_mut() {
+                                            Some(last) if joins_last => {
+                                                for (last, delta) in last.iter_mut().zip(delta_workers.iter().cloned()) {
+                                                    last.absorb(delta, interval_latency);
+                                                }
+                                            }
+                                            _ => per_worker_time_series[node_idx].push(delta_workers.clone()),
+                                        }
                                         
                                         // Convert to StatsSnapshot format for CSV per-worker output
                                         let delta_stats_snapshots: Vec<crate::worker::StatsSnapshot> = delta_workers.iter()
@@ -589,8 +1089,9 @@ impl DistributedCoordinator {
                                     }
                                 }
                                 
-                                // Store delta snapshot for time-series
-                                time_series_snapshots[node_idx].push(delta_snapshot);
+                                let interval = delta_snapshot.elapsed - time_series_snapshots[node_idx].last()
+                                    .map(|s| s.elapsed)
+                                    .unwrap_or_default();
                                 
                                 // Store current resource stats for this snapshot (from service heartbeat)
                                 let heartbeat_resource_stats = crate::util::resource::ResourceStats {
@@ -599,52 +1100,162 @@ impl DistributedCoordinator {
                                     peak_memory_bytes: hb.stats.peak_memory_bytes,
                                 };
                                 
-                                if self.config.runtime.debug {
-                                    eprintln!("DEBUG: Heartbeat resource stats: CPU={:.1}%, Memory={} MB", 
-                                        heartbeat_resource_stats.cpu_percent, 
-                                        heartbeat_resource_stats.memory_bytes / 1_048_576);
+                                tracing::debug!("Heartbeat resource stats: CPU={:.1}%, Memory={} MB",
+                                    heartbeat_resource_stats.cpu_percent,
+                                    heartbeat_resource_stats.memory_bytes / 1_048_576);
+                                
+                                // Append to disk right away (same node_id as the CSV writer below)
+                                if let Some(stream) = time_series_stream.as_mut() {
+                                    let ip_addr = addr.split(':').next().unwrap_or(addr);
+                                    let ip_addr = if ip_addr == "localhost" { "127.0.0.1" } else { ip_addr };
+                                    if let Err(e) = stream.append(ip_addr, &delta_snapshot, interval, Some(&heartbeat_resource_stats)) {
+                                        eprintln!("Warning: Failed to stream time-series, continuing in memory: {:#}", e);
+                                        time_series_stream = None;
+                                    }
+                                }
+                                
+                                // Store delta snapshot for time-series (merged into the last
+                                // interval once --stats-mem-limit has coarsened the series)
+                                match (time_series_snapshots[node_idx].last_mut(), time_series_resource_stats[node_idx].last_mut()) {
+                                    (Some(last), Some(last_resources)) if joins_last => {
+                                        last.absorb(delta_snapshot, interval_latency);
+                                        if last.per_worker.is_some() {
+                                            last.per_worker = per_worker_time_series[node_idx].last()
+                                                .map(|workers| workers.iter().map(|w| w.to_stats_snapshot()).collect());
+                                        }
+                                        *last_resources = heartbeat_resource_stats;
+                                    }
+                                    _ => {
+                                        time_series_snapshots[node_idx].push(delta_snapshot);
+                                        time_series_resource_stats[node_idx].push(heartbeat_resource_stats);
+                                    }
                                 }
                                 
-                                time_series_resource_stats[node_idx].push(heartbeat_resource_stats);
+                                let retained: usize = time_series_snapshots.iter().map(Vec::len).sum();
+                                if retention.record(node_idx, joins_last, retained) {
+                                    coarsen_time_series(&mut retention, &mut time_series_snapshots,
+                                                        &mut time_series_resource_stats, &mut per_worker_time_series,
+                                                        interval_latency);
+                                    retention.warn();
+                                }
                             }
-                            Ok(Ok(_)) => {
+                            Ok(Some(Message::Error(err))) => {
+                                self.handle_node_error(*node_id, &err)?;
+                            }
+                            Ok(Some(Message::Results(results))) => {
+                                // Workers stopped early (--stop-at-op)
+                                early_results[node_idx] = Some(results);
+                            }
+                            Ok(Some(_)) => {
                                 // Other message - ignore (shouldn't happen during test)
                             }
-                            Ok(Err(e)) => {
+                            Err(e) => {
                                 // Error reading from node
                                 eprintln!("Warning: Error reading from node {}: {}", node_idx, e);
                             }
-                            Err(_) => {
+                            Ok(None) => {
                                 // Timeout - no heartbeat received in 1 second
                                 // This is normal if test is ending or node is slow
                             }
                         }
                     }
+                    
+                    if let Some(monitor) = live_monitor.as_mut() {
+                        if let Err(e) = monitor.tick() {
+                            eprintln!("Warning: Failed to write live metadata, stopping it: {:#}", e);
+                            live_monitor = None;
+                        }
+                    }
+                    
+                    if let Some(ref mut tracker) = checkpoints {
+                        let elapsed = start_time.elapsed();
+                        if tracker.is_due(elapsed) {
+                            let checkpoint = tracker.close(elapsed, &previous_cumulative);
+                            if !quiet {
+                                crate::output::text::print_checkpoint(checkpoint, self.config.output.report_percentiles());
+                            }
+                        }
+                    }
+                    
+                    if let Some((ref mut target, ref mut steps)) = latency_target {
+                        let elapsed = start_time.elapsed();
+                        if steps.is_due(elapsed) {
+                            let step = steps.close(elapsed, &previous_cumulative);
+                            let mut latency = step.read_latency.clone();
+                            latency.merge(&step.write_latency);
+                            // Paused steps say nothing about the rate
+                            if !latency.is_empty() {
+                                let iops = (step.read_ops + step.write_ops) as f64 / step.duration().as_secs_f64().max(f64::MIN_POSITIVE);
+                                let p99 = latency.percentile(99.0);
+                                let rate = target.record(iops, p99.as_micros() as u64);
+                                progress!(quiet, "Latency target: {} IOPS at p99 {} -> {}",
+                                    crate::util::time::format_rate(iops),
+                                    crate::util::time::format_duration(p99),
+                                    match target.rate() {
+                                        Some(limit) => format!("limit {} IOPS", limit),
+                                        None => "unlimited".to_string(),
+                                    });
+                                if let Some(rate) = rate {
+                                    let msg = ControlMessage::Rate { iops: rate, bytes_per_sec: 0 };
+                                    self.send_control(&msg, &mut connections, &mut sessions, &node_versions).await;
+                                    control_state.record(&msg);
+                                }
+                            }
+                        }
+                    }
+                    
+                    let elapsed = start_time.elapsed();
+                    self.poll_control(&mut control_state, &mut connections, &mut sessions, &node_versions, || {
+                        let tracker = checkpoints.as_mut().ok_or("no checkpoint tracker")?;
+                        let checkpoint = tracker.close(elapsed, &previous_cumulative);
+                        if !quiet {
+                            crate::output::text::print_checkpoint(checkpoint, self.config.output.report_percentiles());
+                        }
+                        Ok(format!("checkpoint {} ({} - {}): {} reads, {} writes",
+                                   checkpoint.number,
+                                   crate::util::time::format_duration(checkpoint.start),
+                                   crate::util::time::format_duration(checkpoint.end),
+                                   checkpoint.read_ops,
+                                   checkpoint.write_ops))
+                    }).await;
                 }
                 
                 let total_snapshots: usize = time_series_snapshots.iter().map(|s| s.len()).sum();
                 let max_per_node = time_series_snapshots.iter().map(|s| s.len()).max().unwrap_or(0);
-                println!("Collected {} total snapshots ({} max per node)", total_snapshots, max_per_node);
+                progress!(quiet, "Collected {} total snapshots ({} max per node)", total_snapshots, max_per_node);
             } else {
                 // No time-series needed - but still need to drain heartbeats to avoid protocol errors
-                println!("Waiting for test to complete (draining heartbeats)...");
+                progress!(quiet, "Waiting for test to complete (draining heartbeats)...");
                 
                 loop {
                     let elapsed = start_time.elapsed();
-                    if elapsed >= test_duration {
+                    if elapsed >= test_duration || early_results.iter().all(Option::is_some)
+                        || self.job_set.as_ref().is_some_and(JobSetMember::foreground_stopped) {
                         break;
                     }
                     
-                    // Drain heartbeats from all nodes (don't store them)
-                    for (_node_idx, (_node_id, _addr, stream)) in connections.iter_mut().enumerate() {
-                        match tokio::time::timeout(Duration::from_millis(100), read_message(stream)).await {
-                            Ok(Ok(Message::Heartbeat(_))) => {
-                                // Discard heartbeat
+                    // Drain heartbeats from all nodes (only the live progress view keeps them)
+                    for (node_idx, (node_id, _addr, stream)) in connections.iter_mut().enumerate() {
+                        if early_results[node_idx].is_some() {
+                            continue;
+                        }
+                        match sessions[node_idx].read(stream, Duration::from_millis(100)).await {
+                            Ok(Some(Message::Heartbeat(hb))) => {
+                                if let Some(ref job_progress) = self.progress {
+                                    job_progress.update_node(node_idx, &hb.stats, Duration::from_nanos(hb.elapsed_ns));
+                                }
+                            }
+                            Ok(Some(Message::Error(err))) => {
+                                self.handle_node_error(*node_id, &err)?;
+                            }
+                            Ok(Some(Message::Results(results))) => {
+                                // Workers stopped early (--stop-at-op)
+                                early_results[node_idx] = Some(results);
                             }
-                            Ok(Ok(_)) => {
+                            Ok(Some(_)) => {
                                 // Other message - ignore
                             }
-                            Ok(Err(_)) | Err(_) => {
+                            Err(_) | Ok(None) => {
                                 // Error or timeout - ignore
                             }
                         }
@@ -655,48 +1266,109 @@ impl DistributedCoordinator {
                 }
             }
         } else {
-            // For other modes, wait a reasonable time
-            sleep(Duration::from_secs(10)).await;
+            // Other modes end when the workers reach their completion condition;
+            // each node then sends RESULTS without waiting for STOP
+            progress!(quiet, "Waiting for workers to complete...");
+            let stonewall = self.config.workers.stonewall;
+            
+            'wait: while early_results.iter().any(Option::is_none) {
+                for (node_idx, (node_id, _addr, stream)) in connections.iter_mut().enumerate() {
+                    if early_results[node_idx].is_some() {
+                        continue;
+                    }
+                    match sessions[node_idx].read(stream, Duration::from_millis(100)).await {
+                        Ok(Some(Message::Heartbeat(hb))) => {
+                            if let Some(ref job_progress) = self.progress {
+                                job_progress.update_node(node_idx, &hb.stats, Duration::from_nanos(hb.elapsed_ns));
+                            }
+                        }
+                        Ok(Some(Message::Results(results))) => {
+                            progress!(quiet, "  Node {} completed", node_id);
+                            early_results[node_idx] = Some(results);
+                            // Stonewall: the first node to finish stops the rest
+                            if stonewall {
+                                break 'wait;
+                            }
+                        }
+                        Ok(Some(Message::Error(err))) => {
+                            self.handle_node_error(*node_id, &err)?;
+                        }
+                        Ok(Some(_)) => {
+                            // Other message - ignore
+                        }
+                        Err(e) => {
+                            return Err(e).with_context(|| format!("Failed to read from node {}", node_id));
+                        }
+                        Ok(None) => {
+                            // Timeout - node still running
+                        }
+                    }
+                }
+                
+                self.poll_control(&mut control_state, &mut connections, &mut sessions, &node_versions,
+                                  || Err("checkpoints are only taken in duration runs".to_string())).await;
+            }
         }
         
         // Send STOP messages to all nodes
-        println!();
-        println!("Stopping test...");
+        let teardown_start = std::time::Instant::now();
+        if let Some(member) = &self.job_set {
+            member.stopping();
+        }
+        progress!(quiet);
+        progress!(quiet, "Stopping test...");
         
-        for (node_id, _addr, stream) in &mut connections {
-            write_message(stream, &Message::Stop).await
+        for (node_idx, (node_id, _addr, stream)) in connections.iter_mut().enumerate() {
+            if early_results[node_idx].is_some() {
+                continue;
+            }
+            let session = &mut sessions[node_idx];
+            session.stop_expecting_heartbeats();
+            session.send(stream, &Message::Stop).await
                 .with_context(|| format!("Failed to send STOP to node {}", node_id))?;
         }
         
-        println!("Sent STOP to all nodes");
+        progress!(quiet, "Sent STOP to all nodes");
         
         // Give nodes time to complete in-flight operations
         sleep(Duration::from_millis(500)).await;
         
         // Collect RESULTS from all nodes
-        println!();
-        println!("Collecting results from all nodes...");
+        progress!(quiet);
+        progress!(quiet, "Collecting results from all nodes...");
         
         let mut all_results = Vec::new();
-        for (node_id, addr, stream) in &mut connections {
+        for (node_idx, (node_id, addr, stream)) in connections.iter_mut().enumerate() {
+            if let Some(results) = early_results[node_idx].take() {
+                progress!(quiet, "  ✅ Received results from node {} ({} workers)", 
+                    node_id, results.per_worker_stats.len());
+                all_results.push((*node_id, addr.clone(), results));
+                continue;
+            }
+            
             // Read messages until we get RESULTS (skip any late HEARTBEATs)
             loop {
-                let msg = read_message(stream).await
-                    .with_context(|| format!("Failed to read from node {}", node_id))?;
+                // Waits out a reconnect if the connection drops while the node finishes
+                let msg = match sessions[node_idx].read(stream, Duration::from_secs(3600)).await
+                    .with_context(|| format!("Failed to read from node {}", node_id))? {
+                    Some(msg) => msg,
+                    None => continue,
+                };
                 
                 match msg {
                     Message::Results(results) => {
-                        println!("  ✅ Received results from node {} ({} workers)", 
+                        progress!(quiet, "  ✅ Received results from node {} ({} workers)", 
                             node_id, results.per_worker_stats.len());
                         all_results.push((*node_id, addr.clone(), results));
                         break;
                     }
-                    Message::Heartbeat(_) => {
+                    Message::Heartbeat(_) | Message::Resumed(_) => {
                         // Skip late heartbeats
                         continue;
                     }
                     Message::Error(err) => {
-                        anyhow::bail!("Node {} reported error: {}", node_id, err.error);
+                        self.handle_node_error(*node_id, &err)?;
+                        continue;
                     }
                     other => {
                         anyhow::bail!("Expected RESULTS from node {}, got {:?}", node_id, other);
@@ -705,8 +1377,18 @@ impl DistributedCoordinator {
             }
         }
         
+        let teardown_elapsed = teardown_start.elapsed();
+        
+        for session in &sessions {
+            let (reconnects, downtime) = session.reconnects();
+            if reconnects > 0 {
+                progress!(quiet, "  Node {} reconnected {} time(s), {:.1}s without a connection",
+                    session.node_idx(), reconnects, downtime.as_secs_f64());
+            }
+        }
+        
         // Aggregate results
-        println!();
+        progress!(quiet);
         
         // Merge all node statistics into a single WorkerStats for display
         let enable_heatmap = self.config.workload.heatmap;
@@ -716,7 +1398,13 @@ impl DistributedCoordinator {
         let mut merged_stats = crate::stats::WorkerStats::with_heatmap(track_locks, enable_heatmap);
         let mut max_duration_ns = 0u64;
         
-        for (node_id, _addr, results) in &all_results {
+        // Keep each node's stats for the per-node breakdown: (node IP, hostname, stats, duration)
+        let mut all_node_stats: Vec<(String, String, WorkerStats, Duration)> = Vec::with_capacity(all_results.len());
+        
+        // Nodes merged by role: role → (node IPs, stats, longest duration)
+        let mut role_stats: BTreeMap<String, (Vec<String>, WorkerStats, Duration)> = BTreeMap::new();
+        
+        for (node_id, addr, results) in &all_results {
             // Convert snapshot back to WorkerStats
             let node_stats = results.aggregate_stats.to_worker_stats(enable_heatmap, track_locks)
                 .with_context(|| format!("Failed to deserialize stats from node {}", node_id))?;
@@ -724,19 +1412,149 @@ impl DistributedCoordinator {
             // Merge into aggregate
             merged_stats.merge(&node_stats)?;
             
-            // Track max duration
-            max_duration_ns = max_duration_ns.max(results.duration_ns);
+            // Wallclock is the longest worker's IO time (workers begin IO before the
+            // node's START timestamp, so the node's own elapsed time can be shorter)
+            let duration_ns = node_stats.test_duration()
+                .map(|d| d.as_nanos() as u64)
+                .unwrap_or(results.duration_ns);
+            max_duration_ns = max_duration_ns.max(duration_ns);
+            
+            let ip_addr = addr.split(':').next().unwrap_or(addr).to_string();
+            if let Some(role) = self.node_role(*node_id) {
+                let entry = role_stats.entry(role.to_string()).or_insert_with(|| {
+                    (Vec::new(), WorkerStats::with_heatmap(track_locks, enable_heatmap), Duration::ZERO)
+                });
+                entry.0.push(ip_addr.clone());
+                entry.1.merge(&node_stats)?;
+                entry.2 = entry.2.max(Duration::from_nanos(duration_ns));
+            }
+            all_node_stats.push((ip_addr, results.node_id.clone(), node_stats, Duration::from_nanos(duration_ns)));
         }
         
+        // Node index of each per-node and per-worker entry (for their unit IDs)
+        let result_nodes: Vec<usize> = all_results.iter().map(|(node_id, _, _)| *node_id).collect();
+        let worker_nodes: Vec<usize> = all_results.iter()
+            .flat_map(|(node_id, _, results)| std::iter::repeat_n(*node_id, results.per_worker_stats.len()))
+            .collect();
+        
+        let role_refs: Vec<(String, Vec<String>, &WorkerStats, Duration)> = role_stats.iter()
+            .map(|(role, (nodes, stats, duration))| (role.clone(), nodes.clone(), stats, *duration))
+            .collect();
+        let per_role_json: Vec<crate::output::json::JsonRoleStatsFinal> = role_refs.iter()
+            .map(|(role, nodes, stats, duration)| {
+                let workload = self.role_workloads.get(role).unwrap_or(&self.config.workload);
+                crate::output::json::role_stats_to_json_final(
+                    role.clone(), nodes.clone(), stats, *duration, workload, nodes.len() * threads_per_node)
+            })
+            .collect();
+        
+        let checkpoints_json: Vec<crate::output::json::JsonCheckpoint> = checkpoints.iter()
+            .flat_map(|tracker| tracker.checkpoints())
+            .map(|checkpoint| crate::output::json::checkpoint_to_json(checkpoint, self.config.output.report_percentiles()))
+            .collect();
+        
+        let latency_target_result = latency_target.as_ref().map(|(target, _)| target.result());
+        
+        // How raggedly the workers started and stopped, across all nodes
+        let skew = StartStopSkew::from_windows(all_results.iter().flat_map(|(_node_id, addr, results)| {
+            let ip_addr = addr.split(':').next().unwrap_or(addr);
+            results.per_worker_stats.iter().filter_map(move |worker| worker.io_window.map(|window| (ip_addr, window)))
+        }));
+        let skew_json = skew.as_ref().map(crate::output::json::skew_to_json);
+        
+        let all_node_refs: Vec<(String, String, &WorkerStats, Duration)> = all_node_stats.iter()
+            .map(|(node_id, hostname, stats, duration)| (node_id.clone(), hostname.clone(), stats, *duration))
+            .collect();
+        
         let test_duration = Duration::from_nanos(max_duration_ns);
         
-        // Use standalone's print_results() for consistent output
-        crate::output::text::print_results(&merged_stats, test_duration, &self.config);
+        // Report coordinator phases first, then the slowest node/worker phases
+        // (engine init, target open, auto-refill), then teardown
+        setup_timings.extend(merged_stats.setup_timings());
+        setup_timings.record("Stop and result collection", teardown_elapsed);
+        
+        *merged_stats.setup_timings_mut() = setup_timings;
+        
+        // Scaling steps have no output of their own; job sets print their jobs together
+        if !self.scaling_step && self.job_set.is_none() {
+            if !quiet {
+                println!("Job ID: {}", self.job_id);
+            }
+            crate::output::print_summary(&merged_stats, test_duration, &self.config, self.node_addresses.len());
+            if let (false, Some(ref result)) = (quiet, &latency_target_result) {
+                crate::output::text::print_latency_target(result);
+            }
+        }
+        
+        // Final results for the progress view (aggregate schema, no time-series)
+        if let Some(ref job_progress) = self.progress {
+            let mut output = crate::output::json::build_aggregate_node_output(
+                "aggregate".to_string(),
+                None,
+                std::time::SystemTime::now() - test_duration,
+                std::time::SystemTime::now(),
+                test_duration,
+                &self.config,
+                Vec::new(),
+                Vec::new(),
+                Vec::new(),
+                &merged_stats,
+                &[],
+                &all_node_refs,
+                None,
+            );
+            output.test_info.job_id = Some(self.job_id.clone());
+            output.set_unit_ids(None, &result_nodes, &[]);
+            output.set_capabilities(None, &result_nodes, &node_capabilities);
+            output.final_summary.per_role = per_role_json.clone();
+            output.final_summary.thread_scaling = self.thread_scaling.clone();
+            output.final_summary.checkpoints = checkpoints_json.clone();
+            output.final_summary.start_stop_skew = skew_json.clone();
+            output.final_summary.latency_target = latency_target_result.clone();
+            job_progress.set_result(serde_json::to_value(&output)
+                .context("Failed to serialize results")?);
+        }
+        
+        // Per-node breakdown (multi-node runs, or any run with -v)
+        if !quiet && (all_node_refs.len() > 1 || self.config.output.is_verbose()) {
+            crate::output::text::print_node_table(&all_node_refs);
+            crate::output::text::print_node_space(&all_node_refs);
+            if let Some(ref skew) = skew {
+                crate::output::text::print_start_stop_skew(skew);
+            }
+        }
+        
+        // Per-role breakdown (role-based node groups)
+        if !quiet {
+            crate::output::text::print_role_table(&role_refs);
+        }
+        
+        // Per-worker breakdown (verbose only)
+        if self.config.output.is_verbose() {
+            let worker_stats: Vec<(String, usize, WorkerStats)> = all_results.iter()
+                .flat_map(|(_node_id, addr, results)| {
+                    let ip_addr = addr.split(':').next().unwrap_or(addr).to_string();
+                    results.per_worker_stats.iter().enumerate().map(move |(worker_id, snapshot)| {
+                        let worker_stats = snapshot.to_worker_stats(enable_heatmap, track_locks)
+                            .unwrap_or_else(|_| crate::stats::WorkerStats::new());
+                        (ip_addr.clone(), worker_id, worker_stats)
+                    }).collect::<Vec<_>>()
+                })
+                .collect();
+            let worker_refs: Vec<(String, usize, &WorkerStats)> = worker_stats.iter()
+                .map(|(node, worker_id, stats)| (node.clone(), *worker_id, stats))
+                .collect();
+            crate::output::text::print_worker_table(&worker_refs, test_duration, self.config.output.is_very_verbose());
+        }
+        
+        // Rotated segments written during the test are the time-series output
+        let rotated = time_series_stream.as_ref().is_some_and(TimeSeriesStream::is_rotating);
         
         // Write JSON output if requested
+        let mut json_written = false;
         if let Some(ref json_output_path) = self.config.output.json_output {
-            println!();
-            println!("Writing JSON output...");
+            progress!(quiet);
+            progress!(quiet, "Writing JSON output...");
             
             // Create output directory if it doesn't exist
             if let Some(parent) = json_output_path.parent() {
@@ -820,7 +1638,7 @@ impl DistributedCoordinator {
                     let ip_addr = addr.split(':').next().unwrap_or(addr);
                     let ip_addr = if ip_addr == "localhost" { "127.0.0.1" } else { ip_addr }.to_string();
                     
-                    let node_output = crate::output::json::build_node_output(
+                    let mut node_output = crate::output::json::build_node_output(
                         ip_addr.clone(),  // Use IP only as node_id
                         Some(addr.clone()),  // Keep full address as hostname
                         std::time::SystemTime::now() - test_duration,
@@ -835,11 +1653,18 @@ impl DistributedCoordinator {
                         total_blocks,
                     );
                     
+                    node_output.test_info.job_id = Some(self.job_id.clone());
+                    node_output.set_unit_ids(Some(*node_id), &[], &[]);
+                    node_output.set_capabilities(Some(*node_id), &[], &node_capabilities);
+                    if rotated {
+                        node_output.time_series.clear();
+                    }
+                    
                     // Write node JSON file
                     if let Err(e) = crate::output::json::write_json_output(&node_output_path, &node_output, true) {
                         eprintln!("Warning: Failed to write JSON for node {}: {}", addr, e);
                     } else {
-                        println!("  ✅ Node {} JSON: {}", addr, node_output_path.display());
+                        progress!(quiet, "  ✅ Node {} JSON: {}", addr, node_output_path.display());
                     }
                 }
                 
@@ -915,7 +1740,7 @@ impl DistributedCoordinator {
                         })
                         .collect();
                 
-                let aggregate_output = crate::output::json::build_aggregate_node_output(
+                let mut aggregate_output = crate::output::json::build_aggregate_node_output(
                     "aggregate".to_string(),
                     None,
                     std::time::SystemTime::now() - test_duration,
@@ -927,17 +1752,32 @@ impl DistributedCoordinator {
                     all_per_worker_time_series,  // Per-worker time-series (NEW)
                     &merged_stats,
                     &all_per_worker_refs,  // ALL per-worker stats from ALL nodes
+                    &all_node_refs,  // Per-node final stats
                     total_blocks,
                 );
                 
+                aggregate_output.test_info.job_id = Some(self.job_id.clone());
+                aggregate_output.set_unit_ids(None, &result_nodes, &worker_nodes);
+                aggregate_output.set_capabilities(None, &result_nodes, &node_capabilities);
+                
+                aggregate_output.final_summary.per_role = per_role_json.clone();
+                aggregate_output.final_summary.thread_scaling = self.thread_scaling.clone();
+                aggregate_output.final_summary.checkpoints = checkpoints_json.clone();
+                aggregate_output.final_summary.start_stop_skew = skew_json.clone();
+                aggregate_output.final_summary.latency_target = latency_target_result.clone();
+                if rotated {
+                    aggregate_output.time_series.clear();
+                }
+                
                 if let Err(e) = crate::output::json::write_json_output(&aggregate_path, &aggregate_output, true) {
                     eprintln!("Warning: Failed to write aggregate JSON: {}", e);
                 } else {
-                    println!("  ✅ Aggregate JSON: {}", aggregate_path.display());
+                    progress!(quiet, "  ✅ Aggregate JSON: {}", aggregate_path.display());
+                    json_written = true;
                 }
                 
-                println!();
-                println!("JSON output written to: {}", json_output_path.display());
+                progress!(quiet);
+                progress!(quiet, "JSON output written to: {}", json_output_path.display());
             } else {
                 // Single file output - just write aggregate
                 let _total_blocks = if !self.config.targets.is_empty() {
@@ -1025,7 +1865,7 @@ impl DistributedCoordinator {
                         })
                         .collect();
                 
-                let aggregate_output = crate::output::json::build_aggregate_node_output(
+                let mut aggregate_output = crate::output::json::build_aggregate_node_output(
                     if all_results.len() == 1 {
                         // Single node - use actual node address
                         all_results[0].1.clone()
@@ -1043,14 +1883,29 @@ impl DistributedCoordinator {
                     all_per_worker_time_series,  // Per-worker time-series (NEW)
                     &merged_stats,
                     &all_per_worker_refs,  // ALL per-worker stats from ALL nodes
+                    &all_node_refs,  // Per-node final stats
                     total_blocks,
                 );
                 
+                aggregate_output.test_info.job_id = Some(self.job_id.clone());
+                aggregate_output.set_unit_ids(None, &result_nodes, &worker_nodes);
+                aggregate_output.set_capabilities(None, &result_nodes, &node_capabilities);
+                
+                aggregate_output.final_summary.per_role = per_role_json.clone();
+                aggregate_output.final_summary.thread_scaling = self.thread_scaling.clone();
+                aggregate_output.final_summary.checkpoints = checkpoints_json.clone();
+                aggregate_output.final_summary.start_stop_skew = skew_json.clone();
+                aggregate_output.final_summary.latency_target = latency_target_result.clone();
+                if rotated {
+                    aggregate_output.time_series.clear();
+                }
+                
                 if let Err(e) = crate::output::json::write_json_output(json_output_path, &aggregate_output, true) {
                     eprintln!("Warning: Failed to write JSON output: {}", e);
                 } else {
-                    println!();
-                    println!("JSON output written to: {}", json_output_path.display());
+                    progress!(quiet);
+                    progress!(quiet, "JSON output written to: {}", json_output_path.display());
+                    json_written = true;
                 }
             }
         }
@@ -1058,8 +1913,8 @@ impl DistributedCoordinator {
         // Write histogram output if requested
         if self.config.output.json_histogram {
             if let Some(ref json_output_path) = self.config.output.json_output {
-                println!();
-                println!("Writing histogram output...");
+                progress!(quiet);
+                progress!(quiet, "Writing histogram output...");
                 
                 // Determine histogram path based on JSON output path
                 let histogram_path = if json_output_path.is_dir() || 
@@ -1085,16 +1940,48 @@ impl DistributedCoordinator {
                 if let Err(e) = crate::output::json::write_histogram_output(&histogram_path, &histogram_output, true) {
                     eprintln!("Warning: Failed to write histogram output: {}", e);
                 } else {
-                    println!("  ✅ Histogram exported: {}", histogram_path.display());
+                    progress!(quiet, "  ✅ Histogram exported: {}", histogram_path.display());
                 }
             }
         }
         
-        // Write CSV output if requested
-        if let Some(ref csv_output_path) = self.config.output.csv_output {
+        // Heatmap buckets for the export and the heatmap chart
+        let heatmap_export = if self.config.workload.heatmap
+            && (self.config.output.heatmap_output.is_some() || self.config.output.plot.is_some())
+        {
+            let block_size = self.config.workload.block_size.max(1);
+            let total_blocks = self.config.targets.first()
+                .and_then(|target| target.region.map(|(_, end)| end).or(target.file_size))
+                .map(|size| size / block_size);
+            crate::output::heatmap::HeatmapExport::from_stats(
+                &merged_stats, self.config.workload.heatmap_buckets, total_blocks, block_size)
+        } else {
+            None
+        };
+        
+        // Write heatmap buckets if requested
+        if let (Some(heatmap_path), Some(export)) = (self.config.output.heatmap_output.as_ref(), heatmap_export.as_ref()) {
+            match export.write(heatmap_path) {
+                Err(e) => eprintln!("Warning: Failed to write heatmap output: {:#}", e),
+                Ok(()) => progress!(quiet, "Heatmap written to: {}", heatmap_path.display()),
+            }
+        }
+        
+        // Render charts if requested
+        if let Some(ref plot_dir) = self.config.output.plot {
+            let series = crate::output::plot::series_points(&time_series_snapshots);
+            match crate::output::plot::write_plots(plot_dir, &series, &merged_stats, heatmap_export.as_ref()) {
+                Err(e) => eprintln!("Warning: Failed to write plots: {:#}", e),
+                Ok(files) if files.is_empty() => progress!(quiet, "No data to plot"),
+                Ok(files) => progress!(quiet, "Plots written to: {} ({} charts)", plot_dir.display(), files.len()),
+            }
+        }
+        
+        // Write CSV output if requested (already on disk as segments when rotating)
+        if let Some(csv_output_path) = self.config.output.csv_output.as_ref().filter(|_| !rotated) {
             if !time_series_snapshots.is_empty() && time_series_snapshots.iter().any(|s| !s.is_empty()) {
-                println!();
-                println!("Writing CSV output...");
+                progress!(quiet);
+                progress!(quiet, "Writing CSV output...");
                 
                 // Determine if csv_output_path is a directory or file
                 let is_dir = csv_output_path.is_dir() || 
@@ -1118,7 +2005,7 @@ impl DistributedCoordinator {
                         let csv_path = csv_output_path.join(&csv_filename);
                         
                         // Create CSV writer (per-node file)
-                        let mut csv_writer = crate::output::csv::CsvWriter::new_with_node_id(&csv_path, self.config.output.per_worker_output, false)
+                        let mut csv_writer = crate::output::csv::CsvWriter::new(&csv_path, &crate::output::csv::CsvOptions::from_output(&self.config.output, false))
                             .context("Failed to create CSV writer")?;
                         
                         // Write all snapshots for this node
@@ -1137,12 +2024,12 @@ impl DistributedCoordinator {
                                 .context("Failed to write CSV row")?;
                         }
                         
-                        println!("  ✅ Node {} CSV: {}", addr, csv_path.display());
+                        progress!(quiet, "  ✅ Node {} CSV: {}", addr, csv_path.display());
                     }
                     
                     // Write aggregate CSV (with per-node rows, and per-worker if enabled)
                     let aggregate_csv_path = csv_output_path.join("aggregate.csv");
-                    let mut csv_writer = crate::output::csv::CsvWriter::new_with_node_id(&aggregate_csv_path, self.config.output.per_worker_output, true)
+                    let mut csv_writer = crate::output::csv::CsvWriter::new(&aggregate_csv_path, &crate::output::csv::CsvOptions::from_output(&self.config.output, true))
                         .context("Failed to create aggregate CSV writer")?;
                     
                     // Find max number of snapshots across all nodes
@@ -1181,12 +2068,12 @@ impl DistributedCoordinator {
                         }
                     }
                     
-                    println!("  ✅ Aggregate CSV: {}", aggregate_csv_path.display());
-                    println!();
-                    println!("CSV output written to: {}", csv_output_path.display());
+                    progress!(quiet, "  ✅ Aggregate CSV: {}", aggregate_csv_path.display());
+                    progress!(quiet);
+                    progress!(quiet, "CSV output written to: {}", csv_output_path.display());
                 } else {
                     // Single file output - write per-node rows with node_id column (ALWAYS, even for 1 node)
-                    let mut csv_writer = crate::output::csv::CsvWriter::new_with_node_id(csv_output_path, self.config.output.per_worker_output, true)
+                    let mut csv_writer = crate::output::csv::CsvWriter::new(csv_output_path, &crate::output::csv::CsvOptions::from_output(&self.config.output, true))
                         .context("Failed to create CSV writer")?;
                     
                     // Find max number of snapshots across all nodes
@@ -1224,7 +2111,7 @@ impl DistributedCoordinator {
                         }
                     }
                     
-                    println!("CSV output written to: {}", csv_output_path.display());
+                    progress!(quiet, "CSV output written to: {}", csv_output_path.display());
                 }
             } else {
                 eprintln!("Warning: No time-series data collected (heartbeats may not have been received)");
@@ -1232,6 +2119,375 @@ impl DistributedCoordinator {
             }
         }
         
+        // The streamed sidecar is only kept if writing the complete JSON failed;
+        // rotated segments are closed and indexed
+        if let Some(stream) = time_series_stream {
+            match stream.finish(json_written) {
+                Ok(indexes) => {
+                    for index in indexes {
+                        progress!(quiet, "Time-series segments: {}", index.display());
+                    }
+                }
+                Err(e) => eprintln!("Warning: {:#}", e),
+            }
+        }
+        
+        if let Some(ref member) = self.job_set {
+            member.finish(merged_stats, test_duration);
+        }
+        
+        Ok(())
+    }
+    
+    /// Run two-phase verification: writer nodes fill the file, then reader nodes verify it
+    ///
+    /// Each phase is a separate job on its node group. Both share one pattern
+    /// and seed (`--seed`, or a random one), sent to every node with its role.
+    async fn run_verify_phases(self) -> Result<()> {
+        let quiet = self.config.output.is_quiet();
+        let groups = self.config.runtime.verify_groups.clone()
+            .context("Two-phase verification requires verify_groups")?;
+        let (writers, readers) = verify::resolve_groups(&groups, self.node_addresses.len())?;
+        verify::check_config(&self.config, writers.len() * self.config.workers.threads)?;
+        
+        let pattern = self.config.runtime.verify_pattern
+            .unwrap_or(crate::config::workload::VerifyPattern::Random);
+        let seed = self.config.runtime.seed.unwrap_or_else(rand::random);
+        
+        progress!(quiet, "Two-phase verification (job {})", self.job_id);
+        progress!(quiet, "  Writer nodes: {:?}", writers);
+        progress!(quiet, "  Reader nodes: {:?}", readers);
+        progress!(quiet, "  Pattern: {}, seed: {}", pattern, seed);
+        
+        for (role, nodes) in [(NodeRole::Writer, writers), (NodeRole::Reader, readers)] {
+            let assignment = VerifyAssignment { role, pattern, seed };
+            let mut config = (*self.config).clone();
+            assignment.apply(&mut config);
+            
+            let phase = DistributedCoordinator {
+                config: Arc::new(config),
+                node_addresses: nodes.iter().map(|&n| self.node_addresses[n].clone()).collect(),
+                job_id: format!("{}-{}", self.job_id, role),
+                progress: self.progress.clone(),
+                verify_assignment: Some(assignment),
+                node_roles: Vec::new(),
+                role_workloads: BTreeMap::new(),
+                thread_scaling: None,
+                scaling_step: false,
+                dataset_locked: true,
+                control: self.control.clone(),
+                job_set: None,
+            };
+            
+            progress!(quiet);
+            progress!(quiet, "═══ Verification phase: {} (nodes {:?}) ═══", role, nodes);
+            progress!(quiet);
+            phase.run().await
+                .with_context(|| format!("Verification {} phase failed", role))?;
+        }
+        
+        Ok(())
+    }
+    
+    /// Run the thread scaling sweep, then the test with the chosen thread count
+    ///
+    /// Each step is a separate short job with `threads` workers per node and
+    /// no output of its own; its aggregate IOPS and latency feed the sweep.
+    async fn run_thread_scaling(self) -> Result<()> {
+        let quiet = self.config.output.is_quiet();
+        let scaling_config = self.config.workers.thread_scaling.clone()
+            .context("Thread scaling requires a thread_scaling configuration")?;
+        let mut scaling = ThreadScaling::new(scaling_config.clone());
+        
+        progress!(quiet, "Thread scaling (job {})", self.job_id);
+        progress!(quiet, "  {}s steps at 1, 2, 4, ... workers per node (max {}), plateau <{}%",
+            scaling_config.step_seconds, scaling_config.max_threads, scaling_config.plateau_percent);
+        
+        while let Some(threads) = scaling.next_threads() {
+            self.wait_for_idle_nodes().await?;
+            let mut config = (*self.config).clone();
+            config.workers.threads = threads;
+            config.workers.thread_scaling = None;
+            config.workers.stonewall = false;
+            config.workload.completion_mode = crate::config::workload::CompletionMode::Duration {
+                seconds: scaling_config.step_seconds,
+                millis: 0,
+            };
+            config.output = crate::config::OutputConfig {
+                quiet: true,
+                no_live: true,
+                percentiles: config.output.percentiles.clone(),
+                ..Default::default()
+            };
+            
+            let progress = Arc::new(JobProgress::new());
+            let step = DistributedCoordinator {
+                config: Arc::new(config),
+                node_addresses: self.node_addresses.clone(),
+                job_id: format!("{}-t{}", self.job_id, threads),
+                progress: Some(progress.clone()),
+                verify_assignment: None,
+                node_roles: self.node_roles.clone(),
+                role_workloads: self.role_workloads.clone(),
+                thread_scaling: None,
+                scaling_step: true,
+                dataset_locked: true,
+                control: None,
+                job_set: None,
+            };
+            Box::pin(step.run()).await
+                .with_context(|| format!("Thread scaling step with {} threads failed", threads))?;
+            
+            let result = progress.result()
+                .with_context(|| format!("Thread scaling step with {} threads produced no results", threads))?;
+            let aggregate = &result["final_summary"]["aggregate"];
+            let micros = |percentile: &str| aggregate["latency"][percentile]["micros"].as_u64().unwrap_or(0);
+            let iops = aggregate["total_iops"].as_f64().unwrap_or(0.0);
+            let throughput_bps = aggregate["total_throughput"]["bytes_per_sec"].as_f64().unwrap_or(0.0);
+            scaling.record(threads, iops, throughput_bps, micros("mean"), micros("p99"));
+            progress!(quiet, "  {:>4} threads: {:.0} IOPS, mean {}us, p99 {}us",
+                threads, iops, micros("mean"), micros("p99"));
+        }
+        
+        let result = scaling.result();
+        if !quiet {
+            println!();
+            crate::output::text::print_thread_scaling(&result);
+        }
+        
+        self.wait_for_idle_nodes().await?;
+        let mut config = (*self.config).clone();
+        config.workers.threads = result.chosen_threads;
+        config.workers.thread_scaling = None;
+        let test = DistributedCoordinator {
+            config: Arc::new(config),
+            thread_scaling: Some(result),
+            dataset_locked: true,
+            ..self
+        };
+        Box::pin(test.run()).await
+    }
+    
+    /// Run the cross-node visibility test instead of an IO workload
+    ///
+    /// The first node acts and the others observe. Every step is a VISIBILITY
+    /// message that each node answers before the next step is sent, so an
+    /// observer only starts looking once the operation has returned.
+    async fn run_visibility(self) -> Result<()> {
+        let quiet = self.config.output.is_quiet();
+        let config = self.config.workers.visibility.clone()
+            .context("The visibility test requires a visibility configuration")?;
+        if self.node_addresses.len() < 2 {
+            anyhow::bail!("--visibility-test needs at least 2 nodes (one acting, the others observing)");
+        }
+        let target = self.config.targets.first()
+            .context("--visibility-test needs a target directory")?;
+        let test_dir = target.path.join(format!("iopulse-visibility-{}", self.job_id));
+        
+        let mut nodes = Vec::new();
+        for addr in &self.node_addresses {
+            let (stream, version) = connect_and_negotiate(addr).await?;
+            if version < 3 {
+                anyhow::bail!("Node {} speaks protocol v{}; the visibility test needs v3", addr, version);
+            }
+            nodes.push(VisibilityNode { addr: addr.clone(), stream, version });
+        }
+        let (actor, observers) = nodes.split_first_mut().expect("at least 2 nodes");
+        
+        progress!(quiet, "Visibility test (job {})", self.job_id);
+        progress!(quiet, "  {} {} ops on {}, {} check on {} observer(s)",
+            config.ops, config.op, actor.addr, config.check, observers.len());
+        progress!(quiet, "  Directory: {}", test_dir.display());
+        
+        let step = |action, paths| VisibilityStep { action, paths, check: config.check, timeout_ms: config.timeout_ms };
+        let setup = actor.step(&step(VisibilityAction::Setup, vec![test_dir.clone()])).await?;
+        if let Some(error) = setup.error {
+            anyhow::bail!("Node {} could not create the test directory: {}", actor.addr, error);
+        }
+        let result = async {
+            // Every observer has to see the directory before it can see files in it
+            let reports = broadcast_visibility(observers, &step(VisibilityAction::Observe, vec![test_dir.clone()])).await?;
+            for (observer, report) in observers.iter().zip(reports) {
+                if report.latencies_ns.iter().any(Option::is_none) {
+                    anyhow::bail!("Node {} does not see {} (is the target on a shared filesystem?)",
+                        observer.addr, test_dir.display());
+                }
+            }
+            
+            let addrs: Vec<String> = observers.iter().map(|observer| observer.addr.clone()).collect();
+            let mut recorder = VisibilityRecorder::new(&addrs);
+            let mut done = 0;
+            while done < config.ops {
+                let count = (config.ops - done).min(config.batch as u64);
+                let names: Vec<_> = (done..done + count).map(|i| test_dir.join(format!("f{:08}", i))).collect();
+                done += count;
+                
+                let (action, watched) = match config.op {
+                    crate::config::VisibilityOp::Create => (VisibilityAction::Create, names.clone()),
+                    crate::config::VisibilityOp::Rename => {
+                        // Untimed: the sources exist everywhere before the rename
+                        let created = actor.step(&step(VisibilityAction::Create, names.clone())).await?;
+                        if let Some(error) = created.error {
+                            anyhow::bail!("Node {} could not create the files to rename: {}", actor.addr, error);
+                        }
+                        broadcast_visibility(observers, &step(VisibilityAction::Observe, names.clone())).await?;
+                        (VisibilityAction::Rename, names.iter().map(|name| visibility::renamed_path(name)).collect())
+                    }
+                };
+                
+                broadcast_visibility(observers, &step(VisibilityAction::Lookup, watched.clone())).await?;
+                let acted = actor.step(&step(action, names)).await?;
+                let succeeded = recorder.record_ops(&acted);
+                if succeeded.iter().all(|ok| !ok) {
+                    anyhow::bail!("Node {}: {}", actor.addr, acted.error.unwrap_or_default());
+                }
+                let visible: Vec<_> = watched.into_iter().zip(succeeded).filter_map(|(path, ok)| ok.then_some(path)).collect();
+                let reports = broadcast_visibility(observers, &step(VisibilityAction::Observe, visible)).await?;
+                for (observer, report) in reports.iter().enumerate() {
+                    recorder.record_observed(observer, report);
+                }
+            }
+            Ok(recorder)
+        }.await;
+        
+        match actor.step(&step(VisibilityAction::Cleanup, vec![test_dir.clone()])).await {
+            Ok(VisibilityReport { error: Some(error), .. }) => eprintln!("Warning: Failed to remove {}: {}", test_dir.display(), error),
+            Err(e) => eprintln!("Warning: Failed to remove {}: {:#}", test_dir.display(), e),
+            Ok(_) => {}
+        }
+        let result = result?.result(&config, &actor.addr);
+        
+        if !quiet {
+            println!();
+            crate::output::text::print_visibility(&result);
+        }
+        if let Some(ref path) = self.config.output.json_output {
+            let is_dir = path.is_dir() || path.to_string_lossy().ends_with('/') || !path.to_string_lossy().contains('.');
+            let path = if is_dir {
+                std::fs::create_dir_all(path).context("Failed to create JSON output directory")?;
+                path.join("visibility.json")
+            } else {
+                path.clone()
+            };
+            let output = serde_json::json!({ "job_id": self.job_id, "run_uuid": self.config.output.run_uuid, "visibility": result });
+            std::fs::write(&path, serde_json::to_string_pretty(&output)?)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            progress!(quiet, "Visibility results written to: {}", path.display());
+        }
+        Ok(())
+    }
+    
+    /// Wait until every node has finished cleaning up after its previous job
+    ///
+    /// Nodes report results before their workers are torn down, so the next
+    /// job of a sequence can arrive while a node still counts itself busy.
+    async fn wait_for_idle_nodes(&self) -> Result<()> {
+        let deadline = std::time::Instant::now() + Duration::from_secs(30);
+        for addr in &self.node_addresses {
+            loop {
+                let ping = ping_node(addr, Duration::from_secs(5)).await?;
+                if ping.status.is_none_or(|status| status.current_test.is_none()) {
+                    break;
+                }
+                if std::time::Instant::now() >= deadline {
+                    anyhow::bail!("Node {} is still running its previous job", addr);
+                }
+                sleep(Duration::from_millis(100)).await;
+            }
+        }
+        Ok(())
+    }
+    
+    /// Apply the control commands that arrived since the last call
+    ///
+    /// Node commands go to every node that speaks protocol v3; `checkpoint`
+    /// closes an on-demand checkpoint and describes it, or says why this run
+    /// can't take one.
+    async fn poll_control(
+        &self,
+        state: &mut ControlState,
+        connections: &mut [(usize, String, TcpStream)],
+        sessions: &mut [NodeSession],
+        node_versions: &[u32],
+        mut checkpoint: impl FnMut() -> Result<String, String>,
+    ) {
+        let Some(ref inbox) = self.control else { return };
+        let requests: Vec<_> = inbox.lock().unwrap().try_iter().collect();
+        
+        for request in requests {
+            if request.is_stale() {
+                continue;
+            }
+            let reply = match request.command {
+                ControlCommand::Status => Ok(state.status()),
+                ControlCommand::Checkpoint => checkpoint(),
+                ControlCommand::Node(msg) => match state.check(&msg) {
+                    Err(reason) => Err(reason),
+                    Ok(()) => {
+                        if !self.config.output.is_quiet() {
+                            println!("Control: {}", msg);
+                        }
+                        let skipped = self.send_control(&msg, connections, sessions, node_versions).await;
+                        state.record(&msg);
+                        if skipped > 0 {
+                            Ok(format!("{} ({} node(s) too old for control commands)", state.status(), skipped))
+                        } else {
+                            Ok(state.status())
+                        }
+                    }
+                },
+            };
+            request.reply(reply);
+        }
+    }
+    
+    /// Send a whole-test control command to every node, split into node shares
+    ///
+    /// Returns the number of nodes skipped as too old for control commands.
+    async fn send_control(
+        &self,
+        msg: &ControlMessage,
+        connections: &mut [(usize, String, TcpStream)],
+        sessions: &mut [NodeSession],
+        node_versions: &[u32],
+    ) -> usize {
+        let nodes = connections.len();
+        let mut skipped = 0;
+        for (node_idx, (node_id, _addr, stream)) in connections.iter_mut().enumerate() {
+            if node_versions[*node_id] < 3 {
+                skipped += 1;
+                continue;
+            }
+            let node_msg = Message::Control(control::per_node(msg, nodes, node_idx));
+            if let Err(e) = sessions[node_idx].send(stream, &node_msg).await {
+                eprintln!("Warning: Failed to send control command to node {}: {:#}", node_id, e);
+            }
+        }
+        skipped
+    }
+    
+    /// Handle an ERROR from running node `node_id`
+    ///
+    /// A failed worker is reported and tolerated with continue_on_worker_failure
+    /// (the node still sends RESULTS for the remaining workers); anything else
+    /// aborts the test.
+    fn handle_node_error(&self, node_id: usize, err: &ErrorMessage) -> Result<()> {
+        let tolerated = err.worker_id.is_some() && self.config.runtime.continue_on_worker_failure;
+        
+        if tolerated {
+            eprintln!("Warning: node {}: {} (continuing with the remaining workers)", node_id, err.error);
+        }
+        if let Some(ref backtrace) = err.backtrace {
+            if self.config.runtime.debug || self.config.output.is_verbose() {
+                eprintln!("Backtrace:\n{}", backtrace);
+            } else {
+                eprintln!("  (run with -v for the backtrace)");
+            }
+        }
+        
+        if !tolerated {
+            anyhow::bail!("Node {} reported error: {}", node_id, err.error);
+        }
         Ok(())
     }
     
@@ -1242,20 +2498,23 @@ impl DistributedCoordinator {
     async fn distributed_preallocate(
         &self,
         connections: &mut [(usize, String, TcpStream)],
+        node_versions: &[u32],
         fill_files: bool,
     ) -> Result<()> {
+        let quiet = self.config.output.is_quiet();
         let num_nodes = connections.len();
         
         // For each target, partition and distribute
-        for target in &self.config.targets {
+        for (target_index, target) in self.config.targets.iter().enumerate() {
             let file_size = target.file_size.ok_or_else(|| anyhow::anyhow!("File size required for pre-allocation"))?;
             
-            println!("Distributing pre-allocation for: {}", target.path.display());
-            println!("  File size: {} bytes", file_size);
-            println!("  Nodes: {}", num_nodes);
+            progress!(quiet, "Distributing pre-allocation for: {}", target.path.display());
+            progress!(quiet, "  File size: {} bytes", file_size);
+            progress!(quiet, "  Nodes: {}", num_nodes);
             
             // Calculate region size per node
             let region_size = file_size / num_nodes as u64;
+            let fill_regions = crate::distribution::reach::fill_regions(&self.config, target_index, num_nodes);
             
             // Send PrepareFiles to each node
             for (node_id, addr, stream) in connections.iter_mut() {
@@ -1266,27 +2525,34 @@ impl DistributedCoordinator {
                     start_offset + region_size
                 };
                 
-                println!("  Node {}: bytes {}-{} ({} MB)", 
+                progress!(quiet, "  Node {}: bytes {}-{} ({} MB)", 
                     node_id, start_offset, end_offset,
                     (end_offset - start_offset) / 1_000_000);
                 
+                let version = node_versions[*node_id];
                 let prepare_msg = PrepareFilesMessage {
-                    protocol_version: PROTOCOL_VERSION,
+                    protocol_version: version,
                     node_id: addr.clone(),
                     file_list: vec![target.path.clone()],
                     file_size: end_offset - start_offset,
                     start_offset,
                     fill_pattern: self.config.workload.write_pattern,
                     fill_files,
+                    custom_pattern: self.config.workload.custom_pattern.clone(),
+                    prealloc_mode: target.prealloc_mode,
+                    fill_regions: fill_regions.clone(),
+                    // The nodes fill in parallel, so each gets its share of the limit
+                    rate_limit: self.config.runtime.prep_rate_limit
+                        .map(|limit| PrepLimit::share(limit, 1, num_nodes).bytes_per_sec()),
                 };
                 
-                write_message(stream, &Message::PrepareFiles(prepare_msg)).await
+                write_message_for_version(stream, &Message::PrepareFiles(prepare_msg), version).await
                     .with_context(|| format!("Failed to send PrepareFiles to node {}", node_id))?;
             }
             
             // Wait for all nodes to complete (barrier)
-            println!();
-            println!("Waiting for all nodes to complete pre-allocation...");
+            progress!(quiet);
+            progress!(quiet, "Waiting for all nodes to complete pre-allocation...");
             
             // Measure total barrier time
             let barrier_start = std::time::Instant::now();
@@ -1311,7 +2577,7 @@ impl DistributedCoordinator {
                             // Process immediately
                             match &responses.last().unwrap().1 {
                                 Message::FilesReady(ready) => {
-                                    println!("  ✅ Node {} ready ({} files, {:.2}s actual)", 
+                                    progress!(quiet, "  ✅ Node {} ready ({} files, {:.2}s actual)", 
                                         node_id, ready.files_created,
                                         ready.duration_ns as f64 / 1_000_000_000.0);
                                 }
@@ -1337,16 +2603,231 @@ impl DistributedCoordinator {
             }
             
             let barrier_elapsed = barrier_start.elapsed();
-            println!("  ✅ All nodes completed pre-allocation (barrier time: {:.2}s)", barrier_elapsed.as_secs_f64());
+            progress!(quiet, "  ✅ All nodes completed pre-allocation (barrier time: {:.2}s)", barrier_elapsed.as_secs_f64());
         }
         
         Ok(())
     }
+    
+    /// Check on this host and every node that each target can be created and written
+    ///
+    /// The coordinator host generates and fills layouts, so it is checked too.
+    /// Fails listing every failed check; the connections are closed again.
+    async fn preflight(&self) -> Result<()> {
+        let quiet = self.config.output.is_quiet();
+        progress!(quiet, "Running preflight checks...");
+        
+        let config = self.config.clone();
+        let mut hosts = vec![("coordinator".to_string(),
+            Some(tokio::task::spawn_blocking(move || PreflightReport::run(&config)).await?))];
+        for addr in &self.node_addresses {
+            let (mut stream, version) = connect_and_negotiate(addr).await?;
+            if version < 3 {
+                hosts.push((addr.clone(), None));
+                continue;
+            }
+            write_message_for_version(&mut stream, &Message::Preflight(Box::new((*self.config).clone())), version).await
+                .with_context(|| format!("Failed to send PREFLIGHT to {}", addr))?;
+            let report = match read_message(&mut stream).await
+                .with_context(|| format!("Failed to read PREFLIGHT_REPORT from {}", addr))? {
+                Message::PreflightReport(report) => report,
+                Message::Error(err) => anyhow::bail!("Node {} reported error: {}", addr, err.error),
+                other => anyhow::bail!("Expected PREFLIGHT_REPORT from {}, got {:?}", addr, other),
+            };
+            hosts.push((addr.clone(), Some(report)));
+        }
+        
+        let failures: usize = hosts.iter()
+            .filter_map(|(_, report)| report.as_ref())
+            .map(|report| report.failures().count())
+            .sum();
+        if !quiet || failures > 0 {
+            let rows: Vec<(String, Option<&PreflightReport>)> = hosts.iter()
+                .map(|(host, report)| (host.clone(), report.as_ref()))
+                .collect();
+            crate::output::text::print_preflight(&rows);
+        }
+        if failures > 0 {
+            anyhow::bail!("Preflight failed: {} check(s) failed (listed above); nothing was created or filled", failures);
+        }
+        progress!(quiet, "✅ Preflight passed on {} hosts", hosts.len());
+        progress!(quiet);
+        Ok(())
+    }
+}
+
+
+/// A node connection of the visibility test
+struct VisibilityNode {
+    addr: String,
+    stream: TcpStream,
+    version: u32,
+}
+
+impl VisibilityNode {
+    /// Send a step and wait for its report
+    async fn step(&mut self, step: &VisibilityStep) -> Result<VisibilityReport> {
+        self.send(step).await?;
+        self.report().await
+    }
+    
+    async fn send(&mut self, step: &VisibilityStep) -> Result<()> {
+        write_message_for_version(&mut self.stream, &Message::Visibility(step.clone()), self.version).await
+            .with_context(|| format!("Failed to send VISIBILITY to {}", self.addr))
+    }
+    
+    async fn report(&mut self) -> Result<VisibilityReport> {
+        match read_message(&mut self.stream).await
+            .with_context(|| format!("Failed to read VISIBILITY_REPORT from {}", self.addr))? {
+            Message::VisibilityReport(report) => Ok(report),
+            Message::Error(err) => anyhow::bail!("Node {} reported error: {}", self.addr, err.error),
+            other => anyhow::bail!("Expected VISIBILITY_REPORT from {}, got {:?}", self.addr, other),
+        }
+    }
 }
 
+/// Send a step to every node at once, then collect the reports in node order
+async fn broadcast_visibility(nodes: &mut [VisibilityNode], step: &VisibilityStep) -> Result<Vec<VisibilityReport>> {
+    for node in nodes.iter_mut() {
+        node.send(step).await?;
+    }
+    let mut reports = Vec::with_capacity(nodes.len());
+    for node in nodes.iter_mut() {
+        reports.push(node.report().await?);
+    }
+    Ok(reports)
+}
+
+/// Generate a job ID from the local time and process ID (e.g., 20250101-120000-4242)
+pub fn generate_job_id() -> String {
+    format!("{}-{}", chrono::Local::now().format("%Y%m%d-%H%M%S"), std::process::id())
+}
+
+/// Connect to a node and negotiate the protocol version
+///
+/// Sends HELLO with our supported range. Nodes older than the handshake
+/// (protocol v2) fail to decode HELLO and drop the connection; in that case we
+/// reconnect and speak v2 without a handshake, so mixed-version clusters keep
+/// working during rolling upgrades.
+pub(crate) async fn connect_and_negotiate(addr: &str) -> Result<(TcpStream, u32)> {
+    let mut stream = TcpStream::connect(addr).await
+        .with_context(|| format!("Failed to connect to {}", addr))?;
+    
+    match negotiate(&mut stream, addr).await? {
+        Some(version) => Ok((stream, version)),
+        None => {
+            // Pre-handshake node: it closed the connection on HELLO
+            drop(stream);
+            let stream = TcpStream::connect(addr).await
+                .with_context(|| format!("Failed to reconnect to {}", addr))?;
+            Ok((stream, LEGACY_PROTOCOL_VERSION))
+        }
+    }
+}
+
+/// Send HELLO and read the node's reply
+///
+/// Returns None if the node closed the connection without replying, which is
+/// how pre-handshake (v2) nodes react to HELLO.
+async fn negotiate(stream: &mut TcpStream, addr: &str) -> Result<Option<u32>> {
+    write_message(stream, &Message::Hello(HelloMessage::local())).await
+        .with_context(|| format!("Failed to send HELLO to {}", addr))?;
+    
+    match read_message(stream).await {
+        Ok(Message::HelloAck(ack)) => {
+            if !is_supported_version(ack.selected_version) {
+                anyhow::bail!("Node {} selected unsupported protocol version {} (supported: {}-{})",
+                    addr, ack.selected_version, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION);
+            }
+            Ok(Some(ack.selected_version))
+        }
+        Ok(Message::Error(err)) => {
+            anyhow::bail!("Node {} rejected protocol negotiation: {}", addr, err.error)
+        }
+        Ok(other) => {
+            anyhow::bail!("Expected HELLO_ACK from {}, got {:?}", addr, other)
+        }
+        Err(e) if is_connection_closed(&e) && is_supported_version(LEGACY_PROTOCOL_VERSION) => Ok(None),
+        Err(e) => {
+            Err(e.context(format!("Protocol negotiation with {} failed", addr)))
+        }
+    }
+}
+
+/// Whether a node address ("host:port", IPv6 in brackets) is on this host
+fn is_local_address(addr: &str) -> bool {
+    addr.rsplit_once(':').is_some_and(|(host, _)| {
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        host == "localhost" || host.parse::<std::net::IpAddr>().is_ok_and(|ip| ip.is_loopback())
+    })
+}
+
+/// Whether a read failed because the peer closed the connection (EOF or reset)
+fn is_connection_closed(e: &anyhow::Error) -> bool {
+    e.chain()
+        .filter_map(|cause| cause.downcast_ref::<std::io::Error>())
+        .any(|io| matches!(io.kind(), std::io::ErrorKind::UnexpectedEof | std::io::ErrorKind::ConnectionReset))
+}
+
+/// Query a node's status over an established connection (protocol v3+)
+async fn query_status(stream: &mut TcpStream, addr: &str) -> Result<StatusMessage> {
+    write_message(stream, &Message::Status).await
+        .with_context(|| format!("Failed to send STATUS to {}", addr))?;
+    
+    match read_message(stream).await
+        .with_context(|| format!("Failed to read STATUS_REPORT from {}", addr))? {
+        Message::StatusReport(status) => Ok(status),
+        Message::Error(err) => anyhow::bail!("Node {} reported error: {}", addr, err.error),
+        other => anyhow::bail!("Expected STATUS_REPORT from {}, got {:?}", addr, other),
+    }
+}
+
+/// Result of pinging a node service
+#[derive(Debug, Clone)]
+pub struct PingResult {
+    /// Node address as given
+    pub address: String,
+    
+    /// Negotiated protocol version
+    pub protocol_version: u32,
+    
+    /// Time from connect to status reply
+    pub round_trip: Duration,
+    
+    /// Node status (None for v2 nodes, which predate STATUS)
+    pub status: Option<StatusMessage>,
+}
+
+/// Ping a node service: connect, negotiate, and query its status
+pub async fn ping_node(addr: &str, timeout: Duration) -> Result<PingResult> {
+    let start = std::time::Instant::now();
+    
+    let ping = async {
+        let mut stream = TcpStream::connect(addr).await
+            .with_context(|| format!("Failed to connect to {}", addr))?;
+        
+        match negotiate(&mut stream, addr).await? {
+            Some(version) => {
+                let status = query_status(&mut stream, addr).await?;
+                Ok::<_, anyhow::Error>((version, Some(status)))
+            }
+            None => Ok((LEGACY_PROTOCOL_VERSION, None)),
+        }
+    };
+    
+    let (protocol_version, status) = tokio::time::timeout(timeout, ping).await
+        .map_err(|_| anyhow::anyhow!("No response from {} within {}s", addr, timeout.as_secs()))??;
+    
+    Ok(PingResult {
+        address: addr.to_string(),
+        protocol_version,
+        round_trip: start.elapsed(),
+        status,
+    })
+}
 
 /// Check if a file is sparse
-fn is_file_sparse(path: &std::path::Path) -> Result<bool> {
+pub(crate) fn is_file_sparse(path: &std::path::Path) -> Result<bool> {
     let metadata = std::fs::metadata(path)?;
     
     #[cfg(unix)]
@@ -1365,6 +2846,32 @@ fn is_file_sparse(path: &std::path::Path) -> Result<bool> {
     }
 }
 
+/// Merge adjacent retained intervals of every node (--stats-mem-limit)
+fn coarsen_time_series(
+    retention: &mut SeriesRetention,
+    snapshots: &mut [Vec<crate::output::json::AggregatedSnapshot>],
+    resource_stats: &mut [Vec<crate::util::resource::ResourceStats>],
+    per_worker: &mut [Vec<Vec<crate::output::json::AggregatedSnapshot>>],
+    latency: crate::config::IntervalLatency,
+) {
+    let factor = retention.factor();
+    for (node, series) in snapshots.iter_mut().enumerate() {
+        retention.coarsen(node, factor, series, |a, b| a.absorb(b, latency));
+        // Resource stats are samples; keep the later one
+        budget::merge_pairs(&mut resource_stats[node], |a, b| *a = b);
+        budget::merge_pairs(&mut per_worker[node], |a, b| {
+            for (a, b) in a.iter_mut().zip(b) {
+                a.absorb(b, latency);
+            }
+        });
+        for (snapshot, workers) in series.iter_mut().zip(&per_worker[node]).skip(1) {
+            if snapshot.per_worker.is_some() {
+                snapshot.per_worker = Some(workers.iter().map(|w| w.to_stats_snapshot()).collect());
+            }
+        }
+    }
+}
+
 /// Convert WorkerStatsSnapshot to AggregatedSnapshot for time-series
 ///
 /// This is a simplified conversion used for heartbeat data.
@@ -1438,6 +2945,8 @@ fn worker_snapshot_to_aggregated(
         metadata_rename_latency,
         metadata_readdir_latency,
         metadata_fsync_latency,
+        page_faults: snapshot.page_faults,
+        submit_gaps: snapshot.submit_gaps,
         per_worker: None,  // Heartbeats don't include per-worker data
     }
 }
@@ -1445,20 +2954,28 @@ fn worker_snapshot_to_aggregated(
 /// Validate and fill sparse files in parallel
 ///
 /// Checks each file in the list and fills it with the specified pattern if it's sparse (0 bytes on disk).
-/// Uses rayon for parallel processing with progress updates every 1000 files.
+/// Only `fill_regions` of each file are filled when set. Uses rayon for parallel
+/// processing with progress updates every 1000 files.
 ///
 /// Returns the number of files that were filled.
 fn validate_and_fill_files(
     file_list: &[std::path::PathBuf],
     file_size: u64,
     pattern: crate::config::workload::VerifyPattern,
+    custom_pattern: Option<&crate::config::workload::CustomPattern>,
+    fill_regions: Option<&[(u64, u64)]>,
+    prep_limit: Option<PrepLimit>,
+    quiet: bool,
 ) -> Result<usize> {
     use rayon::prelude::*;
     use std::sync::atomic::{AtomicUsize, Ordering};
     
+    let start = std::time::Instant::now();
+    let fill_bytes = fill_regions.map_or(file_size, |regions| regions.iter().map(|(from, to)| to - from).sum());
     let filled_count = AtomicUsize::new(0);
     let processed_count = AtomicUsize::new(0);
     let total_files = file_list.len();
+    let custom_payload: Option<std::sync::Arc<[u8]>> = custom_pattern.map(|custom| custom.payload.as_slice().into());
     
     // Process files in parallel
     file_list.par_iter().try_for_each(|path| -> Result<()> {
@@ -1490,15 +3007,27 @@ fn validate_and_fill_files(
             use crate::target::OpenFlags;
             
             let mut target = FileTarget::new(path.clone(), Some(file_size));
+            target.set_quiet(quiet);
+            if let Some(regions) = fill_regions {
+                target.set_fill_regions(regions.to_vec());
+            }
+            target.set_prep_limit(prep_limit.clone());
             
             let flags = OpenFlags {
                 direct: false,
                 sync: false,
                 create: true,
                 truncate: false,
+                noatime: false,
+                dsync: false,
+                rsync: false,
+                read_only: false,
             };
             
             target.open(flags)?;
+            if let Some(payload) = custom_payload.as_ref() {
+                target.set_custom_payload(payload.clone());
+            }
             target.refill(pattern)?;
             target.close()?;
             
@@ -1508,7 +3037,11 @@ fn validate_and_fill_files(
         // Update progress
         let processed = processed_count.fetch_add(1, Ordering::Relaxed) + 1;
         if processed % 1000 == 0 || processed == total_files {
-            println!("  Progress: {}/{} files validated...", processed, total_files);
+            match prep_limit {
+                Some(ref limit) => progress!(quiet, "  Progress: {}/{} files validated, {}...", processed, total_files,
+                    limit.progress(filled_count.load(Ordering::Relaxed) as u64 * fill_bytes, start.elapsed())),
+                None => progress!(quiet, "  Progress: {}/{} files validated...", processed, total_files),
+            }
         }
         
         Ok(())
@@ -1516,3 +3049,38 @@ fn validate_and_fill_files(
     
     Ok(filled_count.load(Ordering::Relaxed))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coordinator(read_only: bool) -> DistributedCoordinator {
+        let mut config: Config = toml::from_str(
+            "targets = [{ path = \"/data/test.dat\", file_size = 1048576 }]\n\
+             [workload]\nread_percent = 100\nwrite_percent = 0\ncompletion_mode = { Duration = { seconds = 10 } }\n",
+        ).unwrap();
+        config.runtime.read_only = read_only;
+        DistributedCoordinator::new(Arc::new(config), vec!["node1:9999".into(), "node2:9999".into()]).unwrap()
+    }
+
+    #[test]
+    fn test_read_only_applies_to_role_workloads() {
+        let roles = vec![Some("reader".to_string()), Some("writer".to_string())];
+        let mut fragments = BTreeMap::new();
+        fragments.insert("writer".to_string(), "read_percent = 0\nwrite_percent = 100".parse::<toml::Table>().unwrap());
+
+        assert!(coordinator(false).set_roles(roles.clone(), &fragments).is_ok());
+        let err = coordinator(true).set_roles(roles, &fragments).unwrap_err();
+        assert!(format!("{:#}", err).contains("--readonly"));
+    }
+
+    #[test]
+    fn test_is_local_address() {
+        for addr in ["localhost:9999", "127.0.0.1:9999", "[::1]:9999"] {
+            assert!(is_local_address(addr), "{}", addr);
+        }
+        for addr in ["10.0.0.5:9999", "[fe80::1]:9999", "node1:9999"] {
+            assert!(!is_local_address(addr), "{}", addr);
+        }
+    }
+}
@@ -0,0 +1,329 @@
+//! Job sets: several concurrent jobs from one configuration file
+//!
+//! A configuration file (`--config`) may define several jobs in `[[jobs]]`
+//! sections. Everything outside the sections is the default for every job;
+//! each job has a name and replaces any of those fields (nested tables are
+//! merged field by field, arrays such as `targets` are replaced):
+//!
+//! ```toml
+//! [workload]
+//! read_percent = 100
+//! write_percent = 0
+//! engine = "io_uring"
+//! [workload.completion_mode.Duration]
+//! seconds = 60
+//!
+//! [[jobs]]
+//! name = "oltp"
+//! workload = { block_size = 8192, queue_depth = 32, random = true }
+//! targets = [{ path = "/data/db.dat", file_size = 10737418240 }]
+//!
+//! [[jobs]]
+//! name = "log"
+//! workload = { read_percent = 0, write_percent = 100, block_size = 65536, engine = "Sync" }
+//! targets = [{ path = "/logs/wal.dat", file_size = 1073741824 }]
+//! ```
+//!
+//! A file without `[[jobs]]` is a single job. Every job of a set runs on its
+//! own node service with its own targets, engine and workload; jobs start
+//! together once all of them are prepared and are reported together (each
+//! job, then a combined total) when the last one finishes.
+//!
+//! A job with `background = true` is load next to the measured jobs, e.g.
+//! rate-limited sequential writes to study interference. It runs until the
+//! foreground jobs stop (or its own duration ends) and is reported after
+//! them, outside the combined total.
+
+use crate::config::Config;
+use crate::stats::WorkerStats;
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::watch;
+
+/// Job of a job file
+#[derive(Debug, Clone)]
+pub struct Job {
+    pub name: String,
+    pub config: Config,
+    /// Background load, not part of the measured result
+    pub background: bool,
+}
+
+/// Parse a job file; a file without `[[jobs]]` is one job named after the file
+pub fn parse_job_file(path: &Path) -> Result<Vec<Job>> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+    let default_name = path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "job".to_string());
+    parse_jobs(&contents, &default_name)
+        .with_context(|| format!("Failed to parse config file: {}", path.display()))
+}
+
+/// Parse the jobs of a job file's contents
+pub fn parse_jobs(contents: &str, default_name: &str) -> Result<Vec<Job>> {
+    let mut defaults: toml::Table = contents.parse()
+        .context("Failed to parse TOML configuration")?;
+    let sections = match defaults.remove("jobs") {
+        None => {
+            let config = toml::Value::Table(defaults).try_into()
+                .context("Invalid configuration")?;
+            return Ok(vec![Job { name: default_name.to_string(), config, background: false }]);
+        }
+        Some(toml::Value::Array(sections)) if !sections.is_empty() => sections,
+        Some(_) => anyhow::bail!("'jobs' must be a non-empty array of [[jobs]] sections"),
+    };
+
+    let several = sections.len() > 1;
+    let mut jobs: Vec<Job> = Vec::new();
+    for (i, section) in sections.into_iter().enumerate() {
+        let toml::Value::Table(mut section) = section else {
+            anyhow::bail!("Job {} is not a [[jobs]] section", i + 1);
+        };
+        let name = match section.remove("name") {
+            Some(toml::Value::String(name)) => name,
+            Some(_) => anyhow::bail!("Job {}: name must be a string", i + 1),
+            None => anyhow::bail!("Job {} has no name", i + 1),
+        };
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')) {
+            anyhow::bail!("Invalid job name '{}' (use letters, digits, '-', '_' and '.')", name);
+        }
+        if jobs.iter().any(|job| job.name == name) {
+            anyhow::bail!("Duplicate job name '{}'", name);
+        }
+        let background = match section.remove("background") {
+            Some(toml::Value::Boolean(background)) => background,
+            Some(_) => anyhow::bail!("Job '{}': background must be true or false", name),
+            None => false,
+        };
+
+        let mut merged = defaults.clone();
+        crate::config::toml::merge_table(&mut merged, &section);
+        let config: Config = toml::Value::Table(merged).try_into()
+            .with_context(|| format!("Invalid configuration for job '{}'", name))?;
+        if several && config.workers.thread_scaling.is_some() {
+            anyhow::bail!("Job '{}': thread scaling is not supported in a job set", name);
+        }
+        if background && config.workload.completion_mode.test_duration().is_none() {
+            anyhow::bail!("Background job '{}' needs a duration (it stops with the foreground jobs)", name);
+        }
+        jobs.push(Job { name, config, background });
+    }
+    if jobs.iter().all(|job| job.background) {
+        anyhow::bail!("A job file needs at least one job that is not in the background");
+    }
+    Ok(jobs)
+}
+
+/// Give every job its ID (`<set ID>-<job name>` in a set of several jobs)
+///
+/// `{run_id}` in each job's paths becomes its job ID, so jobs sharing output
+/// paths through the defaults write separate files. Jobs still writing the
+/// same JSON or CSV file are rejected.
+pub fn assign_job_ids(jobs: &mut [Job], set_id: &str) -> Result<Vec<String>> {
+    let single = jobs.len() == 1;
+    let mut ids = Vec::with_capacity(jobs.len());
+    for job in jobs.iter_mut() {
+        let id = if single { set_id.to_string() } else { format!("{}-{}", set_id, job.name) };
+        job.config.expand_run_id(&id);
+        ids.push(id);
+    }
+
+    for (i, job) in jobs.iter().enumerate() {
+        for other in &jobs[..i] {
+            for (output, path, other_path) in [
+                ("JSON", &job.config.output.json_output, &other.config.output.json_output),
+                ("CSV", &job.config.output.csv_output, &other.config.output.csv_output),
+            ] {
+                if path.is_some() && path == other_path {
+                    anyhow::bail!("Jobs '{}' and '{}' write {} output to the same path (use {} in the path)",
+                        other.name, job.name, output, crate::config::RUN_ID_PLACEHOLDER);
+                }
+            }
+        }
+    }
+    Ok(ids)
+}
+
+/// Final statistics of one job and its duration
+pub type JobReport = (WorkerStats, Duration);
+
+/// Where a job's report is left for the set (None if the job failed)
+pub type ReportSlot = Arc<Mutex<Option<JobReport>>>;
+
+/// A job's place in a running job set
+///
+/// The coordinator waits in `ready_to_start` before sending START, so the
+/// jobs of a set start together, and hands its final statistics to the set
+/// with `finish` instead of printing them. A member dropped before it was
+/// ready (its job failed) no longer holds back the other jobs. Foreground
+/// jobs call `stopping` when their test ends; background jobs stop once
+/// `foreground_stopped` says every foreground job has.
+#[derive(Debug)]
+pub struct JobSetMember {
+    name: String,
+    background: bool,
+    /// Jobs not yet ready to start
+    pending: Arc<watch::Sender<usize>>,
+    arrived: AtomicBool,
+    /// Foreground jobs still running
+    foreground: Arc<watch::Sender<usize>>,
+    stopped: AtomicBool,
+    report: ReportSlot,
+}
+
+impl JobSetMember {
+    /// Members for the given jobs, and the slots their reports end up in
+    pub fn for_jobs(jobs: &[Job]) -> (Vec<Self>, Vec<ReportSlot>) {
+        let pending = Arc::new(watch::Sender::new(jobs.len()));
+        let foreground = Arc::new(watch::Sender::new(jobs.iter().filter(|job| !job.background).count()));
+        jobs.iter()
+            .map(|job| {
+                let report = Arc::new(Mutex::new(None));
+                let member = Self {
+                    name: job.name.clone(),
+                    background: job.background,
+                    pending: pending.clone(),
+                    arrived: AtomicBool::new(false),
+                    foreground: foreground.clone(),
+                    stopped: AtomicBool::new(false),
+                    report: report.clone(),
+                };
+                (member, report)
+            })
+            .unzip()
+    }
+
+    /// Job name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Wait until every job of the set is ready to start
+    pub async fn ready_to_start(&self) {
+        if !self.arrived.swap(true, Ordering::Relaxed) {
+            self.pending.send_modify(|pending| *pending = pending.saturating_sub(1));
+        }
+        let _ = self.pending.subscribe().wait_for(|&pending| pending == 0).await;
+    }
+
+    /// The job's test has ended (background jobs stop after the last foreground job)
+    pub fn stopping(&self) {
+        if !self.background && !self.stopped.swap(true, Ordering::Relaxed) {
+            self.foreground.send_modify(|running| *running = running.saturating_sub(1));
+        }
+    }
+
+    /// Whether this is a background job and every foreground job has stopped
+    pub fn foreground_stopped(&self) -> bool {
+        self.background && *self.foreground.borrow() == 0
+    }
+
+    /// Hand the job's final statistics to the set
+    pub fn finish(&self, stats: WorkerStats, duration: Duration) {
+        *self.report.lock().unwrap() = Some((stats, duration));
+    }
+}
+
+impl Drop for JobSetMember {
+    fn drop(&mut self) {
+        if !self.arrived.load(Ordering::Relaxed) {
+            self.pending.send_modify(|pending| *pending = pending.saturating_sub(1));
+        }
+        self.stopping();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JOB_FILE: &str = r#"
+[workload]
+read_percent = 100
+write_percent = 0
+queue_depth = 4
+[workload.completion_mode.Duration]
+seconds = 10
+
+[output]
+json_output = "results/{run_id}.json"
+
+[[jobs]]
+name = "oltp"
+workload = { block_size = 8192, random = true }
+targets = [{ path = "/data/db.dat", file_size = 1073741824 }]
+
+[[jobs]]
+name = "log"
+workload = { read_percent = 0, write_percent = 100, engine = "io_uring" }
+targets = [{ path = "/logs/wal.dat", file_size = 1048576 }]
+"#;
+
+    #[test]
+    fn test_parse_jobs() {
+        let mut jobs = parse_jobs(JOB_FILE, "file").unwrap();
+        assert_eq!(jobs.len(), 2);
+        assert_eq!(jobs[0].name, "oltp");
+        assert_eq!(jobs[0].config.workload.block_size, 8192);
+        assert_eq!(jobs[0].config.workload.queue_depth, 4);
+        assert_eq!(jobs[1].config.workload.write_percent, 100);
+        assert_eq!(jobs[1].config.targets[0].path, Path::new("/logs/wal.dat"));
+
+        let ids = assign_job_ids(&mut jobs, "run1").unwrap();
+        assert_eq!(ids, ["run1-oltp", "run1-log"]);
+        assert_eq!(jobs[1].config.output.json_output.as_deref(), Some(Path::new("results/run1-log.json")));
+
+        let shared = JOB_FILE.replace("{run_id}", "all");
+        let mut jobs = parse_jobs(&shared, "file").unwrap();
+        assert!(assign_job_ids(&mut jobs, "run1").is_err());
+
+        let duplicate = JOB_FILE.replace("\"log\"", "\"oltp\"");
+        assert!(parse_jobs(&duplicate, "file").is_err());
+    }
+
+    #[test]
+    fn test_single_job_file() {
+        let contents = "targets = [{ path = \"/tmp/a.dat\" }]\n\
+                        [workload]\nread_percent = 100\nwrite_percent = 0\n\
+                        [workload.completion_mode.Duration]\nseconds = 5\n";
+        let jobs = parse_jobs(contents, "basic").unwrap();
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].name, "basic");
+    }
+
+    #[test]
+    fn test_background_jobs() {
+        let background = JOB_FILE.replace("name = \"log\"", "name = \"log\"\nbackground = true");
+        let jobs = parse_jobs(&background, "file").unwrap();
+        assert!(!jobs[0].background && jobs[1].background);
+        let (members, _) = JobSetMember::for_jobs(&jobs);
+        assert!(!members[1].foreground_stopped());
+        members[1].stopping();
+        assert!(!members[1].foreground_stopped());
+        drop(members.into_iter().next());
+        // The foreground job is gone (stopped or failed)
+        let (members, _) = JobSetMember::for_jobs(&jobs);
+        members[0].stopping();
+        assert!(members[1].foreground_stopped() && !members[0].foreground_stopped());
+
+        let all = background.replace("name = \"oltp\"", "name = \"oltp\"\nbackground = true");
+        assert!(parse_jobs(&all, "file").is_err());
+        let counted = background.replace("[workload.completion_mode.Duration]\nseconds = 10", "[workload.completion_mode.TotalBytes]\nbytes = 4096");
+        assert!(parse_jobs(&counted, "file").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_failed_member_does_not_block_start() {
+        let jobs = parse_jobs(JOB_FILE, "file").unwrap();
+        let (mut members, reports) = JobSetMember::for_jobs(&jobs);
+        drop(members.pop());
+        members[0].ready_to_start().await;
+        members[0].finish(WorkerStats::new(), Duration::from_secs(1));
+        assert!(reports[0].lock().unwrap().is_some());
+        assert!(reports[1].lock().unwrap().is_none());
+    }
+}
@@ -0,0 +1,204 @@
+//! Latency goal-seeking (`--target-p99`)
+//!
+//! Finds the highest IOPS a device sustains while its p99 latency stays at a
+//! target. The test starts unlimited; every step the coordinator measures the
+//! step's IOPS and p99 across all nodes and adjusts the whole test's IOPS
+//! limit (the same limit as the control `rate` command):
+//!
+//! - **Target met unlimited**: the device never exceeds the target; the test
+//!   stays unlimited
+//! - **Target missed**: the rate is bisected between the highest rate that
+//!   met the target and the lowest that missed it
+//! - **Converged**: once the two are within 2%, the test holds the rate that
+//!   met the target, backing off 5% whenever a step misses again
+//!
+//! The sustainable IOPS is the mean of the steps that met the target at the
+//! rate held at the end.
+//!
+//! # Example
+//!
+//! ```
+//! use iopulse::config::LatencyTargetConfig;
+//! use iopulse::distributed::latency_target::LatencyTarget;
+//!
+//! let mut target = LatencyTarget::new(LatencyTargetConfig { p99_us: 2000, step_seconds: 5 });
+//! assert_eq!(target.rate(), None);
+//!
+//! // Unlimited: 40K IOPS at 6ms p99, so try half
+//! assert_eq!(target.record(40_000.0, 6000), Some(20_000));
+//! assert_eq!(target.record(20_000.0, 1500), Some(30_000));
+//! ```
+
+use crate::config::LatencyTargetConfig;
+use serde::{Deserialize, Serialize};
+
+/// Rates within this fraction of each other end the bisection
+const CONVERGED_FRACTION: f64 = 0.02;
+/// Rate reduction when a held rate misses the target
+const BACK_OFF_FRACTION: f64 = 0.05;
+
+/// Measurements of one step
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LatencyTargetStep {
+    /// IOPS limit during the step (None = unlimited)
+    pub rate_iops: Option<u64>,
+    /// Operations per second achieved (all nodes)
+    pub iops: f64,
+    /// 99th percentile latency (microseconds)
+    pub p99_latency_us: u64,
+    /// Whether p99 stayed at or below the target
+    pub met: bool,
+}
+
+/// Search steps and the sustainable IOPS
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LatencyTargetResult {
+    /// Target p99 latency (microseconds)
+    pub target_p99_us: u64,
+    /// Steps in test order
+    pub steps: Vec<LatencyTargetStep>,
+    /// IOPS limit at the end of the test (None = unlimited)
+    pub final_rate_iops: Option<u64>,
+    /// Mean IOPS of the steps that met the target at the final rate (None if none did)
+    pub sustainable_iops: Option<f64>,
+    /// Highest p99 of those steps (microseconds)
+    pub sustainable_p99_us: Option<u64>,
+    /// Whether the bisection narrowed the rate down
+    pub converged: bool,
+}
+
+/// Feedback state: the rate to offer next
+#[derive(Debug)]
+pub struct LatencyTarget {
+    config: LatencyTargetConfig,
+    steps: Vec<LatencyTargetStep>,
+    /// Current IOPS limit (None = unlimited)
+    rate: Option<u64>,
+    /// Highest rate that met the target
+    met: Option<u64>,
+    /// Lowest rate that missed the target
+    missed: Option<u64>,
+    converged: bool,
+}
+
+impl LatencyTarget {
+    /// Start unlimited
+    pub fn new(config: LatencyTargetConfig) -> Self {
+        Self { config, steps: Vec::new(), rate: None, met: None, missed: None, converged: false }
+    }
+
+    /// Current IOPS limit (None = unlimited)
+    pub fn rate(&self) -> Option<u64> {
+        self.rate
+    }
+
+    /// Record a step; returns the new IOPS limit if it changes
+    pub fn record(&mut self, iops: f64, p99_latency_us: u64) -> Option<u64> {
+        let met = p99_latency_us <= self.config.p99_us;
+        self.steps.push(LatencyTargetStep { rate_iops: self.rate, iops, p99_latency_us, met });
+
+        // Unlimited, the offered rate is what the device did
+        let offered = self.rate.unwrap_or(iops.round() as u64).max(1);
+        if met {
+            self.met = Some(self.met.map_or(offered, |rate| rate.max(offered)));
+            // An earlier miss at this rate or below was noise
+            self.missed = self.missed.filter(|&rate| rate > offered);
+            // Met without a limit: nothing to adjust
+            self.rate?;
+        } else {
+            self.missed = Some(self.missed.map_or(offered, |rate| rate.min(offered)));
+            // An earlier success at this rate or above no longer holds
+            self.met = self.met.filter(|&rate| rate < offered);
+        }
+
+        let next = if self.converged {
+            if met {
+                return None;
+            }
+            // Held rate missed: back off below it
+            ((offered as f64 * (1.0 - BACK_OFF_FRACTION)) as u64).max(1)
+        } else {
+            match (self.met, self.missed) {
+                (Some(met), Some(missed)) if (missed - met) as f64 <= missed as f64 * CONVERGED_FRACTION => {
+                    self.converged = true;
+                    met
+                }
+                (Some(met), Some(missed)) => met + (missed - met) / 2,
+                (None, Some(missed)) => (missed / 2).max(1),
+                (met, None) => met?.saturating_mul(2),
+            }
+        };
+        if Some(next) == self.rate {
+            return None;
+        }
+        self.rate = Some(next);
+        Some(next)
+    }
+
+    /// Steps and the sustainable IOPS at the final rate
+    pub fn result(&self) -> LatencyTargetResult {
+        let held: Vec<&LatencyTargetStep> = self.steps.iter()
+            .filter(|step| step.met && step.rate_iops == self.rate)
+            .collect();
+        let sustainable_iops = (!held.is_empty())
+            .then(|| held.iter().map(|step| step.iops).sum::<f64>() / held.len() as f64);
+        LatencyTargetResult {
+            target_p99_us: self.config.p99_us,
+            steps: self.steps.clone(),
+            final_rate_iops: self.rate,
+            sustainable_iops,
+            sustainable_p99_us: held.iter().map(|step| step.p99_latency_us).max(),
+            converged: self.converged,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Device whose p99 crosses 2ms at 25K IOPS
+    fn p99_at(iops: f64) -> u64 {
+        if iops <= 25_000.0 { 1000 + (iops / 25.0) as u64 } else { 6000 }
+    }
+
+    #[test]
+    fn test_bisection_converges_and_holds() {
+        let mut target = LatencyTarget::new(LatencyTargetConfig { p99_us: 2000, step_seconds: 1 });
+        for _ in 0..20 {
+            let iops = target.rate().map_or(40_000.0, |rate| (rate as f64).min(40_000.0));
+            target.record(iops, p99_at(iops));
+        }
+        let result = target.result();
+        assert!(result.converged);
+        let rate = result.final_rate_iops.unwrap();
+        assert!((24_500..=25_000).contains(&rate), "held {}", rate);
+        assert_eq!(result.sustainable_iops, Some(rate as f64));
+        assert!(result.sustainable_p99_us.unwrap() <= 2000);
+        assert_eq!(result.steps[0].rate_iops, None);
+
+        // A held rate that starts missing backs off
+        let missed = target.record(rate as f64, 2500).unwrap();
+        assert_eq!(missed, (rate as f64 * 0.95) as u64);
+    }
+
+    #[test]
+    fn test_target_met_unlimited() {
+        let mut target = LatencyTarget::new(LatencyTargetConfig { p99_us: 2000, step_seconds: 1 });
+        assert_eq!(target.record(40_000.0, 800), None);
+        assert_eq!(target.record(41_000.0, 900), None);
+        let result = target.result();
+        assert_eq!(result.final_rate_iops, None);
+        assert_eq!(result.sustainable_iops, Some(40_500.0));
+        assert!(!result.converged);
+
+        // Target below the device's latency floor: nothing meets it
+        let mut target = LatencyTarget::new(LatencyTargetConfig { p99_us: 10, step_seconds: 1 });
+        for _ in 0..30 {
+            let iops = target.rate().map_or(40_000.0, |rate| rate as f64);
+            target.record(iops, 500);
+        }
+        assert_eq!(target.rate(), Some(1));
+        assert_eq!(target.result().sustainable_iops, None);
+    }
+}
@@ -15,10 +15,31 @@
 //! - `protocol`: Message definitions and serialization
 //! - `node_service`: Node service implementation (Task 27)
 //! - `coordinator`: Distributed coordinator implementation (Task 28)
+//! - `api`: Optional HTTP API for submitting and monitoring coordinator jobs
+//! - `verify`: Two-phase verification (writer nodes, then reader nodes)
+//! - `roles`: Node roles from the clients file and per-role workloads
+//! - `thread_scaling`: Thread count sweep of `--threads auto`
+//! - `latency_target`: IOPS limit feedback of `--target-p99`
+//! - `partition`: Splitting a partitioned file across nodes, then workers
+//! - `session`: Keep-alives and reconnecting lost node connections during a test
+//! - `control`: Pausing, rate limiting and re-mixing a running test
+//! - `jobs`: Several concurrent jobs from one configuration file
+//! - `visibility`: Cross-node create/rename visibility latency of `--visibility-test`
 
 pub mod protocol;
 pub mod node_service;
 pub mod coordinator;
+pub mod api;
+pub mod verify;
+pub mod roles;
+pub mod mpi;
+pub mod thread_scaling;
+pub mod latency_target;
+pub mod partition;
+pub mod session;
+pub mod control;
+pub mod jobs;
+pub mod visibility;
 
 // Re-export key types
 pub use protocol::{
@@ -31,9 +52,13 @@ pub use protocol::{
     HeartbeatMessage,
     ResultsMessage,
     ErrorMessage,
+    StatusMessage,
+    ActiveTestStatus,
     WorkerStatsSnapshot,
     PROTOCOL_VERSION,
+    MIN_PROTOCOL_VERSION,
 };
 
 pub use node_service::NodeService;
-pub use coordinator::DistributedCoordinator;
+pub use coordinator::{DistributedCoordinator, JobProgress, LiveStatus, PingResult, generate_job_id, ping_node};
+pub use api::ApiServer;
@@ -0,0 +1,207 @@
+//! Rank-aware launch under mpirun / srun
+//!
+//! HPC harnesses start one process per rank with the same command line
+//! (`mpirun -np 64 iopulse --mode mpi ...`). Each rank finds its rank and
+//! the world size in the launcher's environment, starts a node service on
+//! its host and publishes the service address in a rendezvous directory on a
+//! shared filesystem. Rank 0 waits for every address, coordinates the test
+//! across all ranks (itself included) and writes a `done` marker, on which
+//! the other ranks shut their services down and exit.
+//!
+//! No MPI library is linked; only the environment variables set by Open MPI,
+//! MPICH/Intel MPI (Hydra) and Slurm are read.
+//!
+//! # Example
+//!
+//! ```
+//! use iopulse::distributed::mpi::RankInfo;
+//!
+//! let env = [("SLURM_PROCID", "3"), ("SLURM_NTASKS", "8"), ("SLURM_LOCALID", "1"), ("SLURM_JOB_ID", "4711")];
+//! let rank = RankInfo::from_vars(|name| env.iter().find(|(k, _)| *k == name).map(|(_, v)| v.to_string())).unwrap();
+//! assert_eq!((rank.rank, rank.size, rank.local_rank), (3, 8, 1));
+//! assert_eq!(rank.job, "4711");
+//! ```
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// (rank, size, local rank) variables of each launcher, in lookup order
+const RANK_VARS: &[(&str, &str, &str)] = &[
+    ("OMPI_COMM_WORLD_RANK", "OMPI_COMM_WORLD_SIZE", "OMPI_COMM_WORLD_LOCAL_RANK"),
+    ("PMI_RANK", "PMI_SIZE", "MPI_LOCALRANKID"),
+    ("SLURM_PROCID", "SLURM_NTASKS", "SLURM_LOCALID"),
+];
+
+/// Variables identifying the job, so concurrent jobs use separate rendezvous
+const JOB_VARS: &[&str] = &["SLURM_JOB_ID", "PMIX_NAMESPACE", "OMPI_MCA_ess_base_jobid", "PMI_KVSNAME"];
+
+/// How often rendezvous files are polled
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Position of this process in the launch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RankInfo {
+    /// Rank in the world (0 coordinates)
+    pub rank: usize,
+    /// Number of ranks
+    pub size: usize,
+    /// Rank among the ranks on this host (0 if unknown)
+    pub local_rank: usize,
+    /// Job identifier from the launcher ("default" if none is set)
+    pub job: String,
+}
+
+impl RankInfo {
+    /// Read rank and size from the process environment
+    pub fn from_env() -> Result<Self> {
+        Self::from_vars(|name| std::env::var(name).ok())
+            .context("No MPI rank in the environment (expected OMPI_COMM_WORLD_RANK, PMI_RANK or SLURM_PROCID); launch with mpirun or srun")
+    }
+
+    /// Read rank and size through a variable lookup
+    pub fn from_vars(get: impl Fn(&str) -> Option<String>) -> Option<Self> {
+        let parse = |name: &str| get(name).and_then(|v| v.trim().parse::<usize>().ok());
+        let (rank, size, local_rank) = RANK_VARS.iter().find_map(|(rank, size, local)| {
+            Some((parse(rank)?, parse(size)?, parse(local).unwrap_or(0)))
+        })?;
+        if rank >= size {
+            return None;
+        }
+
+        let mut job = JOB_VARS.iter().find_map(|name| get(name)).unwrap_or_else(|| "default".to_string());
+        if let Some(step) = get("SLURM_STEP_ID") {
+            job = format!("{}.{}", job, step);
+        }
+        // The job id becomes a directory name
+        let job = job.chars().map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' { c } else { '_' }).collect();
+
+        Some(Self { rank, size, local_rank, job })
+    }
+}
+
+/// Address exchange through a directory on a shared filesystem
+#[derive(Debug)]
+pub struct Rendezvous {
+    dir: PathBuf,
+}
+
+impl Rendezvous {
+    /// Use `<base>/.iopulse-mpi-<job>`, creating it if needed
+    pub fn new(base: &Path, job: &str) -> Result<Self> {
+        let dir = base.join(format!(".iopulse-mpi-{}", job));
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create rendezvous directory {}", dir.display()))?;
+        Ok(Self { dir })
+    }
+
+    fn rank_file(&self, rank: usize) -> PathBuf {
+        self.dir.join(format!("rank-{}", rank))
+    }
+
+    fn done_file(&self) -> PathBuf {
+        self.dir.join("done")
+    }
+
+    /// Publish the node service address of a rank
+    pub fn publish(&self, rank: usize, address: &str) -> Result<()> {
+        // Write then rename, so rank 0 never reads a partial address
+        let tmp = self.dir.join(format!(".rank-{}.tmp", rank));
+        std::fs::write(&tmp, address).context("Failed to write rendezvous file")?;
+        std::fs::rename(&tmp, self.rank_file(rank)).context("Failed to publish rendezvous file")?;
+        Ok(())
+    }
+
+    /// Wait until all `size` ranks published; returns their addresses by rank
+    pub fn wait_for_ranks(&self, size: usize, timeout: Duration) -> Result<Vec<String>> {
+        let start = Instant::now();
+        loop {
+            let published: Vec<Option<String>> = (0..size)
+                .map(|rank| std::fs::read_to_string(self.rank_file(rank)).ok())
+                .collect();
+            let missing = published.iter().filter(|a| a.is_none()).count();
+            if missing == 0 {
+                return Ok(published.into_iter().map(|a| a.unwrap_or_default().trim().to_string()).collect());
+            }
+            if start.elapsed() > timeout {
+                anyhow::bail!("{} of {} ranks did not publish an address in {} within {}s",
+                    missing, size, self.dir.display(), timeout.as_secs());
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    /// Tell the other ranks the test is over (rank 0)
+    pub fn finish(&self, success: bool) -> Result<()> {
+        std::fs::write(self.done_file(), if success { "ok" } else { "failed" })
+            .context("Failed to write rendezvous done marker")
+    }
+
+    /// Wait for rank 0 to finish; returns whether the test succeeded
+    pub fn wait_for_finish(&self) -> bool {
+        loop {
+            if let Ok(status) = std::fs::read_to_string(self.done_file()) {
+                return status.trim() == "ok";
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    /// Withdraw a rank's address once it has seen the done marker
+    pub fn leave(&self, rank: usize) {
+        let _ = std::fs::remove_file(self.rank_file(rank));
+    }
+
+    /// Remove the directory once the other ranks left (rank 0)
+    ///
+    /// Gives up after `timeout` and leaves the directory in place.
+    pub fn cleanup(&self, size: usize, timeout: Duration) {
+        self.leave(0);
+        let start = Instant::now();
+        while (1..size).any(|rank| self.rank_file(rank).exists()) {
+            if start.elapsed() > timeout {
+                return;
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_rank_from_open_mpi_vars() {
+        let env: HashMap<&str, &str> = [
+            ("OMPI_COMM_WORLD_RANK", "0"), ("OMPI_COMM_WORLD_SIZE", "4"),
+            ("OMPI_COMM_WORLD_LOCAL_RANK", "0"), ("PMIX_NAMESPACE", "prterun-host-123@1"),
+            // Slurm variables of the allocation are ignored when Open MPI set its own
+            ("SLURM_PROCID", "7"), ("SLURM_NTASKS", "8"),
+        ].into_iter().collect();
+        let rank = RankInfo::from_vars(|name| env.get(name).map(|v| v.to_string())).unwrap();
+        assert_eq!((rank.rank, rank.size), (0, 4));
+        assert_eq!(rank.job, "prterun-host-123_1");
+
+        assert!(RankInfo::from_vars(|_| None).is_none());
+    }
+
+    #[test]
+    fn test_rendezvous_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let rendezvous = Rendezvous::new(dir.path(), "job").unwrap();
+        rendezvous.publish(1, "node-b:10000").unwrap();
+        assert!(rendezvous.wait_for_ranks(2, Duration::ZERO).is_err());
+        rendezvous.publish(0, "node-a:9999\n").unwrap();
+        assert_eq!(rendezvous.wait_for_ranks(2, Duration::ZERO).unwrap(), vec!["node-a:9999", "node-b:10000"]);
+
+        rendezvous.finish(true).unwrap();
+        assert!(rendezvous.wait_for_finish());
+        rendezvous.leave(1);
+        rendezvous.cleanup(2, Duration::ZERO);
+        assert!(!dir.path().join(".iopulse-mpi-job").exists());
+    }
+}
@@ -8,16 +8,30 @@
 //! - Sends periodic heartbeats
 //! - Implements dead man's switch (self-stop if coordinator disappears)
 //! - Sends final results
+//!
+//! The service is meant to be left running as a daemon. Connections are
+//! handled concurrently so STATUS queries are answered while a test runs.
+//! Each test is a job with its own ID and fresh workers; jobs run one at a
+//! time, and a job arriving while another runs either waits in a FIFO queue
+//! (up to `max_queued_jobs`) or is rejected with a BUSY error. Connections that
+//! stay idle during setup are closed after the idle timeout.
 
 use crate::distributed::protocol::*;
+use crate::distributed::session::{CoordinatorLink, ResumeSender};
+use crate::distributed::visibility;
+use crate::util::preflight::{self, PreflightReport};
 use crate::stats::WorkerStats;
+use crate::worker::failure::{run_guarded, WorkerFailure};
 use anyhow::{Context, Result};
 use std::sync::{Arc, Mutex};
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::time::Duration;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
 use tokio::net::{TcpListener, TcpStream};
 use tokio::time::sleep;
 
+/// Default idle timeout for connections during test setup (seconds)
+pub const DEFAULT_IDLE_TIMEOUT_SECS: u64 = 600;
+
 /// Node service
 ///
 /// Runs on each node in distributed mode, accepting commands from coordinator.
@@ -27,6 +41,130 @@ pub struct NodeService {
     
     /// Node identifier (IP address or hostname)
     node_id: String,
+    
+    /// Close connections that send nothing for this long before the test starts
+    idle_timeout: Option<Duration>,
+    
+    /// Jobs allowed to wait while another job runs (0 = reject with BUSY)
+    max_queued_jobs: usize,
+    
+    /// State shared by all connections
+    state: ServiceState,
+}
+
+/// Service-wide state shared by all connections
+struct ServiceState {
+    /// When the service started
+    started: Instant,
+    
+    /// Jobs started since the service started (also the job sequence number)
+    tests_run: AtomicU64,
+    
+    /// Single permit held by the running job; waiters queue in FIFO order
+    job_permit: tokio::sync::Semaphore,
+    
+    /// Jobs currently waiting for the permit
+    queued_jobs: AtomicUsize,
+    
+    /// Job currently holding the node (at most one)
+    active_test: Mutex<Option<ActiveTest>>,
+    
+    /// Where RESUME connections for the running job go: (job ID, channel)
+    resume: Mutex<Option<(String, ResumeSender)>>,
+}
+
+/// Lets RESUME connections reach the running job until dropped
+struct ResumeRegistration<'a> {
+    state: &'a ServiceState,
+}
+
+impl Drop for ResumeRegistration<'_> {
+    fn drop(&mut self) {
+        *self.state.resume.lock().unwrap() = None;
+    }
+}
+
+/// Job currently holding the node
+struct ActiveTest {
+    job_id: String,
+    coordinator: String,
+    started: Instant,
+    num_workers: usize,
+}
+
+/// Claim on the node's single job slot
+///
+/// Releasing the claim (on drop) makes the node available to the next queued
+/// job, whether this one finished or failed part-way.
+struct TestSlot<'a> {
+    state: &'a ServiceState,
+    _permit: tokio::sync::SemaphorePermit<'a>,
+}
+
+impl TestSlot<'_> {
+    fn set_num_workers(&self, num_workers: usize) {
+        if let Some(ref mut test) = *self.state.active_test.lock().unwrap() {
+            test.num_workers = num_workers;
+        }
+    }
+    
+    /// Adopt the coordinator's job ID, if it sent one
+    fn set_job_id(&self, job_id: Option<&str>) {
+        if let (Some(job_id), Some(ref mut test)) = (job_id, &mut *self.state.active_test.lock().unwrap()) {
+            test.job_id = job_id.to_string();
+        }
+    }
+    
+    /// Count the test as run (after its CONFIG passed the protocol check)
+    fn count_test(&self) {
+        self.state.tests_run.fetch_add(1, Ordering::Relaxed);
+    }
+    
+    fn job_id(&self) -> String {
+        self.state.active_test.lock().unwrap().as_ref()
+            .map(|test| test.job_id.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl Drop for TestSlot<'_> {
+    fn drop(&mut self) {
+        *self.state.active_test.lock().unwrap() = None;
+    }
+}
+
+/// Worker thread of the running job
+///
+/// If the job ends early (e.g., the coordinator disconnects before START),
+/// dropping this stops the workers and waits for them, so the next job starts
+/// with a clean node instead of competing with leftover threads.
+struct JobWorkers {
+    stop_flag: Arc<AtomicBool>,
+    handle: Option<std::thread::JoinHandle<Result<()>>>,
+}
+
+impl JobWorkers {
+    fn is_finished(&self) -> bool {
+        self.handle.as_ref().is_none_or(|h| h.is_finished())
+    }
+    
+    /// Wait for the workers to exit
+    fn join(mut self) -> Result<()> {
+        match self.handle.take() {
+            Some(handle) => handle.join()
+                .map_err(|_| anyhow::anyhow!("Worker thread panicked"))?,
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for JobWorkers {
+    fn drop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            self.stop_flag.store(true, Ordering::Relaxed);
+            let _ = handle.join();
+        }
+    }
 }
 
 impl NodeService {
@@ -38,9 +176,29 @@ impl NodeService {
         Ok(Self {
             listen_port,
             node_id,
+            idle_timeout: Some(Duration::from_secs(DEFAULT_IDLE_TIMEOUT_SECS)),
+            max_queued_jobs: 0,
+            state: ServiceState {
+                started: Instant::now(),
+                tests_run: AtomicU64::new(0),
+                job_permit: tokio::sync::Semaphore::new(1),
+                queued_jobs: AtomicUsize::new(0),
+                active_test: Mutex::new(None),
+                resume: Mutex::new(None),
+            },
         })
     }
     
+    /// Set the idle timeout for connections during test setup (None disables it)
+    pub fn set_idle_timeout(&mut self, idle_timeout: Option<Duration>) {
+        self.idle_timeout = idle_timeout;
+    }
+    
+    /// Set how many jobs may wait while another runs (0 rejects them with BUSY)
+    pub fn set_max_queued_jobs(&mut self, max_queued_jobs: usize) {
+        self.max_queued_jobs = max_queued_jobs;
+    }
+    
     /// Run the node service
     ///
     /// Listens for connections from coordinator and handles test execution.
@@ -51,83 +209,317 @@ impl NodeService {
         
         println!("Node service listening on port {}", self.listen_port);
         println!("Node ID: {}", self.node_id);
+        if let Some(timeout) = self.idle_timeout {
+            println!("Idle timeout: {}s", timeout.as_secs());
+        }
         println!("Waiting for coordinator connection...");
         
+        let service = Arc::new(self);
+        
         loop {
             // Accept connection from coordinator
-            let (stream, addr) = listener.accept().await
-                .context("Failed to accept connection")?;
+            let (stream, addr) = match listener.accept().await {
+                Ok(conn) => conn,
+                Err(e) => {
+                    // Transient (e.g., out of file descriptors): keep serving
+                    tracing::warn!("Failed to accept connection: {}", e);
+                    sleep(Duration::from_millis(100)).await;
+                    continue;
+                }
+            };
             
             println!("Coordinator connected from: {}", addr);
             
-            // Handle this test (blocks until test completes)
-            if let Err(e) = self.handle_test(stream).await {
-                eprintln!("Test failed: {}", e);
+            // Handle each connection on its own task so STATUS and BUSY
+            // replies work while a test is running
+            let service = service.clone();
+            tokio::spawn(async move {
+                match service.handle_test(stream, addr.to_string()).await {
+                    Ok(true) => println!("Test complete. Waiting for next connection..."),
+                    Ok(false) => {}
+                    Err(e) => tracing::error!("Test failed ({}): {:#}", addr, e),
+                }
+            });
+        }
+    }
+    
+    /// Claim the job slot for a coordinator
+    ///
+    /// Waits in the queue if a job is running and the queue has room;
+    /// returns None (BUSY) if it doesn't.
+    async fn claim_test_slot(&self, coordinator: &str) -> Option<TestSlot<'_>> {
+        let permit = match self.state.job_permit.try_acquire() {
+            Ok(permit) => permit,
+            Err(_) => {
+                let position = self.state.queued_jobs.fetch_add(1, Ordering::SeqCst);
+                if position >= self.max_queued_jobs {
+                    self.state.queued_jobs.fetch_sub(1, Ordering::SeqCst);
+                    return None;
+                }
+                
+                println!("Queued job from {} ({} ahead)", coordinator, position + 1);
+                let permit = self.state.job_permit.acquire().await;
+                self.state.queued_jobs.fetch_sub(1, Ordering::SeqCst);
+                permit.ok()?
             }
-            
-            println!("Test complete. Waiting for next connection...");
+        };
+        
+        // Counted once the CONFIG is accepted (see TestSlot::count_test)
+        let seq = self.state.tests_run.load(Ordering::Relaxed) + 1;
+        *self.state.active_test.lock().unwrap() = Some(ActiveTest {
+            job_id: format!("{}-{}", self.node_id, seq),
+            coordinator: coordinator.to_string(),
+            started: Instant::now(),
+            num_workers: 0,
+        });
+        
+        Some(TestSlot { state: &self.state, _permit: permit })
+    }
+    
+    /// Describe the running test for a BUSY error
+    fn busy_reason(&self) -> String {
+        match *self.state.active_test.lock().unwrap() {
+            Some(ref test) => format!("BUSY: node is running job {} for coordinator {} (started {:.0}s ago, {} queued)",
+                test.job_id, test.coordinator, test.started.elapsed().as_secs_f64(),
+                self.state.queued_jobs.load(Ordering::SeqCst)),
+            None => "BUSY: node is running another test".to_string(),
         }
     }
     
-    /// Handle a single test execution
-    async fn handle_test(&self, mut stream: TcpStream) -> Result<()> {
-        // Check if first message is PrepareFiles or Config
-        println!("Waiting for first message (PrepareFiles or CONFIG)...");
-        let first_msg = read_message(&mut stream).await?;
+    /// Build a STATUS_REPORT for this node
+    fn status(&self) -> StatusMessage {
+        let current_test = self.state.active_test.lock().unwrap().as_ref()
+            .map(|test| ActiveTestStatus {
+                job_id: test.job_id.clone(),
+                coordinator: test.coordinator.clone(),
+                elapsed_ns: test.started.elapsed().as_nanos() as u64,
+                num_workers: test.num_workers,
+            });
+        
+        StatusMessage {
+            node_id: self.node_id.clone(),
+            software_version: env!("CARGO_PKG_VERSION").to_string(),
+            min_protocol_version: MIN_PROTOCOL_VERSION,
+            max_protocol_version: PROTOCOL_VERSION,
+            uptime_ns: self.state.started.elapsed().as_nanos() as u64,
+            tests_run: self.state.tests_run.load(Ordering::Relaxed),
+            current_test,
+            queued_jobs: self.state.queued_jobs.load(Ordering::SeqCst),
+            max_queued_jobs: self.max_queued_jobs,
+            capabilities: node_capabilities(),
+        }
+    }
+    
+    /// Read the next message, giving up after the idle timeout
+    async fn read_message_idle(&self, stream: &mut TcpStream) -> Result<Message> {
+        match self.idle_timeout {
+            Some(timeout) => tokio::time::timeout(timeout, read_message(stream)).await
+                .map_err(|_| anyhow::anyhow!("Connection idle for {}s, closing", timeout.as_secs()))?,
+            None => read_message(stream).await,
+        }
+    }
+    
+    /// Handle a single connection
+    ///
+    /// Returns Ok(false) if the connection ended without running a test
+    /// (STATUS query, rejected as BUSY, or closed during setup).
+    async fn handle_test(&self, mut stream: TcpStream, peer: String) -> Result<bool> {
+        // Check if first message is HELLO, STATUS, PrepareFiles or Config
+        println!("Waiting for first message (HELLO, STATUS, PREFLIGHT, VISIBILITY, PrepareFiles or CONFIG)...");
+        
+        // Version handshake (v3+ coordinators). Legacy coordinators skip it and
+        // their protocol_version is checked against our supported range instead.
+        let mut negotiated = None;
+        let mut answered = false;
+        let first_msg = loop {
+            let msg = match self.read_message_idle(&mut stream).await {
+                Ok(msg) => msg,
+                // Client hung up after a STATUS query (e.g., iopulse ping),
+                // preflight check or the last visibility test step
+                Err(_) if answered => return Ok(false),
+                Err(e) => return Err(e),
+            };
+            
+            match msg {
+                Message::Hello(hello) => {
+                    negotiated = Some(self.handle_hello(&mut stream, hello).await?);
+                }
+                Message::Status => {
+                    let version = negotiated.unwrap_or(PROTOCOL_VERSION);
+                    write_message_for_version(&mut stream, &Message::StatusReport(self.status()), version).await?;
+                    answered = true;
+                }
+                Message::Preflight(config) => {
+                    let report = tokio::task::spawn_blocking(move || PreflightReport::run(&config)).await?;
+                    for check in report.failures() {
+                        println!("Preflight: {} {} failed: {}", check.path.display(), check.check,
+                            check.error.as_deref().unwrap_or_default());
+                    }
+                    let version = negotiated.unwrap_or(PROTOCOL_VERSION);
+                    write_message_for_version(&mut stream, &Message::PreflightReport(report), version).await?;
+                    answered = true;
+                }
+                Message::Visibility(step) => {
+                    let node_id = self.node_id.clone();
+                    let report = tokio::task::spawn_blocking(move || visibility::execute(&step, node_id)).await?;
+                    let version = negotiated.unwrap_or(PROTOCOL_VERSION);
+                    write_message_for_version(&mut stream, &Message::VisibilityReport(report), version).await?;
+                    answered = true;
+                }
+                other => break other,
+            }
+        };
+        
+        // A coordinator replacing the lost connection of the running job
+        if let Message::Resume(resume) = first_msg {
+            self.handle_resume(stream, resume, negotiated.unwrap_or(LEGACY_PROTOCOL_VERSION)).await?;
+            return Ok(false);
+        }
+        
+        if !matches!(first_msg, Message::PrepareFiles(_) | Message::Config(_)) {
+            anyhow::bail!("Expected PrepareFiles or CONFIG, got {:?}", first_msg);
+        }
+        
+        // Only one job at a time (extra jobs queue or get BUSY)
+        let Some(slot) = self.claim_test_slot(&peer).await else {
+            let reason = self.busy_reason();
+            println!("Rejecting test from {}: {}", peer, reason);
+            let error = ErrorMessage {
+                node_id: self.node_id.clone(),
+                error: reason,
+                elapsed_ns: 0,
+                worker_id: None,
+                backtrace: None,
+            };
+            write_message(&mut stream, &Message::Error(error)).await?;
+            return Ok(false);
+        };
         
         match first_msg {
             Message::PrepareFiles(prepare_msg) => {
                 // Handle file preparation
                 println!("Received PrepareFiles message");
-                self.handle_prepare_files(&mut stream, prepare_msg).await?;
+                let version = self.handle_prepare_files(&mut stream, prepare_msg, negotiated).await?;
                 
                 // Now wait for CONFIG message
                 println!("Waiting for CONFIG message...");
-                let config_msg = match read_message(&mut stream).await {
+                let config_msg = match self.read_message_idle(&mut stream).await {
                     Ok(Message::Config(msg)) => {
                         println!("Received CONFIG message successfully");
                         msg
                     }
                     Ok(other) => {
                         let err = format!("Expected CONFIG message, got {:?}", other);
-                        eprintln!("{}", err);
+                        tracing::error!("{}", err);
                         anyhow::bail!(err)
                     }
                     Err(e) => {
-                        eprintln!("Failed to read/deserialize CONFIG: {:#}", e);
+                        tracing::error!("Failed to read/deserialize CONFIG: {:#}", e);
                         anyhow::bail!("Failed to deserialize message: {:#}", e)
                     }
                 };
                 
-                self.handle_test_execution(stream, config_msg).await
+                self.handle_test_execution(stream, config_msg, Some(version), &slot).await?;
             }
             Message::Config(config_msg) => {
                 // No file preparation needed, proceed directly to test
                 println!("Received CONFIG message successfully");
-                self.handle_test_execution(stream, config_msg).await
-            }
-            other => {
-                anyhow::bail!("Expected PrepareFiles or CONFIG, got {:?}", other)
+                self.handle_test_execution(stream, config_msg, negotiated, &slot).await?;
             }
+            _ => unreachable!("checked above"),
         }
+        
+        Ok(true)
     }
     
-    /// Handle file preparation (distributed filling)
-    async fn handle_prepare_files(&self, stream: &mut TcpStream, prepare_msg: PrepareFilesMessage) -> Result<()> {
-        use std::time::Instant;
+    /// Hand a RESUME connection to the running job it names
+    async fn handle_resume(&self, mut stream: TcpStream, resume: ResumeMessage, version: u32) -> Result<()> {
+        let sender = match *self.state.resume.lock().unwrap() {
+            Some((ref job_id, ref sender)) if *job_id == resume.job_id => Some(sender.clone()),
+            _ => None,
+        };
+        println!("Received RESUME for job {}", resume.job_id);
         
-        // Validate protocol version
-        if prepare_msg.protocol_version != PROTOCOL_VERSION {
+        let Some(sender) = sender else {
+            let error = ErrorMessage {
+                node_id: self.node_id.clone(),
+                error: format!("no running job {} to resume", resume.job_id),
+                elapsed_ns: 0,
+                worker_id: None,
+                backtrace: None,
+            };
+            write_message_for_version(&mut stream, &Message::Error(error), version).await?;
+            return Ok(());
+        };
+        if sender.send((stream, version)).await.is_err() {
+            // The job ended in the meantime; the coordinator's next attempt is rejected
+            println!("Job {} ended before it could resume", resume.job_id);
+        }
+        Ok(())
+    }
+    
+    /// Answer a HELLO with the highest protocol version both sides support
+    async fn handle_hello(&self, stream: &mut TcpStream, hello: HelloMessage) -> Result<u32> {
+        let Some(version) = negotiate_version(hello.min_version, hello.max_version) else {
+            let error = ErrorMessage {
+                node_id: self.node_id.clone(),
+                error: format!("No common protocol version: coordinator supports {}-{}, node supports {}-{}",
+                    hello.min_version, hello.max_version, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION),
+                elapsed_ns: 0,
+                worker_id: None,
+                backtrace: None,
+            };
+            write_message(stream, &Message::Error(error)).await?;
+            anyhow::bail!("Protocol version negotiation failed");
+        };
+        
+        let ack = HelloAckMessage {
+            node_id: self.node_id.clone(),
+            selected_version: version,
+            min_version: MIN_PROTOCOL_VERSION,
+            max_version: PROTOCOL_VERSION,
+        };
+        write_message_for_version(stream, &Message::HelloAck(ack), version).await?;
+        println!("Negotiated protocol version {}", version);
+        
+        Ok(version)
+    }
+    
+    /// Check the protocol version carried by PrepareFiles/CONFIG
+    ///
+    /// With a negotiated version the message must match it; without one
+    /// (legacy coordinator) any supported version is accepted.
+    async fn check_protocol_version(&self, stream: &mut TcpStream, requested: u32, negotiated: Option<u32>) -> Result<u32> {
+        let accepted = match negotiated {
+            Some(version) => requested == version,
+            None => is_supported_version(requested),
+        };
+        
+        if !accepted {
             let error = ErrorMessage {
                 node_id: self.node_id.clone(),
-                error: format!("Protocol version mismatch: coordinator={}, node={}", 
-                    prepare_msg.protocol_version, PROTOCOL_VERSION),
+                error: format!("Protocol version mismatch: coordinator={}, node supports {}-{}", 
+                    requested, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION),
                 elapsed_ns: 0,
+                worker_id: None,
+                backtrace: None,
             };
             write_message(stream, &Message::Error(error)).await?;
             anyhow::bail!("Protocol version mismatch");
         }
         
+        Ok(requested)
+    }
+    
+    /// Handle file preparation (distributed filling)
+    ///
+    /// Returns the protocol version in use for the rest of the connection.
+    async fn handle_prepare_files(&self, stream: &mut TcpStream, prepare_msg: PrepareFilesMessage, negotiated: Option<u32>) -> Result<u32> {
+        use std::time::Instant;
+        
+        // Validate protocol version
+        let version = self.check_protocol_version(stream, prepare_msg.protocol_version, negotiated).await?;
+        
         println!("  Files to prepare: {}", prepare_msg.file_list.len());
         println!("  File size/region: {} bytes", prepare_msg.file_size);
         println!("  Start offset: {}", prepare_msg.start_offset);
@@ -135,9 +527,13 @@ impl NodeService {
         println!("  Pattern: {:?}", prepare_msg.fill_pattern);
         
         let start = Instant::now();
+        let prep_limit = prepare_msg.rate_limit.map(crate::target::prep_limit::PrepLimit::new);
+        if let Some(ref limit) = prep_limit {
+            println!("  Rate limit: {}", limit);
+        }
         
         // Create/fill files or regions
-        let (files_created, files_filled) = if prepare_msg.start_offset > 0 {
+        let prepared = if prepare_msg.start_offset > 0 {
             // Region pre-allocation (distributed mode)
             preallocate_region(
                 &prepare_msg.file_list[0],
@@ -145,22 +541,43 @@ impl NodeService {
                 prepare_msg.file_size,
                 prepare_msg.fill_files,
                 prepare_msg.fill_pattern,
-            )?
+                prepare_msg.prealloc_mode,
+                prepare_msg.fill_regions,
+                prep_limit,
+            )
         } else if prepare_msg.fill_files {
             // Full file filling
-            let filled = validate_and_fill_files_distributed(
+            validate_and_fill_files_distributed(
                 &prepare_msg.file_list,
                 prepare_msg.file_size,
                 prepare_msg.fill_pattern,
-            )?;
-            (prepare_msg.file_list.len(), filled)
+                prepare_msg.custom_pattern.as_ref(),
+                prepare_msg.fill_regions.as_deref(),
+                prep_limit,
+            ).map(|filled| (prepare_msg.file_list.len(), filled))
         } else {
             // Just create empty files
-            let created = create_files_distributed(
+            create_files_distributed(
                 &prepare_msg.file_list,
                 prepare_msg.file_size,
-            )?;
-            (created, 0)
+            ).map(|created| (created, 0))
+        };
+        
+        // Tell the coordinator why, rather than just dropping the connection
+        let (files_created, files_filled) = match prepared {
+            Ok(counts) => counts,
+            Err(e) => {
+                let e = preflight::explain(e);
+                let error = ErrorMessage {
+                    node_id: self.node_id.clone(),
+                    error: format!("{:#}", e),
+                    elapsed_ns: start.elapsed().as_nanos() as u64,
+                    worker_id: None,
+                    backtrace: None,
+                };
+                write_message_for_version(stream, &Message::Error(error), version).await?;
+                return Err(e);
+            }
         };
         
         let duration = start.elapsed();
@@ -169,31 +586,30 @@ impl NodeService {
         
         // Send FilesReady message
         let ready = FilesReadyMessage {
-            protocol_version: PROTOCOL_VERSION,
+            protocol_version: version,
             node_id: self.node_id.clone(),
             files_created,
             files_filled,
             duration_ns: duration.as_nanos() as u64,
         };
-        write_message(stream, &Message::FilesReady(ready)).await?;
+        write_message_for_version(stream, &Message::FilesReady(ready), version).await?;
         println!("Sent FilesReady message");
         
-        Ok(())
+        Ok(version)
     }
     
     /// Handle test execution (after files are prepared)
-    async fn handle_test_execution(&self, mut stream: TcpStream, config_msg: ConfigMessage) -> Result<()> {
+    async fn handle_test_execution(&self, mut stream: TcpStream, config_msg: ConfigMessage, negotiated: Option<u32>, slot: &TestSlot<'_>) -> Result<()> {
         
         // Validate protocol version
-        if config_msg.protocol_version != PROTOCOL_VERSION {
-            let error = ErrorMessage {
-                node_id: self.node_id.clone(),
-                error: format!("Protocol version mismatch: coordinator={}, node={}", 
-                    config_msg.protocol_version, PROTOCOL_VERSION),
-                elapsed_ns: 0,
-            };
-            write_message(&mut stream, &Message::Error(error)).await?;
-            anyhow::bail!("Protocol version mismatch");
+        let version = self.check_protocol_version(&mut stream, config_msg.protocol_version, negotiated).await?;
+        slot.count_test();
+        slot.set_num_workers(config_msg.config.workers.threads);
+        slot.set_job_id(config_msg.job_id.as_deref());
+        let job_id = slot.job_id();
+        println!("Starting job {}", job_id);
+        if let Some(ref node_uid) = config_msg.node_uid {
+            println!("  Node UID: {}", node_uid);
         }
         
         println!("Received configuration:");
@@ -202,6 +618,11 @@ impl NodeService {
         println!("  Worker threads: {}", num_workers);
         println!("  Worker ID range: {}-{}", config_msg.worker_id_start, config_msg.worker_id_end);
         println!("  Skip preallocation: {}", config_msg.skip_preallocation);
+        if let Some(ref role) = config_msg.role {
+            println!("  Role: {} (read {}%, write {}%, block size {})", role,
+                config_msg.config.workload.read_percent, config_msg.config.workload.write_percent,
+                config_msg.config.workload.block_size);
+        }
         
         if let Some(ref file_list) = config_msg.file_list {
             println!("  File list: {} files", file_list.len());
@@ -210,17 +631,46 @@ impl NodeService {
             }
         }
         
+        // Reject O_DIRECT/block size mismatches and unsafe device writes before workers start
+        if let Err(e) = preflight_direct_io(&config_msg.config).and_then(|_| preflight_block_devices(&config_msg.config)) {
+            let e = preflight::explain(e);
+            let error = ErrorMessage {
+                node_id: self.node_id.clone(),
+                error: format!("{:#}", e),
+                elapsed_ns: 0,
+                worker_id: None,
+                backtrace: None,
+            };
+            write_message_for_version(&mut stream, &Message::Error(error), version).await?;
+            return Err(e);
+        }
+        
+        // Apply this test's log level and per-worker log directory
+        if let Err(e) = crate::util::logging::configure(
+            config_msg.config.output.verbosity,
+            config_msg.config.runtime.debug,
+            config_msg.config.runtime.log_dir.as_deref(),
+        ) {
+            tracing::warn!("Per-worker logs disabled: {:#}", e);
+        }
+        
         // Prepare workers (spawn threads in separate task)
         println!("Preparing {} worker threads...", num_workers);
         
         // Modify config to skip preallocation if coordinator already did it
         let mut config = config_msg.config;
+        if let Some(assignment) = config_msg.verify {
+            println!("  Verification role: {} (pattern {}, seed {})",
+                assignment.role, assignment.pattern, assignment.seed);
+            assignment.apply(&mut config);
+        }
         if config_msg.skip_preallocation {
             for target in &mut config.targets {
                 target.preallocate = false;
                 target.no_refill = true;  // Also skip auto-refill
             }
         }
+        config.workers.unit_id = config_msg.node_uid.clone();
         
         // Create shared state for workers
         use std::sync::{Arc, Mutex};
@@ -228,42 +678,26 @@ impl NodeService {
         
         let stop_flag = Arc::new(AtomicBool::new(false));
         
-        // Create shared snapshots for live stats (like standalone mode)
-        let shared_snapshots: Arc<Mutex<Vec<crate::worker::StatsSnapshot>>> = Arc::new(Mutex::new(
-            vec![crate::worker::StatsSnapshot {
-                read_ops: 0,
-                write_ops: 0,
-                read_bytes: 0,
-                write_bytes: 0,
-                errors: 0,
-                avg_latency_us: 0.0,
-                read_latency: crate::stats::simple_histogram::SimpleHistogram::new(),
-                write_latency: crate::stats::simple_histogram::SimpleHistogram::new(),
-                metadata_open_ops: 0,
-                metadata_close_ops: 0,
-                metadata_stat_ops: 0,
-                metadata_setattr_ops: 0,
-                metadata_mkdir_ops: 0,
-                metadata_rmdir_ops: 0,
-                metadata_unlink_ops: 0,
-                metadata_rename_ops: 0,
-                metadata_readdir_ops: 0,
-                metadata_fsync_ops: 0,
-                metadata_open_latency: crate::stats::simple_histogram::SimpleHistogram::new(),
-                metadata_close_latency: crate::stats::simple_histogram::SimpleHistogram::new(),
-                metadata_stat_latency: crate::stats::simple_histogram::SimpleHistogram::new(),
-                metadata_setattr_latency: crate::stats::simple_histogram::SimpleHistogram::new(),
-                metadata_mkdir_latency: crate::stats::simple_histogram::SimpleHistogram::new(),
-                metadata_rmdir_latency: crate::stats::simple_histogram::SimpleHistogram::new(),
-                metadata_unlink_latency: crate::stats::simple_histogram::SimpleHistogram::new(),
-                metadata_rename_latency: crate::stats::simple_histogram::SimpleHistogram::new(),
-                metadata_readdir_latency: crate::stats::simple_histogram::SimpleHistogram::new(),
-                metadata_fsync_latency: crate::stats::simple_histogram::SimpleHistogram::new(),
-            }; num_workers]
-        ));
+        // Live stats: a shared vector (like standalone mode), or with
+        // --thread-per-core a queue per worker
+        let (snapshot_sinks, live_snapshots) = if config.workers.thread_per_core {
+            let (queues, collector) = crate::worker::snapshot_queue::queues(num_workers, &Default::default());
+            (SnapshotSinks::Queues(queues), LiveSnapshots::Queued(collector))
+        } else {
+            let shared = Arc::new(Mutex::new(vec![crate::worker::StatsSnapshot::default(); num_workers]));
+            (SnapshotSinks::Shared(shared.clone()), LiveSnapshots::Shared(shared))
+        };
         
         // Also keep final stats for RESULTS message
         let worker_stats: Arc<Mutex<Vec<crate::stats::WorkerStats>>> = Arc::new(Mutex::new(Vec::new()));
+        let worker_failures: Arc<Mutex<Vec<WorkerFailure>>> = Arc::new(Mutex::new(Vec::new()));
+        
+        // Keep-alive and session resumption, if the coordinator supports them
+        let timeouts = if config_msg.keepalive {
+            crate::distributed::session::SessionTimeouts::from_config(&config.runtime)
+        } else {
+            None
+        };
         
         // Spawn workers in a separate thread (not async)
         let config = Arc::new(config);
@@ -271,43 +705,79 @@ impl NodeService {
         let config_for_results = config.clone();  // Clone for results collection
         let stop_flag_clone = stop_flag.clone();
         let worker_stats_clone = worker_stats.clone();
-        let shared_snapshots_clone = shared_snapshots.clone();  // For workers to update
+        let worker_failures_clone = worker_failures.clone();
+        let worker_id_start = config_msg.worker_id_start;
+        let total_workers = config_msg.total_workers.unwrap_or(config_msg.worker_id_end);
+        // This node's share of the run's preparation limit, by its share of the workers
+        let prep_limit = config.runtime.prep_rate_limit
+            .map(|limit| crate::target::prep_limit::PrepLimit::share(limit, num_workers, total_workers));
+        let control = Arc::new(crate::worker::control::RunControl::new(
+            num_workers,
+            config.workers.rate_limit_iops,
+            config.workers.rate_limit_throughput,
+        ).with_prep_limit(prep_limit));
+        control.hold_start();
+        let control_clone = control.clone();
         let file_list = config_msg.file_list.clone().map(Arc::new);
         let file_range = config_msg.file_range;
-        let worker_id_start = config_msg.worker_id_start;
-        let worker_id_end = config_msg.worker_id_end;
-        
-        let worker_handle = std::thread::spawn(move || {
-            spawn_workers(
-                config,
-                file_list,
-                file_range,
-                worker_id_start,
-                worker_id_end,
-                stop_flag_clone,
-                worker_stats_clone,
-                shared_snapshots_clone,  // Pass to workers
-            )
+        let fragmentation_files = config.output.fragmentation.then(|| {
+            fragmentation_files(&config, file_list.as_deref().map(Vec::as_slice), file_range, worker_id_start)
         });
+        let offset_ranges = worker_offset_ranges(
+            &config,
+            file_list.is_some(),
+            worker_id_start,
+            total_workers,
+            config_msg.node_offset_range,
+        );
+        
+        let worker_handle = JobWorkers {
+            stop_flag: stop_flag.clone(),
+            handle: Some(std::thread::spawn(move || {
+                spawn_workers(
+                    config,
+                    file_list,
+                    file_range,
+                    worker_id_start,
+                    offset_ranges,
+                    stop_flag_clone,
+                    worker_stats_clone,
+                    snapshot_sinks,  // Pass to workers
+                    worker_failures_clone,
+                    control_clone,
+                )
+            })),
+        };
         
         // Send READY message
         let ready = ReadyMessage {
-            protocol_version: PROTOCOL_VERSION,
+            protocol_version: version,
             node_id: self.node_id.clone(),
             num_workers,
             ready: true,
+            job_id: Some(job_id.clone()),
+            resumable: timeouts.is_some(),
+            capabilities: Some(crate::util::capabilities::NodeCapabilities::detect(&config_for_results.workload)),
         };
-        write_message(&mut stream, &Message::Ready(ready)).await?;
+        write_message_for_version(&mut stream, &Message::Ready(ready), version).await?;
         println!("Sent READY message");
         
         // Wait for START message
-        let start_msg = match read_message(&mut stream).await? {
+        let start_msg = match self.read_message_idle(&mut stream).await? {
             Message::Start(msg) => msg,
             other => anyhow::bail!("Expected START message, got {:?}", other),
         };
         
         println!("Received START message: timestamp={}", start_msg.start_timestamp_ns);
         
+        // Free space before any IO (--space-usage); the sync this takes
+        // overlaps the wait for the start time
+        let space_probe = config_for_results.output.space_usage.then(|| {
+            crate::util::space::SpaceProbe::capture(config_for_results.targets.iter()
+                .filter(|t| t.target_type != crate::config::TargetType::BlockDevice)
+                .map(|t| t.path.as_path()))
+        });
+        
         // Wait until start timestamp
         let now_ns = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)?
@@ -321,6 +791,7 @@ impl NodeService {
         }
         
         println!("Starting IO operations...");
+        control.start();
         let test_start = std::time::Instant::now();
         
         // Initialize resource tracker for CPU/memory monitoring
@@ -331,27 +802,27 @@ impl NodeService {
             tracker
         }));
         
-        // Split stream for concurrent read/write
-        let (read_half, write_half) = stream.into_split();
-        let read_half = Arc::new(tokio::sync::Mutex::new(read_half));
-        let write_half = Arc::new(tokio::sync::Mutex::new(write_half));
+        // From here on the connection may be replaced by a RESUME
+        let (resume_tx, resume_rx) = tokio::sync::mpsc::channel(1);
+        *self.state.resume.lock().unwrap() = Some((job_id.clone(), resume_tx));
+        let _resume_registration = ResumeRegistration { state: &self.state };
+        let mut link = CoordinatorLink::new(stream, version, self.node_id.clone(), timeouts, resume_rx);
         
-        // Start heartbeat task
+        // Start heartbeat task (heartbeats go out through the link)
+        let (heartbeat_tx, mut heartbeat_rx) = tokio::sync::mpsc::channel(16);
         let heartbeat_handle = {
             let node_id = self.node_id.clone();
             let stop_flag = stop_flag.clone();
-            let shared_snapshots = shared_snapshots.clone();  // Use shared snapshots
-            let write_half = write_half.clone();
             let resource_tracker = resource_tracker.clone();
             // config_for_heartbeat already cloned above
             
             tokio::spawn(async move {
                 heartbeat_loop(
-                    write_half,
+                    heartbeat_tx,
                     node_id,
                     test_start,
                     stop_flag,
-                    shared_snapshots,  // Pass shared snapshots
+                    live_snapshots,  // Read by the heartbeat
                     resource_tracker,  // Pass resource tracker
                     config_for_heartbeat,
                 ).await
@@ -362,30 +833,32 @@ impl NodeService {
         loop {
             tokio::select! {
                 // Check for STOP message
-                msg_result = async {
-                    let mut read = read_half.lock().await;
-                    read_message_from_read_half(&mut *read).await
-                } => {
-                    match msg_result {
-                        Ok(Message::Stop) => {
+                msg = link.recv() => {
+                    match msg {
+                        Some(Message::Stop) => {
                             println!("Received STOP message");
                             stop_flag.store(true, Ordering::Relaxed);
                             break;
                         }
-                        Ok(Message::HeartbeatAck) => {
-                            // Ignore ACKs in main loop (handled by heartbeat task)
+                        Some(Message::Control(command)) => {
+                            println!("Control: {}", command);
+                            control.apply(&command);
                         }
-                        Ok(other) => {
+                        Some(other) => {
                             println!("Unexpected message: {:?}", other);
                         }
-                        Err(e) => {
-                            eprintln!("Error reading message: {}", e);
-                            stop_flag.store(true, Ordering::Relaxed);
-                            break;
+                        None => {
+                            // Connection lost; the periodic check below decides what happens
+                            // (a RESUME is taken over inside recv)
                         }
                     }
                 }
                 
+                // Send heartbeats (buffered while disconnected)
+                Some(heartbeat) = heartbeat_rx.recv() => {
+                    link.send(heartbeat).await;
+                }
+                
                 // Check if workers completed
                 _ = sleep(Duration::from_millis(100)) => {
                     // Check if worker thread finished
@@ -394,14 +867,19 @@ impl NodeService {
                         stop_flag.store(true, Ordering::Relaxed);
                         break;
                     }
+                    if let Err(e) = link.check() {
+                        tracing::error!("{:#}", e);
+                        stop_flag.store(true, Ordering::Relaxed);
+                        break;
+                    }
                 }
             }
         }
         
         // Wait for workers to finish
         println!("Waiting for workers to complete in-flight operations...");
-        worker_handle.join()
-            .map_err(|_| anyhow::anyhow!("Worker thread panicked"))??;
+        worker_handle.join()?;
+        let space_usage = space_probe.map(crate::util::space::SpaceProbe::finish);
         
         // Stop heartbeat task
         heartbeat_handle.abort();
@@ -409,8 +887,30 @@ impl NodeService {
         let test_duration = test_start.elapsed();
         println!("Test duration: {:.2}s", test_duration.as_secs_f64());
         
+        // Report failed workers; without continue_on_worker_failure the job ends here
+        let failures = std::mem::take(&mut *worker_failures.lock().unwrap());
+        if !failures.is_empty() {
+            for failure in &failures {
+                let error = ErrorMessage {
+                    node_id: self.node_id.clone(),
+                    error: failure.to_string(),
+                    elapsed_ns: test_duration.as_nanos() as u64,
+                    worker_id: Some(failure.worker_id),
+                    backtrace: failure.backtrace.clone(),
+                };
+                link.deliver(&Message::Error(error)).await?;
+            }
+            println!("Sent ERROR message for {} failed worker(s)", failures.len());
+            
+            if !config_for_results.runtime.continue_on_worker_failure {
+                // As for RESULTS, let the coordinator read before the connection closes
+                tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+                anyhow::bail!("{} worker(s) failed: {}", failures.len(), failures[0]);
+            }
+        }
+        
         // Collect final statistics
-        let stats_vec = worker_stats.lock().unwrap();
+        let stats_vec = std::mem::take(&mut *worker_stats.lock().unwrap());
         
         // Get file_size and block_size from config for coverage calculation
         let file_size = config_for_results.targets.first().and_then(|t| t.file_size);
@@ -434,6 +934,16 @@ impl NodeService {
             for worker_stats in stats_vec.iter() {
                 merged_stats.merge(worker_stats)?;
             }
+            if let Some(usage) = space_usage {
+                merged_stats.set_space_usage(usage);
+            }
+            
+            // Fragmentation caused by the write pattern, mapped where the files are local
+            if let Some(ref files) = fragmentation_files {
+                let analysis_start = Instant::now();
+                merged_stats.set_fragmentation(crate::util::fragmentation::FragmentationReport::analyze(files));
+                merged_stats.record_setup_phase("Fragmentation analysis", analysis_start.elapsed());
+            }
             
             // Create snapshot from merged stats
             WorkerStatsSnapshot::from_worker_stats(&merged_stats, file_size, block_size)
@@ -450,11 +960,11 @@ impl NodeService {
             duration_ns: test_duration.as_nanos() as u64,
             per_worker_stats: per_worker_snapshots,
             aggregate_stats: aggregate,
+            job_id: Some(job_id.clone()),
         };
         
-        let mut write = write_half.lock().await;
-        write_message_to_write_half(&mut *write, &Message::Results(results)).await?;
-        println!("Sent RESULTS message");
+        link.deliver(&Message::Results(results)).await?;
+        println!("Sent RESULTS message for job {}", job_id);
         
         // Give coordinator time to read the message before closing connection
         // This is especially important for large messages (many workers with histograms)
@@ -465,16 +975,156 @@ impl NodeService {
     }
 }
 
+/// Check O_DIRECT alignment of existing targets against the workload's block sizes
+///
+/// Targets that don't exist yet are checked by the workers after creation.
+fn preflight_direct_io(config: &crate::config::Config) -> Result<()> {
+    if !config.workload.direct {
+        return Ok(());
+    }
+    
+    for target in &config.targets {
+        if target.target_type == crate::config::TargetType::Directory || !target.path.exists() {
+            continue;
+        }
+        let alignment = crate::target::dio::DioAlignment::probe(&target.path)
+            .with_context(|| format!("Failed to open {} with O_DIRECT", target.path.display()))?;
+        alignment.check_workload(&target.path, &config.workload)?;
+    }
+    Ok(())
+}
+
+/// Check block device targets: regions within the device, and no writes to
+/// mounted devices or ones holding a signature unless forced
+///
+/// The region of a file must lie within the existing file.
+fn preflight_block_devices(config: &crate::config::Config) -> Result<()> {
+    let check_writes = config.workload.write_percent > 0 && !config.runtime.force_device_write;
+    for target in &config.targets {
+        if target.target_type == crate::config::TargetType::BlockDevice {
+            crate::target::block::check_device(&target.path, target.region, check_writes)?;
+        } else if let Some((start, end)) = target.region {
+            let size = std::fs::metadata(&target.path)
+                .with_context(|| format!("Target region {}-{} needs an existing file: {}", start, end, target.path.display()))?
+                .len();
+            if end > size {
+                anyhow::bail!("Target region {}-{} extends past the end of {} ({} bytes)",
+                    start, end, target.path.display(), size);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Files whose extents this node maps with --fragmentation
+///
+/// Each node maps its own range of a partitioned file list. Files every node
+/// works on (a shared list, or the targets themselves) are mapped by the node
+/// running worker 0 only, so they are counted once.
+fn fragmentation_files(
+    config: &crate::config::Config,
+    file_list: Option<&[std::path::PathBuf]>,
+    file_range: Option<(usize, usize)>,
+    worker_id_start: usize,
+) -> Vec<std::path::PathBuf> {
+    match (file_list, file_range) {
+        (Some(files), Some((start, end))) => files[start.min(files.len())..end.min(files.len())].to_vec(),
+        _ if worker_id_start != 0 => Vec::new(),
+        (Some(files), None) => files.to_vec(),
+        (None, _) => config.targets.iter()
+            .filter(|t| t.target_type == crate::config::TargetType::File)
+            .map(|t| t.path.clone())
+            .collect(),
+    }
+}
+
+/// Byte range of each local worker in partitioned single-file mode
+///
+/// Splits the node's region from the coordinator across the workers. Older
+/// coordinators send no region; the file is then split by global worker ID,
+/// assuming every node runs the same number of workers. With a device
+/// region and no partitioning, every worker gets the whole region.
+fn worker_offset_ranges(
+    config: &crate::config::Config,
+    has_file_list: bool,
+    worker_id_start: usize,
+    total_workers: usize,
+    node_range: Option<(u64, u64)>,
+) -> Option<Vec<(u64, u64)>> {
+    use crate::distributed::partition::split_range;
+    
+    let target = config.targets.first()?;
+    let num_workers = config.workers.threads;
+    if has_file_list {
+        return None;
+    }
+    if target.distribution != crate::config::workload::FileDistribution::Partitioned {
+        return target.region.map(|region| vec![region; num_workers]);
+    }
+    let block_size = config.workload.block_size;
+    match node_range {
+        Some(range) => Some(split_range(range, num_workers, block_size)),
+        None => {
+            let whole = target.region.or(target.file_size.map(|size| (0, size)))?;
+            let ranges = split_range(whole, total_workers, block_size);
+            ranges.get(worker_id_start..worker_id_start + num_workers).map(<[_]>::to_vec)
+        }
+    }
+}
+
+/// Where workers publish live statistics
+enum SnapshotSinks {
+    /// One vector, a slot per worker, behind a lock
+    Shared(Arc<Mutex<Vec<crate::worker::StatsSnapshot>>>),
+    /// A queue per worker (--thread-per-core)
+    Queues(Vec<crate::worker::snapshot_queue::SnapshotSender>),
+}
+
+/// Where the heartbeat reads workers' live statistics
+enum LiveSnapshots {
+    Shared(Arc<Mutex<Vec<crate::worker::StatsSnapshot>>>),
+    Queued(crate::worker::snapshot_queue::SnapshotCollector),
+}
+
+impl LiveSnapshots {
+    /// The latest snapshot of each worker
+    fn lock(&mut self) -> LiveSnapshotsGuard<'_> {
+        match self {
+            Self::Shared(shared) => LiveSnapshotsGuard::Shared(shared.lock().unwrap()),
+            Self::Queued(collector) => LiveSnapshotsGuard::Queued(collector.collect()),
+        }
+    }
+}
+
+/// Workers' snapshots, locked if they are shared
+enum LiveSnapshotsGuard<'a> {
+    Shared(std::sync::MutexGuard<'a, Vec<crate::worker::StatsSnapshot>>),
+    Queued(&'a [crate::worker::StatsSnapshot]),
+}
+
+impl std::ops::Deref for LiveSnapshotsGuard<'_> {
+    type Target = [crate::worker::StatsSnapshot];
+    
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::Shared(guard) => guard,
+            Self::Queued(snapshots) => snapshots,
+        }
+    }
+}
+
 /// Spawn worker threads and run the test
 fn spawn_workers(
     config: Arc<crate::config::Config>,
     file_list: Option<Arc<Vec<std::path::PathBuf>>>,
     file_range: Option<(usize, usize)>,
     worker_id_start: usize,
-    worker_id_end: usize,
+    offset_ranges: Option<Vec<(u64, u64)>>,
     stop_flag: Arc<AtomicBool>,
     worker_stats: Arc<Mutex<Vec<crate::stats::WorkerStats>>>,
-    shared_snapshots: Arc<Mutex<Vec<crate::worker::StatsSnapshot>>>,  // Add this parameter
+    snapshot_sinks: SnapshotSinks,
+    worker_failures: Arc<Mutex<Vec<WorkerFailure>>>,
+    control: Arc<crate::worker::control::RunControl>,
 ) -> Result<()> {
     use crate::worker::Worker;
     
@@ -489,43 +1139,32 @@ fn spawn_workers(
     let is_partitioned = config.targets.iter()
         .any(|t| t.distribution == crate::config::workload::FileDistribution::Partitioned);
     
-    // Determine if we need offset partitioning (single file + partitioned mode)
-    let needs_offset_partitioning = is_partitioned && file_list.is_none() && !config.targets.is_empty();
-    
-    // Calculate offset ranges for partitioned single-file mode
-    // IMPORTANT: In distributed mode, we need to calculate based on GLOBAL worker IDs
-    // to ensure workers across nodes get non-overlapping regions
-    let offset_ranges: Option<Vec<(u64, u64)>> = if needs_offset_partitioning {
-        if let Some(file_size) = config.targets[0].file_size {
-            // In distributed mode, we need to know the total number of workers across ALL nodes
-            // The coordinator doesn't send this, so we need to infer it from worker_id_end
-            // For now, we'll calculate based on the global worker IDs we received
-            
-            // Calculate region size based on the HIGHEST worker ID we know about
-            // This is a limitation: we don't know the true total, so we use worker_id_end as a proxy
-            // Better solution: coordinator should send total_workers_global
-            let estimated_total_workers = worker_id_end;  // This is the highest worker ID + 1
-            let region_size = file_size / estimated_total_workers as u64;
-            
-            let ranges: Vec<(u64, u64)> = (0..num_workers)
-                .map(|local_worker_id| {
-                    let global_worker_id = worker_id_start + local_worker_id;
-                    let start = global_worker_id as u64 * region_size;
-                    let end = if global_worker_id == estimated_total_workers - 1 {
-                        file_size  // Last worker globally gets remainder
-                    } else {
-                        start + region_size
-                    };
-                    (start, end)
-                })
-                .collect();
-            Some(ranges)
-        } else {
-            None
+    // In SHARED file-list mode the workers track which files are in use
+    let file_claims = match file_list {
+        Some(ref fl) if !is_per_worker && !is_partitioned && file_range.is_none() => {
+            Some(Arc::new(crate::worker::file_claims::FileClaims::new(fl.len())))
         }
+        _ => None,
+    };
+    
+    // With --thread-per-core each worker owns a core
+    let owned_cores = if config.workers.thread_per_core {
+        let cores = crate::worker::affinity::owned_cores(config.workers.cpu_cores.as_deref(), num_workers)?;
+        let mut distinct = cores.clone();
+        distinct.sort_unstable();
+        distinct.dedup();
+        if distinct.len() < num_workers {
+            tracing::warn!("Thread per core: {} workers on {} cores, some share a core", num_workers, distinct.len());
+        }
+        tracing::info!("Thread per core: workers on cores {:?}", cores);
+        Some(cores)
     } else {
         None
     };
+    let (shared_snapshots, mut snapshot_queues) = match snapshot_sinks {
+        SnapshotSinks::Shared(shared) => (Some(shared), None),
+        SnapshotSinks::Queues(queues) => (None, Some(queues.into_iter())),
+    };
     
     // Spawn worker threads
     for local_worker_id in 0..num_workers {
@@ -533,11 +1172,20 @@ fn spawn_workers(
         let mut worker_config = (*config).clone();
         let stop_flag = stop_flag.clone();
         let shared_snapshots = shared_snapshots.clone();  // Clone for this worker
+        let snapshot_queue = snapshot_queues.as_mut().and_then(Iterator::next);
+        let file_claims = file_claims.clone();
+        let control = control.clone();
         
         // Set offset range for this worker if partitioned single-file mode
         if let Some(ref ranges) = offset_ranges {
-            worker_config.workers.offset_range = Some(ranges[local_worker_id]);
+            let (start, end) = ranges[local_worker_id];
+            tracing::debug!("worker {}: offset range {}-{} ({} bytes)", global_worker_id, start, end, end - start);
+            worker_config.workers.offset_range = Some((start, end));
         }
+        worker_config.workload.apply_worker_group(global_worker_id);
+        worker_config.workers.unit_id = config.workers.unit_id.as_deref()
+            .map(|node_uid| crate::util::trace_id::worker_uid(node_uid, local_worker_id));
+        worker_config.workers.core = owned_cores.as_ref().map(|cores| cores[local_worker_id]);
         
         let worker_config = Arc::new(worker_config);
         
@@ -555,31 +1203,48 @@ fn spawn_workers(
             file_list.clone()
         };
         
+        let continue_on_failure = config.runtime.continue_on_worker_failure;
         let handle = std::thread::spawn(move || {
-            // Create worker with GLOBAL worker ID for proper identification
-            let mut worker = Worker::new(global_worker_id, worker_config)
-                .expect("Failed to create worker");
-            
-            // Set shared stats so worker updates during execution
-            worker.set_shared_stats(shared_snapshots);
-            
-            // Set file list if provided
-            if let Some(fl) = worker_file_list {
-                worker.set_file_list(fl);
+            // Errors and panics become a WorkerFailure instead of unwinding the thread
+            let result = run_guarded(global_worker_id, || {
+                // Create worker with GLOBAL worker ID for proper identification
+                let mut worker = Worker::new(global_worker_id, worker_config)
+                    .context("Failed to create worker")?;
                 
-                // Set file range if provided (for PARTITIONED mode with file lists)
-                // Note: file_range is not used in per-worker mode
-                if let Some((start, end)) = file_range {
-                    worker.set_file_range(start, end);
+                // Set shared stats (or its own queue) so worker updates during execution
+                if let Some(queue) = snapshot_queue {
+                    worker.set_snapshot_queue(queue);
+                } else if let Some(shared) = shared_snapshots {
+                    worker.set_shared_stats(shared, local_worker_id);
                 }
-            }
-            
-            // Run worker until stop flag is set
-            worker.run_until_stopped(&stop_flag)
-                .expect("Worker failed");
+                worker.set_control(control, local_worker_id);
+                
+                // Set file list if provided
+                if let Some(fl) = worker_file_list {
+                    worker.set_file_list(fl);
+                    
+                    // Set file range if provided (for PARTITIONED mode with file lists)
+                    // Note: file_range is not used in per-worker mode
+                    if let Some((start, end)) = file_range {
+                        worker.set_file_range(start, end);
+                    }
+                    if let Some(claims) = file_claims {
+                        worker.set_file_claims(claims);
+                    }
+                }
+                
+                // Run worker until stop flag is set
+                worker.run_until_stopped(&stop_flag)?;
+                
+                // Return worker stats
+                Ok(worker.into_stats())
+            });
             
-            // Return worker stats
-            worker.into_stats()
+            // A failed worker stops the rest unless failures are tolerated
+            if result.is_err() && !continue_on_failure {
+                stop_flag.store(true, Ordering::Relaxed);
+            }
+            result
         });
         
         handles.push(handle);
@@ -587,14 +1252,23 @@ fn spawn_workers(
     
     // Wait for all workers to complete
     let mut stats_vec = Vec::new();
+    let mut failures = Vec::new();
     for handle in handles {
-        let stats = handle.join()
-            .map_err(|_| anyhow::anyhow!("Worker thread panicked"))?;
-        stats_vec.push(stats);
+        match handle.join().map_err(|_| anyhow::anyhow!("Worker thread panicked"))? {
+            Ok(stats) => stats_vec.push(stats),
+            Err(failure) => {
+                tracing::error!("{}", failure);
+                if let Some(ref backtrace) = failure.backtrace {
+                    tracing::error!("{}", backtrace);
+                }
+                failures.push(failure);
+            }
+        }
     }
     
-    // Store statistics
+    // Store statistics (failed workers have none)
     *worker_stats.lock().unwrap() = stats_vec;
+    *worker_failures.lock().unwrap() = failures;
     
     Ok(())
 }
@@ -602,18 +1276,19 @@ fn spawn_workers(
 /// Heartbeat loop
 ///
 /// Sends periodic heartbeats to coordinator and implements dead man's switch.
+#[allow(clippy::too_many_arguments)]
 async fn heartbeat_loop(
-    write_half: Arc<tokio::sync::Mutex<tokio::net::tcp::OwnedWriteHalf>>,
+    heartbeat_tx: tokio::sync::mpsc::Sender<HeartbeatMessage>,
     node_id: String,
     test_start: std::time::Instant,
     stop_flag: Arc<AtomicBool>,
-    shared_snapshots: Arc<Mutex<Vec<crate::worker::StatsSnapshot>>>,  // Vec of snapshots
+    mut live_snapshots: LiveSnapshots,
     resource_tracker: Arc<Mutex<crate::util::resource::ResourceTracker>>,  // Resource tracker
     config: Arc<crate::config::Config>,  // Config for per-worker flag check
 ) -> Result<()> {
     use tokio::time::interval;
     
-    let mut heartbeat_interval = interval(Duration::from_secs(1));
+    let mut heartbeat_interval = interval(config.output.live_interval_duration());
     
     loop {
         // Check if test stopped
@@ -635,7 +1310,7 @@ async fn heartbeat_loop(
         
         // Aggregate current snapshots (cumulative values)
         let aggregate = {
-            let snapshots = shared_snapshots.lock().unwrap();
+            let snapshots = live_snapshots.lock();
             
             // Aggregate snapshots directly (like standalone monitoring thread does)
             let mut total_read_ops = 0u64;
@@ -655,6 +1330,8 @@ async fn heartbeat_loop(
             let mut total_metadata_rename = 0u64;
             let mut total_metadata_readdir = 0u64;
             let mut total_metadata_fsync = 0u64;
+            let mut total_page_faults = None;
+            let mut total_submit_gaps = None;
             
             // Merge histograms
             use crate::stats::simple_histogram::SimpleHistogram;
@@ -678,6 +1355,8 @@ async fn heartbeat_loop(
                 total_read_bytes += snapshot.read_bytes;
                 total_write_bytes += snapshot.write_bytes;
                 total_errors += snapshot.errors;
+                total_page_faults = crate::util::resource::PageFaults::combine(total_page_faults, snapshot.page_faults);
+                total_submit_gaps = crate::stats::submit_gaps::SubmitGaps::combine(total_submit_gaps, snapshot.submit_gaps);
                 
                 total_metadata_open += snapshot.metadata_open_ops;
                 total_metadata_close += snapshot.metadata_close_ops;
@@ -723,7 +1402,7 @@ async fn heartbeat_loop(
             
             // Debug: print cumulative values
             if elapsed_ns < 6_000_000_000 {
-                eprintln!("DEBUG CUMULATIVE: total_read={}, total_write={}", 
+                tracing::debug!("cumulative: total_read={}, total_write={}",
                     total_read_ops, total_write_ops);
             }
             
@@ -782,7 +1461,38 @@ async fn heartbeat_loop(
                 },
                 unique_blocks: 0,
                 total_blocks: 0,
+                coverage_estimated: false,
                 lock_latency_histogram: None,
+                qd_ramp: None,
+                setup_timings: crate::stats::setup::SetupTimings::new(),
+                page_faults: total_page_faults,
+                buffer_pool: Vec::new(),  // Only available in final results
+                grow: None,  // Only available in final results
+                rate_sum: None,  // Only available in final results
+                io_sizes: None,  // Only available in final results
+                write_io_sizes: None,  // Only available in final results
+                hints: None,  // Only available in final results
+                contention: None,  // Only available in final results
+                engine: None,  // Only available in final results
+                file_latency: None,  // Only available in final results
+                osts: None,  // Only available in final results
+                live_cadence: None,  // Only available in final results
+                cpu_time: None,  // Only available in final results
+                timer: None,  // Only available in final results
+                offset_range: None,  // Only available in final results
+                io_window: None,  // Only available in final results
+                drained: None,  // Only available in final results
+                io_paths: None,  // Only available in final results
+                submit_gaps: total_submit_gaps,
+                space_usage: None,  // Only available in final results
+                eof_clamped: 0,  // Only available in final results
+                queue_depth_limit: None,  // Only available in final results
+                engines: Vec::new(),  // Only available in final results
+                latency_floor: None,  // Only available in final results
+                heatmap: None,  // Only available in final results
+                heatmap_series: None,  // Only available in final results
+                open_fds: crate::util::resource::ResourceSnapshot::open_fd_count(),
+                fragmentation: None,  // Only available in final results
             };
             
             snapshot
@@ -790,7 +1500,7 @@ async fn heartbeat_loop(
         
         // Debug: print cumulative values before sending
         if elapsed_ns < 6_000_000_000 {  // First 6 seconds
-            eprintln!("DEBUG HEARTBEAT: elapsed={}s, read_ops={} (cumulative), write_ops={} (cumulative)", 
+            tracing::debug!("heartbeat: elapsed={}s, read_ops={} (cumulative), write_ops={} (cumulative)",
                 elapsed_ns as f64 / 1_000_000_000.0,
                 aggregate.read_ops,
                 aggregate.write_ops);
@@ -799,7 +1509,7 @@ async fn heartbeat_loop(
         // Send HEARTBEAT with cumulative values
         // Include per-worker snapshots if --per-worker-output is enabled
         let per_worker_snapshots = if config.output.per_worker_output {
-            let snapshots = shared_snapshots.lock().unwrap();
+            let snapshots = live_snapshots.lock();
             
             Some(snapshots.iter()
                 .map(|s| WorkerStatsSnapshot::from_stats_snapshot(s))
@@ -814,23 +1524,20 @@ async fn heartbeat_loop(
             elapsed_ns,
             stats: aggregate,
             per_worker_stats: per_worker_snapshots,
+            seq: 0,  // Numbered by the link
         };
         
-        let mut write = write_half.lock().await;
-        if let Err(e) = write_message_to_write_half(&mut *write, &Message::Heartbeat(heartbeat)).await {
-            eprintln!("Failed to send heartbeat: {}", e);
+        // The test loop sends it (or buffers it while the coordinator is disconnected)
+        if heartbeat_tx.send(heartbeat).await.is_err() {
             break;
         }
-        
-        // Note: We don't wait for HEARTBEAT_ACK in this simplified version
-        // The coordinator will handle ACKs, and we rely on the main loop to detect disconnection
     }
     
     Ok(())
 }
 
 /// Read message from split read half
-async fn read_message_from_read_half(read_half: &mut tokio::net::tcp::OwnedReadHalf) -> Result<Message> {
+pub(crate) async fn read_message_from_read_half(read_half: &mut tokio::net::tcp::OwnedReadHalf) -> Result<Message> {
     use tokio::io::AsyncReadExt;
     
     // Read length field (4 bytes)
@@ -857,12 +1564,12 @@ async fn read_message_from_read_half(read_half: &mut tokio::net::tcp::OwnedReadH
     Ok(msg)
 }
 
-/// Write message to split write half
-async fn write_message_to_write_half(write_half: &mut tokio::net::tcp::OwnedWriteHalf, msg: &Message) -> Result<()> {
+/// Write message to split write half, encoded for the negotiated protocol version
+pub(crate) async fn write_message_to_write_half(write_half: &mut tokio::net::tcp::OwnedWriteHalf, msg: &Message, version: u32) -> Result<()> {
     use tokio::io::AsyncWriteExt;
     
     // Serialize with length prefix
-    let framed = serialize_message(msg)?;
+    let framed = serialize_message_for_version(msg, version)?;
     
     // Write to stream
     write_half.write_all(&framed).await
@@ -875,6 +1582,26 @@ async fn write_message_to_write_half(write_half: &mut tokio::net::tcp::OwnedWrit
     Ok(())
 }
 
+/// Features available on this node, reported in STATUS
+fn node_capabilities() -> Vec<String> {
+    let mut capabilities = vec![
+        "engine:sync".to_string(),
+        "engine:mmap".to_string(),
+    ];
+    if cfg!(feature = "io_uring") {
+        capabilities.push("engine:io_uring".to_string());
+    }
+    // io_setup can be refused (aio-max-nr, seccomp) even on Linux
+    if crate::util::capabilities::engine_available(crate::config::workload::EngineType::Libaio) {
+        capabilities.push("engine:libaio".to_string());
+    }
+    capabilities.push("prepare_files".to_string());
+    capabilities.push("status".to_string());
+    capabilities.push("resume".to_string());
+    capabilities.push("control".to_string());
+    capabilities
+}
+
 /// Get node identifier (IP address or hostname)
 fn get_node_id() -> Result<String> {
     // Try to get hostname first
@@ -891,12 +1618,16 @@ fn get_node_id() -> Result<String> {
 /// Pre-allocate a region of a file (distributed mode)
 ///
 /// Each node pre-allocates its assigned region of the file in parallel.
+#[allow(clippy::too_many_arguments)]
 fn preallocate_region(
     path: &std::path::Path,
     start_offset: u64,
     region_size: u64,
     fill: bool,
     _pattern: crate::config::workload::VerifyPattern,
+    prealloc_mode: crate::config::workload::PreallocMode,
+    fill_regions: Option<Vec<(u64, u64)>>,
+    prep_limit: Option<crate::target::prep_limit::PrepLimit>,
 ) -> Result<(usize, usize)> {
     use crate::target::file::FileTarget;
     use crate::target::Target;
@@ -940,13 +1671,26 @@ fn preallocate_region(
         // Create FileTarget with full file size for proper allocation
         let mut target = FileTarget::new(path.to_path_buf(), Some(full_file_size));
         target.set_preallocate(true);
+        target.set_prealloc_mode(prealloc_mode);
         target.set_offset_range(start_offset, full_file_size);
+        if let Some(regions) = fill_regions {
+            target.set_fill_regions(regions);
+        }
+        target.set_prep_limit(prep_limit);
+        if prealloc_mode != crate::config::workload::PreallocMode::Posix {
+            // Only the default mode fills partitions on its own
+            target.set_refill(fill);
+        }
         
         let flags = OpenFlags {
             direct: false,
             sync: false,
             create: true,
             truncate: false,
+            noatime: false,
+            dsync: false,
+            rsync: false,
+            read_only: false,
         };
         
         target.open(flags)?;
@@ -1016,13 +1760,19 @@ fn validate_and_fill_files_distributed(
     file_list: &[std::path::PathBuf],
     file_size: u64,
     pattern: crate::config::workload::VerifyPattern,
+    custom_pattern: Option<&crate::config::workload::CustomPattern>,
+    fill_regions: Option<&[(u64, u64)]>,
+    prep_limit: Option<crate::target::prep_limit::PrepLimit>,
 ) -> Result<usize> {
     use rayon::prelude::*;
     use std::sync::atomic::{AtomicUsize, Ordering};
     
+    let start = std::time::Instant::now();
+    let fill_bytes = fill_regions.map_or(file_size, |regions| regions.iter().map(|(from, to)| to - from).sum());
     let filled_count = AtomicUsize::new(0);
     let processed_count = AtomicUsize::new(0);
     let total_files = file_list.len();
+    let custom_payload: Option<std::sync::Arc<[u8]>> = custom_pattern.map(|custom| custom.payload.as_slice().into());
     
     // Process files in parallel
     file_list.par_iter().try_for_each(|path| -> Result<()> {
@@ -1059,15 +1809,26 @@ fn validate_and_fill_files_distributed(
             use crate::target::OpenFlags;
             
             let mut target = FileTarget::new(path.clone(), Some(file_size));
+            if let Some(regions) = fill_regions {
+                target.set_fill_regions(regions.to_vec());
+            }
+            target.set_prep_limit(prep_limit.clone());
             
             let flags = OpenFlags {
                 direct: false,
                 sync: false,
                 create: true,
                 truncate: false,
+                noatime: false,
+                dsync: false,
+                rsync: false,
+                read_only: false,
             };
             
             target.open(flags)?;
+            if let Some(payload) = custom_payload.as_ref() {
+                target.set_custom_payload(payload.clone());
+            }
             target.refill(pattern)?;
             target.close()?;
             
@@ -1077,7 +1838,11 @@ fn validate_and_fill_files_distributed(
         // Update progress
         let processed = processed_count.fetch_add(1, Ordering::Relaxed) + 1;
         if processed % 1000 == 0 || processed == total_files {
-            println!("  Progress: {}/{} files validated...", processed, total_files);
+            match prep_limit {
+                Some(ref limit) => println!("  Progress: {}/{} files validated, {}...", processed, total_files,
+                    limit.progress(filled_count.load(Ordering::Relaxed) as u64 * fill_bytes, start.elapsed())),
+                None => println!("  Progress: {}/{} files validated...", processed, total_files),
+            }
         }
         
         Ok(())
@@ -1085,3 +1850,113 @@ fn validate_and_fill_files_distributed(
     
     Ok(filled_count.load(Ordering::Relaxed))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_job_slot_busy_and_release() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            let service = NodeService::new(0).unwrap();
+            
+            let slot = service.claim_test_slot("10.0.1.1:5000").await.unwrap();
+            assert_eq!(slot.job_id(), format!("{}-1", service.node_id));
+            slot.count_test();
+            slot.set_job_id(Some("sweep-7"));
+            assert_eq!(service.status().current_test.unwrap().job_id, "sweep-7");
+            
+            // No queue: a second job is rejected
+            assert!(service.claim_test_slot("10.0.1.2:5000").await.is_none());
+            assert!(service.busy_reason().contains("sweep-7"));
+            
+            // Releasing the slot frees the node for the next job
+            drop(slot);
+            assert!(service.status().current_test.is_none());
+            let slot = service.claim_test_slot("10.0.1.2:5000").await.unwrap();
+            assert_eq!(slot.job_id(), format!("{}-2", service.node_id));
+        });
+    }
+    
+    #[test]
+    fn test_job_slot_queue() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            let mut service = NodeService::new(0).unwrap();
+            service.set_max_queued_jobs(1);
+            let service = Arc::new(service);
+            
+            let first = service.claim_test_slot("a").await.unwrap();
+            first.count_test();
+            
+            let waiter = {
+                let service = service.clone();
+                tokio::spawn(async move {
+                    service.claim_test_slot("b").await.map(|slot| slot.job_id())
+                })
+            };
+            while service.state.queued_jobs.load(Ordering::SeqCst) == 0 {
+                tokio::task::yield_now().await;
+            }
+            
+            // Queue full: a third job is rejected
+            assert!(service.claim_test_slot("c").await.is_none());
+            
+            drop(first);
+            let queued_job = waiter.await.unwrap().unwrap();
+            assert_eq!(queued_job, format!("{}-2", service.node_id));
+        });
+    }
+    
+    #[test]
+    fn test_rejected_protocol_version_not_counted() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            let service = NodeService::new(0).unwrap();
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let mut coordinator = TcpStream::connect(listener.local_addr().unwrap()).await.unwrap();
+            let (mut stream, _) = listener.accept().await.unwrap();
+            
+            let slot = service.claim_test_slot("10.0.1.1:5000").await.unwrap();
+            assert!(service.check_protocol_version(&mut stream, PROTOCOL_VERSION + 1, None).await.is_err());
+            assert!(matches!(read_message(&mut coordinator).await.unwrap(), Message::Error(_)));
+            drop(slot);
+            assert_eq!(service.status().tests_run, 0);
+            
+            // The next job gets the number the rejected one would have had
+            let slot = service.claim_test_slot("10.0.1.1:5000").await.unwrap();
+            assert_eq!(slot.job_id(), format!("{}-1", service.node_id));
+        });
+    }
+    
+    #[test]
+    fn test_fragmentation_files() {
+        let config: crate::config::Config = toml::from_str(r#"
+            [workload]
+            read_percent = 0
+            write_percent = 100
+            completion_mode = { Duration = { seconds = 10 } }
+            
+            [[targets]]
+            path = "/data/test.dat"
+            target_type = "File"
+        "#).unwrap();
+        let files: Vec<std::path::PathBuf> = (0..4).map(|i| format!("/data/f{}", i).into()).collect();
+        
+        // A partitioned list: every node maps its own range
+        assert_eq!(fragmentation_files(&config, Some(&files), Some((2, 4)), 8), files[2..4]);
+        
+        // A shared list or target: only the node running worker 0
+        assert_eq!(fragmentation_files(&config, Some(&files), None, 0), files);
+        assert!(fragmentation_files(&config, Some(&files), None, 8).is_empty());
+        assert_eq!(fragmentation_files(&config, None, None, 0), [std::path::PathBuf::from("/data/test.dat")]);
+        assert!(fragmentation_files(&config, None, None, 8).is_empty());
+    }
+    
+    #[test]
+    fn test_libaio_capability_matches_engine_check() {
+        let libaio = crate::util::capabilities::engine_available(crate::config::workload::EngineType::Libaio);
+        assert_eq!(node_capabilities().contains(&"engine:libaio".to_string()), libaio);
+    }
+}
@@ -0,0 +1,71 @@
+//! Two-level partitioning of a single file
+//!
+//! In partitioned mode with one file, the coordinator splits the file into
+//! one region per node, and each node service splits its region into one
+//! per worker. Worker regions therefore never overlap, across nodes or
+//! within one, even if nodes run different numbers of workers.
+//!
+//! Region boundaries are multiples of the block size, so no IO straddles two
+//! workers; the last region takes the remainder.
+//!
+//! # Example
+//!
+//! ```
+//! use iopulse::distributed::partition::split_range;
+//!
+//! // 10 MiB over 3 nodes, 4 KiB blocks
+//! let nodes = split_range((0, 10 << 20), 3, 4096);
+//! assert_eq!(nodes, vec![(0, 3493888), (3493888, 6987776), (6987776, 10 << 20)]);
+//!
+//! // The second node's region over 2 workers
+//! let workers = split_range(nodes[1], 2, 4096);
+//! assert_eq!(workers, vec![(3493888, 5238784), (5238784, 6987776)]);
+//! ```
+
+/// Split `[start, end)` into `parts` consecutive regions aligned to `align`
+///
+/// If the range is too small for a block per part, regions are sized
+/// without alignment.
+pub fn split_range(range: (u64, u64), parts: usize, align: u64) -> Vec<(u64, u64)> {
+    let (start, end) = range;
+    let parts = parts.max(1) as u64;
+    let len = end.saturating_sub(start);
+    let align = align.max(1);
+
+    let mut chunk = len / parts / align * align;
+    if chunk == 0 {
+        chunk = len / parts;
+    }
+
+    (0..parts)
+        .map(|part| {
+            let region_start = start + part * chunk;
+            let region_end = if part == parts - 1 { end } else { region_start + chunk };
+            (region_start, region_end)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_two_level_split_covers_file() {
+        let file_size = 1_000_000;
+        let mut workers = Vec::new();
+        for node in split_range((0, file_size), 3, 4096) {
+            workers.extend(split_range(node, 5, 4096));
+        }
+        assert_eq!(workers.len(), 15);
+        assert_eq!(workers[0].0, 0);
+        assert_eq!(workers[14].1, file_size);
+        for pair in workers.windows(2) {
+            assert_eq!(pair[0].1, pair[1].0);
+            assert_eq!(pair[0].0 % 4096, 0);
+        }
+
+        // Smaller than a block per part
+        assert_eq!(split_range((0, 100), 4, 4096), vec![(0, 25), (25, 50), (50, 75), (75, 100)]);
+    }
+}
@@ -6,7 +6,21 @@
 //!
 //! # Protocol Version
 //!
-//! Current version: 1
+//! Current version: 3 (oldest supported: 2)
+//!
+//! Before any other message, a v3+ coordinator sends HELLO with the range of
+//! versions it speaks; the node answers HELLO_ACK with the highest version
+//! common to both. A v2 node drops the connection on the unknown HELLO, so the
+//! coordinator reconnects and talks v2 without a handshake. Likewise a node
+//! accepts CONFIG/PREPARE_FILES without a prior HELLO when the carried
+//! protocol_version is within its supported range.
+//!
+//! Every later message on the connection is encoded for the negotiated
+//! version. MessagePack encodes structs as arrays, so a peer rejects structs
+//! with more fields than it knows about. Fields added after v2 are therefore
+//! appended at the end of their struct and marked with
+//! `skip_serializing_if = "...::protocol::since_v3"`, which drops them when
+//! encoding for a v2 peer and reproduces the v2 layout exactly.
 //!
 //! # Serialization Format
 //!
@@ -21,6 +35,9 @@
 //! ```text
 //! Coordinator                     Worker Node
 //!     |                              |
+//!     |-- HELLO(min, max) ---------->|
+//!     |<------ HELLO_ACK(version) ---|
+//!     |                              |
 //!     |-------- CONFIG ------------->|
 //!     |                              |
 //!     |<------- READY ---------------|
@@ -47,12 +64,94 @@ use serde::{Deserialize, Serialize};
 use crate::config::Config;
 use crate::stats::WorkerStats;
 use anyhow::{Context, Result};
+use std::cell::Cell;
 
 /// Protocol version
 ///
 /// Increment this when making breaking changes to the protocol.
-/// Coordinator and workers must have matching protocol versions.
-pub const PROTOCOL_VERSION: u32 = 2;
+/// This is the highest version this build speaks; the version actually used
+/// on a connection is negotiated (see [`negotiate_version`]).
+pub const PROTOCOL_VERSION: u32 = 3;
+
+/// Oldest protocol version this build can still speak
+///
+/// Serialization for every version in `MIN_PROTOCOL_VERSION..=PROTOCOL_VERSION`
+/// must be retained so mixed-version clusters keep working during rolling upgrades.
+pub const MIN_PROTOCOL_VERSION: u32 = 2;
+
+/// Last protocol version without the HELLO handshake
+pub const LEGACY_PROTOCOL_VERSION: u32 = 2;
+
+const _: () = assert!(MIN_PROTOCOL_VERSION <= LEGACY_PROTOCOL_VERSION);
+
+thread_local! {
+    /// Version the current serialize call is encoding for
+    static WIRE_VERSION: Cell<u32> = const { Cell::new(PROTOCOL_VERSION) };
+}
+
+/// Whether `version` is within this build's supported range
+pub fn is_supported_version(version: u32) -> bool {
+    (MIN_PROTOCOL_VERSION..=PROTOCOL_VERSION).contains(&version)
+}
+
+/// Select the highest protocol version supported by both sides
+///
+/// Returns `None` if the remote range `[remote_min, remote_max]` does not
+/// overlap with `[MIN_PROTOCOL_VERSION, PROTOCOL_VERSION]`.
+pub fn negotiate_version(remote_min: u32, remote_max: u32) -> Option<u32> {
+    let highest = remote_max.min(PROTOCOL_VERSION);
+    let lowest = remote_min.max(MIN_PROTOCOL_VERSION);
+    if highest >= lowest {
+        Some(highest)
+    } else {
+        None
+    }
+}
+
+/// Serde skip predicate for fields introduced in protocol version 3
+///
+/// Returns true while encoding for a v2 peer, so the field is left out.
+pub fn since_v3<T>(_: &T) -> bool {
+    WIRE_VERSION.with(|v| v.get() < 3)
+}
+
+/// Options of `config` a peer speaking `version` would not receive
+///
+/// Fields newer than `version` are left out on the wire, so the peer would
+/// silently run with their defaults. Returns the path of every option whose
+/// value would be lost (e.g. `runtime.read_only`, `targets[0].busy_file`);
+/// empty if the peer runs exactly `config`.
+pub fn lost_config_options(config: &Config, version: u32) -> Result<Vec<String>> {
+    let previous = WIRE_VERSION.with(|v| v.replace(version));
+    let encoded = rmp_serde::to_vec(config);
+    WIRE_VERSION.with(|v| v.set(previous));
+    let received: Config = rmp_serde::from_slice(&encoded.context("Failed to serialize config")?)
+        .context("Failed to decode config")?;
+    
+    let mut lost = Vec::new();
+    diff_values(String::new(), &serde_json::to_value(config)?, &serde_json::to_value(&received)?, &mut lost);
+    Ok(lost)
+}
+
+/// Collect the paths where `received` differs from `sent`
+fn diff_values(path: String, sent: &serde_json::Value, received: &serde_json::Value, lost: &mut Vec<String>) {
+    use serde_json::Value;
+    match (sent, received) {
+        (Value::Object(sent), Value::Object(received)) => {
+            for (key, value) in sent {
+                let child = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                diff_values(child, value, received.get(key).unwrap_or(&Value::Null), lost);
+            }
+        }
+        (Value::Array(sent), Value::Array(received)) if sent.len() == received.len() => {
+            for (i, (value, other)) in sent.iter().zip(received).enumerate() {
+                diff_values(format!("{}[{}]", path, i), value, other, lost);
+            }
+        }
+        _ if sent != received => lost.push(path),
+        _ => {}
+    }
+}
 
 /// Serializable worker statistics snapshot
 ///
@@ -128,6 +227,130 @@ pub struct WorkerStatsSnapshot {
     
     // Lock latency histogram (optional, only when locking enabled)
     pub lock_latency_histogram: Option<Vec<u8>>,
+    
+    // Queue depth ramp outcome (only when --qd-ramp enabled, protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub qd_ramp: Option<crate::worker::qd_ramp::QdRampResult>,
+    
+    // Setup/teardown phase timings (engine init, target open, refill; protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub setup_timings: crate::stats::setup::SetupTimings,
+    
+    // Page faults during the IO test (mmap engine only, protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub page_faults: Option<crate::util::resource::PageFaults>,
+    
+    // Buffer pool usage per size class (final results only, protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub buffer_pool: Vec<crate::util::buffer::BufferClassStats>,
+    
+    // Extension vs overwrite writes (append-grow mode, final results only, protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub grow: Option<crate::stats::grow::GrowStats>,
+    
+    // Sum of per-worker rates for merged stats (final results only, protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub rate_sum: Option<crate::stats::rates::WorkerRates>,
+    
+    // Completed transfers by size (final results only, protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub io_sizes: Option<crate::stats::io_sizes::IoSizeHistogram>,
+    
+    // Completed writes by size (final results only, protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub write_io_sizes: Option<crate::stats::io_sizes::IoSizeHistogram>,
+    
+    // fadvise/madvise cost in file-list mode (final results only, protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub hints: Option<crate::stats::hints::HintStats>,
+    
+    // Unique block count is an estimate (--stats-mem-limit sampling, protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub coverage_estimated: bool,
+    
+    // Busy-file collisions in shared file-list mode (final results only, protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub contention: Option<crate::stats::contention::ContentionStats>,
+    
+    // Engine syscall and poll counters (final results only, protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub engine: Option<crate::engine::EngineStats>,
+    
+    // Latency per file or directory (final results with --file-latency-top, protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub file_latency: Option<crate::stats::file_latency::FileLatencyStats>,
+    
+    // Throughput and latency per Lustre OST (final results only, protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub osts: Option<crate::stats::ost::OstStats>,
+    
+    // Live snapshot cadence and its cost (final results only, protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub live_cadence: Option<crate::worker::live_cadence::LiveCadenceStats>,
+    
+    // CPU time vs wait time (final results with --cpu-accounting, protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub cpu_time: Option<crate::stats::cpu_time::CpuAccounting>,
+    
+    // Precision of the latency timer (final results only, protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub timer: Option<crate::util::fast_time::TimerCheck>,
+    
+    // Byte range assigned to the worker (final results in partitioned mode, protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub offset_range: Option<(u64, u64)>,
+    
+    // Wall-clock start and stop of IO, ns since the UNIX epoch (final results only, protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub io_window: Option<(u64, u64)>,
+    
+    // Operations completing after the duration (final results with --drain-latency exclude/separate, protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub drained: Option<crate::stats::drain::DrainStats>,
+    
+    // O_DIRECT vs buffered operations (final results with --buffered-percent, protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub io_paths: Option<crate::stats::io_path::IoPathStats>,
+    
+    // Gaps between submissions (with think time, protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub submit_gaps: Option<crate::stats::submit_gaps::SubmitGaps>,
+    
+    // Filesystem space consumed (final results with --space-usage, protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub space_usage: Option<crate::util::space::SpaceUsage>,
+    
+    // Reads clamped at EOF (final results, protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub eof_clamped: u64,
+    
+    // Queue depth limited by the target or engine (final results, protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub queue_depth_limit: Option<crate::worker::queue_limit::QueueDepthLimit>,
+    
+    // Engines the workers ran (final results, protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub engines: Vec<crate::config::workload::EngineType>,
+    
+    // No-op round trips through the engine's ring (final results with --latency-floor, protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub latency_floor: Option<crate::stats::latency_floor::LatencyFloor>,
+    
+    // Block access heatmap counts (final results with --heatmap, protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub heatmap: Option<crate::stats::heatmap::HeatmapCounts>,
+    
+    // Accesses per offset bucket in each interval (final results with --heatmap-series, protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub heatmap_series: Option<crate::stats::heatmap::HeatmapSeries>,
+    
+    // Open file descriptors of the node service process (heartbeats, protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub open_fds: Option<u64>,
+    
+    // Extents of the node's target files (final results with --fragmentation, protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub fragmentation: Option<crate::util::fragmentation::FragmentationReport>,
 }
 
 impl WorkerStatsSnapshot {
@@ -209,7 +432,38 @@ impl WorkerStatsSnapshot {
             peak_memory_bytes: 0,  // Not tracked per-worker in StatsSnapshot
             unique_blocks: 0,  // Not available in StatsSnapshot
             total_blocks: 0,  // Not available in StatsSnapshot
+            coverage_estimated: false,
             lock_latency_histogram: None,  // Not tracked in StatsSnapshot
+            qd_ramp: None,  // Only available in final results
+            setup_timings: crate::stats::setup::SetupTimings::new(),  // Only available in final results
+            page_faults: snapshot.page_faults,
+            buffer_pool: Vec::new(),  // Only available in final results
+            grow: None,  // Only available in final results
+            rate_sum: None,  // Only available in final results
+            io_sizes: None,  // Only available in final results
+            write_io_sizes: None,  // Only available in final results
+            hints: None,  // Only available in final results
+            contention: None,  // Only available in final results
+            engine: None,  // Only available in final results
+            file_latency: None,  // Only available in final results
+            osts: None,  // Only available in final results
+            live_cadence: None,  // Only available in final results
+            cpu_time: None,  // Only available in final results
+            timer: None,  // Only available in final results
+            offset_range: None,  // Only available in final results
+            io_window: None,  // Only available in final results
+            drained: None,  // Only available in final results
+            io_paths: None,  // Only available in final results
+            submit_gaps: snapshot.submit_gaps,
+            space_usage: None,  // Only available in final results
+            eof_clamped: 0,  // Only available in final results
+            queue_depth_limit: None,  // Only available in final results
+            engines: Vec::new(),  // Only available in final results
+            latency_floor: None,  // Only available in final results
+            heatmap: None,  // Only available in final results
+            heatmap_series: None,  // Only available in final results
+            open_fds: None,  // Only available in service heartbeats
+            fragmentation: None,  // Only available in final results
         })
     }
     
@@ -319,8 +573,39 @@ impl WorkerStatsSnapshot {
             memory_bytes,
             peak_memory_bytes,
             unique_blocks: stats.unique_blocks_count(),
+            coverage_estimated: stats.coverage_is_estimate(),
             total_blocks,
             lock_latency_histogram,
+            qd_ramp: stats.qd_ramp().cloned(),
+            setup_timings: stats.setup_timings().clone(),
+            page_faults: stats.page_faults(),
+            buffer_pool: stats.buffer_pool_stats().to_vec(),
+            grow: stats.grow_stats().cloned(),
+            rate_sum: stats.rate_sum(),
+            io_sizes: Some(stats.io_sizes().clone()),
+            write_io_sizes: Some(stats.write_io_sizes().clone()),
+            hints: Some(stats.hints().clone()),
+            contention: Some(stats.contention().clone()),
+            engine: Some(stats.engine_stats().clone()),
+            file_latency: stats.file_latency().cloned(),
+            osts: Some(stats.ost_stats().clone()),
+            live_cadence: Some(stats.live_cadence().clone()),
+            cpu_time: stats.cpu_time().cloned(),
+            timer: stats.timer(),
+            offset_range: stats.offset_range(),
+            io_window: stats.io_window(),
+            drained: stats.drained().cloned(),
+            io_paths: stats.io_paths().cloned(),
+            submit_gaps: stats.submit_gaps(),
+            space_usage: stats.space_usage().cloned(),
+            eof_clamped: stats.eof_clamped(),
+            queue_depth_limit: stats.queue_depth_limit().cloned(),
+            engines: stats.engines().to_vec(),
+            latency_floor: stats.latency_floor().cloned(),
+            heatmap: stats.heatmap_counts(),
+            heatmap_series: stats.heatmap_series().cloned(),
+            open_fds: None,  // Only available in service heartbeats
+            fragmentation: stats.fragmentation(),
         })
     }
     
@@ -447,7 +732,38 @@ impl From<&WorkerStats> for WorkerStatsSnapshot {
                     peak_memory_bytes: 0,
                     unique_blocks: 0,
                     total_blocks: 0,
+                    coverage_estimated: false,
                     lock_latency_histogram: None,
+                    qd_ramp: stats.qd_ramp().cloned(),
+                    setup_timings: stats.setup_timings().clone(),
+                    page_faults: stats.page_faults(),
+                    buffer_pool: stats.buffer_pool_stats().to_vec(),
+                    grow: stats.grow_stats().cloned(),
+                    rate_sum: stats.rate_sum(),
+                    io_sizes: Some(stats.io_sizes().clone()),
+                    write_io_sizes: Some(stats.write_io_sizes().clone()),
+                    hints: Some(stats.hints().clone()),
+                    contention: Some(stats.contention().clone()),
+                    engine: Some(stats.engine_stats().clone()),
+                    file_latency: stats.file_latency().cloned(),
+                    osts: Some(stats.ost_stats().clone()),
+                    live_cadence: Some(stats.live_cadence().clone()),
+                    cpu_time: stats.cpu_time().cloned(),
+                    timer: stats.timer(),
+                    offset_range: stats.offset_range(),
+                    io_window: stats.io_window(),
+                    drained: stats.drained().cloned(),
+                    io_paths: stats.io_paths().cloned(),
+                    submit_gaps: stats.submit_gaps(),
+                    space_usage: stats.space_usage().cloned(),
+                    eof_clamped: stats.eof_clamped(),
+                    queue_depth_limit: stats.queue_depth_limit().cloned(),
+                    engines: stats.engines().to_vec(),
+                    latency_floor: stats.latency_floor().cloned(),
+                    heatmap: stats.heatmap_counts(),
+                    heatmap_series: stats.heatmap_series().cloned(),
+                    open_fds: None,
+                    fragmentation: stats.fragmentation(),
                 }
             })
     }
@@ -500,9 +816,11 @@ pub enum Message {
     
     /// Heartbeat acknowledgment (Coordinator → Node)
     ///
-    /// Sent by coordinator in response to heartbeat.
-    /// Nodes use this as a dead man's switch (self-stop if no ACK for 10 seconds).
-    HeartbeatAck,
+    /// Sent by coordinator in response to heartbeat when the node accepts
+    /// session resumption, with the heartbeat's sequence number. Nodes treat a
+    /// missing ACK (for `keepalive_timeout`) as a lost connection, and replay
+    /// unacknowledged heartbeats after RESUMED.
+    HeartbeatAck(u64),
     
     /// Results message (Node → Coordinator)
     ///
@@ -514,6 +832,205 @@ pub enum Message {
     /// Sent by node when an error occurs.
     /// Coordinator aborts the test and reports the error.
     Error(ErrorMessage),
+    
+    /// Version handshake (Coordinator → Node, protocol v3+)
+    ///
+    /// First message on a connection. Carries the range of protocol
+    /// versions the coordinator can speak.
+    Hello(HelloMessage),
+    
+    /// Version handshake reply (Node → Coordinator, protocol v3+)
+    ///
+    /// Carries the version selected for the rest of the connection.
+    HelloAck(HelloAckMessage),
+    
+    /// Status query (Coordinator or `iopulse ping` → Node, protocol v3+)
+    ///
+    /// May be sent at any point before CONFIG/PrepareFiles; the node answers
+    /// with STATUS_REPORT and keeps waiting for the next message.
+    Status,
+    
+    /// Status reply (Node → Coordinator)
+    StatusReport(StatusMessage),
+    
+    /// Session resumption (Coordinator → Node, protocol v3+)
+    ///
+    /// First message (after HELLO) on a new connection that replaces a lost
+    /// one during a test. The node answers with RESUMED and continues the job
+    /// on the new connection.
+    Resume(ResumeMessage),
+    
+    /// Session resumption reply (Node → Coordinator, protocol v3+)
+    ///
+    /// Followed by the heartbeats buffered while the connection was down.
+    Resumed(ResumedMessage),
+    
+    /// Change a running test (Coordinator → Node, protocol v3+)
+    ///
+    /// Sent during the test when a control command arrives (control socket
+    /// or API); the node applies it to its workers.
+    Control(ControlMessage),
+    
+    /// Visibility test step (Coordinator → Node, protocol v3+)
+    ///
+    /// Sent instead of CONFIG for `--visibility-test`; the node carries out
+    /// the step, answers with VISIBILITY_REPORT and waits for the next one.
+    Visibility(crate::distributed::visibility::VisibilityStep),
+    
+    /// Visibility step outcome (Node → Coordinator, protocol v3+)
+    VisibilityReport(crate::distributed::visibility::VisibilityReport),
+    
+    /// Preflight check (Coordinator → Node, protocol v3+)
+    ///
+    /// Sent for `--preflight` before any preparation; the node checks the
+    /// targets of the configuration, answers with PREFLIGHT_REPORT and keeps
+    /// waiting like after STATUS.
+    Preflight(Box<crate::config::Config>),
+    
+    /// Preflight outcome (Node → Coordinator, protocol v3+)
+    PreflightReport(crate::util::preflight::PreflightReport),
+}
+
+/// Runtime change to a running test
+///
+/// Rates are for the receiving node as a whole (0 = unlimited).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ControlMessage {
+    /// Stop submitting new operations
+    Pause,
+    /// Continue after a pause
+    Resume,
+    /// Limit the node's IOPS and throughput
+    Rate { iops: u64, bytes_per_sec: u64 },
+    /// Replace the workload's read percentage
+    ReadPercent(u8),
+}
+
+impl std::fmt::Display for ControlMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            ControlMessage::Pause => write!(f, "pause"),
+            ControlMessage::Resume => write!(f, "resume"),
+            ControlMessage::Rate { iops: 0, bytes_per_sec: 0 } => write!(f, "rate unlimited"),
+            ControlMessage::Rate { iops, bytes_per_sec } => {
+                let mut limits = Vec::new();
+                if iops > 0 {
+                    limits.push(format!("{} IOPS", iops));
+                }
+                if bytes_per_sec > 0 {
+                    limits.push(format!("{} bytes/s", bytes_per_sec));
+                }
+                write!(f, "rate {}", limits.join(", "))
+            }
+            ControlMessage::ReadPercent(percent) => write!(f, "read percent {}", percent),
+        }
+    }
+}
+
+/// Session resumption request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResumeMessage {
+    /// Job whose connection is being replaced
+    pub job_id: String,
+}
+
+/// Session resumption reply
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResumedMessage {
+    /// Node identifier
+    pub node_id: String,
+    
+    /// Unacknowledged heartbeats, replayed right after this message
+    pub buffered: usize,
+    
+    /// Unacknowledged heartbeats dropped because the buffer was full
+    pub dropped: usize,
+}
+
+/// Version handshake message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HelloMessage {
+    /// Oldest protocol version the sender supports
+    pub min_version: u32,
+    
+    /// Newest protocol version the sender supports
+    pub max_version: u32,
+}
+
+impl HelloMessage {
+    /// Handshake advertising this build's supported range
+    pub fn local() -> Self {
+        Self {
+            min_version: MIN_PROTOCOL_VERSION,
+            max_version: PROTOCOL_VERSION,
+        }
+    }
+}
+
+/// Version handshake reply
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HelloAckMessage {
+    /// Node identifier
+    pub node_id: String,
+    
+    /// Protocol version selected for this connection
+    pub selected_version: u32,
+    
+    /// Oldest protocol version the node supports
+    pub min_version: u32,
+    
+    /// Newest protocol version the node supports
+    pub max_version: u32,
+}
+
+/// Node status report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusMessage {
+    /// Node identifier
+    pub node_id: String,
+    
+    /// IOPulse version running on the node
+    pub software_version: String,
+    
+    /// Oldest protocol version the node supports
+    pub min_protocol_version: u32,
+    
+    /// Newest protocol version the node supports
+    pub max_protocol_version: u32,
+    
+    /// Time since the service started (nanoseconds)
+    pub uptime_ns: u64,
+    
+    /// Tests run since the service started
+    pub tests_run: u64,
+    
+    /// Test currently running on the node (None if idle)
+    pub current_test: Option<ActiveTestStatus>,
+    
+    /// Jobs waiting for the current test to finish
+    pub queued_jobs: usize,
+    
+    /// Maximum jobs allowed to wait (0 = reject with BUSY)
+    pub max_queued_jobs: usize,
+    
+    /// Features available on the node (IO engines, etc.)
+    pub capabilities: Vec<String>,
+}
+
+/// Test currently running on a node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActiveTestStatus {
+    /// Job identifier
+    pub job_id: String,
+    
+    /// Address of the coordinator that owns the test
+    pub coordinator: String,
+    
+    /// Time since the test was accepted (nanoseconds)
+    pub elapsed_ns: u64,
+    
+    /// Worker threads (0 while files are being prepared)
+    pub num_workers: usize,
 }
 
 /// Prepare files message
@@ -539,6 +1056,22 @@ pub struct PrepareFilesMessage {
     
     /// Whether files need to be filled (true) or just created (false)
     pub fill_files: bool,
+    
+    /// Payload for the custom fill pattern (protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub custom_pattern: Option<crate::config::workload::CustomPattern>,
+    
+    /// How space is pre-allocated (protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub prealloc_mode: crate::config::workload::PreallocMode,
+    
+    /// Regions of the file the workload can reach; only these are filled (protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub fill_regions: Option<Vec<(u64, u64)>>,
+    
+    /// This node's share of `--prep-rate-limit` in bytes/sec (protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub rate_limit: Option<u64>,
 }
 
 /// Files ready message
@@ -563,7 +1096,7 @@ pub struct FilesReadyMessage {
 /// Configuration message
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConfigMessage {
-    /// Protocol version (must match)
+    /// Protocol version (negotiated, or the sender's version for legacy peers)
     pub protocol_version: u32,
     
     /// Node identifier (IP address or hostname)
@@ -598,6 +1131,51 @@ pub struct ConfigMessage {
     /// For SHARED files in distributed mode, coordinator pre-allocates once,
     /// and nodes skip it to avoid redundant work.
     pub skip_preallocation: bool,
+    
+    /// Job identifier assigned by the coordinator (protocol v3+)
+    ///
+    /// Nodes assign their own (`<node>-<seq>`) when absent.
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub job_id: Option<String>,
+    
+    /// Total workers across all nodes of the job (protocol v3+)
+    ///
+    /// Used to split a partitioned file into per-worker regions. Older
+    /// coordinators don't send it; nodes then fall back to `worker_id_end`.
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub total_workers: Option<usize>,
+    
+    /// Role in two-phase verification, with the shared pattern and seed (protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub verify: Option<crate::distributed::verify::VerifyAssignment>,
+    
+    /// Node role from the clients file (protocol v3+)
+    ///
+    /// When the role has a workload fragment, `config.workload` is already the
+    /// role's workload.
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub role: Option<String>,
+    
+    /// Byte range of a partitioned file assigned to this node (protocol v3+)
+    ///
+    /// The node splits it across its workers. Without it, nodes split the
+    /// file by global worker ID, which assumes equal worker counts.
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub node_offset_range: Option<(u64, u64)>,
+    
+    /// The coordinator acknowledges heartbeats and resumes lost connections (protocol v3+)
+    ///
+    /// The node then treats missing acknowledgments as a lost connection and
+    /// keeps the test running while it waits for RESUME (see
+    /// `keepalive_timeout` and `reconnect_timeout` in the runtime config).
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub keepalive: bool,
+    
+    /// ID of this node in the run, `<run-uuid>.n<index>` (protocol v3+)
+    ///
+    /// Workers tag their log lines with IDs derived from it.
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub node_uid: Option<String>,
 }
 
 /// Ready message
@@ -614,6 +1192,18 @@ pub struct ReadyMessage {
     
     /// Node is ready to start
     pub ready: bool,
+    
+    /// Job identifier the node is running this test under (protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub job_id: Option<String>,
+    
+    /// The node accepts RESUME for this job if the connection drops (protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub resumable: bool,
+    
+    /// Version, build features, kernel and engines of the node (protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub capabilities: Option<crate::util::capabilities::NodeCapabilities>,
 }
 
 /// Start message
@@ -642,6 +1232,13 @@ pub struct HeartbeatMessage {
     
     /// Optional per-worker snapshots (only when --per-worker-output is enabled)
     pub per_worker_stats: Option<Vec<WorkerStatsSnapshot>>,
+    
+    /// Sequence number, counting from 1 (protocol v3+; 0 from older nodes)
+    ///
+    /// Identifies the heartbeat in its acknowledgment, and lets the
+    /// coordinator skip replayed heartbeats it already received.
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub seq: u64,
 }
 
 /// Results message
@@ -658,6 +1255,10 @@ pub struct ResultsMessage {
     
     /// Aggregate statistics for this node
     pub aggregate_stats: WorkerStatsSnapshot,
+    
+    /// Job identifier the results belong to (protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub job_id: Option<String>,
 }
 
 /// Error message
@@ -671,6 +1272,14 @@ pub struct ErrorMessage {
     
     /// Error occurred at (elapsed nanoseconds)
     pub elapsed_ns: u64,
+    
+    /// Failed worker (global ID) when a single worker failed (protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub worker_id: Option<usize>,
+    
+    /// Backtrace of the failure site, if captured (protocol v3+)
+    #[serde(default, skip_serializing_if = "since_v3")]
+    pub backtrace: Option<String>,
 }
 
 /// Serialize a message to bytes
@@ -684,9 +1293,24 @@ pub struct ErrorMessage {
 /// [4 bytes: message length (little-endian u32)][N bytes: bincode message]
 /// ```
 pub fn serialize_message(msg: &Message) -> Result<Vec<u8>> {
+    serialize_message_for_version(msg, PROTOCOL_VERSION)
+}
+
+/// Serialize a message for a peer speaking `version`
+///
+/// Fields newer than `version` are left out so older peers can decode the
+/// message. Same framing as [`serialize_message`].
+pub fn serialize_message_for_version(msg: &Message, version: u32) -> Result<Vec<u8>> {
+    if !is_supported_version(version) {
+        anyhow::bail!("Cannot encode for protocol version {} (supported: {}-{})",
+            version, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION);
+    }
+    
     // Serialize message with MessagePack (supports all serde features)
-    let msg_bytes = rmp_serde::to_vec(msg)
-        .context("Failed to serialize message")?;
+    let previous = WIRE_VERSION.with(|v| v.replace(version));
+    let result = rmp_serde::to_vec(msg);
+    WIRE_VERSION.with(|v| v.set(previous));
+    let msg_bytes = result.context("Failed to serialize message")?;
     
     // Prepend length field
     let msg_len = msg_bytes.len() as u32;
@@ -760,10 +1384,15 @@ pub async fn read_message(stream: &mut tokio::net::TcpStream) -> Result<Message>
 ///
 /// Serializes the message with length prefix and writes to stream.
 pub async fn write_message(stream: &mut tokio::net::TcpStream, msg: &Message) -> Result<()> {
+    write_message_for_version(stream, msg, PROTOCOL_VERSION).await
+}
+
+/// Write a message to a TCP stream, encoded for a peer speaking `version`
+pub async fn write_message_for_version(stream: &mut tokio::net::TcpStream, msg: &Message, version: u32) -> Result<()> {
     use tokio::io::AsyncWriteExt;
     
     // Serialize with length prefix
-    let framed = serialize_message(msg)?;
+    let framed = serialize_message_for_version(msg, version)?;
     
     // Write to stream
     stream.write_all(&framed).await
@@ -788,6 +1417,9 @@ mod tests {
             node_id: "10.0.1.10".to_string(),
             num_workers: 16,
             ready: true,
+            job_id: Some("job-1".to_string()),
+            resumable: true,
+            capabilities: None,
         });
         
         let bytes = serialize_message(&msg).unwrap();
@@ -801,6 +1433,7 @@ mod tests {
                 assert_eq!(ready.node_id, "10.0.1.10");
                 assert_eq!(ready.num_workers, 16);
                 assert!(ready.ready);
+                assert_eq!(ready.job_id.as_deref(), Some("job-1"));
             }
             _ => panic!("Wrong message type"),
         }
@@ -846,6 +1479,8 @@ mod tests {
             node_id: "10.0.1.10".to_string(),
             error: "Test error".to_string(),
             elapsed_ns: 5000000000,
+            worker_id: None,
+            backtrace: None,
         });
         
         let bytes = serialize_message(&msg).unwrap();
@@ -865,7 +1500,208 @@ mod tests {
     
     #[test]
     fn test_protocol_version() {
-        assert_eq!(PROTOCOL_VERSION, 2);
+        assert_eq!(PROTOCOL_VERSION, 3);
+        assert!(is_supported_version(2));
+        assert!(!is_supported_version(1));
+        assert!(!is_supported_version(PROTOCOL_VERSION + 1));
+    }
+    
+    #[test]
+    fn test_negotiate_version() {
+        // Same build on both sides
+        assert_eq!(negotiate_version(MIN_PROTOCOL_VERSION, PROTOCOL_VERSION), Some(PROTOCOL_VERSION));
+        // Older peer: fall back to its newest version
+        assert_eq!(negotiate_version(2, 2), Some(2));
+        // Newer peer that still speaks our version
+        assert_eq!(negotiate_version(2, PROTOCOL_VERSION + 2), Some(PROTOCOL_VERSION));
+        // No overlap
+        assert_eq!(negotiate_version(PROTOCOL_VERSION + 1, PROTOCOL_VERSION + 3), None);
+        assert_eq!(negotiate_version(1, 1), None);
+    }
+    
+    #[test]
+    fn test_v2_encoding_omits_v3_fields() {
+        let mut stats = WorkerStats::new();
+        stats.record_setup_phase("Engine init", std::time::Duration::from_millis(1));
+        let snapshot = WorkerStatsSnapshot::from(&stats);
+        
+        let msg = Message::Heartbeat(HeartbeatMessage {
+            node_id: "10.0.1.10".to_string(),
+            elapsed_ns: 1_000_000_000,
+            stats: snapshot,
+            per_worker_stats: None,
+            seq: 1,
+        });
+        
+        let v3 = serialize_message(&msg).unwrap();
+        let v2 = serialize_message_for_version(&msg, 2).unwrap();
+        assert!(v2.len() < v3.len());
+        
+        // v2 encoding decodes with the v3 fields defaulted
+        let (decoded, _) = deserialize_message(&v2).unwrap();
+        match decoded {
+            Message::Heartbeat(hb) => {
+                assert!(hb.stats.setup_timings.is_empty());
+                assert!(hb.stats.qd_ramp.is_none());
+            }
+            _ => panic!("Wrong message type"),
+        }
+        
+        // v3 encoding keeps them
+        let (decoded, _) = deserialize_message(&v3).unwrap();
+        match decoded {
+            Message::Heartbeat(hb) => assert!(hb.stats.setup_timings.get("Engine init").is_some()),
+            _ => panic!("Wrong message type"),
+        }
+        
+        assert!(serialize_message_for_version(&msg, 1).is_err());
+    }
+    
+    /// TargetConfig as protocol v2 peers know it (fields in wire order)
+    #[derive(Debug, Serialize, Deserialize)]
+    struct V2TargetConfig {
+        path: std::path::PathBuf,
+        target_type: crate::config::TargetType,
+        file_size: Option<u64>,
+        num_files: Option<usize>,
+        num_dirs: Option<usize>,
+        layout_config: Option<crate::config::LayoutConfig>,
+        layout_manifest: Option<std::path::PathBuf>,
+        export_layout_manifest: Option<std::path::PathBuf>,
+        distribution: crate::config::workload::FileDistribution,
+        fadvise_flags: crate::config::workload::FadviseFlags,
+        madvise_flags: crate::config::workload::MadviseFlags,
+        lock_mode: crate::config::workload::FileLockMode,
+        preallocate: bool,
+        truncate_to_size: bool,
+        refill: bool,
+        refill_pattern: crate::config::workload::VerifyPattern,
+        no_refill: bool,
+    }
+    
+    /// Config as protocol v2 peers know it
+    #[derive(Debug, Serialize, Deserialize)]
+    struct V2Config {
+        workload: WorkloadConfig,
+        targets: Vec<V2TargetConfig>,
+        workers: WorkerConfig,
+        output: OutputConfig,
+        runtime: RuntimeConfig,
+    }
+    
+    /// Encode `value` the way it goes to a peer speaking `version`
+    fn encode_for_version<T: Serialize>(value: &T, version: u32) -> Vec<u8> {
+        let previous = WIRE_VERSION.with(|v| v.replace(version));
+        let bytes = rmp_serde::to_vec(value);
+        WIRE_VERSION.with(|v| v.set(previous));
+        bytes.unwrap()
+    }
+    
+    #[test]
+    fn test_config_v2_layout() {
+        use crate::config::workload::{BusyFilePolicy, FileLockMode, PreallocMode, VerifyPattern};
+        
+        let mut config: Config = toml::from_str(
+            "targets = [{ path = \"/data/test.dat\", file_size = 1048576, lock_mode = \"Range\", preallocate = true, \
+             truncate_to_size = true, refill = true, refill_pattern = \"Ones\", no_refill = true }]\n\
+             [workload]\nread_percent = 100\nwrite_percent = 0\ncompletion_mode = \"RunUntilComplete\"\n",
+        ).unwrap();
+        config.targets[0].busy_file = BusyFilePolicy::Wait;
+        config.targets[0].prealloc_mode = PreallocMode::Zerofill;
+        
+        // A v2 coordinator's CONFIG decodes, with the v3 fields defaulted
+        let v2_bytes = encode_for_version(&config, 2);
+        let from_v2: V2Config = rmp_serde::from_slice(&v2_bytes).unwrap();
+        let decoded: Config = rmp_serde::from_slice(&encode_for_version(&from_v2, 2)).unwrap();
+        let target = &decoded.targets[0];
+        assert_eq!(target.path, std::path::PathBuf::from("/data/test.dat"));
+        assert_eq!(target.file_size, Some(1048576));
+        assert_eq!(target.lock_mode, FileLockMode::Range);
+        assert!(target.preallocate && target.truncate_to_size && target.refill && target.no_refill);
+        assert_eq!(target.refill_pattern, VerifyPattern::Ones);
+        assert_eq!(target.busy_file, BusyFilePolicy::Proceed);
+        assert_eq!(target.prealloc_mode, PreallocMode::Posix);
+        
+        // What we send a v2 node is exactly its layout
+        assert_eq!(encode_for_version(&from_v2, 2), v2_bytes);
+        assert!(from_v2.targets[0].preallocate && from_v2.targets[0].no_refill);
+        
+        // v3 peers keep the v3 fields
+        let decoded: Config = rmp_serde::from_slice(&encode_for_version(&config, 3)).unwrap();
+        assert_eq!(decoded.targets[0].busy_file, BusyFilePolicy::Wait);
+        assert_eq!(decoded.targets[0].prealloc_mode, PreallocMode::Zerofill);
+        assert!(decoded.targets[0].no_refill);
+    }
+
+    #[test]
+    fn test_lost_config_options() {
+        use crate::config::workload::BusyFilePolicy;
+
+        let mut config: Config = toml::from_str(
+            "targets = [{ path = \"/data/test.dat\", file_size = 1048576 }]\n\
+             [workload]\nread_percent = 100\nwrite_percent = 0\ncompletion_mode = \"RunUntilComplete\"\n",
+        ).unwrap();
+        assert!(lost_config_options(&config, 2).unwrap().is_empty());
+
+        config.runtime.read_only = true;
+        config.targets[0].busy_file = BusyFilePolicy::Wait;
+        assert_eq!(lost_config_options(&config, 2).unwrap(), vec!["runtime.read_only", "targets[0].busy_file"]);
+        assert!(lost_config_options(&config, 3).unwrap().is_empty());
+        // Millisecond durations and intervals
+        let mut config: Config = toml::from_str(
+            "targets = [{ path = \"/data/test.dat\", file_size = 1048576 }]\n\
+             [workload]\nread_percent = 100\nwrite_percent = 0\ncompletion_mode = { Duration = { seconds = 1, millis = 500 } }\n",
+        ).unwrap();
+        config.output.live_interval_ms = Some(250);
+        assert_eq!(lost_config_options(&config, 2).unwrap(),
+                   vec!["output.live_interval_ms", "workload.completion_mode.Duration.millis"]);
+    }
+
+    #[test]
+    fn test_serialize_deserialize_status() {
+        let msg = Message::StatusReport(StatusMessage {
+            node_id: "node1".to_string(),
+            software_version: "0.1.0".to_string(),
+            min_protocol_version: MIN_PROTOCOL_VERSION,
+            max_protocol_version: PROTOCOL_VERSION,
+            uptime_ns: 42_000_000_000,
+            tests_run: 3,
+            current_test: Some(ActiveTestStatus {
+                job_id: "node1-4".to_string(),
+                coordinator: "10.0.1.1:50000".to_string(),
+                elapsed_ns: 1_000_000_000,
+                num_workers: 8,
+            }),
+            queued_jobs: 1,
+            max_queued_jobs: 4,
+            capabilities: vec!["sync".to_string(), "mmap".to_string()],
+        });
+        
+        let bytes = serialize_message(&msg).unwrap();
+        let (deserialized, _) = deserialize_message(&bytes).unwrap();
+        
+        match deserialized {
+            Message::StatusReport(status) => {
+                assert_eq!(status.tests_run, 3);
+                assert_eq!(status.current_test.unwrap().num_workers, 8);
+                assert_eq!(status.capabilities.len(), 2);
+            }
+            _ => panic!("Wrong message type"),
+        }
+    }
+    
+    #[test]
+    fn test_serialize_deserialize_hello() {
+        let bytes = serialize_message(&Message::Hello(HelloMessage::local())).unwrap();
+        let (deserialized, _) = deserialize_message(&bytes).unwrap();
+        
+        match deserialized {
+            Message::Hello(hello) => {
+                assert_eq!(hello.min_version, MIN_PROTOCOL_VERSION);
+                assert_eq!(hello.max_version, PROTOCOL_VERSION);
+            }
+            _ => panic!("Wrong message type"),
+        }
     }
     
     #[test]
@@ -0,0 +1,143 @@
+//! Role-based node groups
+//!
+//! Nodes can be given a role in the clients file (or `--host-list`) by
+//! appending `=<role>` to the address:
+//!
+//! ```text
+//! 10.0.1.10:9999=writer
+//! 10.0.1.11=reader
+//! 10.0.1.12=metadata
+//! ```
+//!
+//! Each role may have a workload fragment (`--role-workload writer=writer.toml`):
+//! a TOML table of workload fields that replace those of the command-line
+//! workload for the role's nodes, e.g.
+//!
+//! ```toml
+//! read_percent = 0
+//! write_percent = 100
+//! block_size = 1048576
+//! ```
+//!
+//! All nodes still start together and run for the same completion condition;
+//! results are reported for the whole cluster and per role.
+
+use crate::config::WorkloadConfig;
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Node address with its optional role
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeEntry {
+    pub address: String,
+    pub role: Option<String>,
+}
+
+/// Parse a node entry of the form `host[:port][=role]`
+///
+/// The default port is appended when the address has none.
+pub fn parse_node_entry(entry: &str, default_port: u16) -> Result<NodeEntry> {
+    let (address, role) = match entry.split_once('=') {
+        Some((address, role)) => {
+            let role = role.trim();
+            if role.is_empty() {
+                anyhow::bail!("Empty role for node {}", address.trim());
+            }
+            (address.trim(), Some(role.to_string()))
+        }
+        None => (entry.trim(), None),
+    };
+    if address.is_empty() {
+        anyhow::bail!("Missing node address in '{}'", entry);
+    }
+    let address = if address.contains(':') {
+        address.to_string()
+    } else {
+        format!("{}:{}", address, default_port)
+    };
+    Ok(NodeEntry { address, role })
+}
+
+/// Load `ROLE=FILE` workload fragments
+pub fn load_fragments(specs: &[String]) -> Result<BTreeMap<String, toml::Table>> {
+    let mut fragments = BTreeMap::new();
+    for spec in specs {
+        let (role, path) = spec.split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("Invalid --role-workload '{}' (expected ROLE=FILE)", spec))?;
+        let contents = std::fs::read_to_string(Path::new(path.trim()))
+            .with_context(|| format!("Failed to read workload fragment {}", path.trim()))?;
+        let fragment: toml::Table = contents.parse()
+            .with_context(|| format!("Failed to parse workload fragment {}", path.trim()))?;
+        if fragments.insert(role.trim().to_string(), fragment).is_some() {
+            anyhow::bail!("Duplicate --role-workload for role {}", role.trim());
+        }
+    }
+    Ok(fragments)
+}
+
+/// Apply a workload fragment on top of a workload
+///
+/// Fields present in the fragment replace those of `workload`; nested
+/// tables are merged field by field.
+pub fn apply_fragment(workload: &WorkloadConfig, fragment: &toml::Table) -> Result<WorkloadConfig> {
+    let mut merged = toml::Table::try_from(workload)
+        .context("Failed to convert workload to TOML")?;
+    crate::config::toml::merge_table(&mut merged, fragment);
+    toml::Value::Table(merged).try_into()
+        .context("Invalid workload fragment")
+}
+
+/// Resolve the workload of every role that has a fragment
+///
+/// Fails on fragments for roles no node has (usually a typo).
+pub fn resolve_workloads(
+    base: &WorkloadConfig,
+    roles: &[Option<String>],
+    fragments: &BTreeMap<String, toml::Table>,
+) -> Result<BTreeMap<String, WorkloadConfig>> {
+    let mut workloads = BTreeMap::new();
+    for (role, fragment) in fragments {
+        if !roles.iter().any(|r| r.as_deref() == Some(role.as_str())) {
+            anyhow::bail!("--role-workload for role '{}', but no node has that role", role);
+        }
+        let workload = apply_fragment(base, fragment)
+            .with_context(|| format!("Invalid workload for role '{}'", role))?;
+        crate::config::validator::validate_workload(&workload)
+            .with_context(|| format!("Invalid workload for role '{}'", role))?;
+        if workload.completion_mode != base.completion_mode {
+            anyhow::bail!("Role '{}' cannot change the completion mode (all nodes start and stop together)", role);
+        }
+        workloads.insert(role.clone(), workload);
+    }
+    Ok(workloads)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_node_entry() {
+        assert_eq!(parse_node_entry("10.0.1.10", 9999).unwrap(),
+                   NodeEntry { address: "10.0.1.10:9999".to_string(), role: None });
+        assert_eq!(parse_node_entry(" host2:7000 = reader ", 9999).unwrap(),
+                   NodeEntry { address: "host2:7000".to_string(), role: Some("reader".to_string()) });
+        assert!(parse_node_entry("host3=", 9999).is_err());
+    }
+
+    #[test]
+    fn test_apply_fragment() {
+        let base: WorkloadConfig = toml::from_str(
+            "read_percent = 100\nwrite_percent = 0\n[completion_mode.Duration]\nseconds = 60",
+        ).unwrap();
+        let fragment: toml::Table = "read_percent = 0\nwrite_percent = 100\nblock_size = 1048576".parse().unwrap();
+        let workload = apply_fragment(&base, &fragment).unwrap();
+        assert_eq!(workload.write_percent, 100);
+        assert_eq!(workload.block_size, 1048576);
+        assert_eq!(workload.queue_depth, base.queue_depth);
+
+        let bad: toml::Table = "block_size = \"big\"".parse().unwrap();
+        assert!(apply_fragment(&base, &bad).is_err());
+    }
+}
@@ -0,0 +1,674 @@
+//! Keep-alive and session resumption for test connections
+//!
+//! A long distributed run shouldn't be lost to a brief network outage. Once
+//! a test starts, both ends watch the connection:
+//!
+//! - **Keep-alive**: nodes send a heartbeat every live interval and the coordinator
+//!   acknowledges each one. Either end treats `keepalive_timeout` seconds
+//!   without the other's messages as a lost connection, even if TCP hasn't
+//!   noticed yet.
+//! - **Reconnect**: the coordinator connects to the node again and sends
+//!   RESUME with the job ID, retrying until `reconnect_timeout`. This runs on
+//!   a background task, so the other nodes are still serviced (and their
+//!   heartbeats acknowledged). The node keeps its workers running meanwhile.
+//! - **Resumption**: heartbeats carry sequence numbers and the node keeps
+//!   each one until the coordinator acknowledges it. After RESUMED it
+//!   replays the unacknowledged ones, and the coordinator skips any it
+//!   already had, so the time-series has no gap. Heartbeats carry cumulative
+//!   counters, so none are needed for the final results.
+//!
+//! Every loss and reconnect is reported on both ends. Session resumption is
+//! only used when both sides support it (`ConfigMessage::keepalive` and
+//! `ReadyMessage::resumable`); otherwise a lost connection fails the run as
+//! before.
+
+use crate::distributed::coordinator::connect_and_negotiate;
+use crate::distributed::node_service::{read_message_from_read_half, write_message_to_write_half};
+use crate::distributed::protocol::*;
+use anyhow::{Context, Result};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+use tokio::net::tcp::OwnedWriteHalf;
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// Longest a single reconnect attempt may take
+const ATTEMPT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Pause between reconnect attempts
+const RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Hands a replacement connection (and its protocol version) to a running job
+pub type ResumeSender = mpsc::Sender<(TcpStream, u32)>;
+
+/// Keep-alive and reconnect timeouts of a session
+#[derive(Debug, Clone, Copy)]
+pub struct SessionTimeouts {
+    /// Silence after which the connection counts as lost
+    pub keepalive: Duration,
+    /// How long a lost connection may take to come back
+    pub reconnect: Duration,
+}
+
+impl SessionTimeouts {
+    /// Timeouts from the runtime config (None if reconnecting is disabled)
+    pub fn from_config(runtime: &crate::config::RuntimeConfig) -> Option<Self> {
+        (runtime.reconnect_timeout > 0).then(|| Self {
+            keepalive: Duration::from_secs(runtime.keepalive_timeout),
+            reconnect: Duration::from_secs(runtime.reconnect_timeout),
+        })
+    }
+}
+
+/// Coordinator end of one node's test connection
+///
+/// A lost connection is re-established on a background task, so the
+/// coordinator keeps servicing the other nodes meanwhile.
+#[derive(Debug)]
+pub struct NodeSession {
+    node_idx: usize,
+    addr: String,
+    job_id: String,
+    /// None when the node can't resume (a lost connection fails the run)
+    timeouts: Option<SessionTimeouts>,
+    /// Heartbeats are due (false once the test is stopping)
+    expect_heartbeats: bool,
+    last_heard: Instant,
+    /// Sequence number of the newest heartbeat received
+    last_seq: u64,
+    /// Reconnect in progress: when the connection was lost, and the task resuming it
+    reconnecting: Option<(Instant, JoinHandle<Result<Resumed>>)>,
+    /// Messages to send once the connection is back
+    pending: VecDeque<Message>,
+    reconnects: usize,
+    downtime: Duration,
+}
+
+/// A resumed connection, with the node's reply and the attempts it took
+type Resumed = (TcpStream, ResumedMessage, usize);
+
+impl NodeSession {
+    /// Session of a node that reported ready
+    pub fn new(node_idx: usize, addr: String, job_id: String, timeouts: Option<SessionTimeouts>) -> Self {
+        Self {
+            node_idx,
+            addr,
+            job_id,
+            timeouts,
+            expect_heartbeats: true,
+            last_heard: Instant::now(),
+            last_seq: 0,
+            reconnecting: None,
+            pending: VecDeque::new(),
+            reconnects: 0,
+            downtime: Duration::ZERO,
+        }
+    }
+
+    /// Stop treating silence as a lost connection (the node is collecting results)
+    pub fn stop_expecting_heartbeats(&mut self) {
+        self.expect_heartbeats = false;
+    }
+
+    /// Index of the node
+    pub fn node_idx(&self) -> usize {
+        self.node_idx
+    }
+
+    /// Times the connection was re-established, and the total time it was down
+    pub fn reconnects(&self) -> (usize, Duration) {
+        (self.reconnects, self.downtime)
+    }
+
+    /// Read the next message, waiting at most `wait`
+    ///
+    /// Returns None on timeout and while the connection is being replaced
+    /// (the replayed heartbeats follow on the new one). Heartbeats are
+    /// acknowledged as keep-alives; replayed ones that already arrived
+    /// before the loss are skipped.
+    pub async fn read(&mut self, stream: &mut TcpStream, wait: Duration) -> Result<Option<Message>> {
+        if self.reconnecting.is_some() {
+            self.finish_reconnect(stream, wait).await?;
+            return Ok(None);
+        }
+        match tokio::time::timeout(wait, read_message(stream)).await {
+            Ok(Ok(msg)) => {
+                self.last_heard = Instant::now();
+                if let Message::Heartbeat(ref hb) = msg {
+                    if self.timeouts.is_some() {
+                        // A failed write shows up as a read error next time
+                        let _ = write_message(stream, &Message::HeartbeatAck(hb.seq)).await;
+                    }
+                    if hb.seq != 0 && hb.seq <= self.last_seq {
+                        return Ok(None);
+                    }
+                    self.last_seq = hb.seq;
+                }
+                Ok(Some(msg))
+            }
+            Ok(Err(e)) => match self.timeouts {
+                Some(timeouts) => {
+                    self.lose(timeouts, &format!("{:#}", e));
+                    Ok(None)
+                }
+                None => Err(e),
+            },
+            Err(_) => match self.timeouts {
+                Some(timeouts) if self.expect_heartbeats && self.last_heard.elapsed() >= timeouts.keepalive => {
+                    self.lose(timeouts, &format!("no heartbeat for {}s", timeouts.keepalive.as_secs()));
+                    Ok(None)
+                }
+                _ => Ok(None),
+            },
+        }
+    }
+
+    /// Send a message; while the connection is being replaced it is queued
+    /// and sent once the node is back
+    pub async fn send(&mut self, stream: &mut TcpStream, msg: &Message) -> Result<()> {
+        if self.reconnecting.is_some() {
+            self.pending.push_back(msg.clone());
+            return Ok(());
+        }
+        match (write_message(stream, msg).await, self.timeouts) {
+            (Ok(()), _) => Ok(()),
+            (Err(e), Some(timeouts)) => {
+                self.lose(timeouts, &format!("{:#}", e));
+                self.pending.push_back(msg.clone());
+                Ok(())
+            }
+            (Err(e), None) => Err(e),
+        }
+    }
+
+    /// Start replacing a lost connection in the background
+    fn lose(&mut self, timeouts: SessionTimeouts, reason: &str) {
+        eprintln!("Warning: Lost connection to node {} ({}): {}; reconnecting for up to {}s",
+            self.node_idx, self.addr, reason, timeouts.reconnect.as_secs());
+        tracing::warn!("lost connection to node {} ({}): {}", self.node_idx, self.addr, reason);
+
+        let task = tokio::spawn(reconnect(self.node_idx, self.addr.clone(), self.job_id.clone(), timeouts));
+        self.reconnecting = Some((Instant::now(), task));
+    }
+
+    /// Wait up to `wait` for the reconnect; take over the new connection once it's done
+    async fn finish_reconnect(&mut self, stream: &mut TcpStream, wait: Duration) -> Result<()> {
+        let Some((lost, task)) = self.reconnecting.as_mut() else {
+            return Ok(());
+        };
+        let Ok(joined) = tokio::time::timeout(wait, task).await else {
+            return Ok(());
+        };
+        let down = lost.elapsed();
+        self.reconnecting = None;
+
+        let (new_stream, resumed, attempts) = match joined.context("Reconnect task failed").and_then(|r| r) {
+            Ok(resumed) => resumed,
+            Err(e) => {
+                // Given up: later reads and writes fail at once
+                self.timeouts = None;
+                return Err(e);
+            }
+        };
+        *stream = new_stream;
+        self.reconnects += 1;
+        self.downtime += down;
+        self.last_heard = Instant::now();
+        let dropped = if resumed.dropped > 0 {
+            format!(", {} dropped", resumed.dropped)
+        } else {
+            String::new()
+        };
+        eprintln!("Reconnected to node {} ({}) after {:.1}s ({} attempts, {} buffered heartbeats{})",
+            self.node_idx, self.addr, down.as_secs_f64(), attempts, resumed.buffered, dropped);
+        tracing::info!("reconnected to node {} after {:.1}s", self.node_idx, down.as_secs_f64());
+
+        // Lost again: send() queues the rest for the next connection
+        while self.reconnecting.is_none() {
+            let Some(msg) = self.pending.pop_front() else { break };
+            self.send(stream, &msg).await?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for NodeSession {
+    fn drop(&mut self) {
+        if let Some((_, task)) = self.reconnecting.take() {
+            task.abort();
+        }
+    }
+}
+
+/// Reconnect to a node and resume its job, retrying until `timeouts.reconnect`
+async fn reconnect(node_idx: usize, addr: String, job_id: String, timeouts: SessionTimeouts) -> Result<Resumed> {
+    let lost = Instant::now();
+    let mut last_error = String::new();
+    let mut attempts = 0;
+    while lost.elapsed() < timeouts.reconnect {
+        attempts += 1;
+        match tokio::time::timeout(ATTEMPT_TIMEOUT, resume(&addr, &job_id)).await {
+            Ok(Ok(Ok((stream, resumed)))) => return Ok((stream, resumed, attempts)),
+            Ok(Ok(Err(rejected))) => {
+                anyhow::bail!("Node {} ({}) cannot resume job {}: {}", node_idx, addr, job_id, rejected);
+            }
+            Ok(Err(e)) => last_error = format!("{:#}", e),
+            Err(_) => last_error = format!("no answer within {}s", ATTEMPT_TIMEOUT.as_secs()),
+        }
+        tokio::time::sleep(RETRY_INTERVAL).await;
+    }
+    anyhow::bail!("Lost connection to node {} ({}) and could not reconnect within {}s: {}",
+        node_idx, addr, timeouts.reconnect.as_secs(), last_error)
+}
+
+/// One reconnect attempt: Err to retry, Ok(Err) if the node rejected it
+async fn resume(addr: &str, job_id: &str) -> Result<std::result::Result<(TcpStream, ResumedMessage), String>> {
+    let (mut stream, version) = connect_and_negotiate(addr).await?;
+    let resume = ResumeMessage { job_id: job_id.to_string() };
+    write_message_for_version(&mut stream, &Message::Resume(resume), version).await?;
+    match read_message(&mut stream).await? {
+        Message::Resumed(resumed) => Ok(Ok((stream, resumed))),
+        Message::Error(err) => Ok(Err(err.error)),
+        other => anyhow::bail!("Expected RESUMED, got {:?}", other),
+    }
+}
+
+/// Node end of a test connection
+///
+/// Reads on a separate task, so waiting for a message never loses part of
+/// one. Heartbeats are kept until the coordinator acknowledges them (a write
+/// can succeed on a connection that is already dead) and replayed when it
+/// resumes the session.
+pub struct CoordinatorLink {
+    node_id: String,
+    version: u32,
+    /// None for coordinators that can't resume (a lost connection stops the test)
+    timeouts: Option<SessionTimeouts>,
+    write: Option<OwnedWriteHalf>,
+    incoming: Option<mpsc::Receiver<Result<Message>>>,
+    reader: Option<JoinHandle<()>>,
+    resumes: mpsc::Receiver<(TcpStream, u32)>,
+    /// Heartbeats not yet acknowledged, oldest first, by sequence number
+    unacked: VecDeque<(u64, Message)>,
+    next_seq: u64,
+    dropped: usize,
+    last_heard: Instant,
+    lost: Option<(Instant, String)>,
+}
+
+impl CoordinatorLink {
+    /// Link over `stream`; replacement connections arrive on `resumes`
+    pub fn new(
+        stream: TcpStream,
+        version: u32,
+        node_id: String,
+        timeouts: Option<SessionTimeouts>,
+        resumes: mpsc::Receiver<(TcpStream, u32)>,
+    ) -> Self {
+        let mut link = Self {
+            node_id,
+            version,
+            timeouts,
+            write: None,
+            incoming: None,
+            reader: None,
+            resumes,
+            unacked: VecDeque::new(),
+            next_seq: 1,
+            dropped: 0,
+            last_heard: Instant::now(),
+            lost: None,
+        };
+        link.attach(stream, version);
+        link
+    }
+
+    fn attach(&mut self, stream: TcpStream, version: u32) {
+        let (mut read, write) = stream.into_split();
+        let (tx, rx) = mpsc::channel(16);
+        self.reader = Some(tokio::spawn(async move {
+            loop {
+                let msg = read_message_from_read_half(&mut read).await;
+                let failed = msg.is_err();
+                if tx.send(msg).await.is_err() || failed {
+                    break;
+                }
+            }
+        }));
+        self.write = Some(write);
+        self.incoming = Some(rx);
+        self.version = version;
+        self.last_heard = Instant::now();
+        self.lost = None;
+    }
+
+    fn detach(&mut self) {
+        self.write = None;
+        self.incoming = None;
+        if let Some(reader) = self.reader.take() {
+            reader.abort();
+        }
+    }
+
+    /// Next message from the coordinator (None if the connection was just lost)
+    ///
+    /// Replacement connections from a RESUME are taken over here, so while
+    /// disconnected this waits for the coordinator to come back.
+    pub async fn recv(&mut self) -> Option<Message> {
+        loop {
+            let resumed = match self.incoming.as_mut() {
+                Some(incoming) => tokio::select! {
+                    msg = incoming.recv() => match msg {
+                        Some(Ok(Message::HeartbeatAck(seq))) => {
+                            self.last_heard = Instant::now();
+                            while self.unacked.front().is_some_and(|(unacked, _)| *unacked <= seq) {
+                                self.unacked.pop_front();
+                            }
+                            continue;
+                        }
+                        Some(Ok(msg)) => {
+                            self.last_heard = Instant::now();
+                            return Some(msg);
+                        }
+                        Some(Err(e)) => {
+                            self.lose(format!("{:#}", e));
+                            return None;
+                        }
+                        None => {
+                            self.lose("connection closed".to_string());
+                            return None;
+                        }
+                    },
+                    resumed = self.resumes.recv() => resumed,
+                },
+                None => self.resumes.recv().await,
+            };
+            match resumed {
+                Some((stream, version)) => {
+                    if let Err(e) = self.resume(stream, version).await {
+                        tracing::warn!("Failed to resume session: {:#}", e);
+                    }
+                }
+                None => return std::future::pending().await,
+            }
+        }
+    }
+
+    /// Continue on a replacement connection and replay the unacknowledged heartbeats
+    async fn resume(&mut self, stream: TcpStream, version: u32) -> Result<()> {
+        let down = self.lost.as_ref().map(|(lost, _)| lost.elapsed());
+        self.detach();
+        self.attach(stream, version);
+
+        let resumed = ResumedMessage {
+            node_id: self.node_id.clone(),
+            buffered: self.unacked.len(),
+            dropped: self.dropped,
+        };
+        match down {
+            Some(down) => println!("Coordinator reconnected after {:.1}s; replaying {} unacknowledged heartbeats",
+                down.as_secs_f64(), resumed.buffered),
+            None => println!("Coordinator replaced its connection; replaying {} unacknowledged heartbeats", resumed.buffered),
+        }
+        self.dropped = 0;
+        self.write_now(&Message::Resumed(resumed)).await?;
+        // They stay unacknowledged until the coordinator confirms them
+        for i in 0..self.unacked.len() {
+            let msg = self.unacked[i].1.clone();
+            self.write_now(&msg).await?;
+        }
+        Ok(())
+    }
+
+    async fn write_now(&mut self, msg: &Message) -> Result<()> {
+        let write = self.write.as_mut().context("Not connected to the coordinator")?;
+        write_message_to_write_half(write, msg, self.version).await
+    }
+
+    /// Send a heartbeat, keeping it until the coordinator acknowledges it
+    ///
+    /// While disconnected it is only kept. Without session resumption
+    /// nothing is kept (a lost connection stops the test).
+    pub async fn send(&mut self, mut heartbeat: HeartbeatMessage) {
+        heartbeat.seq = self.next_seq;
+        self.next_seq += 1;
+        let msg = Message::Heartbeat(heartbeat);
+        if self.write.is_some() {
+            if let Err(e) = self.write_now(&msg).await {
+                self.lose(format!("{:#}", e));
+            }
+        }
+        if let Some(timeouts) = self.timeouts {
+            // About one heartbeat per second of the reconnect window
+            if self.unacked.len() as u64 > timeouts.reconnect.as_secs() + 10 {
+                self.unacked.pop_front();
+                self.dropped += 1;
+            }
+            self.unacked.push_back((self.next_seq - 1, msg));
+        }
+    }
+
+    /// Send a message that must arrive (errors, results), waiting for a reconnect if needed
+    pub async fn deliver(&mut self, msg: &Message) -> Result<()> {
+        loop {
+            if self.write.is_some() {
+                match self.write_now(msg).await {
+                    Ok(()) => return Ok(()),
+                    Err(e) => self.lose(format!("{:#}", e)),
+                }
+            }
+            let (Some(timeouts), Some((lost, reason))) = (self.timeouts, self.lost.clone()) else {
+                anyhow::bail!("Connection to coordinator lost");
+            };
+            let remaining = timeouts.reconnect.saturating_sub(lost.elapsed());
+            match tokio::time::timeout(remaining, self.resumes.recv()).await {
+                Ok(Some((stream, version))) => self.resume(stream, version).await?,
+                _ => anyhow::bail!("Coordinator did not reconnect within {}s ({})", timeouts.reconnect.as_secs(), reason),
+            }
+        }
+    }
+
+    /// Mark the connection lost
+    fn lose(&mut self, reason: String) {
+        if self.write.is_none() && self.incoming.is_none() {
+            return;
+        }
+        self.detach();
+        match self.timeouts {
+            Some(timeouts) => {
+                println!("Connection to coordinator lost ({}); test keeps running for up to {}s while it reconnects",
+                    reason, timeouts.reconnect.as_secs());
+                tracing::warn!("connection to coordinator lost: {}", reason);
+            }
+            None => tracing::error!("Error reading message: {}", reason),
+        }
+        self.lost = Some((Instant::now(), reason));
+    }
+
+    /// Check the keep-alive; Err once the connection is lost for good
+    pub fn check(&mut self) -> Result<()> {
+        if let Some(timeouts) = self.timeouts {
+            if self.write.is_some() && self.last_heard.elapsed() >= timeouts.keepalive {
+                self.lose(format!("no heartbeat acknowledgment for {}s", timeouts.keepalive.as_secs()));
+            }
+        }
+        match (&self.lost, self.timeouts) {
+            (Some((lost, reason)), Some(timeouts)) if lost.elapsed() >= timeouts.reconnect => {
+                anyhow::bail!("Coordinator did not reconnect within {}s ({})", timeouts.reconnect.as_secs(), reason)
+            }
+            (Some((_, reason)), None) => anyhow::bail!("Connection to coordinator lost ({})", reason),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Drop for CoordinatorLink {
+    fn drop(&mut self) {
+        self.detach();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_timeouts_from_config() {
+        let mut runtime = crate::config::RuntimeConfig::default();
+        let timeouts = SessionTimeouts::from_config(&runtime).unwrap();
+        assert_eq!(timeouts.keepalive, Duration::from_secs(15));
+        assert_eq!(timeouts.reconnect, Duration::from_secs(60));
+
+        // Reconnecting disabled: a lost connection fails the run
+        runtime.reconnect_timeout = 0;
+        assert!(SessionTimeouts::from_config(&runtime).is_none());
+    }
+
+    const TIMEOUTS: SessionTimeouts = SessionTimeouts {
+        keepalive: Duration::from_secs(10),
+        reconnect: Duration::from_secs(5),
+    };
+    const WAIT: Duration = Duration::from_millis(100);
+
+    fn heartbeat(elapsed_secs: u64) -> HeartbeatMessage {
+        HeartbeatMessage {
+            node_id: "node".to_string(),
+            elapsed_ns: elapsed_secs * 1_000_000_000,
+            stats: WorkerStatsSnapshot::from(&crate::stats::WorkerStats::new()),
+            per_worker_stats: None,
+            seq: 0,
+        }
+    }
+
+    /// A connection whose peer is gone (reads fail with EOF)
+    async fn closed_stream() -> TcpStream {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let stream = TcpStream::connect(listener.local_addr().unwrap()).await.unwrap();
+        drop(listener.accept().await.unwrap());
+        stream
+    }
+
+    /// Answer HELLO and RESUME like the node service, handing resumed connections to `resumes`
+    async fn accept_resumes(listener: tokio::net::TcpListener, resumes: ResumeSender, job_id: &'static str) {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let Ok(Message::Hello(_)) = read_message(&mut stream).await else { continue };
+            let ack = HelloAckMessage {
+                node_id: "node".to_string(),
+                selected_version: PROTOCOL_VERSION,
+                min_version: MIN_PROTOCOL_VERSION,
+                max_version: PROTOCOL_VERSION,
+            };
+            write_message(&mut stream, &Message::HelloAck(ack)).await.unwrap();
+            match read_message(&mut stream).await {
+                Ok(Message::Resume(resume)) if resume.job_id == job_id => {
+                    resumes.send((stream, PROTOCOL_VERSION)).await.unwrap();
+                }
+                _ => {
+                    let error = ErrorMessage {
+                        node_id: "node".to_string(),
+                        error: "no running job to resume".to_string(),
+                        elapsed_ns: 0,
+                        worker_id: None,
+                        backtrace: None,
+                    };
+                    write_message(&mut stream, &Message::Error(error)).await.unwrap();
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resume_replays_unacknowledged_heartbeats() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let mut coordinator = TcpStream::connect(&addr).await.unwrap();
+        let (node, _) = listener.accept().await.unwrap();
+        let (resume_tx, resume_rx) = mpsc::channel(1);
+        let mut link = CoordinatorLink::new(node, PROTOCOL_VERSION, "node".to_string(), Some(TIMEOUTS), resume_rx);
+        tokio::spawn(accept_resumes(listener, resume_tx, "job-1"));
+        let mut session = NodeSession::new(0, addr, "job-1".to_string(), Some(TIMEOUTS));
+
+        link.send(heartbeat(1)).await;
+        let msg = session.read(&mut coordinator, Duration::from_secs(5)).await.unwrap();
+        assert!(matches!(msg, Some(Message::Heartbeat(ref hb)) if hb.seq == 1));
+
+        // The connection dies; heartbeat 2 goes into it before the node notices
+        drop(coordinator);
+        link.send(heartbeat(2)).await;
+        let node = tokio::spawn(async move {
+            while !matches!(link.recv().await, Some(Message::Stop)) {}
+            link
+        });
+
+        let mut stream = closed_stream().await;
+        assert!(session.read(&mut stream, WAIT).await.unwrap().is_none());
+        // Queued until the connection is back
+        session.send(&mut stream, &Message::Stop).await.unwrap();
+
+        let start = Instant::now();
+        let mut replayed = Vec::new();
+        while replayed.is_empty() {
+            assert!(start.elapsed() < Duration::from_secs(10), "no heartbeat after the resume");
+            if let Some(Message::Heartbeat(hb)) = session.read(&mut stream, WAIT).await.unwrap() {
+                replayed.push(hb.seq);
+            }
+        }
+        // Heartbeat 1 was acknowledged (or is skipped as a duplicate); 2 was not lost
+        assert_eq!(replayed, vec![2]);
+        assert_eq!(session.reconnects().0, 1);
+
+        let mut link = tokio::time::timeout(Duration::from_secs(5), node).await.unwrap().unwrap();
+        // The acknowledgment of the replayed heartbeat clears it
+        let _ = tokio::time::timeout(WAIT, link.recv()).await;
+        assert!(link.unacked.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_resume_rejected() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let (resume_tx, _resume_rx) = mpsc::channel(1);
+        tokio::spawn(accept_resumes(listener, resume_tx, "other-job"));
+        let mut session = NodeSession::new(0, addr, "job-1".to_string(), Some(TIMEOUTS));
+
+        let mut stream = closed_stream().await;
+        let start = Instant::now();
+        let err = loop {
+            assert!(start.elapsed() < Duration::from_secs(10));
+            if let Err(e) = session.read(&mut stream, WAIT).await {
+                break e;
+            }
+        };
+        assert!(format!("{:#}", err).contains("cannot resume job job-1"));
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_gives_up() {
+        // Nothing listens there any more
+        let addr = {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            listener.local_addr().unwrap().to_string()
+        };
+        let timeouts = SessionTimeouts { reconnect: Duration::from_secs(2), ..TIMEOUTS };
+        let mut session = NodeSession::new(0, addr, "job-1".to_string(), Some(timeouts));
+        let mut stream = closed_stream().await;
+
+        // Reconnecting runs in the background: reads keep returning promptly
+        let start = Instant::now();
+        let err = loop {
+            let read = Instant::now();
+            let result = session.read(&mut stream, WAIT).await;
+            assert!(read.elapsed() < Duration::from_secs(1));
+            assert!(start.elapsed() < Duration::from_secs(10));
+            if let Err(e) = result {
+                break e;
+            }
+        };
+        assert!(start.elapsed() >= Duration::from_secs(2));
+        assert!(format!("{:#}", err).contains("could not reconnect within 2s"));
+
+        // Given up: later reads fail at once
+        assert!(session.read(&mut stream, WAIT).await.is_err());
+    }
+}
@@ -0,0 +1,165 @@
+//! Thread count scaling (`--threads auto`)
+//!
+//! Finds how many workers a device needs by running short steps at 1, 2, 4,
+//! ... workers (up to a ceiling) and measuring the throughput of each. The
+//! sweep stops when:
+//!
+//! - **Throughput plateau**: doubling the workers gained less than `plateau_percent`
+//! - **Ceiling reached**: the maximum thread count has been measured
+//!
+//! The chosen thread count is the last one that still gained enough; the
+//! test then runs with it. Unlike the queue depth ramp, each step is a
+//! separate job, so workers of one step never compete with the next.
+//!
+//! # Example
+//!
+//! ```
+//! use iopulse::config::ThreadScalingConfig;
+//! use iopulse::distributed::thread_scaling::ThreadScaling;
+//!
+//! let config = ThreadScalingConfig { step_seconds: 5, plateau_percent: 10.0, max_threads: 16 };
+//! let mut scaling = ThreadScaling::new(config);
+//! assert_eq!(scaling.next_threads(), Some(1));
+//! scaling.record(1, 10_000.0, 40e6, 95, 200);
+//! assert_eq!(scaling.next_threads(), Some(2));
+//! scaling.record(2, 10_500.0, 42e6, 180, 400);
+//! assert_eq!(scaling.next_threads(), None);
+//! assert_eq!(scaling.result().chosen_threads, 1);
+//! ```
+
+use crate::config::ThreadScalingConfig;
+use serde::{Deserialize, Serialize};
+
+/// Measurements of one thread count
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ThreadScalingStep {
+    /// Workers per node
+    pub threads: usize,
+    /// Operations per second (all nodes)
+    pub iops: f64,
+    /// Bytes per second (all nodes)
+    pub throughput_bps: f64,
+    /// Mean latency (microseconds)
+    pub mean_latency_us: u64,
+    /// 99th percentile latency (microseconds)
+    pub p99_latency_us: u64,
+    /// Throughput gain over the previous step (percent; None for the first)
+    pub gain_percent: Option<f64>,
+}
+
+/// Why the sweep stopped
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ThreadScalingStopReason {
+    /// More workers no longer improved throughput
+    ThroughputPlateau,
+    /// The maximum thread count was measured
+    MaxThreads,
+}
+
+impl std::fmt::Display for ThreadScalingStopReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ThreadScalingStopReason::ThroughputPlateau => write!(f, "throughput plateau"),
+            ThreadScalingStopReason::MaxThreads => write!(f, "thread ceiling reached"),
+        }
+    }
+}
+
+/// Scaling curve and the chosen thread count
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ThreadScalingResult {
+    /// Steps in sweep order
+    pub steps: Vec<ThreadScalingStep>,
+    /// Workers per node the test ran with
+    pub chosen_threads: usize,
+    /// Criterion that ended the sweep
+    pub stop_reason: ThreadScalingStopReason,
+}
+
+/// Sweep state: which thread count to measure next
+#[derive(Debug)]
+pub struct ThreadScaling {
+    config: ThreadScalingConfig,
+    steps: Vec<ThreadScalingStep>,
+    stop_reason: Option<ThreadScalingStopReason>,
+}
+
+impl ThreadScaling {
+    /// Start a sweep at one worker
+    pub fn new(config: ThreadScalingConfig) -> Self {
+        Self { config, steps: Vec::new(), stop_reason: None }
+    }
+
+    /// Thread count of the next step (None once the sweep is over)
+    pub fn next_threads(&self) -> Option<usize> {
+        if self.stop_reason.is_some() {
+            return None;
+        }
+        match self.steps.last() {
+            None => Some(1),
+            Some(last) => Some((last.threads * 2).min(self.config.max_threads)),
+        }
+    }
+
+    /// Record the results of a step
+    pub fn record(&mut self, threads: usize, iops: f64, throughput_bps: f64, mean_latency_us: u64, p99_latency_us: u64) {
+        let gain_percent = self.steps.last()
+            .map(|prev| (iops - prev.iops) / prev.iops.max(f64::MIN_POSITIVE) * 100.0);
+        self.steps.push(ThreadScalingStep { threads, iops, throughput_bps, mean_latency_us, p99_latency_us, gain_percent });
+
+        if gain_percent.is_some_and(|gain| gain < self.config.plateau_percent) {
+            self.stop_reason = Some(ThreadScalingStopReason::ThroughputPlateau);
+        } else if threads >= self.config.max_threads {
+            self.stop_reason = Some(ThreadScalingStopReason::MaxThreads);
+        }
+    }
+
+    /// Curve and chosen thread count
+    ///
+    /// At a plateau the step before the one that didn't pay off is chosen,
+    /// otherwise the last step.
+    pub fn result(&self) -> ThreadScalingResult {
+        let stop_reason = self.stop_reason.unwrap_or(ThreadScalingStopReason::MaxThreads);
+        let chosen = match stop_reason {
+            ThreadScalingStopReason::ThroughputPlateau if self.steps.len() >= 2 => &self.steps[self.steps.len() - 2],
+            _ => self.steps.last().expect("thread scaling result before the first step"),
+        };
+        ThreadScalingResult {
+            steps: self.steps.clone(),
+            chosen_threads: chosen.threads,
+            stop_reason,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sweep_reaches_ceiling() {
+        let config = ThreadScalingConfig { step_seconds: 1, plateau_percent: 10.0, max_threads: 6 };
+        let mut scaling = ThreadScaling::new(config);
+        let mut measured = Vec::new();
+        while let Some(threads) = scaling.next_threads() {
+            measured.push(threads);
+            scaling.record(threads, threads as f64 * 1000.0, 0.0, 100, 200);
+        }
+        // Doubling, capped at the ceiling
+        assert_eq!(measured, vec![1, 2, 4, 6]);
+        let result = scaling.result();
+        assert_eq!(result.chosen_threads, 6);
+        assert_eq!(result.stop_reason, ThreadScalingStopReason::MaxThreads);
+        assert_eq!(result.steps[3].gain_percent, Some(50.0));
+
+        // A drop ends the sweep at the previous step
+        let mut scaling = ThreadScaling::new(ThreadScalingConfig { step_seconds: 1, plateau_percent: 10.0, max_threads: 64 });
+        for (threads, iops) in [(1, 1000.0), (2, 1900.0), (4, 3500.0), (8, 3000.0)] {
+            assert_eq!(scaling.next_threads(), Some(threads));
+            scaling.record(threads, iops, 0.0, 100, 200);
+        }
+        assert_eq!(scaling.next_threads(), None);
+        assert_eq!(scaling.result().chosen_threads, 4);
+        assert_eq!(scaling.result().stop_reason, ThreadScalingStopReason::ThroughputPlateau);
+    }
+}
@@ -0,0 +1,159 @@
+//! Two-phase distributed verification
+//!
+//! With `--verify-writers`, data is verified across nodes: one group of nodes
+//! writes the file and a different group reads it back, so lost writes, stale
+//! client caches and torn updates show up as verification failures instead of
+//! being hidden by a node reading back its own page cache.
+//!
+//! The coordinator runs the test as two jobs:
+//!
+//! 1. **write**: the writer nodes fill the whole file once (sequential,
+//!    partitioned across all writer workers, run until complete)
+//! 2. **read**: the reader nodes run the configured workload as 100% reads
+//!    with verification enabled
+//!
+//! Every node receives a [`VerifyAssignment`] in its CONFIG message: its role
+//! plus the pattern and seed shared by both phases. The seed is mixed into the
+//! random pattern, so data left behind by an earlier run fails verification.
+
+use crate::config::workload::{CompletionMode, FileDistribution, VerifyPattern};
+use crate::config::{Config, VerifyGroups};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Role of a node in two-phase verification
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NodeRole {
+    /// Writes the verification pattern (first phase)
+    Writer,
+    /// Reads the data back and verifies it (second phase)
+    Reader,
+}
+
+impl fmt::Display for NodeRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NodeRole::Writer => write!(f, "writer"),
+            NodeRole::Reader => write!(f, "reader"),
+        }
+    }
+}
+
+/// Role and shared verification parameters of one node
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerifyAssignment {
+    pub role: NodeRole,
+    pub pattern: VerifyPattern,
+    pub seed: u64,
+}
+
+impl VerifyAssignment {
+    /// Turn the test configuration into this role's phase
+    ///
+    /// Idempotent: the coordinator applies it to plan the phase and the node
+    /// applies it again to the configuration it receives.
+    pub fn apply(&self, config: &mut Config) {
+        config.runtime.verify = true;
+        config.runtime.verify_pattern = Some(self.pattern);
+        config.runtime.verify_seed = Some(self.seed);
+        config.runtime.verify_groups = None;
+
+        match self.role {
+            NodeRole::Writer => {
+                // One sequential pass over the file, each worker in its own region
+                config.workload.read_percent = 0;
+                config.workload.write_percent = 100;
+                config.workload.random = false;
+                config.workload.completion_mode = CompletionMode::RunUntilComplete;
+                config.workload.qd_ramp = None;
+                config.runtime.stop_at_op = None;
+                for target in &mut config.targets {
+                    target.distribution = FileDistribution::Partitioned;
+                }
+                // JSON and CSV output describe the verifying read phase
+                config.output.json_output = None;
+                config.output.csv_output = None;
+            }
+            NodeRole::Reader => {
+                config.workload.read_percent = 100;
+                config.workload.write_percent = 0;
+            }
+        }
+    }
+}
+
+/// Resolve the writer and reader node indices for `num_nodes` nodes
+///
+/// Readers default to every node that is not a writer.
+pub fn resolve_groups(groups: &VerifyGroups, num_nodes: usize) -> Result<(Vec<usize>, Vec<usize>)> {
+    let readers: Vec<usize> = if groups.readers.is_empty() {
+        (0..num_nodes).filter(|n| !groups.writers.contains(n)).collect()
+    } else {
+        groups.readers.clone()
+    };
+
+    if let Some(&node) = groups.writers.iter().chain(&readers).find(|&&n| n >= num_nodes) {
+        anyhow::bail!("Verification node {} does not exist ({} nodes in the host list)", node, num_nodes);
+    }
+    if groups.writers.is_empty() {
+        anyhow::bail!("Two-phase verification needs at least one writer node");
+    }
+    if readers.is_empty() {
+        anyhow::bail!("Two-phase verification needs at least one reader node that is not a writer");
+    }
+    if let Some(node) = readers.iter().find(|n| groups.writers.contains(n)) {
+        anyhow::bail!("Node {} cannot be both a verification writer and reader", node);
+    }
+    Ok((groups.writers.clone(), readers))
+}
+
+/// Check that the write phase covers the target in blocks the readers can verify
+pub fn check_config(config: &Config, writer_workers: usize) -> Result<()> {
+    if config.targets.len() != 1 {
+        anyhow::bail!("Two-phase verification supports a single target");
+    }
+    let target = &config.targets[0];
+    if target.layout_config.is_some() || target.layout_manifest.is_some() {
+        anyhow::bail!("Two-phase verification does not support directory layouts");
+    }
+    if target.distribution == FileDistribution::PerWorker {
+        anyhow::bail!("Two-phase verification needs a shared or partitioned file (not per-worker)");
+    }
+    if !config.workload.read_distribution.is_empty() || !config.workload.write_distribution.is_empty() {
+        anyhow::bail!("Two-phase verification reads and writes with a single block size (no block size distributions)");
+    }
+    if config.workload.grow_to.is_some() {
+        anyhow::bail!("Two-phase verification does not support --grow-to");
+    }
+
+    // Every writer region must be a whole number of blocks
+    let file_size = target.file_size
+        .ok_or_else(|| anyhow::anyhow!("Two-phase verification requires --file-size"))?;
+    let stripe = config.workload.block_size * writer_workers as u64;
+    if stripe == 0 || file_size % stripe != 0 {
+        anyhow::bail!(
+            "File size {} must be a multiple of block size × writer workers ({} × {} = {})",
+            file_size, config.workload.block_size, writer_workers, stripe
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_groups() {
+        let groups = VerifyGroups { writers: vec![0], readers: Vec::new() };
+        assert_eq!(resolve_groups(&groups, 3).unwrap(), (vec![0], vec![1, 2]));
+
+        let groups = VerifyGroups { writers: vec![2], readers: vec![0] };
+        assert_eq!(resolve_groups(&groups, 3).unwrap(), (vec![2], vec![0]));
+
+        assert!(resolve_groups(&VerifyGroups { writers: vec![0], readers: Vec::new() }, 1).is_err());
+        assert!(resolve_groups(&VerifyGroups { writers: vec![0], readers: vec![0] }, 2).is_err());
+        assert!(resolve_groups(&VerifyGroups { writers: vec![3], readers: Vec::new() }, 2).is_err());
+    }
+}
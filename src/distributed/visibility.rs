@@ -0,0 +1,350 @@
+//! Cross-node metadata visibility (`--visibility-test`)
+//!
+//! On a shared filesystem a file created or renamed by one client is not
+//! always visible to the others right away: NFS clients cache attributes and
+//! failed lookups (`acregmin`/`acdirmin`), Lustre clients wait for DLM lock
+//! callbacks. The visibility test measures that delay instead of running IO.
+//! The first node acts, all other nodes observe, and the coordinator
+//! sequences every batch across them:
+//!
+//! 1. **Lookup**: the observers look up the batch's names once, so their
+//!    caches hold the "no such file" a real reader would have seen
+//! 2. **Act**: the acting node creates the files (or renames each one to
+//!    `<name>.renamed`) and reports how long each operation took
+//! 3. **Observe**: as soon as it replies, the observers stat (or open) every
+//!    name, every 100us, until it appears or the timeout passes
+//!
+//! The paired latency of an operation is the time from an observer starting
+//! to look until it saw the name. It is measured on the observer, so node
+//! clocks don't need to agree; the coordinator's hand-off between steps 2
+//! and 3 (one round trip) comes on top. For renames the source files are
+//! created and seen by every observer before the timed steps of a batch.
+//!
+//! Files go into `iopulse-visibility-<job>` in the target directory, which is
+//! removed at the end.
+
+use crate::config::{VisibilityCheck, VisibilityConfig, VisibilityOp};
+use crate::stats::simple_histogram::SimpleHistogram;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Pause between observation rounds
+const POLL_INTERVAL: Duration = Duration::from_micros(100);
+
+/// What a node does in one step
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VisibilityAction {
+    /// Create the test directory (acting node)
+    Setup,
+    /// Create each file (acting node)
+    Create,
+    /// Rename each file to its `.renamed` path (acting node)
+    Rename,
+    /// Look each path up once (observers)
+    Lookup,
+    /// Check each path until it is visible (observers)
+    Observe,
+    /// Remove the test directory (acting node)
+    Cleanup,
+}
+
+/// One coordinator-sequenced step of the visibility test (protocol v3+)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VisibilityStep {
+    pub action: VisibilityAction,
+    pub paths: Vec<PathBuf>,
+    pub check: VisibilityCheck,
+    pub timeout_ms: u64,
+}
+
+/// A node's outcome of one step (protocol v3+)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VisibilityReport {
+    pub node_id: String,
+    /// Per path: operation time (acting node) or time until visible
+    /// (observers), in nanoseconds; None if it failed or timed out
+    pub latencies_ns: Vec<Option<u64>>,
+    /// Paths already visible at the first check (observers)
+    pub immediate: u64,
+    /// Checks made (observers)
+    pub checks: u64,
+    /// First failure (acting node)
+    pub error: Option<String>,
+}
+
+/// Path a file is renamed to
+pub fn renamed_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".renamed");
+    PathBuf::from(name)
+}
+
+/// Whether `path` is visible to this node
+fn is_visible(path: &Path, check: VisibilityCheck) -> bool {
+    match check {
+        VisibilityCheck::Stat => std::fs::metadata(path).is_ok(),
+        VisibilityCheck::Open => std::fs::File::open(path).is_ok(),
+    }
+}
+
+/// Carry out a step on this node (blocking)
+pub fn execute(step: &VisibilityStep, node_id: String) -> VisibilityReport {
+    let mut report = VisibilityReport { node_id, ..Default::default() };
+    match step.action {
+        VisibilityAction::Observe => observe(step, &mut report),
+        VisibilityAction::Lookup => {
+            for path in &step.paths {
+                is_visible(path, step.check);
+            }
+            report.checks = step.paths.len() as u64;
+        }
+        action => {
+            for path in &step.paths {
+                let start = Instant::now();
+                let result = match action {
+                    VisibilityAction::Setup => std::fs::create_dir_all(path),
+                    VisibilityAction::Create => std::fs::OpenOptions::new()
+                        .write(true)
+                        .create_new(true)
+                        .open(path)
+                        .map(drop),
+                    VisibilityAction::Rename => std::fs::rename(path, renamed_path(path)),
+                    _ => std::fs::remove_dir_all(path),
+                };
+                let elapsed = start.elapsed().as_nanos() as u64;
+                match result {
+                    Ok(()) => report.latencies_ns.push(Some(elapsed)),
+                    Err(e) => {
+                        report.latencies_ns.push(None);
+                        report.error.get_or_insert_with(|| format!("{:?} {}: {}", action, path.display(), e));
+                    }
+                }
+            }
+        }
+    }
+    report
+}
+
+/// Check every path until all are visible or the timeout passes
+fn observe(step: &VisibilityStep, report: &mut VisibilityReport) {
+    let timeout = Duration::from_millis(step.timeout_ms);
+    let mut latencies = vec![None; step.paths.len()];
+    let mut pending: Vec<usize> = (0..step.paths.len()).collect();
+    let start = Instant::now();
+    let mut first_round = true;
+    loop {
+        pending.retain(|&idx| {
+            report.checks += 1;
+            if !is_visible(&step.paths[idx], step.check) {
+                return true;
+            }
+            latencies[idx] = Some(start.elapsed().as_nanos() as u64);
+            if first_round {
+                report.immediate += 1;
+            }
+            false
+        });
+        first_round = false;
+        if pending.is_empty() || start.elapsed() >= timeout {
+            break;
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+    report.latencies_ns = latencies;
+}
+
+/// Latency distribution (microseconds)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VisibilityLatency {
+    pub count: u64,
+    pub mean_us: u64,
+    pub p50_us: u64,
+    pub p99_us: u64,
+    pub max_us: u64,
+}
+
+impl VisibilityLatency {
+    fn from_histogram(hist: &SimpleHistogram) -> Option<Self> {
+        (!hist.is_empty()).then(|| Self {
+            count: hist.len(),
+            mean_us: hist.mean().as_micros() as u64,
+            p50_us: hist.percentile(50.0).as_micros() as u64,
+            p99_us: hist.percentile(99.0).as_micros() as u64,
+            max_us: hist.max().as_micros() as u64,
+        })
+    }
+}
+
+/// What one observing node saw
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ObserverVisibility {
+    /// Node address
+    pub node: String,
+    /// Time until each name was visible
+    pub latency: Option<VisibilityLatency>,
+    /// Names visible at the first check
+    pub immediate: u64,
+    /// Names not visible within the timeout
+    pub timeouts: u64,
+    /// stat/open calls made while waiting
+    pub checks: u64,
+}
+
+/// Outcome of the visibility test
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VisibilityResult {
+    pub op: VisibilityOp,
+    pub check: VisibilityCheck,
+    /// Operations made on the acting node
+    pub ops: u64,
+    /// Operations per batch
+    pub batch: usize,
+    /// Acting node
+    pub actor: String,
+    /// Time of each create or rename on the acting node
+    pub op_latency: Option<VisibilityLatency>,
+    /// Time until visible, over all observers (paired latency)
+    pub visibility_latency: Option<VisibilityLatency>,
+    pub observers: Vec<ObserverVisibility>,
+    /// Operations that failed on the acting node (not observed)
+    pub failed_ops: u64,
+    /// Observations that timed out, over all observers
+    pub timeouts: u64,
+}
+
+/// Collects the reports of the timed steps
+#[derive(Debug)]
+pub struct VisibilityRecorder {
+    op_latency: SimpleHistogram,
+    visibility: SimpleHistogram,
+    observers: Vec<(String, SimpleHistogram, ObserverVisibility)>,
+    ops: u64,
+    failed_ops: u64,
+}
+
+impl VisibilityRecorder {
+    pub fn new(observers: &[String]) -> Self {
+        Self {
+            op_latency: SimpleHistogram::new(),
+            visibility: SimpleHistogram::new(),
+            observers: observers.iter()
+                .map(|node| (node.clone(), SimpleHistogram::new(), ObserverVisibility {
+                    node: node.clone(),
+                    latency: None,
+                    immediate: 0,
+                    timeouts: 0,
+                    checks: 0,
+                }))
+                .collect(),
+            ops: 0,
+            failed_ops: 0,
+        }
+    }
+
+    /// Record the acting node's report; returns the operations that succeeded
+    pub fn record_ops(&mut self, report: &VisibilityReport) -> Vec<bool> {
+        self.ops += report.latencies_ns.len() as u64;
+        report.latencies_ns.iter()
+            .map(|latency| match latency {
+                Some(ns) => {
+                    self.op_latency.record(Duration::from_nanos(*ns));
+                    true
+                }
+                None => {
+                    self.failed_ops += 1;
+                    false
+                }
+            })
+            .collect()
+    }
+
+    /// Record an observer's report on the operations that succeeded
+    pub fn record_observed(&mut self, observer: usize, report: &VisibilityReport) {
+        let (_, hist, seen) = &mut self.observers[observer];
+        seen.immediate += report.immediate;
+        seen.checks += report.checks;
+        for latency in &report.latencies_ns {
+            match latency {
+                Some(ns) => {
+                    hist.record(Duration::from_nanos(*ns));
+                    self.visibility.record(Duration::from_nanos(*ns));
+                }
+                None => seen.timeouts += 1,
+            }
+        }
+    }
+
+    pub fn result(&self, config: &VisibilityConfig, actor: &str) -> VisibilityResult {
+        let observers: Vec<ObserverVisibility> = self.observers.iter()
+            .map(|(_, hist, seen)| ObserverVisibility { latency: VisibilityLatency::from_histogram(hist), ..seen.clone() })
+            .collect();
+        VisibilityResult {
+            op: config.op,
+            check: config.check,
+            ops: self.ops,
+            batch: config.batch,
+            actor: actor.to_string(),
+            op_latency: VisibilityLatency::from_histogram(&self.op_latency),
+            visibility_latency: VisibilityLatency::from_histogram(&self.visibility),
+            timeouts: observers.iter().map(|observer| observer.timeouts).sum(),
+            observers,
+            failed_ops: self.failed_ops,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(action: VisibilityAction, paths: Vec<PathBuf>) -> VisibilityStep {
+        VisibilityStep { action, paths, check: VisibilityCheck::Stat, timeout_ms: 50 }
+    }
+
+    #[test]
+    fn test_steps_on_one_node() {
+        let dir = tempfile::tempdir().unwrap();
+        let test_dir = dir.path().join("iopulse-visibility-test");
+        let paths: Vec<PathBuf> = (0..3).map(|i| test_dir.join(format!("f{}", i))).collect();
+
+        assert!(execute(&step(VisibilityAction::Setup, vec![test_dir.clone()]), "a".into()).error.is_none());
+        let created = execute(&step(VisibilityAction::Create, paths.clone()), "a".into());
+        assert!(created.error.is_none() && created.latencies_ns.iter().all(Option::is_some));
+
+        // Locally everything is visible at the first check
+        let seen = execute(&step(VisibilityAction::Observe, paths.clone()), "b".into());
+        assert_eq!((seen.immediate, seen.checks), (3, 3));
+
+        let renamed = execute(&step(VisibilityAction::Rename, paths.clone()), "a".into());
+        assert!(renamed.error.is_none());
+        assert!(renamed_path(&paths[0]).exists() && !paths[0].exists());
+
+        // Creating an existing name fails; missing names time out
+        let again = execute(&step(VisibilityAction::Create, vec![renamed_path(&paths[0])]), "a".into());
+        assert_eq!(again.latencies_ns, [None]);
+        assert!(again.error.unwrap().contains("Create"));
+        let missing = execute(&step(VisibilityAction::Observe, paths.clone()), "b".into());
+        assert!(missing.latencies_ns.iter().all(Option::is_none) && missing.checks > 3);
+
+        execute(&step(VisibilityAction::Cleanup, vec![test_dir.clone()]), "a".into());
+        assert!(!test_dir.exists());
+    }
+
+    #[test]
+    fn test_recorder() {
+        let config = VisibilityConfig { ops: 2, batch: 2, op: VisibilityOp::Create, check: VisibilityCheck::Open, timeout_ms: 100 };
+        let mut recorder = VisibilityRecorder::new(&["b:9999".to_string(), "c:9999".to_string()]);
+        let done = recorder.record_ops(&VisibilityReport { latencies_ns: vec![Some(20_000), None], ..Default::default() });
+        assert_eq!(done, [true, false]);
+        recorder.record_observed(0, &VisibilityReport { latencies_ns: vec![Some(1_000)], immediate: 1, checks: 1, ..Default::default() });
+        recorder.record_observed(1, &VisibilityReport { latencies_ns: vec![None], checks: 40, ..Default::default() });
+
+        let result = recorder.result(&config, "a:9999");
+        assert_eq!((result.ops, result.failed_ops, result.timeouts), (2, 1, 1));
+        assert_eq!(result.visibility_latency.as_ref().unwrap().count, 1);
+        assert_eq!(result.observers[0].immediate, 1);
+        assert!(result.observers[1].latency.is_none());
+        assert_eq!(result.observers[1].checks, 40);
+    }
+}
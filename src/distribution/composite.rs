@@ -0,0 +1,133 @@
+//! Composite distribution
+//!
+//! Real workloads are rarely a single clean shape: a database may send most
+//! IO to a hot index at the start of a device while the rest scatters over
+//! the whole of it. A composite draws each block from one of several
+//! distributions, picked by weight, and places it in that distribution's
+//! range of the target.
+//!
+//! # Example
+//!
+//! ```
+//! use iopulse::distribution::Distribution;
+//! use iopulse::distribution::composite::{CompositeDistribution, CompositePart};
+//! use iopulse::distribution::{uniform::UniformDistribution, zipf::ZipfDistribution};
+//!
+//! // 80% Zipf over the first 10% of blocks, 20% uniform over all of them
+//! let mut dist = CompositeDistribution::with_seed(vec![
+//!     CompositePart::new(80, 0.0, 0.1, Box::new(ZipfDistribution::with_seed(1.2, 1))),
+//!     CompositePart::new(20, 0.0, 1.0, Box::new(UniformDistribution::with_seed(2))),
+//! ], 3);
+//!
+//! let block_num = dist.next_block(1000);
+//! assert!(block_num < 1000);
+//! ```
+
+use super::Distribution;
+use rand::Rng;
+use rand::SeedableRng;
+use rand_xoshiro::Xoshiro256PlusPlus;
+
+/// One distribution of a composite and the range it covers
+pub struct CompositePart {
+    /// Relative weight
+    weight: u32,
+    /// Range of the target, as fractions (end exclusive)
+    start: f64,
+    end: f64,
+    distribution: Box<dyn Distribution>,
+}
+
+impl CompositePart {
+    /// Draw `weight` of the blocks from `distribution` over `start..end` of the target
+    pub fn new(weight: u32, start: f64, end: f64, distribution: Box<dyn Distribution>) -> Self {
+        Self { weight, start, end, distribution }
+    }
+
+    /// Block range of the part in a target of `num_blocks` blocks (at least one block)
+    fn range(&self, num_blocks: u64) -> (u64, u64) {
+        let first = ((self.start * num_blocks as f64) as u64).min(num_blocks - 1);
+        let end = ((self.end * num_blocks as f64).ceil() as u64).clamp(first + 1, num_blocks);
+        (first, end)
+    }
+}
+
+/// Weighted mix of distributions over ranges of the target
+pub struct CompositeDistribution {
+    parts: Vec<CompositePart>,
+    /// Running total of the weights, one per part
+    cumulative: Vec<u32>,
+    rng: Xoshiro256PlusPlus,
+}
+
+impl CompositeDistribution {
+    /// Create a composite distribution with random seed
+    pub fn new(parts: Vec<CompositePart>) -> Self {
+        Self::with_rng(parts, Xoshiro256PlusPlus::from_entropy())
+    }
+
+    /// Create a composite distribution with specific seed
+    ///
+    /// Seeds only the choice of part; each part keeps its own seed.
+    pub fn with_seed(parts: Vec<CompositePart>, seed: u64) -> Self {
+        Self::with_rng(parts, Xoshiro256PlusPlus::seed_from_u64(seed))
+    }
+
+    fn with_rng(parts: Vec<CompositePart>, rng: Xoshiro256PlusPlus) -> Self {
+        let cumulative = parts.iter()
+            .scan(0u32, |total, part| {
+                *total += part.weight;
+                Some(*total)
+            })
+            .collect();
+        Self { parts, cumulative, rng }
+    }
+}
+
+impl Distribution for CompositeDistribution {
+    #[inline]
+    fn next_block(&mut self, num_blocks: u64) -> u64 {
+        let total = self.cumulative.last().copied().unwrap_or(0);
+        if num_blocks == 0 || total == 0 {
+            return 0;
+        }
+        let pick = self.rng.gen_range(0..total);
+        let index = self.cumulative.partition_point(|&weight| weight <= pick);
+        let part = &mut self.parts[index];
+        let (first, end) = part.range(num_blocks);
+        first + part.distribution.next_block(end - first)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distribution::{uniform::UniformDistribution, zipf::ZipfDistribution};
+
+    #[test]
+    fn test_composite_weights_and_ranges() {
+        let mut dist = CompositeDistribution::with_seed(vec![
+            CompositePart::new(80, 0.0, 0.1, Box::new(ZipfDistribution::with_seed(1.2, 1))),
+            CompositePart::new(20, 0.5, 1.0, Box::new(UniformDistribution::with_seed(2))),
+        ], 3);
+
+        let num_blocks = 1000;
+        let samples = 100_000;
+        let mut low = 0;
+        for _ in 0..samples {
+            let block = dist.next_block(num_blocks);
+            match block {
+                0..100 => low += 1,
+                500..1000 => {}
+                _ => panic!("block {} outside both ranges", block),
+            }
+        }
+        let share = low as f64 / samples as f64;
+        assert!((share - 0.8).abs() < 0.01, "share of the first part: {}", share);
+
+        // A range smaller than a block still covers one block
+        assert_eq!(dist.next_block(1), 0);
+        let part = CompositePart::new(1, 0.95, 0.96, Box::new(UniformDistribution::with_seed(4)));
+        assert_eq!(part.range(10), (9, 10));
+    }
+}
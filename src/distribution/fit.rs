@@ -0,0 +1,324 @@
+//! Goodness of fit of the achieved access distribution
+//!
+//! With `--distribution-report`, the block heatmap of a random workload is
+//! compared with the curve its distribution should produce:
+//!
+//! - **Skew**: the share of accesses the most accessed 1%, 5%, ... of blocks
+//!   received, next to the share the distribution gives them
+//! - **Fit**: the accesses of each `--heatmap-buckets` offset range against
+//!   the expected share, as the total variation distance (the fraction of
+//!   accesses that would have to move to match). The expected distance from
+//!   sampling alone is reported with it, and the fit passes within twice that
+//!   plus 1%
+//!
+//! The expected curves model the generators exactly: Zipf and Pareto ranks
+//! map onto evenly spaced blocks past their CDF size, and Gaussian values
+//! beyond the target are clamped onto its first and last block. In
+//! partitioned mode every worker runs the distribution over its own range.
+//!
+//! Ranked skew is inflated by sampling noise when blocks got only a few
+//! accesses each (a uniform run with one access per block still has a
+//! "hottest 1%"); the bucket fit is not, as long as buckets got many accesses.
+
+use crate::config::workload::DistributionType;
+use crate::stats::heatmap::HeatmapCounts;
+
+/// Fractions of the hottest blocks the skew is reported for
+pub const SKEW_POINTS: [f64; 5] = [0.01, 0.05, 0.10, 0.20, 0.50];
+
+/// Accesses per block below which ranked skew is noted as noisy
+const NOISY_ACCESSES_PER_BLOCK: f64 = 10.0;
+
+/// Slack on top of the sampling noise before the fit fails
+const FIT_SLACK: f64 = 0.01;
+
+/// Gaussian interior cells used to rank blocks by probability
+const GAUSSIAN_CELLS: u64 = 10_000;
+
+/// Share of accesses of the hottest blocks
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SkewPoint {
+    /// Fraction of blocks (hottest first)
+    pub blocks: f64,
+    /// Fraction of accesses they received
+    pub achieved: f64,
+    /// Fraction the distribution gives them
+    pub expected: f64,
+}
+
+/// Achieved distribution against the configured one
+#[derive(Debug, Clone, PartialEq)]
+pub struct DistributionReport {
+    /// Distribution and its parameters
+    pub name: String,
+    /// Blocks the accesses were spread over
+    pub blocks: u64,
+    /// Accesses recorded
+    pub accesses: u64,
+    /// Whether the heatmap counted several blocks together (--stats-mem-limit)
+    pub coarse: bool,
+    pub skew: Vec<SkewPoint>,
+    /// Offset buckets compared
+    pub buckets: usize,
+    /// Total variation distance between achieved and expected bucket shares
+    pub distance: f64,
+    /// Distance expected from sampling noise alone
+    pub noise: f64,
+}
+
+impl DistributionReport {
+    /// Whether the achieved distribution is within sampling noise of the expected one
+    pub fn matches(&self) -> bool {
+        self.distance <= 2.0 * self.noise + FIT_SLACK
+    }
+
+    /// Whether blocks got too few accesses for the ranked skew to be exact
+    pub fn noisy(&self) -> bool {
+        (self.accesses as f64) < self.blocks as f64 * NOISY_ACCESSES_PER_BLOCK
+    }
+}
+
+/// Compare a heatmap with the distribution that generated it
+///
+/// `first_block..first_block + blocks` is the block range the workers drew
+/// from (split into `partitions` equal ranges in partitioned mode). Returns
+/// None without blocks or accesses, or for a composite distribution.
+pub fn distribution_report(
+    distribution: &DistributionType,
+    heatmap: &HeatmapCounts,
+    first_block: u64,
+    blocks: u64,
+    partitions: u64,
+    buckets: usize,
+) -> Option<DistributionReport> {
+    let accesses: u64 = heatmap.entries.iter().map(|&(_, count)| count).sum();
+    if blocks == 0 || accesses == 0 {
+        return None;
+    }
+    let partitions = partitions.clamp(1, blocks);
+    let buckets = buckets.clamp(1, blocks as usize);
+    let model = Model::new(distribution, blocks / partitions)?;
+    let per_block = heatmap.blocks_per_bucket.max(1);
+
+    // Ranked skew: hottest blocks first (a coarse entry spreads over its blocks)
+    let mut achieved: Vec<(u64, f64)> = heatmap.entries.iter()
+        .map(|&(_, count)| (per_block, count as f64 / per_block as f64))
+        .collect();
+    let expected = model.ranked();
+    let skew = SKEW_POINTS.iter()
+        .map(|&fraction| {
+            let top = ((fraction * blocks as f64).ceil() as u64).max(1);
+            SkewPoint {
+                blocks: fraction,
+                achieved: top_share(&mut achieved, top) / accesses as f64,
+                expected: top_share(&mut expected.clone(), top.div_ceil(partitions)),
+            }
+        })
+        .collect();
+
+    // Bucket fit over the whole range
+    let width = blocks.div_ceil(buckets as u64);
+    let mut counts = vec![0u64; buckets];
+    for &(block, count) in &heatmap.entries {
+        let bucket = (block.saturating_sub(first_block) / width).min(buckets as u64 - 1);
+        counts[bucket as usize] += count;
+    }
+    let partition_blocks = blocks / partitions;
+    let mut distance = 0.0;
+    let mut noise = 0.0;
+    for (i, &count) in counts.iter().enumerate() {
+        let (start, end) = (i as u64 * width, ((i as u64 + 1) * width).min(blocks));
+        let expected: f64 = (0..partitions)
+            .map(|p| {
+                let base = p * partition_blocks;
+                let (a, b) = (start.max(base), end.min(base + partition_blocks));
+                if a < b { model.mass(a - base, b - base) } else { 0.0 }
+            })
+            .sum::<f64>() / partitions as f64;
+        distance += (count as f64 / accesses as f64 - expected).abs();
+        noise += (2.0 * expected * (1.0 - expected) / (std::f64::consts::PI * accesses as f64)).sqrt();
+    }
+
+    Some(DistributionReport {
+        name: distribution_name(distribution),
+        blocks,
+        accesses,
+        coarse: per_block > 1,
+        skew,
+        buckets,
+        distance: distance / 2.0,
+        noise: noise / 2.0,
+    })
+}
+
+/// Distribution and its parameters, e.g. "zipf (theta 1.2)"
+pub fn distribution_name(distribution: &DistributionType) -> String {
+    match *distribution {
+        DistributionType::Uniform => "uniform".to_string(),
+        DistributionType::Zipf { theta } => format!("zipf (theta {})", theta),
+        DistributionType::Pareto { h } => format!("pareto (h {})", h),
+        DistributionType::Gaussian { stddev, center, drift: None } => format!("gaussian (stddev {}, center {})", stddev, center),
+        DistributionType::Gaussian { stddev, center, drift: Some(drift) } => {
+            format!("gaussian (stddev {}, center {}, drift {})", stddev, center, drift)
+        }
+        DistributionType::Composite { .. } => distribution.to_string(),
+    }
+}
+
+/// Sum of the `top` largest values of (blocks, value per block) groups
+fn top_share(groups: &mut [(u64, f64)], top: u64) -> f64 {
+    groups.sort_by(|a, b| b.1.total_cmp(&a.1));
+    let mut left = top;
+    let mut share = 0.0;
+    for &(blocks, value) in groups.iter() {
+        let take = blocks.min(left);
+        share += take as f64 * value;
+        left -= take;
+        if left == 0 {
+            break;
+        }
+    }
+    share
+}
+
+/// Access probabilities of one distribution over `blocks` blocks
+enum Model {
+    Uniform { blocks: u64 },
+    /// Zipf and Pareto: `prefix[r]` is the probability of ranks below r
+    Ranked { blocks: u64, prefix: Vec<f64> },
+    Gaussian { blocks: u64, stddev: f64, center: f64 },
+}
+
+impl Model {
+    /// Model of a single distribution (None for a composite)
+    fn new(distribution: &DistributionType, blocks: u64) -> Option<Self> {
+        let ranked = |exponent: f64, max_ranks: u64| {
+            let ranks = blocks.min(max_ranks) as usize;
+            let weights: Vec<f64> = (1..=ranks).map(|i| (i as f64).powf(-exponent)).collect();
+            let total: f64 = weights.iter().sum();
+            let mut prefix = Vec::with_capacity(ranks + 1);
+            prefix.push(0.0);
+            let mut cumulative = 0.0;
+            for weight in weights {
+                cumulative += weight / total;
+                prefix.push(cumulative);
+            }
+            Model::Ranked { blocks, prefix }
+        };
+        match *distribution {
+            DistributionType::Uniform => Some(Model::Uniform { blocks }),
+            DistributionType::Zipf { theta } => Some(ranked(theta, super::zipf::MAX_RANKS)),
+            DistributionType::Pareto { h } => Some(ranked(h, super::pareto::MAX_RANKS)),
+            DistributionType::Gaussian { stddev, center, .. } => Some(Model::Gaussian { blocks, stddev, center }),
+            DistributionType::Composite { .. } => None,
+        }
+    }
+
+    /// Probability of drawing a block in `a..b`
+    fn mass(&self, a: u64, b: u64) -> f64 {
+        match *self {
+            Model::Uniform { blocks } => (b - a) as f64 / blocks as f64,
+            Model::Ranked { blocks, ref prefix } => {
+                // Rank r lands on block r * blocks / ranks
+                let ranks = (prefix.len() - 1) as u128;
+                let first_rank = |block: u64| ((block as u128 * ranks).div_ceil(blocks as u128)).min(ranks) as usize;
+                prefix[first_rank(b)] - prefix[first_rank(a)]
+            }
+            Model::Gaussian { blocks, stddev, center } => {
+                // Values below block 1 clamp onto block 0, values past the end onto the last block
+                let cdf = |block: u64| normal_cdf((block as f64 - center * blocks as f64) / (stddev * blocks as f64));
+                let upper = if b >= blocks { 1.0 } else { cdf(b) };
+                let lower = if a == 0 { 0.0 } else { cdf(a) };
+                upper - lower
+            }
+        }
+    }
+
+    /// Blocks grouped by access probability, as (blocks, probability per block)
+    fn ranked(&self) -> Vec<(u64, f64)> {
+        match *self {
+            Model::Uniform { blocks } => vec![(blocks, 1.0 / blocks as f64)],
+            Model::Ranked { blocks, ref prefix } => {
+                let mut groups: Vec<(u64, f64)> = prefix.windows(2).map(|w| (1, w[1] - w[0])).collect();
+                groups.push((blocks - (prefix.len() as u64 - 1), 0.0));
+                groups
+            }
+            Model::Gaussian { blocks, .. } if blocks <= 2 => {
+                (0..blocks).map(|block| (1, self.mass(block, block + 1))).collect()
+            }
+            Model::Gaussian { blocks, .. } => {
+                // Edge blocks hold the clamped tails; the interior is split into cells
+                let mut groups = vec![(1, self.mass(0, 1)), (1, self.mass(blocks - 1, blocks))];
+                let interior = blocks - 2;
+                let cells = interior.min(GAUSSIAN_CELLS);
+                for cell in 0..cells {
+                    let a = 1 + cell * interior / cells;
+                    let b = 1 + (cell + 1) * interior / cells;
+                    groups.push((b - a, self.mass(a, b) / (b - a) as f64));
+                }
+                groups
+            }
+        }
+    }
+}
+
+/// Standard normal CDF
+fn normal_cdf(x: f64) -> f64 {
+    0.5 * (1.0 + erf(x / std::f64::consts::SQRT_2))
+}
+
+/// Error function (Abramowitz and Stegun 7.1.26, error below 1.5e-7)
+fn erf(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.3275911 * x.abs());
+    let poly = t * (0.254829592 + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    let y = 1.0 - poly * (-x * x).exp();
+    if x < 0.0 { -y } else { y }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distribution::{Distribution, gaussian::GaussianDistribution, zipf::ZipfDistribution};
+
+    fn sample(dist: &mut dyn Distribution, blocks: u64, ops: usize) -> HeatmapCounts {
+        let mut counts = std::collections::BTreeMap::new();
+        for _ in 0..ops {
+            *counts.entry(dist.next_block(blocks)).or_insert(0u64) += 1;
+        }
+        HeatmapCounts { blocks_per_bucket: 1, entries: counts.into_iter().collect() }
+    }
+
+    #[test]
+    fn test_generated_workloads_match() {
+        let zipf = DistributionType::Zipf { theta: 1.2 };
+        let heatmap = sample(&mut ZipfDistribution::with_seed(1.2, 7), 10_000, 200_000);
+        let report = distribution_report(&zipf, &heatmap, 0, 10_000, 1, 100).unwrap();
+        assert!(report.matches(), "{:?}", report);
+        let top = report.skew[0];
+        assert!((top.achieved - top.expected).abs() < 0.02, "{:?}", top);
+
+        let gaussian = DistributionType::Gaussian { stddev: 0.3, center: 0.1, drift: None };
+        let heatmap = sample(&mut GaussianDistribution::with_seed(0.3, 0.1, 7), 5_000, 200_000);
+        let report = distribution_report(&gaussian, &heatmap, 0, 5_000, 1, 50).unwrap();
+        assert!(report.matches(), "{:?}", report);
+
+        // The Zipf accesses don't fit a uniform workload
+        let heatmap = sample(&mut ZipfDistribution::with_seed(1.2, 7), 10_000, 200_000);
+        let report = distribution_report(&DistributionType::Uniform, &heatmap, 0, 10_000, 1, 100).unwrap();
+        assert!(!report.matches());
+        assert_eq!(report.skew[0].expected, 0.01);
+    }
+
+    #[test]
+    fn test_partitioned_ranges() {
+        // Two workers, each with its hot spot at the start of its half
+        let zipf = DistributionType::Zipf { theta: 1.0 };
+        let mut dist = ZipfDistribution::with_seed(1.0, 3);
+        let mut heatmap = sample(&mut dist, 1_000, 50_000);
+        let second = sample(&mut dist, 1_000, 50_000);
+        heatmap.entries.extend(second.entries.iter().map(|&(block, count)| (block + 1_000, count)));
+        let report = distribution_report(&zipf, &heatmap, 0, 2_000, 2, 20).unwrap();
+        assert!(report.matches(), "{:?}", report);
+        assert!(!distribution_report(&zipf, &heatmap, 0, 2_000, 1, 20).unwrap().matches());
+    }
+}
@@ -14,6 +14,12 @@
 //!
 //! - **stddev**: Standard deviation (spread of distribution)
 //! - **center**: Center point as fraction of range (0.0-1.0)
+//! - **drift** (optional): Movement of the center over time, to model a
+//!   locality window that moves (log-structured writes, a scan working through
+//!   a table). A linear sweep moves the center at a fixed rate and wraps
+//!   around at the end of the range; a random walk moves it by a normal step
+//!   (standard deviation `rate` per second) and bounces off the ends. The
+//!   center is updated every 64 draws, from the time since the first draw.
 //!
 //! # Use Cases
 //!
@@ -38,10 +44,15 @@
 //! ```
 
 use super::Distribution;
+use crate::config::workload::CenterDrift;
 use rand::Rng;
 use rand::SeedableRng;
 use rand_xoshiro::Xoshiro256PlusPlus;
 use std::f64::consts::PI;
+use std::time::Instant;
+
+/// Draws between updates of a drifting center (keeps clock reads off the hot path)
+const DRIFT_UPDATE_DRAWS: u32 = 64;
 
 /// Gaussian distribution for locality of reference
 ///
@@ -59,6 +70,21 @@ pub struct GaussianDistribution {
     
     /// Cached spare value from Box-Muller transform
     spare: Option<f64>,
+    
+    /// Center movement over time (None = fixed center)
+    drift: Option<DriftState>,
+}
+
+/// Progress of a drifting center
+struct DriftState {
+    mode: CenterDrift,
+    /// Configured center, where a linear sweep starts
+    start_center: f64,
+    /// First draw, and the last center update
+    origin: Option<Instant>,
+    last_update: Option<Instant>,
+    /// Draws since the last center update
+    draws: u32,
 }
 
 impl GaussianDistribution {
@@ -84,6 +110,7 @@ impl GaussianDistribution {
             center,
             rng: Xoshiro256PlusPlus::from_entropy(),
             spare: None,
+            drift: None,
         }
     }
     
@@ -99,6 +126,47 @@ impl GaussianDistribution {
             center,
             rng: Xoshiro256PlusPlus::seed_from_u64(seed),
             spare: None,
+            drift: None,
+        }
+    }
+    
+    /// Move the center over time (None keeps it fixed)
+    pub fn with_drift(mut self, drift: Option<CenterDrift>) -> Self {
+        self.drift = drift.map(|mode| DriftState {
+            mode,
+            start_center: self.center,
+            origin: None,
+            last_update: None,
+            draws: 0,
+        });
+        self
+    }
+    
+    /// Current center as a fraction of the range
+    pub fn center(&self) -> f64 {
+        self.center
+    }
+    
+    /// Move a drifting center to where it is at `now`
+    fn update_center(&mut self, now: Instant) {
+        let Some(ref mut drift) = self.drift else {
+            return;
+        };
+        let origin = *drift.origin.get_or_insert(now);
+        let last = drift.last_update.replace(now).unwrap_or(now);
+        let (mode, start_center) = (drift.mode, drift.start_center);
+        match mode {
+            CenterDrift::Linear { rate } => {
+                let elapsed = now.duration_since(origin).as_secs_f64();
+                self.center = (start_center + rate * elapsed).rem_euclid(1.0);
+            }
+            CenterDrift::RandomWalk { rate } => {
+                let dt = now.duration_since(last).as_secs_f64();
+                let step = self.generate_standard_normal() * rate * dt.sqrt();
+                // Reflect off both ends of the range
+                let center = (self.center + step).rem_euclid(2.0);
+                self.center = if center > 1.0 { 2.0 - center } else { center };
+            }
         }
     }
     
@@ -139,6 +207,14 @@ impl Distribution for GaussianDistribution {
             return 0;
         }
         
+        if let Some(ref mut drift) = self.drift {
+            drift.draws += 1;
+            if drift.draws >= DRIFT_UPDATE_DRAWS || drift.origin.is_none() {
+                drift.draws = 0;
+                self.update_center(Instant::now());
+            }
+        }
+        
         // Generate standard normal N(0,1)
         let z = self.generate_standard_normal();
         
@@ -265,4 +341,34 @@ mod tests {
             assert!(block_num < num_blocks);
         }
     }
+    
+    #[test]
+    fn test_gaussian_center_drift() {
+        use std::time::Duration;
+        let start = Instant::now();
+        
+        // 10% of the range per second, wrapping at the end
+        let mut dist = GaussianDistribution::with_seed(0.01, 0.9, 1)
+            .with_drift(Some(CenterDrift::Linear { rate: 0.1 }));
+        dist.update_center(start);
+        dist.update_center(start + Duration::from_millis(500));
+        assert!((dist.center() - 0.95).abs() < 1e-9);
+        dist.update_center(start + Duration::from_secs(2));
+        assert!((dist.center() - 0.1).abs() < 1e-9);
+        
+        // A random walk stays within the range
+        let mut dist = GaussianDistribution::with_seed(0.01, 0.5, 1)
+            .with_drift(Some(CenterDrift::RandomWalk { rate: 2.0 }));
+        dist.update_center(start);
+        for second in 1..100 {
+            dist.update_center(start + Duration::from_secs(second));
+            assert!((0.0..=1.0).contains(&dist.center()));
+        }
+        assert_ne!(dist.center(), 0.5);
+        
+        // Without drift the center stays put
+        let mut dist = GaussianDistribution::with_seed(0.01, 0.5, 1);
+        dist.update_center(start + Duration::from_secs(5));
+        assert_eq!(dist.center(), 0.5);
+    }
 }
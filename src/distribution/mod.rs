@@ -10,6 +10,7 @@
 //! - **Zipf**: Power law distribution (hot/cold data)
 //! - **Pareto**: 80/20 rule (Pareto principle)
 //! - **Gaussian**: Normal distribution (locality of reference)
+//! - **Composite**: Weighted mix of the above over ranges of the target
 //!
 //! # Block-Based Design
 //!
@@ -94,4 +95,8 @@ pub mod uniform;
 pub mod zipf;
 pub mod pareto;
 pub mod gaussian;
+pub mod composite;
 pub mod sequential;
+pub mod fit;
+pub mod remap;
+pub mod reach;
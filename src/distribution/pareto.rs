@@ -37,6 +37,9 @@ use rand::Rng;
 use rand::SeedableRng;
 use rand_xoshiro::Xoshiro256PlusPlus;
 
+/// Most ranks in the CDF; larger files map ranks onto evenly spaced blocks
+pub const MAX_RANKS: u64 = 100_000;
+
 /// Pareto distribution for 80/20 access patterns
 ///
 /// Generates offsets following the Pareto principle where a small percentage
@@ -86,7 +89,7 @@ impl ParetoDistribution {
     /// For h=0.9, we want 80% of ops in first 20% of file.
     fn compute_cdf(&mut self, max: u64) {
         // Use fixed N for Pareto
-        let n = max.min(100_000) as usize;
+        let n = max.min(MAX_RANKS) as usize;
         
         // For Pareto 80/20 with h=0.9, empirical tuning:
         // exponent=0.45 → 41% in top 20%
@@ -0,0 +1,167 @@
+//! Parts of a target a workload can reach
+//!
+//! Preparation fills targets so reads find data instead of unwritten
+//! extents. A workload whose offsets all come from composite distributions
+//! only reaches the ranges of their components, e.g. a hot index in the first
+//! 10% of a file, and only those ranges need filling. Anything else (uniform,
+//! Zipf, Pareto or Gaussian offsets over the whole target, sequential streams,
+//! remapped offsets) may reach every block.
+//!
+//! Ranges are fractions of the span the distribution is drawn over: the
+//! whole target, or a worker's partition of it. Regions are rounded out to
+//! the largest IO size, so rounding in the worker never reaches past them.
+//!
+//! # Example
+//!
+//! ```
+//! use iopulse::distribution::reach::Reach;
+//!
+//! let reach = Reach::new(vec![(0.0, 0.1), (0.5, 0.6)], 4096);
+//! let regions = reach.regions(0, 1 << 30);
+//! assert_eq!(regions.len(), 2);
+//! assert_eq!((regions[0].0, regions[1].0), (0, 1 << 29));
+//! ```
+
+use crate::config::workload::DistributionType;
+use crate::config::WorkloadConfig;
+
+/// Ranges of the target a workload's offsets can fall in
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reach {
+    /// Sorted, disjoint fractions of the span (end exclusive)
+    ranges: Vec<(f64, f64)>,
+    /// Regions are rounded out to this many bytes
+    align: u64,
+}
+
+impl Reach {
+    /// Reach over the given fractions of the span, rounded out to `align` bytes
+    pub fn new(mut ranges: Vec<(f64, f64)>, align: u64) -> Self {
+        ranges.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut merged: Vec<(f64, f64)> = Vec::new();
+        for (start, end) in ranges {
+            match merged.last_mut() {
+                Some(last) if start <= last.1 => last.1 = last.1.max(end),
+                _ => merged.push((start, end)),
+            }
+        }
+        Self { ranges: merged, align: align.max(1) }
+    }
+
+    /// Reach of a workload, or None when it can reach the whole target
+    pub fn of(workload: &WorkloadConfig) -> Option<Self> {
+        if workload.remap_offsets.is_some() {
+            return None;
+        }
+        let patterns: Vec<(bool, &DistributionType)> = if workload.worker_groups.is_empty() {
+            vec![(workload.random, &workload.distribution)]
+        } else {
+            workload.worker_groups.iter().map(|group| (group.random, &group.distribution)).collect()
+        };
+        let mut ranges = Vec::new();
+        for (random, distribution) in patterns {
+            let DistributionType::Composite { components } = distribution else {
+                return None;
+            };
+            if !random {
+                return None;
+            }
+            ranges.extend(components.iter()
+                .filter(|component| component.weight > 0)
+                .map(|component| (component.start, component.end)));
+        }
+        let reach = Self::new(ranges, workload.io_sizes().max().unwrap_or(workload.block_size));
+        let whole = matches!(reach.ranges[..], [(start, end)] if start <= 0.0 && end >= 1.0);
+        (!whole).then_some(reach)
+    }
+
+    /// Byte regions of the span `start..end` the workload can reach
+    ///
+    /// Sorted and disjoint, within the span.
+    pub fn regions(&self, start: u64, end: u64) -> Vec<(u64, u64)> {
+        let span = end.saturating_sub(start);
+        let mut regions: Vec<(u64, u64)> = Vec::new();
+        for &(from, to) in &self.ranges {
+            let first = ((from * span as f64) as u64 / self.align) * self.align;
+            let last = ((to * span as f64).ceil() as u64).div_ceil(self.align) * self.align + self.align;
+            let region = (start + first.min(span), start + last.min(span));
+            match regions.last_mut() {
+                Some(prev) if region.0 <= prev.1 => prev.1 = prev.1.max(region.1),
+                _ if region.0 < region.1 => regions.push(region),
+                _ => {}
+            }
+        }
+        regions
+    }
+
+    /// Bytes of the span `start..end` the workload can reach
+    pub fn reachable_bytes(&self, start: u64, end: u64) -> u64 {
+        self.regions(start, end).iter().map(|(from, to)| to - from).sum()
+    }
+}
+
+/// Regions of target `index` the workers of a run can reach
+///
+/// A partitioned first target is split over `nodes` nodes and their workers
+/// like the node services split it, and each worker's partition is covered
+/// separately. A target region is never filled outside it. None when the
+/// whole target is filled: the workload can reach all of it, `--full-fill`
+/// is set, or its layout isn't known here (e.g. per-worker files).
+pub fn fill_regions(config: &crate::config::Config, index: usize, nodes: usize) -> Option<Vec<(u64, u64)>> {
+    use crate::config::workload::FileDistribution;
+    use crate::distributed::partition::split_range;
+    
+    let target = config.targets.get(index)?;
+    let whole = target.region.map(|region| vec![region]);
+    let Some(reach) = Reach::of(&config.workload).filter(|_| !target.full_fill) else {
+        return whole;
+    };
+    let span = target.region.or(target.file_size.map(|size| (0, size)))?;
+    match target.distribution {
+        FileDistribution::Shared => Some(reach.regions(span.0, span.1)),
+        FileDistribution::Partitioned if index == 0 => {
+            let block_size = config.workload.block_size;
+            Some(split_range(span, nodes, block_size).into_iter()
+                .flat_map(|node| split_range(node, config.workers.threads, block_size))
+                .flat_map(|(start, end)| reach.regions(start, end))
+                .collect())
+        }
+        _ => whole,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::workload::DistributionComponent;
+
+    fn component(weight: u8, start: f64, end: f64, distribution: DistributionType) -> DistributionComponent {
+        DistributionComponent { weight, distribution, start, end }
+    }
+
+    #[test]
+    fn test_reach_of_workload() {
+        let mut workload: WorkloadConfig = toml::from_str(
+            "read_percent = 100\nwrite_percent = 0\ncompletion_mode = \"RunUntilComplete\"",
+        ).unwrap();
+        assert_eq!(Reach::of(&workload), None);
+        workload.random = true;
+        assert_eq!(Reach::of(&workload), None);
+
+        // 80% in the first tenth, 20% in 50-60%, nothing in 20-30%
+        workload.distribution = DistributionType::Composite { components: vec![
+            component(80, 0.0, 0.1, DistributionType::Zipf { theta: 1.2 }),
+            component(20, 0.5, 0.6, DistributionType::Uniform),
+            component(0, 0.2, 0.3, DistributionType::Uniform),
+        ] };
+        let reach = Reach::of(&workload).unwrap();
+        let size = 1000 * 4096;
+        assert_eq!(reach.regions(0, size), [(0, 101 * 4096), (500 * 4096, 601 * 4096)]);
+        // A partition is covered the same way from its start
+        assert_eq!(reach.regions(size, 2 * size)[0], (size, size + 101 * 4096));
+        assert_eq!(reach.reachable_bytes(0, size), 202 * 4096);
+
+        workload.remap_offsets = Some(crate::config::workload::OffsetRemap { seed: 1, chunk: None });
+        assert_eq!(Reach::of(&workload), None);
+    }
+}
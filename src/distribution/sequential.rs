@@ -1,7 +1,8 @@
 //! Sequential block generation
 //!
-//! Generates sequential block numbers starting from 0 and incrementing by 1.
-//! When the end of the file is reached, wraps back to the beginning.
+//! Generates sequential block numbers starting from 0 (or a chosen start
+//! block, see `--offset-start`) and incrementing by 1. When the end of the
+//! file is reached, wraps back to the beginning.
 
 use crate::distribution::Distribution;
 
@@ -13,6 +14,9 @@ use crate::distribution::Distribution;
 pub struct SequentialDistribution {
     /// Current block number
     current_block: u64,
+    
+    /// Block to start at, applied on the first call once the block count is known
+    start_block: Option<u64>,
 }
 
 impl SequentialDistribution {
@@ -20,6 +24,15 @@ impl SequentialDistribution {
     pub fn new() -> Self {
         Self {
             current_block: 0,
+            start_block: None,
+        }
+    }
+    
+    /// Create a sequential distribution starting at `start_block` (wrapping at the end)
+    pub fn starting_at(start_block: u64) -> Self {
+        Self {
+            current_block: 0,
+            start_block: Some(start_block),
         }
     }
 }
@@ -36,6 +49,10 @@ impl Distribution for SequentialDistribution {
             return 0; // Handle empty file case
         }
         
+        if let Some(start) = self.start_block.take() {
+            self.current_block = start % num_blocks;
+        }
+        
         let block = self.current_block;
         
         // Increment for next call
@@ -76,6 +93,17 @@ mod tests {
         assert_eq!(dist.next_block(num_blocks), 1);  // Wrapped
     }
     
+    #[test]
+    fn test_sequential_start_block() {
+        let mut dist = SequentialDistribution::starting_at(7);
+        let num_blocks = 5;
+        
+        assert_eq!(dist.next_block(num_blocks), 2);  // 7 wraps to 2
+        assert_eq!(dist.next_block(num_blocks), 3);
+        assert_eq!(dist.next_block(num_blocks), 4);
+        assert_eq!(dist.next_block(num_blocks), 0);  // Wrapped
+    }
+    
     #[test]
     fn test_sequential_large_range() {
         let mut dist = SequentialDistribution::new();
@@ -36,6 +36,9 @@ use rand::Rng;
 use rand::SeedableRng;
 use rand_xoshiro::Xoshiro256PlusPlus;
 
+/// Most ranks in the CDF; larger files map ranks onto evenly spaced blocks
+pub const MAX_RANKS: u64 = 1_000_000;
+
 /// Zipf distribution for power law access patterns
 ///
 /// Implements the standard Zipf distribution using the PMF:
@@ -91,7 +94,7 @@ impl ZipfDistribution {
     /// to keep initialization time reasonable (<100ms).
     fn compute_cdf(&mut self, max: u64) {
         // Use actual N, capped at 1M for performance
-        let n = max.min(1_000_000) as usize;
+        let n = max.min(MAX_RANKS) as usize;
         
         // Compute H(N,s) = sum of i^(-s) for i=1 to N
         let mut h_n_s = 0.0;
@@ -52,12 +52,13 @@
 //! engine.cleanup().unwrap();
 //! ```
 
-use super::{EngineCapabilities, EngineConfig, IOCompletion, IOEngine, IOOperation, OperationType};
+use super::{EngineCapabilities, EngineConfig, EngineStats, IOCompletion, IOEngine, IOOperation, OperationType};
 use crate::Result;
 use anyhow::Context;
 use io_uring::{opcode, types, IoUring};
 use std::collections::HashMap;
 use std::os::unix::io::RawFd;
+use std::time::{Duration, Instant};
 
 /// Maximum number of files that can be pre-registered with the fixed files feature.
 const MAX_REGISTERED_FILES: u32 = 1024;
@@ -69,6 +70,9 @@ const MAX_REGISTERED_FILES: u32 = 1024;
 /// one extra syscall.
 const SQPOLL_IDLE_MS: u32 = 2000;
 
+/// user_data of the no-ops timed by `nop_round_trips` (no IO is in flight then)
+const NOP_USER_DATA: u64 = u64::MAX;
+
 /// io_uring IO engine
 ///
 /// This engine uses Linux's io_uring interface for high-performance asynchronous IO.
@@ -108,6 +112,9 @@ pub struct IoUringEngine {
     /// Deferred until after `poll_completions()` drains all in-flight ops,
     /// because `register_buffers` requires no ops in-flight in the kernel.
     pending_buf_registrations: Vec<(usize, usize)>,
+
+    /// Syscall, poll and queue-full counters
+    stats: EngineStats,
 }
 
 impl IoUringEngine {
@@ -122,6 +129,7 @@ impl IoUringEngine {
             registered_buffers: HashMap::new(),
             registered_bufs_iovecs: Vec::new(),
             pending_buf_registrations: Vec::new(),
+            stats: EngineStats::default(),
         }
     }
 
@@ -135,16 +143,23 @@ impl IoUringEngine {
             return Ok(());
         }
 
-        // Move pending into the persistent iovec vec, skipping duplicates.
+        // Move pending into the persistent iovec vec, growing already-registered
+        // buffers that were later used for a larger op.
         let pending: Vec<(usize, usize)> = self.pending_buf_registrations.drain(..).collect();
         for (ptr, len) in pending {
-            if !self.registered_buffers.contains_key(&ptr) {
-                let index = self.registered_bufs_iovecs.len() as u16;
-                self.registered_bufs_iovecs.push(libc::iovec {
-                    iov_base: ptr as *mut libc::c_void,
-                    iov_len: len,
-                });
-                self.registered_buffers.insert(ptr, index);
+            match self.registered_buffers.get(&ptr) {
+                Some(&index) => {
+                    let iovec = &mut self.registered_bufs_iovecs[index as usize];
+                    iovec.iov_len = iovec.iov_len.max(len);
+                }
+                None => {
+                    let index = self.registered_bufs_iovecs.len() as u16;
+                    self.registered_bufs_iovecs.push(libc::iovec {
+                        iov_base: ptr as *mut libc::c_void,
+                        iov_len: len,
+                    });
+                    self.registered_buffers.insert(ptr, index);
+                }
             }
         }
 
@@ -258,12 +273,15 @@ impl IOEngine for IoUringEngine {
             match op.op_type {
                 OperationType::Read | OperationType::Write => {
                     let ptr = op.buffer as usize;
-                    if let Some(&idx) = self.registered_buffers.get(&ptr) {
-                        Some(idx)
-                    } else {
-                        // Defer registration; use plain Read/Write this time.
-                        self.pending_buf_registrations.push((ptr, op.length));
-                        None
+                    match self.registered_buffers.get(&ptr) {
+                        // The buffer pool may lend a buffer registered for a
+                        // smaller op; only use the fixed index if it covers this one.
+                        Some(&idx) if op.length <= self.registered_bufs_iovecs[idx as usize].iov_len => Some(idx),
+                        _ => {
+                            // Defer (re-)registration; use plain Read/Write this time.
+                            self.pending_buf_registrations.push((ptr, op.length));
+                            None
+                        }
                     }
                 }
                 _ => None,
@@ -385,13 +403,20 @@ impl IOEngine for IoUringEngine {
             }
         };
 
-        // Push to submission queue.
+        // Push to submission queue. If it is full, submit what is queued and
+        // push again once.
         // SAFETY: The submission queue is managed by io_uring and we're using
         // the safe wrapper which handles the unsafe operations internally.
-        unsafe {
-            ring.submission()
-                .push(&entry)
-                .map_err(|_| anyhow::anyhow!("Submission queue full"))?;
+        if unsafe { ring.submission().push(&entry) }.is_err() {
+            self.stats.sq_full += 1;
+            ring.submit().context("Failed to submit full submission queue")?;
+            self.stats.syscalls += 1;
+            unsafe {
+                ring.submission()
+                    .push(&entry)
+                    .map_err(|_| anyhow::anyhow!("Submission queue full"))?;
+            }
+            self.stats.resubmissions += 1;
         }
 
         Ok(())
@@ -403,9 +428,11 @@ impl IOEngine for IoUringEngine {
         // Submit any queued operations and wait for at least one completion
         // if we have pending operations
         let pending_count = self.pending_ops.len();
+        self.stats.polls += 1;
         if pending_count > 0 {
             ring.submit_and_wait(1)
                 .context("Failed to submit and wait for completions")?;
+            self.stats.syscalls += 1;
         }
         
         let mut completions = Vec::new();
@@ -444,6 +471,7 @@ impl IOEngine for IoUringEngine {
         while !self.pending_ops.is_empty() && completions.len() < pending_count {
             ring.submit_and_wait(1)
                 .context("Failed to wait for remaining completions")?;
+            self.stats.syscalls += 1;
 
             for cqe in ring.completion() {
                 let user_data = cqe.user_data();
@@ -481,9 +509,35 @@ impl IOEngine for IoUringEngine {
             }
         }
 
+        self.stats.completions += completions.len() as u64;
         Ok(completions)
     }
     
+    fn nop_round_trips(&mut self, count: usize) -> Result<Option<Vec<Duration>>> {
+        anyhow::ensure!(self.pending_ops.is_empty(), "NOP round trips need a ring without IO in flight");
+        let ring = self.ring.as_mut().context("Engine not initialized")?;
+        let nop = opcode::Nop::new().build().user_data(NOP_USER_DATA);
+
+        let mut round_trips = Vec::with_capacity(count);
+        for _ in 0..count {
+            let start = Instant::now();
+            // SAFETY: a NOP references no memory
+            unsafe { ring.submission().push(&nop) }
+                .map_err(|_| anyhow::anyhow!("Submission queue full"))?;
+            ring.submit_and_wait(1).context("Failed to submit io_uring NOP")?;
+            let cqe = ring.completion().next().context("io_uring NOP completed without a completion entry")?;
+            round_trips.push(start.elapsed());
+            if cqe.result() == -libc::EINVAL {
+                // Polled rings only take IO to polled files
+                return Ok(None);
+            }
+            if cqe.result() < 0 {
+                return Err(std::io::Error::from_raw_os_error(-cqe.result())).context("io_uring NOP failed");
+            }
+        }
+        Ok(Some(round_trips))
+    }
+    
     fn cleanup(&mut self) -> Result<()> {
         // Submit and wait for any remaining operations
         if let Some(ref mut ring) = self.ring {
@@ -534,6 +588,10 @@ impl IOEngine for IoUringEngine {
             max_queue_depth: config.map(|c| c.queue_depth).unwrap_or(128),
         }
     }
+    
+    fn stats(&self) -> EngineStats {
+        self.stats.clone()
+    }
 }
 
 #[cfg(test)]
@@ -959,6 +1017,21 @@ mod tests {
         // After cleanup, ring should be None
         assert!(engine.ring.is_none());
     }
+
+    #[test]
+    fn test_io_uring_engine_nop_round_trips() {
+        let mut engine = IoUringEngine::new();
+        engine.init(&EngineConfig::default()).unwrap();
+
+        let round_trips = engine.nop_round_trips(100).unwrap().unwrap();
+        assert_eq!(round_trips.len(), 100);
+        assert!(round_trips.iter().all(|rt| *rt > Duration::ZERO));
+
+        // NOPs leave no completions or counters behind
+        assert!(engine.poll_completions().unwrap().is_empty());
+        assert_eq!(engine.stats().completions, 0);
+        engine.cleanup().unwrap();
+    }
     
     #[test]
     fn test_io_uring_engine_error_handling() {
@@ -52,7 +52,7 @@
 //! engine.cleanup().unwrap();
 //! ```
 
-use super::{EngineCapabilities, EngineConfig, IOCompletion, IOEngine, IOOperation, OperationType};
+use super::{EngineCapabilities, EngineConfig, EngineStats, IOCompletion, IOEngine, IOOperation, OperationType};
 use crate::Result;
 use anyhow::Context;
 use std::collections::HashMap;
@@ -153,6 +153,9 @@ pub struct LibaioEngine {
 
     /// Iocb indices queued for batch submission (not yet submitted to kernel)
     submit_queue: Vec<usize>,
+
+    /// Syscall, poll and queue-full counters
+    stats: EngineStats,
 }
 
 impl LibaioEngine {
@@ -167,6 +170,7 @@ impl LibaioEngine {
             events: Vec::new(),
             completions: Vec::new(),
             submit_queue: Vec::new(),
+            stats: EngineStats::default(),
         }
     }
 
@@ -187,9 +191,13 @@ impl LibaioEngine {
         }
 
         let result = unsafe { io_submit(ctx, nr as libc::c_long, iocb_ptrs.as_mut_ptr()) };
+        self.stats.syscalls += 1;
 
         if result < 0 {
             let err = std::io::Error::last_os_error();
+            if err.raw_os_error() == Some(libc::EAGAIN) {
+                self.stats.sq_full += 1;
+            }
             // Roll back: return all iocbs to pool and remove from pending_ops
             for &idx in &self.submit_queue {
                 let user_data = self.iocbs[idx].data;
@@ -204,6 +212,7 @@ impl LibaioEngine {
         if submitted < nr {
             // Partial submission: keep unsubmitted indices in queue for next flush
             self.submit_queue.drain(..submitted);
+            self.stats.resubmissions += (nr - submitted) as u64;
         } else {
             self.submit_queue.clear();
         }
@@ -267,8 +276,10 @@ impl IOEngine for LibaioEngine {
     
     fn submit(&mut self, op: IOOperation) -> Result<()> {
         // Get an available iocb
-        let iocb_idx = self.get_iocb()
-            .ok_or_else(|| anyhow::anyhow!("No available iocbs (queue full)"))?;
+        let Some(iocb_idx) = self.get_iocb() else {
+            self.stats.sq_full += 1;
+            anyhow::bail!("No available iocbs (queue full)");
+        };
         
         // Store the operation type for completion tracking
         self.pending_ops.insert(op.user_data, op.op_type);
@@ -311,6 +322,7 @@ impl IOEngine for LibaioEngine {
 
         // Flush any queued iocbs before waiting for completions
         self.flush_submissions()?;
+        self.stats.polls += 1;
 
         if self.pending_ops.is_empty() {
             return Ok(Vec::new());
@@ -334,12 +346,14 @@ impl IOEngine for LibaioEngine {
             )
         };
         
+        self.stats.syscalls += 1;
         if result < 0 {
             let err = std::io::Error::last_os_error();
             return Err(err).context("io_getevents failed");
         }
         
         let num_events = result as usize;
+        self.stats.completions += num_events as u64;
         
         // Process completions
         for i in 0..num_events {
@@ -420,6 +434,10 @@ impl IOEngine for LibaioEngine {
             max_queue_depth: config.map(|c| c.queue_depth).unwrap_or(128),
         }
     }
+    
+    fn stats(&self) -> EngineStats {
+        self.stats.clone()
+    }
 }
 
 #[cfg(test)]
@@ -10,6 +10,7 @@
 //! - Read operations via memcpy from mapped region
 //! - Write operations via memcpy to mapped region
 //! - madvise hints for access pattern optimization
+//! - Optional MAP_POPULATE, mlock, and non-temporal write copies (see `MmapOptions`)
 //! - msync for write persistence
 //! - Automatic munmap cleanup
 //!
@@ -26,6 +27,12 @@
 //! - Write-heavy workloads (page faults)
 //! - O_DIRECT scenarios (mmap bypasses O_DIRECT)
 //!
+//! Page faults and copy bandwidth both show up in IO latency. By default the
+//! mapping is pre-faulted with MAP_POPULATE when the target is prepared, so
+//! the test measures copies; `populate: false` measures faults instead,
+//! `lock` pins the mapping with mlock, and `nontemporal` uses streaming
+//! stores for writes so they don't evict the cache.
+//!
 //! # Requirements
 //!
 //! - POSIX-compliant system with mmap support
@@ -48,6 +55,7 @@
 //! ```
 
 use super::{EngineCapabilities, EngineConfig, IOCompletion, IOEngine, IOOperation, OperationType};
+use crate::config::workload::MmapOptions;
 use crate::Result;
 use anyhow::Context;
 use std::collections::{HashMap, VecDeque};
@@ -79,7 +87,7 @@ impl Drop for SharedMmapRegion {
         let result = unsafe { libc::munmap(self.addr as *mut libc::c_void, self.size) };
         if result < 0 {
             let err = std::io::Error::last_os_error();
-            eprintln!("Warning: munmap failed during shared region drop: {}", err);
+            tracing::warn!("munmap failed during shared region drop: {}", err);
         }
     }
 }
@@ -103,6 +111,9 @@ pub struct MmapEngine {
     /// Configuration
     config: Option<EngineConfig>,
 
+    /// Mapping and copy options
+    options: MmapOptions,
+
     /// Per-fd mapping to shared regions.
     ///
     /// Holds an Arc to keep the shared region alive for this engine's lifetime.
@@ -120,8 +131,14 @@ pub struct MmapEngine {
 impl MmapEngine {
     /// Create a new mmap engine
     pub fn new() -> Self {
+        Self::with_options(MmapOptions::default())
+    }
+
+    /// Create a new mmap engine with mapping and copy options
+    pub fn with_options(options: MmapOptions) -> Self {
         Self {
             config: None,
+            options,
             mappings: HashMap::new(),
             completed: VecDeque::new(),
         }
@@ -165,10 +182,10 @@ impl MmapEngine {
                 existing
             } else {
                 // Weak reference is stale (no workers hold it); fall through to create.
-                Self::create_new_mapping(fd, inode, file_size, &mut registry)?
+                Self::create_new_mapping(fd, inode, file_size, &self.options, &mut registry)?
             }
         } else {
-            Self::create_new_mapping(fd, inode, file_size, &mut registry)?
+            Self::create_new_mapping(fd, inode, file_size, &self.options, &mut registry)?
         };
 
         let (addr, size) = (region.addr, region.size);
@@ -183,16 +200,27 @@ impl MmapEngine {
         fd: RawFd,
         inode: u64,
         file_size: usize,
+        options: &MmapOptions,
         registry: &mut HashMap<u64, Weak<SharedMmapRegion>>,
     ) -> Result<Arc<SharedMmapRegion>> {
-        // Always use PROT_READ | PROT_WRITE for mixed workloads.
-        let prot = libc::PROT_READ | libc::PROT_WRITE;
+        // PROT_READ | PROT_WRITE for mixed workloads; a shared mapping of a
+        // file opened O_RDONLY can only be readable.
+        let access_mode = unsafe { libc::fcntl(fd, libc::F_GETFL) } & libc::O_ACCMODE;
+        let prot = if access_mode == libc::O_RDONLY {
+            libc::PROT_READ
+        } else {
+            libc::PROT_READ | libc::PROT_WRITE
+        };
 
         // MAP_POPULATE pre-faults all pages at mmap time, eliminating page
         // fault latency spikes on first access. With shared mappings this
         // cost is paid once regardless of worker count, not N times.
         #[cfg(target_os = "linux")]
-        let map_flags = libc::MAP_SHARED | libc::MAP_POPULATE;
+        let map_flags = if options.populate {
+            libc::MAP_SHARED | libc::MAP_POPULATE
+        } else {
+            libc::MAP_SHARED
+        };
         #[cfg(not(target_os = "linux"))]
         let map_flags = libc::MAP_SHARED;
 
@@ -210,6 +238,16 @@ impl MmapEngine {
             size: file_size,
         });
 
+        // Pin after creating the region so a failed mlock still unmaps on drop.
+        // munmap releases the lock, so no explicit munlock is needed.
+        if options.lock && unsafe { libc::mlock(addr, file_size) } < 0 {
+            let err = std::io::Error::last_os_error();
+            return Err(err).context(format!(
+                "mlock failed: fd={}, size={} (check `ulimit -l` / RLIMIT_MEMLOCK)",
+                fd, file_size
+            ));
+        }
+
         registry.insert(inode, Arc::downgrade(&region));
         Ok(region)
     }
@@ -252,7 +290,11 @@ impl MmapEngine {
         let to_write = length.min(available);
 
         unsafe {
-            ptr::copy_nonoverlapping(buffer, addr.add(offset_usize), to_write);
+            if self.options.nontemporal {
+                copy_nontemporal(buffer, addr.add(offset_usize), to_write);
+            } else {
+                ptr::copy_nonoverlapping(buffer, addr.add(offset_usize), to_write);
+            }
         }
 
         Ok(to_write)
@@ -308,6 +350,39 @@ impl MmapEngine {
     }
 }
 
+/// Copy with non-temporal (streaming) stores that bypass the CPU cache
+///
+/// The unaligned head and tail use a normal copy; the 16-byte aligned body
+/// uses SSE2 streaming stores followed by a store fence. Other architectures
+/// fall back to a normal copy.
+///
+/// # Safety
+///
+/// Same requirements as `ptr::copy_nonoverlapping`.
+unsafe fn copy_nontemporal(src: *const u8, dst: *mut u8, len: usize) {
+    #[cfg(target_arch = "x86_64")]
+    {
+        use std::arch::x86_64::{__m128i, _mm_loadu_si128, _mm_sfence, _mm_stream_si128};
+
+        let head = dst.align_offset(16).min(len);
+        ptr::copy_nonoverlapping(src, dst, head);
+
+        let body = (len - head) / 16;
+        for i in 0..body {
+            let offset = head + i * 16;
+            let chunk = _mm_loadu_si128(src.add(offset) as *const __m128i);
+            _mm_stream_si128(dst.add(offset) as *mut __m128i, chunk);
+        }
+
+        let done = head + body * 16;
+        ptr::copy_nonoverlapping(src.add(done), dst.add(done), len - done);
+        _mm_sfence();
+    }
+
+    #[cfg(not(target_arch = "x86_64"))]
+    ptr::copy_nonoverlapping(src, dst, len);
+}
+
 impl Default for MmapEngine {
     fn default() -> Self {
         Self::new()
@@ -353,6 +428,25 @@ impl IOEngine for MmapEngine {
         let completions: Vec<IOCompletion> = self.completed.drain(..).collect();
         Ok(completions)
     }
+
+    fn prepare_target(&mut self, fd: RawFd) -> Result<()> {
+        // Map now so MAP_POPULATE/mlock are paid during setup. Empty files
+        // (e.g., sized later by the first write) stay lazily mapped.
+        let mut stat: libc::stat = unsafe { std::mem::zeroed() };
+        if unsafe { libc::fstat(fd, &mut stat) } == 0 && stat.st_size > 0 {
+            self.get_or_create_mapping(fd, false)?;
+        }
+        Ok(())
+    }
+    
+    fn advise_target(&mut self, fd: RawFd, flags: &crate::config::workload::MadviseFlags) -> Result<()> {
+        self.prepare_target(fd)?;
+        // Empty files are mapped lazily and have nothing to advise yet
+        if self.mappings.contains_key(&fd) {
+            self.apply_madvise(fd, flags)?;
+        }
+        Ok(())
+    }
     
     fn cleanup(&mut self) -> Result<()> {
         // Release per-engine Arc references to shared mapping regions.
@@ -773,4 +867,69 @@ mod tests {
         
         engine.cleanup().unwrap();
     }
+    
+    #[test]
+    fn test_copy_nontemporal_unaligned() {
+        // Odd offsets and lengths exercise the head, body, and tail paths
+        let src: Vec<u8> = (0..200u32).map(|i| (i * 7) as u8).collect();
+        for (offset, len) in [(0, 0), (1, 15), (3, 64), (5, 190), (16, 128)] {
+            let mut dst = vec![0u8; 200];
+            unsafe { copy_nontemporal(src.as_ptr(), dst.as_mut_ptr().add(offset), len) };
+            assert_eq!(&dst[offset..offset + len], &src[..len]);
+            assert!(dst[..offset].iter().all(|&b| b == 0));
+            assert!(dst[offset + len..].iter().all(|&b| b == 0));
+        }
+    }
+    
+    #[test]
+    fn test_mmap_engine_options() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("test_options.dat");
+        std::fs::write(&file_path, vec![0u8; 8192]).unwrap();
+        
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&file_path)
+            .unwrap();
+        let fd = file.as_raw_fd();
+        
+        let mut engine = MmapEngine::with_options(MmapOptions {
+            populate: false,
+            lock: false,
+            nontemporal: true,
+        });
+        engine.init(&EngineConfig::default()).unwrap();
+        
+        // prepare_target maps the file before any IO
+        engine.prepare_target(fd).unwrap();
+        assert_eq!(engine.mappings.len(), 1);
+        
+        let test_data: Vec<u8> = (0..1000u32).map(|i| i as u8).collect();
+        let op = IOOperation {
+            op_type: OperationType::Write,
+            target_fd: fd,
+            offset: 4099,
+            buffer: test_data.as_ptr() as *mut u8,
+            length: test_data.len(),
+            user_data: 1,
+        };
+        engine.submit(op).unwrap();
+        assert!(engine.poll_completions().unwrap()[0].result.is_ok());
+        
+        let mut buffer = vec![0u8; test_data.len()];
+        let op = IOOperation {
+            op_type: OperationType::Read,
+            target_fd: fd,
+            offset: 4099,
+            buffer: buffer.as_mut_ptr(),
+            length: buffer.len(),
+            user_data: 2,
+        };
+        engine.submit(op).unwrap();
+        engine.poll_completions().unwrap();
+        assert_eq!(buffer, test_data);
+        
+        engine.cleanup().unwrap();
+    }
 }
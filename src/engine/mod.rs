@@ -17,6 +17,8 @@
 //! - **io_uring**: Modern Linux async IO interface (Linux 5.1+, highest performance)
 //! - **libaio**: Linux native async IO (widely available, good performance)
 //! - **mmap**: Memory-mapped IO using mmap/memcpy (useful for specific workloads)
+//! - **threadpool**: Sync IO on helper threads, for queue depth where io_uring
+//!   and libaio are unavailable
 //!
 //! # Example
 //!
@@ -41,6 +43,7 @@
 //! ```
 
 use crate::Result;
+use serde::{Deserialize, Serialize};
 use std::os::unix::io::RawFd;
 
 /// IO engine trait for all backends
@@ -172,6 +175,46 @@ pub trait IOEngine: Send {
     /// ```
     fn poll_completions(&mut self) -> Result<Vec<IOCompletion>>;
     
+    /// Prepare an opened target before the test starts
+    ///
+    /// Engines that keep per-file state (e.g., mmap mappings) set it up here so
+    /// the cost is reported as a setup phase instead of landing in the latency
+    /// of the first IO. The default does nothing; state is created lazily.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the per-file state cannot be created.
+    fn prepare_target(&mut self, _fd: RawFd) -> Result<()> {
+        Ok(())
+    }
+    
+    /// Apply madvise hints to the engine's view of a file
+    ///
+    /// Only meaningful for engines that map files (mmap); the default does
+    /// nothing.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be mapped.
+    fn advise_target(&mut self, _fd: RawFd, _flags: &crate::config::workload::MadviseFlags) -> Result<()> {
+        Ok(())
+    }
+    
+    /// Time no-op round trips through the engine's kernel interface
+    ///
+    /// Submits `count` no-ops one at a time, each waited for before the next,
+    /// and returns how long each took. Must be called with no IO in flight.
+    /// Only io_uring has a no-op request; other engines return None (the
+    /// default), as does a ring that rejects no-ops (EINVAL, e.g. one set up
+    /// for IOPOLL).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a no-op cannot be submitted or fails.
+    fn nop_round_trips(&mut self, _count: usize) -> Result<Option<Vec<std::time::Duration>>> {
+        Ok(None)
+    }
+    
     /// Cleanup and release engine resources
     ///
     /// This method is called when the engine is no longer needed. Engines should
@@ -218,6 +261,13 @@ pub trait IOEngine: Send {
     /// }
     /// ```
     fn capabilities(&self) -> EngineCapabilities;
+    
+    /// Counters of how the engine talked to the kernel
+    ///
+    /// Engines that don't track these return all zeros (the default).
+    fn stats(&self) -> EngineStats {
+        EngineStats::default()
+    }
 }
 
 /// Engine configuration
@@ -449,7 +499,53 @@ impl Default for EngineCapabilities {
     }
 }
 
+/// Engine-level counters
+///
+/// Shows how operations reached the kernel: how many syscalls were made, how
+/// many completions each poll returned and how often the submission queue
+/// was full. Used to tune batch sizes and polling mode.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EngineStats {
+    /// Syscalls made to submit or reap operations (io_uring_enter,
+    /// io_submit/io_getevents, or one per op for the sync engine)
+    pub syscalls: u64,
+    /// Calls to `poll_completions`
+    pub polls: u64,
+    /// Completions returned by those polls
+    pub completions: u64,
+    /// Submissions that found the submission queue full
+    pub sq_full: u64,
+    /// Operations submitted again after a full queue or a partial submission
+    pub resubmissions: u64,
+}
+
+impl EngineStats {
+    /// Merge another worker's counters
+    pub fn merge(&mut self, other: &EngineStats) {
+        self.syscalls += other.syscalls;
+        self.polls += other.polls;
+        self.completions += other.completions;
+        self.sq_full += other.sq_full;
+        self.resubmissions += other.resubmissions;
+    }
+    
+    /// Average completions returned per poll
+    pub fn completions_per_poll(&self) -> f64 {
+        if self.polls == 0 {
+            0.0
+        } else {
+            self.completions as f64 / self.polls as f64
+        }
+    }
+    
+    /// Nothing was recorded (engine doesn't track stats)
+    pub fn is_empty(&self) -> bool {
+        self.syscalls == 0 && self.polls == 0
+    }
+}
+
 pub mod sync;
+pub mod threadpool;
 pub mod mock;
 
 #[cfg(feature = "io_uring")]
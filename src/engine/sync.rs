@@ -43,7 +43,7 @@
 //! assert_eq!(completions.len(), 1);
 //! ```
 
-use super::{EngineCapabilities, EngineConfig, IOCompletion, IOEngine, IOOperation, OperationType};
+use super::{EngineCapabilities, EngineConfig, EngineStats, IOCompletion, IOEngine, IOOperation, OperationType};
 use crate::Result;
 use anyhow::Context;
 
@@ -65,6 +65,9 @@ pub struct SyncEngine {
     
     /// Pre-allocated single-element vector (reused to avoid allocations)
     completion_vec: Vec<IOCompletion>,
+    
+    /// Syscall and poll counters
+    stats: EngineStats,
 }
 
 impl SyncEngine {
@@ -74,6 +77,7 @@ impl SyncEngine {
             _config: None,
             pending_completion: None,
             completion_vec: Vec::with_capacity(1),
+            stats: EngineStats::default(),
         }
     }
     
@@ -260,6 +264,7 @@ impl IOEngine for SyncEngine {
     
     fn submit(&mut self, op: IOOperation) -> Result<()> {
         // For synchronous engine, we perform the operation immediately
+        self.stats.syscalls += 1;
         let result = match op.op_type {
             OperationType::Read => {
                 self.do_read(op.target_fd, op.buffer, op.length, op.offset)
@@ -288,8 +293,10 @@ impl IOEngine for SyncEngine {
     fn poll_completions(&mut self) -> Result<Vec<IOCompletion>> {
         // Return the single completion if available (reuse pre-allocated vector)
         self.completion_vec.clear();
+        self.stats.polls += 1;
         if let Some(completion) = self.pending_completion.take() {
             self.completion_vec.push(completion);
+            self.stats.completions += 1;
         }
         Ok(std::mem::take(&mut self.completion_vec))
     }
@@ -311,6 +318,10 @@ impl IOEngine for SyncEngine {
             max_queue_depth: 1,
         }
     }
+    
+    fn stats(&self) -> EngineStats {
+        self.stats.clone()
+    }
 }
 
 #[cfg(test)]
@@ -382,6 +393,12 @@ mod tests {
         
         // Verify data
         assert_eq!(&buffer[..], test_data);
+        
+        // One pread, one poll that returned it
+        let stats = engine.stats();
+        assert_eq!(stats.syscalls, 1);
+        assert_eq!(stats.polls, 1);
+        assert_eq!(stats.completions_per_poll(), 1.0);
     }
     
     #[test]
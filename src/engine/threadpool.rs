@@ -0,0 +1,246 @@
+//! Thread pool IO engine
+//!
+//! This module provides an asynchronous shim over the synchronous engine for
+//! kernels where neither io_uring nor libaio can be used (disabled by sysctl,
+//! blocked by seccomp in a container, or missing). Helper threads, one per
+//! queue slot, take operations from a shared submission queue and run them
+//! with blocking pread/pwrite, so up to `queue_depth` operations are in
+//! flight at once and queue depth experiments remain possible.
+//!
+//! # Features
+//!
+//! - Real queue depth on any kernel, with the sync engine's IO path
+//! - Completions in the order the helpers finish them
+//! - Helpers inherit the worker's CPU affinity
+//!
+//! # Performance
+//!
+//! Every operation costs a handoff to a helper and back, and the helpers
+//! contend for the worker's cores. Latency and IOPS are comparable between
+//! queue depths on this engine, but not with io_uring or libaio numbers.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use iopulse::engine::{IOEngine, EngineConfig};
+//! use iopulse::engine::threadpool::ThreadPoolEngine;
+//!
+//! let mut engine = ThreadPoolEngine::new();
+//! let config = EngineConfig { queue_depth: 8, ..Default::default() };
+//! engine.init(&config).unwrap();
+//! // Submit up to 8 operations, then poll_completions() waits for the first
+//! engine.cleanup().unwrap();
+//! ```
+
+use super::sync::SyncEngine;
+use super::{EngineCapabilities, EngineConfig, EngineStats, IOCompletion, IOEngine, IOOperation};
+use crate::Result;
+use anyhow::Context;
+use std::sync::mpsc::{channel, Receiver, Sender, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+/// Asynchronous engine backed by helper threads running the sync engine
+pub struct ThreadPoolEngine {
+    /// Submission queue shared by the helpers (None until init)
+    submissions: Option<Sender<IOOperation>>,
+
+    /// Completions from all helpers
+    completions: Option<Receiver<IOCompletion>>,
+
+    /// Helper threads
+    helpers: Vec<JoinHandle<()>>,
+
+    /// Operations submitted and not yet returned by poll_completions
+    in_flight: usize,
+
+    /// Configured queue depth (number of helpers)
+    queue_depth: usize,
+
+    /// Syscall and poll counters
+    stats: EngineStats,
+}
+
+impl ThreadPoolEngine {
+    /// Create a new thread pool engine
+    pub fn new() -> Self {
+        Self {
+            submissions: None,
+            completions: None,
+            helpers: Vec::new(),
+            in_flight: 0,
+            queue_depth: 0,
+            stats: EngineStats::default(),
+        }
+    }
+}
+
+impl Default for ThreadPoolEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Run operations from the queue until it closes or the engine goes away
+fn helper_loop(submissions: Arc<Mutex<Receiver<IOOperation>>>, completions: Sender<IOCompletion>) {
+    let mut engine = SyncEngine::new();
+    loop {
+        let op = match submissions.lock() {
+            Ok(queue) => queue.recv(),
+            Err(_) => return,
+        };
+        let Ok(op) = op else { return };
+        // The sync engine stores the result in its completion; submit itself can't fail
+        let _ = engine.submit(op);
+        for completion in engine.poll_completions().unwrap_or_default() {
+            if completions.send(completion).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// Whether an engine failed to set up because the kernel doesn't offer it here
+///
+/// ENOSYS (not built in), EPERM/EACCES (disabled by sysctl or a seccomp
+/// filter) and EOPNOTSUPP; the thread pool engine can stand in.
+pub fn is_unavailable_error(error: &anyhow::Error) -> bool {
+    error.chain()
+        .filter_map(|cause| cause.downcast_ref::<std::io::Error>())
+        .filter_map(std::io::Error::raw_os_error)
+        .any(|errno| matches!(errno, libc::ENOSYS | libc::EPERM | libc::EACCES | libc::EOPNOTSUPP))
+}
+
+impl IOEngine for ThreadPoolEngine {
+    fn init(&mut self, config: &EngineConfig) -> Result<()> {
+        self.cleanup()?;
+        let (submit_tx, submit_rx) = channel();
+        let (complete_tx, complete_rx) = channel();
+        let submit_rx = Arc::new(Mutex::new(submit_rx));
+        for index in 0..config.queue_depth.max(1) {
+            let submissions = submit_rx.clone();
+            let completions = complete_tx.clone();
+            let helper = std::thread::Builder::new()
+                .name(format!("iopulse-io-{}", index))
+                .spawn(move || helper_loop(submissions, completions))
+                .context("Failed to start IO helper thread")?;
+            self.helpers.push(helper);
+        }
+        self.submissions = Some(submit_tx);
+        self.completions = Some(complete_rx);
+        self.queue_depth = config.queue_depth.max(1);
+        Ok(())
+    }
+
+    fn submit(&mut self, op: IOOperation) -> Result<()> {
+        let submissions = self.submissions.as_ref().context("Engine not initialized")?;
+        submissions.send(op)
+            .map_err(|_| anyhow::anyhow!("IO helper threads exited"))?;
+        self.in_flight += 1;
+        // The helper makes one pread/pwrite (or fsync) for it
+        self.stats.syscalls += 1;
+        Ok(())
+    }
+
+    fn poll_completions(&mut self) -> Result<Vec<IOCompletion>> {
+        self.stats.polls += 1;
+        let mut completions = Vec::new();
+        if self.in_flight == 0 {
+            return Ok(completions);
+        }
+        let queue = self.completions.as_ref().context("Engine not initialized")?;
+
+        // Wait for the first completion, then take whatever else is ready
+        completions.push(queue.recv().map_err(|_| anyhow::anyhow!("IO helper threads exited"))?);
+        loop {
+            match queue.try_recv() {
+                Ok(completion) => completions.push(completion),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => anyhow::bail!("IO helper threads exited"),
+            }
+        }
+        self.in_flight -= completions.len();
+        self.stats.completions += completions.len() as u64;
+        Ok(completions)
+    }
+
+    fn cleanup(&mut self) -> Result<()> {
+        // Closing the submission queue stops the helpers once they finish
+        self.submissions = None;
+        for helper in self.helpers.drain(..) {
+            helper.join().map_err(|_| anyhow::anyhow!("IO helper thread panicked"))?;
+        }
+        self.completions = None;
+        self.in_flight = 0;
+        Ok(())
+    }
+
+    fn capabilities(&self) -> EngineCapabilities {
+        EngineCapabilities {
+            async_io: true,
+            batch_submission: false,
+            registered_buffers: false,
+            fixed_files: false,
+            polling_mode: false,
+            max_queue_depth: if self.queue_depth > 0 { self.queue_depth } else { 1024 },
+        }
+    }
+
+    fn stats(&self) -> EngineStats {
+        self.stats.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::OperationType;
+    use std::os::unix::io::AsRawFd;
+
+    #[test]
+    fn test_threadpool_engine_queue_depth() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("test_threadpool.dat");
+        let data: Vec<u8> = (0..8 * 4096).map(|i| (i / 4096) as u8).collect();
+        std::fs::write(&file_path, &data).unwrap();
+        let file = std::fs::File::open(&file_path).unwrap();
+
+        let mut engine = ThreadPoolEngine::new();
+        engine.init(&EngineConfig { queue_depth: 4, ..Default::default() }).unwrap();
+        assert_eq!(engine.capabilities().max_queue_depth, 4);
+        assert!(engine.poll_completions().unwrap().is_empty());
+
+        // Eight reads, at most four in flight
+        let mut buffers = vec![vec![0u8; 4096]; 8];
+        let mut done = Vec::new();
+        for (block, buffer) in buffers.iter_mut().enumerate() {
+            if engine.in_flight == 4 {
+                done.extend(engine.poll_completions().unwrap());
+            }
+            engine.submit(IOOperation {
+                op_type: OperationType::Read,
+                target_fd: file.as_raw_fd(),
+                offset: block as u64 * 4096,
+                buffer: buffer.as_mut_ptr(),
+                length: 4096,
+                user_data: block as u64,
+            }).unwrap();
+        }
+        while engine.in_flight > 0 {
+            done.extend(engine.poll_completions().unwrap());
+        }
+
+        let mut blocks: Vec<u64> = done.iter().map(|c| c.user_data).collect();
+        blocks.sort_unstable();
+        assert_eq!(blocks, (0..8).collect::<Vec<_>>());
+        assert!(done.iter().all(|c| c.result.as_ref().is_ok_and(|&bytes| bytes == 4096)));
+        assert!(buffers.iter().enumerate().all(|(block, buffer)| buffer.iter().all(|&b| b == block as u8)));
+        assert_eq!(engine.stats().completions, 8);
+        engine.cleanup().unwrap();
+
+        let error = anyhow::Error::new(std::io::Error::from_raw_os_error(libc::EPERM)).context("Failed to create io_uring instance");
+        assert!(is_unavailable_error(&error));
+        let error = anyhow::Error::new(std::io::Error::from_raw_os_error(libc::ENOMEM)).context("Failed to create io_uring instance");
+        assert!(!is_unavailable_error(&error));
+    }
+}
@@ -28,5 +28,8 @@ pub use config::Config;
 pub use engine::IOEngine;
 // pub use worker::Worker; // TODO: Uncomment when Worker is implemented
 
+/// Git commit the binary was built from (None outside a git checkout)
+pub const GIT_SHA: Option<&str> = option_env!("IOPULSE_GIT_SHA");
+
 /// Result type used throughout IOPulse
 pub type Result<T> = anyhow::Result<T>;
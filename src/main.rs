@@ -1,31 +1,60 @@
 //! IOPulse CLI entry point
 
 use anyhow::{Context, Result};
-use iopulse::config::{cli::Cli, cli_convert, Config, WorkloadConfig, TargetConfig, TargetType, WorkerConfig, OutputConfig, RuntimeConfig, LayoutConfig, NamingPattern};
+use iopulse::config::{cli::Cli, cli_convert, Config, WorkloadConfig, TargetConfig, TargetType, WorkerConfig, OutputConfig, AlertConfig, RuntimeConfig, LayoutConfig, NamingPattern};
 use iopulse::config::workload::*;
+use iopulse::distributed::roles::{load_fragments, parse_node_entry, NodeEntry};
 // Note: LocalCoordinator removed - all modes use distributed architecture
-use iopulse::stats::WorkerStats;
+use iopulse::util::lock_file::LockFile;
 use std::sync::Arc;
 
 fn main() -> Result<()> {
     use std::time::Instant;
     
     let main_start = Instant::now();
-    
-    println!("IOPulse v{}", env!("CARGO_PKG_VERSION"));
-    println!("High-performance IO profiling tool");
-    println!();
+    iopulse::util::logging::init();
     
     // Parse CLI arguments
     let parse_start = Instant::now();
     let cli = Cli::parse_args();
     cli.validate()?;
     let parse_elapsed = parse_start.elapsed();
+    
+    // Quiet mode keeps stdout to the one-line summary; under mpirun only
+    // rank 0 reports
+    let secondary_rank = cli.mode == iopulse::config::cli::ExecutionMode::Mpi
+        && iopulse::distributed::mpi::RankInfo::from_env().is_ok_and(|rank| rank.rank > 0);
+    if !cli.quiet && cli.summary_format == iopulse::config::cli::SummaryFormat::Text && !secondary_rank {
+        match iopulse::GIT_SHA {
+            Some(sha) => println!("IOPulse v{} ({})", env!("CARGO_PKG_VERSION"), sha),
+            None => println!("IOPulse v{}", env!("CARGO_PKG_VERSION")),
+        }
+        println!("High-performance IO profiling tool");
+        println!();
+    }
+    
     if cli.debug {
         eprintln!("DEBUG TIMING: CLI parse: {:.3}s", parse_elapsed.as_secs_f64());
     }
     
-    // Handle different execution modes
+    // Utility subcommands run instead of a test
+    match cli.command {
+        Some(iopulse::config::cli::Command::Ping { ref nodes, timeout }) => {
+            return run_ping(&cli, nodes, timeout);
+        }
+        Some(iopulse::config::cli::Command::Merge { ref inputs, ref output, force }) => {
+            return run_merge(inputs, output, force);
+        }
+        Some(iopulse::config::cli::Command::VerifyJournal { ref target, ref journals }) => {
+            return run_verify_journal(target, journals);
+        }
+        Some(iopulse::config::cli::Command::VerifyOrder { ref target }) => {
+            return run_verify_order(target);
+        }
+        None => {}
+    }
+    
+    // Handle different execution modes (common setup failures get advice)
     match cli.mode {
         iopulse::config::cli::ExecutionMode::Standalone => {
             run_standalone(cli, main_start)
@@ -36,17 +65,30 @@ fn main() -> Result<()> {
         iopulse::config::cli::ExecutionMode::Coordinator => {
             run_coordinator(cli)
         }
-    }
+        iopulse::config::cli::ExecutionMode::Mpi => {
+            run_mpi(cli)
+        }
+    }.map_err(iopulse::util::preflight::explain)
 }
 
 /// Run in standalone mode (single machine)
 fn run_standalone(cli: Cli, _main_start: std::time::Instant) -> Result<()> {
     use std::time::Instant;
     
+    if let Some(ref path) = cli.config {
+        return run_job_file(&cli, path);
+    }
+    
     // Build configuration from CLI
     let config_start = Instant::now();
-    let config = build_config_from_cli(&cli)?;
+    let mut config = build_config_from_cli(&cli)?;
+    let job_id = cli.job_id.clone().unwrap_or_else(iopulse::distributed::generate_job_id);
+    config.expand_run_id(&job_id);
+    config.resolve_offset_start();
+    config.resolve_device_rating()?;
     let config_elapsed = config_start.elapsed();
+    // Worker log files are written by the node service, not here
+    iopulse::util::logging::configure(config.output.verbosity, config.runtime.debug, None)?;
     if cli.debug {
         eprintln!("DEBUG TIMING: Config build: {:.3}s", config_elapsed.as_secs_f64());
     }
@@ -57,7 +99,9 @@ fn run_standalone(cli: Cli, _main_start: std::time::Instant) -> Result<()> {
     
     // Display configuration
     let print_start = Instant::now();
-    print_configuration(&config);
+    if !config.output.is_quiet() {
+        print_configuration(&config);
+    }
     let print_elapsed = print_start.elapsed();
     if cli.debug {
         eprintln!("DEBUG TIMING: Print config: {:.3}s", print_elapsed.as_secs_f64());
@@ -65,27 +109,58 @@ fn run_standalone(cli: Cli, _main_start: std::time::Instant) -> Result<()> {
     
     if cli.dry_run {
         println!();
+        let (estimate, rates) = estimate_resources(&config)?;
+        iopulse::output::text::print_resource_estimate("Resource Estimate", &estimate, rates);
         println!("Dry run mode - configuration validated successfully");
         return Ok(());
     }
 
-    println!();
-    println!("Starting test...");
-    println!();
+    if !config.output.is_quiet() {
+        println!();
+        println!("Starting test...");
+        println!();
+    }
     
+    run_local(&cli, config, job_id)
+}
+
+/// Estimate the resources of a run for --dry-run
+///
+/// When the run prepares files, a short write and create probe in the
+/// target's directory times the preparation.
+fn estimate_resources(config: &Config) -> Result<(iopulse::util::estimate::ResourceEstimate, Option<iopulse::util::estimate::ProbeRates>)> {
+    use iopulse::util::estimate::{self, ResourceEstimate};
+
+    let mut estimate = ResourceEstimate::for_config(config)?;
+    let mut rates = None;
+    if estimate.has_preparation() {
+        match ResourceEstimate::probe_dir(config).map(|dir| estimate::probe(&dir)) {
+            Some(Ok(probed)) => {
+                estimate.apply_probe(probed);
+                rates = Some(probed);
+            }
+            Some(Err(e)) => eprintln!("Warning: Preparation probe failed: {:#}", e),
+            None => {}
+        }
+    }
+    Ok((estimate, rates))
+}
+
+/// Run a test on a node service launched on this host
+fn run_local(cli: &Cli, config: Config, job_id: String) -> Result<()> {
     // Use distributed architecture with localhost service (unified path for all modes)
     if cli.debug {
         eprintln!("DEBUG: Using unified architecture (localhost service)");
     }
     
-    // Find available port
-    let service_port = find_available_port(cli.debug)?;
+    // Find available port (reserved until the run ends)
+    let (service_port, _port_lock) = find_available_port(9999, cli.debug)?;
     if cli.debug {
         eprintln!("DEBUG: Found available port: {}", service_port);
     }
     
     // Auto-launch service on localhost
-    let service_handle = launch_localhost_service(service_port, &cli)?;
+    let service_handle = launch_localhost_service(service_port, cli)?;
     if cli.debug {
         eprintln!("DEBUG: Service launched (PID: {})", service_handle.id());
     }
@@ -96,14 +171,15 @@ fn run_standalone(cli: Cli, _main_start: std::time::Instant) -> Result<()> {
     // Use DistributedCoordinator with localhost
     let node_addresses = vec![format!("localhost:{}", service_port)];
     
-    let runtime = tokio::runtime::Runtime::new()
-        .context("Failed to create tokio runtime")?;
+    let runtime = new_runtime(cli)?;
     
     let result = runtime.block_on(async {
-        let coordinator = iopulse::distributed::DistributedCoordinator::new(
+        let mut coordinator = iopulse::distributed::DistributedCoordinator::new(
             Arc::new(config),
             node_addresses,
         ).context("Failed to create coordinator")?;
+        coordinator.set_job_id(job_id);
+        let _control = bind_control_socket(cli, &mut coordinator)?;
         
         coordinator.run().await
     });
@@ -116,29 +192,202 @@ fn run_standalone(cli: Cli, _main_start: std::time::Instant) -> Result<()> {
     result
 }
 
+/// Run the jobs of a configuration file (--config)
+///
+/// A single job runs like a command-line test. Several jobs run concurrently,
+/// each on its own localhost node service, start together and are reported
+/// together once all have finished: each job's results, then a per-job table
+/// with the combined total. Background jobs stop with the last foreground job
+/// and are reported after it, outside the total.
+fn run_job_file(cli: &Cli, path: &std::path::Path) -> Result<()> {
+    use iopulse::distributed::jobs::{self, JobSetMember};
+    use iopulse::stats::WorkerStats;
+    
+    let mut jobs = jobs::parse_job_file(path)?;
+    let set_id = cli.job_id.clone().unwrap_or_else(iopulse::distributed::generate_job_id);
+    let job_ids = jobs::assign_job_ids(&mut jobs, &set_id)?;
+    for job in &mut jobs {
+        job.config.runtime.debug |= cli.debug;
+        job.config.resolve_offset_start();
+        job.config.resolve_device_rating()?;
+    }
+    let quiet = jobs.iter().all(|job| job.config.output.is_quiet());
+    let verbosity = jobs.iter().map(|job| job.config.output.verbosity).max().unwrap_or(0);
+    iopulse::util::logging::configure(verbosity, cli.debug, None)?;
+    
+    for job in &jobs {
+        iopulse::config::validator::validate_config(&job.config)
+            .with_context(|| format!("Configuration validation failed for job '{}'", job.name))?;
+        if !job.config.output.is_quiet() {
+            if jobs.len() > 1 {
+                println!("Job: {}", job.name);
+            }
+            print_configuration(&job.config);
+            println!();
+        }
+    }
+    
+    if cli.dry_run {
+        let mut total = iopulse::util::estimate::ResourceEstimate::default();
+        for job in &jobs {
+            let (estimate, rates) = estimate_resources(&job.config)?;
+            let title = if jobs.len() > 1 {
+                format!("Resource Estimate (job {})", job.name)
+            } else {
+                "Resource Estimate".to_string()
+            };
+            iopulse::output::text::print_resource_estimate(&title, &estimate, rates);
+            total.add(&estimate);
+        }
+        if jobs.len() > 1 {
+            iopulse::output::text::print_resource_estimate("Resource Estimate (all jobs)", &total, None);
+        }
+        println!("Dry run mode - configuration validated successfully");
+        return Ok(());
+    }
+    
+    if jobs.len() == 1 {
+        let job = jobs.pop().expect("one job");
+        if !quiet {
+            println!("Starting test...");
+            println!();
+        }
+        return run_local(cli, job.config, set_id);
+    }
+    if cli.control_socket.is_some() {
+        anyhow::bail!("--control-socket is only supported for a single job");
+    }
+    
+    // One node service per job (ports stay reserved until the run ends)
+    let mut services = Vec::new();
+    for _ in &jobs {
+        let (port, lock) = find_available_port(9999, cli.debug)?;
+        let child = launch_localhost_service(port, cli)?;
+        services.push((port, lock, child));
+    }
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    
+    if !quiet {
+        let names: Vec<String> = jobs.iter()
+            .map(|job| if job.background { format!("{} (background)", job.name) } else { job.name.clone() })
+            .collect();
+        println!("Starting {} jobs: {} (job set {})", jobs.len(), names.join(", "), set_id);
+        println!();
+    }
+    
+    // Jobs run quietly; their results are printed together at the end
+    let (members, reports) = JobSetMember::for_jobs(&jobs);
+    let outcomes: Vec<Result<()>> = std::thread::scope(|scope| {
+        let handles: Vec<_> = jobs.iter().zip(&job_ids).zip(&services).zip(members)
+            .map(|(((job, job_id), (port, _, _)), member)| {
+                let mut config = job.config.clone();
+                config.output.quiet = true;
+                config.output.no_live = true;
+                let node_addresses = vec![format!("localhost:{}", port)];
+                scope.spawn(move || -> Result<()> {
+                    let runtime = new_runtime(cli)?;
+                    runtime.block_on(async {
+                        let mut coordinator = iopulse::distributed::DistributedCoordinator::new(
+                            Arc::new(config),
+                            node_addresses,
+                        ).context("Failed to create coordinator")?;
+                        coordinator.set_job_id(job_id.clone());
+                        coordinator.set_job_set(member);
+                        coordinator.run().await
+                    })
+                })
+            })
+            .collect();
+        handles.into_iter()
+            .map(|handle| handle.join().unwrap_or_else(|_| Err(anyhow::anyhow!("Job thread panicked"))))
+            .collect()
+    });
+    
+    for (_, _lock, child) in services {
+        if let Err(e) = cleanup_service(child, cli.debug) {
+            eprintln!("Warning: Failed to cleanup service: {}", e);
+        }
+    }
+    
+    // Each job's results (foreground first), then the per-job table with the
+    // combined total of the foreground jobs
+    let mut finished: Vec<(String, WorkerStats, std::time::Duration, bool)> = Vec::new();
+    let order = jobs.iter().zip(&job_ids).zip(&reports)
+        .filter(|((job, _), _)| !job.background)
+        .chain(jobs.iter().zip(&job_ids).zip(&reports).filter(|((job, _), _)| job.background));
+    for ((job, job_id), report) in order {
+        let Some((stats, duration)) = report.lock().unwrap().take() else { continue };
+        let kind = if job.background { "Background job" } else { "Job" };
+        if !job.config.output.is_quiet() {
+            println!("═══ {}: {} ({}) ═══", kind, job.name, job_id);
+        } else if job.config.output.summary_format == iopulse::config::SummaryFormat::Text {
+            print!("{}{}: ", job.name, if job.background { " (background)" } else { "" });
+        }
+        iopulse::output::print_summary(&stats, duration, &job.config, 1);
+        finished.push((job.name.clone(), stats, duration, job.background));
+    }
+    if !quiet && !finished.is_empty() {
+        let mut total = WorkerStats::new();
+        for (name, stats, _, _) in finished.iter().filter(|(_, _, _, background)| !background) {
+            total.merge(stats).with_context(|| format!("Failed to merge results of job '{}'", name))?;
+        }
+        let rows = |background: bool| -> Vec<(String, &WorkerStats, std::time::Duration)> {
+            finished.iter()
+                .filter(|job| job.3 == background)
+                .map(|(name, stats, duration, _)| (name.clone(), stats, *duration))
+                .collect()
+        };
+        iopulse::output::text::print_job_table(&rows(false), &total, &rows(true));
+    }
+    
+    let failures: Vec<String> = jobs.iter().zip(outcomes)
+        .filter_map(|(job, outcome)| outcome.err().map(|e| format!("job '{}': {:#}", job.name, e)))
+        .collect();
+    if !failures.is_empty() {
+        anyhow::bail!("{} of {} jobs failed:\n  {}", failures.len(), jobs.len(), failures.join("\n  "));
+    }
+    Ok(())
+}
+
 /// Build configuration from CLI arguments
 fn build_config_from_cli(cli: &Cli) -> Result<Config> {
     // Parse block size (for future use with IO patterns)
     let block_size = cli_convert::parse_size(&cli.block_size)
         .context("Invalid block size")?;
     
+    // Block size mixes (--bs-split, or per operation type)
+    let bs_split = |split: Option<&String>, option: &str| -> Result<Vec<IOPattern>> {
+        split.map(|s| cli_convert::parse_bs_split(s, cli.random).with_context(|| format!("Invalid {}", option)))
+            .transpose()
+            .map(Option::unwrap_or_default)
+    };
+    let read_distribution = match cli.read_bs_split.as_ref() {
+        Some(split) => bs_split(Some(split), "--read-bs-split")?,
+        None => bs_split(cli.bs_split.as_ref(), "--bs-split")?,
+    };
+    let write_distribution = match cli.write_bs_split.as_ref() {
+        Some(split) => bs_split(Some(split), "--write-bs-split")?,
+        None => bs_split(cli.bs_split.as_ref(), "--bs-split")?,
+    };
+    
     // Determine read/write percentages
     let (read_percent, write_percent) = match (cli.read_percent, cli.write_percent) {
         (Some(r), Some(w)) => (r, w),
         (Some(r), None) => (r, 100 - r),
         (None, Some(w)) => (100 - w, w),
+        (None, None) if cli.grow_to.is_some() => (0, 100), // Append-grow only writes
         (None, None) => (100, 0), // Default to 100% read
     };
     
     // Parse completion mode
     let completion_mode = if let Some(ref duration_str) = cli.duration {
-        let seconds = cli_convert::parse_duration(duration_str)
+        let millis = cli_convert::parse_duration_ms(duration_str)
             .context("Invalid duration")?;
-        if seconds == 0 {
+        if millis == 0 {
             // Duration 0 means "run until file is complete"
             CompletionMode::RunUntilComplete
         } else {
-            CompletionMode::Duration { seconds }
+            CompletionMode::duration(std::time::Duration::from_millis(millis))
         }
     } else if let Some(ref bytes_str) = cli.total_bytes {
         let bytes = cli_convert::parse_size(bytes_str)
@@ -147,7 +396,7 @@ fn build_config_from_cli(cli: &Cli) -> Result<Config> {
     } else if cli.run_until_complete {
         CompletionMode::RunUntilComplete
     } else {
-        CompletionMode::Duration { seconds: 10 } // Default
+        CompletionMode::Duration { seconds: 10, millis: 0 } // Default
     };
     
     // Convert distribution
@@ -157,6 +406,7 @@ fn build_config_from_cli(cli: &Cli) -> Result<Config> {
         cli.pareto_h,
         cli.gaussian_stddev,
         cli.gaussian_center,
+        cli.gaussian_drift.as_deref(),
     )?;
     
     // Parse think time if specified
@@ -168,6 +418,7 @@ fn build_config_from_cli(cli: &Cli) -> Result<Config> {
             mode: cli_convert::convert_think_mode(cli.think_mode),
             apply_every_n_blocks: cli.think_every,
             adaptive_percent: cli.think_adaptive_percent,
+            smooth: cli.think_smooth,
         })
     } else if cli.think_adaptive_percent.is_some() {
         // Adaptive-only mode (no base duration, purely adaptive)
@@ -176,17 +427,32 @@ fn build_config_from_cli(cli: &Cli) -> Result<Config> {
             mode: cli_convert::convert_think_mode(cli.think_mode),
             apply_every_n_blocks: cli.think_every,
             adaptive_percent: cli.think_adaptive_percent,
+            smooth: cli.think_smooth,
         })
     } else {
         None
     };
     
+    // Build queue depth ramp configuration if enabled
+    let qd_ramp = cli_convert::convert_qd_ramp(
+        cli.qd_ramp,
+        &cli.qd_ramp_step,
+        cli.qd_ramp_max_latency.as_deref(),
+        cli.qd_ramp_plateau,
+    )?;
+    
+    // Parse append-grow size target if specified
+    let grow_to = match cli.grow_to {
+        Some(ref size_str) => Some(cli_convert::parse_size(size_str).context("Invalid --grow-to size")?),
+        None => None,
+    };
+    
     // Build workload configuration
     let workload = WorkloadConfig {
         read_percent,
         write_percent,
-        read_distribution: vec![],
-        write_distribution: vec![],
+        read_distribution,
+        write_distribution,
         block_size,  // Pass parsed block size
         queue_depth: cli.queue_depth,
         completion_mode,
@@ -199,6 +465,29 @@ fn build_config_from_cli(cli: &Cli) -> Result<Config> {
         heatmap: cli.heatmap,
         heatmap_buckets: cli.heatmap_buckets,
         write_pattern: cli_convert::convert_verify_pattern(cli.write_pattern),
+        qd_ramp,
+        mmap: MmapOptions {
+            populate: !cli.no_mmap_populate,
+            lock: cli.mmap_lock,
+            nontemporal: cli.mmap_nontemporal,
+        },
+        buffer_hugepages: cli_convert::convert_hugepages(cli.buffer_hugepages),
+        grow_to,
+        open_flags: cli.open_flags.as_deref()
+            .map(cli_convert::parse_open_flags)
+            .transpose()
+            .context("Invalid --open-flags")?
+            .unwrap_or_default(),
+        custom_pattern: cli_convert::custom_pattern(cli.pattern_file.as_deref(), cli.pattern_runs.as_deref())?,
+        rw_split_by: cli_convert::convert_rw_split_by(cli.rw_split_by),
+        offset_start: cli.offset_start.as_deref()
+            .map(cli_convert::parse_offset_start)
+            .transpose()
+            .context("Invalid --offset-start")?,
+        worker_groups: Vec::new(),
+        buffered_percent: cli.buffered_percent.unwrap_or(0),
+        remap_offsets: cli_convert::parse_offset_remap(cli.remap_offsets, cli.remap_chunk.as_deref())?,
+        no_engine_fallback: cli.no_engine_fallback,
     };
     
     // Parse file size if specified
@@ -219,9 +508,15 @@ fn build_config_from_cli(cli: &Cli) -> Result<Config> {
     let target_path = cli.target.clone()
         .ok_or_else(|| anyhow::anyhow!("Target path required in standalone mode"))?;
     
+    let target_type = match std::fs::metadata(&target_path) {
+        Ok(metadata) if std::os::unix::fs::FileTypeExt::is_block_device(&metadata.file_type()) => TargetType::BlockDevice,
+        _ => TargetType::File,
+    };
+    let region = cli_convert::parse_target_region(cli, &target_path)?;
+    
     let mut target = TargetConfig {
         path: target_path,
-        target_type: TargetType::File, // TODO: Detect block devices
+        target_type,
         file_size,
         num_files: cli.num_files,
         num_dirs: cli.num_dirs,
@@ -230,13 +525,20 @@ fn build_config_from_cli(cli: &Cli) -> Result<Config> {
         export_layout_manifest: cli.export_layout_manifest.clone(),
         distribution: cli_convert::convert_file_distribution(cli.file_distribution),
         fadvise_flags,
-        madvise_flags: MadviseFlags::default(),
+        madvise_flags: cli.madvise.as_deref()
+            .map(cli_convert::parse_madvise_flags)
+            .transpose()?
+            .unwrap_or_default(),
         lock_mode: cli_convert::convert_lock_mode(cli.lock_mode),
-        preallocate: cli.preallocate,  // Default: false
+        busy_file: cli_convert::convert_busy_file(cli.busy_file),
+        preallocate: cli.preallocate || cli.prealloc_mode.is_some(),  // Default: false
+        prealloc_mode: cli.prealloc_mode.map(cli_convert::convert_prealloc_mode).unwrap_or_default(),
         truncate_to_size: cli.truncate_to_size,
         refill: cli.refill,
         refill_pattern: cli_convert::convert_verify_pattern(cli.refill_pattern),
         no_refill: cli.no_refill,
+        region,
+        full_fill: cli.full_fill,
     };
     
     // Build layout_config if layout parameters are provided
@@ -331,18 +633,31 @@ fn build_config_from_cli(cli: &Cli) -> Result<Config> {
     }
     
     // Build worker configuration
+    let (threads, thread_scaling) = cli_convert::convert_threads(
+        cli.threads,
+        &cli.threads_auto_step,
+        cli.threads_auto_plateau,
+        cli.threads_auto_max,
+    )?;
     let workers = WorkerConfig {
-        threads: cli.threads,
+        threads,
         cpu_cores: cli.cpu_cores.clone(),
         numa_zones: cli.numa_zones.clone(),
         rate_limit_iops: None,
         rate_limit_throughput: None,
         offset_range: None,  // Set by coordinator for partitioned distribution
+        unit_id: None,  // Set by the node service
+        core: None,  // Set by the node service with --thread-per-core
+        stonewall: cli.stonewall,
+        thread_per_core: cli.thread_per_core,
+        thread_scaling,
+        latency_target: cli_convert::convert_latency_target(cli.target_p99.as_deref(), &cli.target_p99_step)?,
+        visibility: cli_convert::convert_visibility(cli)?,
     };
     
     // Parse live interval if specified
-    let live_interval = if let Some(ref interval_str) = cli.live_interval {
-        Some(cli_convert::parse_duration(interval_str).context("Invalid live interval")?)
+    let live_interval_ms = if let Some(ref interval_str) = cli.live_interval {
+        Some(cli_convert::parse_duration_ms(interval_str).context("Invalid live interval")?)
     } else {
         None
     };
@@ -352,6 +667,23 @@ fn build_config_from_cli(cli: &Cli) -> Result<Config> {
         s.and_then(|interval_str| cli_convert::parse_duration(interval_str).ok())
     };
     
+    // Alert thresholds and hooks (validated together with the output config)
+    let alert = if cli.alert_p99.is_some() || cli.alert_error_rate.is_some() {
+        Some(AlertConfig {
+            p99_us: cli.alert_p99.as_deref()
+                .map(cli_convert::parse_time_us)
+                .transpose()
+                .context("Invalid --alert-p99")?,
+            error_rate_percent: cli.alert_error_rate,
+            exec: cli.alert_exec.clone(),
+            webhook: cli.alert_webhook.clone(),
+            cooldown_secs: cli_convert::parse_duration(&cli.alert_cooldown)
+                .context("Invalid --alert-cooldown")?,
+        })
+    } else {
+        None
+    };
+    
     // Build output configuration
     let output = OutputConfig {
         json_output: cli.json_output.clone(),
@@ -366,21 +698,98 @@ fn build_config_from_cli(cli: &Cli) -> Result<Config> {
         show_latency: cli.show_latency,
         show_histogram: cli.show_histogram,
         show_percentiles: cli.show_percentiles,
-        live_interval,
+        live_interval: None,
         no_live: cli.no_live,
-        verbosity: 0,
+        verbosity: cli.verbose,
+        quiet: cli.quiet,
+        fragmentation: cli.fragmentation,
+        space_usage: cli.space_usage,
+        percentiles: cli.percentiles.as_deref()
+            .map(cli_convert::parse_percentiles)
+            .transpose()
+            .context("Invalid --percentiles")?,
+        labels: cli_convert::parse_labels(&cli.labels)
+            .context("Invalid --label")?,
+        run_uuid: cli.run_uuid.as_deref()
+            .map(iopulse::util::trace_id::parse_run_uuid)
+            .transpose()?,
+        rotate_interval: cli.rotate_interval.as_deref()
+            .map(cli_convert::parse_duration)
+            .transpose()
+            .context("Invalid --rotate-interval")?,
+        rotate_size: cli.rotate_size.as_deref()
+            .map(cli_convert::parse_size)
+            .transpose()
+            .context("Invalid --rotate-size")?,
+        rotate_compress: cli.rotate_compress,
+        summary_interval: cli.summary_interval.as_deref()
+            .map(cli_convert::parse_duration)
+            .transpose()
+            .context("Invalid --summary-interval")?,
+        alert,
+        summary_format: cli_convert::convert_summary_format(cli.summary_format),
+        live_overhead_percent: cli.live_overhead.unwrap_or(iopulse::config::DEFAULT_LIVE_OVERHEAD_PERCENT),
+        live_interval_ms,
+        heatmap_output: cli.heatmap_output.clone(),
+        heatmap_series: cli.heatmap_series,
+        distribution_report: cli.distribution_report,
+        rated: cli_convert::parse_device_rating(cli.rated_iops.as_deref(), cli.rated_throughput.as_deref())?,
+        interval_latency: cli_convert::convert_interval_latency(cli.interval_latency),
+        plot: cli.plot.clone(),
+        live_metadata: cli.live_metadata,
     };
     
     // Build runtime configuration
     let runtime = RuntimeConfig {
         continue_on_error: cli.continue_on_error,
         max_errors: cli.max_errors,
-        continue_on_worker_failure: false,
+        continue_on_worker_failure: cli.continue_on_worker_failure,
         verify: cli.verify,
         verify_pattern: cli.verify_pattern.map(cli_convert::convert_verify_pattern),
         dry_run: cli.dry_run,
         debug: cli.debug,
         allow_write_conflicts: cli.allow_write_conflicts,
+        log_dir: cli.log_dir.clone(),
+        seed: cli.seed,
+        stop_at_op: cli.stop_at_op,
+        offset_dump: cli.dump_offsets.clone(),
+        verify_groups: match cli.verify_writers {
+            Some(ref writers) => Some(iopulse::config::VerifyGroups {
+                writers: cli_convert::parse_node_indices(writers).context("Invalid --verify-writers")?,
+                readers: cli.verify_readers.as_deref()
+                    .map(cli_convert::parse_node_indices)
+                    .transpose()
+                    .context("Invalid --verify-readers")?
+                    .unwrap_or_default(),
+            }),
+            None => None,
+        },
+        verify_seed: None,
+        stats_mem_limit: cli.stats_mem_limit.as_deref()
+            .map(cli_convert::parse_size)
+            .transpose()
+            .context("Invalid --stats-mem-limit")?,
+        latency_sample: cli.latency_sample,
+        file_latency_top: cli.file_latency_top,
+        file_latency_by_dir: cli.file_latency_by_dir,
+        ost_stats: cli.ost_stats,
+        cpu_accounting: cli.cpu_accounting,
+        timer: cli_convert::convert_timer_source(cli.timer),
+        keepalive_timeout: cli_convert::parse_duration(&cli.keepalive_timeout)
+            .context("Invalid --keepalive-timeout")?,
+        reconnect_timeout: cli_convert::parse_duration(&cli.reconnect_timeout)
+            .context("Invalid --reconnect-timeout")?,
+        drain_latency: cli_convert::convert_drain_policy(cli.drain_latency),
+        force_device_write: cli.force,
+        read_only: cli.readonly,
+        write_journal: cli.write_journal.clone(),
+        commit_every: cli.commit_every,
+        prep_rate_limit: cli.prep_rate_limit.as_deref()
+            .map(cli_convert::parse_size)
+            .transpose()
+            .context("Invalid --prep-rate-limit")?,
+        preflight: cli.preflight,
+        latency_floor: cli.latency_floor,
     };
     
     Ok(Config {
@@ -416,9 +825,58 @@ fn print_configuration(config: &Config) {
     println!("Configuration:");
     println!("  Workload:");
     println!("    Read: {}%, Write: {}%", config.workload.read_percent, config.workload.write_percent);
-    println!("    Queue depth: {}", config.workload.queue_depth);
-    println!("    Engine: {}", config.workload.engine);
-    println!("    Distribution: {}", config.workload.distribution);
+    if let Some(ref qd_ramp) = config.workload.qd_ramp {
+        println!("    Queue depth: ramp 1 -> {} ({})", config.workload.queue_depth, qd_ramp);
+    } else {
+        println!("    Queue depth: {}", config.workload.queue_depth);
+    }
+    let effective_engine = config.workload.effective_engine();
+    if effective_engine != config.workload.engine {
+        println!("    Engine: {} (runs {} at queue depth 1)", config.workload.engine, effective_engine);
+    } else {
+        println!("    Engine: {}", config.workload.engine);
+    }
+    if config.workload.engine == EngineType::Mmap {
+        println!("    mmap: {}", config.workload.mmap);
+    }
+    if config.output.is_verbose() {
+        println!("    Block size: {} bytes", config.workload.block_size);
+        for (name, patterns) in [("Read", &config.workload.read_distribution), ("Write", &config.workload.write_distribution)] {
+            if !patterns.is_empty() {
+                let split: Vec<String> = patterns.iter().map(|p| format!("{}B:{}%", p.block_size, p.weight)).collect();
+                println!("    {} block sizes: {}", name, split.join(", "));
+            }
+        }
+        println!("    Access: {}", if config.workload.random { "random" } else { "sequential" });
+        println!("    Direct IO: {}", if config.workload.direct { "yes (O_DIRECT)" } else { "no (buffered)" });
+        if config.workload.buffered_percent > 0 {
+            println!("    Buffered mix: {}% of operations through a buffered fd", config.workload.buffered_percent);
+        }
+        if let Some(ref remap) = config.workload.remap_offsets {
+            println!("    Offset remap: seed {}, {} byte chunks", remap.seed, remap.chunk_size(config.workload.io_sizes()));
+        }
+        println!("    Sync: {}", if config.workload.sync { "yes (O_SYNC)" } else { "no" });
+        if config.workload.open_flags.any() {
+            println!("    Open flags: {}", config.workload.open_flags);
+        }
+        println!("    Write pattern: {:?}", config.workload.write_pattern);
+        if let Some(ref custom) = config.workload.custom_pattern {
+            println!("    Pattern payload: {}", custom);
+        }
+        println!("    Buffer huge pages: {}", config.workload.buffer_hugepages);
+    }
+    if let Some(grow_to) = config.workload.grow_to {
+        println!("    Append-grow: extend to {} bytes, then overwrite", grow_to);
+    }
+    if let Some(start) = config.workload.offset_start {
+        println!("    Start offset: {} bytes (repeat with --offset-start {})", start, start);
+    }
+    if config.workload.worker_groups.is_empty() {
+        println!("    Distribution: {}", config.workload.distribution);
+    } else {
+        let groups: Vec<String> = config.workload.worker_groups.iter().map(|g| g.to_string()).collect();
+        println!("    Worker groups: {}", groups.join(", "));
+    }
     println!("    Completion: {}", config.workload.completion_mode);
     
     if let Some(ref think_time) = config.workload.think_time {
@@ -439,10 +897,24 @@ fn print_configuration(config: &Config) {
         if let Some(size) = target.file_size {
             println!("    Size: {} bytes", size);
         }
+        if let Some((start, end)) = target.region {
+            println!("    Region: {}-{} ({} bytes)", start, end, end - start);
+        }
+        if target.preallocate {
+            println!("    Preallocation: {}", target.prealloc_mode);
+        }
     }
     
     println!("  Workers:");
-    println!("    Threads: {}", config.workers.threads);
+    match config.workers.thread_scaling {
+        Some(ref scaling) => println!("    Threads: auto (1 -> {}, {}s steps, plateau <{}%)",
+            scaling.max_threads, scaling.step_seconds, scaling.plateau_percent),
+        None => println!("    Threads: {}", config.workers.threads),
+    }
+    if let Some(ref visibility) = config.workers.visibility {
+        println!("    Visibility test: {} {} ops, {} check ({} per batch, {}ms timeout)",
+            visibility.ops, visibility.op, visibility.check, visibility.batch, visibility.timeout_ms);
+    }
     if let Some(ref cores) = config.workers.cpu_cores {
         println!("    CPU cores: {}", cores);
     }
@@ -451,35 +923,239 @@ fn print_configuration(config: &Config) {
     }
 }
 
+/// Tokio runtime for a coordinator or node service
+///
+/// With --housekeeping-cores, the calling thread and the runtime's threads
+/// run on those cores, and IO workers started later move to the others.
+fn new_runtime(cli: &Cli) -> Result<tokio::runtime::Runtime> {
+    use iopulse::worker::affinity;
+    
+    if let Some(ref spec) = cli.housekeeping_cores {
+        let cores = affinity::parse_cpu_list(spec).context("Invalid --housekeeping-cores")?;
+        if affinity::housekeeping_cores().is_some() {
+            // Another runtime of this process (job sets) already reserved them
+            affinity::set_cpu_affinity(&cores)?;
+        } else {
+            let workers = affinity::reserve_housekeeping_cores(&cores)?;
+            tracing::debug!("housekeeping cores {:?}, IO workers on {:?}", cores, workers);
+        }
+    }
+    tokio::runtime::Runtime::new().context("Failed to create tokio runtime")
+}
+
 /// Run in service mode (distributed node)
 fn run_service(cli: Cli) -> Result<()> {
     // Service mode uses tokio runtime
-    let runtime = tokio::runtime::Runtime::new()
-        .context("Failed to create tokio runtime")?;
+    let runtime = new_runtime(&cli)?;
+    if let (Some(housekeeping), Some(workers)) = (iopulse::worker::affinity::housekeeping_cores(), iopulse::worker::affinity::worker_cores()) {
+        println!("Housekeeping cores: {:?} (IO workers on {:?})", housekeeping, workers);
+    }
     
     runtime.block_on(async {
-        let service = iopulse::distributed::NodeService::new(cli.listen_port)
+        let mut service = iopulse::distributed::NodeService::new(cli.listen_port)
             .context("Failed to create node service")?;
+        if cli.idle_timeout == 0 {
+            service.set_idle_timeout(None);
+        } else {
+            service.set_idle_timeout(Some(std::time::Duration::from_secs(cli.idle_timeout)));
+        }
+        service.set_max_queued_jobs(cli.max_queued_jobs);
         
         service.run().await
     })
 }
 
-/// Run in coordinator mode (distributed orchestration)
-fn run_coordinator(cli: Cli) -> Result<()> {
-    // Parse node addresses
-    let node_addresses = if let Some(ref host_list) = cli.host_list {
+/// Query node services for status (`iopulse ping`)
+fn run_ping(cli: &Cli, nodes: &[String], timeout: u64) -> Result<()> {
+    let node_addresses: Vec<String> = if nodes.is_empty() {
+        parse_node_addresses(cli)?
+    } else {
+        nodes.iter()
+            .map(|n| parse_node_entry(n, cli.worker_port).map(|node| node.address))
+            .collect::<Result<_>>()?
+    };
+    
+    let runtime = tokio::runtime::Runtime::new()
+        .context("Failed to create tokio runtime")?;
+    
+    let timeout = std::time::Duration::from_secs(timeout);
+    // Ping all nodes concurrently
+    let results = runtime.block_on(async {
+        let handles: Vec<_> = node_addresses.iter()
+            .map(|addr| {
+                let addr = addr.clone();
+                tokio::spawn(async move { iopulse::distributed::ping_node(&addr, timeout).await })
+            })
+            .collect();
+        
+        let mut results = Vec::with_capacity(handles.len());
+        for handle in handles {
+            results.push(handle.await.map_err(anyhow::Error::from).and_then(|r| r));
+        }
+        results
+    });
+    
+    let mut failed = 0;
+    for (addr, result) in node_addresses.iter().zip(results) {
+        match result {
+            Ok(ping) => match ping.status {
+                Some(status) => {
+                    let state = match status.current_test {
+                        Some(ref test) => format!("BUSY (job {} for {}, {} workers, {:.0}s, {}/{} queued)",
+                            test.job_id, test.coordinator, test.num_workers, test.elapsed_ns as f64 / 1e9,
+                            status.queued_jobs, status.max_queued_jobs),
+                        None => "idle".to_string(),
+                    };
+                    println!("{}: {} node={} version={} protocol=v{} uptime={:.0}s tests={} rtt={:.1}ms",
+                        addr, state, status.node_id, status.software_version, ping.protocol_version,
+                        status.uptime_ns as f64 / 1e9, status.tests_run,
+                        ping.round_trip.as_secs_f64() * 1000.0);
+                    if cli.verbose > 0 {
+                        println!("  capabilities: {}", status.capabilities.join(", "));
+                    }
+                }
+                None => {
+                    println!("{}: reachable (protocol v{}, no STATUS support) rtt={:.1}ms",
+                        addr, ping.protocol_version, ping.round_trip.as_secs_f64() * 1000.0);
+                }
+            },
+            Err(e) => {
+                println!("{}: UNREACHABLE ({:#})", addr, e);
+                failed += 1;
+            }
+        }
+    }
+    
+    if failed > 0 {
+        anyhow::bail!("{} of {} nodes did not respond", failed, node_addresses.len());
+    }
+    
+    Ok(())
+}
+
+/// Check a target against the write journal of an earlier run (`iopulse verify-journal`)
+fn run_verify_journal(target: &std::path::Path, journals: &[std::path::PathBuf]) -> Result<()> {
+    use iopulse::worker::write_journal::{verify_target, MAX_REPORTED_MISMATCHES};
+    
+    let result = verify_target(target, journals)?;
+    
+    println!("Verified {} against {} journal file(s):", target.display(), journals.len());
+    println!("  Blocks checked:  {} ({})", format_number(result.blocks), format_bytes(result.bytes));
+    println!("  Matching:        {}", format_number(result.blocks - result.mismatched));
+    println!("  Mismatched:      {}", format_number(result.mismatched));
+    if result.unchecked > 0 {
+        println!("  Not checked:     {} (only written by writes still in flight when the run ended)",
+                 format_number(result.unchecked));
+    }
+    if result.mismatched > 0 {
+        let offsets: Vec<String> = result.mismatch_offsets.iter().map(u64::to_string).collect();
+        println!("  First mismatches at offsets: {}{}", offsets.join(", "),
+                 if result.mismatched > MAX_REPORTED_MISMATCHES as u64 { ", ..." } else { "" });
+        anyhow::bail!("{} of {} blocks don't hold their journaled data", result.mismatched, result.blocks);
+    }
+    
+    Ok(())
+}
+
+/// Check the write ordering of a target after a power cut (`iopulse verify-order`)
+fn run_verify_order(target: &std::path::Path) -> Result<()> {
+    use iopulse::worker::write_order::{verify_target, MAX_REPORTED_VIOLATIONS};
+    
+    let result = verify_target(target)?;
+    
+    println!("Verified write ordering of {} ({} read):", target.display(), format_bytes(result.bytes));
+    if result.streams.is_empty() {
+        anyhow::bail!("No commits found (was the target written with --commit-every?)");
+    }
+    println!("  {:>6}  {:>10}  {:>12}  {:>10}  {:>12}  {:>10}  {:>12}",
+             "Worker", "Committed", "Sequence", "Newest", "Sequence", "Committed", "Uncommitted");
+    for stream in &result.streams {
+        println!("  {:>6}  {:>10}  {:>12}  {:>10}  {:>12}  {:>10}  {:>12}",
+                 stream.worker,
+                 stream.committed,
+                 format_number(stream.committed_sequence),
+                 stream.newest_generation,
+                 format_number(stream.newest_sequence),
+                 format_number(stream.committed_blocks),
+                 format_number(stream.uncommitted_blocks));
+    }
+    if result.torn > 0 {
+        println!("  Torn blocks:     {} (being written at the power cut)", format_number(result.torn));
+    }
+    if result.torn_commits > 0 {
+        println!("  Torn commits:    {} (previous commit used)", format_number(result.torn_commits));
+    }
+    if result.stale > 0 {
+        println!("  Earlier runs:    {} blocks (not checked)", format_number(result.stale));
+    }
+    if result.orphaned > 0 {
+        println!("  Orphaned:        {} blocks of {} streams (no intact commit)",
+                 format_number(result.orphaned), result.orphaned_streams);
+    }
+    println!("  Violations:      {}", format_number(result.violations));
+    if result.violations > 0 {
+        let offsets: Vec<String> = result.violation_offsets.iter().map(u64::to_string).collect();
+        println!("  First violations at offsets: {}{}", offsets.join(", "),
+                 if result.violations > MAX_REPORTED_VIOLATIONS as u64 { ", ..." } else { "" });
+        anyhow::bail!("{} blocks claim a generation newer than the last durable commit point allows", result.violations);
+    }
+    if result.orphaned > 0 {
+        anyhow::bail!("{} streams have writes but no intact commit, so their ordering can't be checked", result.orphaned_streams);
+    }
+    
+    Ok(())
+}
+
+/// Merge aggregate JSON results of independent runs (`iopulse merge`)
+fn run_merge(inputs: &[std::path::PathBuf], output: &std::path::Path, force: bool) -> Result<()> {
+    use iopulse::output::merge::{incompatibilities, merge_runs, RunResult};
+    
+    let runs = inputs.iter()
+        .map(|path| RunResult::load(path))
+        .collect::<Result<Vec<_>>>()?;
+    
+    let problems = incompatibilities(&runs);
+    if !problems.is_empty() {
+        if !force {
+            anyhow::bail!("Runs used different workloads (use --force to merge anyway):\n  {}",
+                problems.join("\n  "));
+        }
+        for problem in &problems {
+            eprintln!("Warning: {}", problem);
+        }
+    }
+    
+    let merged = merge_runs(&runs);
+    iopulse::output::json::write_json_output(output, &merged, true)
+        .with_context(|| format!("Failed to write {}", output.display()))?;
+    
+    println!("Merged {} runs:", runs.len());
+    for run in &runs {
+        let aggregate = &run.output.final_summary.aggregate;
+        println!("  {:<24} {:>14} ops  {:>12} IOPS  {:>12}/s  {}",
+                 run.name, format_number(aggregate.total_ops), format_number(aggregate.total_iops),
+                 format_bytes(aggregate.total_throughput.bytes_per_sec),
+                 if run.histogram.is_some() { "histogram" } else { "no histogram" });
+    }
+    let aggregate = &merged.final_summary.aggregate;
+    println!("  {:<24} {:>14} ops  {:>12} IOPS  {:>12}/s  {} errors",
+             "Total", format_number(aggregate.total_ops), format_number(aggregate.total_iops),
+             format_bytes(aggregate.total_throughput.bytes_per_sec), format_number(aggregate.errors));
+    match aggregate.latency.as_ref().and_then(|l| l.p99.as_ref()) {
+        Some(p99) => println!("  Latency p99 (merged histograms): {}", p99.human),
+        None => println!("  Latency percentiles omitted: not every run has a histogram file (--json-histogram)"),
+    }
+    println!("Written to {}", output.display());
+    
+    Ok(())
+}
+
+/// Nodes (address and optional `=role`) from --host-list or --clients-file
+fn parse_nodes(cli: &Cli) -> Result<Vec<NodeEntry>> {
+    if let Some(ref host_list) = cli.host_list {
         // Parse comma-separated list
         host_list.split(',')
-            .map(|s| {
-                let addr = s.trim();
-                // Add port if not specified
-                if addr.contains(':') {
-                    addr.to_string()
-                } else {
-                    format!("{}:{}", addr, cli.worker_port)
-                }
-            })
+            .map(|s| parse_node_entry(s, cli.worker_port))
             .collect()
     } else if let Some(ref clients_file) = cli.clients_file {
         // Read from file
@@ -488,215 +1164,154 @@ fn run_coordinator(cli: Cli) -> Result<()> {
         
         content.lines()
             .filter(|line| !line.trim().is_empty() && !line.trim().starts_with('#'))
-            .map(|line| {
-                let addr = line.trim();
-                if addr.contains(':') {
-                    addr.to_string()
-                } else {
-                    format!("{}:{}", addr, cli.worker_port)
-                }
-            })
+            .map(|line| parse_node_entry(line, cli.worker_port))
             .collect()
     } else {
-        anyhow::bail!("Coordinator mode requires --host-list or --clients-file");
-    };
+        anyhow::bail!("Node addresses required: use --host-list or --clients-file");
+    }
+}
+
+/// Node addresses from --host-list or --clients-file (roles dropped)
+fn parse_node_addresses(cli: &Cli) -> Result<Vec<String>> {
+    Ok(parse_nodes(cli)?.into_iter().map(|node| node.address).collect())
+}
+
+/// Run in coordinator mode (distributed orchestration)
+fn run_coordinator(cli: Cli) -> Result<()> {
+    // Parse node addresses (with optional roles)
+    let nodes = parse_nodes(&cli)
+        .context("Coordinator mode requires --host-list or --clients-file")?;
+    let node_addresses: Vec<String> = nodes.iter().map(|node| node.address.clone()).collect();
+    let node_roles: Vec<Option<String>> = nodes.into_iter().map(|node| node.role).collect();
+    
+    // API mode: configs arrive over HTTP instead of the command line
+    if let Some(ref listen) = cli.api_listen {
+        let server = iopulse::distributed::ApiServer::new(listen.clone(), node_addresses)?;
+        let runtime = new_runtime(&cli)?;
+        return runtime.block_on(server.run());
+    }
     
     // Build configuration
-    let config = build_config_from_cli(&cli)?;
+    let mut config = build_config_from_cli(&cli)?;
+    let job_id = cli.job_id.clone().unwrap_or_else(iopulse::distributed::generate_job_id);
+    config.expand_run_id(&job_id);
+    config.resolve_offset_start();
+    config.resolve_device_rating()?;
+    iopulse::util::logging::configure(config.output.verbosity, config.runtime.debug, None)?;
     
     // Validate configuration (includes write conflict detection)
     iopulse::config::validator::validate_config(&config)
         .context("Configuration validation failed")?;
     
     // Coordinator mode uses tokio runtime
-    let runtime = tokio::runtime::Runtime::new()
-        .context("Failed to create tokio runtime")?;
+    let runtime = new_runtime(&cli)?;
     
     runtime.block_on(async {
-        let coordinator = iopulse::distributed::DistributedCoordinator::new(
+        let mut coordinator = iopulse::distributed::DistributedCoordinator::new(
             Arc::new(config),
             node_addresses,
         ).context("Failed to create coordinator")?;
+        coordinator.set_job_id(job_id);
+        if node_roles.iter().any(Option::is_some) || !cli.role_workload.is_empty() {
+            let fragments = load_fragments(&cli.role_workload)?;
+            coordinator.set_roles(node_roles, &fragments)?;
+        }
+        let _control = bind_control_socket(&cli, &mut coordinator)?;
         
         coordinator.run().await
     })
 }
 
-/// Print test results
-pub fn print_results(stats: &WorkerStats, duration: std::time::Duration, config: &Config) {
-    use iopulse::util::time::{calculate_iops, calculate_throughput, format_rate, format_throughput};
-    
-    println!("═══════════════════════════════════════════════════════════");
-    println!("                    TEST RESULTS");
-    println!("═══════════════════════════════════════════════════════════");
-    println!();
-    
-    // Print elapsed time
-    println!("Elapsed Time: {:.3}s", duration.as_secs_f64());
-    println!();
-    
-    // Calculate IOPS and throughput
-    let read_iops = calculate_iops(stats.read_ops(), duration);
-    let write_iops = calculate_iops(stats.write_ops(), duration);
-    let total_iops = calculate_iops(stats.total_ops(), duration);
-    
-    let read_throughput = calculate_throughput(stats.read_bytes(), duration);
-    let write_throughput = calculate_throughput(stats.write_bytes(), duration);
-    let total_throughput = calculate_throughput(stats.total_bytes(), duration);
-    
-    // Operations with IOPS
-    println!("Operations:");
-    println!("  Read:  {} ops ({}) - {} IOPS", 
-             format_number(stats.read_ops()), 
-             format_bytes(stats.read_bytes()),
-             format_rate(read_iops));
-    println!("  Write: {} ops ({}) - {} IOPS", 
-             format_number(stats.write_ops()), 
-             format_bytes(stats.write_bytes()),
-             format_rate(write_iops));
-    println!("  Total: {} ops ({}) - {} IOPS", 
-             format_number(stats.total_ops()), 
-             format_bytes(stats.total_bytes()),
-             format_rate(total_iops));
-    
-    if stats.errors() > 0 {
-        println!("  Errors: {}", stats.errors());
-    }
-    
-    // Verification statistics (only if verification enabled)
-    if stats.verify_ops() > 0 {
-        let success_rate = if stats.verify_ops() > 0 {
-            ((stats.verify_ops() - stats.verify_failures()) as f64 / stats.verify_ops() as f64) * 100.0
-        } else {
-            0.0
-        };
-        println!();
-        println!("Verification:");
-        println!("  Operations: {}", format_number(stats.verify_ops()));
-        println!("  Failures:   {}", format_number(stats.verify_failures()));
-        println!("  Success:    {:.2}%", success_rate);
-    }
-    
-    println!();
-    
-    // Coverage and rewrite statistics (only if heatmap enabled)
-    if config.workload.heatmap {
-        if let Some(file_size) = config.targets.get(0).and_then(|t| t.file_size) {
-            let total_blocks = file_size / config.workload.block_size;
-            let unique_blocks = stats.unique_blocks_count();
-            let coverage = stats.coverage_percent(total_blocks);
-            let rewrites = stats.rewrite_percent();
-            
-            println!("Coverage:");
-            println!("  Unique blocks: {} / {} ({:.2}%)", 
-                     format_number(unique_blocks),
-                     format_number(total_blocks),
-                     coverage);
-            println!("  Rewrites:      {} ops ({:.2}% of operations)",
-                     format_number(stats.total_ops() - unique_blocks),
-                     rewrites);
-            println!();
-        }
-    }
-    
-    println!();
-    
-    // Throughput
-    println!("Throughput:");
-    println!("  Read:  {}", format_throughput(read_throughput));
-    println!("  Write: {}", format_throughput(write_throughput));
-    println!("  Total: {}", format_throughput(total_throughput));
+/// Listen for control commands on `--control-socket`, if given
+///
+/// The socket is removed when the returned guard is dropped.
+fn bind_control_socket(
+    cli: &Cli,
+    coordinator: &mut iopulse::distributed::DistributedCoordinator,
+) -> Result<Option<iopulse::distributed::control::ControlSocket>> {
+    use iopulse::distributed::control::{ControlHandle, ControlSocket};
+    
+    let Some(ref path) = cli.control_socket else { return Ok(None) };
+    let (handle, inbox) = ControlHandle::channel();
+    let socket = ControlSocket::bind(path, handle)?;
+    coordinator.set_control(inbox);
+    Ok(Some(socket))
+}
+
+/// How long rank 0 waits for the other ranks to publish their addresses
+const MPI_RENDEZVOUS_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(300);
+
+/// Run as one rank of an mpirun/srun launch
+///
+/// Every rank runs a node service on its host and publishes its address in
+/// the rendezvous directory; rank 0 then coordinates the test across all of
+/// them, like coordinator mode with the ranks as the node list.
+fn run_mpi(cli: Cli) -> Result<()> {
+    use iopulse::distributed::mpi::{RankInfo, Rendezvous};
+    
+    let rank = RankInfo::from_env()?;
+    let job = cli.job_id.clone().unwrap_or_else(|| rank.job.clone());
+    let base = cli.rendezvous.clone().unwrap_or_else(|| std::path::PathBuf::from("."));
+    let rendezvous = Rendezvous::new(&base, &job)?;
+    
+    // Build and validate on every rank so a bad command line fails fast everywhere
+    let mut config = build_config_from_cli(&cli)?;
+    config.expand_run_id(&job);
+    config.resolve_offset_start();
+    config.resolve_device_rating()?;
+    iopulse::config::validator::validate_config(&config)
+        .context("Configuration validation failed")?;
     
-    println!();
+    // Ranks sharing a host start their port search at different ports
+    let first_port = cli.listen_port.saturating_add(rank.local_rank as u16);
+    let (service_port, _port_lock) = find_available_port(first_port, cli.debug)?;
+    let service_handle = launch_localhost_service(service_port, &cli)?;
+    std::thread::sleep(std::time::Duration::from_millis(500));
     
-    // Latency statistics
-    println!("Latency:");
-    let hist = stats.io_latency();
+    let host = hostname::get().ok()
+        .and_then(|h| h.into_string().ok())
+        .unwrap_or_else(|| "localhost".to_string());
+    rendezvous.publish(rank.rank, &format!("{}:{}", host, service_port))?;
     
-    if hist.len() > 0 {
-        let min = hist.min();
-        println!("  Min:    {:?}", min);
-        
-        let mean = hist.mean();
-        println!("  Mean:   {:?}", mean);
-        
-        let max = hist.max();
-        println!("  Max:    {:?}", max);
-        
-        println!();
-        println!("  Percentiles:");
-        for &p in &[50.0, 90.0, 95.0, 99.0, 99.9, 99.99] {
-            let val = hist.percentile(p);
-            println!("    p{:5.2}: {:?}", p, val);
+    if rank.rank > 0 {
+        let success = rendezvous.wait_for_finish();
+        if let Err(e) = cleanup_service(service_handle, cli.debug) {
+            eprintln!("Warning: Failed to cleanup service: {}", e);
         }
-    } else {
-        println!("  No latency data collected");
+        rendezvous.leave(rank.rank);
+        if !success {
+            anyhow::bail!("Rank 0 reported a failed test");
+        }
+        return Ok(());
     }
     
-    println!();
-    
-    // Metadata operations
-    let metadata_ops = stats.metadata.total_ops();
-    if metadata_ops > 0 {
-        println!("Metadata Operations:");
-        println!("  Open:   {}", stats.metadata.open_ops.get());
-        println!("  Close:  {}", stats.metadata.close_ops.get());
-        println!("  Fsync:  {}", stats.metadata.fsync_ops.get());
-        println!("  Total:  {}", metadata_ops);
+    iopulse::util::logging::configure(config.output.verbosity, config.runtime.debug, None)?;
+    if !config.output.is_quiet() {
+        println!("MPI launch: {} ranks, rendezvous {}", rank.size, base.display());
+        print_configuration(&config);
         println!();
     }
     
-    // Lock latency statistics (if locking was enabled)
-    if let Some(ref lock_hist) = stats.lock_latency() {
-        if lock_hist.len() > 0 {
-            println!("File Locking:");
-            println!("  Locks acquired: {}", lock_hist.len());
-            println!("  Min latency:    {:?}", lock_hist.min());
-            println!("  Mean latency:   {:?}", lock_hist.mean());
-            println!("  Max latency:    {:?}", lock_hist.max());
-            println!();
-        }
-    }
-    
-    // Heatmap output (if enabled)
-    if config.workload.heatmap {
-        if let Some(file_size) = config.targets[0].file_size {
-            let total_blocks = file_size / config.workload.block_size;
-            if let Some(heatmap_output) = stats.heatmap_summary(config.workload.heatmap_buckets, total_blocks) {
-                println!("{}", heatmap_output);
-            }
-        }
-    }
+    let result = rendezvous.wait_for_ranks(rank.size, MPI_RENDEZVOUS_TIMEOUT).and_then(|node_addresses| {
+        let runtime = new_runtime(&cli)?;
+        runtime.block_on(async {
+            let mut coordinator = iopulse::distributed::DistributedCoordinator::new(
+                Arc::new(config),
+                node_addresses,
+            ).context("Failed to create coordinator")?;
+            coordinator.set_job_id(job.clone());
+            let _control = bind_control_socket(&cli, &mut coordinator)?;
+            coordinator.run().await
+        })
+    });
     
-    // Resource utilization (CPU and memory)
-    if let Some(resource_stats) = stats.resource_stats() {
-        println!("Resource Utilization:");
-        
-        // CPU utilization - show both process and system perspective
-        let num_threads = config.workers.threads as f64;
-        let process_cpu = resource_stats.cpu_percent;  // Total across all threads
-        let avg_cpu_per_thread = process_cpu / num_threads;
-        
-        // Get system CPU count
-        if let Some(system_cpus) = iopulse::util::resource::ResourceSnapshot::num_cpus() {
-            let system_cpu_percent = process_cpu / system_cpus as f64;
-            println!("  CPU:    {:.0}% per worker avg ({} workers)", 
-                     avg_cpu_per_thread, config.workers.threads);
-            println!("          {:.1}% of system capacity ({} cores total)", 
-                     system_cpu_percent, system_cpus);
-        } else {
-            println!("  CPU:    {:.1}% avg per thread ({} threads)", 
-                     avg_cpu_per_thread, config.workers.threads);
-        }
-        
-        // Memory utilization
-        println!("  Memory: {} (peak: {})", 
-                 format_bytes(resource_stats.memory_bytes),
-                 format_bytes(resource_stats.peak_memory_bytes));
-        println!();
+    rendezvous.finish(result.is_ok())?;
+    if let Err(e) = cleanup_service(service_handle, cli.debug) {
+        eprintln!("Warning: Failed to cleanup service: {}", e);
     }
+    rendezvous.cleanup(rank.size, std::time::Duration::from_secs(30));
     
-    println!("═══════════════════════════════════════════════════════════");
+    result
 }
 
 /// Format a number with thousands separators
@@ -737,21 +1352,32 @@ fn format_bytes(bytes: u64) -> String {
 }
 
 /// Find an available port for the localhost service
-fn find_available_port(debug: bool) -> Result<u16> {
+///
+/// The port stays reserved for this run while the returned lock is held, so
+/// concurrent runs that probe before either service has bound it don't both
+/// pick the same port.
+fn find_available_port(first: u16, debug: bool) -> Result<(u16, LockFile)> {
     use std::net::TcpListener;
     
-    // Try ports 9999-10099
-    for port in 9999..10100 {
+    let holder = format!("pid {}", std::process::id());
+    
+    // Try 100 ports from the first one
+    for port in first..first.saturating_add(100) {
+        let lock_path = std::env::temp_dir().join(format!("iopulse-port-{}.lock", port));
+        let Some(lock) = LockFile::try_acquire(&lock_path, true, &holder)? else {
+            continue;
+        };
         if let Ok(listener) = TcpListener::bind(("127.0.0.1", port)) {
             drop(listener);
             if debug {
                 eprintln!("DEBUG: Port {} is available", port);
             }
-            return Ok(port);
+            return Ok((port, lock));
         }
     }
     
-    anyhow::bail!("No available ports found in range 9999-10099. Please close other IOPulse instances or specify --no-service.")
+    anyhow::bail!("No available ports found in range {}-{}. Please close other IOPulse instances or specify --no-service.",
+        first, first.saturating_add(99))
 }
 
 /// Launch localhost service in background
@@ -771,15 +1397,18 @@ fn launch_localhost_service(port: u16, cli: &Cli) -> Result<std::process::Child>
     if cli.debug {
         cmd.arg("--debug");
     }
+    if let Some(ref cores) = cli.housekeeping_cores {
+        cmd.arg("--housekeeping-cores").arg(cores);
+    }
     
     // Redirect output to /dev/null (or log file if debug)
     if cli.debug {
-        let log_path = format!("/tmp/iopulse_service_{}.log", port);
+        let log_path = std::env::temp_dir().join(format!("iopulse_service_{}.log", port));
         let log_file = std::fs::File::create(&log_path)
             .context("Failed to create service log file")?;
         cmd.stdout(Stdio::from(log_file.try_clone()?));
         cmd.stderr(Stdio::from(log_file));
-        eprintln!("DEBUG: Service log: {}", log_path);
+        eprintln!("DEBUG: Service log: {}", log_path.display());
     } else {
         cmd.stdout(Stdio::null());
         cmd.stderr(Stdio::null());
@@ -0,0 +1,241 @@
+//! Alert hooks for latency and error thresholds
+//!
+//! During a duration run the coordinator checks every heartbeat interval of
+//! every node against the `--alert-*` thresholds. On a breach it prints an
+//! alert and fires the configured hooks with a JSON [`AlertPayload`]:
+//!
+//! - `--alert-exec CMD` runs `sh -c CMD` with the payload on stdin and the
+//!   breach reasons in `IOPULSE_ALERT`
+//! - `--alert-webhook URL` POSTs the payload to an `http://` URL
+//!
+//! Hooks run on their own threads so a slow command or endpoint never delays
+//! heartbeat collection. After an alert, further breaches are only reported
+//! once the cooldown has passed.
+
+use crate::config::AlertConfig;
+use crate::output::json::{format_timestamp, latency_with_percentiles, AggregatedSnapshot, JsonDuration, JsonLatency};
+use crate::util::time::{calculate_iops, format_duration};
+use anyhow::Context;
+use serde::Serialize;
+use std::io::{Read, Write};
+use std::time::{Duration, Instant};
+
+/// Percentiles reported in alert payloads
+const ALERT_PERCENTILES: [f64; 3] = [50.0, 99.0, 99.9];
+
+/// One node interval, as sent to alert hooks
+#[derive(Debug, Clone, Serialize)]
+pub struct AlertInterval {
+    pub duration: JsonDuration,
+    pub read_ops: u64,
+    pub write_ops: u64,
+    pub errors: u64,
+    pub iops: f64,
+    pub error_rate_percent: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency: Option<JsonLatency>,
+}
+
+/// JSON payload of an alert
+#[derive(Debug, Clone, Serialize)]
+pub struct AlertPayload {
+    pub job_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run_uuid: Option<String>,
+    pub node_id: String,
+    pub timestamp: String,
+    pub elapsed: JsonDuration,
+    pub reasons: Vec<String>,
+    pub interval: AlertInterval,
+}
+
+/// Checks node intervals against the thresholds and fires hooks
+pub struct AlertMonitor {
+    config: AlertConfig,
+    job_id: String,
+    run_uuid: Option<String>,
+    last_fired: Option<Instant>,
+}
+
+impl AlertMonitor {
+    pub fn new(config: AlertConfig, job_id: String, run_uuid: Option<String>) -> Self {
+        Self { config, job_id, run_uuid, last_fired: None }
+    }
+
+    /// Check the interval between two cumulative snapshots of one node
+    pub fn check(&mut self, node_id: &str, current: &AggregatedSnapshot, previous: &AggregatedSnapshot) {
+        let duration = current.elapsed.saturating_sub(previous.elapsed);
+        let mut latency = current.read_latency.since(&previous.read_latency);
+        latency.merge(&current.write_latency.since(&previous.write_latency));
+
+        let read_ops = current.read_ops.saturating_sub(previous.read_ops);
+        let write_ops = current.write_ops.saturating_sub(previous.write_ops);
+        let errors = current.errors.saturating_sub(previous.errors);
+        let error_rate = error_rate_percent(read_ops + write_ops, errors);
+        let p99 = (!latency.is_empty()).then(|| latency.percentile(99.0));
+
+        let reasons = breaches(&self.config, p99, error_rate);
+        if reasons.is_empty() {
+            return;
+        }
+        let cooldown = Duration::from_secs(self.config.cooldown_secs);
+        if self.last_fired.is_some_and(|last| last.elapsed() < cooldown) {
+            return;
+        }
+        self.last_fired = Some(Instant::now());
+
+        eprintln!("ALERT: node {}: {}", node_id, reasons.join("; "));
+        let payload = AlertPayload {
+            job_id: self.job_id.clone(),
+            run_uuid: self.run_uuid.clone(),
+            node_id: node_id.to_string(),
+            timestamp: format_timestamp(current.timestamp),
+            elapsed: JsonDuration::from_duration(current.elapsed),
+            reasons,
+            interval: AlertInterval {
+                duration: JsonDuration::from_duration(duration),
+                read_ops,
+                write_ops,
+                errors,
+                iops: calculate_iops(read_ops + write_ops, duration),
+                error_rate_percent: error_rate,
+                latency: (!latency.is_empty()).then(|| latency_with_percentiles(&latency, &ALERT_PERCENTILES)),
+            },
+        };
+        self.fire(&payload);
+    }
+
+    /// Run the hooks in the background
+    fn fire(&self, payload: &AlertPayload) {
+        let body = match serde_json::to_vec(payload) {
+            Ok(body) => body,
+            Err(e) => {
+                eprintln!("Warning: Failed to serialize alert: {}", e);
+                return;
+            }
+        };
+
+        if let Some(ref command) = self.config.exec {
+            let command = command.clone();
+            let body = body.clone();
+            let reasons = payload.reasons.join("; ");
+            std::thread::spawn(move || {
+                if let Err(e) = run_exec(&command, &reasons, &body) {
+                    eprintln!("Warning: Alert command failed: {:#}", e);
+                }
+            });
+        }
+
+        if let Some(ref url) = self.config.webhook {
+            let url = url.clone();
+            std::thread::spawn(move || {
+                if let Err(e) = post_webhook(&url, &body) {
+                    eprintln!("Warning: Alert webhook failed: {:#}", e);
+                }
+            });
+        }
+    }
+}
+
+/// Errors as a percentage of all attempted operations
+fn error_rate_percent(ops: u64, errors: u64) -> f64 {
+    if ops + errors == 0 {
+        0.0
+    } else {
+        errors as f64 * 100.0 / (ops + errors) as f64
+    }
+}
+
+/// Reasons the interval breaches the thresholds (empty if none)
+fn breaches(config: &AlertConfig, p99: Option<Duration>, error_rate: f64) -> Vec<String> {
+    let mut reasons = Vec::new();
+    if let (Some(threshold), Some(p99)) = (config.p99_us, p99) {
+        let threshold = Duration::from_micros(threshold);
+        if p99 > threshold {
+            reasons.push(format!("p99 latency {} exceeds {}", format_duration(p99), format_duration(threshold)));
+        }
+    }
+    if let Some(threshold) = config.error_rate_percent {
+        if error_rate > threshold {
+            reasons.push(format!("error rate {:.2}% exceeds {}%", error_rate, threshold));
+        }
+    }
+    reasons
+}
+
+fn run_exec(command: &str, reasons: &str, body: &[u8]) -> anyhow::Result<()> {
+    let mut child = std::process::Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .env("IOPULSE_ALERT", reasons)
+        .stdin(std::process::Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run '{}'", command))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // The command may not read its input
+        let _ = stdin.write_all(body);
+    }
+    let status = child.wait()?;
+    if !status.success() {
+        anyhow::bail!("'{}' exited with {}", command, status);
+    }
+    Ok(())
+}
+
+/// POST the payload to an http:// URL
+fn post_webhook(url: &str, body: &[u8]) -> anyhow::Result<()> {
+    let rest = url.strip_prefix("http://")
+        .ok_or_else(|| anyhow::anyhow!("Only http:// webhooks are supported: {}", url))?;
+    let (host, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    let address = if host.contains(':') { host.to_string() } else { format!("{}:80", host) };
+
+    let timeout = Duration::from_secs(10);
+    let socket_addr = std::net::ToSocketAddrs::to_socket_addrs(&address)
+        .with_context(|| format!("Failed to resolve {}", address))?
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("No address for {}", address))?;
+    let mut stream = std::net::TcpStream::connect_timeout(&socket_addr, timeout)
+        .with_context(|| format!("Failed to connect to {}", address))?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+
+    write!(stream, "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+           path, host, body.len())?;
+    stream.write_all(body)?;
+
+    let mut response = Vec::new();
+    let _ = stream.take(4096).read_to_end(&mut response);
+    let status_line = String::from_utf8_lossy(&response);
+    let status_line = status_line.lines().next().unwrap_or("");
+    let ok = status_line.split_whitespace().nth(1).is_some_and(|code| code.starts_with('2'));
+    if !ok {
+        anyhow::bail!("{} responded '{}'", url, status_line);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_breaches() {
+        let config = AlertConfig {
+            p99_us: Some(5000),
+            error_rate_percent: Some(1.0),
+            exec: Some("true".to_string()),
+            webhook: None,
+            cooldown_secs: 60,
+        };
+        assert!(breaches(&config, Some(Duration::from_micros(4000)), 0.5).is_empty());
+        assert_eq!(breaches(&config, Some(Duration::from_micros(6000)), 0.5).len(), 1);
+        assert_eq!(breaches(&config, None, 2.0).len(), 1);
+        assert_eq!(breaches(&config, Some(Duration::from_millis(10)), 2.0).len(), 2);
+
+        assert_eq!(error_rate_percent(99, 1), 1.0);
+        assert_eq!(error_rate_percent(0, 0), 0.0);
+    }
+}
@@ -0,0 +1,113 @@
+//! Periodic summary checkpoints
+//!
+//! With `--summary-interval`, the coordinator closes a checkpoint every
+//! interval of a duration run: the operations and read/write latency of that
+//! interval alone, across all nodes. Degradation over a multi-day soak test
+//! (thermal throttling, fragmentation, log growth) shows up as drifting
+//! checkpoints instead of being averaged into the final summary.
+//!
+//! Heartbeats carry cumulative counters and histograms, so a checkpoint is
+//! the difference between each node's latest heartbeat and its heartbeat at
+//! the previous checkpoint.
+
+use crate::output::json::AggregatedSnapshot;
+use crate::stats::simple_histogram::SimpleHistogram;
+use std::time::{Duration, SystemTime};
+
+/// Statistics of one checkpoint interval (all nodes)
+#[derive(Debug, Clone)]
+pub struct Checkpoint {
+    /// 1-based checkpoint number
+    pub number: usize,
+    /// Test time at which the interval started
+    pub start: Duration,
+    /// Test time at which the interval ended
+    pub end: Duration,
+    pub timestamp: SystemTime,
+    pub read_ops: u64,
+    pub write_ops: u64,
+    pub read_bytes: u64,
+    pub write_bytes: u64,
+    pub read_latency: SimpleHistogram,
+    pub write_latency: SimpleHistogram,
+}
+
+impl Checkpoint {
+    /// Length of the interval
+    pub fn duration(&self) -> Duration {
+        self.end.saturating_sub(self.start)
+    }
+}
+
+/// Closes a checkpoint every `interval` of test time
+pub struct CheckpointTracker {
+    interval: Duration,
+    last: Duration,
+    /// Each node's cumulative snapshot at the previous checkpoint
+    base: Vec<Option<AggregatedSnapshot>>,
+    checkpoints: Vec<Checkpoint>,
+}
+
+impl CheckpointTracker {
+    pub fn new(interval: Duration, num_nodes: usize) -> Self {
+        Self {
+            interval,
+            last: Duration::ZERO,
+            base: vec![None; num_nodes],
+            checkpoints: Vec::new(),
+        }
+    }
+
+    /// Whether a checkpoint is due at test time `elapsed`
+    pub fn is_due(&self, elapsed: Duration) -> bool {
+        elapsed >= self.last.saturating_add(self.interval)
+    }
+
+    /// Close a checkpoint at `elapsed` from each node's latest cumulative snapshot
+    pub fn close(&mut self, elapsed: Duration, latest: &[Option<AggregatedSnapshot>]) -> &Checkpoint {
+        let mut checkpoint = Checkpoint {
+            number: self.checkpoints.len() + 1,
+            start: self.last,
+            end: elapsed,
+            timestamp: SystemTime::now(),
+            read_ops: 0,
+            write_ops: 0,
+            read_bytes: 0,
+            write_bytes: 0,
+            read_latency: SimpleHistogram::new(),
+            write_latency: SimpleHistogram::new(),
+        };
+
+        for (base, latest) in self.base.iter_mut().zip(latest) {
+            let Some(latest) = latest else { continue };
+            match base {
+                Some(base) => {
+                    checkpoint.read_ops += latest.read_ops.saturating_sub(base.read_ops);
+                    checkpoint.write_ops += latest.write_ops.saturating_sub(base.write_ops);
+                    checkpoint.read_bytes += latest.read_bytes.saturating_sub(base.read_bytes);
+                    checkpoint.write_bytes += latest.write_bytes.saturating_sub(base.write_bytes);
+                    checkpoint.read_latency.merge(&latest.read_latency.since(&base.read_latency));
+                    checkpoint.write_latency.merge(&latest.write_latency.since(&base.write_latency));
+                }
+                None => {
+                    checkpoint.read_ops += latest.read_ops;
+                    checkpoint.write_ops += latest.write_ops;
+                    checkpoint.read_bytes += latest.read_bytes;
+                    checkpoint.write_bytes += latest.write_bytes;
+                    checkpoint.read_latency.merge(&latest.read_latency);
+                    checkpoint.write_latency.merge(&latest.write_latency);
+                }
+            }
+            *base = Some(latest.clone());
+        }
+
+        self.last = elapsed;
+        self.checkpoints.push(checkpoint);
+        self.checkpoints.last().unwrap()
+    }
+
+    /// All checkpoints closed so far
+    pub fn checkpoints(&self) -> &[Checkpoint] {
+        &self.checkpoints
+    }
+}
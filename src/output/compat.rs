@@ -0,0 +1,236 @@
+//! IOR and mdtest compatible summaries
+//!
+//! HPC acceptance tests often parse the summary of IOR (`Max Write: ...
+//! MiB/sec` and the "Summary of all tests" table) or mdtest (`SUMMARY rate:`
+//! table). With `--summary-format ior` or `--summary-format mdtest`, results
+//! are printed in that layout instead of the IOPulse report, so such harnesses
+//! can run IOPulse unchanged.
+//!
+//! Each IOPulse test is one repetition. Max, min, mean and standard deviation
+//! are taken over the repetitions passed in, as IOR and mdtest do over their
+//! iterations; with a single repetition they are equal and the deviation is 0.
+//!
+//! # Example
+//!
+//! ```
+//! use iopulse::output::compat::{format_ior_summary, IorParams, Repetition};
+//! use std::time::Duration;
+//!
+//! let rep = Repetition {
+//!     duration: Duration::from_secs(2),
+//!     write_bytes: 2048 << 20,
+//!     write_ops: 2048,
+//!     ..Default::default()
+//! };
+//! let params = IorParams { tasks: 4, tasks_per_node: 4, file_per_proc: false, block_size: 1 << 30, transfer_size: 1 << 20, api: "POSIX" };
+//! let summary = format_ior_summary(&[rep], &params);
+//! assert!(summary.starts_with("Max Write: 1024.00 MiB/sec (1073.74 MB/sec)"));
+//! ```
+
+use crate::config::Config;
+use crate::stats::WorkerStats;
+use std::fmt::Write;
+use std::time::Duration;
+
+const MEBIBYTE: f64 = 1024.0 * 1024.0;
+const MEGABYTE: f64 = 1_000_000.0;
+
+/// mdtest operation names, in mdtest's order
+pub const MDTEST_OPERATIONS: [&str; 10] = [
+    "Directory creation",
+    "Directory stat",
+    "Directory rename",
+    "Directory removal",
+    "File creation",
+    "File stat",
+    "File read",
+    "File removal",
+    "Tree creation",
+    "Tree removal",
+];
+
+/// Totals of one repetition
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Repetition {
+    pub duration: Duration,
+    pub read_bytes: u64,
+    pub write_bytes: u64,
+    pub read_ops: u64,
+    pub write_ops: u64,
+    /// Operations counted for each of `MDTEST_OPERATIONS` (None if not measured)
+    pub metadata_ops: [Option<u64>; 10],
+}
+
+impl Repetition {
+    /// Take the totals of a finished test
+    pub fn from_stats(stats: &WorkerStats, duration: Duration) -> Self {
+        let md = &stats.metadata;
+        Self {
+            duration,
+            read_bytes: stats.read_bytes(),
+            write_bytes: stats.write_bytes(),
+            read_ops: stats.read_ops(),
+            write_ops: stats.write_ops(),
+            metadata_ops: [
+                Some(md.mkdir_ops.get()),
+                None,
+                None,
+                Some(md.rmdir_ops.get()),
+                Some(md.open_ops.get()),
+                Some(md.stat_ops.get()),
+                Some(stats.read_ops()),
+                Some(md.unlink_ops.get()),
+                None,
+                None,
+            ],
+        }
+    }
+}
+
+/// Test parameters shown in the IOR summary table
+#[derive(Debug, Clone)]
+pub struct IorParams {
+    /// Workers across all nodes (#Tasks)
+    pub tasks: usize,
+    /// Workers per node (tPN)
+    pub tasks_per_node: usize,
+    /// Each worker has its own file (fPP)
+    pub file_per_proc: bool,
+    /// Bytes per task (blksiz)
+    pub block_size: u64,
+    /// Bytes per IO (xsize)
+    pub transfer_size: u64,
+    /// IO interface name (API)
+    pub api: &'static str,
+}
+
+impl IorParams {
+    /// Parameters of a test run on `nodes` nodes
+    pub fn from_config(config: &Config, nodes: usize) -> Self {
+        use crate::config::workload::{EngineType, FileDistribution};
+        let target = config.targets.first();
+        Self {
+            tasks: nodes * config.workers.threads,
+            tasks_per_node: config.workers.threads,
+            file_per_proc: target.is_some_and(|t| t.distribution == FileDistribution::PerWorker),
+            block_size: target.and_then(|t| t.file_size).unwrap_or(0),
+            transfer_size: config.workload.block_size,
+            api: match config.workload.engine {
+                EngineType::Sync | EngineType::Threadpool => "POSIX",
+                EngineType::IoUring => "IOURING",
+                EngineType::Libaio => "AIO",
+                EngineType::Mmap => "MMAP",
+            },
+        }
+    }
+}
+
+/// Max, min, mean and (population) standard deviation
+fn spread(values: &[f64]) -> (f64, f64, f64, f64) {
+    let n = values.len().max(1) as f64;
+    let max = values.iter().copied().fold(f64::MIN, f64::max).max(0.0);
+    let min = values.iter().copied().fold(f64::MAX, f64::min).min(max);
+    let mean = values.iter().sum::<f64>() / n;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+    (max, min, mean, variance.sqrt())
+}
+
+/// Format the IOR summary: `Max Write/Read` lines and the summary table
+pub fn format_ior_summary(reps: &[Repetition], params: &IorParams) -> String {
+    type Select = fn(&Repetition) -> (u64, u64);
+    let operations: [(&str, &str, Select); 2] = [
+        ("write", "Write:", |r| (r.write_bytes, r.write_ops)),
+        ("read", "Read: ", |r| (r.read_bytes, r.read_ops)),
+    ];
+    let secs = |r: &Repetition| r.duration.as_secs_f64().max(f64::MIN_POSITIVE);
+    let mean_secs = reps.iter().map(secs).sum::<f64>() / reps.len().max(1) as f64;
+
+    let mut out = String::new();
+    let mut rows = String::new();
+    for (name, label, select) in operations {
+        if reps.iter().all(|r| select(r).1 == 0) {
+            continue;
+        }
+        let bandwidth: Vec<f64> = reps.iter().map(|r| select(r).0 as f64 / secs(r)).collect();
+        let ops: Vec<f64> = reps.iter().map(|r| select(r).1 as f64 / secs(r)).collect();
+        let (bw_max, bw_min, bw_mean, bw_sd) = spread(&bandwidth);
+        let (ops_max, ops_min, ops_mean, ops_sd) = spread(&ops);
+        let agg_bytes = reps.iter().map(|r| select(r).0 as f64).sum::<f64>() / reps.len().max(1) as f64;
+
+        let _ = writeln!(out, "Max {} {:.2} MiB/sec ({:.2} MB/sec)", label, bw_max / MEBIBYTE, bw_max / MEGABYTE);
+        let _ = writeln!(rows,
+            "{:<9} {:10.2} {:10.2} {:10.2} {:10.2} {:10.2} {:10.2} {:10.2} {:10.2} {:10.5} {:>13} {:>13} {:5} {:6} {:3} {:4} {:3} {:5} {:8} {:9} {:4} {:6} {:8} {:8} {:9.1} {:>3} {:6}",
+            name,
+            bw_max / MEBIBYTE, bw_min / MEBIBYTE, bw_mean / MEBIBYTE, bw_sd / MEBIBYTE,
+            ops_max, ops_min, ops_mean, ops_sd,
+            mean_secs, "NA", "NA",
+            0, params.tasks, params.tasks_per_node, reps.len(), params.file_per_proc as u8,
+            0, 1, 0, 0, 1,
+            params.block_size, params.transfer_size, agg_bytes / MEBIBYTE,
+            params.api, 0);
+    }
+
+    out.push('\n');
+    out.push_str("Summary of all tests:\n");
+    out.push_str("Operation   Max(MiB)   Min(MiB)  Mean(MiB)     StdDev   Max(OPs)   Min(OPs)  Mean(OPs)     StdDev    Mean(s) Stonewall(s) Stonewall(MiB) Test# #Tasks tPN reps fPP reord reordoff reordrand seed segcnt   blksiz    xsize aggs(MiB)   API RefNum\n");
+    out.push_str(&rows);
+    out
+}
+
+/// Format the mdtest `SUMMARY rate` table (operations per second)
+pub fn format_mdtest_summary(reps: &[Repetition]) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "SUMMARY rate: (of {} iterations)", reps.len());
+    let _ = writeln!(out, "   Operation                     Max            Min           Mean        Std Dev");
+    let _ = writeln!(out, "   ---------                     ---            ---           ----        -------");
+    for (i, name) in MDTEST_OPERATIONS.iter().enumerate() {
+        let rates: Vec<f64> = reps.iter()
+            .map(|r| r.metadata_ops[i].unwrap_or(0) as f64 / r.duration.as_secs_f64().max(f64::MIN_POSITIVE))
+            .collect();
+        let (max, min, mean, sd) = spread(&rates);
+        let _ = writeln!(out, "   {:<22}: {:14.3} {:14.3} {:14.3} {:14.3}", name, max, min, mean, sd);
+    }
+    out
+}
+
+/// Print the IOR summary of a finished test
+pub fn print_ior_summary(stats: &WorkerStats, duration: Duration, config: &Config, nodes: usize) {
+    let rep = Repetition::from_stats(stats, duration);
+    print!("{}", format_ior_summary(&[rep], &IorParams::from_config(config, nodes)));
+}
+
+/// Print the mdtest summary of a finished test
+pub fn print_mdtest_summary(stats: &WorkerStats, duration: Duration) {
+    print!("{}", format_mdtest_summary(&[Repetition::from_stats(stats, duration)]));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spread_over_repetitions() {
+        let rep = |secs: u64, read_bytes: u64| Repetition {
+            duration: Duration::from_secs(secs),
+            read_bytes,
+            read_ops: read_bytes >> 20,
+            metadata_ops: [Some(100), None, None, None, Some(400), None, None, None, None, None],
+            ..Default::default()
+        };
+        let reps = [rep(1, 300 << 20), rep(1, 100 << 20)];
+        let params = IorParams { tasks: 8, tasks_per_node: 2, file_per_proc: true, block_size: 1 << 20, transfer_size: 1 << 20, api: "POSIX" };
+
+        let ior = format_ior_summary(&reps, &params);
+        assert!(ior.starts_with("Max Read:  300.00 MiB/sec (314.57 MB/sec)\n"));
+        assert!(!ior.contains("Max Write"));
+        let row = ior.lines().find(|l| l.starts_with("read ")).unwrap();
+        let fields: Vec<&str> = row.split_whitespace().collect();
+        // Max, Min, Mean, StdDev (MiB/s), then #Tasks, tPN, reps, fPP
+        assert_eq!(&fields[1..5], ["300.00", "100.00", "200.00", "100.00"]);
+        assert_eq!(&fields[13..17], ["8", "2", "2", "1"]);
+
+        let mdtest = format_mdtest_summary(&reps);
+        assert!(mdtest.starts_with("SUMMARY rate: (of 2 iterations)"));
+        assert!(mdtest.contains("   File creation         :        400.000        400.000        400.000          0.000"));
+    }
+}
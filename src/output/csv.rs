@@ -12,7 +12,10 @@
 //! - Metadata operations included
 //! - Resource utilization included
 
+use crate::config::OutputConfig;
 use crate::output::json::AggregatedSnapshot;
+use crate::stats::simple_histogram::SimpleHistogram;
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::Write;
 use std::path::Path;
@@ -23,29 +26,71 @@ pub struct CsvWriter {
     file: File,
     per_worker: bool,
     per_node: bool,  // NEW: For distributed aggregate CSV with per-node rows
+    percentiles: Vec<f64>,  // Extra latency percentile columns (--percentiles)
+    labels: String,  // Pre-formatted run UUID and label values appended to every row (--label)
+}
+
+/// Columns of a CSV time-series file
+#[derive(Debug, Clone, Default)]
+pub struct CsvOptions {
+    /// One row per worker, with a worker_id column
+    pub per_worker: bool,
+    /// A node_id column (distributed aggregate output)
+    pub per_node: bool,
+    /// One `lat_p<N>_us` column per percentile (reads and writes combined)
+    pub percentiles: Vec<f64>,
+    /// A `run_uuid` column before the label columns, the same on every row
+    pub run_uuid: Option<String>,
+    /// One `label_<key>` column per run label, holding its value on every row
+    pub labels: BTreeMap<String, String>,
+}
+
+impl CsvOptions {
+    /// Columns for the configured output (--per-worker-output, --percentiles, --label)
+    pub fn from_output(output: &OutputConfig, per_node: bool) -> Self {
+        Self {
+            per_worker: output.per_worker_output,
+            per_node,
+            percentiles: output.percentiles.clone().unwrap_or_default(),
+            run_uuid: output.run_uuid.clone(),
+            labels: output.labels.clone(),
+        }
+    }
 }
 
 impl CsvWriter {
-    /// Create a new CSV writer with optional node_id column
-    ///
-    /// When per_node is true, adds a node_id column for distributed aggregate output.
-    pub fn new_with_node_id(path: &Path, per_worker: bool, per_node: bool) -> Result<Self> {
+    /// Create a CSV writer and write its header row
+    pub fn new(path: &Path, options: &CsvOptions) -> Result<Self> {
+        let CsvOptions { per_worker, per_node, ref percentiles, ref run_uuid, ref labels } = *options;
         let mut file = File::create(path)?;
         
         // Write header row
         if per_node && per_worker {
             // Distributed per-worker mode: timestamp, elapsed, node_id, worker_id, then stats
-            writeln!(file, "timestamp,elapsed_sec,node_id,worker_id,read_ops,write_ops,total_ops,read_iops,write_iops,total_iops,read_mbps,write_mbps,total_mbps,read_latency_us,write_latency_us,cpu_percent_total,cpu_percent_per_worker,cpu_percent_system,memory_mb,metadata_open,metadata_close,metadata_stat,metadata_setattr,metadata_mkdir,metadata_rmdir,metadata_unlink,metadata_rename,metadata_readdir,metadata_fsync,meta_open_lat_us,meta_close_lat_us,meta_stat_lat_us,meta_setattr_lat_us,meta_mkdir_lat_us,meta_rmdir_lat_us,meta_unlink_lat_us,meta_rename_lat_us,meta_readdir_lat_us,meta_fsync_lat_us")?;
+            write!(file, "timestamp,elapsed_sec,node_id,worker_id,read_ops,write_ops,total_ops,read_iops,write_iops,total_iops,read_mbps,write_mbps,total_mbps,read_latency_us,write_latency_us,cpu_percent_total,cpu_percent_per_worker,cpu_percent_system,memory_mb,metadata_open,metadata_close,metadata_stat,metadata_setattr,metadata_mkdir,metadata_rmdir,metadata_unlink,metadata_rename,metadata_readdir,metadata_fsync,meta_open_lat_us,meta_close_lat_us,meta_stat_lat_us,meta_setattr_lat_us,meta_mkdir_lat_us,meta_rmdir_lat_us,meta_unlink_lat_us,meta_rename_lat_us,meta_readdir_lat_us,meta_fsync_lat_us")?;
         } else if per_node {
             // Distributed aggregate mode: timestamp, elapsed, node_id, then stats
-            writeln!(file, "timestamp,elapsed_sec,node_id,read_ops,write_ops,total_ops,read_iops,write_iops,total_iops,read_mbps,write_mbps,total_mbps,read_latency_us,write_latency_us,cpu_percent_total,cpu_percent_per_worker,cpu_percent_system,memory_mb,metadata_open,metadata_close,metadata_stat,metadata_setattr,metadata_mkdir,metadata_rmdir,metadata_unlink,metadata_rename,metadata_readdir,metadata_fsync,meta_open_lat_us,meta_close_lat_us,meta_stat_lat_us,meta_setattr_lat_us,meta_mkdir_lat_us,meta_rmdir_lat_us,meta_unlink_lat_us,meta_rename_lat_us,meta_readdir_lat_us,meta_fsync_lat_us")?;
+            write!(file, "timestamp,elapsed_sec,node_id,read_ops,write_ops,total_ops,read_iops,write_iops,total_iops,read_mbps,write_mbps,total_mbps,read_latency_us,write_latency_us,cpu_percent_total,cpu_percent_per_worker,cpu_percent_system,memory_mb,metadata_open,metadata_close,metadata_stat,metadata_setattr,metadata_mkdir,metadata_rmdir,metadata_unlink,metadata_rename,metadata_readdir,metadata_fsync,meta_open_lat_us,meta_close_lat_us,meta_stat_lat_us,meta_setattr_lat_us,meta_mkdir_lat_us,meta_rmdir_lat_us,meta_unlink_lat_us,meta_rename_lat_us,meta_readdir_lat_us,meta_fsync_lat_us")?;
         } else if per_worker {
-            writeln!(file, "timestamp,elapsed_sec,worker_id,read_ops,write_ops,total_ops,read_iops,write_iops,total_iops,read_mbps,write_mbps,total_mbps,read_latency_us,write_latency_us,cpu_percent_total,cpu_percent_per_worker,cpu_percent_system,memory_mb,metadata_open,metadata_close,metadata_stat,metadata_setattr,metadata_mkdir,metadata_rmdir,metadata_unlink,metadata_rename,metadata_readdir,metadata_fsync,meta_open_lat_us,meta_close_lat_us,meta_stat_lat_us,meta_setattr_lat_us,meta_mkdir_lat_us,meta_rmdir_lat_us,meta_unlink_lat_us,meta_rename_lat_us,meta_readdir_lat_us,meta_fsync_lat_us")?;
+            write!(file, "timestamp,elapsed_sec,worker_id,read_ops,write_ops,total_ops,read_iops,write_iops,total_iops,read_mbps,write_mbps,total_mbps,read_latency_us,write_latency_us,cpu_percent_total,cpu_percent_per_worker,cpu_percent_system,memory_mb,metadata_open,metadata_close,metadata_stat,metadata_setattr,metadata_mkdir,metadata_rmdir,metadata_unlink,metadata_rename,metadata_readdir,metadata_fsync,meta_open_lat_us,meta_close_lat_us,meta_stat_lat_us,meta_setattr_lat_us,meta_mkdir_lat_us,meta_rmdir_lat_us,meta_unlink_lat_us,meta_rename_lat_us,meta_readdir_lat_us,meta_fsync_lat_us")?;
         } else {
-            writeln!(file, "timestamp,elapsed_sec,read_ops,write_ops,total_ops,read_iops,write_iops,total_iops,read_mbps,write_mbps,total_mbps,read_latency_us,write_latency_us,cpu_percent_total,cpu_percent_per_worker,cpu_percent_system,memory_mb,metadata_ops,meta_open_lat_us,meta_close_lat_us,meta_stat_lat_us,meta_setattr_lat_us,meta_mkdir_lat_us,meta_rmdir_lat_us,meta_unlink_lat_us,meta_rename_lat_us,meta_readdir_lat_us,meta_fsync_lat_us")?;
+            write!(file, "timestamp,elapsed_sec,read_ops,write_ops,total_ops,read_iops,write_iops,total_iops,read_mbps,write_mbps,total_mbps,read_latency_us,write_latency_us,cpu_percent_total,cpu_percent_per_worker,cpu_percent_system,memory_mb,metadata_ops,meta_open_lat_us,meta_close_lat_us,meta_stat_lat_us,meta_setattr_lat_us,meta_mkdir_lat_us,meta_rmdir_lat_us,meta_unlink_lat_us,meta_rename_lat_us,meta_readdir_lat_us,meta_fsync_lat_us")?;
+        }
+        for p in percentiles {
+            write!(file, ",lat_p{}_us", p)?;
+        }
+        if run_uuid.is_some() {
+            write!(file, ",run_uuid")?;
         }
+        for key in labels.keys() {
+            write!(file, ",label_{}", key)?;
+        }
+        writeln!(file)?;
         
-        Ok(Self { file, per_worker, per_node })
+        let labels = run_uuid.as_deref().into_iter().chain(labels.values().map(String::as_str))
+            .map(|value| format!(",{}", csv_field(value)))
+            .collect();
+        Ok(Self { file, per_worker, per_node, percentiles: percentiles.clone(), labels })
     }
     
     /// Append a snapshot to the CSV file
@@ -116,7 +161,7 @@ impl CsvWriter {
                 0.0
             };
             
-            writeln!(
+            write!(
                 self.file,
                 "{},{:.3},Aggregate,{},{},{},{:.1},{:.1},{:.1},{:.2},{:.2},{:.2},{:.1},{:.1},{:.1},{:.1},{:.1},{:.2},{},{},{},{},{},{},{},{},{},{},{:.1},{:.1},{:.1},{:.1},{:.1},{:.1},{:.1},{:.1},{:.1},{:.1}",
                 timestamp,
@@ -157,6 +202,7 @@ impl CsvWriter {
                 if snapshot.metadata_readdir_latency.len() > 0 { snapshot.metadata_readdir_latency.mean().as_micros() as f64 } else { 0.0 },
                 if snapshot.metadata_fsync_latency.len() > 0 { snapshot.metadata_fsync_latency.mean().as_micros() as f64 } else { 0.0 },
             )?;
+            self.end_row(&snapshot.read_latency, &snapshot.write_latency)?;
             
             // Then write one row per worker
             if let Some(ref workers) = snapshot.per_worker {
@@ -196,7 +242,7 @@ impl CsvWriter {
                         0.0
                     };
                     
-                    writeln!(
+                    write!(
                         self.file,
                         "{},{:.3},{},{},{},{},{:.1},{:.1},{:.1},{:.2},{:.2},{:.2},{:.1},{:.1},{:.1},{:.2},{},{},{},{},{},{},{},{},{},{},{:.1},{:.1},{:.1},{:.1},{:.1},{:.1},{:.1},{:.1},{:.1},{:.1}",
                         timestamp,
@@ -236,6 +282,7 @@ impl CsvWriter {
                         if worker.metadata_readdir_latency.len() > 0 { worker.metadata_readdir_latency.mean().as_micros() as f64 } else { 0.0 },
                         if worker.metadata_fsync_latency.len() > 0 { worker.metadata_fsync_latency.mean().as_micros() as f64 } else { 0.0 },
                     )?;
+                    self.end_row(&worker.read_latency, &worker.write_latency)?;
                 }
             }
         } else {
@@ -282,7 +329,7 @@ impl CsvWriter {
                 0.0
             };
             
-            writeln!(
+            write!(
                 self.file,
                 "{},{:.3},{},{},{},{:.1},{:.1},{:.1},{:.2},{:.2},{:.2},{:.1},{:.1},{:.1},{:.1},{:.1},{:.2},{},{:.1},{:.1},{:.1},{:.1},{:.1},{:.1},{:.1},{:.1},{:.1},{:.1}",
                 timestamp,
@@ -314,6 +361,7 @@ impl CsvWriter {
                 if snapshot.metadata_readdir_latency.len() > 0 { snapshot.metadata_readdir_latency.mean().as_micros() as f64 } else { 0.0 },
                 if snapshot.metadata_fsync_latency.len() > 0 { snapshot.metadata_fsync_latency.mean().as_micros() as f64 } else { 0.0 },
             )?;
+            self.end_row(&snapshot.read_latency, &snapshot.write_latency)?;
         }
         
         // Flush to ensure data is written
@@ -403,7 +451,7 @@ impl CsvWriter {
         };
         
         // Write row with node_id
-        writeln!(
+        write!(
             self.file,
             "{},{:.3},{},{},{},{},{:.1},{:.1},{:.1},{:.2},{:.2},{:.2},{:.1},{:.1},{:.1},{:.1},{:.1},{:.2},{},{},{},{},{},{},{},{},{},{},{:.1},{:.1},{:.1},{:.1},{:.1},{:.1},{:.1},{:.1},{:.1},{:.1}",
             timestamp,
@@ -445,6 +493,7 @@ impl CsvWriter {
             if snapshot.metadata_readdir_latency.len() > 0 { snapshot.metadata_readdir_latency.mean().as_micros() as f64 } else { 0.0 },
             if snapshot.metadata_fsync_latency.len() > 0 { snapshot.metadata_fsync_latency.mean().as_micros() as f64 } else { 0.0 },
         )?;
+        self.end_row(&snapshot.read_latency, &snapshot.write_latency)?;
         
         // Write per-worker rows if enabled
         if self.per_worker {
@@ -485,7 +534,7 @@ impl CsvWriter {
                         0.0
                     };
                     
-                    writeln!(
+                    write!(
                         self.file,
                         "{},{:.3},{},{},{},{},{},{:.1},{:.1},{:.1},{:.2},{:.2},{:.2},{:.1},{:.1},{:.1},{:.2},{},{},{},{},{},{},{},{},{},{},{:.1},{:.1},{:.1},{:.1},{:.1},{:.1},{:.1},{:.1},{:.1},{:.1}",
                         timestamp,
@@ -526,6 +575,7 @@ impl CsvWriter {
                         if worker.metadata_readdir_latency.len() > 0 { worker.metadata_readdir_latency.mean().as_micros() as f64 } else { 0.0 },
                         if worker.metadata_fsync_latency.len() > 0 { worker.metadata_fsync_latency.mean().as_micros() as f64 } else { 0.0 },
                     )?;
+                    self.end_row(&worker.read_latency, &worker.write_latency)?;
                 }
             }
         }
@@ -535,6 +585,35 @@ impl CsvWriter {
         
         Ok(())
     }
+    
+    /// Size of the CSV file written so far
+    pub fn bytes_written(&self) -> Result<u64> {
+        Ok(self.file.metadata()?.len())
+    }
+    
+    /// Finish a row: configured percentile and label columns, then the line ending
+    fn end_row(&mut self, read_latency: &SimpleHistogram, write_latency: &SimpleHistogram) -> Result<()> {
+        if !self.percentiles.is_empty() {
+            let mut hist = read_latency.clone();
+            hist.merge(write_latency);
+            for &p in &self.percentiles {
+                let us = if hist.is_empty() { 0.0 } else { hist.percentile(p).as_micros() as f64 };
+                write!(self.file, ",{:.1}", us)?;
+            }
+        }
+        write!(self.file, "{}", self.labels)?;
+        writeln!(self.file)?;
+        Ok(())
+    }
+}
+
+/// Quote a free-text field if it contains a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
 }
 
 /// Format timestamp for CSV (ISO 8601)
@@ -564,8 +643,11 @@ fn format_timestamp_csv(time: std::time::SystemTime) -> String {
     let month = (day_of_year / 30) + 1;
     let day = (day_of_year % 30) + 1;
     
+    // Milliseconds, so sub-second live intervals get distinct timestamps
+    let millis = duration_since_epoch.subsec_millis();
+    
     format!(
-        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
-        year, month, day, hours, minutes, seconds
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year, month, day, hours, minutes, seconds, millis
     )
 }
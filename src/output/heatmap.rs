@@ -0,0 +1,181 @@
+//! Heatmap export (`--heatmap-output`)
+//!
+//! Writes the block access heatmap as raw bucket counts for external analysis,
+//! in the format given by the file extension. Buckets are the
+//! `--heatmap-buckets` equal ranges of the target (byte offsets, end
+//! exclusive). With `--heatmap-series` the accesses of every live interval
+//! follow, one count per bucket.
+//!
+//! CSV has one row per bucket and one column per interval, headed by the
+//! elapsed time at the interval's end:
+//!
+//! ```text
+//! bucket,start_offset,end_offset,ops,ops_1.000s,ops_2.000s
+//! 0,0,10485760,5120,2600,2520
+//! ```
+//!
+//! JSON holds the same data as `buckets` and `intervals`.
+
+use crate::stats::WorkerStats;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::io::Write;
+use std::path::Path;
+
+/// Export file format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeatmapFormat {
+    Csv,
+    Json,
+}
+
+impl HeatmapFormat {
+    /// Format given by the extension of `path` (.csv or .json)
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "csv" => Some(Self::Csv),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+/// Accesses of one bucket over the whole run
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HeatmapBucket {
+    pub bucket: usize,
+    pub start_offset: u64,
+    pub end_offset: u64,
+    pub ops: u64,
+}
+
+/// Accesses per bucket in one interval
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HeatmapInterval {
+    /// Seconds since the start of IO at the end of the interval
+    pub elapsed_secs: f64,
+    pub ops: Vec<u64>,
+}
+
+/// Heatmap as written to `--heatmap-output`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HeatmapExport {
+    pub block_size: u64,
+    pub blocks_per_bucket: u64,
+    pub buckets: Vec<HeatmapBucket>,
+    /// Interval length (only with --heatmap-series)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interval_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub intervals: Vec<HeatmapInterval>,
+}
+
+impl HeatmapExport {
+    /// Bucket the heatmap of `stats` over `total_blocks` blocks
+    ///
+    /// Without a known target size, the buckets of the per-interval series (or
+    /// else the highest block accessed) set the extent. Returns None if the
+    /// heatmap is not enabled.
+    pub fn from_stats(stats: &WorkerStats, num_buckets: usize, total_blocks: Option<u64>, block_size: u64) -> Option<Self> {
+        let series = stats.heatmap_series();
+        let total_blocks = match (series, total_blocks) {
+            // Keep the bucket edges the workers counted the intervals in
+            (Some(series), _) => series.blocks_per_bucket * num_buckets.max(1) as u64,
+            (None, Some(blocks)) => blocks,
+            (None, None) => stats.get_heatmap()?.last()
+                .map_or(0, |&(block, _)| block + 1),
+        };
+        let (blocks_per_bucket, counts) = stats.heatmap_buckets(num_buckets, total_blocks)?;
+        let buckets = counts.into_iter().enumerate()
+            .map(|(bucket, ops)| {
+                let start = bucket as u64 * blocks_per_bucket;
+                let end = (start + blocks_per_bucket).min(total_blocks.max(start));
+                HeatmapBucket { bucket, start_offset: start * block_size, end_offset: end * block_size, ops }
+            })
+            .collect();
+
+        let duration = stats.test_duration().map(|d| d.as_secs_f64());
+        let intervals = series.map(|series| {
+            series.intervals.iter().enumerate()
+                .map(|(i, ops)| {
+                    let end = (i + 1) as f64 * series.interval_ms as f64 / 1000.0;
+                    HeatmapInterval {
+                        elapsed_secs: duration.map_or(end, |duration| end.min(duration)),
+                        ops: ops.clone(),
+                    }
+                })
+                .collect()
+        }).unwrap_or_default();
+
+        Some(Self {
+            block_size,
+            blocks_per_bucket,
+            buckets,
+            interval_ms: series.map(|series| series.interval_ms),
+            intervals,
+        })
+    }
+
+    /// Write as CSV: one row per bucket, one column per interval
+    pub fn write_csv(&self, out: &mut impl Write) -> std::io::Result<()> {
+        write!(out, "bucket,start_offset,end_offset,ops")?;
+        for interval in &self.intervals {
+            write!(out, ",ops_{:.3}s", interval.elapsed_secs)?;
+        }
+        writeln!(out)?;
+        for bucket in &self.buckets {
+            write!(out, "{},{},{},{}", bucket.bucket, bucket.start_offset, bucket.end_offset, bucket.ops)?;
+            for interval in &self.intervals {
+                write!(out, ",{}", interval.ops.get(bucket.bucket).copied().unwrap_or(0))?;
+            }
+            writeln!(out)?;
+        }
+        Ok(())
+    }
+
+    /// Write to `path` in the format of its extension
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let format = HeatmapFormat::from_path(path)
+            .with_context(|| format!("Heatmap output must end in .csv or .json: {}", path.display()))?;
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let file = std::fs::File::create(path)
+            .with_context(|| format!("Failed to create heatmap output {}", path.display()))?;
+        let mut out = std::io::BufWriter::new(file);
+        match format {
+            HeatmapFormat::Csv => self.write_csv(&mut out)?,
+            HeatmapFormat::Json => serde_json::to_writer_pretty(&mut out, self)?,
+        }
+        out.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_csv() {
+        let stats = WorkerStats::with_heatmap(false, true);
+        for block in [0, 1, 2, 9, 9] {
+            stats.record_block_access(block);
+        }
+        let export = HeatmapExport::from_stats(&stats, 4, Some(10), 4096).unwrap();
+        assert_eq!(export.blocks_per_bucket, 3);
+        let ops: Vec<u64> = export.buckets.iter().map(|b| b.ops).collect();
+        assert_eq!(ops, [3, 0, 0, 2]);
+        assert_eq!(export.buckets[3].end_offset, 10 * 4096);
+
+        let mut csv = Vec::new();
+        export.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert_eq!(csv.lines().next(), Some("bucket,start_offset,end_offset,ops"));
+        assert_eq!(csv.lines().nth(4), Some("3,36864,40960,2"));
+
+        assert_eq!(HeatmapFormat::from_path(Path::new("heat.JSON")), Some(HeatmapFormat::Json));
+        assert_eq!(HeatmapFormat::from_path(Path::new("heat.txt")), None);
+    }
+}
@@ -11,11 +11,16 @@
 
 use crate::stats::{WorkerStats, MetadataStats};
 use crate::stats::simple_histogram::SimpleHistogram;
-use crate::util::resource::ResourceStats;
+use crate::stats::submit_gaps::SubmitGaps;
+use crate::util::resource::{PageFaults, ResourceStats};
+use crate::util::capabilities::NodeCapabilities;
 use serde::{Serialize, Deserialize};
+use std::collections::BTreeMap;
 use std::time::Duration;
 use std::path::Path;
 use std::fs::File;
+use crate::config::IntervalLatency;
+use crate::config::workload::EngineType;
 use crate::Result;
 
 /// Duration with both microseconds and human-readable format
@@ -65,6 +70,15 @@ pub struct JsonLatency {
     pub p99: Option<JsonDuration>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub p99_9: Option<JsonDuration>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub percentiles: Vec<JsonPercentile>,  // Only with --percentiles (final summary)
+}
+
+/// One latency percentile from the `--percentiles` list
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonPercentile {
+    pub percentile: f64,
+    pub latency: JsonDuration,
 }
 
 /// Metadata operation latency statistics
@@ -136,6 +150,9 @@ pub struct JsonCoverage {
     pub total_blocks: u64,
     pub coverage_percent: f64,
     pub rewrite_percent: f64,
+    /// Unique blocks were sampled under --stats-mem-limit
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub estimated: bool,
 }
 
 /// Aggregate statistics for a time interval
@@ -169,6 +186,71 @@ pub struct JsonAggregateStats {
     pub block_size_verification: Option<JsonBlockSizeVerification>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub queue_depth_stats: Option<JsonQueueDepthStats>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page_faults: Option<JsonPageFaults>,  // mmap engine only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub submit_pacing: Option<JsonSubmitPacing>,  // think time only
+}
+
+/// Page fault statistics (mmap engine only)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonPageFaults {
+    pub minor: u64,
+    pub major: u64,
+    pub minor_per_sec: f64,
+    pub major_per_sec: f64,
+    pub minor_per_op: f64,
+    pub major_per_op: f64,
+}
+
+impl JsonPageFaults {
+    /// Compute rates from fault counts over a duration and op count
+    pub fn new(faults: PageFaults, duration: Duration, total_ops: u64) -> Self {
+        let secs = duration.as_secs_f64();
+        let per_sec = |n: u64| if secs > 0.0 { n as f64 / secs } else { 0.0 };
+        let per_op = |n: u64| if total_ops > 0 { n as f64 / total_ops as f64 } else { 0.0 };
+        Self {
+            minor: faults.minor,
+            major: faults.major,
+            minor_per_sec: per_sec(faults.minor),
+            major_per_sec: per_sec(faults.major),
+            minor_per_op: per_op(faults.minor),
+            major_per_op: per_op(faults.major),
+        }
+    }
+}
+
+/// Spacing of submissions (only with think time)
+///
+/// `gap_cv` is the coefficient of variation of the gaps between a worker's
+/// submissions: near 0 when think time is smoothed, well above 1 when it
+/// submits in bursts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonSubmitPacing {
+    pub gaps: u64,
+    pub mean_gap_us: f64,
+    pub gap_cv: f64,
+}
+
+impl JsonSubmitPacing {
+    pub fn new(gaps: &SubmitGaps) -> Self {
+        Self {
+            gaps: gaps.count,
+            mean_gap_us: gaps.mean_us(),
+            gap_cv: gaps.cv(),
+        }
+    }
+
+    /// Counters these figures came from (to merge nodes)
+    fn counters(&self) -> SubmitGaps {
+        let sum_us = self.mean_gap_us * self.gaps as f64;
+        let stddev = self.gap_cv * self.mean_gap_us;
+        SubmitGaps {
+            count: self.gaps,
+            sum_us,
+            sum_sq_us: self.gaps as f64 * (stddev * stddev + self.mean_gap_us * self.mean_gap_us),
+        }
+    }
 }
 
 /// Queue depth utilization statistics (for async engines)
@@ -186,6 +268,21 @@ pub struct JsonBlockSizeVerification {
     pub min_bytes: u64,
     pub max_bytes: u64,
     pub configured_block_size: u64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sizes: Vec<JsonIoSize>,  // Completed transfers by exact size
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub other_sizes_ops: Option<u64>,  // Transfers beyond the distinct-size limit
+}
+
+/// Number of completed transfers of one size
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonIoSize {
+    pub bytes: u64,
+    pub ops: u64,
+    #[serde(default)]
+    pub read_ops: u64,
+    #[serde(default)]
+    pub write_ops: u64,
 }
 
 /// Per-worker statistics (simplified for time-series)
@@ -211,11 +308,100 @@ pub struct JsonWorkerStats {
 pub struct JsonWorkerStatsFinal {
     pub node_id: String,  // Which node this worker is on
     pub worker_id: usize,  // Worker ID within that node
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uid: Option<String>,  // <run>.n<node>.w<worker>
     pub read_ops: u64,
     pub write_ops: u64,
     pub read_bytes: u64,
     pub write_bytes: u64,
     pub latency: JsonLatency,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read_latency: Option<JsonLatency>,  // Only if the worker issued reads
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub write_latency: Option<JsonLatency>,  // Only if the worker issued writes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration: Option<JsonDuration>,  // This worker's own IO time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_time: Option<JsonCpuTime>,  // Only with --cpu-accounting
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset_range: Option<JsonOffsetRange>,  // Only in partitioned single-file mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub io_window: Option<JsonIoWindow>,  // When this worker started and stopped issuing IO
+}
+
+/// Wall-clock window of one worker's IO
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonIoWindow {
+    pub start_unix_ns: u64,
+    pub stop_unix_ns: u64,
+}
+
+/// Byte range of a file assigned to one worker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonOffsetRange {
+    pub start: u64,
+    pub end: u64,
+    pub bytes: u64,
+}
+
+/// Per-node final statistics (aggregate output only)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonNodeStatsFinal {
+    pub node_id: String,  // Node IP (matches per-worker and time-series node_id)
+    pub hostname: String,  // Node-reported identifier
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uid: Option<String>,  // <run>.n<node>
+    pub duration: JsonDuration,
+    pub total_ops: u64,
+    pub total_iops: u64,
+    pub total_throughput: JsonThroughput,
+    pub p99_latency: JsonDuration,
+    pub errors: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_percent_total: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub space_consumed_bytes: Option<i64>,  // Only with --space-usage
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub space_amplification: Option<f64>,  // Only with --space-usage
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capabilities: Option<NodeCapabilities>,  // Reported by the node at READY
+}
+
+/// Final statistics of one node role (role-based node groups)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonRoleStatsFinal {
+    pub role: String,
+    pub nodes: Vec<String>,  // Node IPs (match per_node node_id)
+    pub duration: JsonDuration,
+    pub stats: JsonAggregateStats,
+}
+
+/// Statistics of one --summary-interval checkpoint (all nodes)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonCheckpoint {
+    pub number: usize,
+    pub timestamp: String,
+    pub start: JsonDuration,
+    pub end: JsonDuration,
+    pub read_ops: u64,
+    pub write_ops: u64,
+    pub read_iops: f64,
+    pub write_iops: f64,
+    pub read_throughput: f64,  // Bytes per second
+    pub write_throughput: f64,  // Bytes per second
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read_latency: Option<JsonLatency>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub write_latency: Option<JsonLatency>,
+}
+
+/// One node's interval record, streamed as a JSON line while the test runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonIntervalRecord {
+    pub timestamp: String,
+    pub elapsed: JsonDuration,
+    pub node_id: String,
+    pub stats: JsonAggregateStats,
 }
 
 /// Per-node time-series statistics
@@ -242,7 +428,12 @@ pub struct JsonTestConfig {
     pub threads: usize,
     pub block_size: usize,
     pub file_size: u64,
+    /// Engine requested with --engine
     pub engine: String,
+    /// Engine the workers ran: sync at queue depth 1 (without --no-engine-fallback),
+    /// threadpool where io_uring or libaio is unavailable; comma-separated if workers differed
+    #[serde(default)]
+    pub effective_engine: String,
     pub queue_depth: usize,
     pub read_percent: u32,
     pub write_percent: u32,
@@ -254,11 +445,52 @@ pub struct JsonTestConfig {
     pub pareto_h: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub gaussian_stddev: Option<f64>,
+    /// Movement of the Gaussian center (--gaussian-drift), e.g. "linear:0.01"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gaussian_drift: Option<String>,
+    /// Access pattern per worker group (worker_groups), e.g. "6 x random uniform"
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub worker_groups: Vec<String>,
+    /// Percentage of O_DIRECT operations issued through a buffered fd (--buffered-percent)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub buffered_percent: Option<u8>,
+    /// Latencies were recorded for 1 in this many operations (--latency-sample)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_sample: Option<u64>,
+    /// Byte offset sequential streams started at (--offset-start, resolved if random)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset_start: Option<u64>,
+    /// Chunk permutation of the offsets (--remap-offsets), e.g. "seed 7"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remap_offsets: Option<String>,
+    /// What time-series latency covers: "interval" or "cumulative" (--interval-latency)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interval_latency: Option<String>,
+}
+
+impl JsonTestConfig {
+    /// Name the engines the workers ran, once their results recorded them
+    pub fn set_engines_run(&mut self, engines: &[EngineType]) {
+        if !engines.is_empty() {
+            self.effective_engine = engines.iter().map(|&engine| engine_name(engine)).collect::<Vec<_>>().join(",");
+        }
+    }
+}
+
+/// Engine name as the JSON output spells it
+fn engine_name(engine: EngineType) -> String {
+    format!("{:?}", engine).to_lowercase()
 }
 
 /// Test information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JsonTestInfo {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub job_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_uuid: Option<String>,  // Shared by every artifact of the run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub node_uid: Option<String>,  // Only in per-node output
     pub node_id: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub hostname: Option<String>,
@@ -268,6 +500,33 @@ pub struct JsonTestInfo {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub duration: Option<JsonDuration>,
     pub config: JsonTestConfig,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<String, String>,  // --label key=value
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<JsonOrigin>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capabilities: Option<NodeCapabilities>,  // Only in per-node output
+}
+
+/// Binary and host that produced the results
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonOrigin {
+    pub iopulse_version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_sha: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+}
+
+impl JsonOrigin {
+    /// Describe this binary and the local host
+    pub fn current() -> Self {
+        Self {
+            iopulse_version: env!("CARGO_PKG_VERSION").to_string(),
+            git_sha: crate::GIT_SHA.map(str::to_string),
+            hostname: hostname::get().ok().map(|h| h.to_string_lossy().into_owned()),
+        }
+    }
 }
 
 /// Complete per-node JSON output
@@ -278,12 +537,554 @@ pub struct JsonNodeOutput {
     pub final_summary: JsonFinalSummary,
 }
 
+impl JsonNodeOutput {
+    /// Fill in the node and worker IDs derived from the run UUID
+    ///
+    /// `node` is the node a per-node output covers. For aggregate output,
+    /// `nodes` gives the node index of each `per_node` entry and
+    /// `worker_nodes` that of each `per_worker` entry.
+    pub fn set_unit_ids(&mut self, node: Option<usize>, nodes: &[usize], worker_nodes: &[usize]) {
+        use crate::util::trace_id::{node_uid, worker_uid};
+        let Some(ref run_uuid) = self.test_info.run_uuid else { return };
+        self.test_info.node_uid = node.map(|node| node_uid(run_uuid, node));
+        for (entry, &index) in self.final_summary.per_node.iter_mut().zip(nodes) {
+            entry.uid = Some(node_uid(run_uuid, index));
+        }
+        for (i, entry) in self.final_summary.per_worker.iter_mut().enumerate() {
+            if let Some(index) = node.or(worker_nodes.get(i).copied()) {
+                entry.uid = Some(worker_uid(&node_uid(run_uuid, index), entry.worker_id));
+            }
+        }
+    }
+    
+    /// Fill in the capabilities each node reported at READY
+    ///
+    /// `capabilities` is indexed by node; `node` and `nodes` are as for
+    /// [`set_unit_ids`](Self::set_unit_ids).
+    pub fn set_capabilities(&mut self, node: Option<usize>, nodes: &[usize], capabilities: &[Option<NodeCapabilities>]) {
+        let reported = |index: usize| capabilities.get(index).cloned().flatten();
+        self.test_info.capabilities = node.and_then(reported);
+        for (entry, &index) in self.final_summary.per_node.iter_mut().zip(nodes) {
+            entry.capabilities = reported(index);
+        }
+    }
+}
+
 /// Final summary statistics
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JsonFinalSummary {
     pub total_duration: JsonDuration,
     pub aggregate: JsonAggregateStats,
     pub per_worker: Vec<JsonWorkerStatsFinal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub qd_ramp: Option<crate::worker::qd_ramp::QdRampResult>,  // Only with --qd-ramp
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub setup_phases: Vec<JsonSetupPhase>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grow: Option<JsonGrowStats>,  // Only with --grow-to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_hints: Option<JsonFileHints>,  // Only for fadvise/madvise in file-list mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_contention: Option<JsonFileContention>,  // Only when shared file-list picks collided
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slowest_files: Option<JsonSlowestFiles>,  // Only with --file-latency-top
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub osts: Option<JsonOstStats>,  // Only with --ost-stats
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_time: Option<JsonCpuTime>,  // Only with --cpu-accounting
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timer: Option<JsonTimer>,  // Precision of the latency timer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fragmentation: Option<JsonFragmentation>,  // Only with --fragmentation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub per_worker_rate_sum: Option<JsonRateSum>,  // Each worker over its own duration
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub per_node: Vec<JsonNodeStatsFinal>,  // Only in aggregate output
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub per_role: Vec<JsonRoleStatsFinal>,  // Only with node roles
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thread_scaling: Option<crate::distributed::thread_scaling::ThreadScalingResult>,  // Only with --threads auto
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_target: Option<crate::distributed::latency_target::LatencyTargetResult>,  // Only with --target-p99
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub checkpoints: Vec<JsonCheckpoint>,  // Only with --summary-interval
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_stop_skew: Option<JsonStartStopSkew>,  // Only in aggregate output
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub drained: Option<JsonDrained>,  // Only with --drain-latency exclude/separate
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub io_paths: Option<JsonIoPaths>,  // Only with --buffered-percent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rated: Option<JsonRated>,  // Only with --rated-iops/--rated-throughput
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub space_usage: Option<JsonSpaceUsage>,  // Only with --space-usage
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page_cache_warning: Option<crate::output::page_cache::PageCacheWarning>,  // Only for cache-dominated buffered reads
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eof_clamped_reads: Option<u64>,  // Only when reads crossed EOF
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub queue_depth_limit: Option<crate::worker::queue_limit::QueueDepthLimit>,  // Only when the queue depth was reduced
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_floor: Option<JsonLatencyFloor>,  // Only with --latency-floor
+}
+
+/// How raggedly workers started and stopped issuing IO (all nodes)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonStartStopSkew {
+    pub workers: usize,
+    pub start_skew: JsonDuration,
+    pub stop_skew: JsonDuration,
+    pub span: JsonDuration,  // Earliest start to latest stop
+    pub overlap: JsonDuration,  // Time every worker was issuing IO
+    pub overlap_percent: f64,
+    pub per_node: Vec<JsonNodeSkew>,
+}
+
+/// Start/stop offsets of one node's workers, from the earliest start in the run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonNodeSkew {
+    pub node_id: String,
+    pub workers: usize,
+    pub first_start: JsonDuration,
+    pub last_start: JsonDuration,
+    pub first_stop: JsonDuration,
+    pub last_stop: JsonDuration,
+}
+
+/// Sum of each worker's rate over its own duration
+///
+/// `aggregate` rates divide by the longest worker's duration; this sum does not
+/// under-report workers that finished earlier.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonRateSum {
+    pub read_iops: u64,
+    pub write_iops: u64,
+    pub total_iops: u64,
+    pub throughput: JsonThroughput,
+}
+
+impl From<crate::stats::rates::WorkerRates> for JsonRateSum {
+    fn from(rates: crate::stats::rates::WorkerRates) -> Self {
+        Self {
+            read_iops: rates.read_iops as u64,
+            write_iops: rates.write_iops as u64,
+            total_iops: rates.total_iops() as u64,
+            throughput: JsonThroughput::new(rates.total_bps() as u64),
+        }
+    }
+}
+
+/// Target file fragmentation after the test
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonFragmentation {
+    pub files: u64,
+    pub extents: u64,
+    pub extents_per_file: f64,
+    pub max_extents: u64,
+    pub avg_extent_bytes: u64,
+    pub mapped_bytes: u64,
+    pub unsupported_files: u64,
+}
+
+impl From<crate::util::fragmentation::FragmentationReport> for JsonFragmentation {
+    fn from(report: crate::util::fragmentation::FragmentationReport) -> Self {
+        Self {
+            files: report.files,
+            extents: report.extents,
+            extents_per_file: report.extents_per_file(),
+            max_extents: report.max_extents,
+            avg_extent_bytes: report.avg_extent_size(),
+            mapped_bytes: report.mapped_bytes,
+            unsupported_files: report.unsupported,
+        }
+    }
+}
+
+/// Append-grow breakdown: extending writes vs overwrites
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonGrowStats {
+    pub extend: JsonGrowWrites,
+    pub overwrite: JsonGrowWrites,
+    pub final_size_bytes: u64,
+    pub target_reached_secs: Option<f64>,
+}
+
+/// One class of append-grow writes; IOPS is over the phase the writes ran in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonGrowWrites {
+    pub ops: u64,
+    pub bytes: u64,
+    pub iops: f64,
+    pub latency: JsonLatency,
+}
+
+/// Convert append-grow stats to JSON (growth phase ends when the target is reached)
+fn grow_to_json(stats: &WorkerStats, duration: Duration) -> Option<JsonGrowStats> {
+    let grow = stats.grow_stats()?;
+    let grow_secs = grow.target_reached_secs.unwrap_or(duration.as_secs_f64());
+    let overwrite_secs = duration.as_secs_f64() - grow_secs;
+    let writes = |w: &crate::stats::grow::GrowWriteStats, secs: f64| JsonGrowWrites {
+        ops: w.ops,
+        bytes: w.bytes,
+        iops: if secs > 0.0 { w.ops as f64 / secs } else { 0.0 },
+        latency: extract_latency_from_histogram(&w.latency),
+    };
+    Some(JsonGrowStats {
+        extend: writes(&grow.extend, grow_secs),
+        overwrite: writes(&grow.overwrite, overwrite_secs),
+        final_size_bytes: grow.final_size,
+        target_reached_secs: grow.target_reached_secs,
+    })
+}
+
+/// Operations that completed after the duration expired, left out of the results
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonDrained {
+    pub ops: u64,
+    pub bytes: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency: Option<JsonLatency>,  // Only with --drain-latency separate
+}
+
+/// Convert drained operations to JSON
+fn drained_to_json(stats: &WorkerStats) -> Option<JsonDrained> {
+    let drained = stats.drained()?;
+    Some(JsonDrained {
+        ops: drained.ops,
+        bytes: drained.bytes,
+        latency: drained.latency.as_ref().map(extract_latency_from_histogram),
+    })
+}
+
+/// NOP round trips through the engine's ring, measured before the test
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonLatencyFloor {
+    pub engine: String,
+    pub nops: u64,
+    pub min_ns: u64,  // Nanoseconds: round trips are often under a microsecond
+    pub mean_ns: u64,
+    pub p50_ns: u64,
+    pub p99_ns: u64,
+    pub max_ns: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub percent_of_io_p50: Option<f64>,  // Median round trip against the median IO latency
+}
+
+/// Convert the latency floor to JSON
+fn latency_floor_to_json(stats: &WorkerStats) -> Option<JsonLatencyFloor> {
+    let floor = stats.latency_floor()?;
+    let round_trips = &floor.round_trips;
+    Some(JsonLatencyFloor {
+        engine: engine_name(floor.engine),
+        nops: round_trips.len(),
+        min_ns: round_trips.min().as_nanos() as u64,
+        mean_ns: round_trips.mean().as_nanos() as u64,
+        p50_ns: round_trips.percentile(50.0).as_nanos() as u64,
+        p99_ns: round_trips.percentile(99.0).as_nanos() as u64,
+        max_ns: round_trips.max().as_nanos() as u64,
+        percent_of_io_p50: floor.percent_of_median(stats.io_latency()),
+    })
+}
+
+/// O_DIRECT vs buffered breakdown of the IO
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonIoPaths {
+    pub direct: JsonIoPath,
+    pub buffered: JsonIoPath,
+}
+
+/// Operations of one IO path; IOPS is over the whole test
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonIoPath {
+    pub read_ops: u64,
+    pub write_ops: u64,
+    pub bytes: u64,
+    pub iops: f64,
+    pub latency: JsonLatency,
+}
+
+/// Convert the O_DIRECT/buffered breakdown to JSON
+fn io_paths_to_json(stats: &WorkerStats, duration: Duration) -> Option<JsonIoPaths> {
+    let paths = stats.io_paths()?;
+    let secs = duration.as_secs_f64();
+    let path = |p: &crate::stats::io_path::PathStats| JsonIoPath {
+        read_ops: p.read_ops,
+        write_ops: p.write_ops,
+        bytes: p.bytes,
+        iops: if secs > 0.0 { p.ops() as f64 / secs } else { 0.0 },
+        latency: extract_latency_from_histogram(&p.latency),
+    };
+    Some(JsonIoPaths {
+        direct: path(&paths.direct),
+        buffered: path(&paths.buffered),
+    })
+}
+
+/// Achieved vs rated device performance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonRated {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iops: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iops_percent: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub throughput_bytes_per_sec: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub throughput_percent: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub throughput_source: Option<String>,  // "user" or the interface link
+}
+
+/// Compare the results with the device's ratings
+fn rated_to_json(config: &crate::config::Config, stats: &WorkerStats, duration: Duration) -> Option<JsonRated> {
+    let rated = config.output.rated.as_ref()?;
+    let throughput = rated.throughput.as_ref().and_then(|t| t.bytes_per_sec());
+    let iops = crate::util::time::calculate_iops(stats.total_ops(), duration);
+    let bytes_per_sec = crate::util::time::calculate_throughput(stats.total_bytes(), duration);
+    Some(JsonRated {
+        iops: rated.iops,
+        iops_percent: rated.iops.map(|rated| iops / rated as f64 * 100.0),
+        throughput_bytes_per_sec: throughput,
+        throughput_percent: throughput.map(|rated| bytes_per_sec / rated as f64 * 100.0),
+        throughput_source: rated.throughput.as_ref().map(|t| t.source().to_string()),
+    })
+}
+
+/// Filesystem space consumed vs bytes written
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonSpaceUsage {
+    pub filesystems: Vec<crate::util::space::FsSpace>,
+    pub consumed_bytes: i64,  // Negative if space was freed
+    pub bytes_written: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amplification: Option<f64>,  // Consumed per byte written
+}
+
+/// Convert the space readings to JSON (only with --space-usage)
+fn space_usage_to_json(stats: &WorkerStats) -> Option<JsonSpaceUsage> {
+    let usage = stats.space_usage()?;
+    Some(JsonSpaceUsage {
+        filesystems: usage.filesystems.clone(),
+        consumed_bytes: usage.consumed_bytes(),
+        bytes_written: stats.write_bytes(),
+        amplification: usage.amplification(stats.write_bytes()),
+    })
+}
+
+/// Time spent in a setup or teardown phase (outside the measured IO phase)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonSetupPhase {
+    pub name: String,
+    pub duration: JsonDuration,
+}
+
+/// fadvise/madvise calls on files opened during the test
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonFileHints {
+    pub applied: u64,
+    pub cached: u64,
+    pub duration: JsonDuration,
+}
+
+/// Convert hint stats to JSON (None when no file was advised during the test)
+fn file_hints_to_json(stats: &WorkerStats) -> Option<JsonFileHints> {
+    let hints = stats.hints();
+    if hints.is_empty() {
+        return None;
+    }
+    Some(JsonFileHints {
+        applied: hints.applied,
+        cached: hints.cached,
+        duration: JsonDuration::from_duration(hints.time()),
+    })
+}
+
+/// Busy-file collisions in shared file-list mode
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonFileContention {
+    pub collisions: u64,
+    pub retried: u64,
+    pub waited: u64,
+    pub proceeded: u64,
+    pub wait_duration: JsonDuration,
+}
+
+/// Convert busy-file collisions to JSON (None when no pick collided)
+fn file_contention_to_json(stats: &WorkerStats) -> Option<JsonFileContention> {
+    let contention = stats.contention();
+    if contention.is_empty() {
+        return None;
+    }
+    Some(JsonFileContention {
+        collisions: contention.collisions,
+        retried: contention.retried,
+        waited: contention.waited,
+        proceeded: contention.proceeded(),
+        wait_duration: JsonDuration::from_duration(contention.wait_time()),
+    })
+}
+
+/// Files or directories with the highest mean latency
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonSlowestFiles {
+    pub by_dir: bool,
+    pub tracked: usize,
+    pub entries: Vec<JsonFileLatency>,
+}
+
+/// Latency of one file or directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonFileLatency {
+    pub path: String,
+    pub ops: u64,
+    pub errors: u64,
+    pub mean: JsonDuration,
+    pub max: JsonDuration,
+}
+
+/// Convert per-file latency to JSON (None without --file-latency-top)
+fn slowest_files_to_json(stats: &WorkerStats) -> Option<JsonSlowestFiles> {
+    let files = stats.file_latency().filter(|f| !f.is_empty())?;
+    Some(JsonSlowestFiles {
+        by_dir: files.by_dir,
+        tracked: files.entries.len(),
+        entries: files.slowest().into_iter()
+            .map(|(path, latency)| JsonFileLatency {
+                path: path.to_string(),
+                ops: latency.ops,
+                errors: latency.errors,
+                mean: JsonDuration::from_duration(latency.mean()),
+                max: JsonDuration::from_duration(latency.max()),
+            })
+            .collect(),
+    })
+}
+
+/// Throughput and latency per Lustre OST
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonOstStats {
+    pub osts: Vec<JsonOst>,
+    pub unmapped_ops: u64,
+}
+
+/// Counts of one OST
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonOst {
+    pub ost: u32,
+    pub ops: u64,
+    pub bytes: u64,
+    pub throughput: JsonThroughput,
+    pub mean: JsonDuration,
+    pub max: JsonDuration,
+}
+
+/// Convert per-OST counts to JSON (None without --ost-stats)
+fn ost_stats_to_json(stats: &WorkerStats, duration: Duration) -> Option<JsonOstStats> {
+    let osts = stats.ost_stats();
+    if osts.is_empty() {
+        return None;
+    }
+    Some(JsonOstStats {
+        osts: osts.osts.iter()
+            .map(|(&ost, counters)| JsonOst {
+                ost,
+                ops: counters.ops,
+                bytes: counters.bytes,
+                throughput: JsonThroughput::new(crate::util::time::calculate_throughput(counters.bytes, duration) as u64),
+                mean: JsonDuration::from_duration(counters.mean()),
+                max: JsonDuration::from_duration(counters.max()),
+            })
+            .collect(),
+        unmapped_ops: osts.unmapped_ops,
+    })
+}
+
+/// Worker time split into CPU time and wait time
+///
+/// Times are summed over workers; percentages are of `wall`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonCpuTime {
+    pub wall: JsonDuration,
+    pub on_cpu: JsonDuration,
+    pub runqueue: JsonDuration,
+    pub blocked: JsonDuration,
+    pub on_cpu_percent: f64,
+    pub runqueue_percent: f64,
+    pub blocked_percent: f64,
+    pub calls: Vec<JsonCallTime>,
+    pub other_cpu: JsonDuration,  // CPU time outside engine calls (harness)
+}
+
+/// Time in one class of engine calls
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonCallTime {
+    pub call: String,  // read, write or reap
+    pub calls: u64,
+    pub wall: JsonDuration,
+    pub cpu: JsonDuration,
+    pub wait: JsonDuration,
+}
+
+/// Convert CPU time accounting to JSON (None without --cpu-accounting)
+fn cpu_time_to_json(stats: &WorkerStats) -> Option<JsonCpuTime> {
+    let acct = stats.cpu_time()?;
+    let ns = |ns: u64| JsonDuration::from_duration(Duration::from_nanos(ns));
+    Some(JsonCpuTime {
+        wall: ns(acct.wall_ns),
+        on_cpu: ns(acct.run_ns),
+        runqueue: ns(acct.runqueue_ns),
+        blocked: ns(acct.blocked_ns()),
+        on_cpu_percent: acct.percent(acct.run_ns),
+        runqueue_percent: acct.percent(acct.runqueue_ns),
+        blocked_percent: acct.percent(acct.blocked_ns()),
+        calls: acct.calls().iter()
+            .filter(|(_, call)| call.calls > 0)
+            .map(|(name, call)| JsonCallTime {
+                call: name.to_string(),
+                calls: call.calls,
+                wall: ns(call.wall_ns),
+                cpu: ns(call.cpu_ns),
+                wait: ns(call.wait_ns()),
+            })
+            .collect(),
+        other_cpu: ns(acct.other_cpu_ns()),
+    })
+}
+
+/// Precision of the latency timer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonTimer {
+    pub source: String,  // clock_gettime or tsc
+    pub resolution_ns: u64,
+    pub overhead_ns: u64,
+    pub noise_floor_ns: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tsc_hz: Option<u64>,  // Calibrated TSC frequency (--timer tsc)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,  // Median latency close to the noise floor
+}
+
+/// Convert the timer check to JSON
+fn timer_to_json(stats: &WorkerStats) -> Option<JsonTimer> {
+    let timer = stats.timer()?;
+    Some(JsonTimer {
+        source: if timer.tsc { "tsc" } else { "clock_gettime" }.to_string(),
+        resolution_ns: timer.resolution_ns,
+        overhead_ns: timer.overhead_ns,
+        noise_floor_ns: timer.noise_floor().as_nanos() as u64,
+        tsc_hz: timer.tsc.then_some(timer.tsc_hz),
+        warning: stats.timer_warning(),
+    })
+}
+
+/// Convert recorded setup phases to JSON
+fn setup_phases_to_json(stats: &WorkerStats) -> Vec<JsonSetupPhase> {
+    stats.setup_timings().phases().iter()
+        .map(|phase| JsonSetupPhase {
+            name: phase.name.clone(),
+            duration: JsonDuration::from_duration(phase.duration()),
+        })
+        .collect()
 }
 
 
@@ -335,7 +1136,7 @@ fn extract_latency(stats: &WorkerStats) -> JsonLatency {
 }
 
 /// Extract JsonLatency from a histogram
-fn extract_latency_from_histogram(hist: &crate::stats::simple_histogram::SimpleHistogram) -> JsonLatency {
+pub(crate) fn extract_latency_from_histogram(hist: &crate::stats::simple_histogram::SimpleHistogram) -> JsonLatency {
     JsonLatency {
         min: Some(JsonDuration::from_duration(hist.min())),
         max: Some(JsonDuration::from_duration(hist.max())),
@@ -345,7 +1146,30 @@ fn extract_latency_from_histogram(hist: &crate::stats::simple_histogram::SimpleH
         p95: Some(JsonDuration::from_duration(hist.percentile(95.0))),
         p99: Some(JsonDuration::from_duration(hist.percentile(99.0))),
         p99_9: Some(JsonDuration::from_duration(hist.percentile(99.9))),
+        percentiles: Vec::new(),
+    }
+}
+
+/// Convert a histogram to JsonLatency, adding the configured percentile list
+pub(crate) fn latency_with_percentiles(hist: &crate::stats::simple_histogram::SimpleHistogram, percentiles: &[f64]) -> JsonLatency {
+    let mut latency = extract_latency_from_histogram(hist);
+    latency.percentiles = percentiles.iter()
+        .map(|&p| JsonPercentile {
+            percentile: p,
+            latency: JsonDuration::from_duration(hist.percentile(p)),
+        })
+        .collect();
+    latency
+}
+
+/// Add the configured percentile list to the final aggregate latency blocks
+fn add_configured_percentiles(aggregate: &mut JsonAggregateStats, stats: &WorkerStats, percentiles: &[f64]) {
+    if percentiles.is_empty() {
+        return;
     }
+    aggregate.latency = Some(latency_with_percentiles(stats.io_latency(), percentiles));
+    aggregate.read_latency = latency_with_percentiles(stats.read_latency(), percentiles);
+    aggregate.write_latency = latency_with_percentiles(stats.write_latency(), percentiles);
 }
 
 /// Create JsonLatency with only mean (for time-series snapshots)
@@ -362,6 +1186,16 @@ fn latency_mean_only(mean_micros: f64) -> JsonLatency {
         p95: None,
         p99: None,
         p99_9: None,
+        percentiles: Vec::new(),
+    }
+}
+
+/// Latency of a time-series interval (mean only without operations)
+fn series_latency(hist: &SimpleHistogram) -> JsonLatency {
+    if hist.is_empty() {
+        latency_mean_only(0.0)
+    } else {
+        extract_latency_from_histogram(hist)
     }
 }
 
@@ -583,6 +1417,7 @@ pub fn stats_to_json_aggregate(
             total_blocks,
             coverage_percent: stats.coverage_percent(total_blocks),
             rewrite_percent: stats.rewrite_percent(),
+            estimated: stats.coverage_is_estimate(),
         })
     } else {
         None
@@ -593,6 +1428,13 @@ pub fn stats_to_json_aggregate(
             min_bytes: stats.min_bytes_per_op(),
             max_bytes: stats.max_bytes_per_op(),
             configured_block_size,
+            sizes: stats.io_sizes().sizes().iter()
+                .map(|&(bytes, ops)| {
+                    let write_ops = stats.write_io_sizes().ops(bytes);
+                    JsonIoSize { bytes, ops, read_ops: ops.saturating_sub(write_ops), write_ops }
+                })
+                .collect(),
+            other_sizes_ops: Some(stats.io_sizes().overflow()).filter(|&ops| ops > 0),
         })
     } else {
         None
@@ -643,6 +1485,9 @@ pub fn stats_to_json_aggregate(
         coverage,
         block_size_verification,
         queue_depth_stats,
+        page_faults: stats.page_faults()
+            .map(|faults| JsonPageFaults::new(faults, duration, read_ops + write_ops)),
+        submit_pacing: stats.submit_gaps().map(|gaps| JsonSubmitPacing::new(&gaps)),
     }
 }
 
@@ -784,11 +1629,130 @@ pub struct AggregatedSnapshot {
     pub metadata_readdir_latency: crate::stats::simple_histogram::SimpleHistogram,
     pub metadata_fsync_latency: crate::stats::simple_histogram::SimpleHistogram,
     
+    // Page faults (mmap engine only)
+    pub page_faults: Option<crate::util::resource::PageFaults>,
+    
+    // Gaps between submissions (only with think time)
+    pub submit_gaps: Option<crate::stats::submit_gaps::SubmitGaps>,
+    
     // Per-worker snapshots (optional, only when --json-per-worker is enabled)
     pub per_worker: Option<Vec<crate::worker::StatsSnapshot>>,
 }
 
 impl AggregatedSnapshot {
+    /// Interval between an earlier cumulative snapshot and this one
+    ///
+    /// Counters become deltas and errors stay cumulative. Latency histograms
+    /// hold the interval's operations with `IntervalLatency::Interval` and
+    /// stay cumulative with `IntervalLatency::Cumulative`.
+    pub fn since(&self, earlier: &AggregatedSnapshot, latency: IntervalLatency) -> Self {
+        let hist = |now: &SimpleHistogram, before: &SimpleHistogram| match latency {
+            IntervalLatency::Interval => now.since(before),
+            IntervalLatency::Cumulative => now.clone(),
+        };
+        let read_latency = hist(&self.read_latency, &earlier.read_latency);
+        let write_latency = hist(&self.write_latency, &earlier.write_latency);
+        let avg_latency_us = match latency {
+            IntervalLatency::Interval => mean_latency_us(&read_latency, &write_latency),
+            IntervalLatency::Cumulative => self.avg_latency_us,
+        };
+        Self {
+            timestamp: self.timestamp,
+            elapsed: self.elapsed,
+            read_ops: self.read_ops.saturating_sub(earlier.read_ops),
+            write_ops: self.write_ops.saturating_sub(earlier.write_ops),
+            read_bytes: self.read_bytes.saturating_sub(earlier.read_bytes),
+            write_bytes: self.write_bytes.saturating_sub(earlier.write_bytes),
+            errors: self.errors,
+            avg_latency_us,
+            read_latency,
+            write_latency,
+            metadata_open_ops: self.metadata_open_ops.saturating_sub(earlier.metadata_open_ops),
+            metadata_close_ops: self.metadata_close_ops.saturating_sub(earlier.metadata_close_ops),
+            metadata_stat_ops: self.metadata_stat_ops.saturating_sub(earlier.metadata_stat_ops),
+            metadata_setattr_ops: self.metadata_setattr_ops.saturating_sub(earlier.metadata_setattr_ops),
+            metadata_mkdir_ops: self.metadata_mkdir_ops.saturating_sub(earlier.metadata_mkdir_ops),
+            metadata_rmdir_ops: self.metadata_rmdir_ops.saturating_sub(earlier.metadata_rmdir_ops),
+            metadata_unlink_ops: self.metadata_unlink_ops.saturating_sub(earlier.metadata_unlink_ops),
+            metadata_rename_ops: self.metadata_rename_ops.saturating_sub(earlier.metadata_rename_ops),
+            metadata_readdir_ops: self.metadata_readdir_ops.saturating_sub(earlier.metadata_readdir_ops),
+            metadata_fsync_ops: self.metadata_fsync_ops.saturating_sub(earlier.metadata_fsync_ops),
+            metadata_open_latency: hist(&self.metadata_open_latency, &earlier.metadata_open_latency),
+            metadata_close_latency: hist(&self.metadata_close_latency, &earlier.metadata_close_latency),
+            metadata_stat_latency: hist(&self.metadata_stat_latency, &earlier.metadata_stat_latency),
+            metadata_setattr_latency: hist(&self.metadata_setattr_latency, &earlier.metadata_setattr_latency),
+            metadata_mkdir_latency: hist(&self.metadata_mkdir_latency, &earlier.metadata_mkdir_latency),
+            metadata_rmdir_latency: hist(&self.metadata_rmdir_latency, &earlier.metadata_rmdir_latency),
+            metadata_unlink_latency: hist(&self.metadata_unlink_latency, &earlier.metadata_unlink_latency),
+            metadata_rename_latency: hist(&self.metadata_rename_latency, &earlier.metadata_rename_latency),
+            metadata_readdir_latency: hist(&self.metadata_readdir_latency, &earlier.metadata_readdir_latency),
+            metadata_fsync_latency: hist(&self.metadata_fsync_latency, &earlier.metadata_fsync_latency),
+            page_faults: self.page_faults
+                .map(|c| c.since(&earlier.page_faults.unwrap_or_default())),
+            submit_gaps: self.submit_gaps
+                .map(|c| c.since(&earlier.submit_gaps.unwrap_or_default())),
+            per_worker: None,
+        }
+    }
+
+    /// Extend this interval with the one that follows it
+    ///
+    /// Counters are summed; errors are cumulative and taken from `later`.
+    /// Interval latency histograms are merged, cumulative ones taken from
+    /// `later`.
+    pub fn absorb(&mut self, later: AggregatedSnapshot, latency: IntervalLatency) {
+        let earlier = std::mem::replace(self, later);
+        if latency == IntervalLatency::Interval {
+            for (hist, earlier) in self.latency_histograms_mut().into_iter().zip(earlier.latency_histograms()) {
+                hist.merge(earlier);
+            }
+            self.avg_latency_us = mean_latency_us(&self.read_latency, &self.write_latency);
+        }
+        self.read_ops += earlier.read_ops;
+        self.write_ops += earlier.write_ops;
+        self.read_bytes += earlier.read_bytes;
+        self.write_bytes += earlier.write_bytes;
+        self.metadata_open_ops += earlier.metadata_open_ops;
+        self.metadata_close_ops += earlier.metadata_close_ops;
+        self.metadata_stat_ops += earlier.metadata_stat_ops;
+        self.metadata_setattr_ops += earlier.metadata_setattr_ops;
+        self.metadata_mkdir_ops += earlier.metadata_mkdir_ops;
+        self.metadata_rmdir_ops += earlier.metadata_rmdir_ops;
+        self.metadata_unlink_ops += earlier.metadata_unlink_ops;
+        self.metadata_rename_ops += earlier.metadata_rename_ops;
+        self.metadata_readdir_ops += earlier.metadata_readdir_ops;
+        self.metadata_fsync_ops += earlier.metadata_fsync_ops;
+        if let (Some(faults), Some(earlier)) = (self.page_faults.as_mut(), earlier.page_faults) {
+            faults.minor += earlier.minor;
+            faults.major += earlier.major;
+        }
+        if let (Some(gaps), Some(earlier)) = (self.submit_gaps.as_mut(), earlier.submit_gaps) {
+            gaps.add(&earlier);
+        }
+    }
+
+    fn latency_histograms(&self) -> [&SimpleHistogram; 12] {
+        [
+            &self.read_latency, &self.write_latency,
+            &self.metadata_open_latency, &self.metadata_close_latency,
+            &self.metadata_stat_latency, &self.metadata_setattr_latency,
+            &self.metadata_mkdir_latency, &self.metadata_rmdir_latency,
+            &self.metadata_unlink_latency, &self.metadata_rename_latency,
+            &self.metadata_readdir_latency, &self.metadata_fsync_latency,
+        ]
+    }
+
+    fn latency_histograms_mut(&mut self) -> [&mut SimpleHistogram; 12] {
+        [
+            &mut self.read_latency, &mut self.write_latency,
+            &mut self.metadata_open_latency, &mut self.metadata_close_latency,
+            &mut self.metadata_stat_latency, &mut self.metadata_setattr_latency,
+            &mut self.metadata_mkdir_latency, &mut self.metadata_rmdir_latency,
+            &mut self.metadata_unlink_latency, &mut self.metadata_rename_latency,
+            &mut self.metadata_readdir_latency, &mut self.metadata_fsync_latency,
+        ]
+    }
+
     /// Convert to StatsSnapshot (for CSV per-worker output)
     pub fn to_stats_snapshot(&self) -> crate::worker::StatsSnapshot {
         crate::worker::StatsSnapshot {
@@ -820,6 +1784,8 @@ impl AggregatedSnapshot {
             metadata_rename_latency: self.metadata_rename_latency.clone(),
             metadata_readdir_latency: self.metadata_readdir_latency.clone(),
             metadata_fsync_latency: self.metadata_fsync_latency.clone(),
+            page_faults: self.page_faults,
+            submit_gaps: self.submit_gaps,
         }
     }
     
@@ -850,6 +1816,8 @@ impl AggregatedSnapshot {
         let mut total_metadata_rename = 0u64;
         let mut total_metadata_readdir = 0u64;
         let mut total_metadata_fsync = 0u64;
+        let mut total_page_faults = None;
+        let mut total_submit_gaps = None;
         
         // Metadata histograms (will merge)
         let mut merged_read_latency = SimpleHistogram::new();
@@ -871,6 +1839,8 @@ impl AggregatedSnapshot {
             total_read_bytes += snapshot.read_bytes;
             total_write_bytes += snapshot.write_bytes;
             total_errors += snapshot.errors;
+            total_page_faults = crate::util::resource::PageFaults::combine(total_page_faults, snapshot.page_faults);
+            total_submit_gaps = crate::stats::submit_gaps::SubmitGaps::combine(total_submit_gaps, snapshot.submit_gaps);
             if snapshot.avg_latency_us > 0.0 {
                 sum_latency += snapshot.avg_latency_us;
                 count += 1;
@@ -946,12 +1916,21 @@ impl AggregatedSnapshot {
             metadata_rename_latency: merged_rename_latency,
             metadata_readdir_latency: merged_readdir_latency,
             metadata_fsync_latency: merged_fsync_latency,
+            page_faults: total_page_faults,
+            submit_gaps: total_submit_gaps,
             per_worker,
         }
     }
 }
 
 
+/// Mean of the reads and writes together, in microseconds (0 without operations)
+fn mean_latency_us(read: &SimpleHistogram, write: &SimpleHistogram) -> f64 {
+    let mut hist = read.clone();
+    hist.merge(write);
+    if hist.is_empty() { 0.0 } else { hist.mean().as_micros() as f64 }
+}
+
 /// Extract metadata latency from aggregated histograms
 fn extract_metadata_latency_from_aggregated(snapshot: &AggregatedSnapshot) -> Option<JsonMetadataLatency> {
     use crate::stats::simple_histogram::SimpleHistogram;
@@ -1028,7 +2007,7 @@ fn extract_metadata_ops_from_aggregated(snapshot: &AggregatedSnapshot) -> JsonMe
 
 
 /// Format SystemTime as ISO 8601 string
-fn format_timestamp(time: std::time::SystemTime) -> String {
+pub(crate) fn format_timestamp(time: std::time::SystemTime) -> String {
     use std::time::UNIX_EPOCH;
     
     let duration_since_epoch = time.duration_since(UNIX_EPOCH)
@@ -1057,9 +2036,12 @@ fn format_timestamp(time: std::time::SystemTime) -> String {
     let month = (day_of_year / 30) + 1;
     let day = (day_of_year % 30) + 1;
     
+    // Milliseconds, so sub-second live intervals get distinct timestamps
+    let millis = duration_since_epoch.subsec_millis();
+    
     format!(
-        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
-        year, month, day, hours, minutes, seconds
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year, month, day, hours, minutes, seconds, millis
     )
 }
 
@@ -1077,9 +2059,12 @@ pub fn build_test_config(config: &crate::config::Config) -> JsonTestConfig {
         crate::config::workload::DistributionType::Pareto { h } => {
             (Some("pareto".to_string()), None, Some(*h), None)
         }
-        crate::config::workload::DistributionType::Gaussian { stddev, center: _ } => {
+        crate::config::workload::DistributionType::Gaussian { stddev, .. } => {
             (Some("gaussian".to_string()), None, None, Some(*stddev))
         }
+        composite @ crate::config::workload::DistributionType::Composite { .. } => {
+            (Some(composite.to_string()), None, None, None)
+        }
     };
     
     // Get file size from first target (if available)
@@ -1091,7 +2076,8 @@ pub fn build_test_config(config: &crate::config::Config) -> JsonTestConfig {
         threads: config.workers.threads,
         block_size: workload.block_size as usize,
         file_size,
-        engine: format!("{:?}", workload.engine).to_lowercase(),
+        engine: engine_name(workload.engine),
+        effective_engine: engine_name(workload.effective_engine()),
         queue_depth: workload.queue_depth,
         read_percent: workload.read_percent as u32,
         write_percent: workload.write_percent as u32,
@@ -1099,6 +2085,19 @@ pub fn build_test_config(config: &crate::config::Config) -> JsonTestConfig {
         zipf_theta,
         pareto_h,
         gaussian_stddev,
+        gaussian_drift: match &workload.distribution {
+            crate::config::workload::DistributionType::Gaussian { drift, .. } => drift.map(|d| d.to_string()),
+            _ => None,
+        },
+        worker_groups: workload.worker_groups.iter().map(|g| g.to_string()).collect(),
+        buffered_percent: Some(workload.buffered_percent).filter(|&percent| percent > 0),
+        latency_sample: config.runtime.latency_sample.filter(|&n| n > 1),
+        offset_start: match workload.offset_start {
+            Some(crate::config::workload::OffsetStart::At(offset)) => Some(offset),
+            _ => None,
+        },
+        remap_offsets: config.workload.remap_offsets.map(|remap| remap.to_string()),
+        interval_latency: Some(config.output.interval_latency.to_string()),
     }
 }
 
@@ -1112,26 +2111,125 @@ pub fn build_test_info(
     config: &crate::config::Config,
 ) -> JsonTestInfo {
     JsonTestInfo {
+        job_id: None,
+        run_uuid: config.output.run_uuid.clone(),
+        node_uid: None,
         node_id,
         hostname,
         start_time: format_timestamp(start_time),
         end_time: end_time.map(format_timestamp),
         duration: duration.map(JsonDuration::from_duration),
         config: build_test_config(config),
+        labels: config.output.labels.clone(),
+        origin: Some(JsonOrigin::current()),
+        capabilities: None,
     }
 }
 
 
 /// Convert WorkerStats to JsonWorkerStatsFinal (for final summary)
-pub fn worker_stats_to_json_final(node_id: String, worker_id: usize, stats: &WorkerStats) -> JsonWorkerStatsFinal {
+///
+/// `percentiles` is the `--percentiles` list (empty for the default set).
+pub fn worker_stats_to_json_final(node_id: String, worker_id: usize, stats: &WorkerStats, percentiles: &[f64]) -> JsonWorkerStatsFinal {
     JsonWorkerStatsFinal {
         node_id,
         worker_id,
+        uid: None,
         read_ops: stats.read_ops(),
         write_ops: stats.write_ops(),
         read_bytes: stats.read_bytes(),
         write_bytes: stats.write_bytes(),
-        latency: extract_latency(stats),
+        latency: latency_with_percentiles(stats.io_latency(), percentiles),
+        read_latency: (!stats.read_latency().is_empty()).then(|| latency_with_percentiles(stats.read_latency(), percentiles)),
+        write_latency: (!stats.write_latency().is_empty()).then(|| latency_with_percentiles(stats.write_latency(), percentiles)),
+        duration: stats.test_duration().map(JsonDuration::from_duration),
+        cpu_time: cpu_time_to_json(stats),
+        offset_range: stats.offset_range().map(|(start, end)| JsonOffsetRange { start, end, bytes: end - start }),
+        io_window: stats.io_window().map(|(start_unix_ns, stop_unix_ns)| JsonIoWindow { start_unix_ns, stop_unix_ns }),
+    }
+}
+
+
+/// Convert a node's final stats to JSON
+pub fn node_stats_to_json_final(node_id: String, hostname: String, stats: &WorkerStats, duration: Duration) -> JsonNodeStatsFinal {
+    let secs = duration.as_secs_f64();
+    let (total_iops, throughput) = if secs > 0.0 {
+        ((stats.total_ops() as f64 / secs) as u64, (stats.total_bytes() as f64 / secs) as u64)
+    } else {
+        (0, 0)
+    };
+    
+    JsonNodeStatsFinal {
+        node_id,
+        hostname,
+        uid: None,
+        capabilities: None,
+        duration: JsonDuration::from_duration(duration),
+        total_ops: stats.total_ops(),
+        total_iops,
+        total_throughput: JsonThroughput::new(throughput),
+        p99_latency: JsonDuration::from_duration(stats.io_latency().percentile(99.0)),
+        errors: stats.errors(),
+        cpu_percent_total: stats.resource_stats().map(|r| r.cpu_percent),
+        space_consumed_bytes: stats.space_usage().map(|usage| usage.consumed_bytes()),
+        space_amplification: stats.space_usage().and_then(|usage| usage.amplification(stats.write_bytes())),
+    }
+}
+
+/// Convert one role's merged node statistics to JSON
+pub fn role_stats_to_json_final(
+    role: String,
+    nodes: Vec<String>,
+    stats: &WorkerStats,
+    duration: Duration,
+    workload: &crate::config::WorkloadConfig,
+    num_workers: usize,
+) -> JsonRoleStatsFinal {
+    JsonRoleStatsFinal {
+        role,
+        nodes,
+        duration: JsonDuration::from_duration(duration),
+        stats: stats_to_json_aggregate(stats, duration, None, false, workload.block_size, workload.queue_depth, num_workers),
+    }
+}
+
+/// Convert the start/stop skew to JSON
+pub fn skew_to_json(skew: &crate::stats::skew::StartStopSkew) -> JsonStartStopSkew {
+    JsonStartStopSkew {
+        workers: skew.workers,
+        start_skew: JsonDuration::from_duration(skew.start_skew),
+        stop_skew: JsonDuration::from_duration(skew.stop_skew),
+        span: JsonDuration::from_duration(skew.span),
+        overlap: JsonDuration::from_duration(skew.overlap),
+        overlap_percent: skew.overlap_percent(),
+        per_node: skew.per_node.iter().map(|node| JsonNodeSkew {
+            node_id: node.node.clone(),
+            workers: node.workers,
+            first_start: JsonDuration::from_duration(node.first_start),
+            last_start: JsonDuration::from_duration(node.last_start),
+            first_stop: JsonDuration::from_duration(node.first_stop),
+            last_stop: JsonDuration::from_duration(node.last_stop),
+        }).collect(),
+    }
+}
+
+/// Convert a summary checkpoint to JSON
+pub fn checkpoint_to_json(checkpoint: &crate::output::checkpoint::Checkpoint, percentiles: &[f64]) -> JsonCheckpoint {
+    use crate::util::time::{calculate_iops, calculate_throughput};
+    let duration = checkpoint.duration();
+    JsonCheckpoint {
+        number: checkpoint.number,
+        timestamp: format_timestamp(checkpoint.timestamp),
+        start: JsonDuration::from_duration(checkpoint.start),
+        end: JsonDuration::from_duration(checkpoint.end),
+        read_ops: checkpoint.read_ops,
+        write_ops: checkpoint.write_ops,
+        read_iops: calculate_iops(checkpoint.read_ops, duration),
+        write_iops: calculate_iops(checkpoint.write_ops, duration),
+        read_throughput: calculate_throughput(checkpoint.read_bytes, duration),
+        write_throughput: calculate_throughput(checkpoint.write_bytes, duration),
+        read_latency: (!checkpoint.read_latency.is_empty()).then(|| latency_with_percentiles(&checkpoint.read_latency, percentiles)),
+        write_latency: (!checkpoint.write_latency.is_empty()).then(|| latency_with_percentiles(&checkpoint.write_latency, percentiles)),
     }
 }
 
@@ -1180,6 +2278,8 @@ pub fn build_json_snapshot_with_nodes(
             metadata_rename_latency: SimpleHistogram::new(),
             metadata_readdir_latency: SimpleHistogram::new(),
             metadata_fsync_latency: SimpleHistogram::new(),
+            page_faults: None,
+            submit_gaps: None,
             per_worker: None,
         };
         
@@ -1212,6 +2312,8 @@ pub fn build_json_snapshot_with_nodes(
                 coverage: None,
                 block_size_verification: None,
                 queue_depth_stats: None,
+                page_faults: None,
+                submit_pacing: None,
             },
         };
     }
@@ -1307,7 +2409,17 @@ pub fn build_json_snapshot_with_nodes(
         .collect();
     
     // Build aggregate by merging all nodes
-    let aggregate = merge_node_stats(&nodes, interval_duration);
+    let mut aggregate = merge_node_stats(&nodes, interval_duration);
+    
+    // Percentiles don't add up; recompute them from the nodes' histograms
+    let mut read_latency = SimpleHistogram::new();
+    let mut write_latency = SimpleHistogram::new();
+    for (_, snapshot) in node_snapshots {
+        read_latency.merge(&snapshot.read_latency);
+        write_latency.merge(&snapshot.write_latency);
+    }
+    aggregate.read_latency = series_latency(&read_latency);
+    aggregate.write_latency = series_latency(&write_latency);
     
     JsonSnapshot {
         timestamp,
@@ -1317,6 +2429,29 @@ pub fn build_json_snapshot_with_nodes(
     }
 }
 
+/// Length of the interval ending at snapshot `i` of a time-series
+fn interval_before(snapshots: &[AggregatedSnapshot], i: usize) -> Option<Duration> {
+    let end = snapshots.get(i)?.elapsed;
+    let start = snapshots.get(i.checked_sub(1)?)?.elapsed;
+    Some(end.saturating_sub(start)).filter(|interval| !interval.is_zero())
+}
+
+/// Build a streamed interval record from one node's delta snapshot
+pub fn build_interval_record(
+    node_id: &str,
+    snapshot: &AggregatedSnapshot,
+    interval_duration: Duration,
+    resource_stats: Option<ResourceStats>,
+    num_workers: usize,
+) -> JsonIntervalRecord {
+    JsonIntervalRecord {
+        timestamp: format_timestamp(snapshot.timestamp),
+        elapsed: JsonDuration::from_duration(snapshot.elapsed),
+        node_id: node_id.to_string(),
+        stats: snapshot_to_aggregate_stats(snapshot, interval_duration, resource_stats, None, false, num_workers),
+    }
+}
+
 /// Helper: Convert AggregatedSnapshot to JsonAggregateStats
 fn snapshot_to_aggregate_stats(
     snapshot: &AggregatedSnapshot,
@@ -1353,20 +2488,8 @@ fn snapshot_to_aggregate_stats(
         0
     };
     
-    let read_latency = latency_mean_only(
-        if snapshot.read_latency.len() > 0 {
-            snapshot.read_latency.mean().as_micros() as f64
-        } else {
-            0.0
-        }
-    );
-    let write_latency = latency_mean_only(
-        if snapshot.write_latency.len() > 0 {
-            snapshot.write_latency.mean().as_micros() as f64
-        } else {
-            0.0
-        }
-    );
+    let read_latency = series_latency(&snapshot.read_latency);
+    let write_latency = series_latency(&snapshot.write_latency);
     
     let coverage = if include_coverage && total_blocks.is_some() {
         None  // Coverage only in final summary
@@ -1399,11 +2522,14 @@ fn snapshot_to_aggregate_stats(
         coverage,
         block_size_verification: None,
         queue_depth_stats: None,
+        page_faults: snapshot.page_faults
+            .map(|faults| JsonPageFaults::new(faults, interval_duration, snapshot.read_ops + snapshot.write_ops)),
+        submit_pacing: snapshot.submit_gaps.as_ref().map(JsonSubmitPacing::new),
     }
 }
 
 /// Helper: Merge per-node stats into aggregate
-fn merge_node_stats(nodes: &[JsonNodeTimeSeriesStats], _interval_duration: Duration) -> JsonAggregateStats {
+fn merge_node_stats(nodes: &[JsonNodeTimeSeriesStats], interval_duration: Duration) -> JsonAggregateStats {
     if nodes.is_empty() {
         // Create empty snapshot for metadata extraction
         let empty_snapshot = AggregatedSnapshot {
@@ -1437,6 +2563,8 @@ fn merge_node_stats(nodes: &[JsonNodeTimeSeriesStats], _interval_duration: Durat
             metadata_rename_latency: SimpleHistogram::new(),
             metadata_readdir_latency: SimpleHistogram::new(),
             metadata_fsync_latency: SimpleHistogram::new(),
+            page_faults: None,
+            submit_gaps: None,
             per_worker: None,
         };
         
@@ -1465,6 +2593,8 @@ fn merge_node_stats(nodes: &[JsonNodeTimeSeriesStats], _interval_duration: Durat
             coverage: None,
             block_size_verification: None,
             queue_depth_stats: None,
+            page_faults: None,
+            submit_pacing: None,
         };
     }
     
@@ -1501,6 +2631,20 @@ fn merge_node_stats(nodes: &[JsonNodeTimeSeriesStats], _interval_duration: Durat
         // For now, just use simple average - could be improved with weighted average
     }
     
+    // Page fault rates are recomputed from the summed counts
+    let faults = nodes.iter()
+        .filter_map(|n| n.stats.page_faults.as_ref())
+        .map(|f| PageFaults { minor: f.minor, major: f.major })
+        .reduce(|mut a, b| { a.add(&b); a });
+    aggregate.page_faults = faults.map(|f| JsonPageFaults::new(f, interval_duration, aggregate.total_ops));
+    
+    // So is the submission spacing, from the nodes' gap counters
+    let gaps = nodes.iter()
+        .filter_map(|n| n.stats.submit_pacing.as_ref())
+        .map(JsonSubmitPacing::counters)
+        .reduce(|mut a, b| { a.add(&b); a });
+    aggregate.submit_pacing = gaps.as_ref().map(JsonSubmitPacing::new);
+    
     aggregate
 }
 
@@ -1521,7 +2665,7 @@ pub fn build_node_output(
     total_blocks: Option<u64>,
 ) -> JsonNodeOutput {
     // Build test info
-    let test_info = build_test_info(
+    let mut test_info = build_test_info(
         node_id.clone(),
         hostname,
         start_time,
@@ -1529,6 +2673,7 @@ pub fn build_node_output(
         Some(test_duration),
         config,
     );
+    test_info.config.set_engines_run(final_stats.engines());
     
     // Convert time-series snapshots, skipping the first one (startup noise)
     // For single-node output, create nodes array with single entry
@@ -1552,7 +2697,7 @@ pub fn build_node_output(
             
             build_json_snapshot_with_nodes(
                 &node_snapshots,
-                Duration::from_secs(1),
+                interval_before(&time_series_snapshots, i + 1).unwrap_or(Duration::from_secs(1)),
                 &node_resource_stats,
                 workers_at_timestamp,  // NEW: per-worker data
                 total_blocks,
@@ -1566,16 +2711,43 @@ pub fn build_node_output(
     let configured_block_size = config.workload.block_size;
     let configured_queue_depth = config.workload.queue_depth;
     let num_workers = config.workers.threads;
-    let final_aggregate = stats_to_json_aggregate(final_stats, test_duration, total_blocks, include_coverage, configured_block_size, configured_queue_depth, num_workers);
+    let percentiles = config.output.percentiles.as_deref().unwrap_or(&[]);
+    let mut final_aggregate = stats_to_json_aggregate(final_stats, test_duration, total_blocks, include_coverage, configured_block_size, configured_queue_depth, num_workers);
+    add_configured_percentiles(&mut final_aggregate, final_stats, percentiles);
     
     let per_worker: Vec<JsonWorkerStatsFinal> = per_worker_stats.iter()
-        .map(|(worker_id, stats)| worker_stats_to_json_final(node_id.clone(), *worker_id, stats))
+        .map(|(worker_id, stats)| worker_stats_to_json_final(node_id.clone(), *worker_id, stats, percentiles))
         .collect();
     
     let final_summary = JsonFinalSummary {
         total_duration: JsonDuration::from_duration(test_duration),
         aggregate: final_aggregate,
         per_worker,
+        qd_ramp: final_stats.qd_ramp().cloned(),
+        setup_phases: setup_phases_to_json(final_stats),
+        grow: grow_to_json(final_stats, test_duration),
+        file_hints: file_hints_to_json(final_stats),
+        file_contention: file_contention_to_json(final_stats),
+        slowest_files: slowest_files_to_json(final_stats),
+        osts: ost_stats_to_json(final_stats, test_duration),
+        cpu_time: cpu_time_to_json(final_stats),
+        timer: timer_to_json(final_stats),
+        fragmentation: final_stats.fragmentation().map(JsonFragmentation::from),
+        per_worker_rate_sum: final_stats.rate_sum().map(JsonRateSum::from),
+        per_node: Vec::new(),
+        per_role: Vec::new(),
+        thread_scaling: None,
+        latency_target: None,
+        checkpoints: Vec::new(),
+        start_stop_skew: None,
+        drained: drained_to_json(final_stats),
+        io_paths: io_paths_to_json(final_stats, test_duration),
+        rated: rated_to_json(config, final_stats, test_duration),
+        space_usage: space_usage_to_json(final_stats),
+        page_cache_warning: crate::output::page_cache::detect(final_stats, test_duration, config),
+        eof_clamped_reads: Some(final_stats.eof_clamped()).filter(|&clamped| clamped > 0),
+        queue_depth_limit: final_stats.queue_depth_limit().cloned(),
+        latency_floor: latency_floor_to_json(final_stats),
     };
     
     JsonNodeOutput {
@@ -1599,10 +2771,11 @@ pub fn build_aggregate_node_output(
     all_per_worker_time_series: Vec<(String, Vec<Vec<AggregatedSnapshot>>)>,  // (node_id, timestamp → workers) (NEW)
     final_stats: &WorkerStats,
     all_per_worker_stats: &[(String, usize, &WorkerStats)],  // (node_id, worker_id, stats) for ALL workers
+    all_node_stats: &[(String, String, &WorkerStats, Duration)],  // (node_id, hostname, stats, duration) for each node
     total_blocks: Option<u64>,
 ) -> JsonNodeOutput {
     // Build test info
-    let test_info = build_test_info(
+    let mut test_info = build_test_info(
         node_id,
         hostname,
         start_time,
@@ -1610,6 +2783,7 @@ pub fn build_aggregate_node_output(
         Some(test_duration),
         config,
     );
+    test_info.config.set_engines_run(final_stats.engines());
     
     // Find max number of snapshots across all nodes
     let max_snapshots = all_node_snapshots.iter()
@@ -1642,9 +2816,14 @@ pub fn build_aggregate_node_output(
                 })
                 .collect();
             
+            // Intervals are 1 s heartbeats unless --stats-mem-limit merged them
+            let interval = all_node_snapshots.iter()
+                .find_map(|(_, snapshots)| interval_before(snapshots, i))
+                .unwrap_or(Duration::from_secs(1));
+            
             build_json_snapshot_with_nodes(
                 &node_snapshots,
-                Duration::from_secs(1),
+                interval,
                 &node_resource_stats,
                 Some(all_workers_at_timestamp),  // NEW: per-worker data from all nodes
                 total_blocks,
@@ -1663,16 +2842,45 @@ pub fn build_aggregate_node_output(
     } else {
         num_nodes * config.workers.threads  // Otherwise calculate from config
     };
-    let final_aggregate = stats_to_json_aggregate(final_stats, test_duration, total_blocks, include_coverage, configured_block_size, configured_queue_depth, num_workers);
+    let percentiles = config.output.percentiles.as_deref().unwrap_or(&[]);
+    let mut final_aggregate = stats_to_json_aggregate(final_stats, test_duration, total_blocks, include_coverage, configured_block_size, configured_queue_depth, num_workers);
+    add_configured_percentiles(&mut final_aggregate, final_stats, percentiles);
     
     let per_worker: Vec<JsonWorkerStatsFinal> = all_per_worker_stats.iter()
-        .map(|(node_id, worker_id, stats)| worker_stats_to_json_final(node_id.clone(), *worker_id, stats))
+        .map(|(node_id, worker_id, stats)| worker_stats_to_json_final(node_id.clone(), *worker_id, stats, percentiles))
         .collect();
     
     let final_summary = JsonFinalSummary {
         total_duration: JsonDuration::from_duration(test_duration),
         aggregate: final_aggregate,
         per_worker,  // True per-worker stats with node_id
+        qd_ramp: final_stats.qd_ramp().cloned(),
+        setup_phases: setup_phases_to_json(final_stats),
+        grow: grow_to_json(final_stats, test_duration),
+        file_hints: file_hints_to_json(final_stats),
+        file_contention: file_contention_to_json(final_stats),
+        slowest_files: slowest_files_to_json(final_stats),
+        osts: ost_stats_to_json(final_stats, test_duration),
+        cpu_time: cpu_time_to_json(final_stats),
+        timer: timer_to_json(final_stats),
+        fragmentation: final_stats.fragmentation().map(JsonFragmentation::from),
+        per_worker_rate_sum: final_stats.rate_sum().map(JsonRateSum::from),
+        per_node: all_node_stats.iter()
+            .map(|(node_id, hostname, stats, duration)| node_stats_to_json_final(node_id.clone(), hostname.clone(), stats, *duration))
+            .collect(),
+        per_role: Vec::new(),
+        thread_scaling: None,
+        latency_target: None,
+        checkpoints: Vec::new(),
+        start_stop_skew: None,
+        drained: drained_to_json(final_stats),
+        io_paths: io_paths_to_json(final_stats, test_duration),
+        rated: rated_to_json(config, final_stats, test_duration),
+        space_usage: space_usage_to_json(final_stats),
+        page_cache_warning: crate::output::page_cache::detect(final_stats, test_duration, config),
+        eof_clamped_reads: Some(final_stats.eof_clamped()).filter(|&clamped| clamped > 0),
+        queue_depth_limit: final_stats.queue_depth_limit().cloned(),
+        latency_floor: latency_floor_to_json(final_stats),
     };
     
     JsonNodeOutput {
@@ -1714,10 +2922,13 @@ pub fn export_histogram(
     node_id: String,
     stats: &WorkerStats,
 ) -> JsonHistogramOutput {
+    histogram_to_json(node_id, stats.io_latency())
+}
+
+/// Convert a histogram to its JSON export (only non-zero buckets)
+pub fn histogram_to_json(node_id: String, hist: &SimpleHistogram) -> JsonHistogramOutput {
     use crate::stats::simple_histogram::bucket_idx_to_micros;
     
-    let hist = stats.io_latency();
-    
     // Get all non-zero buckets
     let buckets: Vec<JsonHistogramBucket> = (0..112)
         .filter_map(|idx| {
@@ -1771,3 +2982,43 @@ pub fn write_histogram_output(
     Ok(())
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interval_latency_semantics() {
+        let mut first = AggregatedSnapshot::from_worker_snapshots(&[], Duration::from_secs(1), false);
+        for _ in 0..100 {
+            first.read_latency.record(Duration::from_micros(100));
+        }
+        first.read_ops = 100;
+
+        // A latency spike in the second interval
+        let mut second = first.clone();
+        second.elapsed = Duration::from_secs(2);
+        for _ in 0..100 {
+            second.read_latency.record(Duration::from_millis(10));
+        }
+        second.read_ops = 200;
+
+        let interval = second.since(&first, IntervalLatency::Interval);
+        assert_eq!(interval.read_ops, 100);
+        assert_eq!(interval.read_latency.len(), 100);
+        assert!(interval.read_latency.percentile(50.0) >= Duration::from_millis(5));
+
+        // Cumulative: the spike is diluted by the first interval
+        let cumulative = second.since(&first, IntervalLatency::Cumulative);
+        assert_eq!(cumulative.read_ops, 100);
+        assert_eq!(cumulative.read_latency.len(), 200);
+        assert!(cumulative.read_latency.percentile(40.0) < Duration::from_millis(1));
+
+        // Merged intervals keep every operation of both
+        let mut merged = first.clone();
+        merged.absorb(interval, IntervalLatency::Interval);
+        assert_eq!(merged.read_ops, 200);
+        assert_eq!(merged.read_latency.len(), 200);
+        assert_eq!(merged.elapsed, Duration::from_secs(2));
+    }
+}
@@ -0,0 +1,204 @@
+//! Live metadata lines during a duration run
+//!
+//! With `--live-metadata`, the coordinator feeds every node's heartbeats into
+//! a [`LiveStats`] tracker and prints one line per live interval with the
+//! open/close/fsync rates and open fd count next to IOPS and throughput.
+//! With `--csv-output FILE.csv`, the same rows are written to
+//! `FILE_live.csv`.
+//!
+//! Heartbeats carry cumulative counters, so each line sums the latest
+//! heartbeat of every node and the tracker turns consecutive sums into rates.
+//! The open fd count is the sum over the node service processes.
+
+use crate::output::json::AggregatedSnapshot;
+use crate::stats::live::LiveStats;
+use anyhow::Context;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Cumulative counters of a node's latest heartbeat
+#[derive(Debug, Clone, Copy, Default)]
+struct NodeCounters {
+    read_ops: u64,
+    write_ops: u64,
+    read_bytes: u64,
+    write_bytes: u64,
+    errors: u64,
+    open_ops: u64,
+    close_ops: u64,
+    fsync_ops: u64,
+    open_fds: Option<u64>,
+    avg_latency_us: f64,
+}
+
+/// Prints and records live lines with metadata columns
+pub struct LiveMonitor {
+    live: LiveStats,
+    latest: Vec<Option<NodeCounters>>,
+    print: bool,
+    csv: Option<BufWriter<File>>,
+}
+
+impl LiveMonitor {
+    /// Create a monitor for `num_nodes` nodes
+    ///
+    /// Lines are printed when `print` is set; rows are written to `csv_path`
+    /// (header first) when given.
+    pub fn new(interval: Duration, num_nodes: usize, print: bool, csv_path: Option<&Path>) -> anyhow::Result<Self> {
+        let csv = match csv_path {
+            Some(path) => {
+                let file = File::create(path)
+                    .with_context(|| format!("Failed to create live CSV: {}", path.display()))?;
+                let mut writer = BufWriter::new(file);
+                writeln!(writer, "{}", LiveStats::csv_header_with_metadata())?;
+                Some(writer)
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            live: LiveStats::with_metadata(interval, true),
+            latest: vec![None; num_nodes],
+            print,
+            csv,
+        })
+    }
+
+    /// Live CSV written next to the time-series CSV
+    pub fn csv_path(csv_output: &Path) -> PathBuf {
+        let stem = csv_output.file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("results");
+        csv_output.with_file_name(format!("{}_live.csv", stem))
+    }
+
+    /// Record a node's latest heartbeat (cumulative snapshot)
+    pub fn record(&mut self, node_idx: usize, cumulative: &AggregatedSnapshot, open_fds: Option<u64>) {
+        self.latest[node_idx] = Some(NodeCounters {
+            read_ops: cumulative.read_ops,
+            write_ops: cumulative.write_ops,
+            read_bytes: cumulative.read_bytes,
+            write_bytes: cumulative.write_bytes,
+            errors: cumulative.errors,
+            open_ops: cumulative.metadata_open_ops,
+            close_ops: cumulative.metadata_close_ops,
+            fsync_ops: cumulative.metadata_fsync_ops,
+            open_fds,
+            avg_latency_us: cumulative.avg_latency_us,
+        });
+    }
+
+    /// Print and record a line once the live interval has passed
+    pub fn tick(&mut self) -> anyhow::Result<()> {
+        if !self.live.should_update() || self.latest.iter().all(Option::is_none) {
+            return Ok(());
+        }
+
+        let mut total = NodeCounters::default();
+        let mut latency_sum = 0.0;
+        for node in self.latest.iter().flatten() {
+            total.read_ops += node.read_ops;
+            total.write_ops += node.write_ops;
+            total.read_bytes += node.read_bytes;
+            total.write_bytes += node.write_bytes;
+            total.errors += node.errors;
+            total.open_ops += node.open_ops;
+            total.close_ops += node.close_ops;
+            total.fsync_ops += node.fsync_ops;
+            if let Some(fds) = node.open_fds {
+                total.open_fds = Some(total.open_fds.unwrap_or(0) + fds);
+            }
+            latency_sum += node.avg_latency_us * (node.read_ops + node.write_ops) as f64;
+        }
+        let io_ops = total.read_ops + total.write_ops;
+        let avg_latency_us = if io_ops > 0 { latency_sum / io_ops as f64 } else { 0.0 };
+
+        self.live.update_from_snapshot(total.read_ops, total.write_ops, total.read_bytes, total.write_bytes,
+                                       total.errors, avg_latency_us);
+        self.live.update_metadata_from_snapshot(total.open_ops, total.close_ops, total.fsync_ops, total.open_fds);
+
+        if self.print {
+            self.live.display_console_newline();
+        }
+        if let Some(writer) = self.csv.as_mut() {
+            writeln!(writer, "{}", self.live.to_csv())?;
+            writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(read_ops: u64, open_ops: u64, fsync_ops: u64) -> AggregatedSnapshot {
+        let mut snapshot = AggregatedSnapshot::from_worker_snapshots(&[], Duration::from_secs(1), false);
+        snapshot.read_ops = read_ops;
+        snapshot.read_bytes = read_ops * 4096;
+        snapshot.metadata_open_ops = open_ops;
+        snapshot.metadata_close_ops = open_ops;
+        snapshot.metadata_fsync_ops = fsync_ops;
+        snapshot
+    }
+
+    #[test]
+    fn test_live_csv_sums_nodes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = LiveMonitor::csv_path(&dir.path().join("results.csv"));
+        assert_eq!(path, dir.path().join("results_live.csv"));
+
+        let mut monitor = LiveMonitor::new(Duration::from_millis(10), 2, false, Some(&path)).unwrap();
+        // Nothing is written before the first heartbeat
+        std::thread::sleep(Duration::from_millis(20));
+        monitor.tick().unwrap();
+
+        monitor.record(0, &snapshot(100, 10, 5), Some(7));
+        monitor.record(1, &snapshot(300, 30, 0), Some(9));
+        monitor.tick().unwrap();
+        // Not due again until another interval has passed
+        monitor.record(0, &snapshot(200, 20, 10), Some(8));
+        monitor.tick().unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+        monitor.tick().unwrap();
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], LiveStats::csv_header_with_metadata());
+
+        let header: Vec<&str> = lines[0].split(',').collect();
+        let column = |row: &str, name: &str| -> String {
+            let idx = header.iter().position(|h| *h == name).unwrap();
+            row.split(',').nth(idx).unwrap().to_string()
+        };
+        for row in &lines[1..] {
+            assert_eq!(row.split(',').count(), header.len());
+        }
+        assert_eq!(column(lines[1], "total_read_ops"), "400");
+        assert_eq!(column(lines[1], "open_fds"), "16");
+        assert_eq!(column(lines[2], "total_read_ops"), "500");
+        assert_eq!(column(lines[2], "open_fds"), "17");
+        // Node 0 opened 10 more files and fsynced 5 more times; node 1 did nothing
+        let open_rate: f64 = column(lines[2], "open_rate").parse().unwrap();
+        let fsync_rate: f64 = column(lines[2], "fsync_rate").parse().unwrap();
+        assert!(open_rate > 0.0);
+        assert!((open_rate - 2.0 * fsync_rate).abs() < 0.01 * open_rate);
+    }
+
+    #[test]
+    fn test_live_csv_without_fd_counts() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("live.csv");
+        let mut monitor = LiveMonitor::new(Duration::ZERO, 1, false, Some(&path)).unwrap();
+        monitor.record(0, &snapshot(10, 1, 1), None);
+        monitor.tick().unwrap();
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        let row = text.lines().nth(1).unwrap();
+        // Nodes older than protocol v3 report no fd count: empty column
+        assert!(row.ends_with(','));
+    }
+}
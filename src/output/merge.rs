@@ -0,0 +1,389 @@
+//! Merge results of independent runs (`iopulse merge`)
+//!
+//! When the same workload runs on several clusters, each with its own
+//! coordinator, every run writes its own aggregate JSON. `iopulse merge`
+//! combines them into one aggregate in the same schema:
+//!
+//! - Counters (ops, bytes, errors, metadata ops) are summed.
+//! - Rates are summed: the runs are taken to have run at the same time, so
+//!   the combined IOPS is the sum of each run's IOPS over its own duration.
+//! - Latency percentiles are recomputed from the merged histograms when every
+//!   run has a histogram file (`--json-histogram`). Otherwise only the mean
+//!   (weighted by ops), min and max are kept.
+//! - Per-node and per-worker results are concatenated, prefixed with the run.
+//!
+//! Runs must share the workload (block size, read/write mix, engine, queue
+//! depth, access distribution); thread counts and file sizes may differ.
+//! Time-series are not merged, since intervals of independent runs don't line up.
+
+use crate::output::json::{
+    extract_latency_from_histogram, JsonAggregateStats, JsonDuration, JsonFinalSummary,
+    JsonHistogramOutput, JsonLatency, JsonMetadataOps, JsonNodeOutput, JsonOrigin,
+    JsonResourceUtil, JsonTestConfig, JsonTestInfo, JsonThroughput,
+};
+use crate::stats::simple_histogram::{SimpleHistogram, NUM_BUCKETS};
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// One run's results read from disk
+#[derive(Debug, Clone)]
+pub struct RunResult {
+    /// Short name of the run (file name, or directory for `aggregate.json`)
+    pub name: String,
+    pub output: JsonNodeOutput,
+    /// Overall latency histogram, if a histogram file was found
+    pub histogram: Option<SimpleHistogram>,
+}
+
+impl RunResult {
+    /// Read an aggregate JSON file and its histogram file, if any
+    ///
+    /// The histogram is looked up as `<stem>_histogram.json`, or
+    /// `histogram.json` in the same directory (directory output).
+    pub fn load(path: &Path) -> Result<Self> {
+        let data = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let output: JsonNodeOutput = serde_json::from_str(&data)
+            .with_context(|| format!("{} is not an IOPulse JSON result", path.display()))?;
+
+        let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("result");
+        let histogram = [path.with_file_name(format!("{}_histogram.json", stem)), path.with_file_name("histogram.json")]
+            .iter()
+            .find(|candidate| candidate.exists())
+            .map(load_histogram)
+            .transpose()?;
+
+        let name = match path.parent().and_then(|p| p.file_name()) {
+            Some(dir) if stem == "aggregate" => dir.to_string_lossy().into_owned(),
+            _ => stem.to_string(),
+        };
+        Ok(Self { name, output, histogram })
+    }
+}
+
+/// Read a histogram file written with `--json-histogram`
+fn load_histogram(path: &PathBuf) -> Result<SimpleHistogram> {
+    let data = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let export: JsonHistogramOutput = serde_json::from_str(&data)
+        .with_context(|| format!("{} is not an IOPulse histogram", path.display()))?;
+    let data = export.histogram;
+
+    let mut buckets = [0u64; NUM_BUCKETS];
+    for bucket in &data.buckets {
+        if let Some(count) = buckets.get_mut(bucket.index) {
+            *count = bucket.count;
+        }
+    }
+    Ok(SimpleHistogram::from_parts(
+        buckets,
+        Duration::from_micros(data.mean.micros.saturating_mul(data.num_samples)),
+        Duration::from_micros(data.min.micros),
+        Duration::from_micros(data.max.micros),
+    ))
+}
+
+/// Check that runs used the same workload
+///
+/// Returns one line per differing setting, naming each run's value.
+pub fn incompatibilities(runs: &[RunResult]) -> Vec<String> {
+    let Some(first) = runs.first() else {
+        return Vec::new();
+    };
+    type Field = fn(&JsonTestConfig) -> String;
+    let fields: [(&str, Field); 8] = [
+        ("block_size", |c| c.block_size.to_string()),
+        ("read_percent", |c| c.read_percent.to_string()),
+        ("write_percent", |c| c.write_percent.to_string()),
+        ("engine", |c| c.engine.clone()),
+        ("queue_depth", |c| c.queue_depth.to_string()),
+        ("distribution", |c| c.distribution.clone().unwrap_or_else(|| "-".to_string())),
+        ("distribution parameter", |c| format!("{:?}/{:?}/{:?}", c.zipf_theta, c.pareto_h, c.gaussian_stddev)),
+        ("latency_sample", |c| c.latency_sample.unwrap_or(1).to_string()),
+    ];
+
+    let mut problems = Vec::new();
+    for (name, field) in fields {
+        let expected = field(&first.output.test_info.config);
+        for run in &runs[1..] {
+            let value = field(&run.output.test_info.config);
+            if value != expected {
+                problems.push(format!("{}: {} has {}, {} has {}", name, first.name, expected, run.name, value));
+            }
+        }
+    }
+    problems
+}
+
+/// Merge runs into one aggregate result
+pub fn merge_runs(runs: &[RunResult]) -> JsonNodeOutput {
+    let histogram = if runs.iter().all(|r| r.histogram.is_some()) {
+        let mut merged = SimpleHistogram::new();
+        for hist in runs.iter().filter_map(|r| r.histogram.as_ref()) {
+            merged.merge(hist);
+        }
+        Some(merged)
+    } else {
+        None
+    };
+
+    let duration = runs.iter()
+        .map(|r| Duration::from_micros(r.output.final_summary.total_duration.micros))
+        .max()
+        .unwrap_or_default();
+    let aggregates: Vec<&JsonAggregateStats> = runs.iter().map(|r| &r.output.final_summary.aggregate).collect();
+
+    // Configuration of the first run, with the workers of all runs
+    let mut config = runs[0].output.test_info.config.clone();
+    config.threads = runs.iter().map(|r| r.output.test_info.config.threads).sum();
+
+    // Labels every run agrees on
+    let mut labels: BTreeMap<String, String> = runs[0].output.test_info.labels.clone();
+    labels.retain(|key, value| runs.iter().all(|r| r.output.test_info.labels.get(key) == Some(value)));
+
+    let per_worker = runs.iter()
+        .flat_map(|r| r.output.final_summary.per_worker.iter().map(move |w| {
+            let mut worker = w.clone();
+            worker.node_id = format!("{}/{}", r.name, w.node_id);
+            worker
+        }))
+        .collect();
+    let per_node = runs.iter()
+        .flat_map(|r| r.output.final_summary.per_node.iter().map(move |n| {
+            let mut node = n.clone();
+            node.node_id = format!("{}/{}", r.name, n.node_id);
+            node
+        }))
+        .collect();
+
+    JsonNodeOutput {
+        test_info: JsonTestInfo {
+            job_id: Some(runs.iter().map(|r| r.output.test_info.job_id.clone().unwrap_or_else(|| r.name.clone()))
+                .collect::<Vec<_>>().join("+")),
+            // Runs keep their own UUIDs in the per-worker and per-node IDs
+            run_uuid: None,
+            node_uid: None,
+            node_id: "merged".to_string(),
+            hostname: None,
+            start_time: runs.iter().map(|r| r.output.test_info.start_time.clone()).min().unwrap_or_default(),
+            end_time: runs.iter().filter_map(|r| r.output.test_info.end_time.clone()).max(),
+            duration: Some(JsonDuration::from_duration(duration)),
+            config,
+            labels,
+            origin: Some(JsonOrigin::current()),
+            capabilities: None,
+        },
+        time_series: Vec::new(),
+        final_summary: JsonFinalSummary {
+            total_duration: JsonDuration::from_duration(duration),
+            aggregate: merge_aggregates(&aggregates, histogram.as_ref()),
+            per_worker,
+            qd_ramp: None,
+            setup_phases: Vec::new(),
+            grow: None,
+            file_hints: None,
+            file_contention: None,
+            slowest_files: None,
+            osts: None,
+            cpu_time: None,
+            timer: None,
+            fragmentation: None,
+            per_worker_rate_sum: None,
+            per_node,
+            per_role: Vec::new(),
+            thread_scaling: None,
+            latency_target: None,
+            checkpoints: Vec::new(),
+            start_stop_skew: None,
+            drained: None,
+            io_paths: None,
+            rated: None,
+            space_usage: None,
+            page_cache_warning: None,
+            eof_clamped_reads: None,
+            queue_depth_limit: None,
+            latency_floor: None,
+        },
+    }
+}
+
+/// Sum counters and rates; merge latency from the histogram or the means
+fn merge_aggregates(stats: &[&JsonAggregateStats], histogram: Option<&SimpleHistogram>) -> JsonAggregateStats {
+    let sum = |f: fn(&JsonAggregateStats) -> u64| stats.iter().map(|s| f(s)).sum::<u64>();
+    let throughput = |f: fn(&JsonAggregateStats) -> &JsonThroughput| {
+        JsonThroughput::new(stats.iter().map(|s| f(s).bytes_per_sec).sum())
+    };
+    let resources: Vec<&JsonResourceUtil> = stats.iter().map(|s| &s.resource_utilization).collect();
+    let num_workers: usize = resources.iter().map(|r| r.num_workers).sum();
+    let cpu_total: f64 = resources.iter().map(|r| r.cpu_percent_total).sum();
+    let system_cpus: Option<usize> = resources.iter().map(|r| r.num_system_cpus).sum();
+    let memory_bytes: u64 = resources.iter().map(|r| r.memory_bytes).sum();
+
+    let metadata: Vec<&JsonMetadataOps> = stats.iter().map(|s| &s.metadata_operations).collect();
+    let metadata_sum = |f: fn(&JsonMetadataOps) -> u64| metadata.iter().map(|m| f(m)).sum::<u64>();
+
+    JsonAggregateStats {
+        read_ops: sum(|s| s.read_ops),
+        write_ops: sum(|s| s.write_ops),
+        read_bytes: sum(|s| s.read_bytes),
+        write_bytes: sum(|s| s.write_bytes),
+        total_ops: sum(|s| s.total_ops),
+        total_bytes: sum(|s| s.total_bytes),
+        read_iops: sum(|s| s.read_iops),
+        write_iops: sum(|s| s.write_iops),
+        total_iops: sum(|s| s.total_iops),
+        read_throughput: throughput(|s| &s.read_throughput),
+        write_throughput: throughput(|s| &s.write_throughput),
+        total_throughput: throughput(|s| &s.total_throughput),
+        latency: Some(match histogram {
+            Some(hist) => extract_latency_from_histogram(hist),
+            None => merge_latency(stats.iter().filter_map(|s| s.latency.as_ref().map(|l| (l, s.total_ops)))),
+        }),
+        read_latency: merge_latency(stats.iter().map(|s| (&s.read_latency, s.read_ops))),
+        write_latency: merge_latency(stats.iter().map(|s| (&s.write_latency, s.write_ops))),
+        errors: sum(|s| s.errors),
+        errors_read: sum(|s| s.errors_read),
+        errors_write: sum(|s| s.errors_write),
+        errors_metadata: sum(|s| s.errors_metadata),
+        resource_utilization: JsonResourceUtil {
+            cpu_percent_total: cpu_total,
+            cpu_percent_per_worker: cpu_total / num_workers.max(1) as f64,
+            cpu_percent_system: system_cpus.map_or(0.0, |cpus| cpu_total / cpus.max(1) as f64),
+            num_workers,
+            num_system_cpus: system_cpus,
+            memory_bytes,
+            memory_human: format!("{:.1} MB", memory_bytes as f64 / (1024.0 * 1024.0)),
+        },
+        metadata_operations: JsonMetadataOps {
+            open_ops: metadata_sum(|m| m.open_ops),
+            close_ops: metadata_sum(|m| m.close_ops),
+            stat_ops: metadata_sum(|m| m.stat_ops),
+            setattr_ops: metadata_sum(|m| m.setattr_ops),
+            mkdir_ops: metadata_sum(|m| m.mkdir_ops),
+            rmdir_ops: metadata_sum(|m| m.rmdir_ops),
+            unlink_ops: metadata_sum(|m| m.unlink_ops),
+            rename_ops: metadata_sum(|m| m.rename_ops),
+            readdir_ops: metadata_sum(|m| m.readdir_ops),
+            fsync_ops: metadata_sum(|m| m.fsync_ops),
+            total_ops: metadata_sum(|m| m.total_ops),
+            latency: None,
+        },
+        coverage: None,
+        block_size_verification: None,
+        queue_depth_stats: None,
+        page_faults: None,
+        submit_pacing: None,
+    }
+}
+
+/// Mean weighted by ops, lowest min and highest max; no percentiles
+fn merge_latency<'a>(latencies: impl Iterator<Item = (&'a JsonLatency, u64)>) -> JsonLatency {
+    let mut weighted = 0.0;
+    let mut ops = 0u64;
+    let mut min: Option<u64> = None;
+    let mut max: Option<u64> = None;
+    for (latency, count) in latencies.filter(|(_, count)| *count > 0) {
+        weighted += latency.mean.micros as f64 * count as f64;
+        ops += count;
+        if let Some(ref m) = latency.min {
+            min = Some(min.map_or(m.micros, |v| v.min(m.micros)));
+        }
+        if let Some(ref m) = latency.max {
+            max = Some(max.map_or(m.micros, |v| v.max(m.micros)));
+        }
+    }
+    let micros = |us: u64| JsonDuration::from_duration(Duration::from_micros(us));
+    JsonLatency {
+        min: min.map(micros),
+        max: max.map(micros),
+        mean: micros((weighted / ops.max(1) as f64).round() as u64),
+        p50: None,
+        p90: None,
+        p95: None,
+        p99: None,
+        p99_9: None,
+        percentiles: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(name: &str, block_size: usize, read_ops: u64, mean_us: u64, histogram: Option<SimpleHistogram>) -> RunResult {
+        let latency = |mean: u64| JsonLatency {
+            min: None, max: Some(JsonDuration::from_duration(Duration::from_micros(mean * 10))),
+            mean: JsonDuration::from_duration(Duration::from_micros(mean)),
+            p50: None, p90: None, p95: None, p99: None, p99_9: None, percentiles: Vec::new(),
+        };
+        let mut aggregate: JsonAggregateStats = serde_json::from_value(serde_json::json!({
+            "read_ops": read_ops, "write_ops": 0, "read_bytes": read_ops * 4096, "write_bytes": 0,
+            "total_ops": read_ops, "total_bytes": read_ops * 4096, "read_iops": read_ops, "write_iops": 0,
+            "total_iops": read_ops,
+            "read_throughput": {"bytes_per_sec": read_ops * 4096, "human": ""},
+            "write_throughput": {"bytes_per_sec": 0, "human": ""},
+            "total_throughput": {"bytes_per_sec": read_ops * 4096, "human": ""},
+            "read_latency": {"mean": {"micros": 0, "human": ""}},
+            "write_latency": {"mean": {"micros": 0, "human": ""}},
+            "errors": 1, "errors_read": 1, "errors_write": 0, "errors_metadata": 0,
+            "resource_utilization": {"cpu_percent_total": 50.0, "cpu_percent_per_worker": 50.0,
+                "cpu_percent_system": 50.0, "num_workers": 1, "num_system_cpus": 1,
+                "memory_bytes": 0, "memory_human": ""},
+            "metadata_operations": {"open_ops": 1, "close_ops": 1, "stat_ops": 0, "setattr_ops": 0,
+                "mkdir_ops": 0, "rmdir_ops": 0, "unlink_ops": 0, "rename_ops": 0, "readdir_ops": 0,
+                "fsync_ops": 0, "total_ops": 2},
+        })).unwrap();
+        aggregate.latency = Some(latency(mean_us));
+        aggregate.read_latency = latency(mean_us);
+        let output: JsonNodeOutput = serde_json::from_value(serde_json::json!({
+            "test_info": {"node_id": "aggregate", "start_time": "2026-01-01T00:00:00Z",
+                "config": {"threads": 1, "block_size": block_size, "file_size": 0, "engine": "sync",
+                    "queue_depth": 1, "read_percent": 100, "write_percent": 0}},
+            "time_series": [],
+            "final_summary": {"total_duration": {"micros": 1_000_000, "human": "1s"},
+                "aggregate": aggregate, "per_worker": []},
+        })).unwrap();
+        RunResult { name: name.to_string(), output, histogram }
+    }
+
+    #[test]
+    fn test_merge_sums_counters_and_weights_latency() {
+        let runs = [run("a", 4096, 1000, 10, None), run("b", 4096, 3000, 30, None)];
+        assert!(incompatibilities(&runs).is_empty());
+
+        let merged = merge_runs(&runs);
+        let aggregate = &merged.final_summary.aggregate;
+        assert_eq!(aggregate.read_ops, 4000);
+        assert_eq!(aggregate.total_iops, 4000);
+        assert_eq!(aggregate.errors, 2);
+        assert_eq!(aggregate.metadata_operations.total_ops, 4);
+        assert_eq!(aggregate.resource_utilization.num_workers, 2);
+        let latency = aggregate.latency.as_ref().unwrap();
+        assert_eq!(latency.mean.micros, 25);
+        assert_eq!(latency.max.as_ref().unwrap().micros, 300);
+        assert!(latency.p99.is_none());
+        assert_eq!(merged.test_info.config.threads, 2);
+
+        let mismatched = [run("a", 4096, 1000, 10, None), run("b", 8192, 1000, 10, None)];
+        assert_eq!(incompatibilities(&mismatched), vec!["block_size: a has 4096, b has 8192"]);
+    }
+
+    #[test]
+    fn test_percentiles_from_merged_histograms() {
+        let mut fast = SimpleHistogram::new();
+        let mut slow = SimpleHistogram::new();
+        for _ in 0..98 {
+            fast.record(Duration::from_micros(10));
+        }
+        for _ in 0..2 {
+            slow.record(Duration::from_millis(5));
+        }
+        let runs = [run("a", 4096, 98, 10, Some(fast)), run("b", 4096, 2, 5000, Some(slow))];
+        let merged = merge_runs(&runs);
+        let latency = merged.final_summary.aggregate.latency.unwrap();
+        assert_eq!(latency.p50.unwrap().micros, 10);
+        assert!(latency.p99.unwrap().micros >= 4096);
+    }
+}
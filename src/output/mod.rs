@@ -3,4 +3,27 @@
 pub mod text;
 pub mod json;
 pub mod csv;
+pub mod stream;
+pub mod checkpoint;
+pub mod alert;
+pub mod live;
+pub mod compat;
+pub mod merge;
+pub mod heatmap;
+pub mod page_cache;
+pub mod plot;
 // TODO: Add prometheus module
+
+use crate::config::{Config, SummaryFormat};
+use crate::stats::WorkerStats;
+use std::time::Duration;
+
+/// Print the end-of-test summary in the configured format
+pub fn print_summary(stats: &WorkerStats, duration: Duration, config: &Config, nodes: usize) {
+    match config.output.summary_format {
+        SummaryFormat::Ior => compat::print_ior_summary(stats, duration, config, nodes),
+        SummaryFormat::Mdtest => compat::print_mdtest_summary(stats, duration),
+        SummaryFormat::Text if config.output.quiet => text::print_summary_line(stats, duration),
+        SummaryFormat::Text => text::print_results(stats, duration, config, nodes),
+    }
+}
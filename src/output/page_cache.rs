@@ -0,0 +1,196 @@
+//! Detection of page-cache-dominated results
+//!
+//! A buffered read test whose data is cached measures memory, not the
+//! device. The results look plausible (just fast), so the summary warns when
+//! a buffered read test shows either of two symptoms:
+//!
+//! - read throughput above what the device can deliver: its rated throughput
+//!   (`--rated-throughput`) or, failing that, the interface link it is on
+//! - memory-speed latency: at least half of the reads completed in under
+//!   10us, faster than any block device round trip
+//!
+//! The warning then lists what makes caching likely: a working set that fits
+//! in RAM, `fadvise willneed`, or `fadvise dontneed`, which empties the cache
+//! only when files are opened.
+
+use crate::config::Config;
+use serde::{Deserialize, Serialize};
+use crate::stats::simple_histogram::{bucket_idx_to_micros, SimpleHistogram};
+use crate::stats::WorkerStats;
+use std::time::Duration;
+
+/// Reads faster than this didn't reach a device
+const MEMORY_LATENCY_US: u64 = 10;
+/// Share of memory-speed reads that makes the results cache-dominated
+const MEMORY_READ_FRACTION: f64 = 0.5;
+
+/// Why the results likely reflect the page cache
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PageCacheWarning {
+    pub reasons: Vec<String>,
+}
+
+/// What the results and configuration say about caching
+#[derive(Debug, Clone, Default)]
+struct Signals {
+    /// Read throughput (bytes/s)
+    read_bps: f64,
+    /// Device throughput ceiling (bytes/s) and where it came from
+    ceiling: Option<(u64, String)>,
+    /// Share of reads that completed at memory speed
+    memory_reads: f64,
+    /// Bytes the test reads from
+    working_set: Option<u64>,
+    /// Installed memory
+    ram: Option<u64>,
+    willneed: bool,
+    dontneed: bool,
+}
+
+/// Check a buffered read test for page-cache-dominated results
+pub fn detect(stats: &WorkerStats, duration: Duration, config: &Config) -> Option<PageCacheWarning> {
+    if config.workload.direct || stats.read_ops() == 0 {
+        return None;
+    }
+    let signals = Signals {
+        read_bps: crate::util::time::calculate_throughput(stats.read_bytes(), duration),
+        ceiling: device_ceiling(config),
+        memory_reads: memory_speed_fraction(stats.read_latency()),
+        working_set: working_set(config),
+        ram: total_memory(),
+        willneed: config.targets.iter().any(|target| target.fadvise_flags.willneed),
+        dontneed: config.targets.iter().any(|target| target.fadvise_flags.dontneed),
+    };
+    evaluate(&signals)
+}
+
+fn evaluate(signals: &Signals) -> Option<PageCacheWarning> {
+    let mut reasons = Vec::new();
+    if let Some((ceiling, ref source)) = signals.ceiling {
+        if signals.read_bps > ceiling as f64 {
+            reasons.push(format!("read throughput {} exceeds the {} ({})",
+                crate::util::time::format_throughput(signals.read_bps), source,
+                crate::util::time::format_throughput(ceiling as f64)));
+        }
+    }
+    if signals.memory_reads >= MEMORY_READ_FRACTION {
+        reasons.push(format!("{:.0}% of reads completed in under {}us (memory speed)",
+            signals.memory_reads * 100.0, MEMORY_LATENCY_US));
+    }
+    if reasons.is_empty() {
+        return None;
+    }
+
+    // Only symptoms raise the warning; these explain it
+    let fits = matches!((signals.working_set, signals.ram), (Some(set), Some(ram)) if set <= ram);
+    if let (true, Some(set), Some(ram)) = (fits, signals.working_set, signals.ram) {
+        reasons.push(format!("the working set ({}) fits in RAM ({})", format_size(set), format_size(ram)));
+    }
+    if signals.willneed {
+        reasons.push("fadvise willneed reads the files into the cache".to_string());
+    }
+    if signals.dontneed {
+        reasons.push("fadvise dontneed only empties the cache when files are opened; re-read blocks are cached again".to_string());
+    }
+    Some(PageCacheWarning { reasons })
+}
+
+/// Rated throughput if given, otherwise the first target's interface link
+fn device_ceiling(config: &Config) -> Option<(u64, String)> {
+    if let Some(throughput) = config.output.rated.as_ref().and_then(|rated| rated.throughput.as_ref()) {
+        let source = match throughput {
+            crate::config::RatedThroughput::Link { description, .. } => description.clone(),
+            _ => "rated throughput".to_string(),
+        };
+        if let Some(bytes_per_sec) = throughput.bytes_per_sec() {
+            return Some((bytes_per_sec, source));
+        }
+    }
+    let target = config.targets.first()?;
+    let link = crate::util::device_link::interface_link(&target.path)?;
+    Some((link.bytes_per_sec, link.description))
+}
+
+/// Share of the reads recorded below MEMORY_LATENCY_US
+fn memory_speed_fraction(latency: &SimpleHistogram) -> f64 {
+    if latency.is_empty() {
+        return 0.0;
+    }
+    let fast: u64 = latency.buckets().iter().enumerate()
+        .filter(|(idx, _)| bucket_idx_to_micros(*idx) < MEMORY_LATENCY_US)
+        .map(|(_, count)| count)
+        .sum();
+    fast as f64 / latency.len() as f64
+}
+
+/// Total size of the file targets (None for directories and block devices)
+fn working_set(config: &Config) -> Option<u64> {
+    config.targets.iter()
+        .map(|target| match target.target_type {
+            crate::config::TargetType::File => target.file_size
+                .or_else(|| std::fs::metadata(&target.path).ok().map(|m| m.len())),
+            _ => None,
+        })
+        .sum()
+}
+
+/// MemTotal from /proc/meminfo
+fn total_memory() -> Option<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    let kb: u64 = meminfo.lines()
+        .find_map(|line| line.strip_prefix("MemTotal:"))?
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse()
+        .ok()?;
+    Some(kb * 1024)
+}
+
+fn format_size(bytes: u64) -> String {
+    format!("{:.2} GB", bytes as f64 / 1e9)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate() {
+        let gb = 1_000_000_000u64;
+        let device = Signals {
+            read_bps: 2.0e9,
+            ceiling: Some((4 * gb, "PCIe 8.0 GT/s x4 link".to_string())),
+            memory_reads: 0.01,
+            working_set: Some(gb),
+            ram: Some(64 * gb),
+            ..Default::default()
+        };
+        // A small working set alone is no evidence
+        assert_eq!(evaluate(&device), None);
+
+        let cached = Signals { read_bps: 9.0e9, memory_reads: 0.97, ..device.clone() };
+        let warning = evaluate(&cached).unwrap();
+        assert_eq!(warning.reasons.len(), 3);
+        assert!(warning.reasons[0].contains("exceeds the PCIe 8.0 GT/s x4 link"));
+        assert!(warning.reasons[1].starts_with("97% of reads"));
+        assert!(warning.reasons[2].contains("fits in RAM"));
+
+        // Working set larger than RAM: only the symptom is listed
+        let large = Signals { memory_reads: 0.6, working_set: Some(128 * gb), dontneed: true, ..device };
+        let warning = evaluate(&large).unwrap();
+        assert_eq!(warning.reasons.len(), 2);
+        assert!(warning.reasons[1].starts_with("fadvise dontneed"));
+    }
+
+    #[test]
+    fn test_memory_speed_fraction() {
+        let mut hist = SimpleHistogram::new();
+        for _ in 0..3 {
+            hist.record(Duration::from_micros(2));
+        }
+        hist.record(Duration::from_micros(150));
+        assert_eq!(memory_speed_fraction(&hist), 0.75);
+        assert_eq!(memory_speed_fraction(&SimpleHistogram::new()), 0.0);
+    }
+}
@@ -0,0 +1,310 @@
+//! Charts of a run (`--plot DIR`)
+//!
+//! Renders SVG charts into DIR once the test ends, so a run produces
+//! shareable graphics without a separate analysis step:
+//!
+//! - `iops.svg`: read and write IOPS over time (all nodes)
+//! - `throughput.svg`: read and write throughput over time (MB/s)
+//! - `latency_cdf.svg`: share of reads and writes completed within each
+//!   latency, on a log scale, with p50/p99/p99.9 marked
+//! - `heatmap.svg`: accesses per offset range (with `--heatmap`); with
+//!   `--heatmap-series` one column per live interval, shaded by count
+//!
+//! Time charts come from the time-series intervals, so they need a duration
+//! run; charts without data are left out. Requires the `plot` build feature
+//! (enabled by default).
+
+use crate::output::heatmap::HeatmapExport;
+use crate::output::json::AggregatedSnapshot;
+use crate::stats::simple_histogram::{bucket_idx_to_micros, SimpleHistogram};
+use crate::stats::WorkerStats;
+use crate::Result;
+use std::path::{Path, PathBuf};
+
+/// Rates of one time-series interval, summed over the nodes
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SeriesPoint {
+    /// Seconds since the start of IO at the end of the interval
+    pub elapsed_secs: f64,
+    pub read_iops: f64,
+    pub write_iops: f64,
+    /// Bytes per second
+    pub read_bps: f64,
+    pub write_bps: f64,
+}
+
+/// Sum the nodes' intervals into one series
+///
+/// Interval `i` of each node counts toward point `i`; the first interval
+/// (startup) is left out, as in the JSON time-series.
+pub fn series_points(nodes: &[Vec<AggregatedSnapshot>]) -> Vec<SeriesPoint> {
+    let len = nodes.iter().map(Vec::len).max().unwrap_or(0);
+    (1..len)
+        .filter_map(|i| {
+            let mut point = SeriesPoint::default();
+            let mut found = false;
+            for snapshots in nodes {
+                let (Some(prev), Some(snapshot)) = (snapshots.get(i - 1), snapshots.get(i)) else { continue };
+                let secs = snapshot.elapsed.saturating_sub(prev.elapsed).as_secs_f64();
+                if secs <= 0.0 {
+                    continue;
+                }
+                found = true;
+                point.elapsed_secs = point.elapsed_secs.max(snapshot.elapsed.as_secs_f64());
+                point.read_iops += snapshot.read_ops as f64 / secs;
+                point.write_iops += snapshot.write_ops as f64 / secs;
+                point.read_bps += snapshot.read_bytes as f64 / secs;
+                point.write_bps += snapshot.write_bytes as f64 / secs;
+            }
+            found.then_some(point)
+        })
+        .collect()
+}
+
+/// Latency CDF: (upper bucket edge in us, percent of operations at or below it)
+pub fn latency_cdf(hist: &SimpleHistogram) -> Vec<(f64, f64)> {
+    if hist.is_empty() {
+        return Vec::new();
+    }
+    let total = hist.len() as f64;
+    let mut cumulative = 0u64;
+    hist.buckets().iter().enumerate()
+        .filter(|(_, &count)| count > 0)
+        .map(|(idx, &count)| {
+            cumulative += count;
+            let edge = bucket_idx_to_micros(idx + 1).max(1) as f64;
+            (edge, cumulative as f64 * 100.0 / total)
+        })
+        .collect()
+}
+
+/// Render the charts that have data into `dir`; returns the files written
+#[cfg(feature = "plot")]
+pub fn write_plots(
+    dir: &Path,
+    series: &[SeriesPoint],
+    stats: &WorkerStats,
+    heatmap: Option<&HeatmapExport>,
+) -> Result<Vec<PathBuf>> {
+    use anyhow::Context;
+
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create plot directory {}", dir.display()))?;
+    let mut written = Vec::new();
+    if series.len() >= 2 {
+        let iops: Vec<_> = series.iter().map(|p| (p.elapsed_secs, p.read_iops, p.write_iops)).collect();
+        written.push(render::time_chart(&dir.join("iops.svg"), "IOPS", "IOPS", &iops)?);
+        let mbps: Vec<_> = series.iter().map(|p| (p.elapsed_secs, p.read_bps / 1e6, p.write_bps / 1e6)).collect();
+        written.push(render::time_chart(&dir.join("throughput.svg"), "Throughput", "MB/s", &mbps)?);
+    }
+    if stats.read_ops() + stats.write_ops() > 0 {
+        written.push(render::latency_cdf(&dir.join("latency_cdf.svg"), stats.read_latency(), stats.write_latency())?);
+    }
+    if let Some(heatmap) = heatmap.filter(|heatmap| !heatmap.buckets.is_empty()) {
+        written.push(render::heatmap(&dir.join("heatmap.svg"), heatmap)?);
+    }
+    Ok(written)
+}
+
+/// Render the charts that have data into `dir` (not built in)
+#[cfg(not(feature = "plot"))]
+pub fn write_plots(
+    _dir: &Path,
+    _series: &[SeriesPoint],
+    _stats: &WorkerStats,
+    _heatmap: Option<&HeatmapExport>,
+) -> Result<Vec<PathBuf>> {
+    anyhow::bail!("--plot is not available (built without the plot feature)")
+}
+
+#[cfg(feature = "plot")]
+mod render {
+    use super::*;
+    use plotters::prelude::*;
+
+    const SIZE: (u32, u32) = (1024, 576);
+    const FONT: &str = "sans-serif";
+    const READ: RGBColor = RGBColor(31, 119, 180);
+    const WRITE: RGBColor = RGBColor(214, 39, 40);
+
+    /// Read and write values over time (elapsed, read, write)
+    pub fn time_chart(path: &Path, title: &str, unit: &str, points: &[(f64, f64, f64)]) -> Result<PathBuf> {
+        let max_x = points.iter().map(|p| p.0).fold(1.0, f64::max);
+        let max_y = points.iter().map(|p| p.1.max(p.2)).fold(0.0, f64::max).max(1.0) * 1.1;
+        let root = SVGBackend::new(path, SIZE).into_drawing_area();
+        root.fill(&WHITE)?;
+        let mut chart = ChartBuilder::on(&root)
+            .caption(title, (FONT, 24))
+            .margin(15)
+            .x_label_area_size(45)
+            .y_label_area_size(80)
+            .build_cartesian_2d(0.0..max_x, 0.0..max_y)?;
+        chart.configure_mesh()
+            .x_desc("Elapsed (s)")
+            .y_desc(unit)
+            .draw()?;
+        for (name, color, values) in [
+            ("read", READ, points.iter().map(|p| (p.0, p.1)).collect::<Vec<_>>()),
+            ("write", WRITE, points.iter().map(|p| (p.0, p.2)).collect::<Vec<_>>()),
+        ] {
+            if values.iter().all(|v| v.1 == 0.0) {
+                continue;
+            }
+            chart.draw_series(LineSeries::new(values, color.stroke_width(2)))?
+                .label(name)
+                .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color.stroke_width(2)));
+        }
+        chart.configure_series_labels()
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK)
+            .draw()?;
+        root.present()?;
+        Ok(path.to_path_buf())
+    }
+
+    /// Read and write latency CDFs on a log latency axis
+    pub fn latency_cdf(path: &Path, read: &SimpleHistogram, write: &SimpleHistogram) -> Result<PathBuf> {
+        let curves = [("read", READ, read), ("write", WRITE, write)];
+        let edges = curves.iter().flat_map(|(_, _, hist)| super::latency_cdf(hist)).map(|p| p.0);
+        let (min_x, max_x) = edges.fold((f64::MAX, 1.0f64), |(lo, hi), x| (lo.min(x), hi.max(x)));
+        let min_x = (min_x / 2.0).max(0.5);
+
+        let root = SVGBackend::new(path, SIZE).into_drawing_area();
+        root.fill(&WHITE)?;
+        let mut chart = ChartBuilder::on(&root)
+            .caption("Latency CDF", (FONT, 24))
+            .margin(15)
+            .x_label_area_size(45)
+            .y_label_area_size(80)
+            .build_cartesian_2d((min_x..max_x * 2.0).log_scale(), 0.0..100.0)?;
+        chart.configure_mesh()
+            .x_desc("Latency (us)")
+            .y_desc("Operations (%)")
+            .draw()?;
+        for (name, color, hist) in curves {
+            if hist.is_empty() {
+                continue;
+            }
+            let mut cdf = vec![(min_x, 0.0)];
+            cdf.extend(super::latency_cdf(hist));
+            chart.draw_series(LineSeries::new(cdf, color.stroke_width(2)))?
+                .label(name)
+                .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color.stroke_width(2)));
+            let marks = [50.0, 99.0, 99.9].map(|p| ((hist.percentile(p).as_micros() as f64).max(min_x), p));
+            chart.draw_series(marks.iter().map(|&(x, p)| Circle::new((x, p), 4, color.filled())))?;
+        }
+        chart.configure_series_labels()
+            .position(SeriesLabelPosition::LowerRight)
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK)
+            .draw()?;
+        root.present()?;
+        Ok(path.to_path_buf())
+    }
+
+    /// Accesses per offset range, over time when the series was recorded
+    pub fn heatmap(path: &Path, heatmap: &HeatmapExport) -> Result<PathBuf> {
+        let max_offset = heatmap.buckets.last().map_or(1, |b| b.end_offset).max(1) as f64 / 1e6;
+        let root = SVGBackend::new(path, SIZE).into_drawing_area();
+        root.fill(&WHITE)?;
+
+        if heatmap.intervals.is_empty() {
+            let max_ops = heatmap.buckets.iter().map(|b| b.ops).max().unwrap_or(0).max(1) as f64 * 1.1;
+            let mut chart = ChartBuilder::on(&root)
+                .caption("Block Access Heatmap", (FONT, 24))
+                .margin(15)
+                .x_label_area_size(45)
+                .y_label_area_size(80)
+                .build_cartesian_2d(0.0..max_offset, 0.0..max_ops)?;
+            chart.configure_mesh()
+                .x_desc("Offset (MB)")
+                .y_desc("Operations")
+                .draw()?;
+            chart.draw_series(heatmap.buckets.iter().map(|b| {
+                Rectangle::new(
+                    [(b.start_offset as f64 / 1e6, 0.0), (b.end_offset as f64 / 1e6, b.ops as f64)],
+                    READ.filled(),
+                )
+            }))?;
+        } else {
+            let max_x = heatmap.intervals.last().map_or(1.0, |i| i.elapsed_secs).max(0.001);
+            let max_ops = heatmap.intervals.iter()
+                .flat_map(|i| i.ops.iter().copied())
+                .max().unwrap_or(0).max(1) as f64;
+            let mut chart = ChartBuilder::on(&root)
+                .caption("Block Access Heatmap", (FONT, 24))
+                .margin(15)
+                .x_label_area_size(45)
+                .y_label_area_size(80)
+                .build_cartesian_2d(0.0..max_x, 0.0..max_offset)?;
+            chart.configure_mesh()
+                .disable_mesh()
+                .x_desc("Elapsed (s)")
+                .y_desc("Offset (MB)")
+                .draw()?;
+            let mut start = 0.0;
+            for interval in &heatmap.intervals {
+                let end = interval.elapsed_secs;
+                chart.draw_series(heatmap.buckets.iter().map(|b| {
+                    let ops = interval.ops.get(b.bucket).copied().unwrap_or(0) as f64;
+                    Rectangle::new(
+                        [(start, b.start_offset as f64 / 1e6), (end, b.end_offset as f64 / 1e6)],
+                        shade(ops / max_ops).filled(),
+                    )
+                }))?;
+                start = end;
+            }
+        }
+        root.present()?;
+        Ok(path.to_path_buf())
+    }
+
+    /// White for no accesses through to dark red for the busiest cell
+    fn shade(fraction: f64) -> RGBColor {
+        let t = fraction.clamp(0.0, 1.0).sqrt();
+        let channel = |from: f64, to: f64| (from + (to - from) * t).round() as u8;
+        RGBColor(channel(255.0, 165.0), channel(255.0, 0.0), channel(255.0, 38.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn snapshot(elapsed_ms: u64, read_ops: u64) -> AggregatedSnapshot {
+        let mut snapshot = AggregatedSnapshot::from_worker_snapshots(&[], Duration::from_millis(elapsed_ms), false);
+        snapshot.read_ops = read_ops;
+        snapshot.read_bytes = read_ops * 4096;
+        snapshot
+    }
+
+    #[test]
+    fn test_series_points_sum_nodes() {
+        let nodes = vec![
+            vec![snapshot(1000, 50), snapshot(2000, 100), snapshot(2500, 100)],
+            vec![snapshot(1000, 50), snapshot(2000, 300)],
+        ];
+        let points = series_points(&nodes);
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[0].elapsed_secs, 2.0);
+        assert_eq!(points[0].read_iops, 400.0);
+        assert_eq!(points[0].read_bps, 400.0 * 4096.0);
+        // Only the first node has a third interval (half a second)
+        assert_eq!(points[1].read_iops, 200.0);
+    }
+
+    #[test]
+    fn test_latency_cdf() {
+        let mut hist = SimpleHistogram::new();
+        for us in [10, 10, 10, 1000] {
+            hist.record(Duration::from_micros(us));
+        }
+        let cdf = latency_cdf(&hist);
+        assert_eq!(cdf.len(), 2);
+        assert_eq!(cdf[0].1, 75.0);
+        assert_eq!(cdf[1].1, 100.0);
+        assert!(cdf[0].0 > 10.0 && cdf[1].0 > 1000.0);
+        assert!(latency_cdf(&SimpleHistogram::new()).is_empty());
+    }
+}
@@ -0,0 +1,409 @@
+//! Streaming time-series output
+//!
+//! The final JSON and CSV files are written once the test ends, from the
+//! interval snapshots buffered in memory. If the coordinator crashes or is
+//! killed, those buffers are lost. `TimeSeriesStream` appends every interval
+//! record to disk as it is collected, so a partial run still leaves usable
+//! time-series data:
+//!
+//! - **CSV**: rows go to the final CSV path (`aggregate.csv` in directory mode)
+//!   in the aggregate per-node format; the complete file replaces it at the end
+//! - **JSON**: records go to a JSON Lines sidecar (`<name>.partial.jsonl`, or
+//!   `aggregate.partial.jsonl` in directory mode), removed once the complete
+//!   JSON output has been written
+//!
+//! Each record is written with a single `write` on an unbuffered file, so it
+//! reaches the kernel before the next heartbeat is read.
+//!
+//! # Rotation
+//!
+//! With `--rotate-interval` or `--rotate-size`, long runs are split into
+//! numbered segments, and the segments are the time-series output: the final
+//! CSV is not rewritten and the final JSON carries no `time_series`.
+//!
+//! - **CSV**: `<name>.0001.csv`, `<name>.0002.csv`, ... each with a header row
+//! - **JSON**: `<name>.0001.jsonl`, `<name>.0002.jsonl`, ...
+//!
+//! With `--rotate-compress` each segment is gzipped once it is closed
+//! (`<name>.0001.csv.gz`). An index (`<name>.csv.index.json`,
+//! `<name>.jsonl.index.json`) lists the segments in order with their record
+//! counts and time ranges. It is rewritten at every rotation, so it also
+//! describes an interrupted run.
+
+use crate::config::{Config, OutputConfig};
+use crate::output::csv::{CsvOptions, CsvWriter};
+use crate::output::json::{build_interval_record, format_timestamp, AggregatedSnapshot};
+use crate::util::resource::ResourceStats;
+use crate::Result;
+use anyhow::Context;
+use serde::Serialize;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Interval records appended to disk while the test runs
+pub struct TimeSeriesStream {
+    csv: Option<(CsvWriter, Option<Segments>)>,
+    json: Option<(PathBuf, File, Option<Segments>)>,
+    output: OutputConfig,
+    workers_per_node: usize,
+}
+
+impl TimeSeriesStream {
+    /// Open the stream files for the configured outputs
+    pub fn open(config: &Config) -> Result<Self> {
+        let rotation = Rotation::from_config(&config.output);
+
+        let csv = match config.output.csv_output {
+            Some(ref path) => {
+                let path = if is_output_dir(path) {
+                    std::fs::create_dir_all(path)
+                        .context("Failed to create CSV output directory")?;
+                    path.join("aggregate.csv")
+                } else {
+                    path.clone()
+                };
+                let segments = rotation.map(|r| Segments::new(path.with_extension(""), "csv", r));
+                let path = segments.as_ref().map(Segments::path).unwrap_or(path);
+                Some((open_csv(&path, &config.output)?, segments))
+            }
+            None => None,
+        };
+
+        let json = match config.output.json_output {
+            Some(ref path) => {
+                let segments = rotation.map(|r| Segments::new(json_stem(path), "jsonl", r));
+                let path = segments.as_ref().map(Segments::path).unwrap_or_else(|| partial_json_path(path));
+                if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                    std::fs::create_dir_all(parent)
+                        .context("Failed to create JSON output directory")?;
+                }
+                let file = File::create(&path)
+                    .with_context(|| format!("Failed to create {}", path.display()))?;
+                Some((path, file, segments))
+            }
+            None => None,
+        };
+
+        Ok(Self { csv, json, output: config.output.clone(), workers_per_node: config.workers.threads })
+    }
+
+    /// Whether the time-series is written as rotated segments
+    pub fn is_rotating(&self) -> bool {
+        self.csv.as_ref().is_some_and(|(_, segments)| segments.is_some())
+            || self.json.as_ref().is_some_and(|(_, _, segments)| segments.is_some())
+    }
+
+    /// Append one node's interval snapshot (deltas since the previous one)
+    pub fn append(
+        &mut self,
+        node_id: &str,
+        snapshot: &AggregatedSnapshot,
+        interval: Duration,
+        resource_stats: Option<&ResourceStats>,
+    ) -> Result<()> {
+        if let Some((ref mut csv, ref mut segments)) = self.csv {
+            csv.append_snapshot_with_node(node_id, snapshot, interval.as_secs_f64(), resource_stats, self.workers_per_node)?;
+
+            if let Some(segments) = segments {
+                segments.record();
+                if segments.is_due(csv.bytes_written()?) {
+                    // The new writer replaces (and closes) the old one before it is compressed
+                    *csv = open_csv(&segments.next_path(), &self.output)?;
+                    segments.rotate()?;
+                }
+            }
+        }
+
+        if let Some((ref mut path, ref mut file, ref mut segments)) = self.json {
+            let record = build_interval_record(node_id, snapshot, interval, resource_stats.copied(), self.workers_per_node);
+            let mut line = serde_json::to_vec(&record)?;
+            line.push(b'\n');
+            file.write_all(&line)?;
+
+            if let Some(segments) = segments {
+                segments.record();
+                if segments.is_due(file.metadata()?.len()) {
+                    *path = segments.next_path();
+                    *file = File::create(&*path)
+                        .with_context(|| format!("Failed to create {}", path.display()))?;
+                    segments.rotate()?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Close the stream files
+    ///
+    /// Rotated segments are closed and indexed; their index files are
+    /// returned. Otherwise the JSON Lines sidecar is removed if the complete
+    /// JSON output is on disk (`json_written`).
+    pub fn finish(self, json_written: bool) -> Result<Vec<PathBuf>> {
+        let mut indexes = Vec::new();
+
+        if let Some((csv, Some(segments))) = self.csv {
+            drop(csv);
+            indexes.push(segments.finish()?);
+        }
+
+        match self.json {
+            Some((_, file, Some(segments))) => {
+                drop(file);
+                indexes.push(segments.finish()?);
+            }
+            Some((path, file, None)) if json_written => {
+                drop(file);
+                std::fs::remove_file(&path)
+                    .with_context(|| format!("Failed to remove {}", path.display()))?;
+            }
+            _ => {}
+        }
+
+        Ok(indexes)
+    }
+}
+
+fn open_csv(path: &Path, output: &OutputConfig) -> Result<CsvWriter> {
+    CsvWriter::new(path, &CsvOptions::from_output(output, true))
+        .with_context(|| format!("Failed to create {}", path.display()))
+}
+
+/// When to start a new segment
+#[derive(Debug, Clone, Copy)]
+struct Rotation {
+    interval: Option<Duration>,
+    size: Option<u64>,
+    compress: bool,
+}
+
+impl Rotation {
+    fn from_config(output: &OutputConfig) -> Option<Self> {
+        output.is_rotating().then(|| Self {
+            interval: output.rotate_interval.map(Duration::from_secs),
+            size: output.rotate_size,
+            compress: output.rotate_compress,
+        })
+    }
+}
+
+/// One segment file in the index
+#[derive(Debug, Clone, Serialize)]
+struct SegmentEntry {
+    file: String,
+    records: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end_time: Option<String>,
+    /// False for the segment still being written
+    complete: bool,
+}
+
+/// Index file listing the segments of one rotated output
+#[derive(Serialize)]
+struct SegmentIndex<'a> {
+    format: &'a str,
+    compressed: bool,
+    segments: Vec<&'a SegmentEntry>,
+}
+
+/// Numbered segment files of one rotated output
+struct Segments {
+    stem: PathBuf,
+    ext: &'static str,
+    rotation: Rotation,
+    closed: Vec<SegmentEntry>,
+    current: SegmentEntry,
+    opened: Instant,
+}
+
+impl Segments {
+    fn new(stem: PathBuf, ext: &'static str, rotation: Rotation) -> Self {
+        let mut segments = Self {
+            stem,
+            ext,
+            rotation,
+            closed: Vec::new(),
+            current: SegmentEntry { file: String::new(), records: 0, start_time: None, end_time: None, complete: false },
+            opened: Instant::now(),
+        };
+        segments.current.file = segments.file_name(1);
+        segments
+    }
+
+    fn file_name(&self, number: usize) -> String {
+        let name = self.stem.file_name().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+        format!("{}.{:04}.{}", name, number, self.ext)
+    }
+
+    /// Path of the segment being written
+    fn path(&self) -> PathBuf {
+        self.stem.with_file_name(&self.current.file)
+    }
+
+    /// Path of the segment that follows the current one
+    fn next_path(&self) -> PathBuf {
+        self.stem.with_file_name(self.file_name(self.closed.len() + 2))
+    }
+
+    fn index_path(&self) -> PathBuf {
+        let name = self.stem.file_name().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+        self.stem.with_file_name(format!("{}.{}.index.json", name, self.ext))
+    }
+
+    /// Count a record written to the current segment
+    fn record(&mut self) {
+        let now = format_timestamp(SystemTime::now());
+        if self.current.records == 0 {
+            self.current.start_time = Some(now.clone());
+        }
+        self.current.end_time = Some(now);
+        self.current.records += 1;
+    }
+
+    /// Whether the current segment (`size` bytes so far) should be closed
+    fn is_due(&self, size: u64) -> bool {
+        self.current.records > 0
+            && (self.rotation.interval.is_some_and(|interval| self.opened.elapsed() >= interval)
+                || self.rotation.size.is_some_and(|limit| size >= limit))
+    }
+
+    /// Close the current segment and continue in the next one
+    fn rotate(&mut self) -> Result<()> {
+        self.close_current()?;
+        self.current = SegmentEntry {
+            file: self.file_name(self.closed.len() + 1),
+            records: 0,
+            start_time: None,
+            end_time: None,
+            complete: false,
+        };
+        self.opened = Instant::now();
+        self.write_index(true)
+    }
+
+    /// Close the last segment and write the final index
+    fn finish(mut self) -> Result<PathBuf> {
+        if self.current.records == 0 && !self.closed.is_empty() {
+            // Rotated just before the end; drop the empty segment
+            let _ = std::fs::remove_file(self.path());
+        } else {
+            self.close_current()?;
+        }
+        self.write_index(false)?;
+        Ok(self.index_path())
+    }
+
+    fn close_current(&mut self) -> Result<()> {
+        let mut entry = self.current.clone();
+        if self.rotation.compress {
+            entry.file = gzip(&self.path())?;
+        }
+        entry.complete = true;
+        self.closed.push(entry);
+        Ok(())
+    }
+
+    /// Write the index of closed segments (and the one being written, if `with_current`)
+    fn write_index(&self, with_current: bool) -> Result<()> {
+        let index = SegmentIndex {
+            format: self.ext,
+            compressed: self.rotation.compress,
+            segments: self.closed.iter()
+                .chain(with_current.then_some(&self.current))
+                .collect(),
+        };
+        // Replace atomically so readers never see a partial index
+        let path = self.index_path();
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_vec_pretty(&index)?)
+            .with_context(|| format!("Failed to write {}", tmp.display()))?;
+        std::fs::rename(&tmp, &path)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(())
+    }
+}
+
+/// Gzip a closed segment in place, returning the compressed file name
+fn gzip(path: &Path) -> Result<String> {
+    let name = format!("{}.gz", path.file_name().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default());
+    let gz_path = path.with_file_name(&name);
+    let mut input = File::open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    let output = File::create(&gz_path)
+        .with_context(|| format!("Failed to create {}", gz_path.display()))?;
+    let mut encoder = flate2::write::GzEncoder::new(output, flate2::Compression::default());
+    std::io::copy(&mut input, &mut encoder)?;
+    encoder.finish()?;
+    std::fs::remove_file(path)
+        .with_context(|| format!("Failed to remove {}", path.display()))?;
+    Ok(name)
+}
+
+/// Output paths without an extension (or ending in '/') are directories
+fn is_output_dir(path: &Path) -> bool {
+    path.is_dir()
+        || path.to_string_lossy().ends_with('/')
+        || !path.to_string_lossy().contains('.')
+}
+
+/// Path without extension that JSON time-series files are named after
+fn json_stem(path: &Path) -> PathBuf {
+    if is_output_dir(path) {
+        path.join("aggregate")
+    } else {
+        let stem = path.file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "results".to_string());
+        path.with_file_name(stem)
+    }
+}
+
+/// JSON Lines sidecar for a JSON output path
+fn partial_json_path(path: &Path) -> PathBuf {
+    let stem = json_stem(path);
+    let name = stem.file_name().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+    stem.with_file_name(format!("{}.partial.jsonl", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partial_json_path() {
+        assert_eq!(partial_json_path(Path::new("/tmp/out/results.json")),
+                   PathBuf::from("/tmp/out/results.partial.jsonl"));
+        assert_eq!(partial_json_path(Path::new("/tmp/out/run1/")),
+                   PathBuf::from("/tmp/out/run1/aggregate.partial.jsonl"));
+    }
+
+    #[test]
+    fn test_segment_rotation() {
+        let dir = tempfile::tempdir().unwrap();
+        let rotation = Rotation { interval: None, size: Some(10), compress: true };
+        let mut segments = Segments::new(dir.path().join("results"), "csv", rotation);
+        assert_eq!(segments.path(), dir.path().join("results.0001.csv"));
+
+        std::fs::write(segments.path(), "header\nrow\n").unwrap();
+        assert!(!segments.is_due(11));  // nothing recorded yet
+        segments.record();
+        assert!(segments.is_due(11));
+        std::fs::write(segments.next_path(), "header\n").unwrap();
+        segments.rotate().unwrap();
+        assert_eq!(segments.path(), dir.path().join("results.0002.csv"));
+
+        // The empty last segment is dropped; the first was compressed
+        let index_path = segments.finish().unwrap();
+        let index: serde_json::Value = serde_json::from_slice(&std::fs::read(&index_path).unwrap()).unwrap();
+        let files: Vec<&str> = index["segments"].as_array().unwrap().iter()
+            .map(|s| s["file"].as_str().unwrap())
+            .collect();
+        assert_eq!(files, ["results.0001.csv.gz"]);
+        assert!(dir.path().join("results.0001.csv.gz").exists());
+        assert!(!dir.path().join("results.0002.csv").exists());
+    }
+}
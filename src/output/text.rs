@@ -9,12 +9,13 @@ use crate::util::time::{calculate_iops, calculate_throughput, format_rate, forma
 /// Displays comprehensive statistics including:
 /// - Operations and IOPS
 /// - Throughput
-/// - Latency percentiles
+/// - Latency percentiles (also split by reads and writes for mixed workloads)
 /// - Metadata operations
 /// - Resource utilization
 /// - Coverage (if heatmap enabled)
 /// - Heatmap visualization (if enabled)
-pub fn print_results(stats: &WorkerStats, duration: std::time::Duration, config: &Config) {
+/// - Setup/teardown phase breakdown
+pub fn print_results(stats: &WorkerStats, duration: std::time::Duration, config: &Config, nodes: usize) {
     println!("═══════════════════════════════════════════════════════════");
     println!("                    TEST RESULTS");
     println!("═══════════════════════════════════════════════════════════");
@@ -22,6 +23,15 @@ pub fn print_results(stats: &WorkerStats, duration: std::time::Duration, config:
     
     // Print elapsed time
     println!("Elapsed Time: {:.3}s", duration.as_secs_f64());
+    
+    // Engine the workers ran, and the requested one when it differs
+    let ran: Vec<String> = stats.engines().iter().map(|engine| engine.to_string()).collect();
+    let effective = if ran.is_empty() { config.workload.effective_engine().to_string() } else { ran.join(", ") };
+    if effective == config.workload.engine.to_string() {
+        println!("Engine: {}", effective);
+    } else {
+        println!("Engine: {} (requested {}; --no-engine-fallback runs it as requested)", effective, config.workload.engine);
+    }
     println!();
     
     // Calculate IOPS and throughput
@@ -47,10 +57,18 @@ pub fn print_results(stats: &WorkerStats, duration: std::time::Duration, config:
              format_number(stats.total_ops()), 
              format_bytes(stats.total_bytes()),
              format_rate(total_iops));
+    // Each worker over its own duration (merged results only)
+    if let Some(rate_sum) = stats.rate_sum() {
+        println!("  Sum of per-worker rates: {} IOPS", format_rate(rate_sum.total_iops()));
+    }
     
     if stats.errors() > 0 {
         println!("  Errors: {}", stats.errors());
     }
+    // Reads of the partial last block, shortened to end at EOF (not errors)
+    if stats.eof_clamped() > 0 {
+        println!("  Clamped at EOF: {} reads", format_number(stats.eof_clamped()));
+    }
     
     // Verification statistics (only if verification enabled)
     if stats.verify_ops() > 0 {
@@ -77,10 +95,11 @@ pub fn print_results(stats: &WorkerStats, duration: std::time::Duration, config:
             let rewrites = stats.rewrite_percent();
             
             println!("Coverage:");
-            println!("  Unique blocks: {} / {} ({:.2}%)", 
+            println!("  Unique blocks: {} / {} ({:.2}%){}", 
                      format_number(unique_blocks),
                      format_number(total_blocks),
-                     coverage);
+                     coverage,
+                     if stats.coverage_is_estimate() { " (estimated, --stats-mem-limit)" } else { "" });
             println!("  Rewrites:      {} ops ({:.2}% of operations)",
                      format_number(stats.total_ops() - unique_blocks),
                      rewrites);
@@ -95,35 +114,67 @@ pub fn print_results(stats: &WorkerStats, duration: std::time::Duration, config:
     println!("  Read:  {}", format_throughput(read_throughput));
     println!("  Write: {}", format_throughput(write_throughput));
     println!("  Total: {}", format_throughput(total_throughput));
+    if let Some(rate_sum) = stats.rate_sum() {
+        println!("  Sum of per-worker rates: {}", format_throughput(rate_sum.total_bps()));
+    }
     
     println!();
     
+    // Achieved vs rated device performance (--rated-iops, --rated-throughput)
+    if let Some(ref rated) = config.output.rated {
+        println!("Rated Performance:");
+        if let Some(iops) = rated.iops {
+            println!("  IOPS:       {} of {} rated ({:.1}%)",
+                     format_rate(total_iops), format_rate(iops as f64), total_iops / iops as f64 * 100.0);
+        }
+        if let Some(ref throughput) = rated.throughput {
+            if let Some(rate) = throughput.bytes_per_sec() {
+                println!("  Throughput: {} of {} rated ({:.1}%, {})",
+                         format_throughput(total_throughput), format_throughput(rate as f64),
+                         total_throughput / rate as f64 * 100.0, throughput.source());
+            }
+        }
+        println!();
+    }
+    
+    // Buffered reads served from memory rather than the device
+    if let Some(warning) = crate::output::page_cache::detect(stats, duration, config) {
+        print_page_cache_warning(&warning);
+    }
+    
+    // Transfer size distribution (only when not every IO had the same size)
+    print_io_sizes(stats);
+    
     // Latency statistics
-    println!("Latency:");
-    let hist = stats.io_latency();
+    let percentiles = config.output.report_percentiles();
+    print_latency_section("Latency", stats.io_latency(), percentiles);
+    print_latency_sampling(config.runtime.latency_sample, stats.io_latency(), percentiles);
+    print_latency_floor(stats);
     
-    if hist.len() > 0 {
-        let min = hist.min();
-        println!("  Min:    {:?}", min);
-        
-        let mean = hist.mean();
-        println!("  Mean:   {:?}", mean);
-        
-        let max = hist.max();
-        println!("  Max:    {:?}", max);
-        
+    // Mixed workloads: reads and writes usually have very different tails
+    if !stats.read_latency().is_empty() && !stats.write_latency().is_empty() {
         println!();
-        println!("  Percentiles:");
-        for &p in &[50.0, 90.0, 95.0, 99.0, 99.9, 99.99] {
-            let val = hist.percentile(p);
-            println!("    p{:5.2}: {:?}", p, val);
-        }
-    } else {
-        println!("  No latency data collected");
+        print_latency_section("Read Latency", stats.read_latency(), percentiles);
+        println!();
+        print_latency_section("Write Latency", stats.write_latency(), percentiles);
     }
     
     println!();
     
+    // Queue depth ramp (only when --qd-ramp was used)
+    if let Some(ramp) = stats.qd_ramp() {
+        print_qd_ramp(ramp);
+    }
+    
+    // Queue depth reduced to what the target or engine could use
+    if let Some(limit) = stats.queue_depth_limit() {
+        println!("⚠ Queue depth reduced from {} to {}", limit.configured, limit.effective);
+        for reason in &limit.reasons {
+            println!("  - {}", reason);
+        }
+        println!();
+    }
+    
     // Metadata operations
     let metadata_ops = stats.metadata.total_ops();
     if metadata_ops > 0 {
@@ -135,6 +186,13 @@ pub fn print_results(stats: &WorkerStats, duration: std::time::Duration, config:
         println!();
     }
     
+    print_file_hints(stats);
+    print_file_contention(stats);
+    print_file_latency(stats);
+    print_ost_stats(stats, duration);
+    print_cpu_time(stats);
+    print_timer(stats, config.output.is_verbose());
+    
     // Lock latency statistics (if locking was enabled)
     if let Some(ref lock_hist) = stats.lock_latency() {
         if lock_hist.len() > 0 {
@@ -156,6 +214,9 @@ pub fn print_results(stats: &WorkerStats, duration: std::time::Duration, config:
             }
         }
     }
+    if config.output.distribution_report {
+        print_distribution_report(stats, config, nodes);
+    }
     
     // Resource utilization (CPU and memory)
     if let Some(resource_stats) = stats.resource_stats() {
@@ -185,9 +246,917 @@ pub fn print_results(stats: &WorkerStats, duration: std::time::Duration, config:
         println!();
     }
     
+    // Page faults (mmap engine only)
+    if let Some(faults) = stats.page_faults() {
+        let secs = duration.as_secs_f64().max(f64::EPSILON);
+        let ops = stats.total_ops().max(1) as f64;
+        println!("Page Faults:");
+        println!("  Minor:  {} ({:.0}/s, {:.3}/op)",
+                 format_number(faults.minor), faults.minor as f64 / secs, faults.minor as f64 / ops);
+        println!("  Major:  {} ({:.0}/s, {:.3}/op)",
+                 format_number(faults.major), faults.major as f64 / secs, faults.major as f64 / ops);
+        println!();
+    }
+    
+    // Submission spacing (think time only): bursts show as a high CV
+    if let Some(gaps) = stats.submit_gaps().filter(|gaps| gaps.count > 0) {
+        println!("Submit Pacing:");
+        println!("  Mean gap: {:.1}us per worker", gaps.mean_us());
+        println!("  Gap CV:   {:.2} (0 = evenly spaced)", gaps.cv());
+        println!();
+    }
+    
+    // Append-grow: extending writes vs overwrites, each rated over its own phase
+    if let Some(grow) = stats.grow_stats() {
+        let grow_secs = grow.target_reached_secs.unwrap_or(duration.as_secs_f64());
+        let overwrite_secs = duration.as_secs_f64() - grow_secs;
+        println!("Append-Grow:");
+        for (name, writes, secs) in [("Extend:   ", &grow.extend, grow_secs), ("Overwrite:", &grow.overwrite, overwrite_secs)] {
+            if writes.ops == 0 {
+                continue;
+            }
+            println!("  {} {} ops ({}) - {:.0} IOPS, mean {:?}, p99 {:?}",
+                     name, format_number(writes.ops), format_bytes(writes.bytes),
+                     writes.ops as f64 / secs.max(f64::EPSILON),
+                     writes.latency.mean(), writes.latency.percentile(99.0));
+        }
+        match grow.target_reached_secs {
+            Some(secs) => println!("  File size: {} (target reached after {:.3}s)", format_bytes(grow.final_size), secs),
+            None => println!("  File size: {} (target not reached)", format_bytes(grow.final_size)),
+        }
+        println!();
+    }
+    
+    // O_DIRECT vs buffered operations of the mix, each rated over the whole test
+    if let Some(paths) = stats.io_paths() {
+        println!("IO Paths:");
+        for (name, path) in [("Direct:  ", &paths.direct), ("Buffered:", &paths.buffered)] {
+            println!("  {} {} reads, {} writes ({}) - {:.0} IOPS, mean {:?}, p99 {:?}",
+                     name, format_number(path.read_ops), format_number(path.write_ops), format_bytes(path.bytes),
+                     path.ops() as f64 / duration.as_secs_f64().max(f64::EPSILON),
+                     path.latency.mean(), path.latency.percentile(99.0));
+        }
+        println!();
+    }
+    
+    // Operations completing after the duration, left out of the results above
+    if let Some(drained) = stats.drained() {
+        println!("Drained After Duration:");
+        println!("  {} ops ({}) excluded from the results", format_number(drained.ops), format_bytes(drained.bytes));
+        if let Some(ref latency) = drained.latency {
+            if !latency.is_empty() {
+                println!("  Latency: mean {:?}, p50 {:?}, p99 {:?}, max {:?}",
+                         latency.mean(), latency.percentile(50.0), latency.percentile(99.0), latency.max());
+            }
+        }
+        println!();
+    }
+    
+    // Fragmentation of the target files after the test (--fragmentation)
+    if let Some(frag) = stats.fragmentation() {
+        println!("Fragmentation:");
+        if frag.files > 0 {
+            println!("  Files:       {}", format_number(frag.files));
+            println!("  Extents:     {} ({:.1} per file, max {})",
+                     format_number(frag.extents), frag.extents_per_file(), format_number(frag.max_extents));
+            println!("  Avg extent:  {}", format_bytes(frag.avg_extent_size()));
+        }
+        if frag.unsupported > 0 {
+            println!("  Not mapped:  {} files (FIEMAP unsupported or file missing)", format_number(frag.unsupported));
+        }
+        println!();
+    }
+    
+    // Filesystem space consumed by the test (--space-usage)
+    if let Some(usage) = stats.space_usage() {
+        println!("Space Usage:");
+        for fs in &usage.filesystems {
+            println!("  {}: {} free before, {} after (of {})",
+                     fs.path, format_bytes(fs.free_before), format_bytes(fs.free_after), format_bytes(fs.total_bytes));
+        }
+        println!("  Consumed: {}", format_space_consumed(usage, stats.write_bytes()));
+        println!();
+    }
+    
+    // Buffer pool (only interesting with several IO sizes or when ops waited)
+    let pool = stats.buffer_pool_stats();
+    if pool.len() > 1 || pool.iter().any(|c| c.borrowed > 0 || c.starved > 0) {
+        println!("Buffer Pool:");
+        for class in pool {
+            println!("  {:>10}: {} buffers, low water {}, borrowed {}, starved {} (waited {:.2} ms)",
+                     format_bytes(class.size as u64), class.total, class.low_water,
+                     format_number(class.borrowed), format_number(class.starved),
+                     class.wait_ns as f64 / 1_000_000.0);
+        }
+        println!();
+    }
+    
+    if config.output.is_verbose() {
+        print_engine_stats(stats);
+        print_live_cadence(stats);
+    }
+    
+    // Setup/teardown phases (time outside the measured IO phase)
+    let setup = stats.setup_timings();
+    if !setup.is_empty() {
+        println!("Setup Breakdown:");
+        for phase in setup.phases() {
+            println!("  {:<28} {:>10.3}s", phase.name, phase.duration().as_secs_f64());
+        }
+        println!("  {:<28} {:>10.3}s", "IO test", duration.as_secs_f64());
+        println!();
+    }
+    
     println!("═══════════════════════════════════════════════════════════");
 }
 
+/// Print the cost of fadvise/madvise hints on files opened during the test
+pub fn print_file_hints(stats: &WorkerStats) {
+    let hints = stats.hints();
+    if hints.is_empty() {
+        return;
+    }
+    
+    println!("File Hints (file list):");
+    println!("  Applied: {} files in {:.3} ms", hints.applied, hints.time().as_secs_f64() * 1000.0);
+    if hints.cached > 0 {
+        println!("  Cached:  {} reopens (page-cache hints already issued)", hints.cached);
+    }
+    println!();
+}
+
+/// Print busy-file collisions in shared file-list mode
+pub fn print_file_contention(stats: &WorkerStats) {
+    let contention = stats.contention();
+    if contention.is_empty() {
+        return;
+    }
+    
+    println!("Busy Files (shared file list):");
+    println!("  Collisions: {} ({:.2}% of IOs)", format_number(contention.collisions),
+             contention.collisions as f64 * 100.0 / stats.total_ops().max(1) as f64);
+    if contention.retried > 0 {
+        println!("  Retried:    {} picked another file", format_number(contention.retried));
+    }
+    if contention.waited > 0 {
+        println!("  Waited:     {} for {:.3} ms", format_number(contention.waited),
+                 contention.wait_time().as_secs_f64() * 1000.0);
+    }
+    if contention.proceeded() > 0 {
+        println!("  Proceeded:  {} used the busy file", format_number(contention.proceeded()));
+    }
+    println!();
+}
+
+/// Print the files (or directories) with the highest mean latency
+pub fn print_file_latency(stats: &WorkerStats) {
+    let Some(files) = stats.file_latency().filter(|f| !f.is_empty()) else {
+        return;
+    };
+    
+    let slowest = files.slowest();
+    println!("Slowest {} (top {} of {} by mean latency):",
+             if files.by_dir { "Directories" } else { "Files" }, slowest.len(), format_number(files.entries.len() as u64));
+    println!("  {:>10} {:>10} {:>12} {:>8}  Path", "Mean", "Max", "Ops", "Errors");
+    for (path, latency) in slowest {
+        println!("  {:>10} {:>10} {:>12} {:>8}  {}",
+                 crate::util::time::format_duration(latency.mean()),
+                 crate::util::time::format_duration(latency.max()),
+                 format_number(latency.ops), format_number(latency.errors), path);
+    }
+    println!();
+}
+
+/// Print throughput and latency per Lustre OST (--ost-stats)
+pub fn print_ost_stats(stats: &WorkerStats, duration: std::time::Duration) {
+    let osts = stats.ost_stats();
+    if osts.is_empty() {
+        return;
+    }
+    
+    println!("Per-OST Statistics:");
+    if !osts.osts.is_empty() {
+        println!("  {:>6} {:>12} {:>12} {:>10} {:>10}", "OST", "Ops", "Throughput", "Mean", "Max");
+        for (ost, counters) in &osts.osts {
+            println!("  {:>6} {:>12} {:>12} {:>10} {:>10}",
+                     format!("{:04x}", ost), format_number(counters.ops),
+                     format_throughput(calculate_throughput(counters.bytes, duration)),
+                     crate::util::time::format_duration(counters.mean()),
+                     crate::util::time::format_duration(counters.max()));
+        }
+    }
+    if osts.unmapped_ops > 0 {
+        println!("  {} ops on files without a Lustre striping layout", format_number(osts.unmapped_ops));
+    }
+    println!();
+}
+
+/// Print worker time split into CPU time and wait time (--cpu-accounting)
+pub fn print_cpu_time(stats: &WorkerStats) {
+    let Some(acct) = stats.cpu_time() else {
+        return;
+    };
+    let time = |ns: u64| crate::util::time::format_duration(std::time::Duration::from_nanos(ns));
+    
+    println!("CPU Time (summed over workers, {} wall-clock):", time(acct.wall_ns));
+    println!("  On CPU:        {:>10} ({:.1}%)", time(acct.run_ns), acct.percent(acct.run_ns));
+    println!("  Runqueue wait: {:>10} ({:.1}%)", time(acct.runqueue_ns), acct.percent(acct.runqueue_ns));
+    println!("  Blocked:       {:>10} ({:.1}%)", time(acct.blocked_ns()), acct.percent(acct.blocked_ns()));
+    println!("  {:<6} {:>12} {:>10} {:>10} {:>10}", "Call", "Calls", "Wall", "CPU", "Wait");
+    for (name, call) in acct.calls() {
+        if call.calls > 0 {
+            println!("  {:<6} {:>12} {:>10} {:>10} {:>10}",
+                     name, format_number(call.calls), time(call.wall_ns), time(call.cpu_ns), time(call.wait_ns()));
+        }
+    }
+    println!("  Harness CPU (outside engine calls): {}", time(acct.other_cpu_ns()));
+    if acct.percent(acct.run_ns + acct.runqueue_ns) >= 90.0 {
+        println!("  Workers were CPU-bound: results may be limited by this host rather than the storage");
+    }
+    println!();
+}
+
+/// Print how closely block accesses followed the configured distribution (--distribution-report)
+pub fn print_distribution_report(stats: &WorkerStats, config: &Config, nodes: usize) {
+    let Some(heatmap) = stats.heatmap_counts() else {
+        return;
+    };
+    let block_size = config.workload.block_size.max(1);
+    let Some(target) = config.targets.first() else {
+        return;
+    };
+    let Some((start, end)) = target.region.or(target.file_size.map(|size| (0, size))) else {
+        println!("Distribution Report: target size unknown (set --file-size)");
+        println!();
+        return;
+    };
+    let partitions = if target.distribution == crate::config::workload::FileDistribution::Partitioned {
+        (config.workers.threads * nodes.max(1)) as u64
+    } else {
+        1
+    };
+    let Some(report) = crate::distribution::fit::distribution_report(
+        &config.workload.distribution, &heatmap, start / block_size, (end - start) / block_size,
+        partitions, config.workload.heatmap_buckets,
+    ) else {
+        return;
+    };
+
+    println!("Distribution Report ({}):", report.name);
+    println!("  Blocks: {}, accesses: {} ({:.1} per block)",
+             format_number(report.blocks), format_number(report.accesses),
+             report.accesses as f64 / report.blocks as f64);
+    for point in &report.skew {
+        println!("  Top {:>3}% of blocks: {:6.2}% of accesses (expected {:6.2}%)",
+                 point.blocks * 100.0, point.achieved * 100.0, point.expected * 100.0);
+    }
+    println!("  Fit over {} buckets: {:.2}% of accesses off the expected curve (sampling noise {:.2}%): {}",
+             report.buckets, report.distance * 100.0, report.noise * 100.0,
+             if report.matches() { "matches" } else { "DOES NOT MATCH" });
+    if report.noisy() {
+        println!("  Note: fewer than 10 accesses per block; sampling noise inflates the achieved skew");
+    }
+    if report.coarse {
+        println!("  Note: heatmap counted blocks in groups (--stats-mem-limit); achieved skew is approximate");
+    }
+    println!();
+}
+
+/// Print the latency timer precision (verbose mode, or when latencies are near its noise floor)
+pub fn print_timer(stats: &WorkerStats, verbose: bool) {
+    let Some(timer) = stats.timer() else {
+        return;
+    };
+    let warning = stats.timer_warning();
+    if warning.is_none() && !verbose {
+        return;
+    }
+    
+    println!("Latency Timer:");
+    let source = if timer.tsc {
+        format!("TSC ({:.2} GHz)", timer.tsc_hz as f64 / 1e9)
+    } else {
+        "clock_gettime".to_string()
+    };
+    println!("  {}: {}ns resolution, {}ns per reading (noise floor {})",
+             source, timer.resolution_ns, timer.overhead_ns,
+             crate::util::time::format_duration(timer.noise_floor()));
+    if let Some(warning) = warning {
+        println!("  ⚠ Warning: {}", warning);
+    }
+    println!();
+}
+
+/// Print engine syscall and poll counters (verbose mode)
+pub fn print_engine_stats(stats: &WorkerStats) {
+    let engine = stats.engine_stats();
+    if engine.is_empty() {
+        return;
+    }
+    
+    println!("Engine:");
+    println!("  Syscalls:  {} ({:.2} completions per syscall)", format_number(engine.syscalls),
+             engine.completions as f64 / engine.syscalls.max(1) as f64);
+    println!("  Polls:     {} ({:.2} completions per poll)", format_number(engine.polls),
+             engine.completions_per_poll());
+    if engine.sq_full > 0 || engine.resubmissions > 0 {
+        println!("  SQ full:   {} ({} ops resubmitted)", format_number(engine.sq_full),
+                 format_number(engine.resubmissions));
+    }
+    println!();
+}
+
+/// Print the live stats snapshot cadence chosen by the workers (verbose mode)
+pub fn print_live_cadence(stats: &WorkerStats) {
+    let cadence = stats.live_cadence();
+    if cadence.is_empty() {
+        return;
+    }
+    
+    println!("Live Stats Cadence:");
+    println!("  Interval:  every {} ops at the end ({}-{} during the test)",
+             format_number(cadence.final_interval), format_number(cadence.min_interval),
+             format_number(cadence.max_interval));
+    println!("  Snapshots: {} ({:.1} us each)", format_number(cadence.updates),
+             cadence.mean_snapshot().as_nanos() as f64 / 1000.0);
+    println!("  Overhead:  {:.3}% of worker time (budget {}%)",
+             cadence.overhead_percent(), cadence.budget_percent);
+    println!();
+}
+
+/// Prominent warning that buffered reads measured the page cache
+fn print_page_cache_warning(warning: &crate::output::page_cache::PageCacheWarning) {
+    println!("⚠ WARNING: read results likely reflect the page cache, not the device");
+    for reason in &warning.reasons {
+        println!("  - {}", reason);
+    }
+    println!("  Use --direct, a working set larger than RAM, or drop caches before the test");
+    println!();
+}
+
+/// Print completed transfers by size when more than one size occurred
+///
+/// Short reads/writes and split requests show up here as sizes other than
+/// the configured block size, as do the sizes of a `--bs-split`. Mixed
+/// workloads also show the reads and writes of each size.
+pub fn print_io_sizes(stats: &WorkerStats) {
+    let sizes = stats.io_sizes();
+    if sizes.sizes().len() < 2 && sizes.overflow() == 0 {
+        return;
+    }
+    
+    let writes = stats.write_io_sizes();
+    let mixed = !writes.is_empty() && writes.total_ops() < sizes.total_ops();
+    let total = sizes.total_ops().max(1) as f64;
+    println!("IO Sizes:");
+    for &(bytes, ops) in sizes.sizes() {
+        if mixed {
+            let write_ops = writes.ops(bytes);
+            println!("  {:>10} bytes  {:>14} ops  {:>6.2}%  ({} reads, {} writes)",
+                     bytes, format_number(ops), ops as f64 * 100.0 / total,
+                     format_number(ops.saturating_sub(write_ops)), format_number(write_ops));
+        } else {
+            println!("  {:>10} bytes  {:>14} ops  {:>6.2}%",
+                     bytes, format_number(ops), ops as f64 * 100.0 / total);
+        }
+    }
+    if sizes.overflow() > 0 {
+        println!("  {:>16}  {:>14} ops  {:>6.2}%",
+                 "other", format_number(sizes.overflow()), sizes.overflow() as f64 * 100.0 / total);
+    }
+    println!();
+}
+
+/// Print a latency block (min/mean/max and percentiles) for one histogram
+pub fn print_latency_section(title: &str, hist: &crate::stats::simple_histogram::SimpleHistogram, percentiles: &[f64]) {
+    println!("{}:", title);
+    
+    if !hist.is_empty() {
+        println!("  Min:    {:?}", hist.min());
+        println!("  Mean:   {:?}", hist.mean());
+        println!("  Max:    {:?}", hist.max());
+        
+        println!();
+        println!("  Percentiles:");
+        for &p in percentiles {
+            let val = hist.percentile(p);
+            println!("    p{:5.2}: {:?}", p, val);
+        }
+    } else {
+        println!("  No latency data collected");
+    }
+}
+
+/// Note latency sampling (--latency-sample) and its percentile error
+pub fn print_latency_sampling(rate: Option<u64>, hist: &crate::stats::simple_histogram::SimpleHistogram, percentiles: &[f64]) {
+    let Some(rate) = rate.filter(|&rate| rate > 1) else { return };
+    println!("  Sampled 1 in {} operations ({} samples)", rate, format_number(hist.len()));
+    if let Some(&p) = percentiles.iter().max_by(|a, b| a.total_cmp(b)) {
+        println!("  p{:.2} within ±{:.3} percentile points (95% confidence)",
+                 p, crate::stats::simple_histogram::sampled_rank_error(p, hist.len()));
+    }
+}
+
+/// Note the NOP round trip measured before the test (--latency-floor) under the IO latency
+pub fn print_latency_floor(stats: &WorkerStats) {
+    let Some(floor) = stats.latency_floor() else { return };
+    let round_trips = &floor.round_trips;
+    if round_trips.is_empty() {
+        return;
+    }
+    println!();
+    println!("  Latency floor ({} NOP round trip, {} NOPs):", floor.engine, format_number(round_trips.len()));
+    println!("    min {:?}, p50 {:?}, p99 {:?}, max {:?}",
+             round_trips.min(), round_trips.percentile(50.0), round_trips.percentile(99.0), round_trips.max());
+    if let Some(percent) = floor.percent_of_median(stats.io_latency()) {
+        println!("    {:.1}% of the median IO latency", percent);
+    }
+}
+
+/// Print a single-line summary (quiet mode)
+///
+/// Space-separated key=value pairs so scripts can parse the result without
+/// scraping the full report.
+pub fn print_summary_line(stats: &WorkerStats, duration: std::time::Duration) {
+    let hist = stats.io_latency();
+    let (mean_us, p99_us) = if !hist.is_empty() {
+        (hist.mean().as_micros(), hist.percentile(99.0).as_micros())
+    } else {
+        (0, 0)
+    };
+    
+    println!("elapsed={:.3}s iops={:.0} read_iops={:.0} write_iops={:.0} throughput_bps={:.0} mean_lat_us={} p99_lat_us={} errors={}",
+             duration.as_secs_f64(),
+             calculate_iops(stats.total_ops(), duration),
+             calculate_iops(stats.read_ops(), duration),
+             calculate_iops(stats.write_ops(), duration),
+             calculate_throughput(stats.total_bytes(), duration),
+             mean_us,
+             p99_us,
+             stats.errors());
+}
+
+/// Space consumed, bytes written and their ratio, e.g. "2.00 GB for 1.00 GB written (2.00x)"
+fn format_space_consumed(usage: &crate::util::space::SpaceUsage, bytes_written: u64) -> String {
+    let consumed = usage.consumed_bytes();
+    let amount = if consumed < 0 {
+        format!("{} freed", format_bytes(consumed.unsigned_abs()))
+    } else {
+        format_bytes(consumed as u64)
+    };
+    match usage.amplification(bytes_written) {
+        Some(ratio) => format!("{} for {} written ({:.2}x)", amount, format_bytes(bytes_written), ratio),
+        None => format!("{} (nothing written)", amount),
+    }
+}
+
+/// Print the space each node consumed (nodes that ran with --space-usage)
+///
+/// On a shared filesystem every node sees the space all nodes consumed.
+pub fn print_node_space(nodes: &[(String, String, &WorkerStats, std::time::Duration)]) {
+    let with_space: Vec<_> = nodes.iter()
+        .filter_map(|(node, _, stats, _)| stats.space_usage().map(|usage| (node, usage, stats.write_bytes())))
+        .collect();
+    if with_space.is_empty() {
+        return;
+    }
+    
+    println!();
+    println!("Per-Node Space Usage:");
+    for (node, usage, bytes_written) in with_space {
+        println!("  {:<16} {}", node, format_space_consumed(usage, bytes_written));
+    }
+}
+
+/// Print per-node results table
+///
+/// The "vs avg" column shows each node's IOPS relative to the node average so
+/// imbalanced clusters and a single slow node stand out.
+pub fn print_node_table(nodes: &[(String, String, &WorkerStats, std::time::Duration)]) {
+    if nodes.is_empty() {
+        return;
+    }
+    
+    let node_iops: Vec<f64> = nodes.iter()
+        .map(|(_, _, stats, duration)| calculate_iops(stats.total_ops(), *duration))
+        .collect();
+    let avg_iops = node_iops.iter().sum::<f64>() / nodes.len() as f64;
+    
+    println!();
+    println!("Per-Node Results:");
+    println!("  {:<16} {:<16} {:>12}  {:>10}  {:>8}  {:>12}  {:>10}  {:>8}  {:>6}",
+             "Node", "Host", "Ops", "IOPS", "vs avg", "Throughput", "p99", "Errors", "CPU");
+    
+    for ((node, host, stats, duration), iops) in nodes.iter().zip(&node_iops) {
+        let vs_avg = if avg_iops > 0.0 {
+            format!("{:+.1}%", (iops - avg_iops) / avg_iops * 100.0)
+        } else {
+            "-".to_string()
+        };
+        let p99 = if stats.io_latency().is_empty() {
+            "-".to_string()
+        } else {
+            format!("{}us", stats.io_latency().percentile(99.0).as_micros())
+        };
+        let cpu = stats.resource_stats()
+            .map(|r| format!("{:.0}%", r.cpu_percent))
+            .unwrap_or_else(|| "-".to_string());
+        
+        println!("  {:<16} {:<16} {:>12}  {:>10}  {:>8}  {:>12}  {:>10}  {:>8}  {:>6}",
+                 node,
+                 host,
+                 format_number(stats.total_ops()),
+                 format_rate(*iops),
+                 vs_avg,
+                 format_throughput(calculate_throughput(stats.total_bytes(), *duration)),
+                 p99,
+                 stats.errors(),
+                 cpu);
+    }
+    println!();
+}
+
+/// Print how raggedly workers started and stopped issuing IO
+///
+/// A large skew means part of the test ran with fewer workers than configured,
+/// so aggregate rates understate what the storage sustained.
+pub fn print_start_stop_skew(skew: &crate::stats::skew::StartStopSkew) {
+    use crate::util::time::format_duration;
+    
+    println!("Start/Stop Skew ({} workers):", skew.workers);
+    println!("  Start skew: {}", format_duration(skew.start_skew));
+    println!("  Stop skew:  {}", format_duration(skew.stop_skew));
+    println!("  All workers issuing IO: {} of {} ({:.1}%)",
+             format_duration(skew.overlap), format_duration(skew.span), skew.overlap_percent());
+    
+    if skew.per_node.len() > 1 {
+        println!("  {:<16} {:>7}  {:>12}  {:>12}  {:>12}  {:>12}",
+                 "Node", "Workers", "First start", "Last start", "First stop", "Last stop");
+        for node in &skew.per_node {
+            println!("  {:<16} {:>7}  {:>12}  {:>12}  {:>12}  {:>12}",
+                     node.node,
+                     node.workers,
+                     format_duration(node.first_start),
+                     format_duration(node.last_start),
+                     format_duration(node.first_stop),
+                     format_duration(node.last_stop));
+        }
+    }
+    println!();
+}
+
+/// Print per-role results table (role-based node groups)
+///
+/// Each row merges the nodes of one role: (role, node IPs, stats, duration).
+pub fn print_role_table(roles: &[(String, Vec<String>, &WorkerStats, std::time::Duration)]) {
+    if roles.is_empty() {
+        return;
+    }
+    
+    println!("Per-Role Results:");
+    println!("  {:<12} {:>5}  {:>12}  {:>10}  {:>10}  {:>12}  {:>10}  {:>10}  {:>8}",
+             "Role", "Nodes", "Ops", "Read IOPS", "Write IOPS", "Throughput", "Mean", "p99", "Errors");
+    
+    for (role, nodes, stats, duration) in roles {
+        let hist = stats.io_latency();
+        let (mean, p99) = if hist.is_empty() {
+            ("-".to_string(), "-".to_string())
+        } else {
+            (format!("{}us", hist.mean().as_micros()), format!("{}us", hist.percentile(99.0).as_micros()))
+        };
+        
+        println!("  {:<12} {:>5}  {:>12}  {:>10}  {:>10}  {:>12}  {:>10}  {:>10}  {:>8}",
+                 role,
+                 nodes.len(),
+                 format_number(stats.total_ops()),
+                 format_rate(calculate_iops(stats.read_ops(), *duration)),
+                 format_rate(calculate_iops(stats.write_ops(), *duration)),
+                 format_throughput(calculate_throughput(stats.total_bytes(), *duration)),
+                 mean,
+                 p99,
+                 stats.errors());
+    }
+    println!();
+}
+
+/// Print per-job results table (job sets), ending with the combined total
+///
+/// Each row is one job: (job, stats, duration). The total's rates use the
+/// longest job's duration, as the jobs start together. Background jobs are
+/// listed after the total, which does not include them.
+pub fn print_job_table(
+    jobs: &[(String, &WorkerStats, std::time::Duration)],
+    total: &WorkerStats,
+    background: &[(String, &WorkerStats, std::time::Duration)],
+) {
+    let total_duration = jobs.iter().map(|(_, _, duration)| *duration).max().unwrap_or_default();
+    let print_row = |job: &str, stats: &WorkerStats, duration: std::time::Duration| {
+        let hist = stats.io_latency();
+        let (mean, p99) = if hist.is_empty() {
+            ("-".to_string(), "-".to_string())
+        } else {
+            (format!("{}us", hist.mean().as_micros()), format!("{}us", hist.percentile(99.0).as_micros()))
+        };
+        
+        println!("  {:<16} {:>12}  {:>10}  {:>10}  {:>12}  {:>10}  {:>10}  {:>8}",
+                 job,
+                 format_number(stats.total_ops()),
+                 format_rate(calculate_iops(stats.read_ops(), duration)),
+                 format_rate(calculate_iops(stats.write_ops(), duration)),
+                 format_throughput(calculate_throughput(stats.total_bytes(), duration)),
+                 mean,
+                 p99,
+                 stats.errors());
+    };
+    
+    println!("Per-Job Results:");
+    println!("  {:<16} {:>12}  {:>10}  {:>10}  {:>12}  {:>10}  {:>10}  {:>8}",
+             "Job", "Ops", "Read IOPS", "Write IOPS", "Throughput", "Mean", "p99", "Errors");
+    for (job, stats, duration) in jobs {
+        print_row(job, stats, *duration);
+    }
+    print_row("Total", total, total_duration);
+    if !background.is_empty() {
+        println!("  Background:");
+        for (job, stats, duration) in background {
+            print_row(job, stats, *duration);
+        }
+    }
+    println!();
+}
+
+/// Print the resources a run would use (--dry-run)
+pub fn print_resource_estimate(
+    title: &str,
+    estimate: &crate::util::estimate::ResourceEstimate,
+    rates: Option<crate::util::estimate::ProbeRates>,
+) {
+    println!("{}:", title);
+    println!("  Files to create:     {} ({} directories)", format_number(estimate.files), format_number(estimate.dirs));
+    println!("  Preparation writes:  {}", format_bytes(estimate.prep_bytes));
+    match estimate.test_write_bytes {
+        Some(bytes) => println!("  Test writes:         {}", format_bytes(bytes)),
+        None => println!("  Test writes:         depends on the duration"),
+    }
+    println!("  IO buffers:          {}", format_bytes(estimate.buffer_memory));
+    println!("  Statistics memory:   up to {}", format_bytes(estimate.stats_memory));
+    if let Some(prep_time) = estimate.prep_time {
+        let secs = prep_time.as_secs();
+        let time = if secs >= 3600 {
+            format!("{}h {}m", secs / 3600, secs % 3600 / 60)
+        } else if secs >= 60 {
+            format!("{}m {}s", secs / 60, secs % 60)
+        } else {
+            format!("{:.1}s", prep_time.as_secs_f64())
+        };
+        match rates {
+            Some(rates) => println!("  Preparation time:    ~{} (probe: {} writes, {} creates/s)",
+                time, format_throughput(rates.write_bytes_per_sec), format_rate(rates.creates_per_sec)),
+            None => println!("  Preparation time:    ~{}", time),
+        }
+    }
+    println!();
+}
+
+/// Print a summary checkpoint (--summary-interval) while the test runs
+pub fn print_checkpoint(checkpoint: &crate::output::checkpoint::Checkpoint, percentiles: &[f64]) {
+    let duration = checkpoint.duration();
+    println!();
+    println!("Checkpoint {} ({} - {}): {} ops, read {} IOPS, write {} IOPS, {}",
+             checkpoint.number,
+             crate::util::time::format_duration(checkpoint.start),
+             crate::util::time::format_duration(checkpoint.end),
+             format_number(checkpoint.read_ops + checkpoint.write_ops),
+             format_rate(calculate_iops(checkpoint.read_ops, duration)),
+             format_rate(calculate_iops(checkpoint.write_ops, duration)),
+             format_throughput(calculate_throughput(checkpoint.read_bytes + checkpoint.write_bytes, duration)));
+    for (name, hist) in [("Read", &checkpoint.read_latency), ("Write", &checkpoint.write_latency)] {
+        if hist.is_empty() {
+            continue;
+        }
+        let points: Vec<String> = percentiles.iter()
+            .map(|&p| format!("p{}={}us", p, hist.percentile(p).as_micros()))
+            .collect();
+        println!("  {:<5} mean={}us {} max={}us", name, hist.mean().as_micros(), points.join(" "), hist.max().as_micros());
+    }
+}
+
+/// Print per-worker results table (verbose mode)
+///
+/// Rates use each worker's own duration (falling back to `duration`), so workers
+/// that finished early are not under-reported.
+/// `very_verbose` adds the read/write IOPS split and p99.9/max latency columns.
+pub fn print_worker_table(workers: &[(String, usize, &WorkerStats)], duration: std::time::Duration, very_verbose: bool) {
+    if workers.is_empty() {
+        return;
+    }
+    
+    println!();
+    println!("Per-Worker Results:");
+    print!("  {:<16} {:>6}  {:>8}  {:>12}  {:>10}  {:>12}  {:>10}  {:>10}",
+           "Node", "Worker", "Time", "Ops", "IOPS", "Throughput", "Mean", "p99");
+    if very_verbose {
+        print!("  {:>10}  {:>10}  {:>10}  {:>10}", "Read IOPS", "Write IOPS", "p99.9", "Max");
+    }
+    // CPU columns only with --cpu-accounting
+    let cpu_columns = workers.iter().any(|(_, _, stats)| stats.cpu_time().is_some());
+    if cpu_columns {
+        print!("  {:>6}  {:>7}", "On CPU", "Blocked");
+    }
+    println!("  {:>8}", "Errors");
+    
+    for (node, worker_id, stats) in workers {
+        let hist = stats.io_latency();
+        let has_latency = !hist.is_empty();
+        let latency_us = |d: std::time::Duration| if has_latency { format!("{}us", d.as_micros()) } else { "-".to_string() };
+        let duration = stats.test_duration().unwrap_or(duration);
+        
+        print!("  {:<16} {:>6}  {:>8}  {:>12}  {:>10}  {:>12}  {:>10}  {:>10}",
+               node,
+               worker_id,
+               format!("{:.2}s", duration.as_secs_f64()),
+               format_number(stats.total_ops()),
+               format_rate(calculate_iops(stats.total_ops(), duration)),
+               format_throughput(calculate_throughput(stats.total_bytes(), duration)),
+               latency_us(hist.mean()),
+               latency_us(hist.percentile(99.0)));
+        if very_verbose {
+            print!("  {:>10}  {:>10}  {:>10}  {:>10}",
+                   format_rate(calculate_iops(stats.read_ops(), duration)),
+                   format_rate(calculate_iops(stats.write_ops(), duration)),
+                   latency_us(hist.percentile(99.9)),
+                   latency_us(hist.max()));
+        }
+        if cpu_columns {
+            match stats.cpu_time() {
+                Some(acct) => print!("  {:>5.1}%  {:>6.1}%", acct.percent(acct.run_ns), acct.percent(acct.blocked_ns())),
+                None => print!("  {:>6}  {:>7}", "-", "-"),
+            }
+        }
+        println!("  {:>8}", stats.errors());
+    }
+    println!();
+}
+
+/// Print queue depth ramp steps and the identified optimal queue depth
+fn print_qd_ramp(ramp: &crate::worker::qd_ramp::QdRampResult) {
+    println!("Queue Depth Ramp:");
+    println!("  {:>6}  {:>12}  {:>14}  {:>10}  {:>10}", "QD", "IOPS", "Throughput", "Mean", "p99");
+    for step in &ramp.steps {
+        let marker = if step.queue_depth == ramp.optimal_queue_depth { " <- optimal" } else { "" };
+        println!("  {:>6}  {:>12}  {:>14}  {:>8}us  {:>8}us{}",
+                 step.queue_depth,
+                 format_rate(step.iops),
+                 format_throughput(step.throughput_bps),
+                 step.mean_latency_us,
+                 step.p99_latency_us,
+                 marker);
+    }
+    println!();
+    println!("  Optimal queue depth: {} ({})", ramp.optimal_queue_depth, ramp.stop_reason);
+    println!();
+}
+
+/// Print the thread scaling curve and the chosen thread count
+pub fn print_thread_scaling(result: &crate::distributed::thread_scaling::ThreadScalingResult) {
+    println!("Thread Scaling:");
+    println!("  {:>7}  {:>12}  {:>14}  {:>8}  {:>10}  {:>10}", "Threads", "IOPS", "Throughput", "Gain", "Mean", "p99");
+    for step in &result.steps {
+        let marker = if step.threads == result.chosen_threads { " <- chosen" } else { "" };
+        let gain = step.gain_percent.map_or("-".to_string(), |g| format!("{:+.1}%", g));
+        println!("  {:>7}  {:>12}  {:>14}  {:>8}  {:>8}us  {:>8}us{}",
+                 step.threads,
+                 format_rate(step.iops),
+                 format_throughput(step.throughput_bps),
+                 gain,
+                 step.mean_latency_us,
+                 step.p99_latency_us,
+                 marker);
+    }
+    println!();
+    println!("  Chosen thread count: {} per node ({})", result.chosen_threads, result.stop_reason);
+    println!();
+}
+
+/// Print what each node reported at READY, flagging fields that differ
+///
+/// Each row is one node: (address, capabilities), None for a node that did
+/// not report them (older release).
+pub fn print_node_capabilities(nodes: &[(String, Option<&crate::util::capabilities::NodeCapabilities>)]) {
+    use crate::util::capabilities::NodeCapabilities;
+    
+    println!("Node Capabilities:");
+    println!("  {:<21}  {:<16}  {:<20}  {:<14}  {:<26}  Engine", "Node", "Version", "Kernel", "Features", "Engines");
+    for (node, capabilities) in nodes {
+        let Some(capabilities) = capabilities else {
+            println!("  {:<21}  (not reported)", node);
+            continue;
+        };
+        let version = match capabilities.git_sha {
+            Some(ref sha) => format!("{} ({})", capabilities.version, &sha[..sha.len().min(7)]),
+            None => capabilities.version.clone(),
+        };
+        let list = |items: &[String]| if items.is_empty() { "-".to_string() } else { items.join(",") };
+        println!("  {:<21}  {:<16}  {:<20}  {:<14}  {:<26}  {}",
+                 node,
+                 version,
+                 capabilities.kernel.as_deref().unwrap_or("-"),
+                 list(&capabilities.features),
+                 list(&capabilities.engines),
+                 capabilities.engine);
+    }
+    let differences = NodeCapabilities::differences(nodes.iter().filter_map(|(_, capabilities)| *capabilities));
+    if !differences.is_empty() {
+        println!("  ⚠ Nodes differ in: {}", differences.join(", "));
+    }
+    println!();
+}
+
+/// Print the preflight checks of each host, with the reason of every failure
+pub fn print_preflight(hosts: &[(String, Option<&crate::util::preflight::PreflightReport>)]) {
+    println!("Preflight Checks:");
+    for (host, report) in hosts {
+        let Some(report) = report else {
+            println!("  {:<21}  (node too old to check)", host);
+            continue;
+        };
+        let mut targets: Vec<&std::path::Path> = report.checks.iter().map(|check| check.path.as_path()).collect();
+        targets.dedup();
+        for target in targets {
+            let checks = report.checks.iter().filter(|check| check.path == target);
+            let outcomes: Vec<String> = checks.clone()
+                .map(|check| format!("{} {}", check.check, if check.error.is_some() { "FAILED" } else { "ok" }))
+                .collect();
+            println!("  {:<21}  {:<32}  {}", host, target.display(), outcomes.join(", "));
+            for check in checks {
+                if let Some(ref error) = check.error {
+                    println!("    ✗ {}: {}", check.check, error);
+                }
+            }
+        }
+    }
+    println!();
+}
+
+/// Print the cross-node visibility latencies
+pub fn print_visibility(result: &crate::distributed::visibility::VisibilityResult) {
+    let time = |us: u64| crate::util::time::format_duration(std::time::Duration::from_micros(us));
+    let row = |name: &str, latency: &Option<crate::distributed::visibility::VisibilityLatency>, extra: String| match latency {
+        Some(l) => println!("  {:<24}  {:>8}  {:>10}  {:>10}  {:>10}  {:>10}{}",
+                            name, l.count, time(l.mean_us), time(l.p50_us), time(l.p99_us), time(l.max_us), extra),
+        None => println!("  {:<24}  {:>8}  {:>10}{}", name, 0, "-", extra),
+    };
+    println!("Visibility Test ({} then {}, {} per batch):", result.op, result.check, result.batch);
+    println!("  {:<24}  {:>8}  {:>10}  {:>10}  {:>10}  {:>10}", "", "Count", "Mean", "p50", "p99", "Max");
+    row(&format!("{} on {}", result.op, result.actor), &result.op_latency, String::new());
+    row("Visible (all observers)", &result.visibility_latency, String::new());
+    for observer in &result.observers {
+        let extra = format!("  ({} immediate, {} checks{})", observer.immediate, observer.checks,
+                            if observer.timeouts > 0 { format!(", {} timed out", observer.timeouts) } else { String::new() });
+        row(&format!("  {}", observer.node), &observer.latency, extra);
+    }
+    println!();
+    if result.failed_ops > 0 {
+        println!("  ⚠ {} of {} operations failed on {}", result.failed_ops, result.ops, result.actor);
+    }
+    if result.timeouts > 0 {
+        println!("  ⚠ {} observations not visible within the timeout", result.timeouts);
+    }
+    if result.failed_ops > 0 || result.timeouts > 0 {
+        println!();
+    }
+}
+
+/// Print the latency target steps and the sustainable IOPS
+pub fn print_latency_target(result: &crate::distributed::latency_target::LatencyTargetResult) {
+    let time = |us: u64| crate::util::time::format_duration(std::time::Duration::from_micros(us));
+    println!("Latency Target (p99 <= {}):", time(result.target_p99_us));
+    println!("  {:>5}  {:>12}  {:>12}  {:>10}", "Step", "Limit", "IOPS", "p99");
+    for (i, step) in result.steps.iter().enumerate() {
+        let limit = step.rate_iops.map_or("unlimited".to_string(), |rate| format_rate(rate as f64));
+        println!("  {:>5}  {:>12}  {:>12}  {:>8}us{}",
+                 i + 1,
+                 limit,
+                 format_rate(step.iops),
+                 step.p99_latency_us,
+                 if step.met { "" } else { "  (missed)" });
+    }
+    println!();
+    match (result.sustainable_iops, result.sustainable_p99_us) {
+        (Some(iops), Some(p99)) => {
+            let how = match (result.final_rate_iops, result.converged) {
+                (None, _) => "target met without a limit",
+                (Some(_), true) => "converged",
+                (Some(_), false) => "still searching when the test ended",
+            };
+            println!("  Sustainable: {} IOPS at p99 {} ({})",
+                     format_rate(iops), time(p99), how);
+        }
+        _ => println!("  Sustainable: none - no step at the final limit met the target"),
+    }
+    println!();
+}
+
 /// Format a number with thousands separators
 fn format_number(n: u64) -> String {
     let s = n.to_string();
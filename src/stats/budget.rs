@@ -0,0 +1,398 @@
+//! Memory budget for statistics
+//!
+//! Block tracking (the coverage set and the `--heatmap` map) and the
+//! time-series kept for the final JSON/CSV output grow with the length of a
+//! run. With `--stats-mem-limit`, each is held under its share of the budget
+//! by lowering its resolution instead of growing further:
+//!
+//! - the coverage set switches to sampling: only blocks whose hash falls in
+//!   1/2^k of the hash space are kept, and the count is scaled by 2^k
+//! - the heatmap merges neighbouring blocks into coarser buckets
+//! - retained time-series merge adjacent intervals
+//!
+//! Each downgrade prints a warning. Streamed time-series files are written as
+//! the test runs and keep full resolution.
+//!
+//! Half of the budget goes to the time-series retained by the coordinator;
+//! the other half is split evenly between the workers of a node for block
+//! tracking (shared by the coverage set and the heatmap when both are on).
+//! Sizes are estimates of the hash table footprint, not exact allocations.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Estimated bytes per coverage set entry (hash table slot, control byte, load factor)
+const BLOCK_SET_ENTRY_BYTES: u64 = 20;
+
+/// Estimated bytes per heatmap entry
+const HEATMAP_ENTRY_BYTES: u64 = 36;
+
+static COVERAGE_WARNED: AtomicBool = AtomicBool::new(false);
+static HEATMAP_WARNED: AtomicBool = AtomicBool::new(false);
+
+/// Estimated block tracking memory of a worker that touches `blocks` distinct blocks
+pub fn block_tracking_bytes(blocks: u64, heatmap: bool) -> u64 {
+    let per_block = BLOCK_SET_ENTRY_BYTES + if heatmap { HEATMAP_ENTRY_BYTES } else { 0 };
+    blocks.saturating_mul(per_block)
+}
+
+/// Budget for one worker's block tracking
+pub fn worker_block_limit(limit: u64, workers: usize) -> u64 {
+    limit / 2 / workers.max(1) as u64
+}
+
+/// Budget for the time-series retained by the coordinator
+pub fn time_series_limit(limit: u64) -> u64 {
+    limit / 2
+}
+
+/// Print a warning once per process (workers share the same downgrades)
+fn warn_once(flag: &AtomicBool, message: impl FnOnce() -> String) {
+    if !flag.swap(true, Ordering::Relaxed) {
+        eprintln!("Warning: {}", message());
+    }
+}
+
+/// Whether a block is kept at a sampling level of 1/2^shift
+fn sampled(block: u64, shift: u32) -> bool {
+    // splitmix64 finalizer: spreads sequential block numbers over the hash space
+    let mut z = block.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^= z >> 31;
+    z & ((1u64 << shift) - 1) == 0
+}
+
+/// Set of accessed blocks, sampled once it outgrows its budget
+#[derive(Debug, Default)]
+pub struct BlockSet {
+    blocks: HashSet<u64>,
+    /// Blocks are kept at a sampling level of 1/2^shift
+    shift: u32,
+    /// Entries allowed before the sampling level is halved (None = unbounded)
+    max_entries: Option<usize>,
+    /// The count includes an estimate from a sampled set merged or restored into this one
+    estimated: bool,
+}
+
+impl BlockSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limit the set to about `bytes` of memory
+    pub fn set_limit(&mut self, bytes: u64) {
+        self.max_entries = Some((bytes / BLOCK_SET_ENTRY_BYTES).max(1) as usize);
+        self.enforce_limit();
+    }
+
+    #[inline]
+    pub fn insert(&mut self, block: u64) {
+        if sampled(block, self.shift) && self.blocks.insert(block) {
+            self.enforce_limit();
+        }
+    }
+
+    /// Number of distinct blocks (estimated once sampling)
+    pub fn len(&self) -> u64 {
+        (self.blocks.len() as u64) << self.shift
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    /// Whether `len()` is an estimate
+    pub fn is_estimate(&self) -> bool {
+        self.shift > 0 || self.estimated
+    }
+
+    /// Replace the contents with `count` placeholder blocks
+    ///
+    /// Used when only the count of another process's set is known.
+    pub fn restore(&mut self, count: u64, estimated: bool) {
+        self.blocks = (0..count).collect();
+        self.shift = 0;
+        self.estimated = estimated;
+    }
+
+    /// Add the blocks of another set
+    pub fn merge(&mut self, other: &BlockSet) {
+        self.estimated |= other.estimated;
+        self.resample(self.shift.max(other.shift));
+        let shift = self.shift;
+        self.blocks.extend(other.blocks.iter().copied().filter(|&block| sampled(block, shift)));
+        self.enforce_limit();
+    }
+
+    fn resample(&mut self, shift: u32) {
+        if shift > self.shift {
+            self.shift = shift;
+            self.blocks.retain(|&block| sampled(block, shift));
+        }
+    }
+
+    fn enforce_limit(&mut self) {
+        let Some(max_entries) = self.max_entries else { return };
+        if self.blocks.len() <= max_entries {
+            return;
+        }
+        while self.blocks.len() > max_entries / 2 && self.shift < 63 {
+            self.resample(self.shift + 1);
+        }
+        self.blocks.shrink_to_fit();
+        let shift = self.shift;
+        warn_once(&COVERAGE_WARNED, || format!(
+            "Coverage tracking reached its --stats-mem-limit share; sampling 1 in {} blocks (coverage is estimated)",
+            1u64 << shift));
+    }
+}
+
+/// Per-block access counts, merged into coarser buckets once they outgrow their budget
+#[derive(Debug, Default)]
+pub struct BlockHeatmap {
+    /// Access counts by bucket (block >> shift)
+    counts: HashMap<u64, u64>,
+    /// Each bucket covers 2^shift blocks
+    shift: u32,
+    /// Entries allowed before buckets are doubled (None = unbounded)
+    max_entries: Option<usize>,
+}
+
+impl BlockHeatmap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limit the heatmap to about `bytes` of memory
+    pub fn set_limit(&mut self, bytes: u64) {
+        self.max_entries = Some((bytes / HEATMAP_ENTRY_BYTES).max(1) as usize);
+        self.enforce_limit();
+    }
+
+    #[inline]
+    pub fn record(&mut self, block: u64) {
+        self.add(block >> self.shift, 1);
+    }
+
+    fn add(&mut self, bucket: u64, count: u64) {
+        let len = self.counts.len();
+        *self.counts.entry(bucket).or_insert(0) += count;
+        if self.counts.len() > len {
+            self.enforce_limit();
+        }
+    }
+
+    /// Blocks per bucket
+    pub fn blocks_per_bucket(&self) -> u64 {
+        1 << self.shift
+    }
+
+    /// (first block of bucket, access count) pairs, sorted by block
+    pub fn entries(&self) -> Vec<(u64, u64)> {
+        let mut entries: Vec<(u64, u64)> = self.counts.iter()
+            .map(|(&bucket, &count)| (bucket << self.shift, count))
+            .collect();
+        entries.sort_by_key(|&(block, _)| block);
+        entries
+    }
+
+    /// Add the counts of another heatmap
+    pub fn merge(&mut self, other: &BlockHeatmap) {
+        self.coarsen_to(self.shift.max(other.shift));
+        for (&bucket, &count) in &other.counts {
+            self.add(bucket >> (self.shift - other.shift), count);
+        }
+    }
+
+    /// Add counts taken from `entries()` of a heatmap with `blocks_per_bucket` blocks per bucket
+    pub fn add_entries(&mut self, blocks_per_bucket: u64, entries: &[(u64, u64)]) {
+        self.coarsen_to(self.shift.max(blocks_per_bucket.max(1).trailing_zeros()));
+        for &(block, count) in entries {
+            self.add(block >> self.shift, count);
+        }
+    }
+
+    fn coarsen_to(&mut self, shift: u32) {
+        if shift > self.shift {
+            let by = shift - self.shift;
+            let mut counts = HashMap::with_capacity(self.counts.len() >> by.min(16));
+            for (bucket, count) in self.counts.drain() {
+                *counts.entry(bucket >> by).or_insert(0) += count;
+            }
+            self.counts = counts;
+            self.shift = shift;
+        }
+    }
+
+    fn enforce_limit(&mut self) {
+        let Some(max_entries) = self.max_entries else { return };
+        if self.counts.len() <= max_entries {
+            return;
+        }
+        while self.counts.len() > max_entries / 2 && self.shift < 63 {
+            self.coarsen_to(self.shift + 1);
+        }
+        let blocks = self.blocks_per_bucket();
+        warn_once(&HEATMAP_WARNED, || format!(
+            "Heatmap reached its --stats-mem-limit share; counting accesses in buckets of {} blocks",
+            blocks));
+    }
+}
+
+/// Keeps the coordinator's retained time-series under its budget
+///
+/// Every retained interval covers `factor` heartbeats. When the series
+/// outgrow the budget the factor doubles and adjacent intervals are merged.
+/// The first interval of each node (the startup snapshot skipped in the
+/// output) is never merged.
+#[derive(Debug)]
+pub struct SeriesRetention {
+    /// Retained intervals allowed across all nodes (None = unbounded)
+    max_entries: Option<usize>,
+    /// Heartbeats per retained interval
+    factor: usize,
+    /// Heartbeats in each node's last retained interval
+    last_count: Vec<usize>,
+}
+
+impl SeriesRetention {
+    /// `entry_bytes` is the estimated size of one retained interval of one node
+    pub fn new(limit: Option<u64>, entry_bytes: u64, num_nodes: usize) -> Self {
+        Self {
+            max_entries: limit.map(|bytes| (bytes / entry_bytes.max(1)).max(2) as usize),
+            factor: 1,
+            last_count: vec![0; num_nodes],
+        }
+    }
+
+    /// Whether the node's next heartbeat joins its last retained interval
+    pub fn joins_last(&self, node: usize, retained: usize) -> bool {
+        retained > 1 && self.last_count[node] < self.factor
+    }
+
+    /// Record that a heartbeat was retained; returns true if the series
+    /// (`total` intervals across all nodes) must now be coarsened
+    pub fn record(&mut self, node: usize, joined: bool, total: usize) -> bool {
+        if joined {
+            self.last_count[node] += 1;
+        } else {
+            self.last_count[node] = 1;
+        }
+        self.max_entries.is_some_and(|max| total > max)
+    }
+
+    /// Double the interval length and merge one node's series accordingly
+    ///
+    /// Call for every node, with the same `factor` (the value of
+    /// [`factor`](Self::factor) before coarsening started). Series kept in
+    /// step with the node's intervals are merged with [`merge_pairs`].
+    pub fn coarsen<T>(&mut self, node: usize, factor: usize, series: &mut Vec<T>, merge: impl FnMut(&mut T, T)) {
+        if merge_pairs(series, merge) {
+            self.last_count[node] += factor;
+        }
+        self.factor = factor * 2;
+    }
+
+    /// Heartbeats per retained interval
+    pub fn factor(&self) -> usize {
+        self.factor
+    }
+
+    /// Warn about a coarsening to the current factor
+    pub fn warn(&self) {
+        eprintln!("Warning: Retained time-series reached the --stats-mem-limit share; merging every {} heartbeats into one interval",
+                  self.factor);
+    }
+}
+
+/// Merge intervals (1,2), (3,4), ... of a series, keeping the first
+///
+/// Returns true if the last interval was merged (false if it was left
+/// unpaired).
+pub fn merge_pairs<T>(series: &mut Vec<T>, mut merge: impl FnMut(&mut T, T)) -> bool {
+    if series.len() < 2 {
+        return false;
+    }
+    let paired = (series.len() - 1).is_multiple_of(2);
+    let tail: Vec<T> = series.drain(1..).collect();
+    let mut iter = tail.into_iter();
+    while let Some(mut first) = iter.next() {
+        if let Some(second) = iter.next() {
+            merge(&mut first, second);
+        }
+        series.push(first);
+    }
+    paired
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_set_sampling() {
+        let mut set = BlockSet::new();
+        set.set_limit(1000 * BLOCK_SET_ENTRY_BYTES);
+        for block in 0..100_000 {
+            set.insert(block);
+        }
+        assert!(set.is_estimate());
+        assert!(set.blocks.len() <= 1000);
+        // The estimate stays within a few percent of the real count
+        let estimate = set.len() as f64;
+        assert!((estimate - 100_000.0).abs() < 15_000.0, "estimate {}", estimate);
+
+        let mut unbounded = BlockSet::new();
+        unbounded.insert(1);
+        unbounded.insert(1);
+        assert_eq!(unbounded.len(), 1);
+        assert!(!unbounded.is_estimate());
+    }
+
+    #[test]
+    fn test_heatmap_coarsening() {
+        let mut heatmap = BlockHeatmap::new();
+        heatmap.set_limit(100 * HEATMAP_ENTRY_BYTES);
+        for block in 0..1000 {
+            heatmap.record(block);
+        }
+        assert!(heatmap.blocks_per_bucket() > 1);
+        let entries = heatmap.entries();
+        assert!(entries.len() <= 100);
+        assert_eq!(entries.iter().map(|&(_, count)| count).sum::<u64>(), 1000);
+        assert_eq!(entries[0].0, 0);
+
+        let mut fine = BlockHeatmap::new();
+        fine.record(5);
+        fine.merge(&heatmap);
+        assert_eq!(fine.blocks_per_bucket(), heatmap.blocks_per_bucket());
+        assert_eq!(fine.entries().iter().map(|&(_, count)| count).sum::<u64>(), 1001);
+
+        // Counts restored from final results keep their bucket size
+        let mut restored = BlockHeatmap::new();
+        restored.add_entries(heatmap.blocks_per_bucket(), &heatmap.entries());
+        assert_eq!(restored.entries(), heatmap.entries());
+    }
+
+    #[test]
+    fn test_series_retention() {
+        let mut retention = SeriesRetention::new(Some(8), 1, 1);
+        let mut series: Vec<u32> = Vec::new();
+        for _ in 0..100 {
+            let joins = retention.joins_last(0, series.len());
+            if joins {
+                *series.last_mut().unwrap() += 1;
+            } else {
+                series.push(1);
+            }
+            if retention.record(0, joins, series.len()) {
+                let factor = retention.factor();
+                retention.coarsen(0, factor, &mut series, |a, b| *a += b);
+            }
+        }
+        assert!(series.len() <= 8);
+        assert_eq!(series[0], 1);
+        assert_eq!(series.iter().sum::<u32>(), 100);
+    }
+}
@@ -0,0 +1,83 @@
+//! Busy-file collisions in shared file-list mode
+//!
+//! With `--file-distribution shared` every worker picks files from the whole
+//! list, so two workers of a node can land on the same file. Depending on the
+//! `--busy-file` policy the worker uses it anyway, picks another file, or
+//! waits for the other worker to move on. `ContentionStats` counts the
+//! collisions and how each was resolved, so a small-file workload that
+//! serializes on a few files shows up in the results instead of only as
+//! lower throughput.
+//!
+//! # Example
+//!
+//! ```
+//! use iopulse::stats::contention::ContentionStats;
+//! use std::time::Duration;
+//!
+//! let mut contention = ContentionStats::default();
+//! contention.record_collision();
+//! contention.record_collision();
+//! contention.record_retry();
+//! contention.record_wait(Duration::from_micros(30));
+//!
+//! assert_eq!(contention.collisions, 2);
+//! assert_eq!(contention.proceeded(), 0);
+//! assert_eq!(contention.wait_time(), Duration::from_micros(30));
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Picks that found the file in use by another worker
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContentionStats {
+    /// Picks that collided with another worker
+    pub collisions: u64,
+    /// Collisions resolved by picking another file (`--busy-file retry`)
+    pub retried: u64,
+    /// Collisions resolved by waiting (`--busy-file wait`)
+    pub waited: u64,
+    /// Time spent waiting for busy files (nanoseconds)
+    pub wait_ns: u64,
+}
+
+impl ContentionStats {
+    /// Record a pick that found the file in use
+    pub fn record_collision(&mut self) {
+        self.collisions += 1;
+    }
+
+    /// Record a collision resolved by picking another file
+    pub fn record_retry(&mut self) {
+        self.retried += 1;
+    }
+
+    /// Record a collision resolved by waiting for the file
+    pub fn record_wait(&mut self, duration: Duration) {
+        self.waited += 1;
+        self.wait_ns += duration.as_nanos() as u64;
+    }
+
+    /// Merge another worker's counts
+    pub fn merge(&mut self, other: &ContentionStats) {
+        self.collisions += other.collisions;
+        self.retried += other.retried;
+        self.waited += other.waited;
+        self.wait_ns += other.wait_ns;
+    }
+
+    /// Collisions where the worker used the busy file anyway
+    pub fn proceeded(&self) -> u64 {
+        self.collisions.saturating_sub(self.retried + self.waited)
+    }
+
+    /// Total time spent waiting for busy files
+    pub fn wait_time(&self) -> Duration {
+        Duration::from_nanos(self.wait_ns)
+    }
+
+    /// No collisions recorded
+    pub fn is_empty(&self) -> bool {
+        self.collisions == 0
+    }
+}
@@ -0,0 +1,223 @@
+//! Worker CPU time vs wait time (`--cpu-accounting`)
+//!
+//! A low IOPS figure may mean the storage is slow or that the worker threads
+//! can't issue IO any faster. With `--cpu-accounting` each worker splits its
+//! wall-clock time, from the scheduler's per-thread statistics, into:
+//!
+//! - on CPU (harness and kernel IO path),
+//! - runqueue wait (runnable, but all CPUs were busy),
+//! - blocked (waiting for storage, sleeps, locks).
+//!
+//! In addition, the thread CPU clock is read around every engine call, so
+//! time in read and write submissions and in completion polling is split into
+//! CPU time and wait time. CPU time outside these calls is the harness itself
+//! (offset generation, verification, statistics, metadata operations).
+//!
+//! Reading the clock costs two `clock_gettime` calls per engine call, which
+//! is why the accounting is opt-in.
+//!
+//! # Example
+//!
+//! ```
+//! use iopulse::stats::cpu_time::{CallTime, CpuAccounting};
+//!
+//! let acct = CpuAccounting {
+//!     wall_ns: 10_000_000,
+//!     run_ns: 2_000_000,
+//!     runqueue_ns: 500_000,
+//!     read: CallTime { calls: 100, wall_ns: 9_000_000, cpu_ns: 1_500_000 },
+//!     ..Default::default()
+//! };
+//! assert_eq!(acct.blocked_ns(), 7_500_000);
+//! assert_eq!(acct.read.wait_ns(), 7_500_000);
+//! assert_eq!(acct.other_cpu_ns(), 500_000);
+//! ```
+
+use crate::util::resource::{thread_cpu_time, SchedStat};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Engine calls that are timed separately
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallClass {
+    /// Read submissions (the whole read for the sync engine)
+    Read,
+    /// Write submissions (the whole write for the sync engine)
+    Write,
+    /// Completion polling
+    Reap,
+}
+
+/// Wall-clock and CPU time of one class of engine calls
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CallTime {
+    /// Engine calls made
+    pub calls: u64,
+    /// Wall-clock time in the calls (nanoseconds)
+    pub wall_ns: u64,
+    /// Thread CPU time in the calls (nanoseconds)
+    pub cpu_ns: u64,
+}
+
+impl CallTime {
+    /// Time in the calls not spent on a CPU
+    pub fn wait_ns(&self) -> u64 {
+        self.wall_ns.saturating_sub(self.cpu_ns)
+    }
+
+    /// Add another worker's time
+    pub fn merge(&mut self, other: &CallTime) {
+        self.calls += other.calls;
+        self.wall_ns += other.wall_ns;
+        self.cpu_ns += other.cpu_ns;
+    }
+}
+
+/// Split of worker time between CPU and waiting
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CpuAccounting {
+    /// Wall-clock time of the test, summed over workers (nanoseconds)
+    pub wall_ns: u64,
+    /// Time on a CPU (nanoseconds)
+    pub run_ns: u64,
+    /// Time runnable but waiting for a CPU (nanoseconds, 0 without schedstat)
+    pub runqueue_ns: u64,
+    pub read: CallTime,
+    pub write: CallTime,
+    pub reap: CallTime,
+}
+
+impl CpuAccounting {
+    /// Time the workers were blocked (neither running nor runnable)
+    pub fn blocked_ns(&self) -> u64 {
+        self.wall_ns.saturating_sub(self.run_ns).saturating_sub(self.runqueue_ns)
+    }
+
+    /// CPU time outside engine calls (the harness)
+    pub fn other_cpu_ns(&self) -> u64 {
+        self.run_ns.saturating_sub(self.read.cpu_ns + self.write.cpu_ns + self.reap.cpu_ns)
+    }
+
+    /// Share of wall-clock time, in percent
+    pub fn percent(&self, ns: u64) -> f64 {
+        ns as f64 / self.wall_ns.max(1) as f64 * 100.0
+    }
+
+    /// Engine call classes with their names, in report order
+    pub fn calls(&self) -> [(&'static str, &CallTime); 3] {
+        [("read", &self.read), ("write", &self.write), ("reap", &self.reap)]
+    }
+
+    /// Add another worker's time
+    pub fn merge(&mut self, other: &CpuAccounting) {
+        self.wall_ns += other.wall_ns;
+        self.run_ns += other.run_ns;
+        self.runqueue_ns += other.runqueue_ns;
+        self.read.merge(&other.read);
+        self.write.merge(&other.write);
+        self.reap.merge(&other.reap);
+    }
+}
+
+/// Start of a timed engine call
+#[derive(Debug, Clone, Copy)]
+pub struct CallStart {
+    wall: Instant,
+    cpu: Duration,
+}
+
+/// CPU accounting of one worker thread
+///
+/// Must be created and used on the worker's own thread.
+#[derive(Debug)]
+pub struct CpuTracker {
+    start: Instant,
+    sched_start: Option<SchedStat>,
+    cpu_start: Duration,
+    acct: CpuAccounting,
+}
+
+impl CpuTracker {
+    /// Start accounting for the calling thread
+    pub fn start() -> Self {
+        Self {
+            start: Instant::now(),
+            sched_start: SchedStat::current_thread(),
+            cpu_start: thread_cpu_time(),
+            acct: CpuAccounting::default(),
+        }
+    }
+
+    /// Mark the start of an engine call
+    #[inline]
+    pub fn begin(&self) -> CallStart {
+        CallStart { wall: Instant::now(), cpu: thread_cpu_time() }
+    }
+
+    /// Account an engine call that started at `start`
+    #[inline]
+    pub fn end(&mut self, class: CallClass, start: CallStart) {
+        let cpu = thread_cpu_time().saturating_sub(start.cpu);
+        let wall = start.wall.elapsed();
+        let call = match class {
+            CallClass::Read => &mut self.acct.read,
+            CallClass::Write => &mut self.acct.write,
+            CallClass::Reap => &mut self.acct.reap,
+        };
+        call.calls += 1;
+        call.wall_ns += wall.as_nanos() as u64;
+        call.cpu_ns += cpu.as_nanos() as u64;
+    }
+
+    /// Accounting of the test so far
+    ///
+    /// Without schedstat, on-CPU time comes from the thread CPU clock and the
+    /// runqueue wait is 0 (counted as blocked).
+    pub fn finish(&self) -> CpuAccounting {
+        let mut acct = self.acct.clone();
+        acct.wall_ns = self.start.elapsed().as_nanos() as u64;
+        match (self.sched_start, SchedStat::current_thread()) {
+            (Some(start), Some(now)) => {
+                let delta = now.since(&start);
+                acct.run_ns = delta.run_ns;
+                acct.runqueue_ns = delta.runqueue_ns;
+            }
+            _ => acct.run_ns = thread_cpu_time().saturating_sub(self.cpu_start).as_nanos() as u64,
+        }
+        acct
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracker_splits_cpu_and_wait() {
+        let mut tracker = CpuTracker::start();
+
+        let call = tracker.begin();
+        std::thread::sleep(Duration::from_millis(20));
+        tracker.end(CallClass::Read, call);
+
+        let call = tracker.begin();
+        let mut sum = 0u64;
+        for i in 0..2_000_000u64 {
+            sum = std::hint::black_box(sum.wrapping_add(i));
+        }
+        tracker.end(CallClass::Reap, call);
+        assert!(sum > 0);
+
+        let acct = tracker.finish();
+        assert_eq!((acct.read.calls, acct.write.calls, acct.reap.calls), (1, 0, 1));
+        assert!(acct.read.wait_ns() >= 15_000_000);
+        assert!(acct.reap.cpu_ns > 0);
+        assert!(acct.wall_ns >= acct.read.wall_ns + acct.reap.wall_ns);
+        assert!(acct.blocked_ns() >= 15_000_000);
+
+        let mut total = acct.clone();
+        total.merge(&acct);
+        assert_eq!(total.read.calls, 2);
+        assert_eq!(total.wall_ns, 2 * acct.wall_ns);
+    }
+}
@@ -0,0 +1,88 @@
+//! Operations drained after the test duration expired
+//!
+//! When the duration expires, workers stop submitting and wait for the
+//! operations still in flight. With `--drain-latency exclude` or `separate`
+//! those operations are kept out of the results; this records what was left
+//! out, and with `separate` their latency too.
+//!
+//! # Example
+//!
+//! ```
+//! use iopulse::stats::drain::DrainStats;
+//! use std::time::Duration;
+//!
+//! let mut drained = DrainStats::new(true);
+//! drained.record(4096, Duration::from_millis(12));
+//!
+//! assert_eq!(drained.ops, 1);
+//! assert_eq!(drained.latency.unwrap().len(), 1);
+//! ```
+
+use super::simple_histogram::SimpleHistogram;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Operations that completed after the duration expired
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DrainStats {
+    /// Completed operations
+    pub ops: u64,
+    /// Bytes transferred
+    pub bytes: u64,
+    /// Latency (only when reported separately)
+    pub latency: Option<SimpleHistogram>,
+}
+
+impl DrainStats {
+    /// Empty stats, tracking latency when it is reported separately
+    pub fn new(track_latency: bool) -> Self {
+        Self {
+            ops: 0,
+            bytes: 0,
+            latency: track_latency.then(SimpleHistogram::new),
+        }
+    }
+
+    /// Record a drained operation
+    #[inline]
+    pub fn record(&mut self, bytes: usize, latency: Duration) {
+        self.ops += 1;
+        self.bytes += bytes as u64;
+        if let Some(ref mut hist) = self.latency {
+            hist.record(latency);
+        }
+    }
+
+    /// Merge another worker's drained operations
+    pub fn merge(&mut self, other: &DrainStats) {
+        self.ops += other.ops;
+        self.bytes += other.bytes;
+        if let Some(ref other_latency) = other.latency {
+            match self.latency {
+                Some(ref mut latency) => latency.merge(other_latency),
+                None => self.latency = Some(other_latency.clone()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge() {
+        let mut a = DrainStats::new(false);
+        a.record(4096, Duration::from_micros(100));
+        assert!(a.latency.is_none());
+
+        let mut b = DrainStats::new(true);
+        b.record(8192, Duration::from_millis(5));
+        b.record(8192, Duration::from_millis(7));
+
+        a.merge(&b);
+        assert_eq!(a.ops, 3);
+        assert_eq!(a.bytes, 20480);
+        assert_eq!(a.latency.unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,113 @@
+//! Append-grow write statistics
+//!
+//! In append-grow mode (`--grow-to`), writes first extend the file at EOF and
+//! switch to overwriting existing blocks once the size target is reached.
+//! Extending writes pay for block allocation (delayed allocation on XFS/ext4,
+//! layout updates on network filesystems), so they are tracked separately from
+//! overwrites instead of being averaged together in the write statistics.
+//!
+//! # Example
+//!
+//! ```
+//! use iopulse::stats::grow::GrowStats;
+//! use std::time::Duration;
+//!
+//! let mut stats = GrowStats::default();
+//! stats.record(true, 4096, Duration::from_micros(80));
+//! stats.record(false, 4096, Duration::from_micros(20));
+//!
+//! assert_eq!(stats.extend.ops, 1);
+//! assert_eq!(stats.overwrite.bytes, 4096);
+//! ```
+
+use super::simple_histogram::SimpleHistogram;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Counters and latency of one class of writes
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GrowWriteStats {
+    /// Completed writes
+    pub ops: u64,
+    /// Bytes written
+    pub bytes: u64,
+    /// Write latency
+    pub latency: SimpleHistogram,
+}
+
+impl GrowWriteStats {
+    fn record(&mut self, bytes: usize, latency: Duration) {
+        self.ops += 1;
+        self.bytes += bytes as u64;
+        self.latency.record(latency);
+    }
+
+    fn merge(&mut self, other: &GrowWriteStats) {
+        self.ops += other.ops;
+        self.bytes += other.bytes;
+        self.latency.merge(&other.latency);
+    }
+}
+
+/// Extension vs overwrite breakdown of writes in append-grow mode
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GrowStats {
+    /// Writes that extended the file past its EOF
+    pub extend: GrowWriteStats,
+    /// Writes to already-allocated blocks (after the size target was reached)
+    pub overwrite: GrowWriteStats,
+    /// File size reached (bytes, summed across files)
+    pub final_size: u64,
+    /// Seconds into the test when the size target was reached (slowest file)
+    pub target_reached_secs: Option<f64>,
+}
+
+impl GrowStats {
+    /// Record a completed write
+    #[inline]
+    pub fn record(&mut self, extend: bool, bytes: usize, latency: Duration) {
+        if extend {
+            self.extend.record(bytes, latency);
+        } else {
+            self.overwrite.record(bytes, latency);
+        }
+    }
+
+    /// Merge stats of another worker's file
+    pub fn merge(&mut self, other: &GrowStats) {
+        self.extend.merge(&other.extend);
+        self.overwrite.merge(&other.overwrite);
+        self.final_size += other.final_size;
+        self.target_reached_secs = match (self.target_reached_secs, other.target_reached_secs) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge() {
+        let mut a = GrowStats::default();
+        a.record(true, 4096, Duration::from_micros(100));
+        a.final_size = 1 << 20;
+        a.target_reached_secs = Some(2.0);
+
+        let mut b = GrowStats::default();
+        b.record(true, 8192, Duration::from_micros(300));
+        b.record(false, 4096, Duration::from_micros(10));
+        b.final_size = 1 << 20;
+        b.target_reached_secs = Some(3.5);
+
+        a.merge(&b);
+        assert_eq!(a.extend.ops, 2);
+        assert_eq!(a.extend.bytes, 12288);
+        assert_eq!(a.extend.latency.len(), 2);
+        assert_eq!(a.overwrite.ops, 1);
+        assert_eq!(a.final_size, 2 << 20);
+        assert_eq!(a.target_reached_secs, Some(3.5));
+    }
+}
@@ -0,0 +1,135 @@
+//! Heatmap buckets over time
+//!
+//! The block heatmap (`--heatmap`) counts accesses over the whole run. With
+//! `--heatmap-series` each worker also counts accesses per offset bucket for
+//! every live interval, so how access locality moves through the target can
+//! be plotted or animated outside IOPulse. Buckets are laid out like the final
+//! heatmap: `--heatmap-buckets` equal ranges of blocks over the target.
+//!
+//! # Example
+//!
+//! ```
+//! use iopulse::stats::heatmap::HeatmapSeries;
+//! use std::time::{Duration, Instant};
+//!
+//! let start = Instant::now();
+//! let mut series = HeatmapSeries::new(4, 100, Duration::from_secs(1), start);
+//! series.record(10);
+//! series.record(99);
+//! series.advance(start + Duration::from_secs(1));
+//! series.record(60);
+//! series.finish();
+//!
+//! assert_eq!(series.intervals, [vec![1, 0, 0, 1], vec![0, 0, 1, 0]]);
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Heatmap counts as carried in final results
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeatmapCounts {
+    /// Blocks counted together in each entry
+    pub blocks_per_bucket: u64,
+    /// (first block, access count) pairs, sorted by block
+    pub entries: Vec<(u64, u64)>,
+}
+
+/// Accesses per offset bucket in each live interval
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HeatmapSeries {
+    /// Length of each interval (the last one may be shorter)
+    pub interval_ms: u64,
+    /// Blocks in each bucket
+    pub blocks_per_bucket: u64,
+    /// Access counts of every interval, one per bucket
+    pub intervals: Vec<Vec<u64>>,
+    /// Counts of the interval in progress
+    #[serde(skip)]
+    current: Vec<u64>,
+    /// End of the interval in progress
+    #[serde(skip)]
+    next_boundary: Option<Instant>,
+}
+
+impl HeatmapSeries {
+    /// Series of `buckets` buckets over `total_blocks` blocks, starting at `start`
+    pub fn new(buckets: usize, total_blocks: u64, interval: Duration, start: Instant) -> Self {
+        let buckets = buckets.max(1);
+        Self {
+            interval_ms: interval.as_millis() as u64,
+            blocks_per_bucket: total_blocks.div_ceil(buckets as u64).max(1),
+            intervals: Vec::new(),
+            current: vec![0; buckets],
+            next_boundary: Some(start + interval),
+        }
+    }
+
+    /// Count an access to `block`
+    #[inline]
+    pub fn record(&mut self, block: u64) {
+        let bucket = ((block / self.blocks_per_bucket) as usize).min(self.current.len() - 1);
+        self.current[bucket] += 1;
+    }
+
+    /// Close every interval that ended by `now`
+    pub fn advance(&mut self, now: Instant) {
+        let interval = Duration::from_millis(self.interval_ms);
+        while let Some(boundary) = self.next_boundary.filter(|&boundary| now >= boundary) {
+            self.close_interval();
+            self.next_boundary = Some(boundary + interval);
+        }
+    }
+
+    /// Close the interval in progress at the end of the test
+    pub fn finish(&mut self) {
+        if self.current.iter().any(|&count| count > 0) {
+            self.close_interval();
+        }
+        self.next_boundary = None;
+    }
+
+    fn close_interval(&mut self) {
+        let buckets = self.current.len();
+        self.intervals.push(std::mem::replace(&mut self.current, vec![0; buckets]));
+    }
+
+    /// Add another worker's series, interval by interval
+    pub fn merge(&mut self, other: &HeatmapSeries) {
+        for (i, counts) in other.intervals.iter().enumerate() {
+            if i == self.intervals.len() {
+                self.intervals.push(vec![0; counts.len()]);
+            }
+            let interval = &mut self.intervals[i];
+            if interval.len() < counts.len() {
+                interval.resize(counts.len(), 0);
+            }
+            for (total, count) in interval.iter_mut().zip(counts) {
+                *total += count;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_and_idle_intervals() {
+        let start = Instant::now();
+        let mut a = HeatmapSeries::new(2, 10, Duration::from_millis(100), start);
+        a.record(0);
+        // An idle interval is kept as zeros
+        a.advance(start + Duration::from_millis(250));
+        a.record(9);
+        a.finish();
+        assert_eq!(a.intervals, [vec![1, 0], vec![0, 0], vec![0, 1]]);
+
+        let mut b = HeatmapSeries::new(2, 10, Duration::from_millis(100), start);
+        b.record(5);
+        b.finish();
+        b.merge(&a);
+        assert_eq!(b.intervals, [vec![1, 1], vec![0, 0], vec![0, 1]]);
+    }
+}
@@ -0,0 +1,67 @@
+//! Cost of file access hints issued during the test
+//!
+//! In file-list mode each file is opened while the test runs, so its fadvise
+//! (and, with the mmap engine, madvise) hints are issued inside the measured
+//! phase. WILLNEED and DONTNEED start readahead or evict cached pages and can
+//! take far longer than the IO they precede. `HintStats` counts the hint
+//! calls, the time spent in them, and the calls skipped because the file had
+//! already been advised.
+//!
+//! # Example
+//!
+//! ```
+//! use iopulse::stats::hints::HintStats;
+//! use std::time::Duration;
+//!
+//! let mut hints = HintStats::default();
+//! hints.record(Duration::from_micros(40));
+//! hints.record_cached();
+//!
+//! assert_eq!(hints.applied, 1);
+//! assert_eq!(hints.cached, 1);
+//! assert_eq!(hints.time(), Duration::from_micros(40));
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Hint calls made on files opened during the test
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HintStats {
+    /// Files advised (one fadvise/madvise batch per opened file)
+    pub applied: u64,
+    /// Page-cache hints skipped because the file was already advised
+    pub cached: u64,
+    /// Time spent issuing hints (nanoseconds)
+    pub time_ns: u64,
+}
+
+impl HintStats {
+    /// Record one batch of hints applied to a file
+    pub fn record(&mut self, duration: Duration) {
+        self.applied += 1;
+        self.time_ns += duration.as_nanos() as u64;
+    }
+
+    /// Record page-cache hints skipped for an already advised file
+    pub fn record_cached(&mut self) {
+        self.cached += 1;
+    }
+
+    /// Merge another worker's counts
+    pub fn merge(&mut self, other: &HintStats) {
+        self.applied += other.applied;
+        self.cached += other.cached;
+        self.time_ns += other.time_ns;
+    }
+
+    /// Total time spent issuing hints
+    pub fn time(&self) -> Duration {
+        Duration::from_nanos(self.time_ns)
+    }
+
+    /// No hints issued or skipped
+    pub fn is_empty(&self) -> bool {
+        self.applied == 0 && self.cached == 0
+    }
+}
@@ -0,0 +1,114 @@
+//! Direct vs buffered IO statistics
+//!
+//! With `--buffered-percent`, an O_DIRECT test sends a share of its
+//! operations through a second, buffered descriptor of the same target. The
+//! two paths are tracked separately so cache-bypass and cache-hit behavior
+//! can be compared within one run instead of across two.
+//!
+//! # Example
+//!
+//! ```
+//! use iopulse::stats::io_path::IoPathStats;
+//! use iopulse::engine::OperationType;
+//! use std::time::Duration;
+//!
+//! let mut stats = IoPathStats::default();
+//! stats.record(false, OperationType::Read, 4096, Duration::from_micros(90));
+//! stats.record(true, OperationType::Read, 4096, Duration::from_micros(3));
+//!
+//! assert_eq!(stats.direct.read_ops, 1);
+//! assert_eq!(stats.buffered.bytes, 4096);
+//! ```
+
+use super::simple_histogram::SimpleHistogram;
+use crate::engine::OperationType;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Counters and latency of the operations of one IO path
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PathStats {
+    /// Completed reads
+    pub read_ops: u64,
+    /// Completed writes
+    pub write_ops: u64,
+    /// Bytes transferred
+    pub bytes: u64,
+    /// Latency of reads and writes
+    pub latency: SimpleHistogram,
+}
+
+impl PathStats {
+    /// Completed reads and writes
+    pub fn ops(&self) -> u64 {
+        self.read_ops + self.write_ops
+    }
+
+    fn record(&mut self, op_type: OperationType, bytes: usize, latency: Duration) {
+        match op_type {
+            OperationType::Read => self.read_ops += 1,
+            OperationType::Write => self.write_ops += 1,
+            _ => return,
+        }
+        self.bytes += bytes as u64;
+        self.latency.record(latency);
+    }
+
+    fn merge(&mut self, other: &PathStats) {
+        self.read_ops += other.read_ops;
+        self.write_ops += other.write_ops;
+        self.bytes += other.bytes;
+        self.latency.merge(&other.latency);
+    }
+}
+
+/// O_DIRECT vs buffered breakdown of the IO (only with --buffered-percent)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IoPathStats {
+    /// Operations issued through the O_DIRECT descriptor
+    pub direct: PathStats,
+    /// Operations issued through the buffered descriptor
+    pub buffered: PathStats,
+}
+
+impl IoPathStats {
+    /// Record a completed operation
+    #[inline]
+    pub fn record(&mut self, buffered: bool, op_type: OperationType, bytes: usize, latency: Duration) {
+        if buffered {
+            self.buffered.record(op_type, bytes, latency);
+        } else {
+            self.direct.record(op_type, bytes, latency);
+        }
+    }
+
+    /// Merge another worker's stats
+    pub fn merge(&mut self, other: &IoPathStats) {
+        self.direct.merge(&other.direct);
+        self.buffered.merge(&other.buffered);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge() {
+        let mut a = IoPathStats::default();
+        a.record(false, OperationType::Read, 4096, Duration::from_micros(100));
+        a.record(true, OperationType::Write, 4096, Duration::from_micros(5));
+
+        let mut b = IoPathStats::default();
+        b.record(true, OperationType::Read, 8192, Duration::from_micros(2));
+        // Only reads and writes are recorded
+        b.record(true, OperationType::Fsync, 0, Duration::from_micros(50));
+
+        a.merge(&b);
+        assert_eq!(a.direct.ops(), 1);
+        assert_eq!(a.buffered.read_ops, 1);
+        assert_eq!(a.buffered.write_ops, 1);
+        assert_eq!(a.buffered.bytes, 12288);
+        assert_eq!(a.buffered.latency.len(), 2);
+    }
+}
@@ -0,0 +1,123 @@
+//! Distribution of completed transfer sizes
+//!
+//! The min/max bytes per operation show whether any IO deviated from the
+//! configured block size, but not how often. `IoSizeHistogram` counts
+//! completed reads and writes by their exact transfer size, so short IO and
+//! unexpected splits (e.g. a 1 MiB request completing as 128 KiB pieces) show
+//! up with their frequency.
+//!
+//! Sizes are kept exactly, up to [`MAX_DISTINCT_SIZES`] distinct values;
+//! operations of any further size are counted as overflow.
+//!
+//! # Example
+//!
+//! ```
+//! use iopulse::stats::io_sizes::IoSizeHistogram;
+//!
+//! let mut sizes = IoSizeHistogram::default();
+//! sizes.record(4096);
+//! sizes.record(4096);
+//! sizes.record(3584);  // short read
+//!
+//! assert_eq!(sizes.sizes(), &[(3584, 1), (4096, 2)]);
+//! assert_eq!(sizes.total_ops(), 3);
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+/// Distinct sizes tracked before further sizes are counted as overflow
+pub const MAX_DISTINCT_SIZES: usize = 64;
+
+/// Operation counts by exact transfer size
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IoSizeHistogram {
+    /// (bytes, ops), sorted by size
+    sizes: Vec<(u64, u64)>,
+    /// Operations whose size did not fit in the table
+    overflow: u64,
+}
+
+impl IoSizeHistogram {
+    /// Record one completed transfer
+    #[inline]
+    pub fn record(&mut self, bytes: u64) {
+        self.add(bytes, 1);
+    }
+
+    fn add(&mut self, bytes: u64, ops: u64) {
+        match self.sizes.binary_search_by_key(&bytes, |&(size, _)| size) {
+            Ok(i) => self.sizes[i].1 += ops,
+            Err(_) if self.sizes.len() >= MAX_DISTINCT_SIZES => self.overflow += ops,
+            Err(i) => self.sizes.insert(i, (bytes, ops)),
+        }
+    }
+
+    /// Merge another worker's sizes
+    pub fn merge(&mut self, other: &IoSizeHistogram) {
+        for &(bytes, ops) in &other.sizes {
+            self.add(bytes, ops);
+        }
+        self.overflow += other.overflow;
+    }
+
+    /// (bytes, ops) per distinct size, smallest first
+    pub fn sizes(&self) -> &[(u64, u64)] {
+        &self.sizes
+    }
+
+    /// Operations of one size (0 if none, or if the size overflowed)
+    pub fn ops(&self, bytes: u64) -> u64 {
+        self.sizes.binary_search_by_key(&bytes, |&(size, _)| size)
+            .map_or(0, |i| self.sizes[i].1)
+    }
+
+    /// Operations not broken down by size (table full)
+    pub fn overflow(&self) -> u64 {
+        self.overflow
+    }
+
+    /// Operations recorded
+    pub fn total_ops(&self) -> u64 {
+        self.sizes.iter().map(|&(_, ops)| ops).sum::<u64>() + self.overflow
+    }
+
+    /// No operations recorded
+    pub fn is_empty(&self) -> bool {
+        self.sizes.is_empty() && self.overflow == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge() {
+        let mut a = IoSizeHistogram::default();
+        a.record(131072);
+        a.record(1048576);
+        let mut b = IoSizeHistogram::default();
+        b.record(131072);
+        b.record(65536);
+
+        a.merge(&b);
+        assert_eq!(a.sizes(), &[(65536, 1), (131072, 2), (1048576, 1)]);
+        assert_eq!(a.total_ops(), 4);
+        assert_eq!(a.ops(131072), 2);
+        assert_eq!(a.ops(4096), 0);
+    }
+
+    #[test]
+    fn test_overflow() {
+        let mut sizes = IoSizeHistogram::default();
+        for bytes in 1..=(MAX_DISTINCT_SIZES as u64 + 10) {
+            sizes.record(bytes);
+        }
+        sizes.record(1);
+
+        assert_eq!(sizes.sizes().len(), MAX_DISTINCT_SIZES);
+        assert_eq!(sizes.sizes()[0], (1, 2));
+        assert_eq!(sizes.overflow(), 10);
+        assert_eq!(sizes.total_ops(), MAX_DISTINCT_SIZES as u64 + 11);
+    }
+}
@@ -0,0 +1,83 @@
+//! Latency floor of the kernel IO interface
+//!
+//! With `--latency-floor`, every worker submits no-op requests through its
+//! own io_uring ring before the test, one at a time, and times each round
+//! trip: into the kernel, through io_uring's completion path and back,
+//! without touching storage. No IO through that ring on this machine can
+//! complete faster, so the results report it as a baseline next to the
+//! device latency: what is left of the IO latency is the device and the
+//! block layer.
+//!
+//! # Example
+//!
+//! ```
+//! use iopulse::config::workload::EngineType;
+//! use iopulse::stats::latency_floor::LatencyFloor;
+//! use std::time::Duration;
+//!
+//! let floor = LatencyFloor::new(EngineType::IoUring, &[Duration::from_micros(2); 100]);
+//! assert_eq!(floor.round_trips.len(), 100);
+//! ```
+
+use super::simple_histogram::SimpleHistogram;
+use crate::config::workload::EngineType;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// No-ops each worker times
+pub const NOPS_PER_WORKER: usize = 10_000;
+
+/// Round trips of no-op requests through the engine's kernel interface
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LatencyFloor {
+    /// Engine whose interface was measured
+    pub engine: EngineType,
+    /// Round trip of each no-op
+    pub round_trips: SimpleHistogram,
+}
+
+impl LatencyFloor {
+    /// Floor of one worker's round trips
+    pub fn new(engine: EngineType, round_trips: &[Duration]) -> Self {
+        let mut hist = SimpleHistogram::new();
+        for &round_trip in round_trips {
+            hist.record(round_trip);
+        }
+        Self { engine, round_trips: hist }
+    }
+
+    /// Merge another worker's round trips
+    pub fn merge(&mut self, other: &LatencyFloor) {
+        self.round_trips.merge(&other.round_trips);
+    }
+
+    /// Median round trip as a percentage of the median IO latency
+    pub fn percent_of_median(&self, io_latency: &SimpleHistogram) -> Option<f64> {
+        if self.round_trips.is_empty() || io_latency.is_empty() {
+            return None;
+        }
+        let io_median = io_latency.percentile(50.0).as_secs_f64();
+        (io_median > 0.0).then(|| self.round_trips.percentile(50.0).as_secs_f64() / io_median * 100.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_floor_against_io_latency() {
+        let mut floor = LatencyFloor::new(EngineType::IoUring, &[Duration::from_micros(2); 10]);
+        floor.merge(&LatencyFloor::new(EngineType::IoUring, &[Duration::from_micros(2); 30]));
+        assert_eq!(floor.round_trips.len(), 40);
+
+        let mut io_latency = SimpleHistogram::new();
+        assert_eq!(floor.percent_of_median(&io_latency), None);
+        for _ in 0..10 {
+            io_latency.record(Duration::from_micros(100));
+        }
+        let percent = floor.percent_of_median(&io_latency).unwrap();
+        // Bucket boundaries round both medians a little
+        assert!((1.0..4.0).contains(&percent), "{}", percent);
+    }
+}
@@ -12,6 +12,7 @@
 //! - **JSON output**: Structured data for programmatic consumption
 //! - **Instantaneous metrics**: IOPS and throughput since last update
 //! - **Per-worker stats**: Optional per-worker breakdown
+//! - **Metadata columns**: Optional open/close/fsync rates and open fd count
 //!
 //! # Example
 //!
@@ -31,6 +32,7 @@
 //! ```
 
 use crate::stats::WorkerStats;
+use crate::util::resource::ResourceSnapshot;
 use crate::util::time::{calculate_iops, calculate_throughput, format_rate, format_throughput};
 use std::time::{Duration, Instant};
 
@@ -57,6 +59,9 @@ pub struct LiveStats {
     
     /// Test start time (for elapsed time display)
     test_start: Instant,
+    
+    /// Show metadata op rates and open fd count
+    show_metadata: bool,
 }
 
 /// Snapshot of statistics at a point in time
@@ -69,6 +74,10 @@ struct LiveSnapshot {
     write_bytes: u64,
     errors: u64,
     avg_latency_us: f64,
+    open_ops: u64,
+    close_ops: u64,
+    fsync_ops: u64,
+    open_fds: Option<u64>,
 }
 
 impl LiveSnapshot {
@@ -83,6 +92,10 @@ impl LiveSnapshot {
             write_bytes: stats.write_bytes(),
             errors: stats.errors(),
             avg_latency_us,
+            open_ops: stats.metadata.open_ops.get(),
+            close_ops: stats.metadata.close_ops.get(),
+            fsync_ops: stats.metadata.fsync_ops.get(),
+            open_fds: ResourceSnapshot::open_fd_count(),
         }
     }
     
@@ -95,6 +108,10 @@ impl LiveSnapshot {
             write_bytes: 0,
             errors: 0,
             avg_latency_us: 0.0,
+            open_ops: 0,
+            close_ops: 0,
+            fsync_ops: 0,
+            open_fds: None,
         }
     }
 }
@@ -106,6 +123,19 @@ impl LiveStats {
     ///
     /// * `interval` - Update interval
     pub fn new(interval: Duration) -> Self {
+        Self::with_metadata(interval, false)
+    }
+    
+    /// Create a new live statistics tracker with optional metadata columns
+    ///
+    /// When `show_metadata` is set, the console and CSV output include
+    /// open/close/fsync rates and the process's current open fd count.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - Update interval
+    /// * `show_metadata` - Include metadata columns
+    pub fn with_metadata(interval: Duration, show_metadata: bool) -> Self {
         let now = Instant::now();
         Self {
             interval,
@@ -114,6 +144,7 @@ impl LiveStats {
             current_stats: LiveSnapshot::zero(),
             update_count: 0,
             test_start: now,
+            show_metadata,
         }
     }
     
@@ -160,11 +191,66 @@ impl LiveStats {
             write_bytes,
             errors,
             avg_latency_us,
+            open_ops: self.current_stats.open_ops,
+            close_ops: self.current_stats.close_ops,
+            fsync_ops: self.current_stats.fsync_ops,
+            open_fds: self.current_stats.open_fds,
         };
         self.last_update = Instant::now();
         self.update_count += 1;
     }
     
+    /// Update metadata counters for the latest snapshot
+    ///
+    /// Call after `update_from_snapshot()` when the aggregated snapshot
+    /// carries metadata counters.
+    ///
+    /// # Arguments
+    ///
+    /// * `open_ops` - Total open operations
+    /// * `close_ops` - Total close operations
+    /// * `fsync_ops` - Total fsync operations
+    /// * `open_fds` - Open file descriptors of the processes running the workers
+    pub fn update_metadata_from_snapshot(&mut self, open_ops: u64, close_ops: u64, fsync_ops: u64, open_fds: Option<u64>) {
+        self.current_stats.open_ops = open_ops;
+        self.current_stats.close_ops = close_ops;
+        self.current_stats.fsync_ops = fsync_ops;
+        self.current_stats.open_fds = open_fds;
+    }
+    
+    /// Calculate instantaneous open/close/fsync rates since the last update
+    ///
+    /// Returns (open_rate, close_rate, fsync_rate) in operations per second.
+    fn metadata_rates(&self, elapsed: Duration) -> (f64, f64, f64) {
+        if elapsed.is_zero() {
+            return (0.0, 0.0, 0.0);
+        }
+        
+        let open_delta = self.current_stats.open_ops.saturating_sub(self.last_stats.open_ops);
+        let close_delta = self.current_stats.close_ops.saturating_sub(self.last_stats.close_ops);
+        let fsync_delta = self.current_stats.fsync_ops.saturating_sub(self.last_stats.fsync_ops);
+        
+        (
+            calculate_iops(open_delta, elapsed),
+            calculate_iops(close_delta, elapsed),
+            calculate_iops(fsync_delta, elapsed),
+        )
+    }
+    
+    /// Print metadata columns (no-op unless enabled)
+    fn print_metadata(&self, elapsed: Duration) {
+        if !self.show_metadata {
+            return;
+        }
+        
+        let (open_rate, close_rate, fsync_rate) = self.metadata_rates(elapsed);
+        print!("Open: {} Close: {} Fsync: {} ", format_rate(open_rate), format_rate(close_rate), format_rate(fsync_rate));
+        
+        if let Some(fds) = self.current_stats.open_fds {
+            print!("FDs: {} ", fds);
+        }
+    }
+    
     /// Display statistics to console (single-line format)
     ///
     /// Prints a single line with current IOPS, throughput, average latency, and errors.
@@ -173,7 +259,7 @@ impl LiveStats {
         let elapsed = self.current_stats.timestamp
             .duration_since(self.last_stats.timestamp);
         
-        if elapsed.as_secs() == 0 {
+        if elapsed.is_zero() {
             return; // Avoid division by zero
         }
         
@@ -201,6 +287,8 @@ impl LiveStats {
             print!("Lat: {:.0}µs ", self.current_stats.avg_latency_us);
         }
         
+        self.print_metadata(elapsed);
+        
         if self.current_stats.errors > 0 {
             print!("Errors: {} ", self.current_stats.errors);
         }
@@ -218,7 +306,7 @@ impl LiveStats {
         let elapsed = self.current_stats.timestamp
             .duration_since(self.last_stats.timestamp);
         
-        if elapsed.as_secs() == 0 {
+        if elapsed.is_zero() {
             return;
         }
         
@@ -240,6 +328,8 @@ impl LiveStats {
             print!("Lat: {:.0}µs ", self.current_stats.avg_latency_us);
         }
         
+        self.print_metadata(elapsed);
+        
         println!("Errors: {}", self.current_stats.errors);
     }
     
@@ -250,6 +340,13 @@ impl LiveStats {
         "timestamp,read_iops,write_iops,read_throughput,write_throughput,total_read_ops,total_write_ops,total_read_bytes,total_write_bytes,errors".to_string()
     }
     
+    /// Get CSV header including metadata columns
+    ///
+    /// Matches the rows produced by `to_csv()` when metadata columns are enabled.
+    pub fn csv_header_with_metadata() -> String {
+        format!("{},open_rate,close_rate,fsync_rate,open_fds", Self::csv_header())
+    }
+    
     /// Format current statistics as CSV row
     ///
     /// Returns a CSV row with current statistics.
@@ -262,28 +359,28 @@ impl LiveStats {
         let read_bytes_delta = self.current_stats.read_bytes - self.last_stats.read_bytes;
         let write_bytes_delta = self.current_stats.write_bytes - self.last_stats.write_bytes;
         
-        let read_iops = if elapsed.as_secs() > 0 {
+        let read_iops = if !elapsed.is_zero() {
             calculate_iops(read_ops_delta, elapsed)
         } else {
             0.0
         };
-        let write_iops = if elapsed.as_secs() > 0 {
+        let write_iops = if !elapsed.is_zero() {
             calculate_iops(write_ops_delta, elapsed)
         } else {
             0.0
         };
-        let read_throughput = if elapsed.as_secs() > 0 {
+        let read_throughput = if !elapsed.is_zero() {
             calculate_throughput(read_bytes_delta, elapsed)
         } else {
             0.0
         };
-        let write_throughput = if elapsed.as_secs() > 0 {
+        let write_throughput = if !elapsed.is_zero() {
             calculate_throughput(write_bytes_delta, elapsed)
         } else {
             0.0
         };
         
-        format!(
+        let mut row = format!(
             "{},{:.2},{:.2},{:.2},{:.2},{},{},{},{},{}",
             self.update_count,
             read_iops,
@@ -295,7 +392,17 @@ impl LiveStats {
             self.current_stats.read_bytes,
             self.current_stats.write_bytes,
             self.current_stats.errors
-        )
+        );
+        
+        if self.show_metadata {
+            let (open_rate, close_rate, fsync_rate) = self.metadata_rates(elapsed);
+            let open_fds = self.current_stats.open_fds
+                .map(|fds| fds.to_string())
+                .unwrap_or_default();
+            row.push_str(&format!(",{:.2},{:.2},{:.2},{}", open_rate, close_rate, fsync_rate, open_fds));
+        }
+        
+        row
     }
     
     /// Get update count
@@ -389,5 +496,27 @@ mod tests {
         // Should not panic
         live.display_console_newline();
     }
+    
+    #[test]
+    fn test_metadata_csv_columns() {
+        let mut live = LiveStats::with_metadata(Duration::from_secs(1), true);
+        
+        live.update_from_snapshot(0, 0, 0, 0, 0, 0.0);
+        live.update_metadata_from_snapshot(0, 0, 0, Some(8));
+        live.last_stats = live.current_stats.clone();
+        live.current_stats.timestamp = live.last_stats.timestamp + Duration::from_secs(2);
+        live.update_metadata_from_snapshot(200, 100, 50, Some(12));
+        
+        assert_eq!(live.metadata_rates(Duration::from_secs(2)), (100.0, 50.0, 25.0));
+        
+        let header_cols = LiveStats::csv_header_with_metadata().split(',').count();
+        let row = live.to_csv();
+        assert_eq!(row.split(',').count(), header_cols);
+        assert!(row.ends_with(",100.00,50.00,25.00,12"));
+        
+        // Plain tracker keeps the original column set
+        let plain = LiveStats::new(Duration::from_secs(1));
+        assert_eq!(plain.to_csv().split(',').count(), LiveStats::csv_header().split(',').count());
+    }
 }
 
@@ -37,14 +37,31 @@ pub mod histogram;
 pub mod simple_histogram;
 pub mod aggregator;
 pub mod live;
-
+pub mod setup;
+pub mod grow;
+pub mod rates;
+pub mod io_sizes;
+pub mod hints;
+pub mod contention;
+pub mod file_latency;
+pub mod ost;
+pub mod cpu_time;
+pub mod budget;
+pub mod skew;
+pub mod drain;
+pub mod heatmap;
+pub mod io_path;
+pub mod submit_gaps;
+pub mod latency_floor;
+
+use crate::config::workload::EngineType;
 use crate::engine::OperationType;
 use crate::Result;
 use simple_histogram::SimpleHistogram as LatencyHistogram;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
-use std::collections::HashSet;
+use budget::{BlockHeatmap, BlockSet};
 
 /// Cache-line aligned atomic counter to prevent false sharing
 ///
@@ -286,6 +303,54 @@ pub struct WorkerStats {
     min_bytes_per_op: AtomicU64,
     max_bytes_per_op: AtomicU64,
     
+    // Completed reads and writes by transfer size
+    io_sizes: io_sizes::IoSizeHistogram,
+    
+    // Completed writes by transfer size (reads are the difference)
+    write_io_sizes: io_sizes::IoSizeHistogram,
+    
+    // fadvise/madvise calls on files opened during the test (file-list mode)
+    hints: hints::HintStats,
+    
+    // Busy-file collisions (shared file-list mode)
+    contention: contention::ContentionStats,
+    
+    // Engine syscall and poll counters (set by worker at the end of the test)
+    engine: crate::engine::EngineStats,
+    
+    // Live snapshot cadence and its cost (set by worker at the end of the test)
+    live_cadence: crate::worker::live_cadence::LiveCadenceStats,
+    
+    // Latency per file or directory (only with --file-latency-top)
+    file_latency: Option<file_latency::FileLatencyStats>,
+    
+    // Throughput and latency per Lustre OST (only with --ost-stats)
+    osts: ost::OstStats,
+    
+    // CPU time vs wait time (only with --cpu-accounting)
+    cpu_time: Option<cpu_time::CpuAccounting>,
+    
+    // Precision of the latency timer (set by worker at test start)
+    timer: Option<crate::util::fast_time::TimerCheck>,
+    
+    // Byte range assigned to the worker (partitioned single-file mode)
+    offset_range: Option<(u64, u64)>,
+    
+    // Wall-clock start and stop of IO in ns since the UNIX epoch (set by worker at end of test)
+    io_window: Option<(u64, u64)>,
+    
+    // Operations completing after the duration expired (only with --drain-latency exclude/separate)
+    drained: Option<drain::DrainStats>,
+    
+    // O_DIRECT vs buffered operations (only with --buffered-percent)
+    io_paths: Option<io_path::IoPathStats>,
+    
+    // Gaps between submissions (only with think time)
+    submit_gaps: Option<submit_gaps::SubmitGaps>,
+    
+    // Accesses per offset bucket in each live interval (only with --heatmap-series)
+    heatmap_series: Option<heatmap::HeatmapSeries>,
+    
     // Queue depth utilization (for async engines)
     current_queue_depth: AtomicU64,
     peak_queue_depth: AtomicU64,
@@ -310,13 +375,18 @@ pub struct WorkerStats {
     // Lock latency histogram (optional, only when locking is enabled)
     lock_latency: Option<LatencyHistogram>,
     
+    // Latency sampling (--latency-sample): record 1 in N latencies, chosen by
+    // a xorshift state so periodic op patterns don't alias with the rate
+    latency_sample: u64,
+    sample_state: u64,
+    
     // Block access heatmap (optional, only when --heatmap is enabled)
-    // Maps block number to access count
-    block_heatmap: Option<Arc<Mutex<std::collections::HashMap<u64, u64>>>>,
+    // Maps block number (or bucket, under --stats-mem-limit) to access count
+    block_heatmap: Option<Arc<Mutex<BlockHeatmap>>>,
     
     // Unique block tracking (optional, tracks which blocks have been accessed)
     // Used to calculate coverage percentage and rewrite percentage
-    unique_blocks: Option<Arc<Mutex<HashSet<u64>>>>,
+    unique_blocks: Option<Arc<Mutex<BlockSet>>>,
     
     // Actual test duration (excludes setup time like preallocation)
     // Set by worker at end of test
@@ -324,6 +394,42 @@ pub struct WorkerStats {
     
     // Resource utilization tracking (CPU and memory)
     resource_tracker: Arc<Mutex<crate::util::resource::ResourceTracker>>,
+    
+    // Queue depth ramp outcome (only when --qd-ramp is enabled)
+    qd_ramp: Option<crate::worker::qd_ramp::QdRampResult>,
+    
+    // Setup/teardown phase timings (engine init, target open, refill, ...)
+    setup_timings: setup::SetupTimings,
+    
+    // Page faults during the IO test (only tracked for the mmap engine)
+    page_faults: Option<crate::util::resource::PageFaults>,
+    
+    // Buffer pool usage per size class (set by worker at the end of the test)
+    buffer_pool: Vec<crate::util::buffer::BufferClassStats>,
+    
+    // Extension vs overwrite writes (only in append-grow mode)
+    grow: Option<grow::GrowStats>,
+    
+    // Target file fragmentation (set by node service after the test with --fragmentation)
+    fragmentation: Option<crate::util::fragmentation::FragmentationReport>,
+    
+    // Filesystem space consumed (set by the node service after the test with --space-usage)
+    space_usage: Option<crate::util::space::SpaceUsage>,
+    
+    // Reads of the partial block at EOF, shortened to end at the file end
+    eof_clamped: u64,
+    
+    // Queue depth below the configured one (set by worker when the target or engine limits it)
+    queue_depth_limit: Option<crate::worker::queue_limit::QueueDepthLimit>,
+    
+    // Engines the workers ran, which can differ from the configured one (set by worker after engine init)
+    engines: Vec<EngineType>,
+    
+    // No-op round trips through the engine's ring (set by worker before the test with --latency-floor)
+    latency_floor: Option<latency_floor::LatencyFloor>,
+    
+    // Sum of merged workers' own rates (None for a single worker's stats)
+    rate_sum: Option<rates::WorkerRates>,
 }
 
 impl WorkerStats {
@@ -352,6 +458,22 @@ impl WorkerStats {
             verify_failures: AlignedCounter::new(),
             min_bytes_per_op: AtomicU64::new(u64::MAX),
             max_bytes_per_op: AtomicU64::new(0),
+            io_sizes: io_sizes::IoSizeHistogram::default(),
+            write_io_sizes: io_sizes::IoSizeHistogram::default(),
+            hints: hints::HintStats::default(),
+            contention: contention::ContentionStats::default(),
+            engine: crate::engine::EngineStats::default(),
+            live_cadence: crate::worker::live_cadence::LiveCadenceStats::default(),
+            file_latency: None,  // Set by worker with --file-latency-top
+            cpu_time: None,  // Set by worker with --cpu-accounting
+            timer: None,  // Set by worker at test start
+            offset_range: None,  // Set by worker in partitioned mode
+            io_window: None,  // Set by worker at end of test
+            drained: None,  // Set by worker with --drain-latency exclude/separate
+            io_paths: None,  // Set by worker with --buffered-percent
+            submit_gaps: None,  // Set by worker with think time
+            heatmap_series: None,  // Set by worker with --heatmap-series
+            osts: ost::OstStats::default(),
             current_queue_depth: AtomicU64::new(0),
             peak_queue_depth: AtomicU64::new(0),
             queue_depth_samples: AtomicU64::new(0),
@@ -368,10 +490,24 @@ impl WorkerStats {
             } else {
                 None
             },
+            latency_sample: 1,  // Every operation
+            sample_state: 0,
             block_heatmap: None,  // Disabled by default
-            unique_blocks: Some(Arc::new(Mutex::new(HashSet::new()))),  // Always enabled for coverage tracking
+            unique_blocks: Some(Arc::new(Mutex::new(BlockSet::new()))),  // Always enabled for coverage tracking
             test_duration: None,  // Set by worker at end of test
             resource_tracker: Arc::new(Mutex::new(crate::util::resource::ResourceTracker::new())),
+            qd_ramp: None,  // Set by worker when queue depth ramp is enabled
+            page_faults: None,  // Set by worker for the mmap engine
+            buffer_pool: Vec::new(),
+            grow: None,  // Set by worker in append-grow mode
+            fragmentation: None,  // Set by node service after the test
+            space_usage: None,  // Set by node service after the test
+            eof_clamped: 0,
+            queue_depth_limit: None,
+            engines: Vec::new(),
+            latency_floor: None,
+            rate_sum: None,  // Set by merge()
+            setup_timings: setup::SetupTimings::new(),
         }
     }
     
@@ -392,6 +528,22 @@ impl WorkerStats {
             verify_failures: AlignedCounter::new(),
             min_bytes_per_op: AtomicU64::new(u64::MAX),
             max_bytes_per_op: AtomicU64::new(0),
+            io_sizes: io_sizes::IoSizeHistogram::default(),
+            write_io_sizes: io_sizes::IoSizeHistogram::default(),
+            hints: hints::HintStats::default(),
+            contention: contention::ContentionStats::default(),
+            engine: crate::engine::EngineStats::default(),
+            live_cadence: crate::worker::live_cadence::LiveCadenceStats::default(),
+            file_latency: None,  // Set by worker with --file-latency-top
+            cpu_time: None,  // Set by worker with --cpu-accounting
+            timer: None,  // Set by worker at test start
+            offset_range: None,  // Set by worker in partitioned mode
+            io_window: None,  // Set by worker at end of test
+            drained: None,  // Set by worker with --drain-latency exclude/separate
+            io_paths: None,  // Set by worker with --buffered-percent
+            submit_gaps: None,  // Set by worker with think time
+            heatmap_series: None,  // Set by worker with --heatmap-series
+            osts: ost::OstStats::default(),
             current_queue_depth: AtomicU64::new(0),
             peak_queue_depth: AtomicU64::new(0),
             queue_depth_samples: AtomicU64::new(0),
@@ -408,14 +560,28 @@ impl WorkerStats {
             } else {
                 None
             },
+            latency_sample: 1,  // Every operation
+            sample_state: 0,
             block_heatmap: if enable_heatmap {
-                Some(Arc::new(Mutex::new(std::collections::HashMap::new())))
+                Some(Arc::new(Mutex::new(BlockHeatmap::new())))
             } else {
                 None
             },
-            unique_blocks: Some(Arc::new(Mutex::new(HashSet::new()))),  // Always enabled for coverage tracking
+            unique_blocks: Some(Arc::new(Mutex::new(BlockSet::new()))),  // Always enabled for coverage tracking
             test_duration: None,  // Set by worker at end of test
             resource_tracker: Arc::new(Mutex::new(crate::util::resource::ResourceTracker::new())),
+            qd_ramp: None,  // Set by worker when queue depth ramp is enabled
+            page_faults: None,  // Set by worker for the mmap engine
+            buffer_pool: Vec::new(),
+            grow: None,  // Set by worker in append-grow mode
+            fragmentation: None,  // Set by node service after the test
+            space_usage: None,  // Set by node service after the test
+            eof_clamped: 0,
+            queue_depth_limit: None,
+            engines: Vec::new(),
+            latency_floor: None,
+            rate_sum: None,  // Set by merge()
+            setup_timings: setup::SetupTimings::new(),
         }
     }
 
@@ -460,28 +626,67 @@ impl WorkerStats {
                     Err(x) => current_max = x,
                 }
             }
+            
+            self.io_sizes.record(bytes_u64);
+            if op_type == OperationType::Write {
+                self.write_io_sizes.record(bytes_u64);
+            }
         }
         
+        let sampled = self.sample_latency();
         match op_type {
             OperationType::Read => {
                 self.read_ops.add(1);
                 self.read_bytes.add(bytes as u64);
-                self.read_latency.record(latency);
+                if sampled {
+                    self.read_latency.record(latency);
+                }
             }
             OperationType::Write => {
                 self.write_ops.add(1);
                 self.write_bytes.add(bytes as u64);
-                self.write_latency.record(latency);
+                if sampled {
+                    self.write_latency.record(latency);
+                }
             }
             OperationType::Fsync | OperationType::Fdatasync => {
                 self.metadata.fsync_ops.add(1);
-                self.metadata.fsync_latency.record(latency);
+                if sampled {
+                    self.metadata.fsync_latency.record(latency);
+                }
                 return; // Don't record in io_latency histogram
             }
         }
 
         // Record latency in combined histogram (for backward compatibility)
-        self.io_latency.record(latency);
+        if sampled {
+            self.io_latency.record(latency);
+        }
+    }
+    
+    /// Record the latency of only 1 in `rate` operations (--latency-sample)
+    ///
+    /// Counters stay exact; latency histograms hold a random sample of
+    /// operations, so percentiles are estimates (see
+    /// [`simple_histogram::sampled_rank_error`]).
+    pub fn set_latency_sampling(&mut self, rate: u64, seed: u64) {
+        self.latency_sample = rate.max(1);
+        // xorshift state must be non-zero
+        self.sample_state = seed | 1;
+    }
+    
+    /// Whether the current operation's latency is recorded
+    #[inline]
+    fn sample_latency(&mut self) -> bool {
+        if self.latency_sample <= 1 {
+            return true;
+        }
+        let mut x = self.sample_state;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.sample_state = x;
+        x.is_multiple_of(self.latency_sample)
     }
     
     /// Record an error
@@ -520,7 +725,7 @@ impl WorkerStats {
     pub fn record_block_access(&self, block_num: u64) {
         if let Some(ref heatmap) = self.block_heatmap {
             if let Ok(mut map) = heatmap.lock() {
-                *map.entry(block_num).or_insert(0) += 1;
+                map.record(block_num);
             }
         }
     }
@@ -542,18 +747,44 @@ impl WorkerStats {
         }
     }
     
+    /// Cap block tracking memory (--stats-mem-limit)
+    ///
+    /// The coverage set and the heatmap (if enabled) share `bytes` equally.
+    /// Past their share, coverage is sampled and the heatmap uses coarser buckets.
+    pub fn limit_block_tracking(&self, bytes: u64) {
+        let share = if self.block_heatmap.is_some() { bytes / 2 } else { bytes };
+        if let Some(ref heatmap) = self.block_heatmap {
+            if let Ok(mut map) = heatmap.lock() {
+                map.set_limit(share);
+            }
+        }
+        if let Some(ref unique) = self.unique_blocks {
+            if let Ok(mut set) = unique.lock() {
+                set.set_limit(share);
+            }
+        }
+    }
+
     /// Get the number of unique blocks accessed
     ///
-    /// Returns the count of distinct blocks that have been accessed at least once.
+    /// Returns the count of distinct blocks that have been accessed at least once
+    /// (an estimate once coverage tracking is sampled).
     pub fn unique_blocks_count(&self) -> u64 {
         if let Some(ref unique) = self.unique_blocks {
             if let Ok(set) = unique.lock() {
-                return set.len() as u64;
+                return set.len();
             }
         }
         0
     }
     
+    /// Whether the unique block count is an estimate (coverage sampled under --stats-mem-limit)
+    pub fn coverage_is_estimate(&self) -> bool {
+        self.unique_blocks.as_ref()
+            .and_then(|unique| unique.lock().ok().map(|set| set.is_estimate()))
+            .unwrap_or(false)
+    }
+    
     /// Calculate coverage percentage
     ///
     /// Returns the percentage of total blocks that have been accessed.
@@ -692,6 +923,149 @@ impl WorkerStats {
         self.max_bytes_per_op.load(Ordering::Relaxed)
     }
     
+    /// Get the distribution of completed transfer sizes
+    pub fn io_sizes(&self) -> &io_sizes::IoSizeHistogram {
+        &self.io_sizes
+    }
+    
+    /// Get the distribution of completed write sizes
+    pub fn write_io_sizes(&self) -> &io_sizes::IoSizeHistogram {
+        &self.write_io_sizes
+    }
+    
+    /// Record hints applied to a file opened during the test
+    pub fn record_hints(&mut self, duration: Duration) {
+        self.hints.record(duration);
+    }
+    
+    /// Record page-cache hints skipped for an already advised file
+    pub fn record_cached_hints(&mut self) {
+        self.hints.record_cached();
+    }
+    
+    /// Get hint call counts and cost
+    pub fn hints(&self) -> &hints::HintStats {
+        &self.hints
+    }
+    
+    /// Record a file pick that found the file in use by another worker
+    pub fn record_file_collision(&mut self) {
+        self.contention.record_collision();
+    }
+    
+    /// Record a busy file avoided by picking another file
+    pub fn record_file_retry(&mut self) {
+        self.contention.record_retry();
+    }
+    
+    /// Record time spent waiting for a busy file
+    pub fn record_file_wait(&mut self, duration: Duration) {
+        self.contention.record_wait(duration);
+    }
+    
+    /// Get busy-file collision counts
+    pub fn contention(&self) -> &contention::ContentionStats {
+        &self.contention
+    }
+    
+    /// Set the engine's syscall and poll counters
+    pub fn set_engine_stats(&mut self, engine: crate::engine::EngineStats) {
+        self.engine = engine;
+    }
+    
+    /// Get the engine's syscall and poll counters (all zero if not recorded)
+    pub fn engine_stats(&self) -> &crate::engine::EngineStats {
+        &self.engine
+    }
+    
+    /// Set the live snapshot cadence
+    pub fn set_live_cadence(&mut self, cadence: crate::worker::live_cadence::LiveCadenceStats) {
+        self.live_cadence = cadence;
+    }
+    
+    /// Get the live snapshot cadence (empty if not recorded)
+    pub fn live_cadence(&self) -> &crate::worker::live_cadence::LiveCadenceStats {
+        &self.live_cadence
+    }
+    
+    /// Set the per-file latency counters
+    pub fn set_file_latency(&mut self, file_latency: file_latency::FileLatencyStats) {
+        self.file_latency = Some(file_latency);
+    }
+    
+    /// Get the per-file latency counters (None unless --file-latency-top was used)
+    pub fn file_latency(&self) -> Option<&file_latency::FileLatencyStats> {
+        self.file_latency.as_ref()
+    }
+    
+    /// Record a completed IO served by a Lustre OST
+    #[inline]
+    pub fn record_ost_io(&mut self, ost: u32, bytes: usize, latency: Duration) {
+        self.osts.record(ost, bytes, latency);
+    }
+    
+    /// Record a completed IO whose OST is unknown
+    #[inline]
+    pub fn record_unmapped_io(&mut self) {
+        self.osts.record_unmapped();
+    }
+    
+    /// Get per-OST counts (empty unless --ost-stats was used)
+    pub fn ost_stats(&self) -> &ost::OstStats {
+        &self.osts
+    }
+    
+    /// Set the CPU time accounting
+    pub fn set_cpu_time(&mut self, cpu_time: cpu_time::CpuAccounting) {
+        self.cpu_time = Some(cpu_time);
+    }
+    
+    /// Get the CPU time accounting (None unless --cpu-accounting was used)
+    pub fn cpu_time(&self) -> Option<&cpu_time::CpuAccounting> {
+        self.cpu_time.as_ref()
+    }
+    
+    /// Set the measured precision of the latency timer
+    pub fn set_timer(&mut self, timer: crate::util::fast_time::TimerCheck) {
+        self.timer = Some(timer);
+    }
+    
+    /// Get the measured precision of the latency timer
+    pub fn timer(&self) -> Option<crate::util::fast_time::TimerCheck> {
+        self.timer
+    }
+    
+    /// Set the byte range assigned to the worker
+    pub fn set_offset_range(&mut self, range: (u64, u64)) {
+        self.offset_range = Some(range);
+    }
+    
+    /// Get the worker's assigned byte range (None unless partitioned single-file mode)
+    pub fn offset_range(&self) -> Option<(u64, u64)> {
+        self.offset_range
+    }
+    
+    /// Set when the worker started and stopped issuing IO (ns since the UNIX epoch)
+    pub fn set_io_window(&mut self, start_ns: u64, stop_ns: u64) {
+        self.io_window = Some((start_ns, stop_ns));
+    }
+    
+    /// Get when IO started and stopped (ns since the UNIX epoch)
+    ///
+    /// Merged stats cover the earliest start to the latest stop.
+    pub fn io_window(&self) -> Option<(u64, u64)> {
+        self.io_window
+    }
+    
+    /// Warning when the median IO latency is close to the timer noise floor
+    pub fn timer_warning(&self) -> Option<String> {
+        let hist = self.io_latency();
+        if hist.is_empty() {
+            return None;
+        }
+        self.timer?.warning(hist.percentile(50.0))
+    }
+    
     /// Sample current queue depth (for async engines)
     #[inline]
     pub fn sample_queue_depth(&self, in_flight: u64) {
@@ -743,6 +1117,195 @@ impl WorkerStats {
         self.test_duration
     }
 
+    /// Sum of each worker's ops and bytes over its own duration
+    ///
+    /// For a single worker this is its rate over `test_duration`; merged stats
+    /// carry the sum of the merged workers' rates. Unlike rates over the longest
+    /// worker's duration, this does not under-report workers that finished early.
+    pub fn worker_rate_sum(&self) -> rates::WorkerRates {
+        match (self.rate_sum, self.test_duration) {
+            (Some(sum), _) => sum,
+            (None, Some(duration)) => rates::WorkerRates::over(
+                self.read_ops(), self.write_ops(), self.read_bytes(), self.write_bytes(), duration),
+            (None, None) => rates::WorkerRates::default(),
+        }
+    }
+
+    /// Merged per-worker rate sum (None unless these stats came from merge())
+    pub fn rate_sum(&self) -> Option<rates::WorkerRates> {
+        self.rate_sum
+    }
+
+    /// Set the queue depth ramp outcome
+    pub fn set_qd_ramp(&mut self, result: crate::worker::qd_ramp::QdRampResult) {
+        self.qd_ramp = Some(result);
+    }
+    
+    /// Get the queue depth ramp outcome (if the ramp was enabled)
+    pub fn qd_ramp(&self) -> Option<&crate::worker::qd_ramp::QdRampResult> {
+        self.qd_ramp.as_ref()
+    }
+
+    /// Set the page faults taken during the IO test
+    pub fn set_page_faults(&mut self, faults: crate::util::resource::PageFaults) {
+        self.page_faults = Some(faults);
+    }
+    
+    /// Get the page faults taken during the IO test (mmap engine only)
+    pub fn page_faults(&self) -> Option<crate::util::resource::PageFaults> {
+        self.page_faults
+    }
+    
+    /// Set the buffer pool usage per size class
+    pub fn set_buffer_pool_stats(&mut self, classes: Vec<crate::util::buffer::BufferClassStats>) {
+        self.buffer_pool = classes;
+    }
+    
+    /// Get the buffer pool usage per size class (empty if not recorded)
+    pub fn buffer_pool_stats(&self) -> &[crate::util::buffer::BufferClassStats] {
+        &self.buffer_pool
+    }
+    
+    /// Record a completed append-grow write (extending or overwriting)
+    #[inline]
+    pub fn record_grow_write(&mut self, extend: bool, bytes: usize, latency: Duration) {
+        self.grow.get_or_insert_with(grow::GrowStats::default).record(extend, bytes, latency);
+    }
+    
+    /// Record the file size reached and when the size target was hit
+    pub fn set_grow_progress(&mut self, final_size: u64, target_reached_secs: Option<f64>) {
+        let grow = self.grow.get_or_insert_with(grow::GrowStats::default);
+        grow.final_size = final_size;
+        grow.target_reached_secs = target_reached_secs;
+    }
+    
+    /// Record an operation that completed after the duration expired
+    #[inline]
+    pub fn record_drained(&mut self, bytes: usize, latency: Duration, track_latency: bool) {
+        self.drained.get_or_insert_with(|| drain::DrainStats::new(track_latency)).record(bytes, latency);
+    }
+    
+    /// Get the operations left out of the results (--drain-latency exclude/separate)
+    pub fn drained(&self) -> Option<&drain::DrainStats> {
+        self.drained.as_ref()
+    }
+    
+    /// Record a completed operation of an O_DIRECT/buffered mix
+    #[inline]
+    pub fn record_io_path(&mut self, buffered: bool, op_type: OperationType, bytes: usize, latency: Duration) {
+        self.io_paths.get_or_insert_with(io_path::IoPathStats::default).record(buffered, op_type, bytes, latency);
+    }
+    
+    /// Get the O_DIRECT vs buffered breakdown (only with --buffered-percent)
+    pub fn io_paths(&self) -> Option<&io_path::IoPathStats> {
+        self.io_paths.as_ref()
+    }
+    
+    /// Record the gap between a submission and the one before it
+    #[inline]
+    pub fn record_submit_gap(&mut self, gap: Duration) {
+        self.submit_gaps.get_or_insert_with(submit_gaps::SubmitGaps::default).record(gap);
+    }
+    
+    /// Get the gaps between submissions (only with think time)
+    pub fn submit_gaps(&self) -> Option<submit_gaps::SubmitGaps> {
+        self.submit_gaps
+    }
+    
+    /// Set the per-interval heatmap buckets recorded by the worker
+    pub fn set_heatmap_series(&mut self, series: heatmap::HeatmapSeries) {
+        self.heatmap_series = Some(series);
+    }
+    
+    /// Get the per-interval heatmap buckets (only with --heatmap-series)
+    pub fn heatmap_series(&self) -> Option<&heatmap::HeatmapSeries> {
+        self.heatmap_series.as_ref()
+    }
+    
+    /// Get the append-grow breakdown (append-grow mode only)
+    pub fn grow_stats(&self) -> Option<&grow::GrowStats> {
+        self.grow.as_ref()
+    }
+    
+    /// Set the post-test fragmentation analysis of the target files
+    pub fn set_fragmentation(&mut self, report: crate::util::fragmentation::FragmentationReport) {
+        self.fragmentation = Some(report);
+    }
+    
+    /// Get the fragmentation analysis (only with --fragmentation)
+    pub fn fragmentation(&self) -> Option<crate::util::fragmentation::FragmentationReport> {
+        self.fragmentation
+    }
+    
+    /// Set the filesystem space consumed during the test
+    pub fn set_space_usage(&mut self, usage: crate::util::space::SpaceUsage) {
+        self.space_usage = Some(usage);
+    }
+    
+    /// Get the filesystem space consumed (only with --space-usage)
+    pub fn space_usage(&self) -> Option<&crate::util::space::SpaceUsage> {
+        self.space_usage.as_ref()
+    }
+    
+    /// Record a read that crossed EOF and was clamped to the file end
+    #[inline]
+    pub fn record_eof_clamp(&mut self) {
+        self.eof_clamped += 1;
+    }
+    
+    /// Get the number of reads clamped at EOF
+    pub fn eof_clamped(&self) -> u64 {
+        self.eof_clamped
+    }
+    
+    /// Set the queue depth the worker was limited to
+    pub fn set_queue_depth_limit(&mut self, limit: crate::worker::queue_limit::QueueDepthLimit) {
+        self.queue_depth_limit = Some(limit);
+    }
+    
+    /// Get the queue depth limit (None if the configured queue depth was used)
+    pub fn queue_depth_limit(&self) -> Option<&crate::worker::queue_limit::QueueDepthLimit> {
+        self.queue_depth_limit.as_ref()
+    }
+    
+    /// Record the engine a worker ran
+    pub fn record_engine(&mut self, engine: EngineType) {
+        if !self.engines.contains(&engine) {
+            self.engines.push(engine);
+        }
+    }
+    
+    /// Get the engines the workers ran (empty if no worker initialized one)
+    pub fn engines(&self) -> &[EngineType] {
+        &self.engines
+    }
+    
+    /// Set the no-op round trips measured through the engine's ring
+    pub fn set_latency_floor(&mut self, floor: latency_floor::LatencyFloor) {
+        self.latency_floor = Some(floor);
+    }
+    
+    /// Get the latency floor (None without --latency-floor)
+    pub fn latency_floor(&self) -> Option<&latency_floor::LatencyFloor> {
+        self.latency_floor.as_ref()
+    }
+
+    /// Record time spent in a setup or teardown phase
+    pub fn record_setup_phase(&mut self, name: &str, duration: Duration) {
+        tracing::debug!(phase = name, elapsed = ?duration, "setup phase complete");
+        self.setup_timings.record(name, duration);
+    }
+    
+    /// Get setup/teardown phase timings
+    pub fn setup_timings(&self) -> &setup::SetupTimings {
+        &self.setup_timings
+    }
+    
+    /// Mutable access to setup/teardown phase timings
+    pub fn setup_timings_mut(&mut self) -> &mut setup::SetupTimings {
+        &mut self.setup_timings
+    }
+
     /// Get a reference to the IO latency histogram
     pub fn io_latency(&self) -> &LatencyHistogram {
         &self.io_latency
@@ -769,16 +1332,37 @@ impl WorkerStats {
     pub fn get_heatmap(&self) -> Option<Vec<(u64, u64)>> {
         if let Some(ref heatmap) = self.block_heatmap {
             if let Ok(map) = heatmap.lock() {
-                let mut entries: Vec<(u64, u64)> = map.iter()
-                    .map(|(&block, &count)| (block, count))
-                    .collect();
-                entries.sort_by_key(|&(block, _)| block);
-                return Some(entries);
+                return Some(map.entries());
             }
         }
         None
     }
     
+    /// Get the heatmap counts for transfer in final results (if enabled)
+    pub fn heatmap_counts(&self) -> Option<heatmap::HeatmapCounts> {
+        let heatmap = self.block_heatmap.as_ref()?.lock().ok()?;
+        Some(heatmap::HeatmapCounts {
+            blocks_per_bucket: heatmap.blocks_per_bucket(),
+            entries: heatmap.entries(),
+        })
+    }
+    
+    /// Access counts in `num_buckets` equal buckets over `total_blocks` blocks
+    ///
+    /// Returns the blocks per bucket and the count of each bucket, or None if
+    /// heatmap tracking is not enabled. Accesses past the end count in the last bucket.
+    pub fn heatmap_buckets(&self, num_buckets: usize, total_blocks: u64) -> Option<(u64, Vec<u64>)> {
+        let entries = self.get_heatmap()?;
+        let num_buckets = num_buckets.max(1);
+        let blocks_per_bucket = total_blocks.div_ceil(num_buckets as u64).max(1);
+        let mut buckets = vec![0u64; num_buckets];
+        for (block, count) in entries {
+            let bucket_idx = (block / blocks_per_bucket).min((num_buckets - 1) as u64) as usize;
+            buckets[bucket_idx] += count;
+        }
+        Some((blocks_per_bucket, buckets))
+    }
+    
     /// Generate heatmap summary showing distribution of accesses
     ///
     /// Divides the file into buckets and shows operations per bucket.
@@ -789,24 +1373,13 @@ impl WorkerStats {
     /// * `num_buckets` - Number of buckets to divide file into (default: 100)
     /// * `total_blocks` - Total number of blocks in file
     pub fn heatmap_summary(&self, num_buckets: usize, total_blocks: u64) -> Option<String> {
-        let entries = self.get_heatmap()?;
-        
-        if entries.is_empty() {
-            return Some("No block accesses recorded".to_string());
-        }
-        
-        // Create buckets
-        let blocks_per_bucket = (total_blocks as f64 / num_buckets as f64).ceil() as u64;
-        let mut buckets = vec![0u64; num_buckets];
-        
-        // Fill buckets with access counts
-        for (block, count) in entries.iter() {
-            let bucket_idx = (*block / blocks_per_bucket).min((num_buckets - 1) as u64) as usize;
-            buckets[bucket_idx] += count;
-        }
+        let (blocks_per_bucket, buckets) = self.heatmap_buckets(num_buckets, total_blocks)?;
         
         // Calculate total operations
         let total_ops: u64 = buckets.iter().sum();
+        if total_ops == 0 {
+            return Some("No block accesses recorded".to_string());
+        }
         
         // Find max for scaling
         let max_ops = *buckets.iter().max().unwrap_or(&1);
@@ -858,6 +1431,12 @@ impl WorkerStats {
     ///
     /// Returns an error if histogram merging fails.
     pub fn merge(&mut self, other: &WorkerStats) -> Result<()> {
+        // Per-worker rates use each side's own duration, so sum them before
+        // the counters and durations are combined
+        let mut rate_sum = self.worker_rate_sum();
+        rate_sum.add(&other.worker_rate_sum());
+        self.rate_sum = Some(rate_sum);
+        
         // Merge counters
         self.read_ops.add(other.read_ops.get());
         self.write_ops.add(other.write_ops.get());
@@ -941,9 +1520,7 @@ impl WorkerStats {
         {
             let mut self_map = self_heatmap.lock().unwrap();
             let other_map = other_heatmap.lock().unwrap();
-            for (&block, &count) in other_map.iter() {
-                *self_map.entry(block).or_insert(0) += count;
-            }
+            self_map.merge(&other_map);
         }
         
         // Merge unique blocks if both have them
@@ -952,9 +1529,7 @@ impl WorkerStats {
         {
             let mut self_set = self_unique.lock().unwrap();
             let other_set = other_unique.lock().unwrap();
-            for &block in other_set.iter() {
-                self_set.insert(block);
-            }
+            self_set.merge(&other_set);
         }
         
         // Merge test duration (use max duration across all workers)
@@ -967,6 +1542,121 @@ impl WorkerStats {
             );
         }
         
+        // Merge IO windows (earliest start to latest stop)
+        if let Some((start, stop)) = other.io_window {
+            self.io_window = Some(match self.io_window {
+                Some((self_start, self_stop)) => (self_start.min(start), self_stop.max(stop)),
+                None => (start, stop),
+            });
+        }
+        
+        // Merge queue depth ramp results (step-aligned across workers)
+        if let Some(ref other_ramp) = other.qd_ramp {
+            match self.qd_ramp {
+                Some(ref mut self_ramp) => self_ramp.merge(other_ramp),
+                None => self.qd_ramp = Some(other_ramp.clone()),
+            }
+        }
+        
+        // Setup phases run in parallel across workers - keep the slowest
+        self.setup_timings.merge(&other.setup_timings);
+        
+        // Page faults are per-thread - sum them
+        self.page_faults = crate::util::resource::PageFaults::combine(self.page_faults, other.page_faults);
+        
+        // Each worker has its own pool - sum buffers and counters per size class
+        crate::util::buffer::merge_class_stats(&mut self.buffer_pool, &other.buffer_pool);
+        
+        // Transfer size counts add up
+        self.io_sizes.merge(&other.io_sizes);
+        self.write_io_sizes.merge(&other.write_io_sizes);
+        self.hints.merge(&other.hints);
+        self.contention.merge(&other.contention);
+        self.engine.merge(&other.engine);
+        self.live_cadence.merge(&other.live_cadence);
+        if let Some(ref other_files) = other.file_latency {
+            self.file_latency.get_or_insert_with(Default::default).merge(other_files);
+        }
+        self.osts.merge(&other.osts);
+        if let Some(ref other_cpu) = other.cpu_time {
+            self.cpu_time.get_or_insert_with(Default::default).merge(other_cpu);
+        }
+        // Timers may differ between nodes - keep the least precise
+        self.timer = match (self.timer, other.timer) {
+            (Some(a), Some(b)) => Some(a.worst(b)),
+            (a, b) => a.or(b),
+        };
+        
+        // Append-grow writes - one file per worker, so sizes add up
+        if let Some(ref other_grow) = other.grow {
+            match self.grow {
+                Some(ref mut grow) => grow.merge(other_grow),
+                None => self.grow = Some(other_grow.clone()),
+            }
+        }
+        
+        // Operations drained after the duration
+        if let Some(ref other_drained) = other.drained {
+            match self.drained {
+                Some(ref mut drained) => drained.merge(other_drained),
+                None => self.drained = Some(other_drained.clone()),
+            }
+        }
+        
+        // O_DIRECT vs buffered operations
+        if let Some(ref other_paths) = other.io_paths {
+            match self.io_paths {
+                Some(ref mut paths) => paths.merge(other_paths),
+                None => self.io_paths = Some(other_paths.clone()),
+            }
+        }
+        
+        // Space readings are per-node - shared filesystems count once
+        if let Some(ref other_usage) = other.space_usage {
+            match self.space_usage {
+                Some(ref mut usage) => usage.merge(other_usage),
+                None => self.space_usage = Some(other_usage.clone()),
+            }
+        }
+        
+        // Fragmentation covers disjoint file sets per node
+        if let Some(ref other_frag) = other.fragmentation {
+            self.fragmentation.get_or_insert_with(Default::default).merge(other_frag);
+        }
+        
+        self.eof_clamped += other.eof_clamped;
+        
+        // Queue depth limits - keep the lowest
+        if let Some(ref other_limit) = other.queue_depth_limit {
+            match self.queue_depth_limit {
+                Some(ref mut limit) => limit.merge(other_limit),
+                None => self.queue_depth_limit = Some(other_limit.clone()),
+            }
+        }
+        
+        for &engine in &other.engines {
+            self.record_engine(engine);
+        }
+        
+        // Latency floor - pool every worker's round trips
+        if let Some(ref other_floor) = other.latency_floor {
+            match self.latency_floor {
+                Some(ref mut floor) => floor.merge(other_floor),
+                None => self.latency_floor = Some(other_floor.clone()),
+            }
+        }
+        
+        // Submission gaps are per-worker - sum them
+        self.submit_gaps = submit_gaps::SubmitGaps::combine(self.submit_gaps, other.submit_gaps);
+        
+        // Per-interval heatmap buckets - intervals line up as workers start together
+        if let Some(ref other_series) = other.heatmap_series {
+            match self.heatmap_series {
+                Some(ref mut series) => series.merge(other_series),
+                None => self.heatmap_series = Some(other_series.clone()),
+            }
+        }
+        
         // For resource tracking, use the first worker's tracker that has data
         // All workers track the same process, so any worker's data is valid
         if self.resource_stats().is_none() && other.resource_stats().is_some() {
@@ -1110,14 +1800,94 @@ impl WorkerStats {
                     // We can't reconstruct the exact set, but we can set the count
                     // This is sufficient for coverage_percent() calculation
                     // Note: This is a limitation - we lose the actual block numbers
-                    set.clear();
-                    for i in 0..snapshot.unique_blocks {
-                        set.insert(i);
-                    }
+                    set.restore(snapshot.unique_blocks, snapshot.coverage_estimated);
                 }
             }
         }
         
+        // Set queue depth ramp outcome
+        self.qd_ramp = snapshot.qd_ramp.clone();
+        
+        // Set setup phase timings
+        self.setup_timings = snapshot.setup_timings.clone();
+        
+        // Set page faults
+        self.page_faults = snapshot.page_faults;
+        
+        // Set buffer pool usage
+        self.buffer_pool = snapshot.buffer_pool.clone();
+        
+        // Set append-grow breakdown
+        self.grow = snapshot.grow.clone();
+        
+        // Set per-worker rate sum
+        self.rate_sum = snapshot.rate_sum;
+        
+        // Set transfer size distribution (final results only)
+        if let Some(ref sizes) = snapshot.io_sizes {
+            self.io_sizes = sizes.clone();
+        }
+        if let Some(ref sizes) = snapshot.write_io_sizes {
+            self.write_io_sizes = sizes.clone();
+        }
+        
+        // Set hint cost (final results only)
+        if let Some(ref hints) = snapshot.hints {
+            self.hints = hints.clone();
+        }
+        
+        // Set busy-file collisions (final results only)
+        if let Some(ref contention) = snapshot.contention {
+            self.contention = contention.clone();
+        }
+        
+        // Set engine counters (final results only)
+        if let Some(ref engine) = snapshot.engine {
+            self.engine = engine.clone();
+        }
+        
+        // Set live snapshot cadence (final results only)
+        if let Some(ref cadence) = snapshot.live_cadence {
+            self.live_cadence = cadence.clone();
+        }
+        
+        // Set per-file latency (final results only)
+        self.file_latency = snapshot.file_latency.clone();
+        
+        // Set per-OST counts (final results only)
+        if let Some(ref osts) = snapshot.osts {
+            self.osts = osts.clone();
+        }
+        
+        // Set CPU time accounting (final results only)
+        self.cpu_time = snapshot.cpu_time.clone();
+        
+        // Set timer precision (final results only)
+        self.timer = snapshot.timer;
+        
+        // Set the assigned byte range (final results only)
+        self.offset_range = snapshot.offset_range;
+        
+        // Set the IO start/stop times (final results only)
+        self.io_window = snapshot.io_window;
+        self.drained = snapshot.drained.clone();
+        self.io_paths = snapshot.io_paths.clone();
+        self.submit_gaps = snapshot.submit_gaps;
+        self.space_usage = snapshot.space_usage.clone();
+        self.fragmentation = snapshot.fragmentation;
+        self.eof_clamped = snapshot.eof_clamped;
+        self.queue_depth_limit = snapshot.queue_depth_limit.clone();
+        self.engines = snapshot.engines.clone();
+        self.latency_floor = snapshot.latency_floor.clone();
+        
+        // Set heatmap counts and buckets over time (final results only)
+        if let (Some(ref heatmap), Some(ref counts)) = (&self.block_heatmap, &snapshot.heatmap) {
+            if let Ok(mut map) = heatmap.lock() {
+                map.add_entries(counts.blocks_per_bucket, &counts.entries);
+            }
+        }
+        self.heatmap_series = snapshot.heatmap_series.clone();
+        
         // Set resource stats by creating synthetic stats in the tracker
         if let Ok(mut tracker) = self.resource_tracker.lock() {
             tracker.set_synthetic_stats(snapshot.cpu_percent, snapshot.memory_bytes, snapshot.peak_memory_bytes);
@@ -1277,6 +2047,32 @@ mod tests {
         assert_eq!(stats1.total_bytes(), 18432);
     }
 
+    #[test]
+    fn test_merge_worker_rate_sum() {
+        // Worker 1: 100 reads in 1s, worker 2: 100 reads in 4s
+        let mut worker1 = WorkerStats::new();
+        let mut worker2 = WorkerStats::new();
+        for _ in 0..100 {
+            worker1.record_io(OperationType::Read, 4096, Duration::from_micros(100));
+            worker2.record_io(OperationType::Read, 4096, Duration::from_micros(100));
+        }
+        worker1.set_test_duration(Duration::from_secs(1));
+        worker2.set_test_duration(Duration::from_secs(4));
+
+        let mut merged = WorkerStats::new();
+        merged.merge(&worker1).unwrap();
+        merged.merge(&worker2).unwrap();
+
+        // Wallclock rate is 200 ops / 4s; each worker over its own duration sums to 125
+        assert_eq!(merged.test_duration(), Some(Duration::from_secs(4)));
+        assert_eq!(merged.worker_rate_sum().read_iops, 125.0);
+
+        // Merging merged stats keeps summing rates, not recomputing them
+        let mut cluster = WorkerStats::new();
+        cluster.merge(&merged).unwrap();
+        assert_eq!(cluster.rate_sum().unwrap().read_iops, 125.0);
+    }
+
     #[test]
     fn test_metadata_stats_new() {
         let stats = MetadataStats::new();
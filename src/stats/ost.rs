@@ -0,0 +1,104 @@
+//! Per-OST statistics on striped parallel filesystems
+//!
+//! With `--ost-stats`, each IO on a Lustre file is attributed to the OST
+//! holding its first byte (see `util::striping`), and its bytes and latency
+//! are counted for that OST. Spread over a whole test, the table shows an
+//! OST that is slower or busier than its peers. IOs that span a stripe
+//! boundary count fully towards the first stripe's OST.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Counts of one OST
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OstCounters {
+    /// Completed operations
+    pub ops: u64,
+    /// Bytes transferred
+    pub bytes: u64,
+    /// Sum of operation latencies (nanoseconds)
+    pub total_ns: u64,
+    /// Slowest operation (nanoseconds)
+    pub max_ns: u64,
+}
+
+impl OstCounters {
+    /// Mean latency of completed operations
+    pub fn mean(&self) -> Duration {
+        Duration::from_nanos(self.total_ns / self.ops.max(1))
+    }
+
+    /// Slowest operation
+    pub fn max(&self) -> Duration {
+        Duration::from_nanos(self.max_ns)
+    }
+}
+
+/// Counts by OST index
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OstStats {
+    pub osts: BTreeMap<u32, OstCounters>,
+    /// Operations on files whose layout could not be read (not on Lustre, GPFS)
+    pub unmapped_ops: u64,
+}
+
+impl OstStats {
+    /// Record a completed operation served by `ost`
+    #[inline]
+    pub fn record(&mut self, ost: u32, bytes: usize, latency: Duration) {
+        let ns = latency.as_nanos() as u64;
+        let counters = self.osts.entry(ost).or_default();
+        counters.ops += 1;
+        counters.bytes += bytes as u64;
+        counters.total_ns += ns;
+        counters.max_ns = counters.max_ns.max(ns);
+    }
+
+    /// Record a completed operation with no known OST
+    #[inline]
+    pub fn record_unmapped(&mut self) {
+        self.unmapped_ops += 1;
+    }
+
+    /// Merge another worker's counts
+    pub fn merge(&mut self, other: &OstStats) {
+        for (ost, counters) in &other.osts {
+            let into = self.osts.entry(*ost).or_default();
+            into.ops += counters.ops;
+            into.bytes += counters.bytes;
+            into.total_ns += counters.total_ns;
+            into.max_ns = into.max_ns.max(counters.max_ns);
+        }
+        self.unmapped_ops += other.unmapped_ops;
+    }
+
+    /// Nothing was recorded
+    pub fn is_empty(&self) -> bool {
+        self.osts.is_empty() && self.unmapped_ops == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_merge() {
+        let mut a = OstStats::default();
+        a.record(3, 4096, Duration::from_micros(100));
+        a.record(3, 4096, Duration::from_micros(300));
+        let mut b = OstStats::default();
+        b.record(3, 4096, Duration::from_micros(200));
+        b.record(0, 8192, Duration::from_micros(50));
+        b.record_unmapped();
+
+        a.merge(&b);
+        assert_eq!(a.osts.keys().copied().collect::<Vec<_>>(), vec![0, 3]);
+        assert_eq!(a.osts[&3].ops, 3);
+        assert_eq!(a.osts[&3].bytes, 3 * 4096);
+        assert_eq!(a.osts[&3].mean(), Duration::from_micros(200));
+        assert_eq!(a.osts[&3].max(), Duration::from_micros(300));
+        assert_eq!(a.unmapped_ops, 1);
+    }
+}
@@ -0,0 +1,89 @@
+//! Sum of per-worker rates
+//!
+//! Aggregate IOPS divides the cluster-wide op count by the wallclock duration
+//! of the longest-running worker, which under-reports workers that finished
+//! earlier (byte-count and run-until-complete modes, uneven setup times). The
+//! sum of each worker's ops over its own duration is tracked alongside so both
+//! figures can be reported.
+//!
+//! # Example
+//!
+//! ```
+//! use iopulse::stats::rates::WorkerRates;
+//! use std::time::Duration;
+//!
+//! // Two workers: 1000 reads in 1s and 1000 reads in 2s
+//! let mut sum = WorkerRates::over(1000, 0, 4096 * 1000, 0, Duration::from_secs(1));
+//! sum.add(&WorkerRates::over(1000, 0, 4096 * 1000, 0, Duration::from_secs(2)));
+//!
+//! assert_eq!(sum.total_iops(), 1500.0);
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Operation and byte rates, summed over workers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct WorkerRates {
+    /// Read operations per second
+    pub read_iops: f64,
+    /// Write operations per second
+    pub write_iops: f64,
+    /// Read bytes per second
+    pub read_bps: f64,
+    /// Write bytes per second
+    pub write_bps: f64,
+}
+
+impl WorkerRates {
+    /// Rates of one worker over its own duration
+    pub fn over(read_ops: u64, write_ops: u64, read_bytes: u64, write_bytes: u64, duration: Duration) -> Self {
+        let secs = duration.as_secs_f64();
+        if secs <= 0.0 {
+            return Self::default();
+        }
+        Self {
+            read_iops: read_ops as f64 / secs,
+            write_iops: write_ops as f64 / secs,
+            read_bps: read_bytes as f64 / secs,
+            write_bps: write_bytes as f64 / secs,
+        }
+    }
+
+    /// Add another worker's rates
+    pub fn add(&mut self, other: &WorkerRates) {
+        self.read_iops += other.read_iops;
+        self.write_iops += other.write_iops;
+        self.read_bps += other.read_bps;
+        self.write_bps += other.write_bps;
+    }
+
+    /// Read plus write operations per second
+    pub fn total_iops(&self) -> f64 {
+        self.read_iops + self.write_iops
+    }
+
+    /// Read plus write bytes per second
+    pub fn total_bps(&self) -> f64 {
+        self.read_bps + self.write_bps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_over_zero_duration() {
+        assert_eq!(WorkerRates::over(100, 100, 4096, 4096, Duration::ZERO), WorkerRates::default());
+    }
+
+    #[test]
+    fn test_sum() {
+        let mut sum = WorkerRates::over(500, 500, 1 << 20, 1 << 20, Duration::from_millis(500));
+        sum.add(&WorkerRates::over(0, 1000, 0, 4 << 20, Duration::from_secs(4)));
+        assert_eq!(sum.read_iops, 1000.0);
+        assert_eq!(sum.write_iops, 1250.0);
+        assert_eq!(sum.total_bps(), (5 << 20) as f64);
+    }
+}
@@ -0,0 +1,153 @@
+//! Setup and teardown phase timing
+//!
+//! A "10 second test" can spend minutes in layout generation, pre-allocation,
+//! or refill before the first IO is issued. Each phase is recorded as a named
+//! duration so results can show where the wall-clock time went.
+//!
+//! Phases recorded by workers (engine init, target open, auto-refill) run in
+//! parallel across threads and nodes, so merging keeps the slowest instance of
+//! each phase rather than summing them.
+//!
+//! # Example
+//!
+//! ```
+//! use iopulse::stats::setup::SetupTimings;
+//! use std::time::Duration;
+//!
+//! let mut timings = SetupTimings::new();
+//! timings.record("Engine init", Duration::from_millis(2));
+//! timings.record("Target open", Duration::from_millis(40));
+//!
+//! let mut other = SetupTimings::new();
+//! other.record("Target open", Duration::from_millis(90));
+//!
+//! timings.merge(&other);
+//! assert_eq!(timings.get("Target open"), Some(Duration::from_millis(90)));
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// A single named setup or teardown phase
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SetupPhase {
+    /// Phase name (e.g., "Layout generation")
+    pub name: String,
+    /// Time spent in the phase (nanoseconds)
+    pub duration_ns: u64,
+}
+
+impl SetupPhase {
+    /// Phase duration
+    pub fn duration(&self) -> Duration {
+        Duration::from_nanos(self.duration_ns)
+    }
+}
+
+/// Ordered list of setup/teardown phases
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct SetupTimings {
+    phases: Vec<SetupPhase>,
+}
+
+impl SetupTimings {
+    /// Create an empty timing list
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a phase
+    ///
+    /// Recording a name that already exists adds to its duration, so a phase
+    /// that runs several times (e.g., refill per target) is reported once.
+    pub fn record(&mut self, name: &str, duration: Duration) {
+        let duration_ns = duration.as_nanos() as u64;
+        match self.phases.iter_mut().find(|p| p.name == name) {
+            Some(phase) => phase.duration_ns += duration_ns,
+            None => self.phases.push(SetupPhase {
+                name: name.to_string(),
+                duration_ns,
+            }),
+        }
+    }
+
+    /// Merge phases from a parallel worker or node
+    ///
+    /// Phases present in both keep the longer duration; new phases are
+    /// appended in the other list's order.
+    pub fn merge(&mut self, other: &SetupTimings) {
+        for other_phase in &other.phases {
+            match self.phases.iter_mut().find(|p| p.name == other_phase.name) {
+                Some(phase) => phase.duration_ns = phase.duration_ns.max(other_phase.duration_ns),
+                None => self.phases.push(other_phase.clone()),
+            }
+        }
+    }
+
+    /// Append the phases of a sequential stage (durations of shared names add up)
+    pub fn extend(&mut self, other: &SetupTimings) {
+        for phase in &other.phases {
+            self.record(&phase.name, phase.duration());
+        }
+    }
+
+    /// Duration of a named phase
+    pub fn get(&self, name: &str) -> Option<Duration> {
+        self.phases.iter()
+            .find(|p| p.name == name)
+            .map(|p| p.duration())
+    }
+
+    /// All phases in recording order
+    pub fn phases(&self) -> &[SetupPhase] {
+        &self.phases
+    }
+
+    /// Whether no phases were recorded
+    pub fn is_empty(&self) -> bool {
+        self.phases.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_accumulates_repeated_phase() {
+        let mut timings = SetupTimings::new();
+        timings.record("Auto-refill", Duration::from_millis(100));
+        timings.record("Engine init", Duration::from_millis(1));
+        timings.record("Auto-refill", Duration::from_millis(50));
+
+        assert_eq!(timings.phases().len(), 2);
+        assert_eq!(timings.phases()[0].name, "Auto-refill");
+        assert_eq!(timings.get("Auto-refill"), Some(Duration::from_millis(150)));
+    }
+
+    #[test]
+    fn test_merge_keeps_slowest() {
+        let mut a = SetupTimings::new();
+        a.record("Engine init", Duration::from_millis(5));
+        a.record("Target open", Duration::from_millis(10));
+
+        let mut b = SetupTimings::new();
+        b.record("Target open", Duration::from_millis(30));
+        b.record("Auto-refill", Duration::from_secs(2));
+
+        a.merge(&b);
+        assert_eq!(a.get("Engine init"), Some(Duration::from_millis(5)));
+        assert_eq!(a.get("Target open"), Some(Duration::from_millis(30)));
+        assert_eq!(a.phases().last().map(|p| p.name.as_str()), Some("Auto-refill"));
+    }
+
+    #[test]
+    fn test_serde_is_plain_list() {
+        let mut timings = SetupTimings::new();
+        timings.record("Node connections", Duration::from_nanos(1500));
+
+        let json = serde_json::to_string(&timings).unwrap();
+        assert_eq!(json, r#"[{"name":"Node connections","duration_ns":1500}]"#);
+    }
+}
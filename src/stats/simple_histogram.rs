@@ -14,7 +14,7 @@ use std::time::Duration;
 /// Number of buckets in the histogram
 /// 28 log2 levels * 4 sub-buckets per level = 112 buckets
 /// Covers latencies from 0 to 2^28 microseconds (~268 seconds)
-const NUM_BUCKETS: usize = 112;
+pub const NUM_BUCKETS: usize = 112;
 
 /// Bucket fraction: 4 means 1/4 = 0.25 increments between buckets
 const BUCKET_FRACTION: usize = 4;
@@ -78,6 +78,21 @@ impl SimpleHistogram {
         }
     }
     
+    /// Rebuild a histogram from exported bucket counts
+    ///
+    /// Used to merge results read back from histogram JSON files. `total`
+    /// is the sum of all samples (mean × count).
+    pub fn from_parts(buckets: [u64; NUM_BUCKETS], total: Duration, min: Duration, max: Duration) -> Self {
+        let num_samples = buckets.iter().sum();
+        Self {
+            buckets,
+            num_samples,
+            total_nanos: total.as_nanos() as u64,
+            min_nanos: if num_samples == 0 { u64::MAX } else { min.as_nanos() as u64 },
+            max_nanos: max.as_nanos() as u64,
+        }
+    }
+    
     /// Record a latency sample
     ///
     /// This is the hot path - optimized for speed.
@@ -214,6 +229,28 @@ impl SimpleHistogram {
         self.max_nanos = self.max_nanos.max(other.max_nanos);
     }
     
+    /// Samples recorded since an earlier copy of this histogram
+    ///
+    /// Used to turn cumulative histograms into interval histograms. Min and
+    /// max are not kept per interval; they are estimated from the lowest and
+    /// highest non-empty buckets.
+    pub fn since(&self, earlier: &SimpleHistogram) -> SimpleHistogram {
+        let mut delta = SimpleHistogram::new();
+        for (i, (&now, &before)) in self.buckets.iter().zip(earlier.buckets.iter()).enumerate() {
+            delta.buckets[i] = now.saturating_sub(before);
+        }
+        delta.num_samples = self.num_samples.saturating_sub(earlier.num_samples);
+        delta.total_nanos = self.total_nanos.saturating_sub(earlier.total_nanos);
+        
+        let first = delta.buckets.iter().position(|&count| count > 0);
+        let last = delta.buckets.iter().rposition(|&count| count > 0);
+        if let (Some(first), Some(last)) = (first, last) {
+            delta.min_nanos = (bucket_idx_to_micros(first) * 1000).max(self.min_nanos);
+            delta.max_nanos = (bucket_idx_to_micros(last + 1) * 1000).min(self.max_nanos);
+        }
+        delta
+    }
+    
     /// Reset the histogram
     pub fn reset(&mut self) {
         self.buckets = [0; NUM_BUCKETS];
@@ -274,6 +311,21 @@ pub fn bucket_idx_to_micros(idx: usize) -> u64 {
     base + increment
 }
 
+/// Sampling error of a percentile estimated from `samples` random samples
+///
+/// With --latency-sample, the histograms hold a random sample of operations.
+/// The sample's p-th percentile corresponds to a true percentile within this
+/// many percentile points at 95% confidence (normal approximation of the
+/// binomial rank, 1.96 * sqrt(p * (1 - p) / n)). It does not include the
+/// histogram's own bucket resolution.
+pub fn sampled_rank_error(percentile: f64, samples: u64) -> f64 {
+    if samples == 0 {
+        return 100.0;
+    }
+    let p = (percentile / 100.0).clamp(0.0, 1.0);
+    1.96 * (p * (1.0 - p) / samples as f64).sqrt() * 100.0
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -351,6 +403,32 @@ mod tests {
         assert_eq!(hist1.mean().as_micros(), 25);
     }
     
+    #[test]
+    fn test_simple_histogram_since() {
+        let mut hist = SimpleHistogram::new();
+        hist.record(Duration::from_micros(10));
+        let earlier = hist.clone();
+        
+        hist.record(Duration::from_micros(1000));
+        hist.record(Duration::from_micros(3000));
+        
+        let delta = hist.since(&earlier);
+        assert_eq!(delta.len(), 2);
+        assert_eq!(delta.mean().as_micros(), 2000);
+        assert!(delta.min().as_micros() >= 512 && delta.min().as_micros() <= 1000);
+        assert_eq!(delta.max().as_micros(), 3000);
+        assert!(hist.since(&hist).is_empty());
+    }
+    
+    #[test]
+    fn test_sampled_rank_error() {
+        // p99 from 1M samples: within ~0.02 percentile points
+        let error = sampled_rank_error(99.0, 1_000_000);
+        assert!(error > 0.019 && error < 0.020, "{}", error);
+        assert!(sampled_rank_error(50.0, 10_000) > sampled_rank_error(99.0, 10_000));
+        assert_eq!(sampled_rank_error(99.0, 0), 100.0);
+    }
+    
     #[test]
     fn test_simple_histogram_zero_latency() {
         let mut hist = SimpleHistogram::new();
@@ -0,0 +1,124 @@
+//! Start/stop skew across workers
+//!
+//! Every worker records the wall-clock time it started and stopped issuing
+//! IO. Aggregate rates divide by the longest worker's duration, so when
+//! workers start or stop raggedly (slow preallocation on one node, a late
+//! START, a node that drains slowly) part of that duration ran with fewer
+//! workers than configured and the aggregate understates the storage.
+//!
+//! The skew summary reports how far apart the starts and stops were and for
+//! how much of the run every worker was issuing IO. Comparing nodes assumes
+//! their clocks are synchronized (as the synchronized START already does).
+
+use std::time::Duration;
+
+/// IO start/stop offsets of one node's workers
+///
+/// Offsets are from the earliest start of any worker in the run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeSkew {
+    pub node: String,
+    pub workers: usize,
+    pub first_start: Duration,
+    pub last_start: Duration,
+    pub first_stop: Duration,
+    pub last_stop: Duration,
+}
+
+/// Start/stop skew of all workers in a run
+#[derive(Debug, Clone, PartialEq)]
+pub struct StartStopSkew {
+    pub workers: usize,
+    /// Latest start minus earliest start
+    pub start_skew: Duration,
+    /// Latest stop minus earliest stop
+    pub stop_skew: Duration,
+    /// Earliest start to latest stop
+    pub span: Duration,
+    /// Time every worker was issuing IO (latest start to earliest stop)
+    pub overlap: Duration,
+    /// Per node, in the order nodes first appear
+    pub per_node: Vec<NodeSkew>,
+}
+
+impl StartStopSkew {
+    /// Compute the skew from each worker's (node, IO window in ns since the UNIX epoch)
+    ///
+    /// Returns None without any window (results from older nodes).
+    pub fn from_windows<'a>(windows: impl IntoIterator<Item = (&'a str, (u64, u64))>) -> Option<Self> {
+        let windows: Vec<(&str, (u64, u64))> = windows.into_iter().collect();
+        let first_start = windows.iter().map(|(_, (start, _))| *start).min()?;
+        let offset = |ns: u64| Duration::from_nanos(ns.saturating_sub(first_start));
+
+        let last_start = windows.iter().map(|(_, (start, _))| *start).max()?;
+        let first_stop = windows.iter().map(|(_, (_, stop))| *stop).min()?;
+        let last_stop = windows.iter().map(|(_, (_, stop))| *stop).max()?;
+
+        let mut per_node: Vec<NodeSkew> = Vec::new();
+        for &(node, (start, stop)) in &windows {
+            let (start, stop) = (offset(start), offset(stop));
+            match per_node.iter_mut().find(|n| n.node == node) {
+                Some(entry) => {
+                    entry.workers += 1;
+                    entry.first_start = entry.first_start.min(start);
+                    entry.last_start = entry.last_start.max(start);
+                    entry.first_stop = entry.first_stop.min(stop);
+                    entry.last_stop = entry.last_stop.max(stop);
+                }
+                None => per_node.push(NodeSkew {
+                    node: node.to_string(),
+                    workers: 1,
+                    first_start: start,
+                    last_start: start,
+                    first_stop: stop,
+                    last_stop: stop,
+                }),
+            }
+        }
+
+        Some(Self {
+            workers: windows.len(),
+            start_skew: offset(last_start),
+            stop_skew: Duration::from_nanos(last_stop - first_stop),
+            span: offset(last_stop),
+            overlap: Duration::from_nanos(first_stop.saturating_sub(last_start)),
+            per_node,
+        })
+    }
+
+    /// Share of the span during which every worker was issuing IO (0-100)
+    pub fn overlap_percent(&self) -> f64 {
+        if self.span.is_zero() {
+            return 100.0;
+        }
+        self.overlap.as_secs_f64() / self.span.as_secs_f64() * 100.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skew_across_nodes() {
+        const S: u64 = 1_000_000_000;
+        let windows = [
+            ("10.0.0.1", (100 * S, 110 * S)),
+            ("10.0.0.1", (100 * S + S / 10, 110 * S)),
+            ("10.0.0.2", (101 * S, 110 * S + S / 2)),
+        ];
+        let skew = StartStopSkew::from_windows(windows).unwrap();
+
+        assert_eq!(skew.workers, 3);
+        assert_eq!(skew.start_skew, Duration::from_secs(1));
+        assert_eq!(skew.stop_skew, Duration::from_millis(500));
+        assert_eq!(skew.span, Duration::from_millis(10_500));
+        assert_eq!(skew.overlap, Duration::from_secs(9));
+        assert_eq!(skew.per_node.len(), 2);
+        assert_eq!(skew.per_node[0].node, "10.0.0.1");
+        assert_eq!(skew.per_node[0].last_start, Duration::from_millis(100));
+        assert_eq!(skew.per_node[1].first_start, Duration::from_secs(1));
+
+        assert!(StartStopSkew::from_windows(std::iter::empty()).is_none());
+    }
+}
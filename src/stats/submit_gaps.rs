@@ -0,0 +1,121 @@
+//! Gaps between a worker's submissions
+//!
+//! Think time shapes the offered load, not just its average: sleeping once
+//! every N blocks submits in bursts, `--think-smooth` spaces submissions
+//! evenly. The coefficient of variation of the gaps between submissions
+//! tells the two apart (near 0 for even pacing, well above 1 for bursts).
+//!
+//! Counters are sums, so they combine across workers and subtract between
+//! snapshots like the other interval counters.
+//!
+//! # Example
+//!
+//! ```
+//! use iopulse::stats::submit_gaps::SubmitGaps;
+//! use std::time::Duration;
+//!
+//! let mut gaps = SubmitGaps::default();
+//! gaps.record(Duration::from_micros(100));
+//! gaps.record(Duration::from_micros(100));
+//!
+//! assert_eq!(gaps.mean_us(), 100.0);
+//! assert_eq!(gaps.cv(), 0.0);
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Sum and sum of squares of the gaps between submissions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct SubmitGaps {
+    /// Gaps recorded
+    pub count: u64,
+    /// Sum of the gaps in microseconds
+    pub sum_us: f64,
+    /// Sum of the squared gaps in microseconds²
+    pub sum_sq_us: f64,
+}
+
+impl SubmitGaps {
+    /// Record the gap since the previous submission
+    #[inline]
+    pub fn record(&mut self, gap: Duration) {
+        let us = gap.as_secs_f64() * 1e6;
+        self.count += 1;
+        self.sum_us += us;
+        self.sum_sq_us += us * us;
+    }
+
+    /// Gaps recorded since an earlier reading
+    pub fn since(&self, earlier: &SubmitGaps) -> Self {
+        Self {
+            count: self.count.saturating_sub(earlier.count),
+            sum_us: (self.sum_us - earlier.sum_us).max(0.0),
+            sum_sq_us: (self.sum_sq_us - earlier.sum_sq_us).max(0.0),
+        }
+    }
+
+    /// Add another worker's gaps
+    pub fn add(&mut self, other: &SubmitGaps) {
+        self.count += other.count;
+        self.sum_us += other.sum_us;
+        self.sum_sq_us += other.sum_sq_us;
+    }
+
+    /// Sum optional counters; None only if both are None
+    pub fn combine(a: Option<SubmitGaps>, b: Option<SubmitGaps>) -> Option<SubmitGaps> {
+        match (a, b) {
+            (Some(mut a), Some(b)) => {
+                a.add(&b);
+                Some(a)
+            }
+            (a, b) => a.or(b),
+        }
+    }
+
+    /// Mean gap in microseconds (0 without gaps)
+    pub fn mean_us(&self) -> f64 {
+        if self.count == 0 {
+            return 0.0;
+        }
+        self.sum_us / self.count as f64
+    }
+
+    /// Coefficient of variation of the gaps (stddev / mean, 0 without gaps)
+    pub fn cv(&self) -> f64 {
+        let mean = self.mean_us();
+        if mean <= 0.0 {
+            return 0.0;
+        }
+        let variance = (self.sum_sq_us / self.count as f64 - mean * mean).max(0.0);
+        variance.sqrt() / mean
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cv_tells_bursts_from_even_pacing() {
+        // 4 submissions back to back, then a 400us pause: the same rate as
+        // one submission every 100us
+        let mut bursty = SubmitGaps::default();
+        for gap in [0, 0, 0, 400, 0, 0, 0, 400] {
+            bursty.record(Duration::from_micros(gap));
+        }
+        let mut even = SubmitGaps::default();
+        for _ in 0..8 {
+            even.record(Duration::from_micros(100));
+        }
+        assert_eq!(bursty.mean_us(), even.mean_us());
+        assert!((bursty.cv() - 3f64.sqrt()).abs() < 1e-9);
+        assert!(even.cv() < 1e-9);
+
+        let mut both = SubmitGaps::combine(Some(bursty), None).unwrap();
+        both.add(&even);
+        assert_eq!(both.count, 16);
+        assert_eq!(both.since(&bursty), even);
+        assert_eq!(SubmitGaps::default().cv(), 0.0);
+    }
+}
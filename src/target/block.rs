@@ -0,0 +1,479 @@
+//! Block device target implementation
+//!
+//! This module provides a block device target that implements the Target trait
+//! for raw block devices (e.g., /dev/sda, /dev/nvme0n1).
+//!
+//! # Features
+//!
+//! - Opens block devices with O_DIRECT support
+//! - Detects device size via ioctl (BLKGETSIZE64)
+//! - Validates alignment requirements for O_DIRECT
+//! - Supports fadvise hints (though less useful for block devices)
+//! - Supports file locking (though rarely used for block devices)
+//! - Refuses to write to devices that are mounted or hold a filesystem,
+//!   partition table, RAID, LVM or swap signature (see `check_device`)
+//!
+//! # Requirements
+//!
+//! - Root or appropriate permissions to access block devices
+//! - O_DIRECT typically required for best performance
+//! - Buffer alignment to device block size (usually 512 or 4096 bytes)
+//!
+//! # Example
+//!
+//! ```no_run
+//! use iopulse::target::{Target, OpenFlags};
+//! use iopulse::target::block::BlockTarget;
+//! use std::path::PathBuf;
+//!
+//! // Note: Requires root permissions
+//! let mut target = BlockTarget::new(PathBuf::from("/dev/sdb"));
+//!
+//! let flags = OpenFlags {
+//!     direct: true,  // Recommended for block devices
+//!     sync: false,
+//!     create: false, // Can't create block devices
+//!     truncate: false,
+//!     noatime: false,
+//!     dsync: false,
+//!     rsync: false,
+//!     read_only: false,
+//! };
+//!
+//! target.open(flags).unwrap();
+//! let size = target.size(); // Device size in bytes
+//! let fd = target.fd();
+//! target.close().unwrap();
+//! ```
+
+use super::dio::{DioAlignment, DioAlignmentSource};
+use super::{FadviseFlags, FileLockMode, LockGuard, OpenFlags, Target};
+use crate::Result;
+use anyhow::Context;
+use std::fs::OpenOptions;
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::fs::FileExt;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+// ioctl request code for getting block device size
+const BLKGETSIZE64: libc::c_ulong = 0x80081272;
+
+/// Bytes read from the start of a device when looking for signatures
+const SIGNATURE_PROBE_BYTES: usize = 1024 * 1024;
+
+/// Signatures (offset, magic, name) of data a write would destroy, named as wipefs does
+///
+/// Specific signatures come first: FAT and NTFS boot sectors also end in the
+/// DOS partition table's 0x55AA.
+const SIGNATURES: &[(usize, &[u8], &str)] = &[
+    (0x0, b"LUKS\xba\xbe", "crypto_LUKS"),
+    (0x0, b"XFSB", "xfs"),
+    (0x438, b"\x53\xef", "ext4"),
+    (0x10040, b"_BHRfS_M", "btrfs"),
+    (0x400, b"\x10\x20\xf5\xf2", "f2fs"),
+    (0x1000, b"\xfc\x4e\x2b\xa9", "linux_raid_member"),
+    (0x200, b"LABELONE", "LVM2_member"),
+    (0xff6, b"SWAPSPACE2", "swap"),
+    (0x3, b"NTFS    ", "ntfs"),
+    (0x52, b"FAT32   ", "vfat"),
+    (0x36, b"FAT16   ", "vfat"),
+    (0x36, b"FAT12   ", "vfat"),
+    (0x8001, b"CD001", "iso9660"),
+    (0x200, b"EFI PART", "gpt"),
+    (0x1fe, b"\x55\xaa", "dos"),
+];
+
+/// Size of an open block device via ioctl(BLKGETSIZE64)
+fn ioctl_size(fd: RawFd, path: &Path) -> Result<u64> {
+    let mut size: u64 = 0;
+    let result = unsafe {
+        libc::ioctl(fd, BLKGETSIZE64, &mut size)
+    };
+    
+    if result < 0 {
+        let err = std::io::Error::last_os_error();
+        return Err(err).context(format!(
+            "ioctl(BLKGETSIZE64) failed: path={}",
+            path.display()
+        ));
+    }
+    Ok(size)
+}
+
+/// Size of a block device in bytes
+pub fn device_size(path: &Path) -> Result<u64> {
+    let file = std::fs::File::open(path)
+        .with_context(|| format!("Failed to open block device: {}", path.display()))?;
+    ioctl_size(file.as_raw_fd(), path)
+}
+
+/// Name of the first known signature in `head`, the start of a device
+pub fn find_signature(head: &[u8]) -> Option<&'static str> {
+    SIGNATURES.iter()
+        .find(|(offset, magic, _)| head.get(*offset..offset + magic.len()) == Some(*magic))
+        .map(|(_, _, name)| *name)
+}
+
+/// Where the device or one of its partitions is mounted, as (source, mount point)
+pub fn find_mount(path: &Path) -> Option<(String, String)> {
+    let device = std::fs::canonicalize(path).ok()?;
+    let device = device.to_str()?;
+    let mounts = std::fs::read_to_string("/proc/self/mounts").ok()?;
+    mounts.lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            Some((fields.next()?, fields.next()?))
+        })
+        .filter(|(source, _)| source.starts_with("/dev/"))
+        .find(|(source, _)| {
+            let source = std::fs::canonicalize(source)
+                .map(|p| p.to_string_lossy().into_owned())
+                .unwrap_or_else(|_| source.to_string());
+            // The device itself, or a partition of it (sdb1, nvme0n1p1)
+            source.strip_prefix(device).is_some_and(|rest| {
+                let number = rest.strip_prefix('p').unwrap_or(rest);
+                rest.is_empty() || (!number.is_empty() && number.bytes().all(|b| b.is_ascii_digit()))
+            })
+        })
+        .map(|(source, mount_point)| (source.to_string(), mount_point.replace("\\040", " ")))
+}
+
+/// Check a block device target before any IO
+///
+/// `region` must lie within the device. When `check_writes` is set, a device
+/// that is mounted or holds a signature (filesystem, partition table, RAID,
+/// LVM, swap) is refused: writing would destroy its data.
+pub fn check_device(path: &Path, region: Option<(u64, u64)>, check_writes: bool) -> Result<()> {
+    let file = std::fs::File::open(path)
+        .with_context(|| format!("Failed to open block device: {}", path.display()))?;
+    let size = ioctl_size(file.as_raw_fd(), path)?;
+    
+    if let Some((start, end)) = region {
+        if end > size {
+            anyhow::bail!("Target region {}-{} extends past the end of {} ({} bytes)",
+                start, end, path.display(), size);
+        }
+    }
+    
+    if !check_writes {
+        return Ok(());
+    }
+    
+    if let Some((source, mount_point)) = find_mount(path) {
+        anyhow::bail!("{} is mounted ({} on {}); refusing to write to it (use --force to write anyway)",
+            path.display(), source, mount_point);
+    }
+    
+    let mut head = vec![0u8; SIGNATURE_PROBE_BYTES.min(size as usize)];
+    let mut filled = 0;
+    while filled < head.len() {
+        match file.read_at(&mut head[filled..], filled as u64)
+            .with_context(|| format!("Failed to read signatures of {}", path.display()))?
+        {
+            0 => break,
+            n => filled += n,
+        }
+    }
+    head.truncate(filled);
+    if let Some(signature) = find_signature(&head) {
+        anyhow::bail!("{} holds data ({} signature); writing would destroy it. \
+            Inspect it with 'wipefs {}' and use --force to overwrite it",
+            path.display(), signature, path.display());
+    }
+    
+    Ok(())
+}
+
+/// Block device target
+///
+/// This target represents a raw block device. Block devices have fixed sizes
+/// determined by the hardware and cannot be resized or pre-allocated.
+pub struct BlockTarget {
+    /// Path to the block device (e.g., /dev/sda)
+    path: PathBuf,
+    
+    /// File descriptor (Some when open)
+    fd: Option<RawFd>,
+    
+    /// Device size in bytes (determined via ioctl)
+    device_size: u64,
+    
+    /// O_DIRECT alignment requirements (detected at open)
+    dio_alignment: DioAlignment,
+}
+
+impl BlockTarget {
+    /// Create a new block device target
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the block device (e.g., /dev/sda, /dev/nvme0n1)
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            fd: None,
+            device_size: 0,
+            dio_alignment: DioAlignment::uniform(512, DioAlignmentSource::Fallback),
+        }
+    }
+    
+    /// Detect block device size using ioctl
+    ///
+    /// This should be called after the device is opened.
+    fn detect_size(&mut self) -> Result<()> {
+        let fd = self.fd.ok_or_else(|| anyhow::anyhow!("Device not open"))?;
+        self.device_size = ioctl_size(fd, &self.path)?;
+        Ok(())
+    }
+}
+
+impl Target for BlockTarget {
+    fn open(&mut self, flags: OpenFlags) -> Result<()> {
+        let mut options = OpenOptions::new();
+        options.read(true).write(!flags.read_only);
+        
+        // Block devices can't be created or truncated
+        if flags.create {
+            anyhow::bail!("Cannot create block device: {}", self.path.display());
+        }
+        if flags.truncate {
+            anyhow::bail!("Cannot truncate block device: {}", self.path.display());
+        }
+        
+        // O_DIRECT, O_SYNC and the extra open flags
+        let custom_flags = flags.custom_flags();
+        if custom_flags != 0 {
+            options.custom_flags(custom_flags);
+        }
+        
+        // Open the block device
+        let file = options.open(&self.path)
+            .with_context(|| format!("Failed to open block device: {}{}", self.path.display(), flags.open_error_hint()))?;
+        
+        let fd = file.as_raw_fd();
+        self.fd = Some(fd);
+        std::mem::forget(file); // Don't close on drop
+        
+        // Detect device size and O_DIRECT alignment (logical sector size)
+        self.detect_size()?;
+        self.dio_alignment = DioAlignment::detect(fd);
+        
+        Ok(())
+    }
+    
+    fn fd(&self) -> RawFd {
+        self.fd.expect("Device not open")
+    }
+    
+    fn size(&self) -> u64 {
+        self.device_size
+    }
+    
+    fn apply_fadvise(&self, flags: &FadviseFlags) -> Result<()> {
+        let fd = self.fd.ok_or_else(|| anyhow::anyhow!("Device not open"))?;
+        
+        // fadvise hints are less useful for block devices but we support them anyway
+        if flags.sequential {
+            let result = unsafe {
+                libc::posix_fadvise(fd, 0, 0, libc::POSIX_FADV_SEQUENTIAL)
+            };
+            if result != 0 {
+                // Don't fail on fadvise errors for block devices
+                tracing::warn!("posix_fadvise(SEQUENTIAL) failed for block device");
+            }
+        }
+        
+        if flags.random {
+            let result = unsafe {
+                libc::posix_fadvise(fd, 0, 0, libc::POSIX_FADV_RANDOM)
+            };
+            if result != 0 {
+                tracing::warn!("posix_fadvise(RANDOM) failed for block device");
+            }
+        }
+        
+        // Other hints (willneed, dontneed, noreuse) are typically not useful for block devices
+        // but we could apply them if requested
+        
+        Ok(())
+    }
+    
+    fn lock(&self, mode: FileLockMode, offset: u64, len: u64) -> Result<LockGuard> {
+        if mode == FileLockMode::None {
+            return Ok(LockGuard::new(0, FileLockMode::None, 0, 0));
+        }
+        
+        let fd = self.fd.ok_or_else(|| anyhow::anyhow!("Device not open"))?;
+        
+        // File locking on block devices is unusual but supported
+        let (start, length) = match mode {
+            FileLockMode::None => (0, 0),
+            FileLockMode::Range => (offset, len),
+            FileLockMode::Full => (0, 0),
+        };
+        
+        let flock = libc::flock {
+            l_type: libc::F_WRLCK as i16,
+            l_whence: libc::SEEK_SET as i16,
+            l_start: start as i64,
+            l_len: length as i64,
+            l_pid: 0,
+        };
+        
+        let _start_time = Instant::now();
+        let result = unsafe { libc::fcntl(fd, libc::F_SETLKW, &flock) };
+        
+        if result < 0 {
+            let err = std::io::Error::last_os_error();
+            return Err(err).context(format!(
+                "fcntl(F_SETLKW) failed for block device: mode={:?}",
+                mode
+            ));
+        }
+        
+        Ok(LockGuard::new(fd, mode, start, length))
+    }
+    
+    fn close(&mut self) -> Result<()> {
+        if let Some(fd) = self.fd {
+            let result = unsafe { libc::close(fd) };
+            if result < 0 {
+                let err = std::io::Error::last_os_error();
+                return Err(err).context(format!(
+                    "close failed: path={}",
+                    self.path.display()
+                ));
+            }
+            self.fd = None;
+        }
+        Ok(())
+    }
+    
+    fn logical_block_size(&self) -> u64 {
+        self.dio_alignment.offset.max(512)
+    }
+    
+    fn dio_alignment(&self) -> DioAlignment {
+        self.dio_alignment
+    }
+    
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+impl Drop for BlockTarget {
+    fn drop(&mut self) {
+        // Ensure device is closed
+        let _ = self.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    // Note: Block device tests require root permissions and actual block devices
+    // These tests are mostly for documentation and would need to be run manually
+    // or in a CI environment with appropriate setup
+    
+    #[test]
+    fn test_block_target_creation() {
+        let target = BlockTarget::new(PathBuf::from("/dev/null"));
+        assert_eq!(target.device_size, 0);
+        assert!(target.fd.is_none());
+    }
+    
+    #[test]
+    fn test_block_target_rejects_create() {
+        let mut target = BlockTarget::new(PathBuf::from("/dev/null"));
+        let flags = OpenFlags {
+            direct: false,
+            sync: false,
+            create: true,  // Should be rejected
+            truncate: false,
+            noatime: false,
+            dsync: false,
+            rsync: false,
+            read_only: false,
+        };
+        
+        assert!(target.open(flags).is_err());
+    }
+    
+    #[test]
+    fn test_block_target_rejects_truncate() {
+        let mut target = BlockTarget::new(PathBuf::from("/dev/null"));
+        let flags = OpenFlags {
+            direct: false,
+            sync: false,
+            create: false,
+            truncate: true,  // Should be rejected
+            noatime: false,
+            dsync: false,
+            rsync: false,
+            read_only: false,
+        };
+        
+        assert!(target.open(flags).is_err());
+    }
+    
+    #[test]
+    fn test_find_signature() {
+        let mut head = vec![0u8; 64 * 1024];
+        assert_eq!(find_signature(&head), None);
+        
+        head[0x438..0x43a].copy_from_slice(&[0x53, 0xef]);
+        assert_eq!(find_signature(&head), Some("ext4"));
+        
+        // A FAT boot sector is not reported as a partition table
+        let mut head = vec![0u8; 4096];
+        head[0x1fe..0x200].copy_from_slice(&[0x55, 0xaa]);
+        assert_eq!(find_signature(&head), Some("dos"));
+        head[0x52..0x5a].copy_from_slice(b"FAT32   ");
+        assert_eq!(find_signature(&head), Some("vfat"));
+        
+        // Signatures past the end of a small device are skipped
+        assert_eq!(find_signature(&head[..16]), None);
+    }
+    
+    // The following tests would require actual block devices and root permissions
+    // They are commented out but show the intended usage
+    
+    /*
+    #[test]
+    #[ignore] // Requires root and block device
+    fn test_block_target_open_real_device() {
+        let mut target = BlockTarget::new(PathBuf::from("/dev/sdb"));
+        let flags = OpenFlags {
+            direct: true,
+            sync: false,
+            create: false,
+            truncate: false,
+            noatime: false,
+            dsync: false,
+            rsync: false,
+            read_only: false,
+        };
+        
+        target.open(flags).unwrap();
+        assert!(target.size() > 0);
+        assert!(target.close().is_ok());
+    }
+    
+    #[test]
+    #[ignore] // Requires root and block device
+    fn test_block_target_size_detection() {
+        let mut target = BlockTarget::new(PathBuf::from("/dev/sdb"));
+        let flags = OpenFlags::default();
+        
+        target.open(flags).unwrap();
+        let size = target.size();
+        assert!(size > 0);
+        println!("Device size: {} bytes ({} GB)", size, size / (1024 * 1024 * 1024));
+        target.close().unwrap();
+    }
+    */
+}
@@ -0,0 +1,226 @@
+//! Direct IO alignment detection
+//!
+//! O_DIRECT requires the buffer address, file offset and transfer length to be
+//! aligned, and the required alignment depends on the filesystem and device
+//! rather than being a fixed 512 or 4096 bytes. Alignment is queried per open
+//! target, in order of accuracy:
+//!
+//! 1. `statx(STATX_DIOALIGN)` (Linux 6.1+): exact memory and offset alignment,
+//!    and whether the file supports O_DIRECT at all
+//! 2. `BLKSSZGET` (block devices): logical sector size
+//! 3. `st_blksize` from `fstat`, or 512 if that is not usable
+
+use crate::config::WorkloadConfig;
+use crate::Result;
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::path::Path;
+
+/// How the alignment was determined
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DioAlignmentSource {
+    /// statx(STATX_DIOALIGN)
+    Statx,
+    /// BLKSSZGET ioctl (logical block size)
+    BlockDevice,
+    /// st_blksize or the 512-byte default
+    Fallback,
+}
+
+/// Direct IO alignment requirements of a target
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DioAlignment {
+    /// Required alignment of user buffers (bytes)
+    pub memory: u64,
+    /// Required alignment of file offsets and IO sizes (bytes); 0 if O_DIRECT is unsupported
+    pub offset: u64,
+    /// Where the values came from
+    pub source: DioAlignmentSource,
+}
+
+impl DioAlignment {
+    /// Same alignment for buffers and offsets (block devices and fallbacks)
+    pub fn uniform(size: u64, source: DioAlignmentSource) -> Self {
+        Self { memory: size, offset: size, source }
+    }
+
+    /// Query the alignment of an open file descriptor
+    pub fn detect(fd: RawFd) -> Self {
+        if let Some(alignment) = Self::from_statx(fd) {
+            return alignment;
+        }
+
+        let mut block_size: libc::c_int = 0;
+        if unsafe { libc::ioctl(fd, libc::BLKSSZGET, &mut block_size) } == 0 && block_size > 0 {
+            return Self::uniform(block_size as u64, DioAlignmentSource::BlockDevice);
+        }
+
+        // st_blksize is the "optimal" IO size; use it when it looks like a block size
+        let mut stat: libc::stat = unsafe { std::mem::zeroed() };
+        if unsafe { libc::fstat(fd, &mut stat) } == 0 {
+            let blksize = stat.st_blksize as u64;
+            if blksize >= 512 && blksize.is_power_of_two() {
+                return Self::uniform(blksize, DioAlignmentSource::Fallback);
+            }
+        }
+
+        Self::uniform(512, DioAlignmentSource::Fallback)
+    }
+
+    /// Open an existing target with O_DIRECT just to query its alignment
+    ///
+    /// Used for pre-flight checks before workers start.
+    pub fn probe(path: &Path) -> std::io::Result<Self> {
+        let file = std::fs::OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_DIRECT)
+            .open(path)?;
+        Ok(Self::detect(file.as_raw_fd()))
+    }
+
+    /// statx(STATX_DIOALIGN), if the kernel and filesystem report it
+    #[cfg(target_os = "linux")]
+    fn from_statx(fd: RawFd) -> Option<Self> {
+        let mut stx: libc::statx = unsafe { std::mem::zeroed() };
+        let result = unsafe {
+            libc::statx(fd, c"".as_ptr(), libc::AT_EMPTY_PATH, libc::STATX_DIOALIGN, &mut stx)
+        };
+        if result != 0 || stx.stx_mask & libc::STATX_DIOALIGN == 0 {
+            return None;
+        }
+
+        // Block devices only report DIOALIGN since Linux 6.11; older kernels
+        // set the mask with zero values, so let BLKSSZGET answer instead
+        let is_block_device = (stx.stx_mode as u32 & libc::S_IFMT) == libc::S_IFBLK;
+        if is_block_device && stx.stx_dio_offset_align == 0 {
+            return None;
+        }
+
+        Some(Self {
+            memory: stx.stx_dio_mem_align as u64,
+            offset: stx.stx_dio_offset_align as u64,
+            source: DioAlignmentSource::Statx,
+        })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn from_statx(_fd: RawFd) -> Option<Self> {
+        None
+    }
+
+    /// Whether the target accepts O_DIRECT at all
+    pub fn is_supported(&self) -> bool {
+        self.offset > 0
+    }
+
+    /// Alignment to allocate IO buffers with
+    ///
+    /// Buffers are aligned to the offset alignment as well, so each buffer
+    /// starts on a device block boundary.
+    pub fn buffer_alignment(&self) -> usize {
+        self.memory.max(self.offset).max(512) as usize
+    }
+
+    /// Check that an IO size can be used with O_DIRECT
+    pub fn check_io_size(&self, size: u64) -> std::result::Result<(), String> {
+        if !self.is_supported() {
+            return Err("the filesystem does not support O_DIRECT for this file".to_string());
+        }
+        if !size.is_multiple_of(self.offset) {
+            return Err(format!(
+                "block size {} is not a multiple of the {}-byte O_DIRECT alignment",
+                size, self.offset
+            ));
+        }
+        Ok(())
+    }
+
+    /// Check every IO size of a workload (block size and distributions)
+    pub fn check_workload(&self, path: &Path, workload: &WorkloadConfig) -> Result<()> {
+        let sizes = std::iter::once(workload.block_size)
+            .chain(workload.read_distribution.iter().map(|p| p.block_size))
+            .chain(workload.write_distribution.iter().map(|p| p.block_size));
+
+        for size in sizes {
+            if let Err(reason) = self.check_io_size(size) {
+                let hint = if self.is_supported() {
+                    format!("Use a block size that is a multiple of {} bytes, or run without --direct.", self.offset)
+                } else {
+                    "Run without --direct.".to_string()
+                };
+                anyhow::bail!("Direct IO not possible on {}: {} (detected {}). {}", path.display(), reason, self, hint);
+            }
+        }
+        Ok(())
+    }
+
+    /// The stricter of two alignments (multiple targets in one worker)
+    pub fn max(self, other: Self) -> Self {
+        if !self.is_supported() || !other.is_supported() {
+            return if self.is_supported() { other } else { self };
+        }
+        Self {
+            memory: self.memory.max(other.memory),
+            offset: self.offset.max(other.offset),
+            source: if self.offset >= other.offset { self.source } else { other.source },
+        }
+    }
+}
+
+impl std::fmt::Display for DioAlignment {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let source = match self.source {
+            DioAlignmentSource::Statx => "statx",
+            DioAlignmentSource::BlockDevice => "BLKSSZGET",
+            DioAlignmentSource::Fallback => "default",
+        };
+        if self.is_supported() {
+            write!(f, "offset {} bytes, memory {} bytes ({})", self.offset, self.memory, source)
+        } else {
+            write!(f, "O_DIRECT unsupported ({})", source)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_regular_file() {
+        let file = tempfile::tempfile().unwrap();
+        let alignment = DioAlignment::detect(file.as_raw_fd());
+
+        // statx may report O_DIRECT as unsupported (e.g., tmpfs); otherwise sane values
+        if alignment.is_supported() {
+            assert!(alignment.offset.is_power_of_two());
+            assert!(alignment.buffer_alignment().is_power_of_two());
+            assert!(alignment.buffer_alignment() >= 512);
+        }
+    }
+
+    #[test]
+    fn test_check_io_size() {
+        let alignment = DioAlignment { memory: 4, offset: 4096, source: DioAlignmentSource::Statx };
+        assert!(alignment.check_io_size(4096).is_ok());
+        assert!(alignment.check_io_size(1 << 20).is_ok());
+        assert!(alignment.check_io_size(512).unwrap_err().contains("4096-byte"));
+        assert_eq!(alignment.buffer_alignment(), 4096);
+
+        let unsupported = DioAlignment { memory: 0, offset: 0, source: DioAlignmentSource::Statx };
+        assert!(unsupported.check_io_size(4096).unwrap_err().contains("does not support"));
+    }
+
+    #[test]
+    fn test_max_keeps_stricter() {
+        let a = DioAlignment::uniform(512, DioAlignmentSource::BlockDevice);
+        let b = DioAlignment { memory: 8, offset: 4096, source: DioAlignmentSource::Statx };
+        let merged = a.max(b);
+        assert_eq!(merged.offset, 4096);
+        assert_eq!(merged.memory, 512);
+        assert_eq!(merged.source, DioAlignmentSource::Statx);
+
+        let unsupported = DioAlignment { memory: 0, offset: 0, source: DioAlignmentSource::Statx };
+        assert!(!a.max(unsupported).is_supported());
+    }
+}
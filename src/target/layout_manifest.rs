@@ -0,0 +1,518 @@
+//! Layout manifest file handling
+//!
+//! This module provides functionality for reading and writing layout manifest files.
+//! A layout manifest is a text file that defines the directory structure and file paths
+//! for reproducible testing.
+//!
+//! Paths are stored in a portable form, so a dataset prepared on one OS can
+//! be reused on another: relative to the layout root, with `/` separators.
+//! Loading also accepts `\` separators (manifests edited on Windows) and
+//! CRLF line endings, and rejects absolute paths, `..` and duplicates. A
+//! line is a path and its size in bytes; paths may contain spaces.
+
+use crate::Result;
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Component, Path, PathBuf};
+
+/// Layout manifest containing directory/file structure
+#[derive(Debug, Clone)]
+pub struct LayoutManifest {
+    /// Manifest header with metadata
+    pub header: ManifestHeader,
+    /// List of file entries (path and size)
+    pub file_entries: Vec<FileEntry>,
+}
+
+/// File entry with path and size
+#[derive(Debug, Clone)]
+pub struct FileEntry {
+    /// File path (relative to root)
+    pub path: PathBuf,
+    /// File size in bytes
+    pub size: u64,
+}
+
+/// Manifest header with generation metadata
+#[derive(Debug, Clone)]
+pub struct ManifestHeader {
+    /// When the manifest was generated
+    pub generated_at: DateTime<Utc>,
+    /// Directory tree depth
+    pub depth: Option<usize>,
+    /// Directory tree width
+    pub width: Option<usize>,
+    /// Total number of files
+    pub total_files: usize,
+    /// Total number of directories
+    pub total_directories: Option<usize>,
+    /// Files per directory (average)
+    pub files_per_dir: Option<usize>,
+    /// File size in bytes
+    pub file_size: u64,
+    /// Number of workers (for per-worker distribution)
+    pub num_workers: Option<usize>,
+}
+
+impl LayoutManifest {
+    /// Create a new layout manifest
+    pub fn new(file_entries: Vec<FileEntry>, header: ManifestHeader) -> Self {
+        Self {
+            header,
+            file_entries,
+        }
+    }
+    
+    /// Create from paths and uniform size
+    ///
+    /// Paths under `root` (e.g. from the layout generator) are stored relative to it.
+    pub fn from_paths_and_size(root: &Path, file_paths: Vec<PathBuf>, size: u64, header: ManifestHeader) -> Result<Self> {
+        let file_entries = file_paths.into_iter()
+            .map(|path| FileEntry { path: path.strip_prefix(root).map(Path::to_path_buf).unwrap_or(path), size })
+            .collect();
+        let manifest = Self {
+            header,
+            file_entries,
+        };
+        manifest.validate()?;
+        Ok(manifest)
+    }
+    
+    /// Parse layout manifest from file
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read layout manifest: {}", path.display()))?;
+        
+        Self::from_string(&content)
+    }
+    
+    /// Parse layout manifest from string
+    pub fn from_string(content: &str) -> Result<Self> {
+        let mut header = ManifestHeader {
+            generated_at: Utc::now(),
+            depth: None,
+            width: None,
+            total_files: 0,
+            total_directories: None,
+            files_per_dir: None,
+            file_size: 0,
+            num_workers: None,
+        };
+        
+        let mut file_entries = Vec::new();
+        
+        // Editors on Windows may start the file with a byte order mark
+        let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+        for (number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            
+            // Skip empty lines
+            if line.is_empty() {
+                continue;
+            }
+            
+            // Parse header comments
+            if line.starts_with('#') {
+                // Try to parse metadata from comments
+                if line.contains("Generated:") {
+                    // Parse timestamp if needed
+                } else if line.contains("depth=") {
+                    if let Some(val) = extract_value(line, "depth=") {
+                        header.depth = val.parse().ok();
+                    }
+                } else if line.contains("width=") {
+                    if let Some(val) = extract_value(line, "width=") {
+                        header.width = val.parse().ok();
+                    }
+                } else if line.contains("file_size=") {
+                    if let Some(val) = extract_value(line, "file_size=") {
+                        header.file_size = val.parse().unwrap_or(0);
+                    }
+                } else if line.contains("num_workers=") {
+                    if let Some(val) = extract_value(line, "num_workers=") {
+                        header.num_workers = val.parse().ok();
+                    }
+                } else if line.contains("total_files:") {
+                    if let Some(val) = extract_value(line, "total_files:") {
+                        header.total_files = val.parse().unwrap_or(0);
+                    }
+                } else if line.contains("Total files:") {
+                    if let Some(val) = extract_value(line, "Total files:") {
+                        header.total_files = val.parse().unwrap_or(0);
+                    }
+                } else if line.contains("File size:") {
+                    if let Some(val) = extract_value(line, "File size:") {
+                        // Parse "4096 bytes" or just "4096"
+                        let size_str = val.split_whitespace().next().unwrap_or("0");
+                        header.file_size = size_str.parse().unwrap_or(0);
+                    }
+                } else if line.contains("Total directories:") {
+                    if let Some(val) = extract_value(line, "Total directories:") {
+                        header.total_directories = val.parse().ok();
+                    }
+                } else if line.contains("Workers:") {
+                    if let Some(val) = extract_value(line, "Workers:") {
+                        header.num_workers = val.parse().ok();
+                    }
+                }
+                continue;
+            }
+            
+            // Parse file entry (path, then size unless it's left out)
+            let (path, size) = match line.rsplit_once(char::is_whitespace) {
+                Some((path, size)) if size.bytes().all(|b| b.is_ascii_digit()) => {
+                    let size = size.parse()
+                        .with_context(|| format!("Invalid size on layout manifest line {}: {}", number + 1, line))?;
+                    (path.trim_end(), size)
+                }
+                _ => (line, header.file_size),  // Fallback to header file_size if not specified per-line
+            };
+            let path = parse_portable_path(path)
+                .with_context(|| format!("Invalid path on layout manifest line {}: {}", number + 1, line))?;
+            
+            file_entries.push(FileEntry { path, size });
+        }
+        
+        // Update total_files from actual count if not set in header
+        if header.total_files == 0 {
+            header.total_files = file_entries.len();
+        }
+        
+        let manifest = Self {
+            header,
+            file_entries,
+        };
+        manifest.validate()?;
+        Ok(manifest)
+    }
+    
+    /// Check that every path is portable (relative, within the root) and listed once
+    pub fn validate(&self) -> Result<()> {
+        let mut seen = HashSet::with_capacity(self.file_entries.len());
+        for entry in &self.file_entries {
+            let path = portable_path(&entry.path)?;
+            if !seen.insert(path) {
+                anyhow::bail!("Layout manifest lists {} more than once", entry.path.display());
+            }
+        }
+        Ok(())
+    }
+    
+    /// Export layout manifest to file
+    pub fn to_file(&self, path: &Path) -> Result<()> {
+        self.validate()?;
+        let content = self.to_string();
+        fs::write(path, content)
+            .with_context(|| format!("Failed to write layout manifest: {}", path.display()))?;
+        Ok(())
+    }
+    
+    /// Convert layout manifest to string
+    pub fn to_string(&self) -> String {
+        let mut content = String::new();
+        
+        // Header
+        content.push_str("# IOPulse Layout Manifest\n");
+        content.push_str(&format!("# Generated: {}\n", self.header.generated_at.format("%Y-%m-%d %H:%M:%S UTC")));
+        
+        // Parameters
+        if let (Some(depth), Some(width)) = (self.header.depth, self.header.width) {
+            if let Some(num_workers) = self.header.num_workers {
+                content.push_str(&format!("# Parameters: depth={}, width={}, total_files={}, file_size={}, num_workers={}\n", 
+                    depth, width, self.header.total_files, self.header.file_size, num_workers));
+            } else {
+                content.push_str(&format!("# Parameters: depth={}, width={}, total_files={}, file_size={}\n", 
+                    depth, width, self.header.total_files, self.header.file_size));
+            }
+        } else {
+            if let Some(num_workers) = self.header.num_workers {
+                content.push_str(&format!("# Parameters: total_files={}, file_size={}, num_workers={}\n", 
+                    self.header.total_files, self.header.file_size, num_workers));
+            } else {
+                content.push_str(&format!("# Parameters: total_files={}, file_size={}\n", 
+                    self.header.total_files, self.header.file_size));
+            }
+        }
+        
+        content.push_str(&format!("# Total files: {}\n", self.header.total_files));
+        
+        if let Some(dirs) = self.header.total_directories {
+            content.push_str(&format!("# Total directories: {}\n", dirs));
+        }
+        
+        if let Some(fpd) = self.header.files_per_dir {
+            content.push_str(&format!("# Files per directory: {} (avg)\n", fpd));
+        }
+        
+        content.push_str(&format!("# File size: {} bytes\n", self.header.file_size));
+        
+        if let Some(num_workers) = self.header.num_workers {
+            content.push_str(&format!("# Workers: {} (per-worker distribution)\n", num_workers));
+        }
+        
+        content.push_str("#\n");
+        
+        // File entries (portable path and size per line)
+        for entry in &self.file_entries {
+            let path = portable_path(&entry.path).unwrap_or_else(|_| entry.path.display().to_string());
+            content.push_str(&format!("{} {}\n", path, entry.size));
+        }
+        
+        content
+    }
+    
+    /// Calculate hash of manifest for marker validation
+    ///
+    /// FNV-1a over the header fields and the portable entries, so the value
+    /// is the same for every build, OS and word size.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an entry has no portable form.
+    pub fn hash(&self) -> Result<u64> {
+        let mut hasher = Fnv1a::new();
+        
+        // Header metadata (as u64, the same on 32- and 64-bit hosts)
+        for field in [self.header.depth, self.header.width, Some(self.header.total_files), self.header.num_workers] {
+            match field {
+                Some(value) => hasher.write_u64(value as u64),
+                None => hasher.write(&[0xff]),
+            }
+        }
+        hasher.write_u64(self.header.file_size);
+        
+        // File entries in portable form, the same on every OS
+        for entry in &self.file_entries {
+            let path = portable_path(&entry.path)?;
+            hasher.write_u64(path.len() as u64);
+            hasher.write(path.as_bytes());
+            hasher.write_u64(entry.size);
+        }
+        
+        Ok(hasher.0)
+    }
+    
+    /// Manifest hash as computed before it was made portable
+    ///
+    /// Earlier builds hashed native paths and `usize` header fields, so the
+    /// value depends on the OS and word size. Dataset markers they wrote
+    /// carry it; [`DatasetMarker::matches_manifest`](crate::target::DatasetMarker::matches_manifest)
+    /// still accepts them until the dataset is recreated.
+    pub fn legacy_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        
+        if let Some(depth) = self.header.depth {
+            depth.hash(&mut hasher);
+        }
+        if let Some(width) = self.header.width {
+            width.hash(&mut hasher);
+        }
+        self.header.total_files.hash(&mut hasher);
+        self.header.file_size.hash(&mut hasher);
+        if let Some(num_workers) = self.header.num_workers {
+            num_workers.hash(&mut hasher);
+        }
+        
+        for entry in &self.file_entries {
+            entry.path.hash(&mut hasher);
+            entry.size.hash(&mut hasher);
+        }
+        
+        hasher.finish()
+    }
+    
+    /// Get total number of files
+    pub fn file_count(&self) -> usize {
+        self.file_entries.len()
+    }
+}
+
+/// 64-bit FNV-1a hash
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+    
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ byte as u64).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    
+    fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+}
+
+/// Portable form of a manifest path: relative, components joined with `/`
+pub fn portable_path(path: &Path) -> Result<String> {
+    let mut parts = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_str()
+                .ok_or_else(|| anyhow::anyhow!("Layout manifest path is not valid UTF-8: {}", path.display()))?),
+            Component::CurDir => {}
+            _ => anyhow::bail!("Layout manifest path must be relative to the layout root, without '..': {}", path.display()),
+        }
+    }
+    if parts.is_empty() {
+        anyhow::bail!("Empty layout manifest path");
+    }
+    Ok(parts.join("/"))
+}
+
+/// Path of a manifest line written on any OS (`/` or `\` separators)
+pub fn parse_portable_path(text: &str) -> Result<PathBuf> {
+    let drive = matches!(text.as_bytes(), [letter, b':', ..] if letter.is_ascii_alphabetic());
+    if text.starts_with(['/', '\\']) || drive {
+        anyhow::bail!("Absolute path (layout manifest paths are relative to the layout root)");
+    }
+    let mut path = PathBuf::new();
+    for part in text.split(['/', '\\']) {
+        match part {
+            "" | "." => {}
+            ".." => anyhow::bail!("'..' leaves the layout root"),
+            part => path.push(part),
+        }
+    }
+    if path.as_os_str().is_empty() {
+        anyhow::bail!("Empty path");
+    }
+    Ok(path)
+}
+
+/// Extract value from comment line
+fn extract_value(line: &str, prefix: &str) -> Option<String> {
+    if let Some(pos) = line.find(prefix) {
+        let after = &line[pos + prefix.len()..];
+        // Take until comma or end of line
+        let value = after.split(&[',', '\n'][..]).next()?;
+        Some(value.trim().to_string())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_layout_manifest_parse() {
+        let content = r#"# IOPulse Layout Manifest
+# Generated: 2026-01-24 10:30:00 UTC
+# Parameters: depth=3, width=10, total_files=1000, file_size=4096
+# Total files: 1000
+# File size: 4096 bytes
+#
+dir_0000/file_000000 4096
+dir_0000/file_000001 4096
+dir_0001/file_000000 4096
+"#;
+        
+        let manifest = LayoutManifest::from_string(content).unwrap();
+        assert_eq!(manifest.file_count(), 3);
+        assert_eq!(manifest.header.total_files, 1000);
+        assert_eq!(manifest.header.depth, Some(3));
+        assert_eq!(manifest.header.width, Some(10));
+        assert_eq!(manifest.header.file_size, 4096);
+        assert_eq!(manifest.file_entries[0].size, 4096);
+    }
+    
+    #[test]
+    fn test_layout_manifest_export() {
+        let header = ManifestHeader {
+            generated_at: Utc::now(),
+            depth: Some(2),
+            width: Some(5),
+            total_files: 100,
+            total_directories: Some(25),
+            files_per_dir: Some(4),
+            file_size: 4096,
+            num_workers: None,
+        };
+        
+        let file_entries = vec![
+            FileEntry { path: PathBuf::from("dir_0000/file_000000"), size: 4096 },
+            FileEntry { path: PathBuf::from("dir_0000/file_000001"), size: 4096 },
+        ];
+        
+        let manifest = LayoutManifest::new(file_entries, header);
+        let content = manifest.to_string();
+        
+        assert!(content.contains("# IOPulse Layout Manifest"));
+        assert!(content.contains("depth=2"));
+        assert!(content.contains("width=5"));
+        assert!(content.contains("total_files=100"));
+        assert!(content.contains("file_size=4096"));
+        assert!(content.contains("File size: 4096 bytes"));
+        assert!(content.contains("dir_0000/file_000000 4096"));
+    }
+    
+    #[test]
+    fn test_layout_manifest_hash() {
+        let header = ManifestHeader {
+            generated_at: Utc::now(),
+            depth: Some(2),
+            width: Some(5),
+            total_files: 2,
+            total_directories: None,
+            files_per_dir: None,
+            file_size: 4096,
+            num_workers: None,
+        };
+        
+        let file_entries = vec![
+            FileEntry { path: PathBuf::from("dir_0000/file_000000"), size: 4096 },
+            FileEntry { path: PathBuf::from("dir_0000/file_000001"), size: 4096 },
+        ];
+        
+        let manifest1 = LayoutManifest::new(file_entries.clone(), header.clone());
+        let manifest2 = LayoutManifest::new(file_entries.clone(), header.clone());
+        
+        // Same content should produce same hash, in every build
+        assert_eq!(manifest1.hash().unwrap(), manifest2.hash().unwrap());
+        assert_eq!(manifest1.hash().unwrap(), 0xd9b9_670b_5501_44ca);
+        
+        // A path without a portable form is an error, not an empty path
+        let mut entries = file_entries;
+        entries[1].path = PathBuf::from("../escape");
+        assert!(LayoutManifest::new(entries, header).hash().is_err());
+    }
+    
+    #[test]
+    fn test_layout_manifest_portable_paths() {
+        // Written on Windows: backslashes, CRLF, a byte order mark; spaces in names
+        let content = "\u{feff}# IOPulse Layout Manifest\r\n# File size: 8192 bytes\r\n\
+                       dir_0000\\file_000000 4096\r\ndir 1/my file.dat 8192\r\n./dir_0002/file_000002\r\n";
+        let manifest = LayoutManifest::from_string(content).unwrap();
+        let paths: Vec<PathBuf> = manifest.file_entries.iter().map(|entry| entry.path.clone()).collect();
+        assert_eq!(paths, [
+            Path::new("dir_0000").join("file_000000"),
+            Path::new("dir 1").join("my file.dat"),
+            Path::new("dir_0002").join("file_000002"),
+        ]);
+        assert_eq!(manifest.file_entries.iter().map(|entry| entry.size).collect::<Vec<_>>(), [4096, 8192, 8192]);
+        // Written back with / separators, and read back the same
+        let content = manifest.to_string();
+        assert!(content.contains("\ndir_0000/file_000000 4096\ndir 1/my file.dat 8192\n"));
+        assert_eq!(LayoutManifest::from_string(&content).unwrap().hash().unwrap(), manifest.hash().unwrap());
+        
+        for line in ["/data/tree/file 4096", "C:\\tree\\file 4096", "dir/../../etc/passwd 4096", "a/b 1\na/b 1"] {
+            assert!(LayoutManifest::from_string(line).is_err(), "{}", line);
+        }
+        
+        // Generated paths are stored relative to the root
+        let header = manifest.header.clone();
+        let root = Path::new("/data/tree");
+        let manifest = LayoutManifest::from_paths_and_size(root, vec![root.join("dir_0000/file_000000")], 4096, header.clone()).unwrap();
+        assert_eq!(manifest.file_entries[0].path, Path::new("dir_0000/file_000000"));
+        assert!(LayoutManifest::from_paths_and_size(root, vec!["/elsewhere/file".into()], 4096, header).is_err());
+    }
+}
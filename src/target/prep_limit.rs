@@ -0,0 +1,97 @@
+//! Throughput cap for target preparation
+//!
+//! Pre-allocation, refills and layout fills write as fast as the storage
+//! allows, which on a shared filer can disturb its other users. With
+//! `--prep-rate-limit` they write no faster than the given rate: the
+//! coordinator splits it over the nodes preparing in parallel, a node over
+//! its workers, and the files of a layout filled in parallel share one
+//! `PrepLimit`.
+//!
+//! # Example
+//!
+//! ```
+//! use iopulse::target::prep_limit::PrepLimit;
+//!
+//! let limit = PrepLimit::new(64 * 1024 * 1024);
+//! let shared = limit.clone();
+//! limit.take(1024 * 1024);
+//! shared.take(1024 * 1024);
+//! assert_eq!(shared.bytes_per_sec(), 64 * 1024 * 1024);
+//! ```
+
+use crate::worker::control::Pacer;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Bytes per MB in rate messages
+const MB: f64 = 1024.0 * 1024.0;
+
+/// Paces preparation writes to a rate; clones share the rate
+#[derive(Debug, Clone)]
+pub struct PrepLimit {
+    bytes_per_sec: u64,
+    pacer: Arc<Mutex<Pacer>>,
+}
+
+impl PrepLimit {
+    /// Limit to `bytes_per_sec` (at least 1)
+    pub fn new(bytes_per_sec: u64) -> Self {
+        Self { bytes_per_sec: bytes_per_sec.max(1), pacer: Arc::default() }
+    }
+
+    /// Share of `total` bytes/sec for `part` of `parts` preparing in parallel
+    pub fn share(total: u64, part: usize, parts: usize) -> Self {
+        Self::new((total as u128 * part as u128 / parts.max(1) as u128) as u64)
+    }
+
+    /// The rate in bytes/sec
+    pub fn bytes_per_sec(&self) -> u64 {
+        self.bytes_per_sec
+    }
+
+    /// Progress note for `bytes` filled in `elapsed`: the rate reached against the limit
+    pub fn progress(&self, bytes: u64, elapsed: Duration) -> String {
+        format!("filling at {:.1} MB/s (limit {})", bytes as f64 / MB / elapsed.as_secs_f64().max(1e-9), self)
+    }
+
+    /// Block until `bytes` more may be written
+    pub fn take(&self, bytes: u64) {
+        loop {
+            let wait = self.pacer.lock().unwrap().wait(0, self.bytes_per_sec, bytes, Instant::now());
+            match wait {
+                Some(wait) => std::thread::sleep(wait),
+                None => return,
+            }
+        }
+    }
+}
+
+impl fmt::Display for PrepLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.1} MB/s", self.bytes_per_sec as f64 / MB)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prep_limit_paces_shared_writers() {
+        assert_eq!(PrepLimit::share(100 << 20, 2, 8).bytes_per_sec(), 25 << 20);
+        assert_eq!(PrepLimit::share(10, 1, 100).bytes_per_sec(), 1);
+        assert_eq!(PrepLimit::new(8 << 20).progress(6 << 20, Duration::from_secs(1)), "filling at 6.0 MB/s (limit 8.0 MB/s)");
+
+        // 40 MiB/s: each MiB takes 25ms, whichever clone writes it
+        let limit = PrepLimit::new(40 << 20);
+        let other = limit.clone();
+        let start = Instant::now();
+        for _ in 0..3 {
+            limit.take(1 << 20);
+            other.take(1 << 20);
+        }
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(120), "{:?}", elapsed);
+    }
+}
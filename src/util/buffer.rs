@@ -2,11 +2,20 @@
 //!
 //! This module provides memory-aligned buffers required for O_DIRECT operations
 //! and a buffer pool to avoid allocations in the hot path.
+//!
+//! The pool is split into size classes, one per distinct IO size in the
+//! workload, so a 4K op in a mixed 4K/1M workload doesn't pin a 1M buffer.
+//! Each class is one contiguous slab that can be backed by huge pages.
 
+use crate::config::workload::BufferHugePages;
+use serde::{Deserialize, Serialize};
 use std::alloc::{alloc, dealloc, Layout};
 use std::collections::VecDeque;
 use std::ptr;
 
+/// Huge page size used for slab rounding (x86_64/aarch64 PMD size)
+const HUGE_PAGE_SIZE: usize = 2 * 1024 * 1024;
+
 /// Fill pattern for buffer initialization and verification
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FillPattern {
@@ -28,7 +37,8 @@ pub struct AlignedBuffer {
     ptr: *mut u8,
     size: usize,
     alignment: usize,
-    layout: Layout,
+    /// Allocation to free on drop (None when the memory belongs to a pool slab)
+    layout: Option<Layout>,
 }
 
 impl AlignedBuffer {
@@ -56,7 +66,17 @@ impl AlignedBuffer {
             ptr,
             size,
             alignment,
-            layout,
+            layout: Some(layout),
+        }
+    }
+
+    /// Wrap a region of a pool slab (not freed on drop)
+    fn from_slab(ptr: *mut u8, size: usize, alignment: usize) -> Self {
+        AlignedBuffer {
+            ptr,
+            size,
+            alignment,
+            layout: None,
         }
     }
 
@@ -177,8 +197,10 @@ impl AlignedBuffer {
 
 impl Drop for AlignedBuffer {
     fn drop(&mut self) {
-        unsafe {
-            dealloc(self.ptr, self.layout);
+        if let Some(layout) = self.layout {
+            unsafe {
+                dealloc(self.ptr, layout);
+            }
         }
     }
 }
@@ -186,15 +208,152 @@ impl Drop for AlignedBuffer {
 // AlignedBuffer is Send because it owns its memory
 unsafe impl Send for AlignedBuffer {}
 
+/// Contiguous memory holding all buffers of one size class
+struct Slab {
+    ptr: *mut u8,
+    len: usize,
+    /// Heap layout, or None for an mmap'd (MAP_HUGETLB) region
+    layout: Option<Layout>,
+}
+
+impl Slab {
+    /// Allocate a slab with the requested huge page backing
+    fn new(len: usize, alignment: usize, hugepages: BufferHugePages) -> crate::Result<Self> {
+        match hugepages {
+            BufferHugePages::Hugetlb => {
+                let len = len.next_multiple_of(HUGE_PAGE_SIZE);
+                let ptr = unsafe {
+                    libc::mmap(
+                        ptr::null_mut(),
+                        len,
+                        libc::PROT_READ | libc::PROT_WRITE,
+                        libc::MAP_PRIVATE | libc::MAP_ANONYMOUS | libc::MAP_HUGETLB,
+                        -1,
+                        0,
+                    )
+                };
+                if ptr == libc::MAP_FAILED {
+                    let err = std::io::Error::last_os_error();
+                    anyhow::bail!(
+                        "Failed to allocate {} bytes of huge pages for IO buffers: {} \
+                         (reserve more with /proc/sys/vm/nr_hugepages, or use --buffer-hugepages madvise)",
+                        len, err
+                    );
+                }
+                Ok(Self { ptr: ptr as *mut u8, len, layout: None })
+            }
+            BufferHugePages::Madvise if len >= HUGE_PAGE_SIZE => {
+                // THP needs 2MB-aligned ranges; round the slab to whole huge pages
+                let len = len.next_multiple_of(HUGE_PAGE_SIZE);
+                let slab = Self::heap(len, alignment.max(HUGE_PAGE_SIZE))?;
+                // Best effort: THP may be disabled system-wide
+                unsafe { libc::madvise(slab.ptr as *mut libc::c_void, len, libc::MADV_HUGEPAGE) };
+                Ok(slab)
+            }
+            _ => Self::heap(len, alignment),
+        }
+    }
+
+    /// Allocate a slab on the heap
+    fn heap(len: usize, alignment: usize) -> crate::Result<Self> {
+        let layout = Layout::from_size_align(len, alignment)
+            .map_err(|e| anyhow::anyhow!("Invalid IO buffer layout ({} bytes, {} byte alignment): {}", len, alignment, e))?;
+        let ptr = unsafe { alloc(layout) };
+        if ptr.is_null() {
+            anyhow::bail!("Failed to allocate {} bytes for IO buffers", len);
+        }
+        Ok(Self { ptr, len, layout: Some(layout) })
+    }
+}
+
+impl Drop for Slab {
+    fn drop(&mut self) {
+        unsafe {
+            match self.layout {
+                Some(layout) => dealloc(self.ptr, layout),
+                None => {
+                    libc::munmap(self.ptr as *mut libc::c_void, self.len);
+                }
+            }
+        }
+    }
+}
+
+// Slab is Send because the pool owns its memory
+unsafe impl Send for Slab {}
+
+/// One size class of a buffer pool
+struct SizeClass {
+    size: usize,
+    available: VecDeque<usize>,
+    total: usize,
+    low_water: usize,
+    borrowed: u64,
+    starved: u64,
+    wait_ns: u64,
+}
+
+/// Occupancy statistics of one buffer pool size class
+///
+/// A low watermark near zero means the class nearly ran out at peak queue
+/// depth; `borrowed` counts ops served from a larger class and `starved`
+/// counts submissions that had to wait for a buffer to be returned, with the
+/// time they waited in `wait_ns`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BufferClassStats {
+    /// Buffer size of the class (bytes)
+    pub size: usize,
+    /// Buffers in the class
+    pub total: usize,
+    /// Fewest buffers that were ever free at once
+    pub low_water: usize,
+    /// Requests served by a larger class because this one was empty
+    pub borrowed: u64,
+    /// Requests that found no free buffer of this size or larger
+    pub starved: u64,
+    /// Time submissions spent waiting for a buffer of this class (nanoseconds)
+    #[serde(default)]
+    pub wait_ns: u64,
+}
+
+impl BufferClassStats {
+    /// Merge stats of the same size class from another worker
+    pub fn merge(&mut self, other: &BufferClassStats) {
+        self.total += other.total;
+        self.low_water += other.low_water;
+        self.borrowed += other.borrowed;
+        self.starved += other.starved;
+        self.wait_ns += other.wait_ns;
+    }
+}
+
+/// Merge per-worker class stats by buffer size
+pub fn merge_class_stats(into: &mut Vec<BufferClassStats>, other: &[BufferClassStats]) {
+    for class in other {
+        match into.iter_mut().find(|c| c.size == class.size) {
+            Some(existing) => existing.merge(class),
+            None => into.push(class.clone()),
+        }
+    }
+    into.sort_by_key(|c| c.size);
+}
+
 /// Pre-allocated pool of aligned buffers for zero-allocation IO operations
 ///
 /// The buffer pool maintains a collection of pre-allocated buffers that can be
 /// borrowed and returned without any allocation overhead in the hot path.
+/// Buffer indices are unique across all size classes.
 pub struct BufferPool {
     buffers: Vec<AlignedBuffer>,
-    available: VecDeque<usize>,
+    /// Size classes, ascending by size
+    classes: Vec<SizeClass>,
+    /// Class index of each buffer
+    class_of: Vec<usize>,
     buffer_size: usize,
     alignment: usize,
+    hugepages: BufferHugePages,
+    // Declared after `buffers` so the slab views are dropped first
+    _slabs: Vec<Slab>,
 }
 
 impl BufferPool {
@@ -204,21 +363,74 @@ impl BufferPool {
     /// * `num_buffers` - Number of buffers to pre-allocate
     /// * `buffer_size` - Size of each buffer in bytes
     /// * `alignment` - Alignment requirement (typically 512 or 4096)
+    ///
+    /// # Panics
+    /// Panics if the buffers can't be allocated; use `with_size_classes` to
+    /// get an error instead.
     pub fn new(num_buffers: usize, buffer_size: usize, alignment: usize) -> Self {
-        let mut buffers = Vec::with_capacity(num_buffers);
-        let mut available = VecDeque::with_capacity(num_buffers);
+        Self::with_size_classes(&[(buffer_size, num_buffers)], alignment, BufferHugePages::None)
+            .expect("Failed to allocate buffer pool")
+    }
 
-        for i in 0..num_buffers {
-            buffers.push(AlignedBuffer::new(buffer_size, alignment));
-            available.push_back(i);
-        }
+    /// Create a pool with one size class per `(buffer_size, num_buffers)` entry
+    ///
+    /// # Errors
+    /// Returns an error if the buffer memory (heap or MAP_HUGETLB huge pages)
+    /// cannot be allocated.
+    pub fn with_size_classes(classes: &[(usize, usize)], alignment: usize, hugepages: BufferHugePages) -> crate::Result<Self> {
+        assert!(alignment.is_power_of_two(), "Alignment must be a power of 2");
 
-        BufferPool {
-            buffers,
-            available,
-            buffer_size,
+        let mut sorted: Vec<(usize, usize)> = classes.to_vec();
+        sorted.sort_by_key(|&(size, _)| size);
+        sorted.dedup_by(|later, earlier| {
+            // Same size listed twice: keep one class with both counts
+            if later.0 == earlier.0 {
+                earlier.1 += later.1;
+                true
+            } else {
+                false
+            }
+        });
+
+        let mut pool = BufferPool {
+            buffers: Vec::new(),
+            classes: Vec::with_capacity(sorted.len()),
+            class_of: Vec::new(),
+            buffer_size: sorted.last().map(|&(size, _)| size).unwrap_or(0),
             alignment,
+            hugepages,
+            _slabs: Vec::new(),
+        };
+
+        for (class_idx, &(size, count)) in sorted.iter().enumerate() {
+            assert!(size > 0, "Buffer size must be greater than 0");
+            let mut available = VecDeque::with_capacity(count);
+
+            if count > 0 {
+                // Buffers are laid out back to back, each starting on an aligned boundary
+                let stride = size.next_multiple_of(alignment);
+                let slab = Slab::new(stride * count, alignment, hugepages)?;
+                for i in 0..count {
+                    let ptr = unsafe { slab.ptr.add(i * stride) };
+                    available.push_back(pool.buffers.len());
+                    pool.buffers.push(AlignedBuffer::from_slab(ptr, size, alignment));
+                    pool.class_of.push(class_idx);
+                }
+                pool._slabs.push(slab);
+            }
+
+            pool.classes.push(SizeClass {
+                size,
+                available,
+                total: count,
+                low_water: count,
+                borrowed: 0,
+                starved: 0,
+                wait_ns: 0,
+            });
         }
+
+        Ok(pool)
     }
     
     /// Pre-fill all buffers with random data
@@ -238,10 +450,53 @@ impl BufferPool {
     /// Get a buffer from the pool
     ///
     /// Returns `Some(index)` if a buffer is available, or `None` if the pool is empty.
-    /// The caller must return the buffer using `return_buffer()` when done.
+    /// Takes from the largest size class. The caller must return the buffer
+    /// using `return_buffer()` when done.
     #[inline(always)]
     pub fn get(&mut self) -> Option<usize> {
-        self.available.pop_front()
+        let class_idx = self.classes.len().checked_sub(1)?;
+        self.take(class_idx)
+    }
+
+    /// Get a buffer of at least `size` bytes
+    ///
+    /// Uses the smallest class that fits; if it is empty, a larger class is
+    /// borrowed from. Returns `None` (and counts the class as starved) when
+    /// no class that fits has a free buffer.
+    #[inline]
+    pub fn get_sized(&mut self, size: usize) -> Option<usize> {
+        let class_idx = self.classes.iter().position(|c| c.size >= size)?;
+        if let Some(index) = self.take(class_idx) {
+            return Some(index);
+        }
+
+        let larger = (class_idx + 1..self.classes.len())
+            .find(|&i| !self.classes[i].available.is_empty());
+        match larger {
+            Some(larger_idx) => {
+                self.classes[class_idx].borrowed += 1;
+                self.take(larger_idx)
+            }
+            None => {
+                self.classes[class_idx].starved += 1;
+                None
+            }
+        }
+    }
+
+    /// Record time a request of `size` bytes waited for a free buffer
+    pub fn record_wait(&mut self, size: usize, duration: std::time::Duration) {
+        if let Some(class) = self.classes.iter_mut().find(|c| c.size >= size) {
+            class.wait_ns += duration.as_nanos() as u64;
+        }
+    }
+
+    #[inline(always)]
+    fn take(&mut self, class_idx: usize) -> Option<usize> {
+        let class = &mut self.classes[class_idx];
+        let index = class.available.pop_front()?;
+        class.low_water = class.low_water.min(class.available.len());
+        Some(index)
     }
 
     /// Return a buffer to the pool
@@ -254,7 +509,7 @@ impl BufferPool {
     #[inline(always)]
     pub fn return_buffer(&mut self, index: usize) {
         assert!(index < self.buffers.len(), "Invalid buffer index");
-        self.available.push_back(index);
+        self.classes[self.class_of[index]].available.push_back(index);
     }
 
     /// Get a reference to a buffer by index
@@ -278,7 +533,7 @@ impl BufferPool {
     /// Get the number of available buffers
     #[inline]
     pub fn available_count(&self) -> usize {
-        self.available.len()
+        self.classes.iter().map(|c| c.available.len()).sum()
     }
 
     /// Get the total number of buffers in the pool
@@ -287,7 +542,7 @@ impl BufferPool {
         self.buffers.len()
     }
 
-    /// Get the size of each buffer
+    /// Get the size of the largest buffers
     #[inline]
     pub fn buffer_size(&self) -> usize {
         self.buffer_size
@@ -298,6 +553,30 @@ impl BufferPool {
     pub fn alignment(&self) -> usize {
         self.alignment
     }
+
+    /// Huge page backing the pool was created with
+    pub fn hugepages(&self) -> BufferHugePages {
+        self.hugepages
+    }
+
+    /// Total bytes of buffer memory (excluding alignment padding)
+    pub fn memory_bytes(&self) -> usize {
+        self.classes.iter().map(|c| c.size * c.total).sum()
+    }
+
+    /// Occupancy statistics per size class
+    pub fn class_stats(&self) -> Vec<BufferClassStats> {
+        self.classes.iter()
+            .map(|c| BufferClassStats {
+                size: c.size,
+                total: c.total,
+                low_water: c.low_water,
+                borrowed: c.borrowed,
+                starved: c.starved,
+                wait_ns: c.wait_ns,
+            })
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -461,6 +740,69 @@ mod tests {
         pool.return_buffer(index);
     }
 
+    #[test]
+    fn test_buffer_pool_size_classes() {
+        let mut pool = BufferPool::with_size_classes(&[(65536, 1), (4096, 2)], 4096, BufferHugePages::None).unwrap();
+        assert_eq!(pool.total_count(), 3);
+        assert_eq!(pool.buffer_size(), 65536);
+        assert_eq!(pool.memory_bytes(), 2 * 4096 + 65536);
+
+        // Small requests use the small class, then borrow the large one
+        let a = pool.get_sized(4096).unwrap();
+        let b = pool.get_sized(512).unwrap();
+        assert_eq!(pool.get_buffer(a).size(), 4096);
+        assert_eq!(pool.get_buffer(b).size(), 4096);
+        let c = pool.get_sized(4096).unwrap();
+        assert_eq!(pool.get_buffer(c).size(), 65536);
+
+        // Nothing left that fits
+        assert!(pool.get_sized(4096).is_none());
+        assert!(pool.get_sized(65536).is_none());
+        assert!(pool.get_sized(1 << 20).is_none());
+
+        pool.return_buffer(c);
+        let d = pool.get_sized(65536).unwrap();
+        assert_eq!(pool.get_buffer(d).size(), 65536);
+
+        let stats = pool.class_stats();
+        assert_eq!(stats[0], BufferClassStats { size: 4096, total: 2, low_water: 0, borrowed: 1, starved: 1, wait_ns: 0 });
+        assert_eq!(stats[1], BufferClassStats { size: 65536, total: 1, low_water: 0, borrowed: 0, starved: 1, wait_ns: 0 });
+
+        for i in 0..pool.total_count() {
+            assert!(pool.get_buffer(i).is_aligned());
+        }
+    }
+
+    #[test]
+    fn test_buffer_pool_madvise_hugepages() {
+        // Slabs of 2MB or more are huge-page aligned; THP itself is best effort
+        let pool = BufferPool::with_size_classes(&[(1 << 20, 4)], 4096, BufferHugePages::Madvise).unwrap();
+        assert_eq!(pool.get_buffer(0).as_ptr() as usize % HUGE_PAGE_SIZE, 0);
+        assert_eq!(pool.hugepages(), BufferHugePages::Madvise);
+    }
+
+    #[test]
+    fn test_buffer_pool_allocation_failure() {
+        // More memory than any machine has is an error, not a panic
+        let result = BufferPool::with_size_classes(&[(1 << 62, 1)], 4096, BufferHugePages::None);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_merge_class_stats() {
+        let a = vec![BufferClassStats { size: 4096, total: 8, low_water: 2, borrowed: 1, starved: 0, wait_ns: 0 }];
+        let b = vec![
+            BufferClassStats { size: 65536, total: 4, low_water: 0, borrowed: 0, starved: 3, wait_ns: 0 },
+            BufferClassStats { size: 4096, total: 8, low_water: 5, borrowed: 0, starved: 0, wait_ns: 0 },
+        ];
+        let mut merged = a.clone();
+        merge_class_stats(&mut merged, &b);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].total, 16);
+        assert_eq!(merged[0].low_water, 7);
+        assert_eq!(merged[1].starved, 3);
+    }
+
     #[test]
     fn test_buffer_pool_all_aligned() {
         let pool = BufferPool::new(10, 4096, 4096);
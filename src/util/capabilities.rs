@@ -0,0 +1,128 @@
+//! Node capabilities
+//!
+//! Every node reports what it runs with when it is ready for a test: its
+//! IOPulse version and build features, its kernel, the IO engines that can be
+//! set up there and the engine its workers use for this test. The
+//! coordinator prints them as a matrix and records them in the results, so
+//! a heterogeneous cluster (a node on another release, or one whose workers
+//! run the sync engine while the others run io_uring) shows up in the
+//! output rather than only in the numbers.
+
+use crate::config::workload::EngineType;
+use crate::config::WorkloadConfig;
+use serde::{Deserialize, Serialize};
+
+/// Compares one field of two nodes' capabilities
+type SameField = fn(&NodeCapabilities, &NodeCapabilities) -> bool;
+
+/// Every engine, in the order capabilities list them
+const ENGINES: [EngineType; 5] = [EngineType::Sync, EngineType::IoUring, EngineType::Libaio, EngineType::Mmap, EngineType::Threadpool];
+
+/// What a node runs a test with
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeCapabilities {
+    /// IOPulse version
+    pub version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_sha: Option<String>,
+    /// Cargo features the binary was built with
+    pub features: Vec<String>,
+    /// Kernel release (`uname -r`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kernel: Option<String>,
+    /// Engines that can be set up on the node
+    pub engines: Vec<String>,
+    /// Engine the workers use for this test
+    pub engine: String,
+}
+
+impl NodeCapabilities {
+    /// Capabilities of this node for a test running `workload`
+    pub fn detect(workload: &WorkloadConfig) -> Self {
+        let features = [("io_uring", cfg!(feature = "io_uring")), ("plot", cfg!(feature = "plot"))];
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            git_sha: crate::GIT_SHA.map(str::to_string),
+            features: features.iter().filter(|(_, on)| *on).map(|(name, _)| name.to_string()).collect(),
+            kernel: kernel_release(),
+            engines: ENGINES.iter().filter(|&&engine| engine_available(engine)).map(ToString::to_string).collect(),
+            engine: workload.effective_engine().to_string(),
+        }
+    }
+
+    /// Fields in which the given nodes differ
+    pub fn differences<'a>(nodes: impl IntoIterator<Item = &'a Self>) -> Vec<&'static str> {
+        let nodes: Vec<&Self> = nodes.into_iter().collect();
+        let Some(first) = nodes.first() else { return Vec::new() };
+        let fields: [(&str, SameField); 5] = [
+            ("version", |a, b| a.version == b.version && a.git_sha == b.git_sha),
+            ("features", |a, b| a.features == b.features),
+            ("kernel", |a, b| a.kernel == b.kernel),
+            ("engines", |a, b| a.engines == b.engines),
+            ("engine", |a, b| a.engine == b.engine),
+        ];
+        fields.iter()
+            .filter(|(_, same)| nodes.iter().any(|node| !same(first, node)))
+            .map(|(name, _)| *name)
+            .collect()
+    }
+}
+
+/// Kernel release of this host
+fn kernel_release() -> Option<String> {
+    let mut uts: libc::utsname = unsafe { std::mem::zeroed() };
+    if unsafe { libc::uname(&mut uts) } != 0 {
+        return None;
+    }
+    let release = unsafe { std::ffi::CStr::from_ptr(uts.release.as_ptr()) };
+    Some(release.to_string_lossy().into_owned())
+}
+
+/// Whether an engine can be set up here (built in and allowed by the kernel)
+pub fn engine_available(engine: EngineType) -> bool {
+    match engine {
+        EngineType::Sync | EngineType::Mmap | EngineType::Threadpool => true,
+        #[cfg(feature = "io_uring")]
+        EngineType::IoUring => io_uring::IoUring::new(2).is_ok(),
+        #[cfg(not(feature = "io_uring"))]
+        EngineType::IoUring => false,
+        #[cfg(target_os = "linux")]
+        EngineType::Libaio => {
+            let mut ctx: libc::c_ulong = 0;
+            if unsafe { libc::syscall(libc::SYS_io_setup, 1, &mut ctx) } != 0 {
+                return false;
+            }
+            unsafe { libc::syscall(libc::SYS_io_destroy, ctx) };
+            true
+        }
+        #[cfg(not(target_os = "linux"))]
+        EngineType::Libaio => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_and_differences() {
+        let mut workload: WorkloadConfig = toml::from_str(
+            "read_percent = 100\nwrite_percent = 0\nqueue_depth = 1\nengine = \"io_uring\"\ncompletion_mode = \"RunUntilComplete\"",
+        ).unwrap();
+        let node = NodeCapabilities::detect(&workload);
+        assert_eq!(node.version, env!("CARGO_PKG_VERSION"));
+        assert!(node.engines.contains(&"sync".to_string()));
+        // Queue depth 1 runs the sync engine
+        assert_eq!(node.engine, "sync");
+
+        workload.queue_depth = 8;
+        let other = NodeCapabilities { kernel: Some("5.4.0".to_string()), ..NodeCapabilities::detect(&workload) };
+        assert_eq!(other.engine, "io_uring");
+        assert!(NodeCapabilities::differences([&node, &node]).is_empty());
+        let mut expected = vec!["engine"];
+        if node.kernel != other.kernel {
+            expected.insert(0, "kernel");
+        }
+        assert_eq!(NodeCapabilities::differences([&node, &other]), expected);
+    }
+}
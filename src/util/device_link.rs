@@ -0,0 +1,105 @@
+//! Interface link speed of a target's device
+//!
+//! Vendors don't publish rated throughput anywhere the kernel can read it,
+//! but the link a device is attached through caps it: an NVMe drive on a
+//! PCIe 3.0 x4 link can't move more than ~3.9 GB/s, a SATA drive more than
+//! 600 MB/s. `--rated-throughput auto` reports results against that ceiling.
+//!
+//! The device is found through sysfs: a block device by its own number, a
+//! file by the device its filesystem is on (partitions resolve to their
+//! disk). Walking up from the disk, the first SATA port (`ataN`) or PCIe
+//! function (`current_link_speed`) gives the link.
+
+use std::path::{Path, PathBuf};
+
+/// Usable bytes per second of an interface link, and a description of it
+#[derive(Debug, Clone, PartialEq)]
+pub struct InterfaceLink {
+    pub bytes_per_sec: u64,
+    pub description: String,
+}
+
+/// Link of the device holding `path` (None if sysfs doesn't report one)
+pub fn interface_link(path: &Path) -> Option<InterfaceLink> {
+    let disk = disk_dir(path)?;
+    let device = std::fs::canonicalize(disk.join("device")).ok()?;
+    for dir in device.ancestors() {
+        let name = dir.file_name()?.to_string_lossy();
+        if let Some(port) = name.strip_prefix("ata").filter(|n| n.parse::<u32>().is_ok()) {
+            let speed = read_trimmed(&Path::new("/sys/class/ata_link").join(format!("link{}", port)).join("sata_spd"))?;
+            return sata_link(&speed);
+        }
+        if let Some(speed) = read_trimmed(&dir.join("current_link_speed")) {
+            let width = read_trimmed(&dir.join("current_link_width"))?;
+            return pcie_link(&speed, &width);
+        }
+    }
+    None
+}
+
+/// sysfs directory of the disk a block device or a file's filesystem is on
+pub(crate) fn disk_dir(path: &Path) -> Option<PathBuf> {
+    use std::os::unix::fs::{FileTypeExt, MetadataExt};
+
+    let metadata = std::fs::metadata(path).ok()?;
+    let dev = if metadata.file_type().is_block_device() { metadata.rdev() } else { metadata.dev() };
+    let (major, minor) = (libc::major(dev), libc::minor(dev));
+    let dir = std::fs::canonicalize(format!("/sys/dev/block/{}:{}", major, minor)).ok()?;
+    // A partition's disk is its parent directory
+    if dir.join("partition").exists() {
+        dir.parent().map(Path::to_path_buf)
+    } else {
+        Some(dir)
+    }
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    std::fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+/// Throughput of a PCIe link from sysfs `current_link_speed` (e.g., "8.0 GT/s PCIe") and width
+fn pcie_link(speed: &str, width: &str) -> Option<InterfaceLink> {
+    let gts: f64 = speed.split_whitespace().next()?.parse().ok()?;
+    let lanes: u32 = width.parse().ok().filter(|&lanes| lanes > 0)?;
+    // 8b/10b up to PCIe 2.0, 128b/130b from PCIe 3.0, FLIT mode from PCIe 6.0
+    let encoding = match gts {
+        gts if gts < 8.0 => 8.0 / 10.0,
+        gts if gts < 64.0 => 128.0 / 130.0,
+        _ => 242.0 / 256.0,
+    };
+    let bytes_per_sec = gts * 1e9 * encoding / 8.0 * lanes as f64;
+    Some(InterfaceLink {
+        bytes_per_sec: bytes_per_sec as u64,
+        description: format!("PCIe {} GT/s x{} link", speed.split_whitespace().next()?, lanes),
+    })
+}
+
+/// Throughput of a SATA link from sysfs `sata_spd` (e.g., "6.0 Gbps")
+fn sata_link(speed: &str) -> Option<InterfaceLink> {
+    let gbps: f64 = speed.strip_suffix("Gbps")?.trim().parse().ok()?;
+    // 8b/10b encoding
+    Some(InterfaceLink {
+        bytes_per_sec: (gbps * 1e9 * 0.8 / 8.0) as u64,
+        description: format!("SATA {} link", speed),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_link_speeds() {
+        let gen3 = pcie_link("8.0 GT/s PCIe", "4").unwrap();
+        assert_eq!(gen3.bytes_per_sec, 3_938_461_538);
+        assert_eq!(gen3.description, "PCIe 8.0 GT/s x4 link");
+        assert_eq!(pcie_link("2.5 GT/s", "1").unwrap().bytes_per_sec, 250_000_000);
+        assert!(pcie_link("Unknown", "4").is_none());
+        assert!(pcie_link("8.0 GT/s PCIe", "0").is_none());
+
+        let sata = sata_link("6.0 Gbps").unwrap();
+        assert_eq!(sata.bytes_per_sec, 600_000_000);
+        assert_eq!(sata.description, "SATA 6.0 Gbps link");
+        assert!(sata_link("<unknown>").is_none());
+    }
+}
@@ -0,0 +1,247 @@
+//! Resource estimates for `--dry-run`
+//!
+//! Before committing hours to a large layout or fill, `--dry-run` reports what
+//! the run would use: the files and directories it creates, the bytes written
+//! preparing files and by the test itself, worker memory for IO buffers and
+//! statistics, and how long preparation would take, extrapolated from a short
+//! write and create probe in the target's directory.
+//!
+//! The estimate follows the preparation the coordinator does: files are filled
+//! only for reads (or the mmap engine) when missing or sparse, and layouts
+//! create all of their directories and files. Test writes are only known up
+//! front for total-bytes and run-until-complete tests.
+
+use crate::config::workload::{CompletionMode, EngineType, FileDistribution};
+use crate::config::{Config, TargetType};
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Bytes written by the write probe
+const PROBE_WRITE_BYTES: usize = 32 * 1024 * 1024;
+
+/// Files created by the create probe
+const PROBE_CREATES: usize = 200;
+
+/// Resources a run would use
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResourceEstimate {
+    /// Files created (missing file targets and layout files)
+    pub files: u64,
+    /// Directories created by layouts
+    pub dirs: u64,
+    /// Bytes written filling files before the test
+    pub prep_bytes: u64,
+    /// Bytes written by the test (None when it depends on the duration)
+    pub test_write_bytes: Option<u64>,
+    /// IO buffers of all workers
+    pub buffer_memory: u64,
+    /// Statistics of all workers, at most (block tracking grows with the blocks touched)
+    pub stats_memory: u64,
+    /// Preparation time extrapolated from the probe
+    pub prep_time: Option<Duration>,
+}
+
+/// Rates measured in the target's directory
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProbeRates {
+    pub write_bytes_per_sec: f64,
+    pub creates_per_sec: f64,
+}
+
+impl ResourceEstimate {
+    /// Estimate the resources of a standalone run of `config`
+    pub fn for_config(config: &Config) -> Result<Self> {
+        let workload = &config.workload;
+        let threads = config.workers.threads as u64;
+        let needs_fill = workload.read_percent > 0 || workload.engine == EngineType::Mmap;
+        let mut estimate = Self::default();
+
+        let mut data_bytes = 0u64;
+        for (index, target) in config.targets.iter().enumerate() {
+            let file_size = target.region.map(|(start, end)| end - start)
+                .or(target.file_size)
+                .unwrap_or(0);
+            let (files, dirs, fill_bytes) = if let Some(ref manifest_path) = target.layout_manifest {
+                let manifest = crate::target::LayoutManifest::from_file(manifest_path)
+                    .context("Failed to load layout manifest")?;
+                let missing = manifest.file_entries.iter()
+                    .filter(|entry| !target.path.join(&entry.path).exists())
+                    .count() as u64;
+                let dirs: HashSet<&Path> = manifest.file_entries.iter()
+                    .filter_map(|entry| entry.path.parent())
+                    .filter(|dir| !dir.as_os_str().is_empty() && !target.path.join(dir).exists())
+                    .collect();
+                (missing, dirs.len() as u64, missing * file_size)
+            } else if let Some(ref layout) = target.layout_config {
+                let workers = (target.distribution == FileDistribution::PerWorker).then_some(config.workers.threads);
+                let generator = crate::target::layout::LayoutConfig {
+                    depth: layout.depth,
+                    width: layout.width,
+                    files_per_dir: layout.files_per_dir,
+                    file_size,
+                    naming_pattern: crate::target::layout::NamingPattern::Sequential,
+                    num_workers: workers,
+                    total_files: layout.total_files,
+                };
+                let files = generator.expected_files() as u64;
+                (files, generator.expected_dirs() as u64, files * file_size)
+            } else if target.target_type == TargetType::File {
+                let missing = !target.path.exists();
+                let sparse = !missing && crate::distributed::coordinator::is_file_sparse(&target.path)?;
+                // Only the regions the workload can reach are filled
+                let fill = crate::distribution::reach::fill_regions(config, index, 1)
+                    .map_or(file_size, |regions| regions.iter().map(|(start, end)| end - start).sum());
+                (missing as u64, 0, if missing || sparse { fill } else { 0 })
+            } else {
+                (0, 0, 0)
+            };
+            estimate.files += files;
+            estimate.dirs += dirs;
+            if needs_fill && !target.no_refill {
+                estimate.prep_bytes += fill_bytes;
+            }
+            data_bytes += if files > 0 { files * file_size } else { file_size };
+        }
+
+        // Bytes moved by the whole test, when known, split by the write share
+        let test_bytes = match workload.completion_mode {
+            CompletionMode::TotalBytes { bytes } => Some(bytes * threads),
+            CompletionMode::RunUntilComplete if config.targets.iter().all(|t| t.layout_config.is_none() && t.layout_manifest.is_none()) => {
+                match config.targets.first().map(|t| t.distribution) {
+                    Some(FileDistribution::Partitioned) => Some(data_bytes),
+                    _ => Some(data_bytes * threads),
+                }
+            }
+            _ => None,
+        };
+        estimate.test_write_bytes = test_bytes.map(|bytes| (bytes as u128 * workload.write_percent as u128 / 100) as u64);
+
+        // Block tracking is bounded by the blocks a worker can reach (and its
+        // share of --stats-mem-limit)
+        let block_size = workload.block_size.max(1);
+        let mut blocks = data_bytes / block_size;
+        if config.targets.first().is_some_and(|t| t.distribution == FileDistribution::Partitioned) {
+            blocks /= threads.max(1);
+        }
+        if let Some(bytes) = test_bytes {
+            blocks = blocks.min(bytes / threads.max(1) / block_size);
+        }
+        let mut tracking = crate::stats::budget::block_tracking_bytes(blocks, workload.heatmap);
+        if let Some(limit) = config.runtime.stats_mem_limit {
+            tracking = tracking.min(crate::stats::budget::worker_block_limit(limit, config.workers.threads));
+        }
+        estimate.buffer_memory = threads * crate::worker::buffer_memory_bytes(config);
+        estimate.stats_memory = threads * (std::mem::size_of::<crate::stats::WorkerStats>() as u64 + tracking);
+
+        Ok(estimate)
+    }
+
+    /// Directory the probe runs in: the nearest existing ancestor of the first target
+    pub fn probe_dir(config: &Config) -> Option<PathBuf> {
+        let target = config.targets.first()?;
+        let start = if target.target_type == TargetType::Directory {
+            target.path.as_path()
+        } else {
+            target.path.parent()?
+        };
+        start.ancestors()
+            .map(|dir| if dir.as_os_str().is_empty() { Path::new(".") } else { dir })
+            .find(|dir| dir.is_dir())
+            .map(Path::to_path_buf)
+    }
+
+    /// Whether the run prepares anything the probe can time
+    pub fn has_preparation(&self) -> bool {
+        self.prep_bytes > 0 || self.files > 0 || self.dirs > 0
+    }
+
+    /// Extrapolate the preparation time from measured rates
+    pub fn apply_probe(&mut self, rates: ProbeRates) {
+        let writes = self.prep_bytes as f64 / rates.write_bytes_per_sec.max(1.0);
+        let creates = (self.files + self.dirs) as f64 / rates.creates_per_sec.max(1.0);
+        self.prep_time = Some(Duration::from_secs_f64(writes + creates));
+    }
+
+    /// Add the estimate of a job that runs at the same time
+    ///
+    /// Amounts add up; preparation runs concurrently, so the longest one counts.
+    pub fn add(&mut self, other: &ResourceEstimate) {
+        self.files += other.files;
+        self.dirs += other.dirs;
+        self.prep_bytes += other.prep_bytes;
+        self.test_write_bytes = self.test_write_bytes.zip(other.test_write_bytes).map(|(a, b)| a + b);
+        self.buffer_memory += other.buffer_memory;
+        self.stats_memory += other.stats_memory;
+        self.prep_time = match (self.prep_time, other.prep_time) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+    }
+}
+
+/// Measure write throughput (fsynced) and file create rate in `dir`
+///
+/// Writes and removes a 32 MiB file and 200 empty files.
+pub fn probe(dir: &Path) -> Result<ProbeRates> {
+    let scratch = dir.join(format!(".iopulse-probe-{}", std::process::id()));
+    std::fs::create_dir(&scratch)
+        .with_context(|| format!("Failed to create probe directory in {}", dir.display()))?;
+    let rates = run_probe(&scratch);
+    let _ = std::fs::remove_dir_all(&scratch);
+    rates
+}
+
+fn run_probe(scratch: &Path) -> Result<ProbeRates> {
+    let chunk = vec![0xA5u8; 1024 * 1024];
+    let start = Instant::now();
+    let mut file = std::fs::File::create(scratch.join("data"))?;
+    for _ in 0..PROBE_WRITE_BYTES / chunk.len() {
+        file.write_all(&chunk)?;
+    }
+    file.sync_all()?;
+    let write_bytes_per_sec = PROBE_WRITE_BYTES as f64 / start.elapsed().as_secs_f64();
+
+    let start = Instant::now();
+    for i in 0..PROBE_CREATES {
+        std::fs::File::create(scratch.join(format!("file_{}", i)))?;
+    }
+    let creates_per_sec = PROBE_CREATES as f64 / start.elapsed().as_secs_f64();
+
+    Ok(ProbeRates { write_bytes_per_sec, creates_per_sec })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_fill_and_test_writes() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let config: Config = toml::from_str(&format!(
+            "targets = [{{ path = \"{}\", file_size = 1048576 }}]\n\
+             workers = {{ threads = 2 }}\n\
+             [workload]\nread_percent = 50\nwrite_percent = 50\n\
+             completion_mode = {{ TotalBytes = {{ bytes = 1073741824 }} }}\n",
+            dir.path().join("test.dat").display(),
+        )).unwrap();
+
+        let mut estimate = ResourceEstimate::for_config(&config).unwrap();
+        assert_eq!(estimate.files, 1);
+        assert_eq!(estimate.prep_bytes, 1 << 20);
+        assert_eq!(estimate.test_write_bytes, Some(1 << 30));
+        assert!(estimate.buffer_memory > 0);
+        assert_eq!(ResourceEstimate::probe_dir(&config).as_deref(), Some(dir.path()));
+
+        estimate.apply_probe(ProbeRates { write_bytes_per_sec: (1 << 20) as f64, creates_per_sec: 1.0 });
+        assert_eq!(estimate.prep_time, Some(Duration::from_secs(2)));
+
+        let mut total = estimate.clone();
+        total.add(&ResourceEstimate::default());
+        assert_eq!(total.files, 1);
+        assert_eq!(total.test_write_bytes, None);
+        assert_eq!(total.prep_time, Some(Duration::from_secs(2)));
+    }
+}
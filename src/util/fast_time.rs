@@ -2,9 +2,114 @@
 //!
 //! This module provides ultra-fast timing for IO latency measurement.
 //! Uses direct libc calls to avoid Rust's Instant overhead.
+//!
+//! With `--timer tsc`, `FastInstant::now` reads the CPU timestamp counter
+//! instead (x86_64 with an invariant TSC), calibrated against CLOCK_MONOTONIC
+//! once per process. This helps where clock_gettime falls back to a syscall
+//! (VMs without a TSC clocksource).
+//!
+//! `TimerCheck` measures the resolution and per-call cost of the timer, so
+//! results can warn when operations are too fast for it to time.
 
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
 use std::time::Duration;
 
+/// TSC calibration; set only once `--timer tsc` was enabled
+static TSC: OnceLock<TscCalibration> = OnceLock::new();
+
+/// How long the TSC is compared against CLOCK_MONOTONIC
+const TSC_CALIBRATION_TIME: Duration = Duration::from_millis(20);
+
+/// Operations this many times slower than the timer noise floor are timed reliably
+pub const NOISE_FLOOR_MARGIN: u64 = 50;
+
+/// Mapping from TSC ticks to CLOCK_MONOTONIC nanoseconds
+#[derive(Debug)]
+struct TscCalibration {
+    base_tsc: u64,
+    base_nanos: u64,
+    /// Nanoseconds per tick, as a 32.32 fixed point number
+    nanos_per_tick: u64,
+}
+
+impl TscCalibration {
+    #[inline(always)]
+    fn nanos(&self, tsc: u64) -> u64 {
+        let ticks = tsc.wrapping_sub(self.base_tsc) as u128;
+        self.base_nanos + ((ticks * self.nanos_per_tick as u128) >> 32) as u64
+    }
+}
+
+/// Read the CPU timestamp counter (None where there is no usable TSC)
+#[inline(always)]
+fn read_tsc() -> Option<u64> {
+    #[cfg(target_arch = "x86_64")]
+    {
+        // SAFETY: RDTSC is available on every x86_64 CPU
+        Some(unsafe { core::arch::x86_64::_rdtsc() })
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    {
+        None
+    }
+}
+
+/// Whether the CPU has an invariant TSC (constant rate, synchronized across cores)
+pub fn tsc_available() -> bool {
+    #[cfg(target_arch = "x86_64")]
+    {
+        use core::arch::x86_64::__cpuid;
+        // Leaf 0x80000007 is only queried when the CPU reports it
+        __cpuid(0x8000_0000).eax >= 0x8000_0007 && __cpuid(0x8000_0007).edx & (1 << 8) != 0
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    {
+        false
+    }
+}
+
+/// Switch `FastInstant` to the TSC for the rest of the process
+///
+/// Calibrates once (about 20ms); later calls return immediately. Fails if
+/// the CPU has no invariant TSC, leaving CLOCK_MONOTONIC in use.
+pub fn enable_tsc() -> Result<(), String> {
+    if TSC.get().is_some() {
+        return Ok(());
+    }
+    if !tsc_available() {
+        return Err("CPU has no invariant TSC".to_string());
+    }
+    let start_nanos = FastInstant::clock_now().nanos;
+    let start_tsc = read_tsc().ok_or("no TSC on this architecture")?;
+    std::thread::sleep(TSC_CALIBRATION_TIME);
+    let end_nanos = FastInstant::clock_now().nanos;
+    let end_tsc = read_tsc().ok_or("no TSC on this architecture")?;
+
+    let ticks = end_tsc.wrapping_sub(start_tsc);
+    if ticks == 0 {
+        return Err("TSC did not advance".to_string());
+    }
+    let nanos_per_tick = (((end_nanos - start_nanos) as u128) << 32) / ticks as u128;
+    let _ = TSC.set(TscCalibration {
+        base_tsc: end_tsc,
+        base_nanos: end_nanos,
+        nanos_per_tick: nanos_per_tick as u64,
+    });
+    Ok(())
+}
+
+/// Whether `FastInstant` reads the TSC
+pub fn tsc_enabled() -> bool {
+    TSC.get().is_some()
+}
+
+/// Calibrated TSC frequency in Hz (None unless the TSC is enabled)
+pub fn tsc_frequency() -> Option<u64> {
+    let tsc = TSC.get()?;
+    Some(((1_000_000_000u128 << 32) / tsc.nanos_per_tick.max(1) as u128) as u64)
+}
+
 /// Fast timestamp using direct clock_gettime with CLOCK_MONOTONIC
 ///
 /// This is faster than std::time::Instant because it bypasses Rust's
@@ -18,9 +123,20 @@ pub struct FastInstant {
 }
 
 impl FastInstant {
-    /// Get the current time using CLOCK_MONOTONIC (accurate, nanosecond resolution)
+    /// Get the current time (CLOCK_MONOTONIC, or the TSC once enabled)
     #[inline(always)]
     pub fn now() -> Self {
+        if let Some(tsc) = TSC.get() {
+            if let Some(ticks) = read_tsc() {
+                return Self { nanos: tsc.nanos(ticks) };
+            }
+        }
+        Self::clock_now()
+    }
+    
+    /// Get the current time using CLOCK_MONOTONIC (accurate, nanosecond resolution)
+    #[inline(always)]
+    fn clock_now() -> Self {
         let mut ts = libc::timespec {
             tv_sec: 0,
             tv_nsec: 0,
@@ -68,6 +184,76 @@ impl FastInstant {
     }
 }
 
+/// Measured precision of the latency timer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimerCheck {
+    /// `FastInstant` reads the TSC
+    pub tsc: bool,
+    /// Smallest step between two readings (nanoseconds)
+    pub resolution_ns: u64,
+    /// Mean cost of one reading (nanoseconds)
+    pub overhead_ns: u64,
+    /// Calibrated TSC frequency in Hz (0 with clock_gettime)
+    #[serde(default)]
+    pub tsc_hz: u64,
+}
+
+impl TimerCheck {
+    /// Measure the timer `FastInstant` currently uses (well under 1ms for a vDSO clock)
+    pub fn measure() -> Self {
+        const CALLS: u64 = 10_000;
+        const STEPS: usize = 200;
+
+        let start = FastInstant::now();
+        for _ in 0..CALLS {
+            std::hint::black_box(FastInstant::now());
+        }
+        let overhead_ns = start.elapsed().as_nanos() as u64 / CALLS;
+
+        let mut resolution_ns = u64::MAX;
+        for _ in 0..STEPS {
+            let first = FastInstant::now();
+            let mut next = FastInstant::now();
+            // A timer that doesn't tick within 1ms counts as 1ms resolution
+            for _ in 0..1_000_000 {
+                if next.nanos != first.nanos {
+                    break;
+                }
+                next = FastInstant::now();
+            }
+            let step = next.nanos.saturating_sub(first.nanos);
+            resolution_ns = resolution_ns.min(if step == 0 { 1_000_000 } else { step });
+        }
+
+        Self { tsc: tsc_enabled(), resolution_ns, overhead_ns, tsc_hz: tsc_frequency().unwrap_or(0) }
+    }
+
+    /// Smallest latency difference the timer can tell apart
+    ///
+    /// A latency is two readings apart: it is off by up to one resolution
+    /// step, plus the cost of the reading that closes it.
+    pub fn noise_floor(&self) -> Duration {
+        Duration::from_nanos(self.resolution_ns + self.overhead_ns)
+    }
+
+    /// Keep the less precise of two checks (e.g., from two nodes)
+    pub fn worst(self, other: TimerCheck) -> TimerCheck {
+        if other.noise_floor() > self.noise_floor() { other } else { self }
+    }
+
+    /// Warning if `typical` latency is within `NOISE_FLOOR_MARGIN` of the noise floor
+    pub fn warning(&self, typical: Duration) -> Option<String> {
+        let floor = self.noise_floor();
+        if typical.is_zero() || typical >= floor * NOISE_FLOOR_MARGIN as u32 {
+            return None;
+        }
+        let hint = if !self.tsc && tsc_available() { "; --timer tsc may lower it" } else { "" };
+        Some(format!(
+            "median latency {:?} is less than {}x the timer noise floor of {:?} ({}ns resolution, {}ns per reading), so latencies may be off by a few percent{}",
+            typical, NOISE_FLOOR_MARGIN, floor, self.resolution_ns, self.overhead_ns, hint))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -110,6 +296,37 @@ mod tests {
         assert!(elapsed < Duration::from_millis(50));
     }
     
+    #[test]
+    fn test_timer_check() {
+        let check = TimerCheck::measure();
+        assert!(check.resolution_ns > 0);
+        assert!(check.noise_floor() < Duration::from_millis(2));
+
+        let check = TimerCheck { tsc: true, resolution_ns: 20, overhead_ns: 20, tsc_hz: 3_000_000_000 };
+        assert!(check.warning(Duration::from_micros(1)).is_some());
+        assert!(check.warning(Duration::from_micros(2)).is_none());
+        assert!(check.warning(Duration::ZERO).is_none());
+        let slow = TimerCheck { tsc: false, resolution_ns: 1000, overhead_ns: 600, tsc_hz: 0 };
+        assert_eq!(check.worst(slow), slow);
+
+    }
+    
+    #[test]
+    fn test_tsc_matches_clock() {
+        if enable_tsc().is_err() {
+            return;
+        }
+        assert!(tsc_frequency().unwrap() > 100_000_000);
+
+        // TSC and CLOCK_MONOTONIC agree over a sleep within 1%
+        let clock_start = FastInstant::clock_now();
+        let start = FastInstant::now();
+        thread::sleep(Duration::from_millis(50));
+        let elapsed = start.elapsed().as_nanos() as f64;
+        let clock_elapsed = FastInstant::clock_now().duration_since(clock_start).as_nanos() as f64;
+        assert!((elapsed / clock_elapsed - 1.0).abs() < 0.01, "tsc {} vs clock {}", elapsed, clock_elapsed);
+    }
+    
     #[test]
     fn test_fast_instant_ordering() {
         let t1 = FastInstant::now();
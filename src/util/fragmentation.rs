@@ -0,0 +1,215 @@
+//! Filesystem fragmentation analysis
+//!
+//! Maps the extents of target files with the FIEMAP ioctl (what `filefrag`
+//! uses) after a test, so results can be correlated with the fragmentation the
+//! write pattern caused. Physically contiguous extents are merged, matching
+//! filefrag's extent count; `FIEMAP_FLAG_SYNC` flushes delayed allocations
+//! first so freshly written data has real block addresses.
+
+use serde::{Deserialize, Serialize};
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::path::Path;
+
+/// FS_IOC_FIEMAP = _IOWR('f', 11, struct fiemap)
+const FS_IOC_FIEMAP: libc::Ioctl = libc::_IOWR::<FiemapHeader>(b'f' as u32, 11);
+const FIEMAP_FLAG_SYNC: u32 = 0x1;
+const FIEMAP_EXTENT_LAST: u32 = 0x1;
+
+/// Extents requested per ioctl call
+const EXTENTS_PER_CALL: usize = 512;
+
+/// struct fiemap_extent (linux/fiemap.h)
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+struct FiemapExtent {
+    fe_logical: u64,
+    fe_physical: u64,
+    fe_length: u64,
+    fe_reserved64: [u64; 2],
+    fe_flags: u32,
+    fe_reserved: [u32; 3],
+}
+
+/// struct fiemap (linux/fiemap.h) without its flexible extent array
+#[repr(C)]
+struct FiemapHeader {
+    fm_start: u64,
+    fm_length: u64,
+    fm_flags: u32,
+    fm_mapped_extents: u32,
+    fm_extent_count: u32,
+    fm_reserved: u32,
+}
+
+const _: () = assert!(std::mem::size_of::<FiemapHeader>() == 32);
+
+/// struct fiemap with room for `EXTENTS_PER_CALL` extents
+#[repr(C)]
+struct Fiemap {
+    header: FiemapHeader,
+    fm_extents: [FiemapExtent; EXTENTS_PER_CALL],
+}
+
+/// Extent layout of one file
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FileExtents {
+    /// Physically contiguous runs of blocks
+    pub extents: u64,
+    /// Bytes backed by extents (excludes holes)
+    pub mapped_bytes: u64,
+}
+
+/// Map the extents of an open file
+pub fn file_extents(fd: RawFd) -> std::io::Result<FileExtents> {
+    let mut result = FileExtents::default();
+    let mut start = 0u64;
+    let mut flags = FIEMAP_FLAG_SYNC;
+    let mut last_physical_end: Option<u64> = None;
+
+    loop {
+        let mut map = Box::new(Fiemap {
+            header: FiemapHeader {
+                fm_start: start,
+                fm_length: u64::MAX - start,
+                fm_flags: flags,
+                fm_mapped_extents: 0,
+                fm_extent_count: EXTENTS_PER_CALL as u32,
+                fm_reserved: 0,
+            },
+            fm_extents: [FiemapExtent::default(); EXTENTS_PER_CALL],
+        });
+        if unsafe { libc::ioctl(fd, FS_IOC_FIEMAP, &mut *map as *mut Fiemap) } != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        flags = 0;  // Sync once
+
+        let mapped = map.header.fm_mapped_extents as usize;
+        for extent in &map.fm_extents[..mapped] {
+            if last_physical_end != Some(extent.fe_physical) {
+                result.extents += 1;
+            }
+            last_physical_end = Some(extent.fe_physical + extent.fe_length);
+            result.mapped_bytes += extent.fe_length;
+        }
+
+        match map.fm_extents[..mapped].last() {
+            Some(last) if last.fe_flags & FIEMAP_EXTENT_LAST == 0 => {
+                start = last.fe_logical + last.fe_length;
+            }
+            _ => return Ok(result),
+        }
+    }
+}
+
+/// Fragmentation summary over a set of files
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FragmentationReport {
+    /// Files analyzed
+    pub files: u64,
+    /// Extents across all files
+    pub extents: u64,
+    /// Bytes backed by extents
+    pub mapped_bytes: u64,
+    /// Extents of the most fragmented file
+    pub max_extents: u64,
+    /// Files that could not be mapped (FIEMAP unsupported, e.g. tmpfs or NFS)
+    pub unsupported: u64,
+}
+
+impl FragmentationReport {
+    /// Analyze files by path (missing and unmappable files count as unsupported)
+    pub fn analyze<P: AsRef<Path>>(paths: impl IntoIterator<Item = P>) -> Self {
+        let mut report = Self::default();
+        for path in paths {
+            let extents = std::fs::File::open(path.as_ref())
+                .and_then(|file| file_extents(file.as_raw_fd()));
+            match extents {
+                Ok(extents) => report.add_file(extents),
+                Err(_) => report.unsupported += 1,
+            }
+        }
+        report
+    }
+
+    /// Add one file's extents
+    pub fn add_file(&mut self, file: FileExtents) {
+        self.files += 1;
+        self.extents += file.extents;
+        self.mapped_bytes += file.mapped_bytes;
+        self.max_extents = self.max_extents.max(file.extents);
+    }
+
+    /// Add another node's report (covering other files)
+    pub fn merge(&mut self, other: &FragmentationReport) {
+        self.files += other.files;
+        self.extents += other.extents;
+        self.mapped_bytes += other.mapped_bytes;
+        self.max_extents = self.max_extents.max(other.max_extents);
+        self.unsupported += other.unsupported;
+    }
+
+    /// Average extent size in bytes
+    pub fn avg_extent_size(&self) -> u64 {
+        self.mapped_bytes.checked_div(self.extents).unwrap_or(0)
+    }
+
+    /// Average extents per analyzed file
+    pub fn extents_per_file(&self) -> f64 {
+        if self.files == 0 {
+            0.0
+        } else {
+            self.extents as f64 / self.files as f64
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_file_extents() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+
+        // Empty files have no extents
+        if let Ok(extents) = file_extents(file.as_file().as_raw_fd()) {
+            assert_eq!(extents, FileExtents::default());
+        }
+
+        file.write_all(&vec![0xA5u8; 1 << 20]).unwrap();
+        file.flush().unwrap();
+
+        // tmpfs and some network filesystems don't support FIEMAP
+        let report = FragmentationReport::analyze([file.path()]);
+        if report.unsupported == 0 {
+            assert_eq!(report.files, 1);
+            assert!(report.extents >= 1);
+            assert_eq!(report.mapped_bytes, 1 << 20);
+            assert_eq!(report.avg_extent_size(), report.mapped_bytes / report.extents);
+        }
+    }
+
+    #[test]
+    fn test_merge_reports() {
+        let mut report = FragmentationReport::default();
+        report.add_file(FileExtents { extents: 3, mapped_bytes: 3 << 20 });
+        let mut other = FragmentationReport { unsupported: 1, ..Default::default() };
+        other.add_file(FileExtents { extents: 5, mapped_bytes: 1 << 20 });
+
+        report.merge(&other);
+        assert_eq!(report.files, 2);
+        assert_eq!(report.extents, 8);
+        assert_eq!(report.mapped_bytes, 4 << 20);
+        assert_eq!(report.max_extents, 5);
+        assert_eq!(report.unsupported, 1);
+    }
+
+    #[test]
+    fn test_report_missing_file() {
+        let report = FragmentationReport::analyze(["/nonexistent/iopulse-frag-test"]);
+        assert_eq!(report.files, 0);
+        assert_eq!(report.unsupported, 1);
+        assert_eq!(report.avg_extent_size(), 0);
+    }
+}
@@ -0,0 +1,77 @@
+//! Advisory lock files
+//!
+//! Concurrent IOPulse runs on one host claim what they can't share (a
+//! dataset, a localhost service port) with `flock(2)` on a lock file. The
+//! lock is released when the `LockFile` is dropped or the process exits, so
+//! a crashed run never leaves a stale lock behind. Lock files themselves are
+//! left in place: removing them would race with a run that is opening them.
+//!
+//! The holder writes a description of itself into the file (the latest one
+//! of several shared holders), which a run that finds the lock taken can show
+//! in its error message.
+//!
+//! # Example
+//!
+//! ```
+//! use iopulse::util::lock_file::LockFile;
+//!
+//! let dir = tempfile::tempdir().unwrap();
+//! let path = dir.path().join("dataset.lock");
+//! let lock = LockFile::try_acquire(&path, true, "run 42").unwrap().unwrap();
+//! assert!(LockFile::try_acquire(&path, true, "run 43").unwrap().is_none());
+//! assert_eq!(LockFile::holder(&path), "run 42");
+//! drop(lock);
+//! assert!(LockFile::try_acquire(&path, false, "run 43").unwrap().is_some());
+//! ```
+
+use anyhow::{Context, Result};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
+
+/// A held advisory lock
+#[derive(Debug)]
+pub struct LockFile {
+    _file: File,
+    path: PathBuf,
+}
+
+impl LockFile {
+    /// Take the lock without waiting
+    ///
+    /// Returns `Ok(None)` when another process holds it (exclusively, or at
+    /// all when `exclusive` is requested).
+    pub fn try_acquire(path: &Path, exclusive: bool, holder: &str) -> Result<Option<Self>> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)
+            .with_context(|| format!("Failed to open lock file {}", path.display()))?;
+
+        let operation = if exclusive { libc::LOCK_EX } else { libc::LOCK_SH };
+        if unsafe { libc::flock(file.as_raw_fd(), operation | libc::LOCK_NB) } != 0 {
+            let err = std::io::Error::last_os_error();
+            if err.raw_os_error() == Some(libc::EWOULDBLOCK) {
+                return Ok(None);
+            }
+            return Err(err).with_context(|| format!("Failed to lock {}", path.display()));
+        }
+
+        file.set_len(0)?;
+        file.write_all(holder.as_bytes())?;
+        Ok(Some(Self { _file: file, path: path.to_path_buf() }))
+    }
+
+    /// Description written by the latest holder (empty if unknown)
+    pub fn holder(path: &Path) -> String {
+        std::fs::read_to_string(path).unwrap_or_default().trim().to_string()
+    }
+
+    /// Path of the lock file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
@@ -0,0 +1,272 @@
+//! Diagnostic logging
+//!
+//! Runtime diagnostics (IO errors under `--continue-on-error`, engine notes,
+//! node service events, `--debug` traces) go through the `tracing` crate
+//! rather than ad-hoc `eprintln!`. Each worker runs inside a `worker` span
+//! carrying its ID (and in distributed runs its unit ID, see
+//! [`crate::util::trace_id`]), and setup phases run inside `setup` spans, so
+//! every message says which worker (and phase) it came from.
+//!
+//! Two outputs are installed once per process by [`init`]:
+//!
+//! - **stderr**: INFO and above by default; DEBUG with `--debug` or `-vvv`,
+//!   TRACE with `-vvvv`
+//! - **per-worker files** (`--log-dir`): every DEBUG-and-above event inside a
+//!   worker span is appended to `<log-dir>/worker-<id>.log`, whatever the
+//!   stderr level. Events outside worker spans are not written to files.
+//!
+//! Setup phase durations are logged at DEBUG as each phase completes.
+//!
+//! The level and log directory come from the test configuration, which a node
+//! service only receives with CONFIG, so [`configure`] can be called again
+//! for every test.
+
+use crate::Result;
+use anyhow::Context;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs::{File, OpenOptions};
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::{Mutex, Once};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context as LayerContext, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{filter, Layer};
+
+/// Current stderr level (see `level_index`)
+static STDERR_LEVEL: AtomicU8 = AtomicU8::new(2);
+
+/// Per-worker log files (None when `--log-dir` is not set)
+static WORKER_LOGS: Mutex<Option<WorkerLogs>> = Mutex::new(None);
+
+/// Whether `WORKER_LOGS` is set (checked without taking the lock)
+static WORKER_LOGS_ENABLED: AtomicBool = AtomicBool::new(false);
+
+static INIT: Once = Once::new();
+
+/// Stderr log level for the given `-v` count and `--debug` flag
+pub fn level_for(verbosity: u8, debug: bool) -> Level {
+    match verbosity {
+        4.. => Level::TRACE,
+        3 => Level::DEBUG,
+        _ if debug => Level::DEBUG,
+        _ => Level::INFO,
+    }
+}
+
+/// Install the process-wide subscriber (stderr at INFO, no log files)
+///
+/// Safe to call more than once; only the first call has an effect.
+pub fn init() {
+    INIT.call_once(|| {
+        let stderr = tracing_subscriber::fmt::layer()
+            .with_writer(std::io::stderr)
+            .without_time()
+            .with_target(false)
+            .with_ansi(false)
+            .with_filter(filter::filter_fn(|meta| {
+                level_index(*meta.level()) <= STDERR_LEVEL.load(Ordering::Relaxed)
+            }));
+
+        let files = WorkerFileLayer.with_filter(filter::filter_fn(|meta| {
+            WORKER_LOGS_ENABLED.load(Ordering::Relaxed) && *meta.level() <= Level::DEBUG
+        }));
+
+        // Another subscriber may already be installed (e.g. by a test harness)
+        let _ = tracing_subscriber::registry()
+            .with(stderr)
+            .with(files)
+            .try_init();
+    });
+}
+
+/// Apply the logging settings of a test configuration
+///
+/// Sets the stderr level and opens (or closes) the per-worker log directory.
+pub fn configure(verbosity: u8, debug: bool, log_dir: Option<&Path>) -> Result<()> {
+    init();
+    STDERR_LEVEL.store(level_index(level_for(verbosity, debug)), Ordering::Relaxed);
+
+    let logs = match log_dir {
+        Some(dir) => {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create log directory {}", dir.display()))?;
+            Some(WorkerLogs { dir: dir.to_path_buf(), files: HashMap::new() })
+        }
+        None => None,
+    };
+    WORKER_LOGS_ENABLED.store(logs.is_some(), Ordering::Relaxed);
+    *WORKER_LOGS.lock().unwrap_or_else(|e| e.into_inner()) = logs;
+    Ok(())
+}
+
+/// Path of a worker's log file inside the log directory
+pub fn worker_log_path(dir: &Path, worker_id: usize) -> PathBuf {
+    dir.join(format!("worker-{}.log", worker_id))
+}
+
+/// Levels as ordered indices (ERROR = 0 ... TRACE = 4)
+fn level_index(level: Level) -> u8 {
+    match level {
+        Level::ERROR => 0,
+        Level::WARN => 1,
+        Level::INFO => 2,
+        Level::DEBUG => 3,
+        Level::TRACE => 4,
+    }
+}
+
+struct WorkerLogs {
+    dir: PathBuf,
+    files: HashMap<usize, File>,
+}
+
+/// Worker ID and unit ID stored in the extensions of a `worker` span
+struct WorkerId(usize, Option<String>);
+
+/// Routes events inside a `worker` span to that worker's log file
+struct WorkerFileLayer;
+
+impl<S> Layer<S> for WorkerFileLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: LayerContext<'_, S>) {
+        if attrs.metadata().name() != "worker" {
+            return;
+        }
+        let mut visitor = IdVisitor::default();
+        attrs.record(&mut visitor);
+        if let (Some(worker_id), Some(span)) = (visitor.id, ctx.span(id)) {
+            span.extensions_mut().insert(WorkerId(worker_id, visitor.uid));
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: LayerContext<'_, S>) {
+        let Some(scope) = ctx.event_scope(event) else {
+            return;
+        };
+
+        let mut worker_id = None;
+        let mut uid = None;
+        let mut spans = String::new();
+        for span in scope.from_root() {
+            if let Some(id) = span.extensions().get::<WorkerId>() {
+                worker_id = Some(id.0);
+                uid = id.1.clone();
+            } else {
+                spans.push_str(span.name());
+                spans.push_str(": ");
+            }
+        }
+        let Some(worker_id) = worker_id else {
+            return;
+        };
+
+        let mut guard = WORKER_LOGS.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(logs) = guard.as_mut() else {
+            return;
+        };
+
+        let mut line = format!("{} {:>5} {}{}",
+            chrono::Local::now().format("%Y-%m-%dT%H:%M:%S%.6f"),
+            event.metadata().level(),
+            uid.map(|uid| format!("[{}] ", uid)).unwrap_or_default(),
+            spans);
+        event.record(&mut LineVisitor(&mut line));
+        line.push('\n');
+
+        let file = match logs.files.entry(worker_id) {
+            std::collections::hash_map::Entry::Occupied(entry) => entry.into_mut(),
+            std::collections::hash_map::Entry::Vacant(entry) => {
+                let path = worker_log_path(&logs.dir, worker_id);
+                match OpenOptions::new().create(true).append(true).open(&path) {
+                    Ok(file) => entry.insert(file),
+                    Err(_) => return,
+                }
+            }
+        };
+        // Logging must never fail the test
+        let _ = file.write_all(line.as_bytes());
+    }
+}
+
+/// Extracts the `id` and `uid` fields of a `worker` span
+#[derive(Default)]
+struct IdVisitor {
+    id: Option<usize>,
+    uid: Option<String>,
+}
+
+impl Visit for IdVisitor {
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        if field.name() == "id" {
+            self.id = Some(value as usize);
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "uid" {
+            self.uid = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, _field: &Field, _value: &dyn std::fmt::Debug) {}
+}
+
+/// Formats an event as "message key=value ..."
+struct LineVisitor<'a>(&'a mut String);
+
+impl Visit for LineVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.0, "{:?}", value);
+        } else {
+            let _ = write!(self.0, " {}={:?}", field.name(), value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_for() {
+        assert_eq!(level_for(0, false), Level::INFO);
+        assert_eq!(level_for(2, false), Level::INFO);
+        assert_eq!(level_for(0, true), Level::DEBUG);
+        assert_eq!(level_for(3, false), Level::DEBUG);
+        assert_eq!(level_for(4, true), Level::TRACE);
+    }
+
+    #[test]
+    fn test_worker_log_file() {
+        let dir = std::env::temp_dir().join(format!("iopulse_logs_{}", std::process::id()));
+        configure(0, false, Some(&dir)).unwrap();
+
+        {
+            let _span = tracing::info_span!("worker", id = 7usize).entered();
+            tracing::debug!(offset = 4096u64, "short read");
+        }
+        {
+            let _span = tracing::info_span!("worker", id = 8usize, uid = Some("run.n0.w1")).entered();
+            tracing::warn!("slow");
+        }
+        tracing::warn!("outside any worker");
+
+        let log = std::fs::read_to_string(worker_log_path(&dir, 7)).unwrap();
+        assert!(log.contains("DEBUG short read offset=4096"), "{}", log);
+        assert!(!log.contains("outside"));
+        let log = std::fs::read_to_string(worker_log_path(&dir, 8)).unwrap();
+        assert!(log.contains("WARN [run.n0.w1] slow"), "{}", log);
+
+        configure(0, false, None).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -2,6 +2,17 @@
 
 pub mod buffer;
 pub mod verification;
+pub mod pattern_buffers;
 pub mod time;
 pub mod fast_time;
-pub mod resource;
\ No newline at end of file
+pub mod resource;
+pub mod fragmentation;
+pub mod estimate;
+pub mod striping;
+pub mod logging;
+pub mod lock_file;
+pub mod device_link;
+pub mod space;
+pub mod trace_id;
+pub mod capabilities;
+pub mod preflight;
@@ -0,0 +1,121 @@
+//! Pre-filled write buffers for repeating patterns
+//!
+//! Filling a write buffer per op costs a pass over the buffer in the submit
+//! path. The zeros, ones, sequential and custom patterns repeat with a fixed
+//! period, so the data for a write at file offset N depends only on the
+//! phase `N % period`. `PatternBuffers` keeps one read-only buffer per phase,
+//! filled the first time the phase is seen, and hands out its pointer for
+//! every later write with that phase.
+//!
+//! Offsets are block-aligned, so a workload sees few phases: one for zeros
+//! and ones, and usually one for sequential. Phases beyond the memory cap are
+//! not cached and the caller fills its own buffer as before. The random
+//! pattern with verification is seeded by offset and has no period.
+//!
+//! # Example
+//!
+//! ```
+//! use iopulse::util::pattern_buffers::PatternBuffers;
+//!
+//! let period: Vec<u8> = (0..=255).collect();
+//! let mut buffers = PatternBuffers::new(period, 4096, 512);
+//!
+//! let ptr = buffers.get(8192 + 10, 4096).unwrap();
+//! let data = unsafe { std::slice::from_raw_parts(ptr, 4096) };
+//! assert_eq!(data[0], 10);
+//! assert_eq!(data[246], 0);
+//! ```
+
+use crate::util::buffer::AlignedBuffer;
+use crate::util::verification::{fill_buffer, VerificationPattern};
+use std::collections::HashMap;
+
+/// Memory allowed for pre-filled buffers (per worker)
+pub const MAX_CACHED_BYTES: usize = 8 * 1024 * 1024;
+
+/// Read-only write buffers, one per phase of a repeating pattern
+pub struct PatternBuffers {
+    /// One period of the pattern, starting at file offset 0
+    period: Vec<u8>,
+    /// Size of each buffer (the largest write)
+    buffer_size: usize,
+    alignment: usize,
+    /// Buffers by phase (file offset % period length)
+    buffers: HashMap<usize, AlignedBuffer>,
+    /// Buffers allowed before new phases are no longer cached
+    max_buffers: usize,
+}
+
+impl PatternBuffers {
+    /// Create an empty cache for a pattern with the given period
+    ///
+    /// `buffer_size` is the largest write, `alignment` the buffer alignment
+    /// required by the target (O_DIRECT).
+    pub fn new(period: Vec<u8>, buffer_size: usize, alignment: usize) -> Self {
+        assert!(!period.is_empty(), "Pattern period must not be empty");
+        Self {
+            period,
+            buffer_size,
+            alignment,
+            buffers: HashMap::new(),
+            max_buffers: (MAX_CACHED_BYTES / buffer_size.max(1)).max(1),
+        }
+    }
+
+    /// Pattern data for a write of `length` bytes at file offset `offset`
+    ///
+    /// Returns None when the write is larger than the buffers or its phase
+    /// can't be cached; the caller then fills its own buffer.
+    #[inline]
+    pub fn get(&mut self, offset: u64, length: usize) -> Option<*const u8> {
+        if length > self.buffer_size {
+            return None;
+        }
+        let phase = (offset % self.period.len() as u64) as usize;
+        if let Some(buffer) = self.buffers.get(&phase) {
+            return Some(buffer.as_ptr());
+        }
+        if self.buffers.len() >= self.max_buffers {
+            return None;
+        }
+
+        let mut buffer = AlignedBuffer::new(self.buffer_size, self.alignment);
+        fill_buffer(buffer.as_mut_slice(), VerificationPattern::Repeat(&self.period), phase as u64);
+        let ptr = buffer.as_ptr();
+        self.buffers.insert(phase, buffer);
+        Some(ptr)
+    }
+
+    /// Number of phases cached
+    pub fn len(&self) -> usize {
+        self.buffers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buffers.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_per_op_fill() {
+        let payload = vec![1u8, 2, 3, 4, 5, 6];
+        let mut buffers = PatternBuffers::new(payload.clone(), 1024, 512);
+
+        for offset in [0u64, 1024, 2048, 6144] {
+            let ptr = buffers.get(offset, 1024).unwrap();
+            let cached = unsafe { std::slice::from_raw_parts(ptr, 1024) };
+            let mut expected = vec![0u8; 1024];
+            fill_buffer(&mut expected, VerificationPattern::Repeat(&payload), offset);
+            assert_eq!(cached, &expected[..]);
+            assert_eq!(ptr as usize % 512, 0);
+        }
+        // 0 and 6144 share a phase; 1024 and 2048 each have their own
+        assert_eq!(buffers.len(), 3);
+
+        assert!(buffers.get(0, 2048).is_none());
+    }
+}
@@ -0,0 +1,235 @@
+//! Preflight checks and advice for setup failures
+//!
+//! A run can fail after hours of preparation because one node mounts the
+//! target read-only, lacks permission on a directory or runs out of quota.
+//! `--preflight` checks each target on the coordinator host and on every
+//! node before any preparation begins:
+//!
+//! - **create**: a file can be created where the run creates the target
+//! - **write**: it can be written (a block, synced), or an existing target
+//!   opened for writing
+//! - **O_DIRECT** (with `--direct`): the filesystem accepts direct IO
+//!
+//! Targets the run creates are probed with a scratch file next to them,
+//! which is removed again; existing targets are only opened.
+//!
+//! Setup failures with a common cause (read-only filesystem, permission
+//! denied, quota or space exhausted, a file where a directory is expected)
+//! get advice on what to do, whether or not `--preflight` was given.
+
+use crate::config::{Config, TargetConfig, TargetType};
+use crate::util::buffer::AlignedBuffer;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::os::unix::fs::{FileExt, OpenOptionsExt};
+use std::path::{Path, PathBuf};
+
+/// Bytes written by the write and O_DIRECT probes
+const PROBE_BLOCK: usize = 4096;
+
+/// One check of one target
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TargetCheck {
+    pub path: PathBuf,
+    /// What was checked: create, write, read or O_DIRECT
+    pub check: String,
+    /// Why the check failed, with advice when the cause is a common one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Checks of the targets of a run on one host
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PreflightReport {
+    pub checks: Vec<TargetCheck>,
+}
+
+impl PreflightReport {
+    /// Check every target of `config` from this host
+    pub fn run(config: &Config) -> Self {
+        let mut report = Self::default();
+        for target in &config.targets {
+            report.check_target(config, target);
+        }
+        report
+    }
+
+    /// Checks that failed
+    pub fn failures(&self) -> impl Iterator<Item = &TargetCheck> {
+        self.checks.iter().filter(|check| check.error.is_some())
+    }
+
+    fn check_target(&mut self, config: &Config, target: &TargetConfig) {
+        let path = &target.path;
+        let read_only = config.runtime.read_only;
+        let direct = config.workload.direct;
+
+        // Used in place: an existing file or a device is opened, never written
+        let in_place = target.target_type == TargetType::BlockDevice
+            || (target.target_type != TargetType::Directory && path.exists());
+        if in_place || read_only {
+            let writes = !read_only && (target.target_type != TargetType::BlockDevice || config.workload.write_percent > 0);
+            self.record(path, if writes { "write" } else { "read" }, open_existing(path, writes, 0));
+            if direct && target.target_type != TargetType::Directory {
+                self.record(path, "O_DIRECT", open_existing(path, writes, libc::O_DIRECT));
+            }
+            return;
+        }
+
+        // Created by the run: probe with a scratch file in its directory
+        let start = if target.target_type == TargetType::Directory { Some(path.as_path()) } else { path.parent() };
+        let dir = start.into_iter()
+            .flat_map(Path::ancestors)
+            .map(|dir| if dir.as_os_str().is_empty() { Path::new(".") } else { dir })
+            .find(|dir| dir.exists())
+            .unwrap_or(Path::new("."));
+        if !dir.is_dir() {
+            let error = anyhow::Error::new(std::io::Error::from_raw_os_error(libc::ENOTDIR))
+                .context(format!("{} is in the way of {}", dir.display(), path.display()));
+            return self.record(path, "create", Err(error));
+        }
+        let probe = dir.join(format!(".iopulse-preflight-{}", std::process::id()));
+        let file = match std::fs::OpenOptions::new().write(true).create_new(true).open(&probe) {
+            Ok(file) => file,
+            Err(e) => {
+                let error = anyhow::Error::new(e).context(format!("Failed to create a file in {}", dir.display()));
+                return self.record(path, "create", Err(error));
+            }
+        };
+        self.record(path, "create", Ok(()));
+        let written = (&file).write_all(&[0xA5; PROBE_BLOCK])
+            .and_then(|_| file.sync_all())
+            .with_context(|| format!("Failed to write a file in {}", dir.display()));
+        self.record(path, "write", written);
+        if direct {
+            self.record(path, "O_DIRECT", write_direct(&probe));
+        }
+        drop(file);
+        let _ = std::fs::remove_file(&probe);
+    }
+
+    fn record(&mut self, path: &Path, check: &str, result: Result<()>) {
+        self.checks.push(TargetCheck {
+            path: path.to_path_buf(),
+            check: check.to_string(),
+            error: result.err().map(|e| format!("{:#}", explain(e))),
+        });
+    }
+}
+
+/// Open an existing target for reading, or reading and writing
+fn open_existing(path: &Path, write: bool, flags: i32) -> Result<()> {
+    std::fs::OpenOptions::new().read(true).write(write).custom_flags(flags).open(path)
+        .map(drop)
+        .map_err(|e| direct_unsupported(e, flags))
+        .with_context(|| format!("Failed to open {}{}", path.display(), if flags != 0 { " with O_DIRECT" } else { "" }))
+}
+
+/// Write a block to `path` with O_DIRECT
+fn write_direct(path: &Path) -> Result<()> {
+    let file = std::fs::OpenOptions::new().write(true).custom_flags(libc::O_DIRECT).open(path)
+        .map_err(|e| direct_unsupported(e, libc::O_DIRECT))
+        .with_context(|| format!("Failed to open {} with O_DIRECT", path.display()))?;
+    let buffer = AlignedBuffer::new(PROBE_BLOCK, PROBE_BLOCK);
+    file.write_all_at(buffer.as_slice(), 0)
+        .with_context(|| format!("Failed to write {} with O_DIRECT", path.display()))
+}
+
+/// EINVAL from an O_DIRECT open means the filesystem doesn't support it
+fn direct_unsupported(error: std::io::Error, flags: i32) -> anyhow::Error {
+    if flags & libc::O_DIRECT != 0 && error.raw_os_error() == Some(libc::EINVAL) {
+        anyhow::Error::new(error).context("The filesystem doesn't support O_DIRECT: run without --direct, or choose a target on another filesystem")
+    } else {
+        error.into()
+    }
+}
+
+/// Create a directory and its parents
+///
+/// `create_dir_all` reports a file in the way as "File exists"; this reports
+/// it as not a directory, which gets advice.
+pub fn create_dirs(dir: &Path) -> Result<()> {
+    std::fs::create_dir_all(dir)
+        .map_err(|e| if e.kind() == std::io::ErrorKind::AlreadyExists {
+            std::io::Error::from_raw_os_error(libc::ENOTDIR)
+        } else {
+            e
+        })
+        .with_context(|| format!("Failed to create directory: {}", dir.display()))
+}
+
+/// What to do about a setup failure with a common cause
+pub fn advice(error: &std::io::Error) -> Option<&'static str> {
+    match error.raw_os_error()? {
+        libc::EROFS => Some("The filesystem is mounted read-only: remount it read-write, choose a target on a writable filesystem, or use --readonly for a read test"),
+        libc::EACCES | libc::EPERM => Some("Permission denied: check the owner and mode of the path and its parent directories, or run as a user with access to them"),
+        libc::EDQUOT => Some("Disk quota exceeded: free space within the quota, ask for a larger one, or use a smaller --file-size"),
+        libc::ENOSPC => Some("No space left on the filesystem: free space or use a smaller --file-size"),
+        libc::ENOTDIR => Some("A component of the path is a file, not a directory: check the target path"),
+        _ => None,
+    }
+}
+
+/// Put advice in front of an error whose cause is a common setup failure
+pub fn explain(error: anyhow::Error) -> anyhow::Error {
+    let advice = error.chain()
+        .filter_map(|cause| cause.downcast_ref::<std::io::Error>())
+        .find_map(advice);
+    match advice {
+        Some(advice) if !error.chain().any(|cause| cause.to_string() == advice) => error.context(advice),
+        _ => error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explain_common_failures() {
+        let error = anyhow::Error::new(std::io::Error::from_raw_os_error(libc::EROFS)).context("Failed to open file: /mnt/ro/test.dat");
+        let explained = explain(error);
+        assert!(explained.to_string().starts_with("The filesystem is mounted read-only"));
+        assert!(format!("{:#}", explained).contains("Failed to open file: /mnt/ro/test.dat"));
+        // Explained once only
+        assert_eq!(explain(explained).chain().count(), 3);
+
+        let error = anyhow::Error::new(std::io::Error::from_raw_os_error(libc::EIO)).context("Failed to write");
+        assert_eq!(explain(error).to_string(), "Failed to write");
+
+        // A file where a directory should be
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("file"), b"").unwrap();
+        let error = explain(create_dirs(&dir.path().join("file/sub")).unwrap_err());
+        assert!(error.to_string().starts_with("A component of the path is a file"));
+    }
+
+    #[test]
+    fn test_preflight_targets() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let existing = dir.path().join("existing.dat");
+        std::fs::write(&existing, b"data").unwrap();
+        let config: Config = toml::from_str(&format!(
+            "targets = [{{ path = \"{}\", file_size = 1048576 }}, {{ path = \"{}\" }}, {{ path = \"{}\" }}]\n\
+             [workload]\nread_percent = 100\nwrite_percent = 0\ncompletion_mode = \"RunUntilComplete\"\n",
+            dir.path().join("new/test.dat").display(),
+            existing.display(),
+            existing.join("under-a-file.dat").display(),
+        )).unwrap();
+
+        let report = PreflightReport::run(&config);
+        let checks: Vec<(&str, bool)> = report.checks.iter().map(|c| (c.check.as_str(), c.error.is_none())).collect();
+        // New target: probed (and removed) in the nearest existing directory;
+        // existing target: opened; a file in the way: not a directory
+        assert_eq!(checks, [("create", true), ("write", true), ("write", true), ("create", false)]);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+        assert!(report.checks[3].error.as_ref().unwrap().starts_with("A component of the path is a file"));
+
+        // Read-only: missing targets fail
+        let mut config = config;
+        config.runtime.read_only = true;
+        let failed: Vec<_> = PreflightReport::run(&config).failures().map(|c| c.path.clone()).collect();
+        assert_eq!(failed, [dir.path().join("new/test.dat"), existing.join("under-a-file.dat")]);
+    }
+}
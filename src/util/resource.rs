@@ -2,9 +2,12 @@
 //!
 //! This module provides CPU and memory utilization tracking for the IOPulse process.
 //! It reads from /proc/self/stat and /proc/self/status on Linux to get resource usage.
+//! Page fault counts come from getrusage per worker thread, scheduler times
+//! from /proc/thread-self/schedstat.
 
+use serde::{Deserialize, Serialize};
 use std::fs;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 /// Resource utilization snapshot
 #[derive(Debug, Clone, Copy)]
@@ -69,6 +72,16 @@ impl ResourceSnapshot {
         Some(num_cpus::get())
     }
     
+    /// Count the open file descriptors of this process
+    ///
+    /// Reads the entries of /proc/self/fd. Returns None if unavailable
+    /// (e.g., on non-Linux systems).
+    pub fn open_fd_count() -> Option<u64> {
+        let entries = fs::read_dir("/proc/self/fd").ok()?;
+        // read_dir holds its own fd while iterating, don't count it
+        Some((entries.count() as u64).saturating_sub(1))
+    }
+    
     /// Read CPU time from /proc/self/stat
     ///
     /// Returns (user_time_us, system_time_us) or None on error.
@@ -254,6 +267,110 @@ impl ResourceTracker {
     }
 }
 
+/// Page fault counters (minor = no IO needed, major = had to read from storage)
+///
+/// For mmap workloads these are the real cost of an access; the memcpy
+/// latency alone hides them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PageFaults {
+    pub minor: u64,
+    pub major: u64,
+}
+
+impl PageFaults {
+    /// Faults taken so far by the calling thread
+    ///
+    /// Returns None where per-thread rusage is unavailable (non-Linux).
+    pub fn current_thread() -> Option<Self> {
+        #[cfg(target_os = "linux")]
+        {
+            let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+            if unsafe { libc::getrusage(libc::RUSAGE_THREAD, &mut usage) } == 0 {
+                return Some(Self {
+                    minor: usage.ru_minflt as u64,
+                    major: usage.ru_majflt as u64,
+                });
+            }
+        }
+        None
+    }
+
+    /// Faults taken since an earlier reading
+    pub fn since(&self, earlier: &PageFaults) -> Self {
+        Self {
+            minor: self.minor.saturating_sub(earlier.minor),
+            major: self.major.saturating_sub(earlier.major),
+        }
+    }
+
+    /// Add another counter (e.g., another worker's faults)
+    pub fn add(&mut self, other: &PageFaults) {
+        self.minor += other.minor;
+        self.major += other.major;
+    }
+
+    /// Sum optional counters; None only if both are None
+    pub fn combine(a: Option<PageFaults>, b: Option<PageFaults>) -> Option<PageFaults> {
+        match (a, b) {
+            (Some(mut a), Some(b)) => {
+                a.add(&b);
+                Some(a)
+            }
+            (a, b) => a.or(b),
+        }
+    }
+}
+
+/// Scheduler times of a thread
+///
+/// `run_ns` is time on a CPU, `runqueue_ns` time spent runnable but waiting
+/// for a CPU. The rest of the wall-clock time the thread was blocked (IO,
+/// sleeps, locks).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SchedStat {
+    pub run_ns: u64,
+    pub runqueue_ns: u64,
+}
+
+impl SchedStat {
+    /// Times of the calling thread so far
+    ///
+    /// Reads /proc/thread-self/schedstat. Returns None if unavailable
+    /// (non-Linux, or a kernel built without CONFIG_SCHED_INFO).
+    pub fn current_thread() -> Option<Self> {
+        Self::parse(&fs::read_to_string("/proc/thread-self/schedstat").ok()?)
+    }
+
+    /// Parse a schedstat line: `<run ns> <runqueue ns> <timeslices>`
+    fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.split_whitespace().map(|f| f.parse::<u64>().ok());
+        Some(Self {
+            run_ns: fields.next()??,
+            runqueue_ns: fields.next()??,
+        })
+    }
+
+    /// Times since an earlier reading
+    pub fn since(&self, earlier: &SchedStat) -> Self {
+        Self {
+            run_ns: self.run_ns.saturating_sub(earlier.run_ns),
+            runqueue_ns: self.runqueue_ns.saturating_sub(earlier.runqueue_ns),
+        }
+    }
+}
+
+/// CPU time consumed by the calling thread (CLOCK_THREAD_CPUTIME_ID)
+///
+/// Returns zero if the clock is unavailable.
+#[inline]
+pub fn thread_cpu_time() -> Duration {
+    let mut ts = libc::timespec { tv_sec: 0, tv_nsec: 0 };
+    if unsafe { libc::clock_gettime(libc::CLOCK_THREAD_CPUTIME_ID, &mut ts) } != 0 {
+        return Duration::ZERO;
+    }
+    Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -271,6 +388,59 @@ mod tests {
         }
     }
     
+    #[test]
+    fn test_open_fd_count() {
+        // This test only works on Linux
+        if let Some(fds) = ResourceSnapshot::open_fd_count() {
+            // At least stdin/stdout/stderr are open
+            assert!(fds > 0);
+        }
+    }
+    
+    #[test]
+    fn test_page_faults_current_thread() {
+        // This test only works on Linux
+        if let Some(start) = PageFaults::current_thread() {
+            // Touch a fresh anonymous mapping to force minor faults
+            let len = 64 * 4096;
+            let ptr = unsafe {
+                libc::mmap(std::ptr::null_mut(), len, libc::PROT_READ | libc::PROT_WRITE,
+                           libc::MAP_PRIVATE | libc::MAP_ANONYMOUS, -1, 0)
+            };
+            assert_ne!(ptr, libc::MAP_FAILED);
+            for offset in (0..len).step_by(4096) {
+                unsafe { *(ptr as *mut u8).add(offset) = 1 };
+            }
+            let delta = PageFaults::current_thread().unwrap().since(&start);
+            unsafe { libc::munmap(ptr, len) };
+            assert!(delta.minor > 0);
+        }
+
+        let a = PageFaults { minor: 3, major: 1 };
+        assert_eq!(PageFaults::combine(Some(a), None), Some(a));
+        assert_eq!(PageFaults::combine(Some(a), Some(a)), Some(PageFaults { minor: 6, major: 2 }));
+        assert_eq!(PageFaults::combine(None, None), None);
+    }
+    
+    #[test]
+    fn test_sched_stat() {
+        assert_eq!(SchedStat::parse("1500 200 7\n"), Some(SchedStat { run_ns: 1500, runqueue_ns: 200 }));
+        assert_eq!(SchedStat::parse("1500"), None);
+
+        // This part only works on Linux
+        if let Some(start) = SchedStat::current_thread() {
+            let cpu_start = thread_cpu_time();
+            let mut sum = 0u64;
+            for i in 0..1_000_000u64 {
+                sum = std::hint::black_box(sum.wrapping_add(i));
+            }
+            let delta = SchedStat::current_thread().unwrap().since(&start);
+            assert!(delta.run_ns > 0);
+            assert!(thread_cpu_time() > cpu_start);
+            assert!(sum > 0);
+        }
+    }
+    
     #[test]
     fn test_cpu_percent() {
         // This test only works on Linux
@@ -0,0 +1,176 @@
+//! Filesystem space consumed by a test
+//!
+//! Bytes written and space allocated differ on copy-on-write filesystems
+//! (every overwrite allocates), with compression or dedup (less than
+//! written), and with preallocation or sparse files. `--space-usage` reads
+//! the free space of the targets' filesystems before and after the IO and
+//! reports the difference next to the bytes written.
+//!
+//! Each reading syncs the filesystem first, so delayed allocation and
+//! writeback have settled. Free space counts all free blocks, including those
+//! reserved for root, so reservations don't hide consumption. Other activity
+//! on the filesystem during the test is counted too.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Free space of one filesystem before and after the test
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FsSpace {
+    /// Filesystem ID (statvfs f_fsid)
+    pub fsid: u64,
+    /// First target path on the filesystem
+    pub path: String,
+    /// Size of the filesystem
+    pub total_bytes: u64,
+    /// Free bytes before the IO started
+    pub free_before: u64,
+    /// Free bytes after the IO finished
+    pub free_after: u64,
+}
+
+impl FsSpace {
+    /// Bytes allocated during the test (negative if space was freed)
+    pub fn consumed_bytes(&self) -> i64 {
+        self.free_before as i64 - self.free_after as i64
+    }
+}
+
+/// Space consumed on the targets' filesystems
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpaceUsage {
+    pub filesystems: Vec<FsSpace>,
+}
+
+impl SpaceUsage {
+    /// Bytes allocated on all filesystems during the test
+    pub fn consumed_bytes(&self) -> i64 {
+        self.filesystems.iter().map(FsSpace::consumed_bytes).sum()
+    }
+
+    /// Space consumed per byte written (None if nothing was written)
+    pub fn amplification(&self, bytes_written: u64) -> Option<f64> {
+        if bytes_written == 0 {
+            return None;
+        }
+        Some(self.consumed_bytes() as f64 / bytes_written as f64)
+    }
+
+    /// Merge another node's readings
+    ///
+    /// A filesystem seen by several nodes (shared storage) is counted once:
+    /// every node sees the consumption of all of them, so the reading with
+    /// the largest change is kept.
+    pub fn merge(&mut self, other: &SpaceUsage) {
+        for fs in &other.filesystems {
+            match self.filesystems.iter_mut().find(|mine| mine.fsid == fs.fsid) {
+                Some(mine) => {
+                    if fs.consumed_bytes().abs() > mine.consumed_bytes().abs() {
+                        *mine = fs.clone();
+                    }
+                }
+                None => self.filesystems.push(fs.clone()),
+            }
+        }
+    }
+}
+
+/// Free space of the targets' filesystems when the IO started
+#[derive(Debug, Clone, Default)]
+pub struct SpaceProbe {
+    before: Vec<FsSpace>,
+    paths: Vec<PathBuf>,
+}
+
+impl SpaceProbe {
+    /// Read the free space of the filesystems holding `paths`
+    ///
+    /// Paths that don't exist yet are measured at their nearest existing
+    /// ancestor; filesystems that can't be read are left out.
+    pub fn capture<'a>(paths: impl IntoIterator<Item = &'a Path>) -> Self {
+        let mut probe = Self::default();
+        for path in paths {
+            let Some(path) = existing_ancestor(path) else { continue };
+            let Some((fsid, total_bytes, free)) = fs_free(&path) else { continue };
+            if probe.before.iter().any(|fs| fs.fsid == fsid) {
+                continue;
+            }
+            probe.before.push(FsSpace {
+                fsid,
+                path: path.display().to_string(),
+                total_bytes,
+                free_before: free,
+                free_after: free,
+            });
+            probe.paths.push(path);
+        }
+        probe
+    }
+
+    /// Read the free space again now that the IO finished
+    pub fn finish(self) -> SpaceUsage {
+        let filesystems = self.before.into_iter().zip(self.paths)
+            .filter_map(|(fs, path)| {
+                let (_, _, free_after) = fs_free(&path)?;
+                Some(FsSpace { free_after, ..fs })
+            })
+            .collect();
+        SpaceUsage { filesystems }
+    }
+}
+
+fn existing_ancestor(path: &Path) -> Option<PathBuf> {
+    std::fs::canonicalize(path).ok()
+        .or_else(|| path.ancestors().skip(1).find_map(|dir| std::fs::canonicalize(dir).ok()))
+}
+
+/// Sync the filesystem holding `path`, then read (fsid, size, free bytes)
+fn fs_free(path: &Path) -> Option<(u64, u64, u64)> {
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::io::AsRawFd;
+
+    if let Ok(file) = std::fs::File::open(path) {
+        unsafe { libc::syncfs(file.as_raw_fd()) };
+    }
+    let c_path = std::ffi::CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut buf: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut buf) } != 0 {
+        return None;
+    }
+    let frsize = buf.f_frsize as u64;
+    Some((buf.f_fsid as u64, buf.f_blocks as u64 * frsize, buf.f_bfree as u64 * frsize))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fs(fsid: u64, free_before: u64, free_after: u64) -> FsSpace {
+        FsSpace { fsid, path: format!("/mnt/{}", fsid), total_bytes: 1 << 40, free_before, free_after }
+    }
+
+    #[test]
+    fn test_merge_counts_shared_filesystems_once() {
+        // Two nodes with a local filesystem each, both on shared storage (fsid 9)
+        let mut usage = SpaceUsage { filesystems: vec![fs(1, 1000, 600), fs(9, 10_000, 8_000)] };
+        usage.merge(&SpaceUsage { filesystems: vec![fs(2, 1000, 700), fs(9, 10_000, 7_900)] });
+
+        assert_eq!(usage.filesystems.len(), 3);
+        assert_eq!(usage.consumed_bytes(), 400 + 2_100 + 300);
+        assert_eq!(usage.amplification(1_400), Some(2.0));
+        assert_eq!(usage.amplification(0), None);
+
+        // Freed space counts against consumption
+        assert_eq!(fs(3, 500, 800).consumed_bytes(), -300);
+    }
+
+    #[test]
+    fn test_probe_missing_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let probe = SpaceProbe::capture([dir.path().join("not-yet-created").as_path(), dir.path()]);
+        let usage = probe.finish();
+        // Both paths are on the same filesystem
+        assert_eq!(usage.filesystems.len(), 1);
+        assert!(usage.filesystems[0].total_bytes > 0);
+    }
+}
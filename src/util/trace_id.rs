@@ -0,0 +1,53 @@
+//! Unit-of-work IDs
+//!
+//! Every run gets a UUID (random, or `--run-uuid` from an outer harness),
+//! shared by all phases and steps of the run. Nodes and workers get IDs
+//! derived from it:
+//!
+//! - **run**: `<uuid>`
+//! - **node**: `<uuid>.n<node>`, with the node's index in `--host-list`
+//! - **worker**: `<uuid>.n<node>.w<worker>`, with the worker's ID on that node
+//!
+//! The IDs appear in JSON and CSV results, alert payloads and worker log
+//! lines, so results collected on different systems can be joined without
+//! relying on timestamps and hostnames. A derived ID also names its run, so
+//! a worker's log line leads back to the run's results.
+
+/// Generate a run UUID
+pub fn new_run_uuid() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// Check a run UUID given by the user, returning it in canonical form
+pub fn parse_run_uuid(text: &str) -> anyhow::Result<String> {
+    uuid::Uuid::parse_str(text.trim())
+        .map(|uuid| uuid.hyphenated().to_string())
+        .map_err(|e| anyhow::anyhow!("Invalid run UUID '{}': {}", text, e))
+}
+
+/// ID of a node of the run
+pub fn node_uid(run_uuid: &str, node: usize) -> String {
+    format!("{}.n{}", run_uuid, node)
+}
+
+/// ID of a worker of a node
+pub fn worker_uid(node_uid: &str, worker: usize) -> String {
+    format!("{}.w{}", node_uid, worker)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ids() {
+        let run = new_run_uuid();
+        assert_eq!(parse_run_uuid(&run).unwrap(), run);
+        assert_ne!(new_run_uuid(), run);
+        assert_eq!(parse_run_uuid(" 6F9619FF8B86D011B42D00C04FC964FF").unwrap(), "6f9619ff-8b86-d011-b42d-00c04fc964ff");
+        assert!(parse_run_uuid("run-7").is_err());
+
+        let node = node_uid(&run, 2);
+        assert_eq!(worker_uid(&node, 5), format!("{}.n2.w5", run));
+    }
+}
@@ -5,7 +5,7 @@
 
 /// Verification pattern for data integrity checking
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum VerificationPattern {
+pub enum VerificationPattern<'a> {
     /// All zeros
     Zeros,
     /// All ones (0xFF)
@@ -14,6 +14,8 @@ pub enum VerificationPattern {
     Random(u64),
     /// Sequential bytes (0x00, 0x01, 0x02, ..., 0xFF, 0x00, ...)
     Sequential,
+    /// A payload repeated across the file (byte at file offset N is payload[N % len])
+    Repeat(&'a [u8]),
 }
 
 /// Verification result
@@ -54,6 +56,7 @@ pub fn verify_buffer(
         VerificationPattern::Ones => verify_ones(buffer),
         VerificationPattern::Random(seed) => verify_random(buffer, seed),
         VerificationPattern::Sequential => verify_sequential(buffer, offset),
+        VerificationPattern::Repeat(payload) => verify_repeat(buffer, payload, offset),
     }
 }
 
@@ -78,6 +81,9 @@ pub fn fill_buffer(buffer: &mut [u8], pattern: VerificationPattern, offset: u64)
         VerificationPattern::Sequential => {
             fill_sequential(buffer, offset);
         }
+        VerificationPattern::Repeat(payload) => {
+            fill_repeat(buffer, payload, offset);
+        }
     }
 }
 
@@ -153,6 +159,33 @@ fn fill_sequential(buffer: &mut [u8], file_offset: u64) {
     }
 }
 
+fn verify_repeat(buffer: &[u8], payload: &[u8], file_offset: u64) -> VerificationResult {
+    let len = payload.len() as u64;
+    for (i, &byte) in buffer.iter().enumerate() {
+        let expected = payload[((file_offset + i as u64) % len) as usize];
+        if byte != expected {
+            return VerificationResult::Failure {
+                offset: i,
+                expected,
+                actual: byte,
+            };
+        }
+    }
+    VerificationResult::Success
+}
+
+fn fill_repeat(buffer: &mut [u8], payload: &[u8], file_offset: u64) {
+    // Copy whole runs of the payload, starting mid-payload at the file offset
+    let mut start = (file_offset % payload.len() as u64) as usize;
+    let mut filled = 0;
+    while filled < buffer.len() {
+        let n = (payload.len() - start).min(buffer.len() - filled);
+        buffer[filled..filled + n].copy_from_slice(&payload[start..start + n]);
+        filled += n;
+        start = 0;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -232,4 +265,22 @@ mod tests {
         assert_eq!(buffer[56], 0);
         assert_eq!(buffer[57], 1);
     }
+
+    #[test]
+    fn test_repeat_follows_file_offset() {
+        let payload = [0xAA, 0xAA, 0x55, 0x55, 0x55];
+        let mut buffer = vec![0u8; 12];
+        fill_buffer(&mut buffer, VerificationPattern::Repeat(&payload), 3);
+        assert_eq!(buffer[..4], [0x55, 0x55, 0xAA, 0xAA]);
+
+        // Any window of the file verifies against the same payload
+        assert_eq!(
+            verify_buffer(&buffer[2..], VerificationPattern::Repeat(&payload), 5),
+            VerificationResult::Success
+        );
+        assert!(matches!(
+            verify_buffer(&buffer, VerificationPattern::Repeat(&payload), 0),
+            VerificationResult::Failure { offset: 0, .. }
+        ));
+    }
 }
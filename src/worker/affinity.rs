@@ -11,6 +11,14 @@
 //! CPU affinity is supported on Linux via `sched_setaffinity`. NUMA binding is
 //! supported on Linux systems with NUMA hardware via `set_mempolicy`.
 //!
+//! # Housekeeping Cores
+//!
+//! `--housekeeping-cores` keeps the network and runtime threads of a node
+//! service or coordinator off the cores that run IO workers.
+//! `reserve_housekeeping_cores` moves the calling thread (and so the threads
+//! it spawns) to the housekeeping cores and records the rest for workers,
+//! which move there when they start unless `--cpu-cores` places them.
+//!
 //! # Example
 //!
 //! ```no_run
@@ -23,6 +31,12 @@
 
 use crate::Result;
 use anyhow::Context;
+use std::sync::OnceLock;
+
+/// Cores left to IO workers once housekeeping cores are reserved
+static WORKER_CORES: OnceLock<Vec<usize>> = OnceLock::new();
+/// Cores reserved for housekeeping threads
+static HOUSEKEEPING_CORES: OnceLock<Vec<usize>> = OnceLock::new();
 
 /// Set CPU affinity for the current thread
 ///
@@ -94,6 +108,80 @@ pub fn set_cpu_affinity(_cores: &[usize]) -> Result<()> {
     anyhow::bail!("CPU affinity is only supported on Linux")
 }
 
+/// CPU cores the current thread may run on
+#[cfg(target_os = "linux")]
+pub fn cpu_affinity() -> Result<Vec<usize>> {
+    use libc::{cpu_set_t, CPU_ISSET, sched_getaffinity};
+    use std::mem;
+
+    unsafe {
+        let mut cpu_set: cpu_set_t = mem::zeroed();
+        if sched_getaffinity(0, mem::size_of::<cpu_set_t>(), &mut cpu_set) != 0 {
+            return Err(std::io::Error::last_os_error()).context("Failed to get CPU affinity");
+        }
+        Ok((0..1024).filter(|&core| CPU_ISSET(core, &cpu_set)).collect())
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn cpu_affinity() -> Result<Vec<usize>> {
+    anyhow::bail!("CPU affinity is only supported on Linux")
+}
+
+/// Reserve cores for housekeeping threads (`--housekeeping-cores`)
+///
+/// Binds the calling thread to `cores`; threads it spawns afterwards (e.g.
+/// the tokio runtime's) start there too. The process's other cores are
+/// recorded for IO workers (see `worker_cores`). Returns the worker cores.
+///
+/// # Errors
+///
+/// Returns an error if a core is outside the process's affinity, no core
+/// would be left for workers, or housekeeping cores were already reserved.
+pub fn reserve_housekeeping_cores(cores: &[usize]) -> Result<Vec<usize>> {
+    let available = cpu_affinity()?;
+    if let Some(core) = cores.iter().find(|core| !available.contains(core)) {
+        anyhow::bail!("Housekeeping core {} is not available to this process (cores {:?})", core, available);
+    }
+    let workers: Vec<usize> = available.into_iter().filter(|core| !cores.contains(core)).collect();
+    if workers.is_empty() {
+        anyhow::bail!("Housekeeping cores {:?} leave no cores for IO workers", cores);
+    }
+    set_cpu_affinity(cores).context("Failed to bind to housekeeping cores")?;
+    if HOUSEKEEPING_CORES.set(cores.to_vec()).is_err() || WORKER_CORES.set(workers.clone()).is_err() {
+        anyhow::bail!("Housekeeping cores are already reserved");
+    }
+    Ok(workers)
+}
+
+/// Cores for IO workers, when housekeeping cores are reserved
+pub fn worker_cores() -> Option<&'static [usize]> {
+    WORKER_CORES.get().map(Vec::as_slice)
+}
+
+/// Cores reserved for housekeeping threads, if any
+pub fn housekeeping_cores() -> Option<&'static [usize]> {
+    HOUSEKEEPING_CORES.get().map(Vec::as_slice)
+}
+
+/// A core for each of `workers` workers that own one (`--thread-per-core`)
+///
+/// Cores are taken in order from `cpu_cores` (`--cpu-cores`), else from the
+/// worker cores left by `--housekeeping-cores`, else from the cores the
+/// process may run on. With more workers than cores, they are handed out
+/// again from the first.
+pub fn owned_cores(cpu_cores: Option<&str>, workers: usize) -> Result<Vec<usize>> {
+    let pool = match (cpu_cores, worker_cores()) {
+        (Some(spec), _) => parse_cpu_list(spec).context("Failed to parse CPU core list")?,
+        (None, Some(cores)) => cores.to_vec(),
+        (None, None) => cpu_affinity()?,
+    };
+    if pool.is_empty() {
+        anyhow::bail!("No cores for workers");
+    }
+    Ok(pool.iter().copied().cycle().take(workers).collect())
+}
+
 /// Set NUMA memory policy for the current thread
 ///
 /// Binds memory allocations for the current thread to the specified NUMA nodes.
@@ -319,6 +407,25 @@ pub fn warn_if_oversubscribed(thread_count: usize) -> bool {
 mod tests {
     use super::*;
 
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_cpu_affinity() {
+        let cores = cpu_affinity().unwrap();
+        assert!(!cores.is_empty());
+        // Binding to the current set changes nothing
+        set_cpu_affinity(&cores).unwrap();
+        assert_eq!(cpu_affinity().unwrap(), cores);
+    }
+
+    #[test]
+    fn test_owned_cores() {
+        assert_eq!(owned_cores(Some("4-6"), 2).unwrap(), [4, 5]);
+        // More workers than cores: cores are shared from the first
+        assert_eq!(owned_cores(Some("4-6"), 5).unwrap(), [4, 5, 6, 4, 5]);
+        #[cfg(target_os = "linux")]
+        assert_eq!(owned_cores(None, 1).unwrap()[0], cpu_affinity().unwrap()[0]);
+    }
+
     #[test]
     fn test_parse_cpu_list_single() {
         let cores = parse_cpu_list("0").unwrap();
@@ -0,0 +1,221 @@
+//! Runtime control of a running test
+//!
+//! Commands from the control socket or the coordinator API reach each node
+//! service as CONTROL messages and are applied to the node's `RunControl`,
+//! which its workers check before every submission:
+//!
+//! - **Pause/resume**: a paused worker submits nothing new; operations in
+//!   flight complete normally
+//! - **Rate limit**: IOPS and/or bytes per second, spread evenly over the
+//!   node's workers (the first ones taking the remainder) and paced by each
+//!   worker's `Pacer`
+//! - **Read percentage**: replaces the workload's read/write mix
+//!
+//! It also carries the node's share of `--prep-rate-limit`, shared by the
+//! workers refilling their targets as they open them.
+//!
+//! The node service also holds its workers with `hold_start` while they
+//! initialize, so IO begins at START rather than when the configuration
+//! arrived.
+//!
+//! # Example
+//!
+//! ```
+//! use iopulse::distributed::protocol::ControlMessage;
+//! use iopulse::worker::control::RunControl;
+//!
+//! let control = RunControl::new(4, None, None);
+//! control.apply(&ControlMessage::Rate { iops: 10_001, bytes_per_sec: 0 });
+//! assert_eq!(control.limits(0), Some((2_501, 0)));
+//! assert_eq!(control.limits(3), Some((2_500, 0)));
+//!
+//! control.apply(&ControlMessage::Pause);
+//! assert!(control.is_paused());
+//! ```
+
+use crate::distributed::protocol::ControlMessage;
+use crate::target::prep_limit::PrepLimit;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, Ordering};
+use std::time::{Duration, Instant};
+
+/// How often a paused worker checks whether it was resumed
+pub const PAUSE_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Longest a worker that fell behind its rate may catch up in a burst
+const MAX_CATCH_UP: Duration = Duration::from_millis(10);
+
+/// Read percentage value meaning "use the workload's mix"
+const NO_OVERRIDE: u8 = u8::MAX;
+
+/// Control state shared by the workers of one node
+#[derive(Debug)]
+pub struct RunControl {
+    workers: usize,
+    started: AtomicBool,
+    paused: AtomicBool,
+    /// Node IOPS limit (0 = unlimited)
+    iops: AtomicU64,
+    /// Node throughput limit in bytes/sec (0 = unlimited)
+    bytes_per_sec: AtomicU64,
+    read_percent: AtomicU8,
+    /// The node's share of the preparation rate limit
+    prep_limit: Option<PrepLimit>,
+}
+
+impl RunControl {
+    /// Control for `workers` workers, starting at the configured per-worker limits
+    pub fn new(workers: usize, iops: Option<u64>, bytes_per_sec: Option<u64>) -> Self {
+        let workers = workers.max(1);
+        let node_limit = |limit: Option<u64>| limit.unwrap_or(0).saturating_mul(workers as u64);
+        Self {
+            workers,
+            started: AtomicBool::new(true),
+            paused: AtomicBool::new(false),
+            iops: AtomicU64::new(node_limit(iops)),
+            bytes_per_sec: AtomicU64::new(node_limit(bytes_per_sec)),
+            read_percent: AtomicU8::new(NO_OVERRIDE),
+            prep_limit: None,
+        }
+    }
+
+    /// Cap the workers' refills at `limit` between them
+    pub fn with_prep_limit(mut self, limit: Option<PrepLimit>) -> Self {
+        self.prep_limit = limit;
+        self
+    }
+
+    /// Preparation rate limit shared by the node's workers
+    pub fn prep_limit(&self) -> Option<&PrepLimit> {
+        self.prep_limit.as_ref()
+    }
+
+    /// Apply a command; rates are for the whole node and split across its workers
+    pub fn apply(&self, msg: &ControlMessage) {
+        match *msg {
+            ControlMessage::Pause => self.paused.store(true, Ordering::Relaxed),
+            ControlMessage::Resume => self.paused.store(false, Ordering::Relaxed),
+            ControlMessage::Rate { iops, bytes_per_sec } => {
+                self.iops.store(iops, Ordering::Relaxed);
+                self.bytes_per_sec.store(bytes_per_sec, Ordering::Relaxed);
+            }
+            ControlMessage::ReadPercent(percent) => self.read_percent.store(percent.min(100), Ordering::Relaxed),
+        }
+    }
+
+    /// Hold workers before their first submission until `start`
+    pub fn hold_start(&self) {
+        self.started.store(false, Ordering::Relaxed);
+    }
+    
+    /// Release workers held by `hold_start`
+    pub fn start(&self) {
+        self.started.store(true, Ordering::Relaxed);
+    }
+    
+    /// Whether workers may begin issuing IO
+    pub fn is_started(&self) -> bool {
+        self.started.load(Ordering::Relaxed)
+    }
+    
+    /// Whether workers are paused
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    /// (IOPS, bytes/sec) limits of the node's `worker`th worker, 0 meaning unlimited
+    ///
+    /// None when the node's rate is too low to leave this worker a share:
+    /// it submits nothing until the rate is raised.
+    pub fn limits(&self, worker: usize) -> Option<(u64, u64)> {
+        let limit = |total: u64| match total {
+            0 => Some(0),
+            total => Some(share(total, self.workers, worker)).filter(|&share| share > 0),
+        };
+        Some((limit(self.iops.load(Ordering::Relaxed))?, limit(self.bytes_per_sec.load(Ordering::Relaxed))?))
+    }
+
+    /// Read percentage replacing the workload's, if one was set
+    pub fn read_percent(&self) -> Option<u8> {
+        Some(self.read_percent.load(Ordering::Relaxed)).filter(|&p| p != NO_OVERRIDE)
+    }
+}
+
+/// Share of `total` for part `index` of `parts`, the first parts taking the remainder
+pub fn share(total: u64, parts: usize, index: usize) -> u64 {
+    let parts = parts.max(1) as u64;
+    total / parts + u64::from((index as u64) < total % parts)
+}
+
+/// Spaces one worker's submissions to its rate limit
+#[derive(Debug, Default)]
+pub struct Pacer {
+    next: Option<Instant>,
+}
+
+impl Pacer {
+    /// Time to wait before submitting `bytes`, or None to submit now (taking the slot)
+    pub fn wait(&mut self, iops: u64, bytes_per_sec: u64, bytes: u64, now: Instant) -> Option<Duration> {
+        if iops == 0 && bytes_per_sec == 0 {
+            self.next = None;
+            return None;
+        }
+
+        let per_op = Duration::from_nanos(1_000_000_000u64.checked_div(iops).unwrap_or(0));
+        let per_byte = if bytes_per_sec > 0 {
+            Duration::from_nanos((bytes as u128 * 1_000_000_000 / bytes_per_sec as u128) as u64)
+        } else {
+            Duration::ZERO
+        };
+
+        let next = self.next.unwrap_or(now);
+        if next > now {
+            return Some(next - now);
+        }
+        // Make up for sleep overshoot, but don't burst after an idle period
+        let base = next.max(now.checked_sub(MAX_CATCH_UP).unwrap_or(now));
+        self.next = Some(base + per_op.max(per_byte));
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pacer_spaces_submissions() {
+        let mut pacer = Pacer::default();
+        let start = Instant::now();
+
+        // 1000 IOPS: one submission per millisecond
+        assert_eq!(pacer.wait(1000, 0, 4096, start), None);
+        assert_eq!(pacer.wait(1000, 0, 4096, start), Some(Duration::from_millis(1)));
+        assert_eq!(pacer.wait(1000, 0, 4096, start + Duration::from_millis(1)), None);
+
+        // 4 MB/s at 4 KiB per op is the tighter limit
+        let mut pacer = Pacer::default();
+        assert_eq!(pacer.wait(1_000_000, 4_096_000, 4096, start), None);
+        assert_eq!(pacer.wait(1_000_000, 4_096_000, 4096, start), Some(Duration::from_millis(1)));
+
+        // Unlimited
+        assert_eq!(pacer.wait(0, 0, 4096, start), None);
+        assert_eq!(pacer.wait(0, 0, 4096, start), None);
+    }
+
+    #[test]
+    fn test_read_percent_override() {
+        let control = RunControl::new(2, Some(100), None);
+        assert_eq!(control.limits(1), Some((100, 0)));
+        assert_eq!(control.read_percent(), None);
+        control.apply(&ControlMessage::ReadPercent(70));
+        assert_eq!(control.read_percent(), Some(70));
+        control.apply(&ControlMessage::Rate { iops: 0, bytes_per_sec: 0 });
+        assert_eq!(control.limits(1), Some((0, 0)));
+        
+        assert!(control.is_started());
+        control.hold_start();
+        assert!(!control.is_started());
+        control.start();
+        assert!(control.is_started());
+    }
+}
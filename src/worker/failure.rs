@@ -0,0 +1,132 @@
+//! Worker failure capture
+//!
+//! Worker threads run behind [`run_guarded`], which turns both returned errors
+//! and panics into a [`WorkerFailure`] instead of letting a panic tear down the
+//! thread with only a line on the node's stderr. Panics keep the backtrace of
+//! the panic site (captured by a process-wide panic hook, installed once), so
+//! the coordinator can report where the worker died.
+//!
+//! # Example
+//!
+//! ```
+//! use iopulse::worker::failure::{run_guarded, FailureKind};
+//!
+//! let failure = run_guarded(3, || -> anyhow::Result<()> { anyhow::bail!("disk full") })
+//!     .unwrap_err();
+//! assert_eq!(failure.worker_id, 3);
+//! assert_eq!(failure.kind, FailureKind::Error);
+//! ```
+
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::cell::RefCell;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+
+thread_local! {
+    /// Backtrace of the last panic on this thread (set by the panic hook)
+    static PANIC_BACKTRACE: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+static INSTALL_HOOK: Once = Once::new();
+
+/// How a worker failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureKind {
+    /// The worker returned an error
+    Error,
+    /// The worker thread panicked
+    Panic,
+}
+
+/// A worker that stopped with an error or panic
+#[derive(Debug, Clone)]
+pub struct WorkerFailure {
+    /// Global worker ID
+    pub worker_id: usize,
+    /// Error or panic
+    pub kind: FailureKind,
+    /// Error chain or panic message
+    pub message: String,
+    /// Backtrace of the panic site, or of the error if one was captured
+    pub backtrace: Option<String>,
+}
+
+impl fmt::Display for WorkerFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            FailureKind::Error => write!(f, "Worker {} failed: {}", self.worker_id, self.message),
+            FailureKind::Panic => write!(f, "Worker {} panicked: {}", self.worker_id, self.message),
+        }
+    }
+}
+
+/// Run a worker body, converting errors and panics into a [`WorkerFailure`]
+pub fn run_guarded<T, F>(worker_id: usize, body: F) -> Result<T, WorkerFailure>
+where
+    F: FnOnce() -> anyhow::Result<T>,
+{
+    install_panic_hook();
+
+    match panic::catch_unwind(AssertUnwindSafe(body)) {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(e)) => {
+            // Errors only capture a backtrace with RUST_BACKTRACE / RUST_LIB_BACKTRACE set
+            let backtrace = e.backtrace();
+            let backtrace = (backtrace.status() == BacktraceStatus::Captured).then(|| backtrace.to_string());
+            Err(WorkerFailure {
+                worker_id,
+                kind: FailureKind::Error,
+                message: format!("{:#}", crate::util::preflight::explain(e)),
+                backtrace,
+            })
+        }
+        Err(payload) => {
+            let message = payload.downcast_ref::<&str>().map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic payload".to_string());
+            Err(WorkerFailure {
+                worker_id,
+                kind: FailureKind::Panic,
+                message,
+                backtrace: PANIC_BACKTRACE.with(|bt| bt.borrow_mut().take()),
+            })
+        }
+    }
+}
+
+/// Record the backtrace of every panic for the panicking thread, then defer
+/// to the previous hook (which prints the usual panic message)
+fn install_panic_hook() {
+    INSTALL_HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let backtrace = Backtrace::force_capture().to_string();
+            PANIC_BACKTRACE.with(|bt| *bt.borrow_mut() = Some(backtrace));
+            previous(info);
+        }));
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_guarded() {
+        assert_eq!(run_guarded(0, || Ok(42)).unwrap(), 42);
+
+        let failure = run_guarded(1, || -> anyhow::Result<()> {
+            Err(anyhow::anyhow!("read failed").context("IO operation failed"))
+        }).unwrap_err();
+        assert_eq!(failure.kind, FailureKind::Error);
+        assert_eq!(failure.message, "IO operation failed: read failed");
+        assert_eq!(failure.to_string(), "Worker 1 failed: IO operation failed: read failed");
+
+        let failure = run_guarded(2, || -> anyhow::Result<()> { panic!("buffer index {} out of range", 7) })
+            .unwrap_err();
+        assert_eq!(failure.kind, FailureKind::Panic);
+        assert_eq!(failure.message, "buffer index 7 out of range");
+        assert!(failure.backtrace.is_some());
+    }
+}
@@ -0,0 +1,82 @@
+//! Busy-file detection for shared file lists
+//!
+//! In SHARED file-list mode the workers of a node pick files at random from
+//! the same list. `FileClaims` records which files are currently in use: a
+//! worker claims the file it picked and keeps the claim until its next pick
+//! (or until it exits). A pick that finds the file already claimed is a
+//! collision, handled according to the `--busy-file` policy.
+//!
+//! Claims are per node. Workers on different nodes, or other processes, are
+//! not seen here.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// In-use flags for the files of a shared file list
+#[derive(Debug)]
+pub struct FileClaims {
+    in_use: Vec<AtomicBool>,
+}
+
+impl FileClaims {
+    /// Create claims for `num_files` files, all free
+    pub fn new(num_files: usize) -> Self {
+        Self {
+            in_use: (0..num_files).map(|_| AtomicBool::new(false)).collect(),
+        }
+    }
+
+    /// Claim a file; returns None if another worker holds it
+    pub fn try_claim(self: &Arc<Self>, index: usize) -> Option<FileClaim> {
+        self.in_use[index]
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .ok()
+            .map(|_| FileClaim { claims: Arc::clone(self), index })
+    }
+
+    /// Number of files
+    pub fn len(&self) -> usize {
+        self.in_use.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.in_use.is_empty()
+    }
+}
+
+/// A claimed file, released when dropped
+#[derive(Debug)]
+pub struct FileClaim {
+    claims: Arc<FileClaims>,
+    index: usize,
+}
+
+impl FileClaim {
+    /// Index of the claimed file in the file list
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl Drop for FileClaim {
+    fn drop(&mut self) {
+        self.claims.in_use[self.index].store(false, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_claim_and_release() {
+        let claims = Arc::new(FileClaims::new(2));
+        let first = claims.try_claim(0).expect("file 0 is free");
+        assert_eq!(first.index(), 0);
+        assert!(claims.try_claim(0).is_none());
+        assert!(claims.try_claim(1).is_some());
+
+        drop(first);
+        assert!(claims.try_claim(0).is_some());
+    }
+}
@@ -0,0 +1,80 @@
+//! In-flight operation slots
+//!
+//! Every submitted operation owns a buffer of the worker's pool, and the
+//! buffer index travels through the engine as the operation's `user_data`.
+//! Indexing a slot array by it matches each completion to its operation
+//! without hashing or searching, and without moving other entries, which
+//! keeps the cost per op flat at queue depths of 256 and more.
+
+/// Operations in flight, indexed by buffer index
+#[derive(Debug)]
+pub struct InFlightSlots<T> {
+    slots: Vec<Option<T>>,
+    len: usize,
+}
+
+impl<T> InFlightSlots<T> {
+    /// Slots for `capacity` buffers (more are added when a higher index is used)
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            slots: std::iter::repeat_with(|| None).take(capacity).collect(),
+            len: 0,
+        }
+    }
+
+    /// Track `op` under buffer index `slot`, returning an operation already there
+    #[inline]
+    pub fn insert(&mut self, slot: usize, op: T) -> Option<T> {
+        if slot >= self.slots.len() {
+            self.slots.resize_with(slot + 1, || None);
+        }
+        let previous = self.slots[slot].replace(op);
+        if previous.is_none() {
+            self.len += 1;
+        }
+        previous
+    }
+
+    /// Take the operation under buffer index `slot`
+    #[inline]
+    pub fn remove(&mut self, slot: usize) -> Option<T> {
+        let op = self.slots.get_mut(slot)?.take();
+        if op.is_some() {
+            self.len -= 1;
+        }
+        op
+    }
+
+    /// Operations in flight
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut slots = InFlightSlots::with_capacity(2);
+        assert_eq!(slots.insert(1, "a"), None);
+        // Indexes past the capacity grow the slots
+        assert_eq!(slots.insert(5, "b"), None);
+        assert_eq!(slots.len(), 2);
+
+        assert_eq!(slots.remove(1), Some("a"));
+        assert_eq!(slots.remove(1), None);
+        assert_eq!(slots.remove(99), None);
+        assert_eq!(slots.insert(5, "c"), Some("b"));
+        assert_eq!(slots.len(), 1);
+        assert_eq!(slots.remove(5), Some("c"));
+        assert!(slots.is_empty());
+    }
+}
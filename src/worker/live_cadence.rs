@@ -0,0 +1,222 @@
+//! Adaptive live statistics cadence
+//!
+//! Workers publish a statistics snapshot for the live display and heartbeats
+//! every N operations. Each snapshot clones the latency histograms, so its
+//! cost is fixed while the time between snapshots shrinks with the IO rate:
+//! N=1 is free at 10K IOPS but dominates at 1M IOPS. Guessing N from the
+//! engine and O_DIRECT misjudges fast NVMe with O_DIRECT and slow buffered IO.
+//!
+//! `LiveCadence` times every snapshot and the IO work between snapshots, and
+//! sets N so that snapshot time stays within a budget (by default 0.1% of the
+//! worker's time):
+//!
+//! ```text
+//! N = snapshot cost / (budget × time per operation)
+//! ```
+//!
+//! N starts at 1, grows at most 16× and shrinks at most 4× per snapshot, so a
+//! single outlier doesn't swing the cadence. The snapshot cost is smoothed
+//! over recent snapshots.
+//!
+//! # Example
+//!
+//! ```
+//! use iopulse::worker::live_cadence::LiveCadence;
+//! use std::time::{Duration, Instant};
+//!
+//! let start = Instant::now();
+//! let mut cadence = LiveCadence::new(0.1, start);
+//! assert!(cadence.tick());
+//!
+//! // 1 us per operation, 10 us per snapshot: 0.1% needs 10,000 ops between snapshots
+//! cadence.record(Duration::from_micros(10), start + Duration::from_micros(11));
+//! assert_eq!(cadence.interval(), 16);
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Upper bound on operations between snapshots
+const MAX_INTERVAL: u64 = 1 << 20;
+/// Largest growth of the interval per snapshot
+const MAX_GROWTH: u64 = 16;
+/// Largest shrink of the interval per snapshot
+const MAX_SHRINK: u64 = 4;
+/// Weight of the newest snapshot in the smoothed cost
+const COST_SMOOTHING: f64 = 0.25;
+
+/// Chooses how many operations pass between live snapshots
+#[derive(Debug)]
+pub struct LiveCadence {
+    /// Snapshot time allowed, as a fraction of worker time
+    budget: f64,
+    /// Operations between snapshots
+    interval: u64,
+    /// Operations since the last snapshot
+    ops: u64,
+    /// End of the last snapshot
+    last_update: Instant,
+    /// Smoothed snapshot cost (nanoseconds)
+    cost_ns: f64,
+    start: Instant,
+    stats: LiveCadenceStats,
+}
+
+impl LiveCadence {
+    /// Start at one operation per snapshot with a budget of `budget_percent`
+    pub fn new(budget_percent: f64, now: Instant) -> Self {
+        Self {
+            budget: budget_percent / 100.0,
+            interval: 1,
+            ops: 0,
+            last_update: now,
+            cost_ns: 0.0,
+            start: now,
+            stats: LiveCadenceStats {
+                budget_percent,
+                min_interval: u64::MAX,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Count an operation; true when a snapshot is due
+    #[inline]
+    pub fn tick(&mut self) -> bool {
+        self.ops += 1;
+        self.ops >= self.interval
+    }
+
+    /// Record a snapshot that took `cost` and ended at `now`, and retune the interval
+    pub fn record(&mut self, cost: Duration, now: Instant) {
+        let cost_ns = cost.as_nanos() as f64;
+        self.cost_ns = if self.stats.updates == 0 {
+            cost_ns
+        } else {
+            self.cost_ns + COST_SMOOTHING * (cost_ns - self.cost_ns)
+        };
+        self.stats.updates += 1;
+        self.stats.snapshot_ns += cost.as_nanos() as u64;
+        self.stats.min_interval = self.stats.min_interval.min(self.interval);
+        self.stats.max_interval = self.stats.max_interval.max(self.interval);
+
+        // IO time per operation since the previous snapshot
+        let work_ns = now.saturating_duration_since(self.last_update).as_nanos() as f64 - cost_ns;
+        let per_op_ns = (work_ns / self.ops.max(1) as f64).max(1.0);
+        let target = (self.cost_ns / (self.budget * per_op_ns)).ceil() as u64;
+        self.interval = target
+            .clamp((self.interval / MAX_SHRINK).max(1), self.interval.saturating_mul(MAX_GROWTH))
+            .clamp(1, MAX_INTERVAL);
+
+        self.ops = 0;
+        self.last_update = now;
+    }
+
+    /// Operations between snapshots
+    pub fn interval(&self) -> u64 {
+        self.interval
+    }
+
+    /// Cadence statistics of the test that ended at `now`
+    pub fn finish(&self, now: Instant) -> LiveCadenceStats {
+        let mut stats = self.stats.clone();
+        stats.elapsed_ns = now.saturating_duration_since(self.start).as_nanos() as u64;
+        stats.final_interval = self.interval;
+        if stats.updates == 0 {
+            stats.min_interval = self.interval;
+            stats.max_interval = self.interval;
+        }
+        stats
+    }
+}
+
+/// Live snapshot cadence and its measured cost
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LiveCadenceStats {
+    /// Configured overhead budget (percent of worker time)
+    pub budget_percent: f64,
+    /// Snapshots taken
+    pub updates: u64,
+    /// Time spent taking snapshots (nanoseconds)
+    pub snapshot_ns: u64,
+    /// Worker time the snapshots were taken in (nanoseconds)
+    pub elapsed_ns: u64,
+    /// Fewest operations between snapshots
+    pub min_interval: u64,
+    /// Most operations between snapshots
+    pub max_interval: u64,
+    /// Operations between snapshots at the end of the test (largest across workers)
+    pub final_interval: u64,
+}
+
+impl LiveCadenceStats {
+    /// Merge another worker's cadence
+    pub fn merge(&mut self, other: &LiveCadenceStats) {
+        if other.updates == 0 && other.elapsed_ns == 0 {
+            return;
+        }
+        if self.updates == 0 && self.elapsed_ns == 0 {
+            *self = other.clone();
+            return;
+        }
+        self.budget_percent = self.budget_percent.max(other.budget_percent);
+        self.updates += other.updates;
+        self.snapshot_ns += other.snapshot_ns;
+        self.elapsed_ns += other.elapsed_ns;
+        self.min_interval = self.min_interval.min(other.min_interval);
+        self.max_interval = self.max_interval.max(other.max_interval);
+        self.final_interval = self.final_interval.max(other.final_interval);
+    }
+
+    /// Mean time per snapshot
+    pub fn mean_snapshot(&self) -> Duration {
+        Duration::from_nanos(self.snapshot_ns / self.updates.max(1))
+    }
+
+    /// Snapshot time as a percentage of worker time
+    pub fn overhead_percent(&self) -> f64 {
+        self.snapshot_ns as f64 / self.elapsed_ns.max(1) as f64 * 100.0
+    }
+
+    /// Nothing was recorded
+    pub fn is_empty(&self) -> bool {
+        self.updates == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interval_tracks_budget() {
+        let mut now = Instant::now();
+        let mut cadence = LiveCadence::new(0.1, now);
+        // 1 us per operation, 10 us per snapshot: settles at 10,000 ops
+        for _ in 0..10 {
+            let ops = cadence.interval();
+            for _ in 0..ops {
+                cadence.tick();
+            }
+            now += Duration::from_micros(ops + 10);
+            cadence.record(Duration::from_micros(10), now);
+        }
+        assert_eq!(cadence.interval(), 10_000);
+
+        // IO slows down 100x: the interval shrinks, at most 4x per snapshot
+        for expected in [2500, 625, 156, 100, 100] {
+            let ops = cadence.interval();
+            for _ in 0..ops {
+                cadence.tick();
+            }
+            now += Duration::from_micros(ops * 100 + 10);
+            cadence.record(Duration::from_micros(10), now);
+            assert_eq!(cadence.interval(), expected);
+        }
+
+        let stats = cadence.finish(now);
+        assert_eq!(stats.updates, 15);
+        assert_eq!((stats.min_interval, stats.max_interval, stats.final_interval), (1, 10_000, 100));
+        assert!(stats.overhead_percent() < 0.1);
+    }
+}
@@ -30,7 +30,7 @@
 //! #       read_distribution: vec![],
 //! #       write_distribution: vec![],
 //! #       queue_depth: 32,
-//! #       completion_mode: CompletionMode::Duration { seconds: 10 },
+//! #       completion_mode: CompletionMode::Duration { seconds: 10, millis: 0 },
 //! #       distribution: iopulse::config::workload::DistributionType::Uniform,
 //! #       think_time: None,
 //! #       engine: iopulse::config::workload::EngineType::Sync,
@@ -53,26 +53,46 @@
 
 pub mod executor;
 pub mod affinity;
+pub mod qd_ramp;
+pub mod queue_limit;
+pub mod live_cadence;
+pub mod failure;
+pub mod offset_dump;
+pub mod write_journal;
+pub mod write_order;
+pub mod file_claims;
+pub mod control;
+pub mod rw_split;
+pub mod in_flight;
+pub mod think_schedule;
+pub mod snapshot_queue;
 
-use crate::config::{Config, WorkloadConfig, TargetType, workload::*};
+use crate::config::{Config, WorkloadConfig, TargetType, TimerSource, DrainPolicy, workload::*};
 use crate::distribution::{
     Distribution,
     uniform::UniformDistribution,
     zipf::ZipfDistribution,
     pareto::ParetoDistribution,
     gaussian::GaussianDistribution,
+    composite::{CompositeDistribution, CompositePart},
 };
 use crate::engine::{IOEngine, IOOperation, OperationType, EngineConfig};
 use crate::stats::WorkerStats;
+use crate::stats::heatmap::HeatmapSeries;
+use crate::stats::cpu_time::{CallClass, CpuTracker};
 use crate::target::{Target, FileLockMode as TargetFileLockMode};
+use crate::target::dio::DioAlignment;
 use crate::util::buffer::BufferPool;
-use crate::util::fast_time::FastInstant;
+use crate::util::fast_time::{self, FastInstant, TimerCheck};
+use crate::util::resource::PageFaults;
+use crate::util::striping::StripeLayout;
 use crate::Result;
 use anyhow::Context;
 use rand::Rng;
 use rand::SeedableRng;
 use rand_xoshiro::Xoshiro256PlusPlus;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use in_flight::InFlightSlots;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
@@ -90,8 +110,71 @@ struct InFlightOp {
     op_type: OperationType,
     /// File offset for the operation
     offset: u64,
+    /// Bytes requested
+    length: usize,
     /// Start time for latency calculation
     start_time: FastInstant,
+    /// Append-grow write past EOF (false for overwrites and reads)
+    extend: bool,
+    /// Submission index of the operation within this worker (from 0)
+    op_index: u64,
+    /// Index of the file in the file list (None in single-file mode)
+    file_index: Option<usize>,
+    /// Lustre OST holding the first byte (only with --ost-stats)
+    ost: Option<u32>,
+    /// Issued through the target's buffered fd (only with --buffered-percent)
+    buffered: bool,
+}
+
+/// Open target as the hot path uses it
+#[derive(Debug, Clone)]
+struct CachedTarget {
+    fd: i32,
+    size: u64,
+    /// Striping layout (only with --ost-stats)
+    stripes: Option<Arc<StripeLayout>>,
+    /// Second fd opened without O_DIRECT (only with --buffered-percent)
+    buffered_fd: Option<i32>,
+}
+
+/// Append-grow progress of the worker's file
+#[derive(Debug)]
+struct GrowState {
+    /// Offset of the next extending write (EOF including in-flight appends)
+    next_offset: u64,
+    /// Size to grow the file to
+    target: u64,
+    /// False once no further append fits below the target
+    appending: bool,
+    /// Extending writes submitted but not yet completed
+    in_flight: u64,
+    /// An extending write came up short, so the target can't be reached
+    short: bool,
+    /// Seconds into the test when the last extending write completed
+    reached_secs: Option<f64>,
+}
+
+impl GrowState {
+    /// Account for a completed extending write of `length` bytes at `offset`
+    ///
+    /// A write that stored fewer bytes (or failed, with `written` 0) stops
+    /// appending: EOF is where its data ended, so later overwrites stay in
+    /// the written part of the file and the target is never reported reached.
+    fn landed(&mut self, offset: u64, length: usize, written: usize) {
+        self.in_flight = self.in_flight.saturating_sub(1);
+        if written < length {
+            self.next_offset = self.next_offset.min(offset + written as u64);
+            self.appending = false;
+            self.short = true;
+        }
+    }
+    
+    /// Record the target as reached once appending stopped and all appends landed
+    fn check_reached(&mut self, start_time: Option<Instant>) {
+        if !self.appending && !self.short && self.in_flight == 0 && self.reached_secs.is_none() {
+            self.reached_secs = Some(start_time.map(|s| s.elapsed().as_secs_f64()).unwrap_or(0.0));
+        }
+    }
 }
 
 /// Worker thread that executes IO operations
@@ -120,6 +203,10 @@ pub struct Worker {
     /// IO engine for submitting operations
     engine: Box<dyn IOEngine>,
     
+    /// Type of `engine`, which engine setup may lower the queue depth of
+    /// without changing it
+    engine_type: EngineType,
+    
     /// Target files/devices
     targets: Vec<Box<dyn Target>>,
     
@@ -129,9 +216,18 @@ pub struct Worker {
     /// Random distribution for offset generation
     distribution: Box<dyn Distribution>,
     
-    /// Buffer pool for IO operations
+    /// Buffer pool for IO operations (allocated on the worker thread at init)
     buffer_pool: BufferPool,
     
+    /// Pre-filled write data for repeating patterns (None for random data)
+    pattern_buffers: Option<crate::util::pattern_buffers::PatternBuffers>,
+    
+    /// Operation deferred because the buffer pool had no free buffer for its size
+    deferred_op: Option<(OperationType, usize)>,
+    
+    /// When the deferred op first found the pool empty
+    starved_since: Option<Instant>,
+    
     /// Random number generator for operation selection
     rng: Xoshiro256PlusPlus,
     
@@ -144,18 +240,61 @@ pub struct Worker {
     /// Operation counter (for think time application)
     operation_count: usize,
     
-    /// Cached target file descriptor (avoid trait call overhead)
-    cached_target_fd: i32,
+    /// Fd, size and layout of each open target, in target order (avoid trait call overhead)
+    cached_targets: Vec<CachedTarget>,
+    
+    /// Buffered fds of the targets, in target order (only with --buffered-percent)
+    buffered_files: Vec<std::fs::File>,
+    
+    /// Target the next operation goes to (round-robin over `cached_targets`
+    /// for random access; a sequential stream stays on its target)
+    next_target: usize,
     
     /// File list for directory layout testing (if using layout_manifest or layout_config)
     file_list: Option<Arc<Vec<std::path::PathBuf>>>,
     
+    /// Latency per file of the list (only with --file-latency-top)
+    file_latency: Option<crate::stats::file_latency::FileLatencyTracker>,
+    
+    /// Striping layouts of files opened from the list (only with --ost-stats)
+    stripe_layouts: HashMap<usize, Option<Arc<StripeLayout>>>,
+    
+    /// Striping layout of the file IO currently goes to
+    current_stripes: Option<Arc<StripeLayout>>,
+    
     /// File range for PARTITIONED mode (start_index, end_index)
     file_range: Option<(usize, usize)>,
     
     /// Current file index for sequential file access
     current_file_index: usize,
     
+    /// In-use flags shared by the node's workers (SHARED file-list mode)
+    file_claims: Option<Arc<file_claims::FileClaims>>,
+    
+    /// This worker's claim on the file it last picked
+    file_claim: Option<file_claims::FileClaim>,
+    
+    /// Pause, rate limit and read/write mix changed during the run
+    control: Option<Arc<control::RunControl>>,
+    
+    /// Position among the node's workers, which picks the share of its rate
+    control_index: usize,
+    
+    /// O_DIRECT offset and length alignment of the targets (4096 until known)
+    direct_alignment: u64,
+    
+    /// Spacing of submissions under a rate limit
+    pacer: control::Pacer,
+    
+    /// Release times of submissions owed think time (only with --think-smooth)
+    think_schedule: Option<think_schedule::ThinkSchedule>,
+    
+    /// When the last operation was submitted (only with think time)
+    last_submit: Option<FastInstant>,
+    
+    /// Bytes submitted per class (only with --rw-split-by bytes)
+    byte_split: Option<rw_split::ByteSplit>,
+    
     /// Currently open file (for file list mode)
     current_file: Option<Box<dyn Target>>,
     
@@ -165,11 +304,51 @@ pub struct Worker {
     /// Current file size (for file list mode)
     current_file_size: u64,
     
-    /// Cached target size (avoid trait call overhead)
-    cached_target_size: u64,
+    /// Files from the list whose page-cache hints were already issued
+    advised_files: HashSet<usize>,
     
     /// Shared statistics snapshots for live updates (optional)
     shared_snapshots: Option<Arc<Mutex<Vec<StatsSnapshot>>>>,
+    
+    /// This worker's slot in `shared_snapshots` (node-local worker index)
+    snapshot_slot: usize,
+    
+    /// This worker's own live statistics queue (--thread-per-core; replaces `shared_snapshots`)
+    snapshot_queue: Option<snapshot_queue::SnapshotSender>,
+    
+    /// Queue depth ramp controller (only when qd_ramp is configured)
+    qd_ramp: Option<qd_ramp::QdRampController>,
+    
+    /// Thread page fault counters at test start (mmap engine only)
+    fault_baseline: Option<PageFaults>,
+    
+    /// CPU vs wait time of the worker thread (only with --cpu-accounting)
+    cpu_tracker: Option<CpuTracker>,
+    
+    /// Append-grow progress (only when grow_to is configured)
+    grow: Option<GrowState>,
+    
+    /// Operations submitted so far (for --stop-at-op and the offset dump)
+    ops_submitted: u64,
+    
+    /// Submitted operation log (only when offset_dump is configured)
+    offset_dump: Option<offset_dump::OffsetDump>,
+    
+    /// Checksums of submitted and completed writes (only with --write-journal)
+    write_journal: Option<write_journal::WriteJournal>,
+    
+    /// Generation stamps and commit points (only with --commit-every)
+    write_order: Option<write_order::WriteOrder>,
+    
+    /// End of the duration, after which completions are drained stragglers
+    /// (only with --drain-latency exclude/separate)
+    drain_deadline: Option<Instant>,
+    
+    /// Accesses per offset bucket in each live interval (only with --heatmap-series)
+    heatmap_series: Option<HeatmapSeries>,
+    
+    /// Chunk size and seed of the offset permutation (only with --remap-offsets)
+    offset_remap: Option<(u64, u64)>,
 }
 
 /// Lightweight statistics snapshot for live updates
@@ -182,7 +361,7 @@ pub struct Worker {
 /// 
 /// Total size: ~11 KB (10 metadata + 2 IO histograms)
 /// Cost: <0.01% overhead (verified negligible)
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct StatsSnapshot {
     pub read_ops: u64,
     pub write_ops: u64,
@@ -218,6 +397,12 @@ pub struct StatsSnapshot {
     pub metadata_rename_latency: crate::stats::simple_histogram::SimpleHistogram,
     pub metadata_readdir_latency: crate::stats::simple_histogram::SimpleHistogram,
     pub metadata_fsync_latency: crate::stats::simple_histogram::SimpleHistogram,
+    
+    // Page faults since test start (mmap engine only)
+    pub page_faults: Option<PageFaults>,
+    
+    // Gaps between submissions since test start (only with think time)
+    pub submit_gaps: Option<crate::stats::submit_gaps::SubmitGaps>,
 }
 
 impl Worker {
@@ -233,63 +418,106 @@ impl Worker {
     /// Returns an error if initialization fails (e.g., cannot create engine,
     /// cannot open targets, invalid configuration).
     pub fn new(id: usize, config: Arc<Config>) -> Result<Self> {
+        let think_schedule = config.workload.think_time.as_ref()
+            .filter(|think| think.smooth)
+            .map(|_| think_schedule::ThinkSchedule::default());
+        let offset_remap = config.workload.remap_offsets
+            .map(|remap| (remap.chunk_size(config.workload.io_sizes()), remap.seed));
+        
         // Create IO engine based on configuration
         let engine = Self::create_engine(&config.workload)?;
+        let engine_type = config.workload.effective_engine();
         
         // Create distribution based on configuration
-        let distribution = Self::create_distribution(&config.workload)?;
-        
-        // Create buffer pool (size = queue_depth * 2 for safety)
-        let buffer_size = if config.workload.read_distribution.is_empty() && config.workload.write_distribution.is_empty() {
-            config.workload.block_size as usize // Use configured block size
-        } else {
-            // Use the largest block size from distributions
-            let max_read = config.workload.read_distribution.iter()
-                .map(|p| p.block_size)
-                .max()
-                .unwrap_or(config.workload.block_size);
-            let max_write = config.workload.write_distribution.iter()
-                .map(|p| p.block_size)
-                .max()
-                .unwrap_or(config.workload.block_size);
-            max_read.max(max_write) as usize
+        let distribution = Self::create_distribution(
+            &config.workload,
+            config.runtime.seed.map(|seed| worker_seed(seed, id, SEED_STREAM_OFFSETS)),
+        )?;
+        
+        // With --seed, op selection is reproducible per worker
+        let rng = match config.runtime.seed {
+            Some(seed) => Xoshiro256PlusPlus::seed_from_u64(worker_seed(seed, id, SEED_STREAM_OPS)),
+            None => Xoshiro256PlusPlus::from_entropy(),
         };
         
-        let pool_size = config.workload.queue_depth * 2;
-        let alignment = if config.workload.direct { 4096 } else { 512 };
-        let mut buffer_pool = BufferPool::new(pool_size, buffer_size, alignment);
+        let offset_dump = config.runtime.offset_dump.as_deref()
+            .map(|path| offset_dump::OffsetDump::create(path, id))
+            .transpose()?;
+        let write_journal = config.runtime.write_journal.as_deref()
+            .map(|path| write_journal::WriteJournal::create(path, id))
+            .transpose()?;
         
-        // Pre-fill buffers with random data if using random write pattern
-        if config.workload.write_pattern == VerifyPattern::Random && !config.runtime.verify {
-            buffer_pool.prefill_random();
-        }
+        // Empty until init_engine_and_targets() allocates it on the worker
+        // thread, after CPU/NUMA binding and O_DIRECT alignment detection
+        let buffer_pool = BufferPool::new(0, config.workload.block_size as usize, 512);
         
         // Determine if lock tracking is needed
         let track_locks = config.targets.iter().any(|t| t.lock_mode != FileLockMode::None);
         let enable_heatmap = config.workload.heatmap;
-        let stats = WorkerStats::with_heatmap(track_locks, enable_heatmap);
-        
+        let mut stats = WorkerStats::with_heatmap(track_locks, enable_heatmap);
+        if let Some(limit) = config.runtime.stats_mem_limit {
+            stats.limit_block_tracking(crate::stats::budget::worker_block_limit(limit, config.workers.threads));
+        }
+        if let Some(rate) = config.runtime.latency_sample {
+            stats.set_latency_sampling(rate, (id as u64 + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15));
+        }
+        if let Some(range) = config.workers.offset_range {
+            stats.set_offset_range(range);
+        }
+        let byte_split = (config.workload.rw_split_by == RwSplitBy::Bytes).then(rw_split::ByteSplit::default);
+
         Ok(Self {
             id,
             config,
             engine,
+            engine_type,
             targets: Vec::new(),
             stats,
             distribution,
             buffer_pool,
-            rng: Xoshiro256PlusPlus::from_entropy(),
+            pattern_buffers: None,  // Created with the buffer pool
+            deferred_op: None,
+            starved_since: None,
+            rng,
             start_time: None,
             total_bytes_transferred: 0,
             operation_count: 0,
-            cached_target_fd: -1,  // Will be set after targets are opened
-            cached_target_size: 0,  // Will be set after targets are opened
+            cached_targets: Vec::new(),  // Will be set after targets are opened
+            buffered_files: Vec::new(),  // Opened with the targets
+            next_target: 0,
             shared_snapshots: None,  // Will be set by set_shared_stats() if needed
+            snapshot_slot: 0,
+            snapshot_queue: None,  // Will be set by set_snapshot_queue() with --thread-per-core
             file_list: None,  // Will be set by set_file_list() if needed
+            file_latency: None,
+            stripe_layouts: HashMap::new(),
+            current_stripes: None,
             file_range: None,  // Will be set by set_file_range() for PARTITIONED mode
             current_file_index: 0,
+            file_claims: None,  // Will be set by set_file_claims() for SHARED mode
+            file_claim: None,
+            control: None,  // Will be set by set_control() by the node service
+            control_index: 0,
+            direct_alignment: 4096,
+            pacer: control::Pacer::default(),
+            think_schedule,
+            last_submit: None,
+            byte_split,
             current_file: None,
             current_file_fd: -1,
             current_file_size: 0,
+            advised_files: HashSet::new(),
+            qd_ramp: None,  // Created at test start by start_qd_ramp()
+            fault_baseline: None,  // Taken at test start by start_fault_tracking()
+            cpu_tracker: None,  // Started at test start with --cpu-accounting
+            grow: None,  // Starts at the file's EOF once targets are open
+            ops_submitted: 0,
+            offset_dump,
+            write_journal,
+            write_order: None,
+            drain_deadline: None,  // Set at test start by start_drain_tracking()
+            heatmap_series: None,  // Created at test start by start_heatmap_series()
+            offset_remap,
         })
     }
     
@@ -302,6 +530,9 @@ impl Worker {
     ///
     /// * `file_list` - Shared file list
     pub fn set_file_list(&mut self, file_list: Arc<Vec<std::path::PathBuf>>) {
+        if self.config.runtime.file_latency_top.is_some() {
+            self.file_latency = Some(crate::stats::file_latency::FileLatencyTracker::new(file_list.len()));
+        }
         self.file_list = Some(file_list);
     }
     
@@ -318,6 +549,23 @@ impl Worker {
         self.current_file_index = start;
     }
     
+    /// Set the in-use flags shared with the other workers of the node
+    ///
+    /// In SHARED file-list mode, picks that land on a file another worker
+    /// is using are counted and handled by the `--busy-file` policy.
+    pub fn set_file_claims(&mut self, claims: Arc<file_claims::FileClaims>) {
+        self.file_claims = Some(claims);
+    }
+    
+    /// Set the run control shared with the other workers of the node
+    ///
+    /// Lets the test be paused, rate limited or given another read/write
+    /// mix while it runs. `index` is the worker's position on its node.
+    pub fn set_control(&mut self, control: Arc<control::RunControl>, index: usize) {
+        self.control = Some(control);
+        self.control_index = index;
+    }
+    
     /// Set shared statistics snapshots for live updates
     ///
     /// This allows the coordinator to read worker statistics during execution
@@ -326,8 +574,20 @@ impl Worker {
     /// # Arguments
     ///
     /// * `shared` - Shared statistics snapshot vector
-    pub fn set_shared_stats(&mut self, shared: Arc<Mutex<Vec<StatsSnapshot>>>) {
+    /// * `slot` - Index of this worker's entry in `shared` (its node-local index,
+    ///   which differs from the global worker ID on all but the first node)
+    pub fn set_shared_stats(&mut self, shared: Arc<Mutex<Vec<StatsSnapshot>>>, slot: usize) {
         self.shared_snapshots = Some(shared);
+        self.snapshot_slot = slot;
+    }
+    
+    /// Send live statistics snapshots through this worker's own queue
+    ///
+    /// With `--thread-per-core` each worker has a queue to the node service
+    /// instead of a slot in the shared snapshot vector, so publishing a
+    /// snapshot never waits on a lock another worker or the heartbeat holds.
+    pub fn set_snapshot_queue(&mut self, queue: snapshot_queue::SnapshotSender) {
+        self.snapshot_queue = Some(queue);
     }
     
     /// Create IO engine based on configuration
@@ -344,22 +604,15 @@ impl Worker {
         use std::sync::atomic::{AtomicBool, Ordering};
         
         // Smart engine selection: use sync for QD=1, async for QD>1
-        // This avoids async overhead for single-depth queues
-        let effective_engine = if workload.queue_depth == 1 {
-            match workload.engine {
-                EngineType::Libaio | EngineType::IoUring => {
-                    // Only print message once across all workers
-                    static SMART_SELECTION_NOTIFIED: AtomicBool = AtomicBool::new(false);
-                    if !SMART_SELECTION_NOTIFIED.swap(true, Ordering::Relaxed) {
-                        eprintln!("Note: Using sync engine for queue depth 1 (more efficient than async engines)");
-                    }
-                    EngineType::Sync
-                }
-                _ => workload.engine,
+        // This avoids async overhead for single-depth queues (unless --no-engine-fallback)
+        let effective_engine = workload.effective_engine();
+        if effective_engine != workload.engine {
+            // Only print message once across all workers
+            static SMART_SELECTION_NOTIFIED: AtomicBool = AtomicBool::new(false);
+            if !SMART_SELECTION_NOTIFIED.swap(true, Ordering::Relaxed) {
+                tracing::info!("Using sync engine for queue depth 1 (more efficient than async engines; --no-engine-fallback keeps {})", workload.engine);
             }
-        } else {
-            workload.engine
-        };
+        }
         
         let engine: Box<dyn IOEngine> = match effective_engine {
             EngineType::Sync => Box::new(SyncEngine::new()),
@@ -380,36 +633,75 @@ impl Worker {
                 anyhow::bail!("libaio engine only available on Linux")
             }
             
-            EngineType::Mmap => Box::new(MmapEngine::new()),
+            EngineType::Mmap => Box::new(MmapEngine::with_options(workload.mmap.clone())),
+            
+            EngineType::Threadpool => Box::new(crate::engine::threadpool::ThreadPoolEngine::new()),
         };
         
         Ok(engine)
     }
     
     /// Create distribution based on configuration
-    fn create_distribution(workload: &WorkloadConfig) -> Result<Box<dyn Distribution>> {
+    ///
+    /// Random distributions are seeded from `seed` when given, otherwise from entropy.
+    fn create_distribution(workload: &WorkloadConfig, seed: Option<u64>) -> Result<Box<dyn Distribution>> {
         // If not random, use sequential distribution
         if !workload.random {
-            return Ok(Box::new(crate::distribution::sequential::SequentialDistribution::new()));
+            return Ok(Box::new(match workload.offset_start {
+                Some(OffsetStart::At(offset)) => {
+                    crate::distribution::sequential::SequentialDistribution::starting_at(offset / workload.block_size.max(1))
+                }
+                _ => crate::distribution::sequential::SequentialDistribution::new(),
+            }));
         }
         
         // Otherwise use configured random distribution
-        let dist: Box<dyn Distribution> = match &workload.distribution {
-            DistributionType::Uniform => {
+        Ok(Self::random_distribution(&workload.distribution, seed))
+    }
+    
+    /// Create a random distribution, seeded from `seed` when given
+    fn random_distribution(distribution: &DistributionType, seed: Option<u64>) -> Box<dyn Distribution> {
+        match (distribution, seed) {
+            (DistributionType::Uniform, None) => {
                 Box::new(UniformDistribution::new())
             }
-            DistributionType::Zipf { theta } => {
+            (DistributionType::Uniform, Some(seed)) => {
+                Box::new(UniformDistribution::with_seed(seed))
+            }
+            (DistributionType::Zipf { theta }, None) => {
                 Box::new(ZipfDistribution::new(*theta))
             }
-            DistributionType::Pareto { h } => {
+            (DistributionType::Zipf { theta }, Some(seed)) => {
+                Box::new(ZipfDistribution::with_seed(*theta, seed))
+            }
+            (DistributionType::Pareto { h }, None) => {
                 Box::new(ParetoDistribution::new(*h))
             }
-            DistributionType::Gaussian { stddev, center } => {
-                Box::new(GaussianDistribution::new(*stddev, *center))
+            (DistributionType::Pareto { h }, Some(seed)) => {
+                Box::new(ParetoDistribution::with_seed(*h, seed))
             }
-        };
-        
-        Ok(dist)
+            (DistributionType::Gaussian { stddev, center, drift }, None) => {
+                Box::new(GaussianDistribution::new(*stddev, *center).with_drift(*drift))
+            }
+            (DistributionType::Gaussian { stddev, center, drift }, Some(seed)) => {
+                Box::new(GaussianDistribution::with_seed(*stddev, *center, seed).with_drift(*drift))
+            }
+            (DistributionType::Composite { components }, seed) => {
+                // Every component draws from its own seed
+                let parts = components.iter().enumerate()
+                    .map(|(i, component)| CompositePart::new(
+                        component.weight as u32,
+                        component.start,
+                        component.end,
+                        Self::random_distribution(&component.distribution, seed.map(|seed| seed.wrapping_add(i as u64 + 1))),
+                    ))
+                    .collect();
+                match seed {
+                    Some(seed) => Box::new(CompositeDistribution::with_seed(parts, seed)),
+                    None => Box::new(CompositeDistribution::new(parts)),
+                }
+            }
+        }
     }
     
     /// Main execution loop
@@ -429,34 +721,41 @@ impl Worker {
     /// - IO operation fails (unless continue-on-error is enabled)
     /// - Engine cleanup fails
     pub fn run(&mut self) -> Result<WorkerStats> {
+        let _span = tracing::info_span!("worker", id = self.id, uid = self.config.workers.unit_id.as_deref()).entered();
+        
         // Apply CPU/NUMA affinity if configured
         self.apply_affinity()
             .context("Failed to apply CPU/NUMA affinity")?;
         
-        // Initialize engine
-        let engine_config = self.config.workload.to_engine_config();
-        self.engine.init(&engine_config)
-            .context("Failed to initialize IO engine")?;
-        
-        // Open targets
-        self.open_targets()
-            .context("Failed to open targets")?;
+        // Initialize engine and open targets (timed as setup phases)
+        self.init_engine_and_targets()?;
         
         // Verify we have targets or file list
         if self.targets.is_empty() && self.file_list.is_none() {
             anyhow::bail!("No targets or file list available for IO operations");
         }
+        self.begin_write_order()?;
+        
+        self.start_timer();
         
         // Record start time
         self.start_time = Some(Instant::now());
+        self.start_drain_tracking();
+        self.start_heatmap_series();
         
         // Start resource tracking
         self.stats.start_resource_tracking();
+        self.start_fault_tracking();
+        if self.config.runtime.cpu_accounting {
+            self.cpu_tracker = Some(CpuTracker::start());
+        }
+        
+        // Start queue depth ramp at QD=1 (if enabled)
+        self.start_qd_ramp();
         
         // Main execution loop - ASYNC-AWARE
         // This loop allows multiple operations to be in-flight simultaneously for async engines
-        let queue_depth = self.config.workload.queue_depth;
-        let mut in_flight_ops: HashMap<usize, InFlightOp> = HashMap::with_capacity(queue_depth);
+        let mut in_flight_ops: InFlightSlots<InFlightOp> = InFlightSlots::with_capacity(self.buffer_pool.total_count());
 
         // Check duration every N operations to reduce clock_gettime overhead
         // At high IOPS (>100K), check every 100 ops (~1ms)
@@ -468,42 +767,45 @@ impl Worker {
         const RESOURCE_SAMPLE_INTERVAL: usize = 10000;
         let mut ops_since_resource_sample = 0;
         
-        // Update live stats frequency depends on engine performance characteristics
-        // High-IOPS scenarios (>500K IOPS): Update every 1000 ops to minimize overhead
-        // Low-IOPS scenarios (<500K IOPS): Update every 1 op for perfect precision
-        //
-        // High-IOPS scenarios:
-        // - mmap engine (always fast: 1-3M IOPS)
-        // - Buffered IO with any engine (page cache: 500K-2M IOPS)
-        //
-        // Low-IOPS scenarios:
-        // - O_DIRECT with sync/io_uring/libaio (<100K IOPS typically)
-        let live_stats_update_interval = if matches!(self.config.workload.engine, crate::config::workload::EngineType::Mmap) || !self.config.workload.direct {
-            1000  // High-IOPS: mmap or buffered
-        } else {
-            1  // Low-IOPS: O_DIRECT with other engines
-        };
-        
-        let mut ops_since_live_update = 0;
+        // Live stats snapshots are timed and spaced to stay within the overhead budget
+        let mut live_cadence = live_cadence::LiveCadence::new(self.config.output.live_overhead_percent, Instant::now());
         
         loop {
-            // Phase 1: Fill the queue up to queue_depth
-            while in_flight_ops.len() < queue_depth && !self.should_stop() {
+            // Phase 1: Fill the queue up to queue_depth (or the current ramp step)
+            let mut held_back = None;
+            while in_flight_ops.len() < self.active_queue_depth() && !self.should_stop() {
+                // Commit point: the generation's writes complete before it is committed
+                if self.write_order.as_ref().is_some_and(write_order::WriteOrder::commit_due) {
+                    while !in_flight_ops.is_empty() {
+                        self.process_completions(&mut in_flight_ops)?;
+                    }
+                    self.commit_write_order()?;
+                }
+                
                 // Select operation type (read or write)
-                let op_type = self.select_operation_type();
+                let (op_type, block_size) = self.next_operation();
+                
+                // Paused or rate limited: keep the operation for later
+                if let Some(wait) = self.control_wait(block_size) {
+                    self.deferred_op = Some((op_type, block_size));
+                    held_back = Some(wait);
+                    break;
+                }
                 
                 // Prepare and submit operation (no polling yet)
-                match self.prepare_and_submit_operation(op_type) {
-                    Ok(in_flight_op) => {
+                match self.prepare_and_submit_operation(op_type, block_size) {
+                    Ok(Some(in_flight_op)) => {
                         in_flight_ops.insert(in_flight_op.buf_idx, in_flight_op);
 
                         // Sample queue depth after each submit (for accurate tracking)
                         self.stats.sample_queue_depth(in_flight_ops.len() as u64);
                     }
+                    // Buffer pool starved: wait for completions to return a buffer
+                    Ok(None) => break,
                     Err(e) => {
                         if self.config.runtime.continue_on_error {
                             // Log error and continue
-                            eprintln!("Worker {}: IO error: {}", self.id, e);
+                            tracing::warn!("IO error: {:#}", e);
                             
                             // Check max errors threshold
                             if let Some(max) = self.config.runtime.max_errors {
@@ -523,7 +825,7 @@ impl Worker {
             if !in_flight_ops.is_empty() {
                 if let Err(e) = self.process_completions(&mut in_flight_ops) {
                     if self.config.runtime.continue_on_error {
-                        eprintln!("Worker {}: Completion error: {}", self.id, e);
+                        tracing::warn!("Completion error: {:#}", e);
                         
                         // Check max errors threshold
                         if let Some(max) = self.config.runtime.max_errors {
@@ -535,17 +837,19 @@ impl Worker {
                         return Err(e).context("Completion processing failed");
                     }
                 }
+            } else if let Some(wait) = held_back {
+                self.hold_back(wait);
             }
             
             // Phase 3: Check duration periodically
             ops_since_duration_check += 1;
             if ops_since_duration_check >= DURATION_CHECK_INTERVAL {
+                self.update_qd_ramp();
+                
                 if self.should_stop() && in_flight_ops.is_empty() {
-                    if self.config.runtime.debug {
-                        eprintln!("DEBUG: should_stop returned true at {} ops, elapsed={:.3}s", 
-                            self.operation_count, 
-                            self.start_time.unwrap().elapsed().as_secs_f64());
-                    }
+                    tracing::debug!("should_stop returned true at {} ops, elapsed={:.3}s",
+                        self.operation_count,
+                        self.start_time.unwrap().elapsed().as_secs_f64());
                     break;
                 }
                 ops_since_duration_check = 0;
@@ -559,62 +863,18 @@ impl Worker {
             }
             
             // Phase 5: Update live stats snapshot periodically
-            ops_since_live_update += 1;
-            if ops_since_live_update >= live_stats_update_interval {
+            if live_cadence.tick() {
+                let update_start = Instant::now();
+                if let Some(ref mut series) = self.heatmap_series {
+                    series.advance(update_start);
+                }
+                
                 // Sample queue depth for async engines (always, not just when shared_snapshots is set)
                 self.stats.sample_queue_depth(in_flight_ops.len() as u64);
                 
-                if let Some(ref shared) = self.shared_snapshots {
-                    let avg_latency_us = self.stats.io_latency().mean().as_micros() as f64;
-                    
-                    if let Ok(mut snapshots) = shared.lock() {
-                        snapshots[self.id] = StatsSnapshot {
-                            read_ops: self.stats.read_ops(),
-                            write_ops: self.stats.write_ops(),
-                            read_bytes: self.stats.read_bytes(),
-                            write_bytes: self.stats.write_bytes(),
-                            errors: self.stats.errors(),
-                            avg_latency_us,
-                            // Separate read/write latency histograms (for detailed analysis)
-                            read_latency: self.stats.read_latency().clone(),
-                            write_latency: self.stats.write_latency().clone(),
-                            // Metadata operation counters (just atomic reads, very fast)
-                            metadata_open_ops: self.stats.metadata.open_ops.get(),
-                            metadata_close_ops: self.stats.metadata.close_ops.get(),
-                            metadata_stat_ops: self.stats.metadata.stat_ops.get(),
-                            metadata_setattr_ops: self.stats.metadata.setattr_ops.get(),
-                            metadata_mkdir_ops: self.stats.metadata.mkdir_ops.get(),
-                            metadata_rmdir_ops: self.stats.metadata.rmdir_ops.get(),
-                            metadata_unlink_ops: self.stats.metadata.unlink_ops.get(),
-                            metadata_rename_ops: self.stats.metadata.rename_ops.get(),
-                            metadata_readdir_ops: self.stats.metadata.readdir_ops.get(),
-                            metadata_fsync_ops: self.stats.metadata.fsync_ops.get(),
-                            // Metadata latency histograms (clone for time-series analysis)
-                            // Cost: ~9 KB memcpy every 1K ops = <0.01% overhead
-                            metadata_open_latency: self.stats.metadata.open_latency.clone(),
-                            metadata_close_latency: self.stats.metadata.close_latency.clone(),
-                            metadata_stat_latency: self.stats.metadata.stat_latency.clone(),
-                            metadata_setattr_latency: self.stats.metadata.setattr_latency.clone(),
-                            metadata_mkdir_latency: self.stats.metadata.mkdir_latency.clone(),
-                            metadata_rmdir_latency: self.stats.metadata.rmdir_latency.clone(),
-                            metadata_unlink_latency: self.stats.metadata.unlink_latency.clone(),
-                            metadata_rename_latency: self.stats.metadata.rename_latency.clone(),
-                            metadata_readdir_latency: self.stats.metadata.readdir_latency.clone(),
-                            metadata_fsync_latency: self.stats.metadata.fsync_latency.clone(),
-                        };
-                    }
-                }
-                ops_since_live_update = 0;
-            }
-            
-            // Apply think time if configured
-            if let Some(ref think_time) = self.config.workload.think_time {
-                if self.operation_count % think_time.apply_every_n_blocks == 0 {
-                    // Use a nominal latency for think time calculation
-                    // In async mode, we don't have per-operation latency readily available
-                    let nominal_latency = Duration::from_micros(100);
-                    self.apply_think_time(think_time, nominal_latency);
-                }
+                self.publish_snapshot();
+                let now = Instant::now();
+                live_cadence.record(now - update_start, now);
             }
         }
         
@@ -622,6 +882,17 @@ impl Worker {
         while !in_flight_ops.is_empty() {
             self.process_completions(&mut in_flight_ops)?;
         }
+        if self.write_order.as_ref().is_some_and(write_order::WriteOrder::uncommitted) {
+            self.commit_write_order()?;
+        }
+        
+        self.finish_qd_ramp();
+        if let Some(ref mut dump) = self.offset_dump {
+            dump.finish()?;
+        }
+        if let Some(ref mut journal) = self.write_journal {
+            journal.finish()?;
+        }
         
         // Fsync targets BEFORE cleanup (if not using O_DIRECT)
         // NOTE: Disabled for performance - fsync not required by default
@@ -657,6 +928,17 @@ impl Worker {
         }
         */
         
+        self.finish_fault_tracking();
+        if let Some(ref tracker) = self.cpu_tracker {
+            self.stats.set_cpu_time(tracker.finish());
+        }
+        self.finish_grow_tracking();
+        self.finish_heatmap_series();
+        self.stats.set_buffer_pool_stats(self.buffer_pool.class_stats());
+        self.stats.set_engine_stats(self.engine.stats());
+        self.stats.set_live_cadence(live_cadence.finish(Instant::now()));
+        self.finish_file_latency();
+        
         // Cleanup engine
         self.engine.cleanup()
             .context("Failed to cleanup IO engine")?;
@@ -676,7 +958,8 @@ impl Worker {
         };
         
         // Set test duration in stats before returning
-        self.stats.set_test_duration(test_duration);
+        self.stats.set_test_duration(self.results_duration(test_duration));
+        self.record_io_window(test_duration);
         
         // Return statistics
         // Create a dummy stats to replace with (matching the original config)
@@ -687,6 +970,106 @@ impl Worker {
         Ok(std::mem::replace(&mut self.stats, replacement_stats))
     }
     
+    /// Record the wall-clock window of this worker's IO, ending now
+    ///
+    /// Absolute times let the coordinator compare workers across nodes
+    /// (start/stop skew).
+    fn record_io_window(&mut self, test_duration: Duration) {
+        let stop_ns = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        self.stats.set_io_window(stop_ns.saturating_sub(test_duration.as_nanos() as u64), stop_ns);
+    }
+    
+    /// Note when the duration ends, if drained operations are kept out of the results
+    fn start_drain_tracking(&mut self) {
+        if self.config.runtime.drain_latency != DrainPolicy::Include {
+            self.drain_deadline = self.start_time
+                .zip(self.config.workload.completion_mode.test_duration())
+                .map(|(start, duration)| start + duration);
+        }
+    }
+    
+    /// Write the first commit and keep writes off the commit slots (with --commit-every)
+    ///
+    /// The two commit slots are the first blocks of the worker's span; its
+    /// writes use the rest.
+    fn begin_write_order(&mut self) -> Result<()> {
+        let Some(every) = self.config.runtime.commit_every else {
+            return Ok(());
+        };
+        let block_size = self.config.workload.block_size;
+        let (start, end) = self.config.workers.offset_range.unwrap_or((0, self.targets[0].size()));
+        if end.saturating_sub(start) < 3 * block_size {
+            anyhow::bail!("--commit-every needs at least three blocks per worker ({} bytes available)", end.saturating_sub(start));
+        }
+        Arc::make_mut(&mut self.config).workers.offset_range = Some((start + 2 * block_size, end));
+        let (order, took) = write_order::WriteOrder::begin(self.targets[0].fd(), self.id, start, block_size as usize, every)?;
+        self.write_order = Some(order);
+        self.stats.metadata.fsync_ops.add(1);
+        self.stats.metadata.fsync_latency.record(took);
+        Ok(())
+    }
+    
+    /// Commit the current generation (with --commit-every), counted as an fsync
+    fn commit_write_order(&mut self) -> Result<()> {
+        let fd = self.targets[0].fd();
+        let Some(ref mut order) = self.write_order else {
+            return Ok(());
+        };
+        let took = order.commit(fd)?;
+        self.stats.metadata.fsync_ops.add(1);
+        self.stats.metadata.fsync_latency.record(took);
+        Ok(())
+    }
+    
+    /// Start the per-interval heatmap buckets over the whole target (with --heatmap-series)
+    fn start_heatmap_series(&mut self) {
+        if !(self.config.workload.heatmap && self.config.output.heatmap_series) {
+            return;
+        }
+        let target_size = self.config.targets.first()
+            .and_then(|target| target.region)
+            .map_or(self.cached_targets.first().map_or(0, |target| target.size), |(_, end)| end);
+        if let (Some(start), true) = (self.start_time, target_size > 0) {
+            self.heatmap_series = Some(HeatmapSeries::new(
+                self.config.workload.heatmap_buckets,
+                target_size / self.config.workload.block_size.max(1),
+                self.config.output.live_interval_duration(),
+                start,
+            ));
+        }
+    }
+    
+    /// Close the last interval and hand the series to the statistics
+    fn finish_heatmap_series(&mut self) {
+        if let Some(mut series) = self.heatmap_series.take() {
+            series.finish();
+            self.stats.set_heatmap_series(series);
+        }
+    }
+    
+    /// Whether the duration expired and in-flight operations are being drained
+    ///
+    /// Workers stop submitting at this point instead of waiting for STOP, so
+    /// only operations already in flight complete afterwards.
+    #[inline]
+    fn past_drain_deadline(&self) -> bool {
+        self.drain_deadline.is_some_and(|deadline| Instant::now() >= deadline)
+    }
+    
+    /// Time the results cover
+    ///
+    /// Operations after the duration are left out with --drain-latency
+    /// exclude/separate, so rates are over the configured duration.
+    fn results_duration(&self, elapsed: Duration) -> Duration {
+        match self.config.workload.completion_mode.test_duration() {
+            Some(duration) if self.drain_deadline.is_some() => elapsed.min(duration),
+            _ => elapsed,
+        }
+    }
+    
     /// Run worker until stop flag is set (for distributed mode)
     ///
     /// Similar to run() but checks a stop flag instead of duration/bytes.
@@ -694,44 +1077,55 @@ impl Worker {
     pub fn run_until_stopped(&mut self, stop_flag: &std::sync::atomic::AtomicBool) -> Result<()> {
         use std::sync::atomic::Ordering;
         
+        let _span = tracing::info_span!("worker", id = self.id, uid = self.config.workers.unit_id.as_deref()).entered();
+        
         // Apply CPU/NUMA affinity if configured
         self.apply_affinity()
             .context("Failed to apply CPU/NUMA affinity")?;
         
-        // Initialize engine
-        let engine_config = self.config.workload.to_engine_config();
-        self.engine.init(&engine_config)
-            .context("Failed to initialize IO engine")?;
-        
-        // Open targets
-        self.open_targets()
-            .context("Failed to open targets")?;
+        // Initialize engine and open targets (timed as setup phases)
+        self.init_engine_and_targets()?;
         
         // Verify we have targets or file list
         if self.targets.is_empty() && self.file_list.is_none() {
             anyhow::bail!("No targets or file list available for IO operations");
         }
+        self.begin_write_order()?;
+        
+        // Engines and targets are ready; wait for the node's START
+        if let Some(control) = self.control.clone() {
+            while !control.is_started() && !stop_flag.load(Ordering::Relaxed) {
+                std::thread::sleep(control::PAUSE_POLL_INTERVAL);
+            }
+        }
+        
+        self.start_timer();
         
         // Record start time
         self.start_time = Some(Instant::now());
+        self.start_drain_tracking();
+        self.start_heatmap_series();
         
         // Start resource tracking
         self.stats.start_resource_tracking();
+        self.start_fault_tracking();
+        if self.config.runtime.cpu_accounting {
+            self.cpu_tracker = Some(CpuTracker::start());
+        }
+        
+        // Start queue depth ramp at QD=1 (if enabled)
+        self.start_qd_ramp();
         
         // Main execution loop
-        let queue_depth = self.config.workload.queue_depth;
-        let mut in_flight_ops: HashMap<usize, InFlightOp> = HashMap::with_capacity(queue_depth);
+        let mut in_flight_ops: InFlightSlots<InFlightOp> = InFlightSlots::with_capacity(self.buffer_pool.total_count());
 
-        // Track operations for live stats updates
-        // High-IOPS (mmap or buffered): Every 1000 ops
-        // Low-IOPS (O_DIRECT): Every 1 op for perfect precision
-        let live_stats_update_interval = if matches!(self.config.workload.engine, crate::config::workload::EngineType::Mmap) || !self.config.workload.direct {
-            1000
-        } else {
-            1
-        };
+        // Live stats snapshots are timed and spaced to stay within the overhead budget
+        let mut live_cadence = live_cadence::LiveCadence::new(self.config.output.live_overhead_percent, Instant::now());
+        let mut live_update_due = false;
         
-        let mut ops_since_live_update = 0;
+        // Queue depth ramp is advanced every N loop iterations
+        const QD_RAMP_CHECK_INTERVAL: usize = 100;
+        let mut iterations_since_ramp_check = 0;
         
         loop {
             // Check stop flag
@@ -739,19 +1133,55 @@ impl Worker {
                 break;
             }
             
+            // Byte-count and run-until-complete runs end when this worker is done;
+            // with stonewall the first worker to finish stops its siblings
+            // (but not at --stop-at-op, where each worker replays its own N ops)
+            if self.reached_completion() {
+                if self.config.workers.stonewall && !self.reached_stop_at_op() {
+                    stop_flag.store(true, Ordering::Relaxed);
+                }
+                break;
+            }
+            
+            // Advance queue depth ramp periodically
+            iterations_since_ramp_check += 1;
+            if iterations_since_ramp_check >= QD_RAMP_CHECK_INTERVAL {
+                self.update_qd_ramp();
+                iterations_since_ramp_check = 0;
+            }
+            
             // Fill the queue
-            while in_flight_ops.len() < queue_depth && !stop_flag.load(Ordering::Relaxed) {
-                let op_type = self.select_operation_type();
+            let mut held_back = None;
+            while in_flight_ops.len() < self.active_queue_depth() && !stop_flag.load(Ordering::Relaxed)
+                && !self.reached_completion() && !self.past_drain_deadline() {
+                // Commit point: the generation's writes complete before it is committed
+                if self.write_order.as_ref().is_some_and(write_order::WriteOrder::commit_due) {
+                    while !in_flight_ops.is_empty() {
+                        self.process_completions(&mut in_flight_ops)?;
+                    }
+                    self.commit_write_order()?;
+                }
+                
+                let (op_type, block_size) = self.next_operation();
+                
+                // Paused or rate limited: keep the operation for later
+                if let Some(wait) = self.control_wait(block_size) {
+                    self.deferred_op = Some((op_type, block_size));
+                    held_back = Some(wait);
+                    break;
+                }
                 
-                match self.prepare_and_submit_operation(op_type) {
-                    Ok(in_flight_op) => {
+                match self.prepare_and_submit_operation(op_type, block_size) {
+                    Ok(Some(in_flight_op)) => {
                         in_flight_ops.insert(in_flight_op.buf_idx, in_flight_op);
                         self.stats.sample_queue_depth(in_flight_ops.len() as u64);
-                        ops_since_live_update += 1;
+                        live_update_due |= live_cadence.tick();
                     }
+                    // Buffer pool starved: wait for completions to return a buffer
+                    Ok(None) => break,
                     Err(e) => {
                         if self.config.runtime.continue_on_error {
-                            eprintln!("Worker {}: IO error: {}", self.id, e);
+                            tracing::warn!("IO error: {:#}", e);
                         } else {
                             return Err(e).context("IO operation failed");
                         }
@@ -766,49 +1196,25 @@ impl Worker {
                         return Err(e).context("Completion processing failed");
                     }
                 }
+            } else if let Some(wait) = held_back {
+                self.hold_back(wait);
+            } else if self.past_drain_deadline() {
+                // Drained; wait for STOP without spinning
+                std::thread::sleep(control::PAUSE_POLL_INTERVAL);
             }
             
-            // Update shared snapshots periodically (every 1K ops)
-            if ops_since_live_update >= live_stats_update_interval {
+            // Update shared snapshots periodically
+            if live_update_due {
+                live_update_due = false;
+                let update_start = Instant::now();
+                if let Some(ref mut series) = self.heatmap_series {
+                    series.advance(update_start);
+                }
                 self.stats.sample_queue_depth(in_flight_ops.len() as u64);
                 
-                if let Some(ref shared) = self.shared_snapshots {
-                    let avg_latency_us = self.stats.io_latency().mean().as_micros() as f64;
-                    
-                    if let Ok(mut snapshots) = shared.lock() {
-                        snapshots[self.id] = StatsSnapshot {
-                            read_ops: self.stats.read_ops(),
-                            write_ops: self.stats.write_ops(),
-                            read_bytes: self.stats.read_bytes(),
-                            write_bytes: self.stats.write_bytes(),
-                            errors: self.stats.errors(),
-                            avg_latency_us,
-                            read_latency: self.stats.read_latency().clone(),
-                            write_latency: self.stats.write_latency().clone(),
-                            metadata_open_ops: self.stats.metadata.open_ops.get(),
-                            metadata_close_ops: self.stats.metadata.close_ops.get(),
-                            metadata_stat_ops: self.stats.metadata.stat_ops.get(),
-                            metadata_setattr_ops: self.stats.metadata.setattr_ops.get(),
-                            metadata_mkdir_ops: self.stats.metadata.mkdir_ops.get(),
-                            metadata_rmdir_ops: self.stats.metadata.rmdir_ops.get(),
-                            metadata_unlink_ops: self.stats.metadata.unlink_ops.get(),
-                            metadata_rename_ops: self.stats.metadata.rename_ops.get(),
-                            metadata_readdir_ops: self.stats.metadata.readdir_ops.get(),
-                            metadata_fsync_ops: self.stats.metadata.fsync_ops.get(),
-                            metadata_open_latency: self.stats.metadata.open_latency.clone(),
-                            metadata_close_latency: self.stats.metadata.close_latency.clone(),
-                            metadata_stat_latency: self.stats.metadata.stat_latency.clone(),
-                            metadata_setattr_latency: self.stats.metadata.setattr_latency.clone(),
-                            metadata_mkdir_latency: self.stats.metadata.mkdir_latency.clone(),
-                            metadata_rmdir_latency: self.stats.metadata.rmdir_latency.clone(),
-                            metadata_unlink_latency: self.stats.metadata.unlink_latency.clone(),
-                            metadata_rename_latency: self.stats.metadata.rename_latency.clone(),
-                            metadata_readdir_latency: self.stats.metadata.readdir_latency.clone(),
-                            metadata_fsync_latency: self.stats.metadata.fsync_latency.clone(),
-                        };
-                    }
-                }
-                ops_since_live_update = 0;
+                self.publish_snapshot();
+                let now = Instant::now();
+                live_cadence.record(now - update_start, now);
             }
         }
         
@@ -816,6 +1222,27 @@ impl Worker {
         while !in_flight_ops.is_empty() {
             self.process_completions(&mut in_flight_ops)?;
         }
+        if self.write_order.as_ref().is_some_and(write_order::WriteOrder::uncommitted) {
+            self.commit_write_order()?;
+        }
+        
+        self.finish_qd_ramp();
+        if let Some(ref mut dump) = self.offset_dump {
+            dump.finish()?;
+        }
+        if let Some(ref mut journal) = self.write_journal {
+            journal.finish()?;
+        }
+        self.finish_fault_tracking();
+        if let Some(ref tracker) = self.cpu_tracker {
+            self.stats.set_cpu_time(tracker.finish());
+        }
+        self.finish_grow_tracking();
+        self.finish_heatmap_series();
+        self.stats.set_buffer_pool_stats(self.buffer_pool.class_stats());
+        self.stats.set_engine_stats(self.engine.stats());
+        self.stats.set_live_cadence(live_cadence.finish(Instant::now()));
+        self.finish_file_latency();
         
         // Cleanup
         self.engine.cleanup()?;
@@ -824,7 +1251,9 @@ impl Worker {
         
         // Set test duration
         if let Some(start) = self.start_time {
-            self.stats.set_test_duration(start.elapsed());
+            let test_duration = start.elapsed();
+            self.stats.set_test_duration(self.results_duration(test_duration));
+            self.record_io_window(test_duration);
         }
         
         Ok(())
@@ -840,7 +1269,14 @@ impl Worker {
         use crate::worker::affinity;
         
         // Apply CPU affinity if configured
-        if let Some(ref cpu_spec) = self.config.workers.cpu_cores {
+        if let Some(core) = self.config.workers.core {
+            // The core this worker owns (--thread-per-core)
+            affinity::set_cpu_affinity(&[core])
+                .with_context(|| format!("Failed to bind worker to core {}", core))?;
+            if affinity::housekeeping_cores().is_some_and(|hk| hk.contains(&core)) {
+                tracing::warn!("worker core {} is a housekeeping core", core);
+            }
+        } else if let Some(ref cpu_spec) = self.config.workers.cpu_cores {
             let cores = affinity::parse_cpu_list(cpu_spec)
                 .context("Failed to parse CPU core list")?;
             
@@ -848,6 +1284,13 @@ impl Worker {
             // For now, bind to all specified cores (coordinator will handle distribution)
             affinity::set_cpu_affinity(&cores)
                 .context("Failed to set CPU affinity")?;
+            if let Some(shared) = affinity::housekeeping_cores().filter(|hk| cores.iter().any(|core| hk.contains(core))) {
+                tracing::warn!("--cpu-cores {} overlaps the housekeeping cores {:?}", cpu_spec, shared);
+            }
+        } else if let Some(cores) = affinity::worker_cores() {
+            // Leave the housekeeping cores the worker was spawned on
+            affinity::set_cpu_affinity(cores)
+                .context("Failed to move worker off the housekeeping cores")?;
         }
         
         // Apply NUMA affinity if configured
@@ -862,19 +1305,215 @@ impl Worker {
         Ok(())
     }
     
+    /// Initialize the IO engine at the highest queue depth the target and engine can use
+    ///
+    /// An async engine's queue depth is clamped to the device queue and the
+    /// free libaio events, then halved while the engine fails to set up for
+    /// lack of resources. io_uring or libaio that the kernel doesn't offer
+    /// here is replaced by the thread pool engine at the same queue depth,
+    /// unless `--no-engine-fallback` was given.
+    /// An engine that runs one IO at a time gets queue depth 1. A lower
+    /// queue depth replaces the configured one for this worker and is
+    /// recorded in the stats.
+    fn init_engine(&mut self) -> Result<()> {
+        let mut engine_config = self.config.workload.to_engine_config();
+        let mut limit = queue_limit::QueueDepthLimit::new(engine_config.queue_depth);
+        let capabilities = self.engine.capabilities();
+        if !capabilities.async_io {
+            limit.clamp(capabilities.max_queue_depth, match self.config.workload.engine {
+                EngineType::Sync => "the sync engine runs one IO at a time (--engine threadpool keeps more in flight)".to_string(),
+                engine => format!("the {} engine runs one IO at a time", engine),
+            });
+            engine_config.queue_depth = limit.effective;
+            self.engine.init(&engine_config)?;
+            self.apply_queue_depth_limit(limit);
+            return Ok(());
+        }
+        
+        let device_queue = self.config.targets.iter()
+            .filter(|target| self.config.workload.direct || target.target_type == TargetType::BlockDevice)
+            .filter_map(|target| queue_limit::device_queue_limit(&target.path))
+            .min();
+        if let Some(requests) = device_queue {
+            limit.clamp(requests, format!("device queue holds {} requests (nr_requests)", requests));
+        }
+        if self.config.workload.engine == EngineType::Libaio {
+            if let Some(available) = queue_limit::aio_events_available() {
+                limit.clamp(available, format!("{} libaio events free (fs.aio-max-nr)", available));
+            }
+        }
+        
+        loop {
+            engine_config.queue_depth = limit.effective;
+            match self.engine.init(&engine_config) {
+                Ok(()) => break,
+                Err(e) if limit.effective > 1 && queue_limit::is_resource_error(&e) => {
+                    limit.clamp(limit.effective / 2, format!(
+                        "{} engine setup failed at queue depth {} ({})",
+                        self.config.workload.engine, limit.effective, e.root_cause()));
+                }
+                Err(e) if limit.effective > 1
+                    && !self.config.workload.no_engine_fallback
+                    && matches!(self.config.workload.engine, EngineType::IoUring | EngineType::Libaio)
+                    && crate::engine::threadpool::is_unavailable_error(&e) =>
+                {
+                    static FALLBACK_WARNED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+                    if !FALLBACK_WARNED.swap(true, std::sync::atomic::Ordering::Relaxed) {
+                        tracing::warn!("{} is unavailable here ({}): running queue depth {} on the threadpool engine",
+                            self.config.workload.engine, e.root_cause(), limit.effective);
+                    }
+                    self.engine = Box::new(crate::engine::threadpool::ThreadPoolEngine::new());
+                    self.engine_type = EngineType::Threadpool;
+                    Arc::make_mut(&mut self.config).workload.engine = EngineType::Threadpool;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        
+        self.apply_queue_depth_limit(limit);
+        Ok(())
+    }
+    
+    /// Time NOP round trips through the engine's ring (--latency-floor)
+    ///
+    /// Recorded as a setup phase. An engine without a NOP (the thread pool
+    /// standing in for io_uring, or a ring that rejects them) measures
+    /// nothing, with a warning.
+    fn measure_latency_floor(&mut self) -> Result<()> {
+        let start = Instant::now();
+        let round_trips = tracing::debug_span!("setup", phase = "Latency floor")
+            .in_scope(|| self.engine.nop_round_trips(crate::stats::latency_floor::NOPS_PER_WORKER))
+            .context("Failed to measure the latency floor")?;
+        match round_trips {
+            Some(round_trips) => {
+                let floor = crate::stats::latency_floor::LatencyFloor::new(self.engine_type, &round_trips);
+                self.stats.set_latency_floor(floor);
+                self.stats.record_setup_phase("Latency floor", start.elapsed());
+            }
+            None => {
+                static NO_NOP_WARNED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+                if !NO_NOP_WARNED.swap(true, std::sync::atomic::Ordering::Relaxed) {
+                    tracing::warn!("The {} engine can't run NOPs here: no latency floor measured", self.engine_type);
+                }
+            }
+        }
+        Ok(())
+    }
+    
+    /// Run this worker at a lower queue depth than configured, if `limit` says so
+    fn apply_queue_depth_limit(&mut self, limit: queue_limit::QueueDepthLimit) {
+        if limit.is_clamped() {
+            static CLAMP_WARNED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+            if !CLAMP_WARNED.swap(true, std::sync::atomic::Ordering::Relaxed) {
+                tracing::warn!("Queue depth {} reduced to {}: {}",
+                    limit.configured, limit.effective, limit.reasons.join("; "));
+            }
+            Arc::make_mut(&mut self.config).workload.queue_depth = limit.effective;
+            self.stats.set_queue_depth_limit(limit);
+        }
+    }
+    
+    /// Create the buffer pool (queue_depth * 2 buffers for safety)
+    ///
+    /// There is one size class per distinct IO size, holding that size's
+    /// share of the buffers. Small ops borrow larger buffers when their class
+    /// runs dry; ops wait for a completion when nothing fits.
+    fn create_buffer_pool(config: &Config, alignment: usize) -> Result<BufferPool> {
+        let classes = buffer_size_classes(config);
+        let mut buffer_pool = BufferPool::with_size_classes(&classes, alignment, config.workload.buffer_hugepages)?;
+        
+        // Pre-fill buffers with random data if using random write pattern
+        if config.workload.write_pattern == VerifyPattern::Random && !config.runtime.verify {
+            buffer_pool.prefill_random();
+        }
+        
+        Ok(buffer_pool)
+    }
+    
+    /// Initialize the IO engine and open targets, recording setup phase timings
+    ///
+    /// Auto-refill time is reported separately from target open.
+    fn init_engine_and_targets(&mut self) -> Result<()> {
+        let engine_start = Instant::now();
+        tracing::debug_span!("setup", phase = "Engine init")
+            .in_scope(|| self.init_engine())
+            .context("Failed to initialize IO engine")?;
+        self.stats.record_setup_phase("Engine init", engine_start.elapsed());
+        self.stats.record_engine(self.engine_type);
+        if self.config.runtime.latency_floor {
+            self.measure_latency_floor()?;
+        }
+        
+        let open_start = Instant::now();
+        let dio_alignment = tracing::debug_span!("setup", phase = "Target open")
+            .in_scope(|| self.open_targets())
+            .context("Failed to open targets")?;
+        let refill_time = self.stats.setup_timings().get("Auto-refill").unwrap_or_default();
+        self.stats.record_setup_phase("Target open", open_start.elapsed().saturating_sub(refill_time));
+        
+        // Allocate (and first-touch) buffers here so they land on this thread's NUMA node
+        let buffer_start = Instant::now();
+        let alignment = match dio_alignment {
+            Some(alignment) => alignment.buffer_alignment(),
+            None if self.config.workload.direct => 4096,  // File list mode: files opened later
+            None => 512,
+        };
+        self.direct_alignment = dio_alignment.map_or(4096, |alignment| alignment.offset);
+        self.buffer_pool = tracing::debug_span!("setup", phase = "Buffer allocation")
+            .in_scope(|| Self::create_buffer_pool(&self.config, alignment))
+            .context("Failed to allocate IO buffers")?;
+        self.pattern_buffers = write_pattern_period(&self.config)
+            .map(|period| crate::util::pattern_buffers::PatternBuffers::new(
+                period, self.buffer_pool.buffer_size(), alignment));
+        self.stats.record_setup_phase("Buffer allocation", buffer_start.elapsed());
+        
+        // Append-grow starts at the file's current EOF
+        if let Some(target) = self.config.workload.grow_to {
+            let eof = fd_size(self.first_target_fd())
+                .context("Failed to get file size for append-grow")?;
+            let alignment = dio_alignment.map(|a| a.offset).unwrap_or(512);
+            if self.config.workload.direct && !eof.is_multiple_of(alignment) {
+                anyhow::bail!(
+                    "Cannot append with O_DIRECT: file size {} is not a multiple of {} bytes. Truncate or remove the file.",
+                    eof, alignment
+                );
+            }
+            self.grow = Some(GrowState { next_offset: eof, target, appending: true, in_flight: 0, short: false, reached_secs: None });
+        }
+        
+        // Map (and pre-fault/pin) mmap targets now rather than on the first IO
+        if self.config.workload.engine == EngineType::Mmap {
+            let map_start = Instant::now();
+            let _span = tracing::debug_span!("setup", phase = "Mmap setup").entered();
+            for (target, target_config) in self.targets.iter().zip(&self.config.targets) {
+                self.engine.prepare_target(target.fd())
+                    .context("Failed to map target")?;
+                self.engine.advise_target(target.fd(), &target_config.madvise_flags)
+                    .context("Failed to apply madvise hints")?;
+            }
+            self.stats.record_setup_phase("Mmap setup", map_start.elapsed());
+        }
+        
+        Ok(())
+    }
+    
     /// Open all targets from configuration
-    fn open_targets(&mut self) -> Result<()> {
+    ///
+    /// Returns the strictest O_DIRECT alignment of the opened targets (direct IO only).
+    fn open_targets(&mut self) -> Result<Option<DioAlignment>> {
         // If we have a file list, skip opening targets here
         // Files will be opened dynamically during execution
         if self.file_list.is_some() {
-            return Ok(());
+            return Ok(None);
         }
         
         use crate::target::file::FileTarget;
         use crate::target::block::BlockTarget;
         use crate::target::{OpenFlags, FadviseFlags as TargetFadviseFlags};
         
-        for target_config in &self.config.targets {
+        let mut dio_alignment: Option<DioAlignment> = None;
+        let reach = crate::distribution::reach::Reach::of(&self.config.workload);
+        for (target_index, target_config) in self.config.targets.iter().enumerate() {
             let mut target: Box<dyn Target> = match target_config.target_type {
                 TargetType::File => {
                     let mut file_target = FileTarget::new(
@@ -898,9 +1537,13 @@ impl Worker {
                     
                     // Set preallocate and truncate options
                     file_target.set_preallocate(target_config.preallocate || force_preallocate);
+                    file_target.set_prealloc_mode(target_config.prealloc_mode);
                     file_target.set_truncate_to_size(target_config.truncate_to_size);
                     file_target.set_refill(target_config.refill);
                     file_target.set_refill_pattern(target_config.refill_pattern);
+                    if let Some(ref custom) = self.config.workload.custom_pattern {
+                        file_target.set_custom_payload(custom.payload.as_slice().into());
+                    }
                     file_target.set_using_direct_io(self.config.workload.direct);
                     
                     // Set offset range for partitioned distribution
@@ -909,6 +1552,15 @@ impl Worker {
                         file_target.set_offset_range(start, end);
                     }
                     
+                    // Refills skip what this worker's distribution can't reach
+                    if let (Some(reach), Some(size), false) = (&reach, target_config.file_size, target_config.full_fill) {
+                        let span = self.config.workers.offset_range.filter(|_| target_index == 0).unwrap_or((0, size));
+                        file_target.set_fill_regions(reach.regions(span.0, span.1));
+                    }
+                    
+                    // Refills share the node's preparation limit with the other workers
+                    file_target.set_prep_limit(self.control.as_ref().and_then(|control| control.prep_limit().cloned()));
+                    
                     Box::new(file_target)
                 }
                 TargetType::BlockDevice => {
@@ -927,13 +1579,20 @@ impl Worker {
             // Check if file exists for read-only tests
             let file_exists = target_config.path.exists();
             let is_read_only = self.config.workload.write_percent == 0;
-            let should_create = self.config.workload.write_percent > 0 || (is_read_only && !file_exists);
+            // --readonly never creates anything
+            let should_create = target_config.target_type == TargetType::File
+                && !self.config.runtime.read_only
+                && (self.config.workload.write_percent > 0 || (is_read_only && !file_exists));
             
             let flags = OpenFlags {
                 direct: self.config.workload.direct,
                 sync: self.config.workload.sync,
                 create: should_create,
                 truncate: false,
+                noatime: self.config.workload.open_flags.noatime,
+                dsync: self.config.workload.open_flags.dsync,
+                rsync: self.config.workload.open_flags.rsync,
+                read_only: self.config.runtime.read_only,
             };
             
             let open_start = Instant::now();
@@ -950,6 +1609,10 @@ impl Worker {
             self.stats.metadata.open_ops.add(1);
             self.stats.metadata.open_latency.record(open_latency);
             
+            if self.config.workload.buffered_percent > 0 {
+                self.buffered_files.push(open_buffered(&target_config.path, flags)?);
+            }
+            
             // Apply fadvise hints if any are set
             let config_fadvise = &target_config.fadvise_flags;
             if config_fadvise.sequential
@@ -971,12 +1634,25 @@ impl Worker {
                     .context("Failed to apply fadvise hints")?;
             }
             
+            if self.config.workload.direct {
+                let target_alignment = target.dio_alignment();
+                target_alignment.check_workload(&target_config.path, &self.config.workload)?;
+                dio_alignment = Some(match dio_alignment {
+                    Some(current) => target_alignment.max(current),
+                    None => target_alignment,
+                });
+            }
+            
             self.targets.push(target);
         }
         
+
         // Smart auto-refill: If reads are requested and file is empty, auto-fill it
         // This prevents silent failures where reads from empty files return 0 bytes
-        if !self.targets.is_empty() && self.config.workload.read_percent > 0 {
+        // (block devices report no size through fstat and can't be filled)
+        if !self.targets.is_empty() && self.config.workload.read_percent > 0
+            && self.config.targets[0].target_type == TargetType::File
+        {
             let target_fd = self.targets[0].fd();
             
             // Check actual file size by reading metadata
@@ -994,6 +1670,10 @@ impl Worker {
                 let target_path = &self.config.targets[0].path;
                 let file_size = self.config.targets[0].file_size.unwrap_or(0);
                 
+                if self.config.runtime.read_only {
+                    anyhow::bail!("Cannot use empty file {} (--readonly never fills targets)",
+                        target_path.display());
+                }
                 if self.config.targets[0].no_refill {
                     // User explicitly disabled auto-refill, error out
                     eprintln!("\nError: Cannot read from empty file (auto-refill disabled)");
@@ -1023,6 +1703,7 @@ impl Worker {
                             crate::config::workload::VerifyPattern::Zeros => "zero",
                             crate::config::workload::VerifyPattern::Ones => "one",
                             crate::config::workload::VerifyPattern::Sequential => "sequential",
+                            crate::config::workload::VerifyPattern::Custom => "custom",
                         });
                     eprintln!("   File: {}", target_path.display());
                     eprintln!("   Size: {} bytes", file_size);
@@ -1039,6 +1720,7 @@ impl Worker {
                     }
                     
                     let refill_duration = refill_start.elapsed();
+                    self.stats.record_setup_phase("Auto-refill", refill_duration);
                     eprintln!("   ✅ File filled in {:.2}s", refill_duration.as_secs_f64());
                     eprintln!();
                     
@@ -1104,6 +1786,10 @@ impl Worker {
                 let target_path = &self.config.targets[0].path;
                 let file_size = self.config.targets[0].file_size.unwrap_or(0);
                 
+                if self.config.runtime.read_only {
+                    anyhow::bail!("Cannot use empty file {} (--readonly never fills targets)",
+                        target_path.display());
+                }
                 if self.config.targets[0].no_refill {
                     // User explicitly disabled auto-refill, error out with helpful message
                     eprintln!("\nError: mmap engine requires non-zero file size (auto-refill disabled)");
@@ -1132,6 +1818,7 @@ impl Worker {
                             crate::config::workload::VerifyPattern::Zeros => "zero",
                             crate::config::workload::VerifyPattern::Ones => "one",
                             crate::config::workload::VerifyPattern::Sequential => "sequential",
+                            crate::config::workload::VerifyPattern::Custom => "custom",
                         });
                     eprintln!("   File: {}", target_path.display());
                     eprintln!("   Size: {} bytes", file_size);
@@ -1148,19 +1835,28 @@ impl Worker {
                     }
                     
                     let refill_duration = refill_start.elapsed();
+                    self.stats.record_setup_phase("Auto-refill", refill_duration);
                     eprintln!("   ✅ File ready for mmap in {:.2}s", refill_duration.as_secs_f64());
                     eprintln!();
                 }
             }
         }
         
-        // Cache target fd and size to avoid trait call overhead in hot path
-        if !self.targets.is_empty() {
-            self.cached_target_fd = self.targets[0].fd();
-            self.cached_target_size = self.targets[0].size();
-        }
-        
-        Ok(())
+        // Cache target fds and sizes to avoid trait call overhead in hot path
+        let ost_stats = self.config.runtime.ost_stats && self.file_list.is_none();
+        self.cached_targets = self.targets.iter()
+            .enumerate()
+            .map(|(i, target)| CachedTarget {
+                fd: target.fd(),
+                size: target.size(),
+                stripes: if ost_stats { read_stripe_layout(target.fd()) } else { None },
+                buffered_fd: self.buffered_files.get(i).map(std::os::unix::io::AsRawFd::as_raw_fd),
+            })
+            .collect();
+        // Workers start at different targets so they spread over all of them
+        self.next_target = self.id % self.cached_targets.len().max(1);
+        
+        Ok(dio_alignment)
     }
     
     /// Close all targets
@@ -1178,20 +1874,26 @@ impl Worker {
             self.stats.metadata.close_ops.add(1);
             self.stats.metadata.close_latency.record(close_latency);
         }
+        self.buffered_files.clear();
         
         Ok(())
     }
     
     /// Check if worker should stop based on completion criteria
     fn should_stop(&self) -> bool {
+        // Replay limit applies on top of the completion mode
+        if self.reached_stop_at_op() {
+            return true;
+        }
+        
         match &self.config.workload.completion_mode {
-            CompletionMode::Duration { seconds } => {
-                if let Some(start) = self.start_time {
+            mode @ CompletionMode::Duration { .. } => {
+                if let (Some(start), Some(target)) = (self.start_time, mode.test_duration()) {
                     let elapsed = start.elapsed();
-                    let should_stop = elapsed >= Duration::from_secs(*seconds);
-                    if self.config.runtime.debug && self.operation_count % 10000 == 0 {
-                        eprintln!("DEBUG should_stop: Duration mode, elapsed={:.3}s, target={}s, should_stop={}", 
-                            elapsed.as_secs_f64(), seconds, should_stop);
+                    let should_stop = elapsed >= target;
+                    if self.operation_count.is_multiple_of(10000) {
+                        tracing::trace!("should_stop: Duration mode, elapsed={:.3}s, target={:.3}s, should_stop={}", 
+                            elapsed.as_secs_f64(), target.as_secs_f64(), should_stop);
                     }
                     should_stop
                 } else {
@@ -1209,8 +1911,8 @@ impl Worker {
                         let files_to_process = end - start;
                         let files_processed = self.operation_count;
                         let should_stop = files_processed >= files_to_process;
-                        if self.config.runtime.debug && self.operation_count % 1000 == 0 {
-                            eprintln!("DEBUG should_stop: RunUntilComplete (file list PARTITIONED), processed={}, target={}, should_stop={}", 
+                        if self.operation_count.is_multiple_of(1000) {
+                            tracing::trace!("should_stop: RunUntilComplete (file list PARTITIONED), processed={}, target={}, should_stop={}", 
                                 files_processed, files_to_process, should_stop);
                         }
                         return should_stop;
@@ -1219,14 +1921,19 @@ impl Worker {
                         let files_to_process = file_list.len();
                         let files_processed = self.operation_count;
                         let should_stop = files_processed >= files_to_process;
-                        if self.config.runtime.debug && self.operation_count % 1000 == 0 {
-                            eprintln!("DEBUG should_stop: RunUntilComplete (file list SHARED), processed={}, target={}, should_stop={}", 
+                        if self.operation_count.is_multiple_of(1000) {
+                            tracing::trace!("should_stop: RunUntilComplete (file list SHARED), processed={}, target={}, should_stop={}", 
                                 files_processed, files_to_process, should_stop);
                         }
                         return should_stop;
                     }
                 }
                 
+                // Append-grow: complete once the last append has been issued
+                if self.grow.is_some() {
+                    return self.grow_finished();
+                }
+                
                 // Original logic for single file mode
                 // Stop when we've written the target amount
                 // For partitioned distribution, target is the worker's region size
@@ -1242,8 +1949,8 @@ impl Worker {
                 };
                 
                 let should_stop = self.total_bytes_transferred >= target_size;
-                if self.config.runtime.debug && self.operation_count % 10000 == 0 {
-                    eprintln!("DEBUG should_stop: RunUntilComplete, transferred={}, target_size={}, should_stop={}", 
+                if self.operation_count.is_multiple_of(10000) {
+                    tracing::trace!("should_stop: RunUntilComplete, transferred={}, target_size={}, should_stop={}", 
                         self.total_bytes_transferred, target_size, should_stop);
                 }
                 should_stop
@@ -1254,8 +1961,14 @@ impl Worker {
     /// Select operation type based on read/write percentages
     #[inline(always)]
     fn select_operation_type(&mut self) -> OperationType {
+        let read_percent = self.control.as_ref()
+            .and_then(|control| control.read_percent())
+            .unwrap_or(self.config.workload.read_percent);
+        if let Some(ref split) = self.byte_split {
+            return split.next(read_percent);
+        }
         let roll = self.rng.gen_range(0..100);
-        if roll < self.config.workload.read_percent {
+        if roll < read_percent {
             OperationType::Read
         } else {
             OperationType::Write
@@ -1285,6 +1998,53 @@ impl Worker {
         }
     }
     
+    /// Claim the picked file, applying the busy-file policy on a collision
+    ///
+    /// Returns the index of the file to use, which is a different file when
+    /// the retry policy found a free one.
+    fn claim_file(&mut self, index: usize) -> usize {
+        let Some(claims) = self.file_claims.clone() else {
+            return index;
+        };
+        
+        // Release the previous pick first: a waiting worker then holds no
+        // claim, so waits can't form a cycle
+        self.file_claim = None;
+        if let Some(claim) = claims.try_claim(index) {
+            self.file_claim = Some(claim);
+            return index;
+        }
+        self.stats.record_file_collision();
+        
+        match self.config.targets[0].busy_file {
+            BusyFilePolicy::Proceed => index,
+            BusyFilePolicy::Retry => {
+                for _ in 0..BUSY_FILE_RETRIES.min(claims.len()) {
+                    let other = self.rng.gen_range(0..claims.len());
+                    if let Some(claim) = claims.try_claim(other) {
+                        self.file_claim = Some(claim);
+                        self.stats.record_file_retry();
+                        return other;
+                    }
+                }
+                // Every attempt was busy: use the original pick
+                index
+            }
+            BusyFilePolicy::Wait => {
+                let wait_start = Instant::now();
+                loop {
+                    if let Some(claim) = claims.try_claim(index) {
+                        self.file_claim = Some(claim);
+                        break;
+                    }
+                    std::thread::yield_now();
+                }
+                self.stats.record_file_wait(wait_start.elapsed());
+                index
+            }
+        }
+    }
+    
     /// Open a file from the file list
     ///
     /// Opens the file at the specified index and caches it for subsequent operations.
@@ -1315,17 +2075,84 @@ impl Worker {
         if self.config.workload.sync {
             flags.sync = true;
         }
+        flags.noatime = self.config.workload.open_flags.noatime;
+        flags.dsync = self.config.workload.open_flags.dsync;
+        flags.rsync = self.config.workload.open_flags.rsync;
+        flags.read_only = self.config.runtime.read_only;
         // Don't create - files already exist from layout generation
         flags.create = false;
         
         // Open the file
         target.open(flags)?;
+        self.apply_file_hints(file_index, &target)?;
         
         // Cache file info
         self.current_file_fd = target.fd();
         self.current_file_size = target.size();
         self.current_file = Some(Box::new(target));
         
+        if self.config.runtime.ost_stats {
+            let fd = self.current_file_fd;
+            self.current_stripes = match self.stripe_layouts.get(&file_index) {
+                Some(layout) => layout.clone(),
+                None => {
+                    let layout = read_stripe_layout(fd);
+                    self.stripe_layouts.insert(file_index, layout.clone());
+                    layout
+                }
+            };
+        }
+        
+        Ok(())
+    }
+    
+    /// Apply the configured fadvise/madvise hints to a file opened from the list
+    ///
+    /// Access-pattern hints (sequential, random, noreuse) belong to the open
+    /// file and are reapplied to every new descriptor. Page-cache hints
+    /// (willneed, dontneed) and madvise act on the file's cached pages or
+    /// shared mapping, so they are issued only the first time a file is opened.
+    fn apply_file_hints(&mut self, file_index: usize, target: &dyn Target) -> Result<()> {
+        use crate::target::FadviseFlags as TargetFadviseFlags;
+        
+        let target_config = &self.config.targets[0];
+        let fadvise = &target_config.fadvise_flags;
+        let madvise = &target_config.madvise_flags;
+        let mmap = self.config.workload.engine == EngineType::Mmap;
+        let page_cache_hints = fadvise.willneed || fadvise.dontneed
+            || (mmap && (madvise.sequential || madvise.random || madvise.willneed
+                || madvise.dontneed || madvise.hugepage || madvise.nohugepage));
+        let first_open = self.advised_files.insert(file_index);
+        
+        let flags = TargetFadviseFlags {
+            sequential: fadvise.sequential,
+            random: fadvise.random,
+            willneed: fadvise.willneed && first_open,
+            dontneed: fadvise.dontneed && first_open,
+            noreuse: fadvise.noreuse,
+        };
+        let any_fadvise = flags.sequential || flags.random || flags.willneed
+            || flags.dontneed || flags.noreuse;
+        let advise_mapping = mmap && page_cache_hints && first_open;
+        
+        if page_cache_hints && !first_open {
+            self.stats.record_cached_hints();
+        }
+        if !any_fadvise && !advise_mapping {
+            return Ok(());
+        }
+        
+        let hint_start = Instant::now();
+        if any_fadvise {
+            target.apply_fadvise(&flags)
+                .context("Failed to apply fadvise hints")?;
+        }
+        if advise_mapping {
+            self.engine.advise_target(target.fd(), madvise)
+                .context("Failed to apply madvise hints")?;
+        }
+        self.stats.record_hints(hint_start.elapsed());
+        
         Ok(())
     }
     
@@ -1335,44 +2162,168 @@ impl Worker {
     /// It does NOT poll for completions - that's done separately to allow batching.
     /// 
     /// Returns metadata about the in-flight operation for later completion processing.
-    fn prepare_and_submit_operation(&mut self, op_type: OperationType) -> Result<InFlightOp> {
-        // Select block size first (needs &mut self)
-        let block_size = self.select_block_size(op_type);
+    /// Returns `Ok(None)` without submitting when the buffer pool has no free
+    /// buffer for the op's size; the op is retried after completions. Fails if
+    /// no buffer is returned within `BUFFER_WAIT_TIMEOUT`.
+    fn prepare_and_submit_operation(&mut self, op_type: OperationType, block_size: usize) -> Result<Option<InFlightOp>> {
+        // Get buffer from the matching size class first, so a starved pool
+        // defers the op before any side effects (file selection, offsets)
+        let Some(buf_idx) = self.buffer_pool.get_sized(block_size) else {
+            let since = *self.starved_since.get_or_insert_with(Instant::now);
+            if since.elapsed() > BUFFER_WAIT_TIMEOUT {
+                self.starved_since = None;
+                anyhow::bail!(
+                    "No {} byte IO buffer became free within {}s (pool of {} buffers, {} free)",
+                    block_size, BUFFER_WAIT_TIMEOUT.as_secs(),
+                    self.buffer_pool.total_count(), self.buffer_pool.available_count()
+                );
+            }
+            self.deferred_op = Some((op_type, block_size));
+            return Ok(None);
+        };
+        if let Some(since) = self.starved_since.take() {
+            self.buffer_pool.record_wait(block_size, since.elapsed());
+        }
         
+        match self.submit_with_buffer(op_type, block_size, buf_idx) {
+            Ok(in_flight_op) => {
+                // Only a submission uses up its think time release
+                if let Some(ref mut schedule) = self.think_schedule {
+                    schedule.take();
+                }
+                Ok(Some(in_flight_op))
+            }
+            Err(e) => {
+                // Nothing was queued, so the buffer goes straight back
+                self.buffer_pool.return_buffer(buf_idx);
+                Err(e)
+            }
+        }
+    }
+    
+    /// Next operation to issue: a deferred (starved) op first, otherwise a new one
+    fn next_operation(&mut self) -> (OperationType, usize) {
+        if let Some(op) = self.deferred_op.take() {
+            return op;
+        }
+        let op_type = self.select_operation_type();
+        let block_size = self.select_block_size(op_type);
+        if let Some(ref mut split) = self.byte_split {
+            split.record(op_type, block_size);
+        }
+        (op_type, block_size)
+    }
+    
+    /// Time to hold back an operation of `bytes` (smoothed think time, paused or over the rate limit)
+    fn control_wait(&mut self, bytes: usize) -> Option<Duration> {
+        let now = Instant::now();
+        if let Some(wait) = self.think_schedule.as_ref().and_then(|schedule| schedule.wait(now)) {
+            return Some(wait);
+        }
+        if let Some(ref control) = self.control {
+            if control.is_paused() {
+                return Some(control::PAUSE_POLL_INTERVAL);
+            }
+            let Some((iops, bytes_per_sec)) = control.limits(self.control_index) else {
+                return Some(control::PAUSE_POLL_INTERVAL);
+            };
+            if let Some(wait) = self.pacer.wait(iops, bytes_per_sec, bytes as u64, now) {
+                return Some(wait);
+            }
+        }
+        None
+    }
+    
+    /// Wait out a held-back operation with nothing in flight
+    ///
+    /// Smoothed spin-mode think time spins, since sleep granularity would
+    /// undo the spacing; pauses and rate limits sleep.
+    fn hold_back(&self, wait: Duration) {
+        let wait = wait.min(control::PAUSE_POLL_INTERVAL);
+        let paused = self.control.as_ref().is_some_and(|control| control.is_paused());
+        match self.config.workload.think_time {
+            Some(ref think) if think.smooth && !paused => apply_think_time(think.mode, wait),
+            _ => std::thread::sleep(wait),
+        }
+    }
+    
+    /// Build and submit an IO operation using an already acquired buffer
+    fn submit_with_buffer(&mut self, op_type: OperationType, block_size: usize, buf_idx: usize) -> Result<InFlightOp> {
         // Handle file list mode vs single file mode
+        let mut selected_file = None;
+        let mut target_index = 0;
+        let mut buffered = false;
         let (target_fd, target_size) = if self.file_list.is_some() {
             // File list mode: select and open file
             if let Some(file_index) = self.select_file_index() {
+                let file_index = self.claim_file(file_index);
                 self.open_file_from_list(file_index)?;
+                selected_file = Some(file_index);
                 (self.current_file_fd, self.current_file_size)
             } else {
                 anyhow::bail!("Failed to select file from list");
             }
         } else {
-            // Single file mode: use cached target info
-            (self.cached_target_fd, self.cached_target_size)
+            // Target mode: use cached info of the next target
+            target_index = self.select_target();
+            let target = self.cached_targets.get(target_index)
+                .with_context(|| format!("Target {} is not open ({} open)", target_index, self.cached_targets.len()))?;
+            match target.buffered_fd {
+                // O_DIRECT/buffered mix: this share bypasses O_DIRECT
+                Some(fd) if self.rng.gen_range(0..100) < self.config.workload.buffered_percent => {
+                    buffered = true;
+                    (fd, target.size)
+                }
+                _ => (target.fd, target.size),
+            }
         };
         
-        let lock_mode = self.config.targets[0].lock_mode;
+        let lock_mode = self.config.targets[target_index].lock_mode;
         
         // Generate block number using distribution, then convert to byte offset
         // This ensures offsets are naturally aligned to block size (required for O_DIRECT)
         
-        let offset = if let Some((start_offset, end_offset)) = self.config.workers.offset_range {
+        let grow_offset = if op_type == OperationType::Write { self.next_grow_offset(block_size) } else { None };
+        let extend = matches!(grow_offset, Some((_, true)));
+        
+        // Reads also use the partial block at EOF when the file size isn't a
+        // multiple of the block size; they are clamped to the file end below
+        let blocks_in = |span: u64| if op_type == OperationType::Read {
+            span.div_ceil(block_size as u64)
+        } else {
+            span / block_size as u64
+        };
+        
+        let offset = if let Some((offset, _)) = grow_offset {
+            // Append-grow: EOF or a block of the grown file
+            offset
+        } else if let Some((start_offset, end_offset)) = self.partition_of(target_index, target_size, block_size as u64) {
             // Partitioned mode: constrain to assigned offset range
             let range_size = end_offset - start_offset;
-            let num_blocks = range_size / (block_size as u64);
+            let num_blocks = if end_offset >= target_size {
+                blocks_in(target_size.saturating_sub(start_offset))
+            } else {
+                range_size / (block_size as u64)
+            };
             let block_num = self.distribution.next_block(num_blocks);
             start_offset + (block_num * (block_size as u64))
         } else {
             // Shared mode: use full file
-            let num_blocks = target_size / (block_size as u64);
+            let num_blocks = blocks_in(target_size);
             let block_num = self.distribution.next_block(num_blocks);
             block_num * (block_size as u64)
         };
         
-        // Length is simply the block size (already aligned by design)
-        let length = block_size;
+        // Length is the block size (already aligned by design), except for a
+        // read crossing EOF
+        let mut length = block_size;
+        if op_type == OperationType::Read {
+            let direct_alignment = Some(self.direct_alignment).filter(|_| self.config.workload.direct && !buffered);
+            if let Some(clamped) = clamp_at_eof(offset, block_size, target_size, direct_alignment) {
+                self.stats.record_eof_clamp();
+                length = clamped;
+            }
+        }
         
         // Track block access statistics (only if heatmap enabled)
         // Note: Coverage and unique block tracking have ~5-10% performance overhead
@@ -1380,11 +2331,17 @@ impl Worker {
             let block_num = offset / (block_size as u64);
             self.stats.record_block_access(block_num);
             self.stats.record_unique_block(block_num);
+            if let Some(ref mut series) = self.heatmap_series {
+                series.record(block_num);
+            }
         }
         
-        // Get buffer from pool (remove .context() for hot path performance)
-        let buf_idx = self.buffer_pool.get()
-            .ok_or_else(|| anyhow::anyhow!("No buffers available"))?;
+        // Remapping moves the access, not what the heatmap shows: the
+        // distribution's logical offsets
+        let offset = match self.offset_remap {
+            Some((chunk, seed)) => crate::distribution::remap::remap_offset(offset, chunk, target_size, seed),
+            None => offset,
+        };
         
         // Determine actual length
         let length = {
@@ -1393,6 +2350,7 @@ impl Worker {
         };
         
         // Fill buffer with pattern data if writing (only for non-random patterns or verification)
+        let mut pattern_ptr = None;
         if op_type == OperationType::Write {
             let pattern = if self.config.runtime.verify {
                 // If verification is enabled, use verification pattern
@@ -1402,17 +2360,32 @@ impl Worker {
                 self.config.workload.write_pattern
             };
             
-            // Only fill buffer if NOT using random pattern (random buffers are pre-filled at init)
+            // Only fill buffer if NOT using random pattern (random buffers are pre-filled at init).
+            // Repeating patterns come pre-filled: the write reads from a shared pattern buffer.
             if pattern != VerifyPattern::Random || self.config.runtime.verify {
-                let buffer = self.buffer_pool.get_buffer_mut(buf_idx);
-                fill_buffer_for_verification(buffer, pattern, offset, length, self.id);
+                pattern_ptr = self.pattern_buffers.as_mut().and_then(|buffers| buffers.get(offset, length));
+                if pattern_ptr.is_none() {
+                    let buffer = self.buffer_pool.get_buffer_mut(buf_idx);
+                    let seed = self.config.runtime.verify_seed.unwrap_or(0);
+                    let payload = custom_payload(&self.config);
+                    fill_buffer_for_verification(buffer, pattern, payload, offset, length, seed);
+                }
             }
         }
         
-        // Get buffer pointer for IO
-        let buffer_ptr = {
-            let buffer = self.buffer_pool.get_buffer_mut(buf_idx);
-            buffer.as_mut_ptr()
+        // Stamp the write with its generation (the stamp makes the buffer this write's own)
+        if let (OperationType::Write, Some(ref mut order)) = (op_type, &mut self.write_order) {
+            let buffer = &mut self.buffer_pool.get_buffer_mut(buf_idx).as_mut_slice()[..length];
+            if let Some(ptr) = pattern_ptr.take() {
+                buffer.copy_from_slice(unsafe { std::slice::from_raw_parts(ptr, length) });
+            }
+            order.stamp(buffer, offset);
+        }
+        
+        // Get buffer pointer for IO (writes never modify the buffer, so a pattern buffer can be shared)
+        let buffer_ptr = match pattern_ptr {
+            Some(ptr) => ptr as *mut u8,
+            None => self.buffer_pool.get_buffer_mut(buf_idx).as_mut_ptr(),
         };
         
         // Acquire lock if needed
@@ -1428,7 +2401,7 @@ impl Worker {
                 FileLockMode::Full => TargetFileLockMode::Full,
             };
             
-            // Use current_file if in file list mode, otherwise use targets[0]
+            // Use current_file if in file list mode, otherwise the selected target
             let guard = if let Some(ref mut current_file) = self.current_file {
                 Some(current_file.lock(
                     target_lock_mode,
@@ -1436,7 +2409,7 @@ impl Worker {
                     length as u64,
                 )?)
             } else {
-                Some(self.targets[0].lock(
+                Some(self.targets[target_index].lock(
                     target_lock_mode,
                     offset,
                     length as u64,
@@ -1451,8 +2424,21 @@ impl Worker {
             None
         };
         
+        if op_type == OperationType::Write {
+            if let Some(ref mut journal) = self.write_journal {
+                let data = unsafe { std::slice::from_raw_parts(buffer_ptr as *const u8, length) };
+                journal.submitted(self.ops_submitted, offset, data)?;
+            }
+        }
+        
         // Record start time for latency measurement
         let io_start = FastInstant::now();
+        if self.config.workload.think_time.is_some() {
+            if let Some(last) = self.last_submit {
+                self.stats.record_submit_gap(io_start.duration_since(last));
+            }
+            self.last_submit = Some(io_start);
+        }
         
         // Build and submit IO operation
         let op = IOOperation {
@@ -1465,14 +2451,37 @@ impl Worker {
         };
         
         // Submit to engine (does NOT poll)
+        let call = self.cpu_tracker.as_ref().map(|tracker| tracker.begin());
         self.engine.submit(op)?;
+        if let (Some(ref mut tracker), Some(call)) = (&mut self.cpu_tracker, call) {
+            match op_type {
+                OperationType::Read => tracker.end(CallClass::Read, call),
+                OperationType::Write => tracker.end(CallClass::Write, call),
+                _ => {}
+            }
+        }
+        
+        let op_index = self.ops_submitted;
+        self.ops_submitted += 1;
+        if let Some(ref mut dump) = self.offset_dump {
+            dump.record(op_index, op_type, selected_file, offset, length)?;
+        }
         
         // Return metadata for completion processing
         Ok(InFlightOp {
             buf_idx,
             op_type,
             offset,
+            length,
             start_time: io_start,
+            extend,
+            op_index,
+            file_index: selected_file,
+            ost: match selected_file {
+                Some(_) => self.current_stripes.as_deref(),
+                None => self.cached_targets[target_index].stripes.as_deref(),
+            }.and_then(|layout| layout.ost_for(offset)),
+            buffered,
         })
     }
     
@@ -1483,20 +2492,34 @@ impl Worker {
     ///
     /// # Arguments
     ///
-    /// * `in_flight_ops` - Map of buf_idx to in-flight operations to match against completions
-    fn process_completions(&mut self, in_flight_ops: &mut HashMap<usize, InFlightOp>) -> Result<()> {
+    /// * `in_flight_ops` - In-flight operations by buf_idx to match against completions
+    fn process_completions(&mut self, in_flight_ops: &mut InFlightSlots<InFlightOp>) -> Result<()> {
         // Poll for completions
+        let call = self.cpu_tracker.as_ref().map(|tracker| tracker.begin());
         let completions = self.engine.poll_completions()?;
+        if let (Some(ref mut tracker), Some(call)) = (&mut self.cpu_tracker, call) {
+            tracker.end(CallClass::Reap, call);
+        }
+        
+        // Every completion of the batch was reaped now: one timestamp for all,
+        // so processing earlier completions doesn't add to later latencies
+        let io_end = FastInstant::now();
+        
+        // Think time owed for this batch, applied once all completions are
+        // recorded so it doesn't inflate the latency of the rest of the batch
+        let mut think_delay = Duration::ZERO;
+        
+        // Completions after the duration expired are stragglers being drained
+        let drained = self.past_drain_deadline();
 
         // Process each completion
         for completion in completions {
-            // Find the matching in-flight operation — O(1) via HashMap key lookup
+            // Find the matching in-flight operation — O(1) via its buffer's slot
             let buf_idx = completion.user_data as usize;
-            let in_flight_op = in_flight_ops.remove(&buf_idx)
+            let in_flight_op = in_flight_ops.remove(buf_idx)
                 .ok_or_else(|| anyhow::anyhow!("Completion for unknown operation"))?;
             
             // Calculate latency
-            let io_end = FastInstant::now();
             let io_latency = io_end.duration_since(in_flight_op.start_time);
             
             // Verify buffer if reading
@@ -1508,7 +2531,11 @@ impl Worker {
                     // Record verification attempt
                     self.stats.record_verification();
                     
-                    if !verify_buffer_after_verification(buffer, verify_pattern, in_flight_op.offset, bytes, self.id) {
+                    let seed = self.config.runtime.verify_seed.unwrap_or(0);
+                    let payload = custom_payload(&self.config);
+                    if !verify_buffer_after_verification(buffer, verify_pattern, payload, in_flight_op.offset, bytes, seed) {
+                        tracing::warn!("Verification failed for op {} (replay with --seed and --stop-at-op {})",
+                            in_flight_op.op_index, in_flight_op.op_index + 1);
                         self.stats.record_verification_failure();
                         self.stats.record_error();
                     }
@@ -1518,23 +2545,324 @@ impl Worker {
             // Return buffer to pool
             self.buffer_pool.return_buffer(in_flight_op.buf_idx);
             
+            if let (Some(ref mut journal), OperationType::Write, Ok(bytes)) =
+                (&mut self.write_journal, completion.op_type, &completion.result)
+            {
+                journal.completed(in_flight_op.op_index, *bytes)?;
+            }
+            
             // Record statistics
             match completion.result {
+                Ok(bytes) if drained => {
+                    let separate = self.config.runtime.drain_latency == DrainPolicy::Separate;
+                    self.stats.record_drained(bytes, io_latency, separate);
+                    if in_flight_op.extend {
+                        if let Some(ref mut grow) = self.grow {
+                            grow.landed(in_flight_op.offset, in_flight_op.length, bytes);
+                        }
+                    }
+                    self.total_bytes_transferred += bytes as u64;
+                    self.operation_count += 1;
+                }
                 Ok(bytes) => {
                     self.stats.record_io(completion.op_type, bytes, io_latency);
+                    if let (Some(ref mut files), Some(index)) = (&mut self.file_latency, in_flight_op.file_index) {
+                        files.record(index, io_latency);
+                    }
+                    if self.config.runtime.ost_stats {
+                        match in_flight_op.ost {
+                            Some(ost) => self.stats.record_ost_io(ost, bytes, io_latency),
+                            None => self.stats.record_unmapped_io(),
+                        }
+                    }
+                    if self.config.workload.buffered_percent > 0 {
+                        self.stats.record_io_path(in_flight_op.buffered, completion.op_type, bytes, io_latency);
+                    }
+                    if self.grow.is_some() && completion.op_type == OperationType::Write {
+                        self.stats.record_grow_write(in_flight_op.extend, bytes, io_latency);
+                    }
+                    if in_flight_op.extend {
+                        if let Some(ref mut grow) = self.grow {
+                            grow.landed(in_flight_op.offset, in_flight_op.length, bytes);
+                            grow.check_reached(self.start_time);
+                        }
+                    }
                     self.total_bytes_transferred += bytes as u64;
                     self.operation_count += 1;
+                    
+                    if let Some(ref mut ramp) = self.qd_ramp {
+                        ramp.record_latency(io_latency);
+                    }
+                    
+                    if let Some(ref think_time) = self.config.workload.think_time {
+                        if let Some(ref mut schedule) = self.think_schedule {
+                            // Smoothed: every op owes its share of the think time
+                            let share = think_time_for(think_time, io_latency) / think_time.apply_every_n_blocks as u32;
+                            schedule.owe(share, Instant::now());
+                        } else if self.operation_count.is_multiple_of(think_time.apply_every_n_blocks) {
+                            think_delay += think_time_for(think_time, io_latency);
+                        }
+                    }
                 }
                 Err(e) => {
                     self.stats.record_error();
+                    if in_flight_op.extend {
+                        if let Some(ref mut grow) = self.grow {
+                            grow.landed(in_flight_op.offset, in_flight_op.length, 0);
+                        }
+                    }
+                    if let (Some(ref mut files), Some(index)) = (&mut self.file_latency, in_flight_op.file_index) {
+                        files.record_error(index);
+                    }
                     return Err(e);
                 }
             }
         }
         
+        if !think_delay.is_zero() {
+            if let Some(ref think_time) = self.config.workload.think_time {
+                apply_think_time(think_time.mode, think_delay);
+            }
+        }
+        
         Ok(())
     }
     
+    /// Current in-flight limit
+    ///
+    /// Returns the ramp step's queue depth when qd_ramp is active, otherwise
+    /// the configured queue_depth.
+    #[inline(always)]
+    fn active_queue_depth(&self) -> usize {
+        match self.qd_ramp {
+            Some(ref ramp) => ramp.current_queue_depth(),
+            None => self.config.workload.queue_depth,
+        }
+    }
+    
+    /// Switch to the configured latency timer and measure its precision
+    fn start_timer(&mut self) {
+        if self.config.runtime.timer == TimerSource::Tsc {
+            if let Err(e) = fast_time::enable_tsc() {
+                if self.id == 0 {
+                    tracing::warn!("--timer tsc unavailable ({}), timing with clock_gettime", e);
+                }
+            }
+        }
+        self.stats.set_timer(TimerCheck::measure());
+    }
+    
+    /// Take the page fault baseline (called at test start)
+    ///
+    /// Only the mmap engine turns accesses into page faults, so other engines
+    /// skip the per-snapshot getrusage call.
+    fn start_fault_tracking(&mut self) {
+        if self.config.workload.engine == EngineType::Mmap {
+            self.fault_baseline = PageFaults::current_thread();
+        }
+    }
+    
+    /// Publish a live statistics snapshot to the node service, if it reads them
+    fn publish_snapshot(&self) {
+        if self.shared_snapshots.is_none() && self.snapshot_queue.is_none() {
+            return;
+        }
+        let avg_latency_us = self.stats.io_latency().mean().as_micros() as f64;
+        let snapshot = StatsSnapshot {
+            read_ops: self.stats.read_ops(),
+            write_ops: self.stats.write_ops(),
+            read_bytes: self.stats.read_bytes(),
+            write_bytes: self.stats.write_bytes(),
+            errors: self.stats.errors(),
+            avg_latency_us,
+            // Separate read/write latency histograms (for detailed analysis)
+            read_latency: self.stats.read_latency().clone(),
+            write_latency: self.stats.write_latency().clone(),
+            // Metadata operation counters (just atomic reads, very fast)
+            metadata_open_ops: self.stats.metadata.open_ops.get(),
+            metadata_close_ops: self.stats.metadata.close_ops.get(),
+            metadata_stat_ops: self.stats.metadata.stat_ops.get(),
+            metadata_setattr_ops: self.stats.metadata.setattr_ops.get(),
+            metadata_mkdir_ops: self.stats.metadata.mkdir_ops.get(),
+            metadata_rmdir_ops: self.stats.metadata.rmdir_ops.get(),
+            metadata_unlink_ops: self.stats.metadata.unlink_ops.get(),
+            metadata_rename_ops: self.stats.metadata.rename_ops.get(),
+            metadata_readdir_ops: self.stats.metadata.readdir_ops.get(),
+            metadata_fsync_ops: self.stats.metadata.fsync_ops.get(),
+            // Metadata latency histograms (clone for time-series analysis)
+            // Cost: ~9 KB memcpy every 1K ops = <0.01% overhead
+            metadata_open_latency: self.stats.metadata.open_latency.clone(),
+            metadata_close_latency: self.stats.metadata.close_latency.clone(),
+            metadata_stat_latency: self.stats.metadata.stat_latency.clone(),
+            metadata_setattr_latency: self.stats.metadata.setattr_latency.clone(),
+            metadata_mkdir_latency: self.stats.metadata.mkdir_latency.clone(),
+            metadata_rmdir_latency: self.stats.metadata.rmdir_latency.clone(),
+            metadata_unlink_latency: self.stats.metadata.unlink_latency.clone(),
+            metadata_rename_latency: self.stats.metadata.rename_latency.clone(),
+            metadata_readdir_latency: self.stats.metadata.readdir_latency.clone(),
+            metadata_fsync_latency: self.stats.metadata.fsync_latency.clone(),
+            page_faults: self.faults_since_start(),
+            submit_gaps: self.stats.submit_gaps(),
+        };
+        if let Some(ref queue) = self.snapshot_queue {
+            queue.send(snapshot);
+        } else if let Some(ref shared) = self.shared_snapshots {
+            if let Ok(mut snapshots) = shared.lock() {
+                snapshots[self.snapshot_slot] = snapshot;
+            }
+        }
+    }
+    
+    /// Page faults taken by this worker thread since test start
+    fn faults_since_start(&self) -> Option<PageFaults> {
+        let baseline = self.fault_baseline?;
+        PageFaults::current_thread().map(|now| now.since(&baseline))
+    }
+    
+    /// Store the test's page faults in the worker's statistics
+    fn finish_fault_tracking(&mut self) {
+        if let Some(faults) = self.faults_since_start() {
+            self.stats.set_page_faults(faults);
+        }
+    }
+    
+    /// Offset of the next append-grow write and whether it extends the file
+    ///
+    /// Writes are appended at EOF until another one would pass the size
+    /// target; from then on they overwrite blocks of the grown file using the
+    /// configured access pattern. Returns None outside append-grow mode.
+    fn next_grow_offset(&mut self, block_size: usize) -> Option<(u64, bool)> {
+        let grow = self.grow.as_mut()?;
+        let length = block_size as u64;
+        
+        if grow.appending && grow.next_offset + length <= grow.target {
+            let offset = grow.next_offset;
+            grow.next_offset += length;
+            grow.in_flight += 1;
+            grow.appending = grow.next_offset + length <= grow.target;
+            return Some((offset, true));
+        }
+        grow.appending = false;
+        grow.check_reached(self.start_time);
+        
+        let num_blocks = (grow.next_offset / length).max(1);
+        Some((self.distribution.next_block(num_blocks) * length, false))
+    }
+    
+    /// Whether a run-until-complete append-grow has issued its last append
+    fn grow_finished(&self) -> bool {
+        matches!(self.config.workload.completion_mode, CompletionMode::RunUntilComplete)
+            && self.grow.as_ref().is_some_and(|grow| !grow.appending)
+    }
+    
+    /// Whether this worker reached its own completion condition
+    ///
+    /// Duration runs are ended by the coordinator's STOP, so only byte-count and
+    /// run-until-complete modes complete on their own.
+    fn reached_completion(&self) -> bool {
+        self.reached_stop_at_op()
+            || (!matches!(self.config.workload.completion_mode, CompletionMode::Duration { .. })
+                && self.should_stop())
+    }
+    
+    /// Whether this worker has submitted the `--stop-at-op` number of operations
+    fn reached_stop_at_op(&self) -> bool {
+        self.config.runtime.stop_at_op
+            .is_some_and(|limit| self.ops_submitted >= limit)
+    }
+    
+    /// Pick the target of the next operation: in turn for random access,
+    /// the worker's own target for sequential streams
+    #[inline]
+    fn select_target(&mut self) -> usize {
+        let index = self.next_target;
+        if self.cached_targets.len() > 1 && self.config.workload.random {
+            self.next_target = (index + 1) % self.cached_targets.len();
+        }
+        index
+    }
+    
+    /// The worker's offset range of target `index` (partitioned distribution)
+    ///
+    /// The range is assigned for the first target. Other targets get the
+    /// same share of their own size, in whole blocks, so workers stay apart
+    /// on a smaller target too; one too small to give the worker a block is
+    /// used whole.
+    fn partition_of(&self, index: usize, size: u64, block_size: u64) -> Option<(u64, u64)> {
+        let (start, end) = self.config.workers.offset_range?;
+        let first_size = self.cached_targets.first().map_or(size, |target| target.size);
+        if index == 0 || size == first_size {
+            return Some((start, end));
+        }
+        let scale = |offset: u64| {
+            let offset = (offset as u128 * size as u128 / first_size.max(1) as u128) as u64;
+            offset / block_size * block_size
+        };
+        let range = (scale(start), if end >= first_size { size } else { scale(end) });
+        Some(range).filter(|&(start, end)| end.saturating_sub(start) >= block_size)
+    }
+    
+    /// Fd of the first target (-1 before targets are opened)
+    fn first_target_fd(&self) -> i32 {
+        self.cached_targets.first().map_or(-1, |target| target.fd)
+    }
+    
+    /// Store the file size reached in append-grow mode
+    fn finish_grow_tracking(&mut self) {
+        if let Some(ref grow) = self.grow {
+            let final_size = fd_size(self.first_target_fd()).unwrap_or(grow.next_offset);
+            self.stats.set_grow_progress(final_size, grow.reached_secs);
+        }
+    }
+    
+    /// Key the per-file latency counters by path (--file-latency-top)
+    fn finish_file_latency(&mut self) {
+        if let (Some(ref files), Some(ref list), Some(top)) =
+            (&self.file_latency, &self.file_list, self.config.runtime.file_latency_top)
+        {
+            self.stats.set_file_latency(files.to_stats(list, self.config.runtime.file_latency_by_dir, top));
+        }
+    }
+    
+    /// Create the queue depth ramp controller (called at test start)
+    fn start_qd_ramp(&mut self) {
+        if let Some(ref ramp_config) = self.config.workload.qd_ramp {
+            self.qd_ramp = Some(qd_ramp::QdRampController::new(
+                ramp_config.clone(),
+                self.config.workload.queue_depth,
+                Instant::now(),
+            ));
+        }
+    }
+    
+    /// Advance the queue depth ramp if the current step is complete
+    fn update_qd_ramp(&mut self) {
+        if let Some(ref mut ramp) = self.qd_ramp {
+            if ramp.is_finished() {
+                return;
+            }
+            let total_ops = self.stats.total_ops();
+            let total_bytes = self.stats.total_bytes();
+            if ramp.tick(Instant::now(), total_ops, total_bytes) {
+                tracing::debug!("queue depth ramp -> QD={}{}",
+                    ramp.current_queue_depth(),
+                    if ramp.is_finished() { " (optimal)" } else { "" });
+            }
+        }
+    }
+    
+    /// Store the queue depth ramp outcome in the worker's statistics
+    fn finish_qd_ramp(&mut self) {
+        if let Some(ramp) = self.qd_ramp.take() {
+            let result = ramp.into_result(
+                Instant::now(),
+                self.stats.total_ops(),
+                self.stats.total_bytes(),
+            );
+            self.stats.set_qd_ramp(result);
+        }
+    }
+    
     /// Select block size based on operation type and IO patterns
     #[inline(always)]
     fn select_block_size(&mut self, op_type: OperationType) -> usize {
@@ -1569,42 +2897,179 @@ impl Worker {
         patterns.last().unwrap().block_size as usize
     }
     
-    /// Apply think time delay
-    fn apply_think_time(&self, config: &ThinkTimeConfig, io_latency: Duration) {
-        let duration = if let Some(pct) = config.adaptive_percent {
-            // Adaptive: percentage of IO latency
-            io_latency.mul_f64(pct as f64 / 100.0)
-        } else {
-            // Fixed duration
-            Duration::from_micros(config.duration_us)
-        };
-        
-        match config.mode {
-            ThinkTimeMode::Sleep => {
-                std::thread::sleep(duration);
-            }
-            ThinkTimeMode::Spin => {
-                let start = Instant::now();
-                while start.elapsed() < duration {
-                    std::hint::spin_loop();
-                }
+    /// Get worker ID
+    pub fn id(&self) -> usize {
+        self.id
+    }
+}
+
+/// Seed stream for read/write, block size and file selection
+const SEED_STREAM_OPS: u64 = 0;
+
+/// Seed stream for the offset distribution
+const SEED_STREAM_OFFSETS: u64 = 1;
+
+/// Other files tried by `--busy-file retry` before using a busy pick
+const BUSY_FILE_RETRIES: usize = 8;
+
+/// Longest a submission waits for the buffer pool before the worker fails
+const BUFFER_WAIT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Striping layout of an open file for --ost-stats (None if not on Lustre)
+fn read_stripe_layout(fd: std::os::unix::io::RawFd) -> Option<Arc<StripeLayout>> {
+    match StripeLayout::read(fd) {
+        Ok(layout) => layout.map(Arc::new),
+        Err(e) => {
+            tracing::warn!("Failed to read striping layout: {}", e);
+            None
+        }
+    }
+}
+
+/// Seed of one random stream of one worker, derived from the test seed
+///
+/// Mixed with SplitMix64 so neighbouring worker IDs get unrelated streams.
+fn worker_seed(seed: u64, worker_id: usize, stream: u64) -> u64 {
+    let mut z = seed
+        ^ (worker_id as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ stream.wrapping_mul(0xD1B5_4A32_D192_ED03);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Length of a read at `offset` that crosses EOF
+///
+/// None if the read ends before EOF, or starts at or past it (an empty
+/// file). Buffered reads end at the file end. O_DIRECT reads, given the
+/// target's `direct_alignment`, end at the file end rounded up to it; the
+/// kernel stops them at EOF.
+fn clamp_at_eof(offset: u64, length: usize, file_size: u64, direct_alignment: Option<u64>) -> Option<usize> {
+    if offset + length as u64 <= file_size || offset >= file_size {
+        return None;
+    }
+    let remaining = file_size - offset;
+    Some(match direct_alignment {
+        Some(alignment) => remaining.next_multiple_of(alignment.max(1)).min(length as u64) as usize,
+        None => remaining as usize,
+    })
+}
+
+/// Think time owed after an IO that completed in `io_latency`
+///
+/// The fixed duration plus, in adaptive mode, a percentage of the IO's own
+/// completion latency, so pacing scales with device speed.
+fn think_time_for(config: &ThinkTimeConfig, io_latency: Duration) -> Duration {
+    let base = Duration::from_micros(config.duration_us);
+    match config.adaptive_percent {
+        Some(pct) => base + io_latency.mul_f64(pct as f64 / 100.0),
+        None => base,
+    }
+}
+
+/// Apply a think time delay
+fn apply_think_time(mode: ThinkTimeMode, duration: Duration) {
+    match mode {
+        ThinkTimeMode::Sleep => {
+            std::thread::sleep(duration);
+        }
+        ThinkTimeMode::Spin => {
+            let start = Instant::now();
+            while start.elapsed() < duration {
+                std::hint::spin_loop();
             }
         }
     }
+}
+
+/// Open a second fd of a target without O_DIRECT for the O_DIRECT/buffered mix
+///
+/// Keeps the other open flags of the O_DIRECT fd; never creates the target.
+fn open_buffered(path: &std::path::Path, flags: crate::target::OpenFlags) -> Result<std::fs::File> {
+    use std::os::unix::fs::OpenOptionsExt;
     
-    /// Get worker ID
-    pub fn id(&self) -> usize {
-        self.id
+    let flags = crate::target::OpenFlags { direct: false, create: false, truncate: false, ..flags };
+    let mut options = std::fs::OpenOptions::new();
+    options.read(true).write(!flags.read_only);
+    let custom_flags = flags.custom_flags();
+    if custom_flags != 0 {
+        options.custom_flags(custom_flags);
     }
+    options.open(path)
+        .with_context(|| format!("Failed to open buffered fd of target: {}", path.display()))
+}
+
+/// Current size of an open file
+fn fd_size(fd: std::os::unix::io::RawFd) -> std::io::Result<u64> {
+    let mut stat: libc::stat = unsafe { std::mem::zeroed() };
+    if unsafe { libc::fstat(fd, &mut stat) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(stat.st_size as u64)
+}
+
+/// One period of the write data pattern, if it repeats
+///
+/// Writes use the verification pattern when verifying. The random pattern
+/// has no period: without verification its buffers are pre-filled once, with
+/// verification each write is seeded by its offset.
+/// Buffer sizes of a worker's IO buffer pool and the number of buffers of each
+///
+/// Twice the queue depth, split in proportion to how often each IO size is used.
+fn buffer_size_classes(config: &Config) -> Vec<(usize, usize)> {
+    let pool_size = (config.workload.queue_depth * 2) as f64;
+    io_size_shares(&config.workload)
+        .into_iter()
+        .map(|(size, share)| (size, ((pool_size * share).ceil() as usize).max(1)))
+        .collect()
+}
+
+/// Most memory a worker uses for buffers: its IO buffer pool and, for
+/// repeating write patterns, the pre-filled pattern buffers
+pub fn buffer_memory_bytes(config: &Config) -> u64 {
+    let pool: u64 = buffer_size_classes(config).iter()
+        .map(|&(size, count)| (size * count) as u64)
+        .sum();
+    let patterns = if config.workload.write_percent > 0 && write_pattern_period(config).is_some() {
+        crate::util::pattern_buffers::MAX_CACHED_BYTES as u64
+    } else {
+        0
+    };
+    pool + patterns
+}
+
+fn write_pattern_period(config: &Config) -> Option<Vec<u8>> {
+    let pattern = if config.runtime.verify {
+        config.runtime.verify_pattern.unwrap_or(VerifyPattern::Sequential)
+    } else {
+        config.workload.write_pattern
+    };
+    match pattern {
+        VerifyPattern::Zeros => Some(vec![0]),
+        VerifyPattern::Ones => Some(vec![0xFF]),
+        VerifyPattern::Sequential => Some((0..=255).collect()),
+        VerifyPattern::Custom => config.workload.custom_pattern.as_ref().map(|custom| custom.payload.clone()),
+        VerifyPattern::Random => None,
+    }
+}
+
+/// Payload of the custom pattern (empty when none is configured)
+fn custom_payload(config: &Config) -> &[u8] {
+    config.workload.custom_pattern.as_ref().map_or(&[], |custom| custom.payload.as_slice())
 }
 
 /// Fill buffer with verification pattern for write operations
+///
+/// `seed` is mixed into the random pattern so data left by an earlier run
+/// (written with another seed) fails verification. `payload` is used by the
+/// custom pattern.
 fn fill_buffer_for_verification(
     buffer: &mut crate::util::buffer::AlignedBuffer,
     pattern: VerifyPattern,
+    payload: &[u8],
     offset: u64,
     length: usize,
-    _worker_id: usize,
+    seed: u64,
 ) {
     use crate::util::verification::{fill_buffer, VerificationPattern as VerifyPat};
     
@@ -1615,20 +3080,22 @@ fn fill_buffer_for_verification(
     let verify_pattern = match pattern {
         VerifyPattern::Zeros => VerifyPat::Zeros,
         VerifyPattern::Ones => VerifyPat::Ones,
-        VerifyPattern::Random => VerifyPat::Random(offset),
+        VerifyPattern::Random => VerifyPat::Random(offset ^ seed),
         VerifyPattern::Sequential => VerifyPat::Sequential,
+        VerifyPattern::Custom => VerifyPat::Repeat(payload),
     };
     
     fill_buffer(slice, verify_pattern, offset);
 }
 
-/// Verify buffer after read operation
+/// Verify buffer after read operation (`seed` and `payload` as in `fill_buffer_for_verification`)
 fn verify_buffer_after_verification(
     buffer: &mut crate::util::buffer::AlignedBuffer,
     pattern: VerifyPattern,
+    payload: &[u8],
     offset: u64,
     bytes: usize,
-    worker_id: usize,
+    seed: u64,
 ) -> bool {
     use crate::util::verification::{verify_buffer, VerificationPattern as VerifyPat, VerificationResult};
     
@@ -1639,22 +3106,62 @@ fn verify_buffer_after_verification(
     let verify_pattern = match pattern {
         VerifyPattern::Zeros => VerifyPat::Zeros,
         VerifyPattern::Ones => VerifyPat::Ones,
-        VerifyPattern::Random => VerifyPat::Random(offset),
+        VerifyPattern::Random => VerifyPat::Random(offset ^ seed),
         VerifyPattern::Sequential => VerifyPat::Sequential,
+        VerifyPattern::Custom => VerifyPat::Repeat(payload),
     };
     
     match verify_buffer(slice, verify_pattern, offset) {
         VerificationResult::Success => true,
         VerificationResult::Failure { offset: fail_offset, expected, actual } => {
-            eprintln!(
-                "Worker {}: Verification failure at buffer offset {}: expected 0x{:02x}, got 0x{:02x}",
-                worker_id, fail_offset, expected, actual
+            tracing::warn!(
+                "Verification failure at file offset {} + {}: expected 0x{:02x}, got 0x{:02x}",
+                offset, fail_offset, expected, actual
             );
             false
         }
     }
 }
 
+/// Fraction of operations using each IO size, sorted by size
+///
+/// Reads and writes contribute in proportion to read_percent/write_percent;
+/// an op type without a distribution uses the fixed block size.
+fn io_size_shares(workload: &WorkloadConfig) -> Vec<(usize, f64)> {
+    let block_size = workload.block_size as usize;
+    let mut shares: Vec<(usize, f64)> = Vec::new();
+    let mut add = |size: usize, share: f64| {
+        match shares.iter_mut().find(|(s, _)| *s == size) {
+            Some(entry) => entry.1 += share,
+            None => shares.push((size, share)),
+        }
+    };
+    
+    for (percent, distribution) in [
+        (workload.read_percent, &workload.read_distribution),
+        (workload.write_percent, &workload.write_distribution),
+    ] {
+        if percent == 0 {
+            continue;
+        }
+        let op_share = percent as f64 / 100.0;
+        let total_weight: u32 = distribution.iter().map(|p| p.weight as u32).sum();
+        if total_weight == 0 {
+            add(block_size, op_share);
+        } else {
+            for pattern in distribution {
+                add(pattern.block_size as usize, op_share * pattern.weight as f64 / total_weight as f64);
+            }
+        }
+    }
+    
+    if shares.is_empty() {
+        shares.push((block_size, 1.0));
+    }
+    shares.sort_by_key(|(size, _)| *size);
+    shares
+}
+
 // Extension trait for WorkloadConfig to convert to EngineConfig
 #[allow(dead_code)]
 trait WorkloadConfigExt {
@@ -1677,6 +3184,7 @@ mod tests {
     use super::*;
     use crate::config::{TargetConfig, TargetType, WorkerConfig, OutputConfig, RuntimeConfig};
     use crate::config::workload::{IOPattern, AccessPattern};
+    use crate::distributed::protocol::ControlMessage;
     use std::path::PathBuf;
     
     fn create_test_config() -> Config {
@@ -1688,7 +3196,7 @@ mod tests {
                 write_distribution: vec![],
                 block_size: 4096,
                 queue_depth: 32,
-                completion_mode: CompletionMode::Duration { seconds: 1 },
+                completion_mode: CompletionMode::Duration { seconds: 1, millis: 0 },
                 random: false,
                 distribution: DistributionType::Uniform,
                 think_time: None,
@@ -1698,6 +3206,18 @@ mod tests {
                 heatmap: false,
                 heatmap_buckets: 100,
                 write_pattern: VerifyPattern::Random,
+                qd_ramp: None,
+                mmap: MmapOptions::default(),
+                buffer_hugepages: BufferHugePages::None,
+                grow_to: None,
+                open_flags: OpenFlagOptions::default(),
+                custom_pattern: None,
+                rw_split_by: RwSplitBy::Ops,
+                offset_start: None,
+                worker_groups: Vec::new(),
+                buffered_percent: 0,
+                remap_offsets: None,
+                no_engine_fallback: false,
             },
             targets: vec![
                 TargetConfig {
@@ -1713,11 +3233,15 @@ mod tests {
                     fadvise_flags: FadviseFlags::default(),
                     madvise_flags: MadviseFlags::default(),
                     lock_mode: FileLockMode::None,
+                    busy_file: crate::config::workload::BusyFilePolicy::Proceed,
                     preallocate: false,
+                    prealloc_mode: PreallocMode::default(),
                     truncate_to_size: false,
                     refill: false,
                     refill_pattern: VerifyPattern::Random,
                     no_refill: false,
+                    region: None,
+                    full_fill: false,
                 }
             ],
             workers: WorkerConfig::default(),
@@ -1743,7 +3267,7 @@ mod tests {
     #[test]
     fn test_create_distribution_uniform() {
         let config = create_test_config();
-        let dist = Worker::create_distribution(&config.workload);
+        let dist = Worker::create_distribution(&config.workload, None);
         assert!(dist.is_ok());
     }
     
@@ -1751,7 +3275,7 @@ mod tests {
     fn test_create_distribution_zipf() {
         let mut config = create_test_config();
         config.workload.distribution = DistributionType::Zipf { theta: 1.2 };
-        let dist = Worker::create_distribution(&config.workload);
+        let dist = Worker::create_distribution(&config.workload, None);
         assert!(dist.is_ok());
     }
     
@@ -1847,5 +3371,235 @@ mod tests {
         let size = worker.select_block_size(OperationType::Read);
         assert!(size == 4096 || size == 65536);
     }
+    
+    #[test]
+    fn test_io_size_shares() {
+        let mut config = create_test_config();
+        config.workload.read_percent = 60;
+        config.workload.write_percent = 40;
+        config.workload.read_distribution = vec![
+            IOPattern { weight: 50, access: AccessPattern::Random, block_size: 4096 },
+            IOPattern { weight: 50, access: AccessPattern::Sequential, block_size: 131072 },
+        ];
+        
+        // Writes use the fixed block size, which coincides with the small reads
+        let shares = io_size_shares(&config.workload);
+        assert_eq!(shares.len(), 2);
+        assert_eq!(shares[0].0, 4096);
+        assert!((shares[0].1 - 0.7).abs() < 1e-9);
+        assert_eq!(shares[1].0, 131072);
+        assert!((shares[1].1 - 0.3).abs() < 1e-9);
+        
+        // Queue depth 32 -> 64 buffers split 45/20 (rounded up per class)
+        let pool = Worker::create_buffer_pool(&config, 4096).unwrap();
+        assert_eq!(pool.total_count(), 45 + 20);
+        assert_eq!(pool.buffer_size(), 131072);
+    }
+    
+    #[test]
+    fn test_next_grow_offset() {
+        let mut config = create_test_config();
+        config.workload.read_percent = 0;
+        config.workload.write_percent = 100;
+        config.workload.completion_mode = CompletionMode::RunUntilComplete;
+        config.workload.grow_to = Some(3 * 4096 + 100);
+        let mut worker = Worker::new(0, Arc::new(config)).unwrap();
+        worker.grow = Some(GrowState { next_offset: 4096, target: 3 * 4096 + 100, appending: true, in_flight: 0, short: false, reached_secs: None });
+        
+        // Appends continue from EOF; the last one that fits ends appending
+        assert_eq!(worker.next_grow_offset(4096), Some((4096, true)));
+        assert!(!worker.grow_finished());
+        assert_eq!(worker.next_grow_offset(4096), Some((8192, true)));
+        assert!(worker.grow_finished());
+        
+        // Target is reached only once both appends completed
+        let grow = worker.grow.as_mut().unwrap();
+        grow.landed(4096, 4096, 4096);
+        grow.check_reached(None);
+        assert!(grow.reached_secs.is_none());
+        grow.landed(8192, 4096, 4096);
+        grow.check_reached(None);
+        assert!(grow.reached_secs.is_some());
+        
+        // Later writes overwrite the grown file
+        let (offset, extend) = worker.next_grow_offset(4096).unwrap();
+        assert!(!extend);
+        assert!(offset < 3 * 4096);
+        assert!(worker.next_grow_offset(4096).is_some());
+        
+        // A short append ends growth where its data ended
+        let mut grow = GrowState { next_offset: 4096, target: 1 << 20, appending: true, in_flight: 0, short: false, reached_secs: None };
+        grow.next_offset += 2 * 4096;
+        grow.in_flight += 2;
+        grow.landed(4096, 4096, 1000);
+        grow.landed(8192, 4096, 4096);
+        grow.check_reached(None);
+        assert_eq!(grow.next_offset, 5096);
+        assert!(!grow.appending);
+        assert!(grow.reached_secs.is_none());
+    }
+    
+    #[test]
+    fn test_select_target() {
+        let mut config = create_test_config();
+        config.workload.random = true;
+        let mut worker = Worker::new(0, Arc::new(config.clone())).unwrap();
+        let target = |fd| CachedTarget { fd, size: 1 << 20, stripes: None, buffered_fd: None };
+        worker.cached_targets = vec![target(3), target(4), target(5)];
+        worker.next_target = 1;
+        
+        // Random access takes the targets in turn
+        let picks: Vec<usize> = (0..4).map(|_| worker.select_target()).collect();
+        assert_eq!(picks, [1, 2, 0, 1]);
+        
+        // A sequential stream stays on its target
+        config.workload.random = false;
+        worker.config = Arc::new(config);
+        assert_eq!(worker.select_target(), 2);
+        assert_eq!(worker.select_target(), 2);
+    }
+    
+    #[test]
+    fn test_buffered_percent_fd() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        file.as_file().set_len(1 << 20).unwrap();
+        
+        // Read one block and report whether it went through the buffered fd
+        let read_once = |worker: &mut Worker| -> Result<bool> {
+            let mut in_flight_ops = InFlightSlots::with_capacity(worker.buffer_pool.total_count());
+            let op = worker.prepare_and_submit_operation(OperationType::Read, 4096)?.unwrap();
+            let buffered = op.buffered;
+            in_flight_ops.insert(op.buf_idx, op);
+            while !in_flight_ops.is_empty() {
+                worker.process_completions(&mut in_flight_ops)?;
+            }
+            Ok(buffered)
+        };
+        
+        for buffered_percent in [0, 100] {
+            let mut config = create_test_config();
+            config.targets[0].path = file.path().to_path_buf();
+            config.workload.buffered_percent = buffered_percent;
+            let mut worker = Worker::new(0, Arc::new(config)).unwrap();
+            worker.init_engine_and_targets().unwrap();
+            assert_eq!(worker.cached_targets[0].buffered_fd.is_some(), buffered_percent > 0);
+            assert_eq!(read_once(&mut worker).unwrap(), buffered_percent == 100);
+            
+            // Break the fd that must not be used: the read still succeeds
+            if buffered_percent == 100 {
+                worker.cached_targets[0].fd = -1;
+            } else {
+                worker.cached_targets[0].buffered_fd = Some(-1);
+            }
+            assert_eq!(read_once(&mut worker).unwrap(), buffered_percent == 100);
+            
+            // Break the fd that must be used: the read fails
+            let target = &mut worker.cached_targets[0];
+            if buffered_percent == 100 {
+                target.buffered_fd = Some(-1);
+            } else {
+                target.fd = -1;
+            }
+            assert!(read_once(&mut worker).is_err());
+            
+            // Per-path stats count the reads that went through
+            match worker.stats.io_paths() {
+                Some(paths) => assert_eq!((paths.direct.ops(), paths.buffered.ops()), (0, 2)),
+                None => assert_eq!(buffered_percent, 0),
+            }
+        }
+    }
+    
+    #[test]
+    fn test_partition_of() {
+        let mut config = create_test_config();
+        config.workers.offset_range = Some((512 << 10, 768 << 10));
+        let mut worker = Worker::new(0, Arc::new(config)).unwrap();
+        let target = |size| CachedTarget { fd: 3, size, stripes: None, buffered_fd: None };
+        worker.cached_targets = vec![target(1 << 20), target(256 << 10), target(8192)];
+        
+        // The same quarter of a smaller target, in whole blocks
+        assert_eq!(worker.partition_of(0, 1 << 20, 4096), Some((512 << 10, 768 << 10)));
+        assert_eq!(worker.partition_of(1, 256 << 10, 4096), Some((128 << 10, 192 << 10)));
+        assert_eq!(worker.partition_of(2, 8192, 4096), None);
+    }
+    
+    #[test]
+    fn test_think_time_for() {
+        let mut think = ThinkTimeConfig {
+            duration_us: 50,
+            mode: ThinkTimeMode::Sleep,
+            apply_every_n_blocks: 1,
+            adaptive_percent: None,
+            smooth: false,
+        };
+        assert_eq!(think_time_for(&think, Duration::from_millis(2)), Duration::from_micros(50));
+        
+        // Adaptive part follows the IO's own latency
+        think.adaptive_percent = Some(25);
+        assert_eq!(think_time_for(&think, Duration::from_micros(200)), Duration::from_micros(100));
+        assert_eq!(think_time_for(&think, Duration::from_millis(2)), Duration::from_micros(550));
+    }
+    
+    #[test]
+    fn test_control_wait() {
+        let config = Arc::new(create_test_config());
+        let control = Arc::new(control::RunControl::new(3, None, None));
+        let mut workers: Vec<Worker> = (0..3).map(|index| {
+            let mut worker = Worker::new(index, config.clone()).unwrap();
+            worker.set_control(control.clone(), index);
+            worker
+        }).collect();
+        assert_eq!(workers[0].control_wait(4096), None);
+        
+        control.apply(&ControlMessage::Pause);
+        assert_eq!(workers[0].control_wait(4096), Some(control::PAUSE_POLL_INTERVAL));
+        control.apply(&ControlMessage::Resume);
+        assert_eq!(workers[0].control_wait(4096), None);
+        
+        // 2 IOPS over 3 workers: the third has no share and holds
+        control.apply(&ControlMessage::Rate { iops: 2, bytes_per_sec: 0 });
+        assert_eq!(workers[2].control_wait(4096), Some(control::PAUSE_POLL_INTERVAL));
+        assert_eq!(workers[1].control_wait(4096), None);
+        assert!(workers[1].control_wait(4096).is_some_and(|wait| wait > Duration::from_millis(900)));
+        
+        // 3001 IOPS: the first worker takes the remainder
+        control.apply(&ControlMessage::Rate { iops: 3001, bytes_per_sec: 0 });
+        assert_eq!((0..3).map(|index| control.limits(index).unwrap().0).collect::<Vec<_>>(), [1001, 1000, 1000]);
+        control.apply(&ControlMessage::Rate { iops: 0, bytes_per_sec: 0 });
+        assert_eq!(workers[2].control_wait(4096), None);
+    }
+    
+    #[test]
+    fn test_clamp_at_eof() {
+        // 10000-byte file, 4K blocks: the third block holds the last 1808 bytes
+        assert_eq!(clamp_at_eof(4096, 4096, 10000, None), None);
+        assert_eq!(clamp_at_eof(8192, 4096, 10000, None), Some(1808));
+        // O_DIRECT rounds up to the alignment
+        assert_eq!(clamp_at_eof(8192, 4096, 10000, Some(512)), Some(2048));
+        assert_eq!(clamp_at_eof(8192, 4096, 10000, Some(4096)), Some(4096));
+        assert_eq!(clamp_at_eof(0, 4096, 0, None), None);
+    }
+    
+    #[test]
+    fn test_seeded_sequence_is_reproducible() {
+        let mut config = create_test_config();
+        config.workload.read_percent = 50;
+        config.workload.write_percent = 50;
+        config.workload.random = true;
+        config.runtime.seed = Some(42);
+        let config = Arc::new(config);
+        
+        let sequence = |id: usize| {
+            let mut worker = Worker::new(id, config.clone()).unwrap();
+            (0..64)
+                .map(|_| (worker.select_operation_type(), worker.distribution.next_block(1 << 20)))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(sequence(0), sequence(0));
+        assert_ne!(sequence(0), sequence(1));
+        
+        // Op selection and offsets use independent streams
+        assert_ne!(worker_seed(42, 0, SEED_STREAM_OPS), worker_seed(42, 0, SEED_STREAM_OFFSETS));
+    }
 }
-
@@ -0,0 +1,105 @@
+//! Intended offset dump for reproducing a run
+//!
+//! With `--seed`, every worker's operation sequence (op type, block size,
+//! file and offset) is a pure function of the seed and the worker ID, so a
+//! verification failure can be replayed with `--stop-at-op`. `OffsetDump`
+//! records that sequence as it is submitted, one line per operation:
+//!
+//! ```text
+//! # op_index op file offset length
+//! 0 write - 1048576 4096
+//! 1 read 12 0 4096
+//! ```
+//!
+//! `file` is the index into the layout file list, or `-` for the single
+//! target. Each worker writes its own file (see [`dump_path`]).
+
+use crate::engine::OperationType;
+use crate::Result;
+use anyhow::Context;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// Per-worker writer of submitted operations
+pub struct OffsetDump {
+    writer: BufWriter<File>,
+    path: PathBuf,
+}
+
+impl OffsetDump {
+    /// Create the dump file for a worker (truncating any previous run)
+    pub fn create(path: &Path, worker_id: usize) -> Result<Self> {
+        let path = dump_path(path, worker_id);
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let file = File::create(&path)
+            .with_context(|| format!("Failed to create offset dump {}", path.display()))?;
+        let mut writer = BufWriter::new(file);
+        writeln!(writer, "# op_index op file offset length")?;
+        Ok(Self { writer, path })
+    }
+
+    /// Append one submitted operation
+    pub fn record(
+        &mut self,
+        op_index: u64,
+        op_type: OperationType,
+        file_index: Option<usize>,
+        offset: u64,
+        length: usize,
+    ) -> Result<()> {
+        match file_index {
+            Some(index) => writeln!(self.writer, "{} {} {} {} {}", op_index, op_type, index, offset, length)?,
+            None => writeln!(self.writer, "{} {} - {} {}", op_index, op_type, offset, length)?,
+        }
+        Ok(())
+    }
+
+    /// Flush the dump to disk
+    pub fn finish(&mut self) -> Result<()> {
+        self.writer.flush()
+            .with_context(|| format!("Failed to write offset dump {}", self.path.display()))
+    }
+}
+
+/// Dump file of a worker: `offsets.txt` becomes `offsets.worker-<id>.txt`
+pub fn dump_path(path: &Path, worker_id: usize) -> PathBuf {
+    let stem = path.file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "offsets".to_string());
+    let name = match path.extension() {
+        Some(ext) => format!("{}.worker-{}.{}", stem, worker_id, ext.to_string_lossy()),
+        None => format!("{}.worker-{}", stem, worker_id),
+    };
+    path.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dump_path() {
+        assert_eq!(dump_path(Path::new("/tmp/offsets.txt"), 3),
+                   PathBuf::from("/tmp/offsets.worker-3.txt"));
+        assert_eq!(dump_path(Path::new("run1/offsets"), 0),
+                   PathBuf::from("run1/offsets.worker-0"));
+    }
+
+    #[test]
+    fn test_record() {
+        let path = std::env::temp_dir().join(format!("iopulse_offsets_{}.txt", std::process::id()));
+        let mut dump = OffsetDump::create(&path, 1).unwrap();
+        dump.record(0, OperationType::Write, None, 8192, 4096).unwrap();
+        dump.record(1, OperationType::Read, Some(5), 0, 4096).unwrap();
+        dump.finish().unwrap();
+
+        let written = dump_path(&path, 1);
+        let text = std::fs::read_to_string(&written).unwrap();
+        assert_eq!(text, "# op_index op file offset length\n0 write - 8192 4096\n1 read 5 0 4096\n");
+        std::fs::remove_file(&written).unwrap();
+    }
+}
@@ -0,0 +1,400 @@
+//! Dynamic queue depth ramp
+//!
+//! Finds the "knee" of the latency/throughput curve by starting each worker at
+//! QD=1 and doubling the in-flight limit at fixed intervals. Every step records
+//! throughput and p99 latency; the ramp stops when:
+//!
+//! - **Latency threshold**: step p99 exceeds `max_latency_us`
+//! - **Throughput plateau**: throughput gain over the previous step is below `plateau_percent`
+//! - **Ceiling reached**: the configured queue_depth has been measured
+//!
+//! The worker then holds the optimal queue depth for the rest of the test.
+//!
+//! # Example
+//!
+//! ```
+//! use iopulse::config::workload::QdRampConfig;
+//! use iopulse::worker::qd_ramp::QdRampController;
+//! use std::time::Instant;
+//!
+//! let ramp = QdRampController::new(QdRampConfig::default(), 64, Instant::now());
+//! assert_eq!(ramp.current_queue_depth(), 1);
+//! ```
+
+use crate::config::workload::QdRampConfig;
+use crate::stats::simple_histogram::SimpleHistogram;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Measurements for a single queue depth step
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QdRampStep {
+    /// In-flight limit during this step
+    pub queue_depth: usize,
+    /// Operations per second
+    pub iops: f64,
+    /// Bytes per second
+    pub throughput_bps: f64,
+    /// 99th percentile latency (microseconds)
+    pub p99_latency_us: u64,
+    /// Mean latency (microseconds)
+    pub mean_latency_us: u64,
+}
+
+/// Why the ramp stopped
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum QdRampStopReason {
+    /// p99 latency exceeded the configured limit
+    LatencyThreshold,
+    /// Doubling queue depth no longer improved throughput
+    ThroughputPlateau,
+    /// The queue depth ceiling was reached
+    MaxQueueDepth,
+    /// The test ended before any stop criterion was met
+    TestEnded,
+}
+
+impl std::fmt::Display for QdRampStopReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QdRampStopReason::LatencyThreshold => write!(f, "p99 latency limit exceeded"),
+            QdRampStopReason::ThroughputPlateau => write!(f, "throughput plateau"),
+            QdRampStopReason::MaxQueueDepth => write!(f, "queue depth ceiling reached"),
+            QdRampStopReason::TestEnded => write!(f, "test ended during ramp"),
+        }
+    }
+}
+
+/// Complete ramp outcome for a worker (or merged across workers)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QdRampResult {
+    /// Per-step measurements in ramp order
+    pub steps: Vec<QdRampStep>,
+    /// Identified optimal queue depth
+    pub optimal_queue_depth: usize,
+    /// Criterion that ended the ramp
+    pub stop_reason: QdRampStopReason,
+}
+
+impl QdRampResult {
+    /// Merge another worker's ramp result into this one
+    ///
+    /// Steps are aligned by index: IOPS and throughput are summed, latency
+    /// takes the worst (max) value. The optimal queue depth is the smaller of
+    /// the two, since the first worker to hit the knee bounds the device.
+    pub fn merge(&mut self, other: &QdRampResult) {
+        for (i, other_step) in other.steps.iter().enumerate() {
+            if let Some(step) = self.steps.get_mut(i) {
+                step.iops += other_step.iops;
+                step.throughput_bps += other_step.throughput_bps;
+                step.p99_latency_us = step.p99_latency_us.max(other_step.p99_latency_us);
+                step.mean_latency_us = step.mean_latency_us.max(other_step.mean_latency_us);
+            } else {
+                self.steps.push(other_step.clone());
+            }
+        }
+
+        if other.optimal_queue_depth < self.optimal_queue_depth {
+            self.optimal_queue_depth = other.optimal_queue_depth;
+            self.stop_reason = other.stop_reason;
+        }
+    }
+}
+
+/// Per-worker ramp state machine
+///
+/// The worker feeds every completion latency into `record_latency()` and
+/// periodically calls `tick()` with its cumulative counters. The controller
+/// owns the current in-flight limit the worker must respect.
+#[derive(Debug)]
+pub struct QdRampController {
+    config: QdRampConfig,
+    max_queue_depth: usize,
+    current_queue_depth: usize,
+    step_start: Instant,
+    step_start_ops: u64,
+    step_start_bytes: u64,
+    step_latency: SimpleHistogram,
+    steps: Vec<QdRampStep>,
+    outcome: Option<(usize, QdRampStopReason)>,
+}
+
+impl QdRampController {
+    /// Create a new ramp starting at QD=1
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Ramp parameters
+    /// * `max_queue_depth` - Ramp ceiling (the configured queue_depth)
+    /// * `now` - Start of the first step
+    pub fn new(config: QdRampConfig, max_queue_depth: usize, now: Instant) -> Self {
+        Self {
+            config,
+            max_queue_depth: max_queue_depth.max(1),
+            current_queue_depth: 1,
+            step_start: now,
+            step_start_ops: 0,
+            step_start_bytes: 0,
+            step_latency: SimpleHistogram::new(),
+            steps: Vec::new(),
+            outcome: None,
+        }
+    }
+
+    /// Current in-flight limit
+    #[inline]
+    pub fn current_queue_depth(&self) -> usize {
+        self.current_queue_depth
+    }
+
+    /// Whether the ramp has settled on an optimal queue depth
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        self.outcome.is_some()
+    }
+
+    /// Record a completion latency for the current step
+    #[inline]
+    pub fn record_latency(&mut self, latency: Duration) {
+        if self.outcome.is_none() {
+            self.step_latency.record(latency);
+        }
+    }
+
+    /// Advance the ramp if the current step has run its full duration
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - Current time
+    /// * `total_ops` - Cumulative completed operations for this worker
+    /// * `total_bytes` - Cumulative bytes transferred for this worker
+    ///
+    /// # Returns
+    ///
+    /// `true` if the in-flight limit changed.
+    pub fn tick(&mut self, now: Instant, total_ops: u64, total_bytes: u64) -> bool {
+        if self.outcome.is_some() {
+            return false;
+        }
+
+        let elapsed = now.duration_since(self.step_start);
+        if elapsed < Duration::from_secs(self.config.step_seconds) {
+            return false;
+        }
+
+        let step = self.close_step(elapsed, total_ops, total_bytes);
+        let previous = self.steps.last().cloned();
+        self.steps.push(step.clone());
+
+        let previous_qd = previous.as_ref()
+            .map(|p| p.queue_depth)
+            .unwrap_or(step.queue_depth);
+
+        if let Some(max_us) = self.config.max_latency_us {
+            if step.p99_latency_us > max_us {
+                return self.finish(previous_qd, QdRampStopReason::LatencyThreshold);
+            }
+        }
+
+        if let Some(ref prev) = previous {
+            let gain_percent = if prev.throughput_bps > 0.0 {
+                (step.throughput_bps - prev.throughput_bps) / prev.throughput_bps * 100.0
+            } else {
+                100.0
+            };
+            if gain_percent < self.config.plateau_percent {
+                return self.finish(prev.queue_depth, QdRampStopReason::ThroughputPlateau);
+            }
+        }
+
+        if self.current_queue_depth >= self.max_queue_depth {
+            return self.finish(self.current_queue_depth, QdRampStopReason::MaxQueueDepth);
+        }
+
+        self.current_queue_depth = (self.current_queue_depth * 2).min(self.max_queue_depth);
+        self.reset_step(now, total_ops, total_bytes);
+        true
+    }
+
+    /// Finalize the ramp at the end of the test
+    ///
+    /// If the ramp never settled, the partial step is recorded (when it saw any
+    /// IO) and the step with the highest throughput is reported as optimal.
+    pub fn into_result(mut self, now: Instant, total_ops: u64, total_bytes: u64) -> QdRampResult {
+        let (optimal_queue_depth, stop_reason) = match self.outcome {
+            Some(outcome) => outcome,
+            None => {
+                let elapsed = now.duration_since(self.step_start);
+                if total_ops > self.step_start_ops && !elapsed.is_zero() {
+                    let step = self.close_step(elapsed, total_ops, total_bytes);
+                    self.steps.push(step);
+                }
+                let best = self.steps.iter()
+                    .max_by(|a, b| a.throughput_bps.total_cmp(&b.throughput_bps))
+                    .map(|s| s.queue_depth)
+                    .unwrap_or(self.current_queue_depth);
+                (best, QdRampStopReason::TestEnded)
+            }
+        };
+
+        QdRampResult {
+            steps: self.steps,
+            optimal_queue_depth,
+            stop_reason,
+        }
+    }
+
+    fn close_step(&self, elapsed: Duration, total_ops: u64, total_bytes: u64) -> QdRampStep {
+        let secs = elapsed.as_secs_f64();
+        QdRampStep {
+            queue_depth: self.current_queue_depth,
+            iops: (total_ops - self.step_start_ops) as f64 / secs,
+            throughput_bps: (total_bytes - self.step_start_bytes) as f64 / secs,
+            p99_latency_us: self.step_latency.percentile(99.0).as_micros() as u64,
+            mean_latency_us: self.step_latency.mean().as_micros() as u64,
+        }
+    }
+
+    fn reset_step(&mut self, now: Instant, total_ops: u64, total_bytes: u64) {
+        self.step_start = now;
+        self.step_start_ops = total_ops;
+        self.step_start_bytes = total_bytes;
+        self.step_latency.reset();
+    }
+
+    fn finish(&mut self, optimal_queue_depth: usize, reason: QdRampStopReason) -> bool {
+        let changed = optimal_queue_depth != self.current_queue_depth;
+        self.current_queue_depth = optimal_queue_depth;
+        self.outcome = Some((optimal_queue_depth, reason));
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(max_latency_us: Option<u64>) -> QdRampConfig {
+        QdRampConfig {
+            step_seconds: 1,
+            max_latency_us,
+            plateau_percent: 5.0,
+        }
+    }
+
+    /// Run one step: record `ops` completions at `latency_us` over one second
+    fn run_step(ramp: &mut QdRampController, start: Instant, step: u32, ops: &mut u64, step_ops: u64, latency_us: u64) -> bool {
+        for _ in 0..step_ops {
+            ramp.record_latency(Duration::from_micros(latency_us));
+        }
+        *ops += step_ops;
+        ramp.tick(start + Duration::from_secs(step as u64), *ops, *ops * 4096)
+    }
+
+    #[test]
+    fn test_ramp_doubles_until_ceiling() {
+        let start = Instant::now();
+        let mut ramp = QdRampController::new(config(None), 8, start);
+        let mut ops = 0;
+
+        assert!(run_step(&mut ramp, start, 1, &mut ops, 1000, 100));
+        assert_eq!(ramp.current_queue_depth(), 2);
+        assert!(run_step(&mut ramp, start, 2, &mut ops, 2000, 100));
+        assert_eq!(ramp.current_queue_depth(), 4);
+        assert!(run_step(&mut ramp, start, 3, &mut ops, 4000, 100));
+        assert_eq!(ramp.current_queue_depth(), 8);
+        assert!(!run_step(&mut ramp, start, 4, &mut ops, 8000, 100));
+        assert!(ramp.is_finished());
+
+        let result = ramp.into_result(start + Duration::from_secs(5), ops, ops * 4096);
+        assert_eq!(result.steps.len(), 4);
+        assert_eq!(result.optimal_queue_depth, 8);
+        assert_eq!(result.stop_reason, QdRampStopReason::MaxQueueDepth);
+    }
+
+    #[test]
+    fn test_ramp_stops_on_plateau() {
+        let start = Instant::now();
+        let mut ramp = QdRampController::new(config(None), 64, start);
+        let mut ops = 0;
+
+        run_step(&mut ramp, start, 1, &mut ops, 1000, 100);
+        run_step(&mut ramp, start, 2, &mut ops, 2000, 100);
+        // QD=4 gives only 1% more throughput than QD=2
+        assert!(run_step(&mut ramp, start, 3, &mut ops, 2020, 200));
+
+        assert!(ramp.is_finished());
+        assert_eq!(ramp.current_queue_depth(), 2);
+        let result = ramp.into_result(start + Duration::from_secs(10), ops, ops * 4096);
+        assert_eq!(result.optimal_queue_depth, 2);
+        assert_eq!(result.stop_reason, QdRampStopReason::ThroughputPlateau);
+    }
+
+    #[test]
+    fn test_ramp_stops_on_latency() {
+        let start = Instant::now();
+        let mut ramp = QdRampController::new(config(Some(500)), 64, start);
+        let mut ops = 0;
+
+        run_step(&mut ramp, start, 1, &mut ops, 1000, 100);
+        run_step(&mut ramp, start, 2, &mut ops, 2000, 200);
+        run_step(&mut ramp, start, 3, &mut ops, 4000, 2000);
+
+        assert!(ramp.is_finished());
+        let result = ramp.into_result(start + Duration::from_secs(10), ops, ops * 4096);
+        assert_eq!(result.optimal_queue_depth, 2);
+        assert_eq!(result.stop_reason, QdRampStopReason::LatencyThreshold);
+    }
+
+    #[test]
+    fn test_ramp_waits_for_step_duration() {
+        let start = Instant::now();
+        let mut ramp = QdRampController::new(config(None), 64, start);
+
+        assert!(!ramp.tick(start + Duration::from_millis(500), 100, 409600));
+        assert_eq!(ramp.current_queue_depth(), 1);
+    }
+
+    #[test]
+    fn test_ramp_test_ended_picks_best_step() {
+        let start = Instant::now();
+        let mut ramp = QdRampController::new(config(None), 64, start);
+        let mut ops = 0;
+
+        run_step(&mut ramp, start, 1, &mut ops, 1000, 100);
+
+        let result = ramp.into_result(start + Duration::from_millis(1500), ops + 1500, (ops + 1500) * 4096);
+        assert_eq!(result.steps.len(), 2);
+        assert_eq!(result.optimal_queue_depth, 2);
+        assert_eq!(result.stop_reason, QdRampStopReason::TestEnded);
+    }
+
+    #[test]
+    fn test_ramp_result_merge() {
+        let step = |qd, iops, p99| QdRampStep {
+            queue_depth: qd,
+            iops,
+            throughput_bps: iops * 4096.0,
+            p99_latency_us: p99,
+            mean_latency_us: p99 / 2,
+        };
+
+        let mut a = QdRampResult {
+            steps: vec![step(1, 1000.0, 100), step(2, 2000.0, 150)],
+            optimal_queue_depth: 2,
+            stop_reason: QdRampStopReason::MaxQueueDepth,
+        };
+        let b = QdRampResult {
+            steps: vec![step(1, 1100.0, 120)],
+            optimal_queue_depth: 1,
+            stop_reason: QdRampStopReason::ThroughputPlateau,
+        };
+
+        a.merge(&b);
+        assert_eq!(a.steps[0].iops, 2100.0);
+        assert_eq!(a.steps[0].p99_latency_us, 120);
+        assert_eq!(a.steps[1].iops, 2000.0);
+        assert_eq!(a.optimal_queue_depth, 1);
+        assert_eq!(a.stop_reason, QdRampStopReason::ThroughputPlateau);
+    }
+}
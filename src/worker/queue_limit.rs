@@ -0,0 +1,120 @@
+//! Queue depth the target and engine can use
+//!
+//! `--queue-depth` is only checked against 1024, but lower limits apply at
+//! run time. Without a check they surface as confusing engine errors or as
+//! a queue that never fills:
+//!
+//! - **Device queue**: a block device accepts `nr_requests` requests per
+//!   hardware queue; more in flight only wait in the block layer
+//! - **libaio contexts**: `io_setup` fails once the system's contexts
+//!   reserve more than `fs.aio-max-nr` events (`fs.aio-nr` are in use)
+//! - **Ring setup**: io_uring and libaio setup can fail for lack of memory
+//!   (locked memory limit) or kernel resources
+//! - **Synchronous engines**: sync and mmap run one IO at a time
+//!   (`--engine threadpool` runs sync IO at a real queue depth)
+//!
+//! Each worker probes the device and aio limits before creating its engine
+//! and clamps the queue depth to them. If the engine still can't be set up,
+//! the queue depth is halved until it can. Clamping warns once and the
+//! results report the effective queue depth.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Queue depth a worker ran with, when lower than configured
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueueDepthLimit {
+    /// Configured queue depth
+    pub configured: usize,
+    /// Queue depth used (the lowest of all workers when merged)
+    pub effective: usize,
+    /// What limited it
+    pub reasons: Vec<String>,
+}
+
+impl QueueDepthLimit {
+    pub fn new(configured: usize) -> Self {
+        Self { configured, effective: configured, reasons: Vec::new() }
+    }
+
+    /// Lower the queue depth to `limit` if it is below the current one
+    pub fn clamp(&mut self, limit: usize, reason: String) {
+        let limit = limit.max(1);
+        if limit < self.effective {
+            self.effective = limit;
+            self.reasons.push(reason);
+        }
+    }
+
+    /// Whether any limit applied
+    pub fn is_clamped(&self) -> bool {
+        self.effective < self.configured
+    }
+
+    /// Merge another worker's limit: the lowest queue depth and all reasons
+    pub fn merge(&mut self, other: &QueueDepthLimit) {
+        self.effective = self.effective.min(other.effective);
+        for reason in &other.reasons {
+            if !self.reasons.contains(reason) {
+                self.reasons.push(reason.clone());
+            }
+        }
+    }
+}
+
+/// Requests the device holding `path` queues per hardware queue (sysfs `nr_requests`)
+pub fn device_queue_limit(path: &Path) -> Option<usize> {
+    let disk = crate::util::device_link::disk_dir(path)?;
+    std::fs::read_to_string(disk.join("queue/nr_requests")).ok()?
+        .trim()
+        .parse()
+        .ok()
+        .filter(|&requests| requests > 0)
+}
+
+/// libaio events that can still be reserved (`fs.aio-max-nr` - `fs.aio-nr`)
+pub fn aio_events_available() -> Option<usize> {
+    let read = |name: &str| -> Option<usize> {
+        std::fs::read_to_string(Path::new("/proc/sys/fs").join(name)).ok()?.trim().parse().ok()
+    };
+    Some(read("aio-max-nr")?.saturating_sub(read("aio-nr")?))
+}
+
+/// Whether an engine setup error is a resource limit a smaller queue may fit
+pub fn is_resource_error(error: &anyhow::Error) -> bool {
+    error.chain()
+        .filter_map(|cause| cause.downcast_ref::<std::io::Error>())
+        .filter_map(std::io::Error::raw_os_error)
+        .any(|errno| errno == libc::EAGAIN || errno == libc::ENOMEM)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clamp_and_merge() {
+        let mut limit = QueueDepthLimit::new(512);
+        limit.clamp(1023, "device queue".to_string());
+        assert!(!limit.is_clamped());
+        limit.clamp(255, "device queue (nr_requests 255)".to_string());
+        limit.clamp(300, "aio".to_string());
+        assert_eq!(limit.effective, 255);
+        assert_eq!(limit.reasons.len(), 1);
+
+        let mut other = QueueDepthLimit::new(512);
+        other.clamp(0, "engine setup".to_string());
+        assert_eq!(other.effective, 1);
+        limit.merge(&other);
+        assert_eq!(limit.effective, 1);
+        assert_eq!(limit.reasons, ["device queue (nr_requests 255)", "engine setup"]);
+    }
+
+    #[test]
+    fn test_is_resource_error() {
+        let again = anyhow::Error::from(std::io::Error::from_raw_os_error(libc::EAGAIN)).context("io_setup failed");
+        assert!(is_resource_error(&again));
+        let denied = anyhow::Error::from(std::io::Error::from_raw_os_error(libc::EPERM));
+        assert!(!is_resource_error(&denied));
+    }
+}
@@ -0,0 +1,88 @@
+//! Read/write split by bytes
+//!
+//! By default each operation is a read with probability `read_percent`, so
+//! the split holds for operation counts. With different read and write block
+//! sizes the byte split drifts far from it: 50% reads of 4k and 50% writes of
+//! 1M move 99.6% of the bytes as writes. With `--rw-split-by bytes` the
+//! worker tracks the bytes it submitted per class and sends each operation to
+//! the class that is below its share.
+//!
+//! # Example
+//!
+//! ```
+//! use iopulse::engine::OperationType;
+//! use iopulse::worker::rw_split::ByteSplit;
+//!
+//! let mut split = ByteSplit::default();
+//! split.record(OperationType::Write, 1024 * 1024);
+//!
+//! // 50% of bytes as reads: reads are behind until they catch up
+//! assert_eq!(split.next(50), OperationType::Read);
+//! ```
+
+use crate::engine::OperationType;
+
+/// Bytes submitted per class, steering the next operation
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ByteSplit {
+    read_bytes: u64,
+    write_bytes: u64,
+}
+
+impl ByteSplit {
+    /// Class of the next operation to keep reads at `read_percent` of the bytes
+    #[inline]
+    pub fn next(&self, read_percent: u8) -> OperationType {
+        match read_percent {
+            0 => OperationType::Write,
+            100.. => OperationType::Read,
+            _ => {
+                let total = self.read_bytes as u128 + self.write_bytes as u128;
+                if self.read_bytes as u128 * 100 <= total * read_percent as u128 {
+                    OperationType::Read
+                } else {
+                    OperationType::Write
+                }
+            }
+        }
+    }
+
+    /// Count a submitted operation
+    #[inline]
+    pub fn record(&mut self, op_type: OperationType, bytes: usize) {
+        match op_type {
+            OperationType::Read => self.read_bytes += bytes as u64,
+            OperationType::Write => self.write_bytes += bytes as u64,
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_byte_split_with_mixed_sizes() {
+        // 70% of bytes as reads, 4k reads vs 64k writes
+        let mut split = ByteSplit::default();
+        let (mut reads, mut writes) = (0u64, 0u64);
+        for _ in 0..10_000 {
+            match split.next(70) {
+                OperationType::Read => {
+                    split.record(OperationType::Read, 4096);
+                    reads += 4096;
+                }
+                _ => {
+                    split.record(OperationType::Write, 65536);
+                    writes += 65536;
+                }
+            }
+        }
+        let read_share = reads as f64 / (reads + writes) as f64 * 100.0;
+        assert!((read_share - 70.0).abs() < 1.0, "read share {:.2}%", read_share);
+
+        assert_eq!(ByteSplit::default().next(0), OperationType::Write);
+        assert_eq!(ByteSplit::default().next(100), OperationType::Read);
+    }
+}
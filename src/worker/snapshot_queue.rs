@@ -0,0 +1,119 @@
+//! Per-worker live statistics queues (thread per core)
+//!
+//! By default workers publish live statistics into one vector behind a
+//! mutex, which the node service's heartbeat locks to aggregate them. At
+//! high worker counts and IOPS, workers contend for that lock with each
+//! other and with the heartbeat. With `--thread-per-core` every worker owns
+//! a core and a single-producer, single-consumer queue to the service
+//! instead: a worker never waits to publish, and the heartbeat drains the
+//! queues and keeps each worker's latest snapshot.
+//!
+//! Snapshots are cumulative, so only the latest one matters: each queue is
+//! a single slot the worker swaps its newest snapshot into, superseding one
+//! the service hasn't collected yet.
+//!
+//! # Example
+//!
+//! ```
+//! use iopulse::worker::snapshot_queue::queues;
+//! use iopulse::worker::StatsSnapshot;
+//!
+//! let (senders, mut collector) = queues(2, &StatsSnapshot::default());
+//! senders[1].send(StatsSnapshot { read_ops: 10, ..Default::default() });
+//! assert_eq!(collector.collect()[1].read_ops, 10);
+//! ```
+
+use crate::worker::StatsSnapshot;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+use std::sync::Arc;
+
+/// One worker's queue: the latest snapshot not collected yet, or null
+///
+/// The pointer always comes from `Box::into_raw`, and whoever swaps it out
+/// owns it.
+#[derive(Debug, Default)]
+struct Slot(AtomicPtr<StatsSnapshot>);
+
+impl Slot {
+    /// Store a snapshot, returning the one it supersedes
+    fn put(&self, snapshot: StatsSnapshot) -> Option<Box<StatsSnapshot>> {
+        let old = self.0.swap(Box::into_raw(Box::new(snapshot)), Ordering::AcqRel);
+        // SAFETY: non-null pointers in the slot come from Box::into_raw and the swap made this one ours
+        (!old.is_null()).then(|| unsafe { Box::from_raw(old) })
+    }
+
+    /// Take the snapshot, if one was stored since the last take
+    fn take(&self) -> Option<Box<StatsSnapshot>> {
+        let snapshot = self.0.swap(ptr::null_mut(), Ordering::AcqRel);
+        // SAFETY: as in put
+        (!snapshot.is_null()).then(|| unsafe { Box::from_raw(snapshot) })
+    }
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        self.take();
+    }
+}
+
+/// A worker's end of its queue
+#[derive(Debug)]
+pub struct SnapshotSender(Arc<Slot>);
+
+impl SnapshotSender {
+    /// Publish a snapshot without waiting
+    pub fn send(&self, snapshot: StatsSnapshot) {
+        self.0.put(snapshot);
+    }
+}
+
+/// The node service's end of every worker's queue
+#[derive(Debug)]
+pub struct SnapshotCollector {
+    slots: Vec<Arc<Slot>>,
+    latest: Vec<StatsSnapshot>,
+}
+
+impl SnapshotCollector {
+    /// Collect new snapshots; the latest snapshot of each worker, by node-local index
+    pub fn collect(&mut self) -> &[StatsSnapshot] {
+        for (slot, latest) in self.slots.iter().zip(&mut self.latest) {
+            if let Some(snapshot) = slot.take() {
+                *latest = *snapshot;
+            }
+        }
+        &self.latest
+    }
+}
+
+/// Queues for `workers` workers, each starting from `initial`
+pub fn queues(workers: usize, initial: &StatsSnapshot) -> (Vec<SnapshotSender>, SnapshotCollector) {
+    let slots: Vec<Arc<Slot>> = (0..workers).map(|_| Arc::default()).collect();
+    let senders = slots.iter().cloned().map(SnapshotSender).collect();
+    (senders, SnapshotCollector { slots, latest: vec![initial.clone(); workers] })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collector_keeps_latest_per_worker() {
+        let (senders, mut collector) = queues(3, &StatsSnapshot::default());
+        for write_ops in 1..=10 {
+            senders[0].send(StatsSnapshot { write_ops, ..Default::default() });
+        }
+        senders[2].send(StatsSnapshot { read_ops: 7, ..Default::default() });
+
+        // Newer snapshots supersede ones not collected yet
+        let latest: Vec<(u64, u64)> = collector.collect().iter().map(|s| (s.read_ops, s.write_ops)).collect();
+        assert_eq!(latest, [(0, 10), (0, 0), (7, 0)]);
+        senders[0].send(StatsSnapshot { write_ops: 11, ..Default::default() });
+        assert_eq!(collector.collect()[0].write_ops, 11);
+
+        // A finished worker keeps its last snapshot
+        drop(senders);
+        assert_eq!(collector.collect()[2].read_ops, 7);
+    }
+}
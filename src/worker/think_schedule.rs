@@ -0,0 +1,91 @@
+//! Deadline-based think time
+//!
+//! Think time applied every N blocks sleeps once and then submits N
+//! operations back to back, so the offered load is a sawtooth. With
+//! `--think-smooth` the same think time is spread over the operations: each
+//! completion owes `think / N` and schedules one submission that far after
+//! the later of its own completion and the previously scheduled submission.
+//!
+//! At queue depth 1 this offers exactly the load of the bursty form (every
+//! op takes its latency plus `think / N`); at higher queue depths the
+//! schedule also spaces the submissions of different slots, so they can't
+//! bunch up behind one poll.
+//!
+//! # Example
+//!
+//! ```
+//! use iopulse::worker::think_schedule::ThinkSchedule;
+//! use std::time::{Duration, Instant};
+//!
+//! let start = Instant::now();
+//! let mut schedule = ThinkSchedule::default();
+//!
+//! // Two completions at once owe 100us each: submissions 100us apart
+//! schedule.owe(Duration::from_micros(100), start);
+//! schedule.owe(Duration::from_micros(100), start);
+//! assert_eq!(schedule.wait(start), Some(Duration::from_micros(100)));
+//!
+//! let due = start + Duration::from_micros(100);
+//! assert_eq!(schedule.wait(due), None);
+//! schedule.take();
+//! assert_eq!(schedule.wait(due), Some(Duration::from_micros(100)));
+//! ```
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Release times of the submissions owed think time
+///
+/// One entry per completion not yet followed by a submission, so it never
+/// holds more entries than the queue depth.
+#[derive(Debug, Default)]
+pub struct ThinkSchedule {
+    releases: VecDeque<Instant>,
+    last: Option<Instant>,
+}
+
+impl ThinkSchedule {
+    /// Schedule a submission `delay` after `now` or the previous release, whichever is later
+    pub fn owe(&mut self, delay: Duration, now: Instant) {
+        let base = self.last.map_or(now, |last| last.max(now));
+        let release = base + delay;
+        self.last = Some(release);
+        self.releases.push_back(release);
+    }
+
+    /// Time until the next submission may go (None to submit now)
+    pub fn wait(&self, now: Instant) -> Option<Duration> {
+        self.releases.front()
+            .and_then(|release| release.checked_duration_since(now))
+            .filter(|wait| !wait.is_zero())
+    }
+
+    /// Use up the earliest release on a submission
+    pub fn take(&mut self) {
+        self.releases.pop_front();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_late_completion_restarts_schedule() {
+        let start = Instant::now();
+        let mut schedule = ThinkSchedule::default();
+
+        // Nothing owed: the first submissions go straight away
+        assert_eq!(schedule.wait(start), None);
+        schedule.take();
+
+        schedule.owe(Duration::from_micros(50), start);
+        schedule.take();
+
+        // A completion long after the last release counts from itself
+        let later = start + Duration::from_millis(1);
+        schedule.owe(Duration::from_micros(50), later);
+        assert_eq!(schedule.wait(later), Some(Duration::from_micros(50)));
+        assert_eq!(schedule.wait(later + Duration::from_millis(1)), None);
+    }
+}
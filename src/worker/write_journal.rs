@@ -0,0 +1,359 @@
+//! Write journal for verifying a target after the run
+//!
+//! With `--write-journal`, every worker records the CRC32 of each write it
+//! submits and which of those writes completed. `iopulse verify-journal`
+//! later reads the target back, read-only, and checks every block the run
+//! wrote: after a power cut, a firmware upgrade or a reboot, each block must
+//! still hold the data of its last write.
+//!
+//! Journals are binary: `IOPJRNL1`, then one record per event, integers
+//! little-endian:
+//!
+//! ```text
+//! 'W' op_index:u64 offset:u64 length:u32 crc32:u32   write submitted
+//! 'C' op_index:u64 bytes:u32                         write completed
+//! ```
+//!
+//! Writes to a block that overlapped in time may land in either order, so a
+//! block matches any write that no later completed write replaced, including
+//! one still in flight when the run ended (a short or failed write counts as
+//! in flight). Blocks written only by writes that never completed may still
+//! hold their earlier contents and are not checked. With several workers the
+//! order of their writes is unknown, so any worker's remaining writes match.
+//! Each worker writes its own file (see [`dump_path`]).
+//!
+//! A write's record is synced to the journal file before the write is
+//! submitted, so after a crash every write that may have reached the target
+//! has its record. Completion records are synced along with the next
+//! submitted write; one lost in a crash leaves its write counted as in
+//! flight. The journal must live on storage that survives the failure under
+//! test.
+
+use super::offset_dump::dump_path;
+use crate::Result;
+use anyhow::Context;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::os::unix::fs::FileExt;
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
+
+/// First bytes of a journal file
+const MAGIC: &[u8; 8] = b"IOPJRNL1";
+
+/// Mismatched offsets listed in a verification report
+pub const MAX_REPORTED_MISMATCHES: usize = 10;
+
+/// Per-worker writer of the write journal
+pub struct WriteJournal {
+    writer: BufWriter<File>,
+    path: PathBuf,
+}
+
+impl WriteJournal {
+    /// Create the journal file for a worker (truncating any previous run)
+    pub fn create(path: &Path, worker_id: usize) -> Result<Self> {
+        let path = dump_path(path, worker_id);
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let file = File::create(&path)
+            .with_context(|| format!("Failed to create write journal {}", path.display()))?;
+        let mut writer = BufWriter::new(file);
+        writer.write_all(MAGIC)?;
+        Ok(Self { writer, path })
+    }
+
+    /// Record a write about to be submitted, and the checksum of its data
+    ///
+    /// The record (and any completion records before it) is on disk when
+    /// this returns, so call it before submitting the write.
+    pub fn submitted(&mut self, op_index: u64, offset: u64, data: &[u8]) -> Result<()> {
+        let mut crc = flate2::Crc::new();
+        crc.update(data);
+        self.writer.write_all(b"W")?;
+        self.writer.write_all(&op_index.to_le_bytes())?;
+        self.writer.write_all(&offset.to_le_bytes())?;
+        self.writer.write_all(&(data.len() as u32).to_le_bytes())?;
+        self.writer.write_all(&crc.sum().to_le_bytes())?;
+        self.writer.flush()
+            .and_then(|_| self.writer.get_ref().sync_data())
+            .with_context(|| format!("Failed to sync write journal {}", self.path.display()))
+    }
+
+    /// Record that a write completed, writing `bytes`
+    pub fn completed(&mut self, op_index: u64, bytes: usize) -> Result<()> {
+        self.writer.write_all(b"C")?;
+        self.writer.write_all(&op_index.to_le_bytes())?;
+        self.writer.write_all(&(bytes as u32).to_le_bytes())?;
+        Ok(())
+    }
+
+    /// Flush the journal to disk
+    pub fn finish(&mut self) -> Result<()> {
+        self.writer.flush()
+            .and_then(|_| self.writer.get_ref().sync_all())
+            .with_context(|| format!("Failed to write write journal {}", self.path.display()))
+    }
+}
+
+/// A write to a block that may be its current contents
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Candidate {
+    op_index: u64,
+    length: u32,
+    crc: u32,
+    completed: bool,
+}
+
+/// Writes of one block, in one journal
+#[derive(Debug, Default)]
+struct BlockWrites {
+    candidates: Vec<Candidate>,
+    /// Per candidate: the completed writes it replaces once it completes
+    replaces: Vec<Vec<u64>>,
+}
+
+impl BlockWrites {
+    fn submit(&mut self, op_index: u64, length: u32, crc: u32) {
+        let completed = self.candidates.iter().filter(|c| c.completed).map(|c| c.op_index).collect();
+        self.candidates.push(Candidate { op_index, length, crc, completed: false });
+        self.replaces.push(completed);
+    }
+
+    fn complete(&mut self, op_index: u64, bytes: u32) {
+        let Some(i) = self.candidates.iter().position(|c| c.op_index == op_index) else {
+            return;
+        };
+        // A short write leaves the block torn: treat it as still in flight
+        if bytes < self.candidates[i].length {
+            return;
+        }
+        self.candidates[i].completed = true;
+        let replaced = std::mem::take(&mut self.replaces[i]);
+        let mut j = 0;
+        while j < self.candidates.len() {
+            if replaced.contains(&self.candidates[j].op_index) {
+                self.candidates.remove(j);
+                self.replaces.remove(j);
+            } else {
+                j += 1;
+            }
+        }
+    }
+}
+
+/// What a block may hold after the run
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BlockExpectation {
+    /// (length, CRC32) of the writes the block may hold
+    pub candidates: Vec<(u32, u32)>,
+    /// Whether any of them completed (otherwise the block isn't checked)
+    pub completed: bool,
+}
+
+/// Read the journals of a run into the expected contents of each block written
+pub fn load_journals(paths: &[PathBuf]) -> Result<BTreeMap<u64, BlockExpectation>> {
+    let mut blocks: BTreeMap<u64, BlockExpectation> = BTreeMap::new();
+    for path in paths {
+        let file = File::open(path)
+            .with_context(|| format!("Failed to open write journal {}", path.display()))?;
+        let writes = read_journal(&mut BufReader::new(file))
+            .with_context(|| format!("Invalid write journal {}", path.display()))?;
+        for (offset, writes) in writes {
+            let expected = blocks.entry(offset).or_default();
+            for candidate in writes.candidates {
+                expected.candidates.push((candidate.length, candidate.crc));
+                expected.completed |= candidate.completed;
+            }
+        }
+    }
+    Ok(blocks)
+}
+
+fn read_journal(reader: &mut impl Read) -> Result<HashMap<u64, BlockWrites>> {
+    let mut magic = [0u8; 8];
+    reader.read_exact(&mut magic).context("Missing journal header")?;
+    if &magic != MAGIC {
+        anyhow::bail!("Not a write journal (written with --write-journal)");
+    }
+
+    let mut blocks: HashMap<u64, BlockWrites> = HashMap::new();
+    let mut offsets: HashMap<u64, u64> = HashMap::new();
+    let mut tag = [0u8; 1];
+    loop {
+        if reader.read(&mut tag)? == 0 {
+            break;
+        }
+        match &tag {
+            b"W" => {
+                let mut record = [0u8; 24];
+                // A record cut short by a crash ends the journal
+                if reader.read_exact(&mut record).is_err() {
+                    break;
+                }
+                let op_index = u64::from_le_bytes(record[0..8].try_into().unwrap());
+                let offset = u64::from_le_bytes(record[8..16].try_into().unwrap());
+                let length = u32::from_le_bytes(record[16..20].try_into().unwrap());
+                let crc = u32::from_le_bytes(record[20..24].try_into().unwrap());
+                blocks.entry(offset).or_default().submit(op_index, length, crc);
+                offsets.insert(op_index, offset);
+            }
+            b"C" => {
+                let mut record = [0u8; 12];
+                if reader.read_exact(&mut record).is_err() {
+                    break;
+                }
+                let op_index = u64::from_le_bytes(record[0..8].try_into().unwrap());
+                let bytes = u32::from_le_bytes(record[8..12].try_into().unwrap());
+                if let Some(offset) = offsets.remove(&op_index) {
+                    if let Some(writes) = blocks.get_mut(&offset) {
+                        writes.complete(op_index, bytes);
+                    }
+                }
+            }
+            _ => anyhow::bail!("Unknown record type 0x{:02x}", tag[0]),
+        }
+    }
+    Ok(blocks)
+}
+
+/// Result of verifying a target against its write journal
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct JournalVerification {
+    /// Blocks checked
+    pub blocks: u64,
+    /// Bytes read back
+    pub bytes: u64,
+    /// Blocks not holding any of their expected writes
+    pub mismatched: u64,
+    /// Offsets of the first mismatched blocks
+    pub mismatch_offsets: Vec<u64>,
+    /// Blocks only written by writes that never completed
+    pub unchecked: u64,
+}
+
+/// Verify a target against the journals of a run, reading it read-only
+///
+/// Cached pages of the target are dropped first so the data comes from the
+/// device rather than from memory.
+pub fn verify_target(target: &Path, journals: &[PathBuf]) -> Result<JournalVerification> {
+    let blocks = load_journals(journals)?;
+    let file = File::open(target)
+        .with_context(|| format!("Failed to open {}", target.display()))?;
+    unsafe {
+        libc::posix_fadvise(file.as_raw_fd(), 0, 0, libc::POSIX_FADV_DONTNEED);
+    }
+
+    let mut result = JournalVerification::default();
+    let mut buffer = Vec::new();
+    for (offset, expected) in &blocks {
+        if !expected.completed {
+            result.unchecked += 1;
+            continue;
+        }
+        let length = expected.candidates.iter().map(|&(length, _)| length).max().unwrap_or(0) as usize;
+        buffer.resize(length, 0);
+        let read = read_full(&file, &mut buffer, *offset)
+            .with_context(|| format!("Failed to read {} at offset {}", target.display(), offset))?;
+
+        let matches = expected.candidates.iter().any(|&(length, crc)| {
+            let length = length as usize;
+            length <= read && {
+                let mut sum = flate2::Crc::new();
+                sum.update(&buffer[..length]);
+                sum.sum() == crc
+            }
+        });
+        result.blocks += 1;
+        result.bytes += read as u64;
+        if !matches {
+            result.mismatched += 1;
+            if result.mismatch_offsets.len() < MAX_REPORTED_MISMATCHES {
+                result.mismatch_offsets.push(*offset);
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Read up to `buffer.len()` bytes at `offset`, stopping early only at the end of the target
+fn read_full(file: &File, buffer: &mut [u8], offset: u64) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match file.read_at(&mut buffer[filled..], offset + filled as u64)? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+    Ok(filled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overlapping_writes() {
+        let mut writes = BlockWrites::default();
+        writes.submit(0, 4096, 0xa);
+        writes.complete(0, 4096);
+        // Two writes in flight together: either may land last
+        writes.submit(1, 4096, 0xb);
+        writes.submit(2, 4096, 0xc);
+        writes.complete(2, 4096);
+        writes.complete(1, 4096);
+        let crcs: Vec<u32> = writes.candidates.iter().map(|c| c.crc).collect();
+        assert_eq!(crcs, [0xb, 0xc]);
+
+        // A write submitted after both completed replaces them; a short one doesn't
+        writes.submit(3, 4096, 0xd);
+        writes.complete(3, 4096);
+        writes.submit(4, 4096, 0xe);
+        writes.complete(4, 512);
+        let crcs: Vec<u32> = writes.candidates.iter().map(|c| c.crc).collect();
+        assert_eq!(crcs, [0xd, 0xe]);
+    }
+
+    #[test]
+    fn test_verify_target() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let target = dir.path().join("target.dat");
+        let block = |fill: u8| vec![fill; 4096];
+        std::fs::write(&target, [block(1), block(2), block(3)].concat()).unwrap();
+
+        let journal_path = dir.path().join("journal.bin");
+        let mut journal = WriteJournal::create(&journal_path, 0).unwrap();
+        journal.submitted(0, 0, &block(1)).unwrap();
+        journal.completed(0, 4096).unwrap();
+        journal.submitted(1, 4096, &block(9)).unwrap();
+        journal.completed(1, 4096).unwrap();
+        journal.submitted(2, 8192, &block(7)).unwrap();
+        journal.finish().unwrap();
+
+        let journals = vec![dump_path(&journal_path, 0)];
+        let result = verify_target(&target, &journals).unwrap();
+        assert_eq!(result.blocks, 2);
+        assert_eq!(result.mismatched, 1);
+        assert_eq!(result.mismatch_offsets, [4096]);
+        assert_eq!(result.unchecked, 1);
+    }
+
+    #[test]
+    fn test_submitted_record_on_disk_before_submit() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let journal_path = dir.path().join("journal.bin");
+        let mut journal = WriteJournal::create(&journal_path, 0).unwrap();
+        journal.submitted(0, 4096, &[5u8; 4096]).unwrap();
+        journal.completed(0, 4096).unwrap();
+
+        // Crash before finish(): the write's record is there, its completion not yet
+        let blocks = load_journals(&[dump_path(&journal_path, 0)]).unwrap();
+        std::mem::forget(journal);
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[&4096].candidates.len(), 1);
+        assert!(!blocks[&4096].completed);
+    }
+}
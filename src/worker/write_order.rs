@@ -0,0 +1,498 @@
+//! Write ordering check for power-fail tests
+//!
+//! With `--commit-every N`, every write starts with a stamp: the worker's
+//! stream, a generation number and a sequence number that increases with
+//! each write. After every N writes the worker reaches a commit point: it
+//! waits for its writes in flight, syncs the target, writes the generation
+//! to one of its two commit slots and syncs again. Writes after that belong
+//! to the next generation.
+//!
+//! `iopulse verify-order` reads the target back after a power cut. A write
+//! of generation G+2 is only submitted once commit G+1 was synced, so with
+//! the newest intact commit holding G no block may claim a generation newer
+//! than G+1. One that does means the storage acknowledged a sync it didn't
+//! make durable: the barrier between the data and the commit record was lost.
+//!
+//! Each worker's commit slots are the first two blocks of its span (its
+//! partition of the target, or the whole target), which its writes skip.
+//! Generation G goes to slot G % 2, so a commit torn by the power cut leaves
+//! the previous one intact in the other slot. A stream whose writes survive
+//! without any intact commit can't be checked and fails verification. Stamps
+//! are 56 bytes, integers little-endian:
+//!
+//! ```text
+//! magic:[u8; 8] stream:u64 worker:u32 length:u32 generation:u64 sequence:u64 offset:u64 crc32:u32 pad:u32
+//! ```
+//!
+//! The magic is `IOPGEN01` for a write and `IOPCMT01` for a commit slot.
+//! The stream is random per worker and run, so stamps left by earlier runs
+//! are told apart. The CRC covers the whole block with the CRC field zeroed;
+//! a block whose CRC doesn't match was torn by the power cut.
+
+use crate::util::buffer::AlignedBuffer;
+use crate::Result;
+use anyhow::Context;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::os::unix::fs::FileExt;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Magic of a stamped write
+const DATA_MAGIC: &[u8; 8] = b"IOPGEN01";
+
+/// Magic of a commit slot
+const COMMIT_MAGIC: &[u8; 8] = b"IOPCMT01";
+
+/// Bytes of a stamp at the start of each block
+pub const STAMP_LEN: usize = 56;
+
+/// Blocks are looked for at this granularity when verifying
+const SCAN_ALIGN: usize = 512;
+
+/// Bytes read at a time when verifying
+const SCAN_CHUNK: usize = 4 << 20;
+
+/// Violating offsets listed in a verification report
+pub const MAX_REPORTED_VIOLATIONS: usize = 10;
+
+/// A block's stamp
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stamp {
+    pub commit: bool,
+    pub stream: u64,
+    pub worker: u32,
+    pub length: u32,
+    pub generation: u64,
+    pub sequence: u64,
+    pub offset: u64,
+}
+
+impl Stamp {
+    /// Write the stamp to the start of `block` and checksum the block
+    pub fn write(&self, block: &mut [u8]) {
+        block[0..8].copy_from_slice(if self.commit { COMMIT_MAGIC } else { DATA_MAGIC });
+        block[8..16].copy_from_slice(&self.stream.to_le_bytes());
+        block[16..20].copy_from_slice(&self.worker.to_le_bytes());
+        block[20..24].copy_from_slice(&self.length.to_le_bytes());
+        block[24..32].copy_from_slice(&self.generation.to_le_bytes());
+        block[32..40].copy_from_slice(&self.sequence.to_le_bytes());
+        block[40..48].copy_from_slice(&self.offset.to_le_bytes());
+        block[52..STAMP_LEN].fill(0);
+        let crc = block_crc(block);
+        block[48..52].copy_from_slice(&crc.to_le_bytes());
+    }
+
+    /// Stamp at the start of `data`, if there is one
+    ///
+    /// Returns the stamp and whether the block it covers is intact (None
+    /// when `data` ends before the block does).
+    pub fn read(data: &[u8]) -> Option<(Self, Option<bool>)> {
+        let commit = match data.get(0..8)? {
+            magic if magic == DATA_MAGIC => false,
+            magic if magic == COMMIT_MAGIC => true,
+            _ => return None,
+        };
+        if data.len() < STAMP_LEN {
+            return None;
+        }
+        let u64_at = |at: usize| u64::from_le_bytes(data[at..at + 8].try_into().unwrap());
+        let u32_at = |at: usize| u32::from_le_bytes(data[at..at + 4].try_into().unwrap());
+        let stamp = Self {
+            commit,
+            stream: u64_at(8),
+            worker: u32_at(16),
+            length: u32_at(20),
+            generation: u64_at(24),
+            sequence: u64_at(32),
+            offset: u64_at(40),
+        };
+        let length = stamp.length as usize;
+        if length < STAMP_LEN {
+            return None;
+        }
+        let intact = data.get(..length).map(|block| block_crc(block) == u32_at(48));
+        Some((stamp, intact))
+    }
+}
+
+/// CRC32 of a block with its CRC field zeroed
+fn block_crc(block: &[u8]) -> u32 {
+    let mut crc = flate2::Crc::new();
+    crc.update(&block[..48]);
+    crc.update(&[0u8; 4]);
+    crc.update(&block[52..]);
+    crc.sum()
+}
+
+/// Per-worker state of the write ordering check
+pub struct WriteOrder {
+    stream: u64,
+    worker: u32,
+    /// Offset of the worker's first commit slot, the second follows it
+    commit_offset: u64,
+    commit_block: AlignedBuffer,
+    /// Writes per generation
+    every: u64,
+    generation: u64,
+    sequence: u64,
+    /// Writes stamped in the current generation
+    written: u64,
+}
+
+impl WriteOrder {
+    /// Start the check: write generation 0 to the commit slot at `commit_offset`
+    ///
+    /// The second slot is the block after it. Returns the state and how long
+    /// writing the commit took.
+    pub fn begin(fd: RawFd, worker: usize, commit_offset: u64, block_size: usize, every: u64) -> Result<(Self, Duration)> {
+        let mut order = Self {
+            stream: rand::random(),
+            worker: worker as u32,
+            commit_offset,
+            commit_block: AlignedBuffer::new(block_size, 4096),
+            every: every.max(1),
+            generation: 0,
+            sequence: 0,
+            written: 0,
+        };
+        let synced = order.write_commit(fd)?;
+        order.generation = 1;
+        Ok((order, synced))
+    }
+
+    /// Stamp a write of `block` at `offset`
+    pub fn stamp(&mut self, block: &mut [u8], offset: u64) {
+        self.sequence += 1;
+        self.written += 1;
+        Stamp {
+            commit: false,
+            stream: self.stream,
+            worker: self.worker,
+            length: block.len() as u32,
+            generation: self.generation,
+            sequence: self.sequence,
+            offset,
+        }.write(block);
+    }
+
+    /// Whether the current generation is complete and must be committed
+    pub fn commit_due(&self) -> bool {
+        self.written >= self.every
+    }
+
+    /// Whether writes were stamped since the last commit point
+    pub fn uncommitted(&self) -> bool {
+        self.written > 0
+    }
+
+    /// Commit the current generation once its writes completed
+    ///
+    /// Syncs the data, then writes and syncs the commit slot. Returns how
+    /// long the commit point took.
+    pub fn commit(&mut self, fd: RawFd) -> Result<Duration> {
+        let start = Instant::now();
+        sync(fd)?;
+        self.write_commit(fd)?;
+        self.generation += 1;
+        self.written = 0;
+        Ok(start.elapsed())
+    }
+
+    /// Write the current generation to its commit slot and sync it
+    ///
+    /// Generations alternate between the two slots, so the previous commit
+    /// stays intact while this one is written.
+    fn write_commit(&mut self, fd: RawFd) -> Result<Duration> {
+        let start = Instant::now();
+        let block = self.commit_block.as_mut_slice();
+        let offset = self.commit_offset + (self.generation % 2) * block.len() as u64;
+        Stamp {
+            commit: true,
+            stream: self.stream,
+            worker: self.worker,
+            length: block.len() as u32,
+            generation: self.generation,
+            sequence: self.sequence,
+            offset,
+        }.write(block);
+        let written = unsafe {
+            libc::pwrite(fd, block.as_ptr() as *const libc::c_void, block.len(), offset as libc::off_t)
+        };
+        if written != block.len() as isize {
+            return Err(std::io::Error::last_os_error())
+                .with_context(|| format!("Failed to write commit slot at offset {}", offset));
+        }
+        sync(fd)?;
+        Ok(start.elapsed())
+    }
+}
+
+fn sync(fd: RawFd) -> Result<()> {
+    if unsafe { libc::fdatasync(fd) } != 0 {
+        return Err(std::io::Error::last_os_error()).context("fdatasync failed at a commit point");
+    }
+    Ok(())
+}
+
+/// What verification found of one worker's stream
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct StreamVerification {
+    pub worker: u32,
+    /// Generation of the newest intact commit
+    pub committed: u64,
+    /// Sequence number of the last committed write
+    pub committed_sequence: u64,
+    /// Newest generation and sequence number claimed by an intact write
+    pub newest_generation: u64,
+    pub newest_sequence: u64,
+    /// Intact writes of committed generations and of the one in progress
+    pub committed_blocks: u64,
+    pub uncommitted_blocks: u64,
+}
+
+/// Result of verifying a target's write ordering
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct OrderVerification {
+    /// Bytes read back
+    pub bytes: u64,
+    /// Streams found (one per worker of the run)
+    pub streams: Vec<StreamVerification>,
+    /// Writes claiming a generation past the one in progress at the power cut
+    pub violations: u64,
+    /// Offsets of the first violating writes
+    pub violation_offsets: Vec<u64>,
+    /// Writes torn by the power cut (stamp found, checksum wrong)
+    pub torn: u64,
+    /// Commits torn by the power cut; the stream's other slot is used
+    pub torn_commits: u64,
+    /// Writes of streams without a commit whose worker has a newer stream (earlier runs)
+    pub stale: u64,
+    /// Streams with intact writes but no intact commit, which can't be checked
+    pub orphaned_streams: u64,
+    /// Writes of those streams
+    pub orphaned: u64,
+}
+
+/// Verify the write ordering of a target, reading it read-only
+///
+/// Cached pages of the target are dropped first so the data comes from the
+/// device rather than from memory.
+pub fn verify_target(target: &Path) -> Result<OrderVerification> {
+    let file = File::open(target)
+        .with_context(|| format!("Failed to open {}", target.display()))?;
+    unsafe {
+        libc::posix_fadvise(file.as_raw_fd(), 0, 0, libc::POSIX_FADV_DONTNEED);
+    }
+
+    let mut commits: HashMap<u64, Stamp> = HashMap::new();
+    let mut writes: Vec<Stamp> = Vec::new();
+    let mut result = OrderVerification::default();
+    scan(&file, &mut result.bytes, |stamp, intact| match (stamp.commit, intact) {
+        (true, false) => result.torn_commits += 1,
+        (false, false) => result.torn += 1,
+        (true, true) => {
+            commits.entry(stamp.stream)
+                .and_modify(|commit| if stamp.generation > commit.generation { *commit = stamp })
+                .or_insert(stamp);
+        }
+        (false, true) => writes.push(stamp),
+    }).with_context(|| format!("Failed to read {}", target.display()))?;
+
+    let mut streams: HashMap<u64, StreamVerification> = commits.values()
+        .map(|commit| (commit.stream, StreamVerification {
+            worker: commit.worker,
+            committed: commit.generation,
+            committed_sequence: commit.sequence,
+            ..Default::default()
+        }))
+        .collect();
+    let committed_workers: HashSet<u32> = commits.values().map(|commit| commit.worker).collect();
+    let mut orphaned_streams = HashSet::new();
+    writes.sort_by_key(|write| write.offset);
+    for write in &writes {
+        let Some(stream) = streams.get_mut(&write.stream) else {
+            // A worker's slots are reused by its next run, which leaves the
+            // stamps of the previous one without a commit
+            if committed_workers.contains(&write.worker) {
+                result.stale += 1;
+            } else {
+                result.orphaned += 1;
+                orphaned_streams.insert(write.stream);
+            }
+            continue;
+        };
+        stream.newest_generation = stream.newest_generation.max(write.generation);
+        stream.newest_sequence = stream.newest_sequence.max(write.sequence);
+        if write.generation <= stream.committed {
+            stream.committed_blocks += 1;
+        } else {
+            stream.uncommitted_blocks += 1;
+            if write.generation > stream.committed + 1 {
+                result.violations += 1;
+                if result.violation_offsets.len() < MAX_REPORTED_VIOLATIONS {
+                    result.violation_offsets.push(write.offset);
+                }
+            }
+        }
+    }
+    result.orphaned_streams = orphaned_streams.len() as u64;
+    result.streams = streams.into_values().collect();
+    result.streams.sort_by_key(|stream| stream.worker);
+    Ok(result)
+}
+
+/// Call `found` with every stamp of the target and whether its block is intact
+fn scan(file: &File, bytes: &mut u64, mut found: impl FnMut(Stamp, bool)) -> std::io::Result<()> {
+    let mut chunk = vec![0u8; SCAN_CHUNK];
+    let mut offset = 0u64;
+    loop {
+        let read = read_full(file, &mut chunk, offset)?;
+        if read == 0 {
+            return Ok(());
+        }
+        *bytes += read as u64;
+        let mut at = 0;
+        while at + STAMP_LEN <= read {
+            match Stamp::read(&chunk[at..read]) {
+                // The block continues in the next chunk: read it from its start
+                Some((_, None)) if at > 0 => break,
+                Some((stamp, intact)) => {
+                    found(stamp, intact.unwrap_or(false));
+                    at += (stamp.length as usize).div_ceil(SCAN_ALIGN) * SCAN_ALIGN;
+                }
+                None => at += SCAN_ALIGN,
+            }
+        }
+        if read < chunk.len() {
+            return Ok(());
+        }
+        offset += at as u64;
+    }
+}
+
+/// Read up to `buffer.len()` bytes at `offset`, stopping early only at the end of the target
+fn read_full(file: &File, buffer: &mut [u8], offset: u64) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match file.read_at(&mut buffer[filled..], offset + filled as u64)? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+    Ok(filled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open_target(dir: &tempfile::TempDir) -> (std::path::PathBuf, File) {
+        let target = dir.path().join("target.dat");
+        let file = std::fs::OpenOptions::new().read(true).write(true).create(true).truncate(true).open(&target).unwrap();
+        file.set_len(64 * 4096).unwrap();
+        (target, file)
+    }
+
+    #[test]
+    fn test_verify_target() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let (target, file) = open_target(&dir);
+        let fd = file.as_raw_fd();
+
+        // Two generations committed, a third in progress
+        let (mut order, _) = WriteOrder::begin(fd, 3, 0, 4096, 2).unwrap();
+        let mut block = vec![0xa5u8; 4096];
+        let mut write = |order: &mut WriteOrder, offset: u64| {
+            order.stamp(&mut block, offset);
+            file.write_all_at(&block, offset).unwrap();
+        };
+        for offset in [2, 3, 4, 5] {
+            write(&mut order, offset * 4096);
+            if order.commit_due() {
+                order.commit(fd).unwrap();
+            }
+        }
+        write(&mut order, 6 * 4096);
+        let result = verify_target(&target).unwrap();
+        assert_eq!(result.violations, 0);
+        assert_eq!(result.streams.len(), 1);
+        let stream = &result.streams[0];
+        assert_eq!((stream.worker, stream.committed, stream.committed_sequence), (3, 2, 4));
+        assert_eq!((stream.committed_blocks, stream.uncommitted_blocks), (4, 1));
+        assert_eq!((stream.newest_generation, stream.newest_sequence), (3, 5));
+
+        // Generation 4 on the media without commit 3: the barrier was lost
+        order.written = order.every;
+        order.generation += 1;
+        write(&mut order, 7 * 4096);
+        // A torn block and one of an earlier run of the same worker
+        write(&mut order, 8 * 4096);
+        file.write_all_at(&[0u8; 512], 8 * 4096 + 1024).unwrap();
+        let (mut old, _) = WriteOrder::begin(fd, 3, 10 * 4096, 4096, 1).unwrap();
+        write(&mut old, 12 * 4096);
+        file.write_all_at(&[0u8; 8192], 10 * 4096).unwrap();
+
+        let result = verify_target(&target).unwrap();
+        assert_eq!(result.violations, 1);
+        assert_eq!(result.violation_offsets, [7 * 4096]);
+        assert_eq!((result.torn, result.stale, result.orphaned), (1, 1, 0));
+    }
+
+    #[test]
+    fn test_torn_commit_falls_back_to_other_slot() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let (target, file) = open_target(&dir);
+        let fd = file.as_raw_fd();
+
+        let (mut order, _) = WriteOrder::begin(fd, 0, 0, 4096, 1).unwrap();
+        let mut block = vec![0x5au8; 4096];
+        for offset in [2, 3, 4] {
+            order.stamp(&mut block, offset * 4096);
+            file.write_all_at(&block, offset * 4096).unwrap();
+            order.commit(fd).unwrap();
+        }
+        // Commit 3 (slot 1) torn: generation 3 is still the one in progress
+        file.write_all_at(&[0xffu8; 512], 4096 + 1024).unwrap();
+
+        let result = verify_target(&target).unwrap();
+        assert_eq!((result.violations, result.torn_commits, result.orphaned), (0, 1, 0));
+        let stream = &result.streams[0];
+        assert_eq!((stream.committed, stream.committed_blocks, stream.uncommitted_blocks), (2, 2, 1));
+    }
+
+    #[test]
+    fn test_orphaned_stream() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let (target, file) = open_target(&dir);
+        let fd = file.as_raw_fd();
+
+        // Both commit slots lost: the writes can't be checked
+        let (mut order, _) = WriteOrder::begin(fd, 1, 0, 4096, 1).unwrap();
+        let mut block = vec![0x5au8; 4096];
+        for offset in [2, 3] {
+            order.stamp(&mut block, offset * 4096);
+            file.write_all_at(&block, offset * 4096).unwrap();
+            order.commit(fd).unwrap();
+        }
+        file.write_all_at(&[0xffu8; 512], 1024).unwrap();
+        file.write_all_at(&[0xffu8; 512], 4096 + 1024).unwrap();
+
+        let result = verify_target(&target).unwrap();
+        assert!(result.streams.is_empty());
+        assert_eq!((result.orphaned_streams, result.orphaned, result.torn_commits), (1, 2, 2));
+    }
+
+    #[test]
+    fn test_stamp_roundtrip() {
+        let stamp = Stamp { commit: false, stream: 7, worker: 1, length: 512, generation: 3, sequence: 9, offset: 4096 };
+        let mut block = vec![1u8; 512];
+        stamp.write(&mut block);
+        assert_eq!(Stamp::read(&block), Some((stamp, Some(true))));
+        assert_eq!(Stamp::read(&block[..100]), Some((stamp, None)));
+        block[300] ^= 1;
+        assert_eq!(Stamp::read(&block), Some((stamp, Some(false))));
+        assert_eq!(Stamp::read(&[0u8; 512]), None);
+    }
+}